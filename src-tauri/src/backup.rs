@@ -7,7 +7,14 @@
 
 use crate::config as config_module;
 use crate::config::ConfigError;
-use crate::file_ops::{create_tar_gz, delete_file, get_file_size, FileOpsError, FileOpsResult};
+use crate::file_ops::{
+    create_tar_gz_skipping_locked, delete_file, get_dir_size, get_file_size, FileOpsError,
+    FileOpsResult, SkippedFile,
+};
+use crate::keys::BackupKey;
+use crate::listing_cache::{self, BackupListing};
+use crate::rcon::{self, ServerFlush};
+use crate::tasks::TaskHandle;
 use crate::tags::Tag;
 use chrono::{DateTime, Local, Utc};
 use serde::{Deserialize, Serialize, Serializer};
@@ -36,10 +43,27 @@ pub struct BackupInfo {
     /// Base64-encoded thumbnail image (thumb.png) data URL, if exists in backup
     #[serde(skip_serializing_if = "Option::is_none")]
     pub thumb_data: Option<String>,
+    /// Whether some save files couldn't be read and are missing from the backup
+    #[serde(default)]
+    pub partial: bool,
+    /// Files missing from a partial backup, with the reason
+    #[serde(default)]
+    pub skipped_files: Vec<SkippedFile>,
+    /// RCON flush before the backup; None for saves without RCON settings
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub server_flush: Option<ServerFlush>,
+}
+
+/// Result of a backup deletion.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct DeleteBackupResult {
+    /// Problems with cleanup after the file was deleted (e.g., tags left behind)
+    #[serde(default)]
+    pub warnings: Vec<String>,
 }
 
 /// Result of a backup creation operation.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BackupResult {
     /// Path to the created backup
     pub backup_path: String,
@@ -49,6 +73,47 @@ pub struct BackupResult {
     pub retained_count: usize,
     /// Number of backups deleted by GC
     pub deleted_count: usize,
+    /// Names of the backups deleted by GC
+    #[serde(default)]
+    pub deleted_backups: Vec<String>,
+    /// Size of the created backup in bytes
+    #[serde(default)]
+    pub size_bytes: u64,
+    /// Whether some save files stayed locked and were left out
+    #[serde(default)]
+    pub partial: bool,
+    /// Files left out of the backup, with the reason
+    #[serde(default)]
+    pub skipped_files: Vec<SkippedFile>,
+    /// RCON flush before the backup; None for saves without RCON settings
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub server_flush: Option<ServerFlush>,
+}
+
+/// Aggregate backup statistics for a single save.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BackupStats {
+    /// Number of completed backups
+    pub backup_count: usize,
+    /// Total size of all backups in bytes
+    pub total_bytes: u64,
+    /// Creation time of the newest backup
+    pub last_backup_at: Option<DateTime<Utc>>,
+}
+
+impl BackupStats {
+    /// Computes statistics from an already listed set of backups.
+    pub fn from_backups(backups: &[BackupInfo]) -> Self {
+        BackupStats {
+            backup_count: backups.len(),
+            total_bytes: backups.iter().map(|b| b.size_bytes).sum(),
+            last_backup_at: backups
+                .iter()
+                .filter_map(|b| DateTime::parse_from_rfc3339(&b.created_at).ok())
+                .map(|dt| dt.with_timezone(&Utc))
+                .max(),
+        }
+    }
 }
 
 /// Error type for backup operations.
@@ -124,7 +189,7 @@ pub type BackupResultT<T> = Result<T, BackupError>;
 ///
 /// # Arguments
 /// * `_save_name` - Save name parameter kept for API compatibility, but not used
-///                 since the backup filename is now just a timestamp
+///   since the backup filename is now just a timestamp
 ///
 /// # Example
 /// ```
@@ -145,15 +210,18 @@ pub fn generate_backup_name(_save_name: &str) -> String {
 /// * `save_name` - Name of the save
 ///
 /// # Returns
-/// Path to the save's backup subdirectory
-pub fn get_save_backup_dir(backup_base_path: &Path, save_name: &str) -> PathBuf {
-    backup_base_path.join(save_name)
+/// `FileOpsResult<PathBuf>` - Path to the save's backup subdirectory; `UnsafeSavePath`
+/// if the name would leave the backup directory (see `file_ops::validate_save_name`)
+pub fn get_save_backup_dir(backup_base_path: &Path, save_name: &str) -> FileOpsResult<PathBuf> {
+    crate::file_ops::validate_save_name(save_name)?;
+    Ok(backup_base_path.join(save_name))
 }
 
 /// Creates a backup of the specified save directory (async version).
 ///
 /// # Arguments
 /// * `save_name` - Relative path of the save to backup (e.g., "sandbox/aaa")
+/// * `task` - Task handle receiving compression progress; cancelling it aborts the backup
 ///
 /// # Returns
 /// `BackupResultT<BackupResult>` - Information about the created backup
@@ -165,12 +233,11 @@ pub fn get_save_backup_dir(backup_base_path: &Path, save_name: &str) -> PathBuf
 /// # Backup Path Structure
 /// For a save at `Saves/sandbox/aaa`:
 /// - Backup path: `$PZ_BACKUP_PATH/sandbox/aaa/aaa_2024-12-28_14-30-45.tar.gz`
-pub async fn create_backup_async(save_name: &str) -> BackupResultT<BackupResult> {
+pub async fn create_backup_async(save_name: &str, task: TaskHandle) -> BackupResultT<BackupResult> {
     let save_name = save_name.to_string();
-    tokio::task::spawn_blocking(move || create_backup(&save_name))
+    tokio::task::spawn_blocking(move || create_backup_with_task(&save_name, &task))
         .await
-        .map_err(|e| BackupError::FileOp(FileOpsError::Io(std::io::Error::new(
-            std::io::ErrorKind::Other,
+        .map_err(|e| BackupError::FileOp(FileOpsError::Io(std::io::Error::other(
             format!("Task join error: {}", e),
         ))))?
 }
@@ -187,16 +254,45 @@ pub async fn create_backup_async(save_name: &str) -> BackupResultT<BackupResult>
 /// 1. Cleans up any leftover temporary files from previous interrupted backups
 /// 2. Validates the save directory exists
 /// 3. Generates timestamped backup name (using only save leaf name)
-/// 4. Creates a compressed tar.gz archive (atomically)
+/// 4. Creates a compressed tar.gz archive (atomically), with `mods_manifest.json`
+///    at its root if the save uses mods (see `mods::capture_manifest`)
 /// 5. Runs garbage collection to remove old backups exceeding retention limit
 ///
+/// Saves with RCON settings are flushed before step 4 (see `rcon::flush_before_backup`).
+///
 /// # Backup Path Structure
 /// For a save at `Saves/sandbox/aaa`:
 /// - Backup path: `$PZ_BACKUP_PATH/sandbox/aaa/aaa_2024-12-28_14-30-45.tar.gz`
 pub fn create_backup(save_name: &str) -> BackupResultT<BackupResult> {
+    create_backup_with_task(save_name, &TaskHandle::detached())
+}
+
+/// Creates a backup, reporting progress to a task handle.
+///
+/// # Arguments
+/// * `save_name` - Relative path of the save to backup (e.g., "sandbox/aaa")
+/// * `task` - Task handle receiving compression progress
+///
+/// # Returns
+/// `BackupResultT<BackupResult>` - Information about the created backup
+///
+/// # Behavior
+/// Same as `create_backup`. If the task is cancelled during compression, the partial
+/// archive is removed and `FileOpsError::Cancelled` is returned; GC doesn't run.
+///
+/// Files the game holds locked are retried as configured (`locked_file_attempts`,
+/// `locked_file_retry_delay_ms`). Files still locked after that are left out: the
+/// backup is kept, marked partial, and the skipped files are written to a report
+/// next to the archive.
+///
+/// A dedicated server save with RCON settings is told to `save` first. If that
+/// fails, the backup is still taken and recorded as taken without flush.
+pub fn create_backup_with_task(save_name: &str, task: &TaskHandle) -> BackupResultT<BackupResult> {
     let config = config_module::load_config()?;
     let save_path = config.get_save_path()?;
     let backup_base_path = config.get_backup_path()?;
+    // Rejects names leaving the save and backup directories before anything is read
+    let save_backup_dir = get_save_backup_dir(&backup_base_path, save_name)?;
 
     // Validate save directory exists
     let save_dir = save_path.join(save_name);
@@ -212,7 +308,6 @@ pub fn create_backup(save_name: &str) -> BackupResultT<BackupResult> {
 
     // Create backup base directory if it doesn't exist
     // Use the relative path as the backup directory structure
-    let save_backup_dir = get_save_backup_dir(&backup_base_path, save_name);
     if !save_backup_dir.exists() {
         fs::create_dir_all(&save_backup_dir).map_err(FileOpsError::Io)?;
     }
@@ -224,21 +319,135 @@ pub fn create_backup(save_name: &str) -> BackupResultT<BackupResult> {
     let backup_name = generate_backup_name(save_name);
     let backup_path = save_backup_dir.join(&backup_name);
 
+    // A live server must write its world before it is archived
+    let server_flush = rcon::flush_before_backup(&config, save_name, &save_dir);
+
+    // The save's mod list travels with the archive for restore-time checks
+    let extras: Vec<_> = crate::mods::capture_manifest(&save_dir, &config).into_iter().collect();
+
     // Perform the backup compression (atomic write)
-    create_tar_gz(&save_dir, &backup_path)?;
+    let skipped_files = create_tar_gz_skipping_locked(
+        &save_dir,
+        &backup_path,
+        task,
+        &config.locked_file_retry(),
+        &extras,
+    )?;
+    if !skipped_files.is_empty() {
+        // A partial backup must never look complete
+        if let Err(err) = write_skipped_report(&backup_path, &skipped_files) {
+            let _ = fs::remove_file(&backup_path);
+            return Err(err.into());
+        }
+    }
+    if let Some(flush) = &server_flush {
+        // Best-effort: listings then show the backup without flush status
+        if let Err(err) = write_report(&backup_path, FLUSH_REPORT_SUFFIX, flush) {
+            eprintln!("[WARN] Failed to record server flush of {}: {}", backup_name, err);
+        }
+    }
+    let size_bytes = get_file_size(&backup_path)?;
+    task.set_bytes(get_dir_size(&save_dir).ok(), Some(size_bytes));
 
     // Run garbage collection
     let retention_count = config.retention_count;
-    let (retained, deleted) = garbage_collection(&save_backup_dir, retention_count)?;
+    let gc_result = garbage_collection(&save_backup_dir, retention_count, &backup_protection(save_name));
+    // The new archive exists even if GC failed
+    listing_cache::invalidate(save_name);
+    let (retained, deleted) = gc_result?;
+    // One tags.json rewrite for all pruned backups
+    crate::tags::clear_tags_of_backups_best_effort(save_name, &deleted);
 
     Ok(BackupResult {
         backup_path: crate::file_ops::normalize_path_for_display(&backup_path),
         backup_name,
         retained_count: retained,
-        deleted_count: deleted,
+        deleted_count: deleted.len(),
+        deleted_backups: deleted,
+        size_bytes,
+        partial: !skipped_files.is_empty(),
+        skipped_files,
+        server_flush,
     })
 }
 
+/// Suffix of the report listing the files a partial backup left out.
+const SKIPPED_REPORT_SUFFIX: &str = ".skipped.json";
+
+/// Suffix of the report recording the RCON flush before a backup.
+const FLUSH_REPORT_SUFFIX: &str = ".flush.json";
+
+/// Reports kept next to a backup, removed along with it.
+const REPORT_SUFFIXES: [&str; 2] = [SKIPPED_REPORT_SUFFIX, FLUSH_REPORT_SUFFIX];
+
+/// Report written next to a partial backup.
+#[derive(Debug, Serialize, Deserialize)]
+struct SkippedReport {
+    skipped_files: Vec<SkippedFile>,
+}
+
+/// Returns the path of a report next to a backup (e.g., `<backup>.skipped.json`).
+fn report_path(backup_path: &Path, suffix: &str) -> PathBuf {
+    let mut name = backup_path.file_name().unwrap_or_default().to_os_string();
+    name.push(suffix);
+    backup_path.with_file_name(name)
+}
+
+/// Returns the path of a backup's skipped-files report (`<backup>.skipped.json`).
+fn skipped_report_path(backup_path: &Path) -> PathBuf {
+    report_path(backup_path, SKIPPED_REPORT_SUFFIX)
+}
+
+/// Writes a report next to a backup.
+fn write_report<T: Serialize>(backup_path: &Path, suffix: &str, report: &T) -> FileOpsResult<()> {
+    let json = serde_json::to_string_pretty(report).map_err(|e| FileOpsError::Io(e.into()))?;
+    fs::write(report_path(backup_path, suffix), json)?;
+    Ok(())
+}
+
+/// Writes the skipped-files report of a partial backup.
+fn write_skipped_report(backup_path: &Path, skipped_files: &[SkippedFile]) -> FileOpsResult<()> {
+    let report = SkippedReport {
+        skipped_files: skipped_files.to_vec(),
+    };
+    write_report(backup_path, SKIPPED_REPORT_SUFFIX, &report)
+}
+
+/// Reads the files a backup left out; empty for complete backups.
+///
+/// An unreadable report still marks the backup partial, with the read error as the reason.
+fn read_skipped_files(backup_path: &Path) -> Vec<SkippedFile> {
+    let report_path = skipped_report_path(backup_path);
+    if !report_path.exists() {
+        return Vec::new();
+    }
+
+    fs::read_to_string(&report_path)
+        .map_err(|e| e.to_string())
+        .and_then(|json| serde_json::from_str::<SkippedReport>(&json).map_err(|e| e.to_string()))
+        .map(|report| report.skipped_files)
+        .unwrap_or_else(|reason| {
+            vec![SkippedFile {
+                path: report_path.file_name().unwrap_or_default().to_string_lossy().into_owned(),
+                reason,
+            }]
+        })
+}
+
+/// Reads the RCON flush recorded for a backup; None if it wasn't flushed or the
+/// record is unreadable.
+fn read_server_flush(backup_path: &Path) -> Option<ServerFlush> {
+    let json = fs::read_to_string(report_path(backup_path, FLUSH_REPORT_SUFFIX)).ok()?;
+    serde_json::from_str(&json).ok()
+}
+
+/// Removes the reports next to a backup, if any (best-effort).
+fn remove_reports(backup_path: &Path) {
+    for suffix in REPORT_SUFFIXES {
+        let _ = fs::remove_file(report_path(backup_path, suffix));
+    }
+}
+
 /// Cleans up leftover temporary files from interrupted backup operations.
 ///
 /// # Arguments
@@ -246,6 +455,7 @@ pub fn create_backup(save_name: &str) -> BackupResultT<BackupResult> {
 ///
 /// # Behavior
 /// - Removes all `.tar.gz.tmp` files in the backup directory
+/// - Removes reports (skipped files, server flush) whose backup no longer exists
 /// - Silently ignores errors (cleanup is best-effort)
 fn cleanup_temp_files(save_backup_dir: &Path) {
     if !save_backup_dir.exists() {
@@ -261,6 +471,12 @@ fn cleanup_temp_files(save_backup_dir: &Path) {
                         if name_str.ends_with(".tar.gz.tmp") {
                             // Silently ignore errors during cleanup
                             let _ = fs::remove_file(&path);
+                        } else if let Some(backup) =
+                            REPORT_SUFFIXES.iter().find_map(|suffix| name_str.strip_suffix(suffix))
+                        {
+                            if !save_backup_dir.join(backup).exists() {
+                                let _ = fs::remove_file(&path);
+                            }
                         }
                     }
                 }
@@ -273,42 +489,184 @@ fn cleanup_temp_files(save_backup_dir: &Path) {
 ///
 /// # Arguments
 /// * `save_backup_dir` - Directory containing backups for a specific save
-/// * `retention_count` - Maximum number of backups to retain
+/// * `retention_count` - Maximum number of unprotected backups to retain
+/// * `is_protected` - Whether a backup (by file name) must never be deleted
 ///
 /// # Returns
-/// `FileOpsResult<(usize, usize)>` - (retained_count, deleted_count)
+/// `FileOpsResult<(usize, Vec<String>)>` - (retained_count, names of deleted backups)
 ///
 /// # Behavior
 /// - Lists all backup tar.gz files sorted by creation time (newest first)
-/// - Keeps the newest `retention_count` backups
-/// - Deletes older backups
+/// - Keeps protected backups, without counting them toward `retention_count`
+/// - Keeps the newest `retention_count` other backups and deletes the rest
 fn garbage_collection(
     save_backup_dir: &Path,
     retention_count: usize,
-) -> FileOpsResult<(usize, usize)> {
+    is_protected: &dyn Fn(&str) -> bool,
+) -> FileOpsResult<(usize, Vec<String>)> {
+    let (total_backups, to_delete) =
+        select_expired_backups(save_backup_dir, retention_count, is_protected)?;
+
+    // Delete old backups
+    let mut deleted = Vec::with_capacity(to_delete.len());
+    for backup in to_delete {
+        let backup_path = save_backup_dir.join(&backup.name);
+        // A failed deletion is not critical; the backup is retried on the next GC
+        if delete_file(&backup_path).is_ok() {
+            remove_reports(&backup_path);
+            deleted.push(backup.name);
+        }
+    }
+
+    let retained = total_backups.saturating_sub(deleted.len());
+
+    Ok((retained, deleted))
+}
+
+/// Selects the backups garbage collection would delete.
+///
+/// # Returns
+/// `FileOpsResult<(usize, Vec<BackupFile>)>` - (total backup count, unprotected backups
+/// beyond the newest `retention_count`)
+fn select_expired_backups(
+    save_backup_dir: &Path,
+    retention_count: usize,
+    is_protected: &dyn Fn(&str) -> bool,
+) -> FileOpsResult<(usize, Vec<BackupFile>)> {
     let mut backups = list_backup_files(save_backup_dir)?;
+    let total_backups = backups.len();
+
+    // Protected backups are kept outside the quota
+    backups.retain(|backup| !is_protected(&backup.name));
 
     // Sort by creation time (newest first)
     backups.sort_by(|a, b| b.created.cmp(&a.created));
 
-    let total_backups = backups.len();
-    let to_delete = if total_backups > retention_count {
+    let to_delete = if backups.len() > retention_count {
         backups.split_off(retention_count)
     } else {
         Vec::new()
     };
 
-    // Delete old backups
-    for backup in &to_delete {
-        let backup_path = save_backup_dir.join(&backup.name);
-        // Silently ignore errors during GC - a failed deletion is not critical
-        let _ = delete_file(&backup_path);
+    Ok((total_backups, to_delete))
+}
+
+/// Returns whether a backup of the save carries a protected tag.
+///
+/// # Behavior
+/// If the tags can't be read, every backup counts as protected: pruning then deletes
+/// nothing rather than risk deleting a backup the user asked to keep.
+fn backup_protection(save_name: &str) -> impl Fn(&str) -> bool + '_ {
+    move |backup_name| {
+        crate::tags::is_backup_protected(save_name, backup_name).unwrap_or_else(|err| {
+            eprintln!("[WARN] Failed to check protection of {}: {}", backup_name, err);
+            true
+        })
     }
+}
 
-    let retained = total_backups.saturating_sub(to_delete.len());
-    let deleted = to_delete.len();
+/// Result of pruning a save's backups.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PruneResult {
+    /// Relative path of the save
+    pub save_name: String,
+    /// Number of backups kept
+    pub retained_count: usize,
+    /// Names of the deleted backups (or, in a dry run, of those that would be deleted)
+    pub deleted: Vec<String>,
+    /// Whether nothing was actually deleted
+    pub dry_run: bool,
+}
 
-    Ok((retained, deleted))
+/// Applies the retention policy to a save's backups.
+///
+/// # Arguments
+/// * `save_name` - Relative path of the save (e.g., "Survival/MySave")
+/// * `dry_run` - Only report what would be deleted
+///
+/// # Returns
+/// `BackupResultT<PruneResult>` - Backups kept and deleted
+///
+/// # Behavior
+/// Deletes the same backups garbage collection after a backup would, keeping the
+/// newest `retention_count` and every backup with a protected tag. Tags of deleted
+/// backups are removed (best-effort).
+pub fn prune_backups(save_name: &str, dry_run: bool) -> BackupResultT<PruneResult> {
+    let config = config_module::load_config()?;
+    let backup_base_path = config.get_backup_path()?;
+    let save_backup_dir = get_save_backup_dir(&backup_base_path, save_name)?;
+
+    let is_protected = backup_protection(save_name);
+
+    let (retained_count, deleted) = if dry_run {
+        let (total, expired) =
+            select_expired_backups(&save_backup_dir, config.retention_count, &is_protected)?;
+        let names: Vec<String> = expired.into_iter().map(|b| b.name).collect();
+        (total - names.len(), names)
+    } else {
+        let (retained, deleted) =
+            garbage_collection(&save_backup_dir, config.retention_count, &is_protected)?;
+        if !deleted.is_empty() {
+            listing_cache::invalidate(save_name);
+            crate::tags::clear_tags_of_backups_best_effort(save_name, &deleted);
+        }
+        (retained, deleted)
+    };
+
+    Ok(PruneResult {
+        save_name: save_name.to_string(),
+        retained_count,
+        deleted,
+        dry_run,
+    })
+}
+
+/// Lists the saves that have backups, as relative paths (e.g., "Survival/MySave").
+///
+/// # Returns
+/// `BackupResultT<Vec<String>>` - Sorted relative paths of backup directories holding
+/// at least one backup
+///
+/// # Behavior
+/// Unlike `list_saves_with_backups`, nested saves are listed by their full relative
+/// path, and saves deleted from the game are included. Undo snapshot directories
+/// (`<save>_undo`) and hidden directories are skipped.
+pub fn list_backed_up_saves() -> BackupResultT<Vec<String>> {
+    let config = config_module::load_config()?;
+    let backup_base_path = config.get_backup_path()?;
+
+    let mut saves = Vec::new();
+    collect_backed_up_saves(&backup_base_path, Path::new(""), &mut saves)?;
+    saves.sort();
+    Ok(saves)
+}
+
+fn collect_backed_up_saves(
+    dir: &Path,
+    relative_dir: &Path,
+    saves: &mut Vec<String>,
+) -> FileOpsResult<()> {
+    if !dir.is_dir() {
+        return Ok(());
+    }
+
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if !path.is_dir() || name.starts_with('.') || name.ends_with("_undo") {
+            continue;
+        }
+
+        let relative_path = relative_dir.join(&*name);
+        if !list_backup_files(&path)?.is_empty() {
+            saves.push(relative_path.to_string_lossy().replace('\\', "/"));
+        }
+        collect_backed_up_saves(&path, &relative_path, saves)?;
+    }
+
+    Ok(())
 }
 
 /// Internal struct for tracking backup files during GC.
@@ -316,6 +674,7 @@ fn garbage_collection(
 struct BackupFile {
     name: String,
     created: SystemTime,
+    size_bytes: u64,
 }
 
 /// Lists all backup tar.gz files in a save's backup folder.
@@ -355,6 +714,7 @@ fn list_backup_files(save_backup_dir: &Path) -> FileOpsResult<Vec<BackupFile>> {
                         backups.push(BackupFile {
                             name: name_str.to_string(),
                             created,
+                            size_bytes: metadata.len(),
                         });
                     }
                 }
@@ -365,6 +725,31 @@ fn list_backup_files(save_backup_dir: &Path) -> FileOpsResult<Vec<BackupFile>> {
     Ok(backups)
 }
 
+/// Gets aggregate backup statistics for a specific save.
+///
+/// # Arguments
+/// * `save_name` - Relative path of the save (e.g., "sandbox/aaa")
+///
+/// # Returns
+/// `BackupResultT<BackupStats>` - Backup count, total size and newest backup time
+///
+/// # Behavior
+/// - Only reads file metadata; archives are not opened (unlike `list_backups`)
+/// - Returns empty statistics if the save has no backup folder
+pub fn get_backup_stats(save_name: &str) -> BackupResultT<BackupStats> {
+    let config = config_module::load_config()?;
+    let backup_base_path = config.get_backup_path()?;
+    let save_backup_dir = get_save_backup_dir(&backup_base_path, save_name)?;
+
+    let backups = list_backup_files(&save_backup_dir)?;
+
+    Ok(BackupStats {
+        backup_count: backups.len(),
+        total_bytes: backups.iter().map(|b| b.size_bytes).sum(),
+        last_backup_at: backups.iter().map(|b| DateTime::<Utc>::from(b.created)).max(),
+    })
+}
+
 /// Lists all backups for a specific save.
 ///
 /// # Arguments
@@ -374,20 +759,44 @@ fn list_backup_files(save_backup_dir: &Path) -> FileOpsResult<Vec<BackupFile>> {
 /// `BackupResultT<Vec<BackupInfo>>` - List of backups sorted by creation time (newest first)
 ///
 /// # Behavior
+/// Same as `list_backups_cached`, without the generation.
+pub fn list_backups(save_name: &str) -> BackupResultT<Vec<BackupInfo>> {
+    Ok(list_backups_cached(save_name)?.backups)
+}
+
+/// Lists all backups for a specific save, served from the listing cache when unchanged.
+///
+/// # Arguments
+/// * `save_name` - Relative path of the save (e.g., "sandbox/aaa")
+///
+/// # Returns
+/// `BackupResultT<BackupListing>` - Backups sorted by creation time (newest first),
+/// with the generation they belong to
+///
+/// # Behavior
 /// - Only includes completed .tar.gz files (excludes .tmp temporary files)
 /// - Populates tag information for each backup
-pub fn list_backups(save_name: &str) -> BackupResultT<Vec<BackupInfo>> {
+/// - Reassembles the listing only after a backup or tag change (see `listing_cache`)
+pub fn list_backups_cached(save_name: &str) -> BackupResultT<BackupListing> {
     let config = config_module::load_config()?;
     let backup_base_path = config.get_backup_path()?;
-    let save_backup_dir = get_save_backup_dir(&backup_base_path, save_name);
+    let save_backup_dir = get_save_backup_dir(&backup_base_path, save_name)?;
 
+    listing_cache::get_or_assemble(save_name, &save_backup_dir, || {
+        assemble_backup_listing(save_name, &save_backup_dir)
+    })
+}
+
+/// Reads the backups of a save from disk, joining their tags and thumbnails.
+fn assemble_backup_listing(save_name: &str, save_backup_dir: &Path) -> BackupResultT<Vec<BackupInfo>> {
     if !save_backup_dir.exists() {
         return Ok(Vec::new());
     }
 
     let mut backups = Vec::new();
+    let mut tags_by_backup = crate::tags::get_all_backup_tags_for_save(save_name).unwrap_or_default();
 
-    for entry in fs::read_dir(&save_backup_dir).map_err(FileOpsError::Io)? {
+    for entry in fs::read_dir(save_backup_dir).map_err(FileOpsError::Io)? {
         let entry = entry.map_err(FileOpsError::Io)?;
         let path = entry.path();
 
@@ -409,13 +818,13 @@ pub fn list_backups(save_name: &str) -> BackupResultT<Vec<BackupInfo>> {
                         let created_dt: DateTime<Utc> = created.into();
                         let created_at = created_dt.to_rfc3339();
 
-                        // Get tags for this backup
-                        let tags = crate::tags::get_backup_tags(save_name, name_str)
-                            .unwrap_or_default();
+                        // Get tags for this backup; the tags map is keyed by the NFC name
+                        let tags = tags_by_backup.remove(BackupKey::new(name_str).as_str()).unwrap_or_default();
 
                         // Read thumb.png from the tar.gz archive
                         let thumb_data = crate::file_ops::read_file_from_tar_gz_base64(&path, "thumb.png")
                             .unwrap_or(None);
+                        let skipped_files = read_skipped_files(&path);
 
                         backups.push(BackupInfo {
                             name: name_str.to_string(),
@@ -426,6 +835,9 @@ pub fn list_backups(save_name: &str) -> BackupResultT<Vec<BackupInfo>> {
                             save_name: save_name.to_string(),
                             tags,
                             thumb_data,
+                            partial: !skipped_files.is_empty(),
+                            skipped_files,
+                            server_flush: read_server_flush(&path),
                         });
                     }
                 }
@@ -450,7 +862,7 @@ pub fn list_backups(save_name: &str) -> BackupResultT<Vec<BackupInfo>> {
 pub fn get_backup_info(save_name: &str, backup_name: &str) -> BackupResultT<BackupInfo> {
     let config = config_module::load_config()?;
     let backup_base_path = config.get_backup_path()?;
-    let save_backup_dir = get_save_backup_dir(&backup_base_path, save_name);
+    let save_backup_dir = get_save_backup_dir(&backup_base_path, save_name)?;
     let backup_path = save_backup_dir.join(backup_name);
 
     if !backup_path.exists() {
@@ -478,6 +890,7 @@ pub fn get_backup_info(save_name: &str, backup_name: &str) -> BackupResultT<Back
     // Read thumb.png from the tar.gz archive
     let thumb_data = crate::file_ops::read_file_from_tar_gz_base64(&backup_path, "thumb.png")
         .unwrap_or(None);
+    let skipped_files = read_skipped_files(&backup_path);
 
     Ok(BackupInfo {
         name: backup_name.to_string(),
@@ -488,6 +901,9 @@ pub fn get_backup_info(save_name: &str, backup_name: &str) -> BackupResultT<Back
         save_name: save_name.to_string(),
         tags,
         thumb_data,
+        partial: !skipped_files.is_empty(),
+        skipped_files,
+        server_flush: read_server_flush(&backup_path),
     })
 }
 
@@ -542,7 +958,7 @@ pub fn count_backups(save_name: &str) -> BackupResultT<usize> {
 /// * `backup_name` - Name of the backup file to delete (e.g., "aaa_2024-12-28_14-30-45.tar.gz")
 ///
 /// # Returns
-/// `BackupResultT<()>` - Ok(()) on success
+/// `BackupResultT<DeleteBackupResult>` - Same as `delete_backup`
 ///
 /// # Behavior
 /// Runs the synchronous delete operation in a blocking thread pool to avoid
@@ -550,13 +966,12 @@ pub fn count_backups(save_name: &str) -> BackupResultT<usize> {
 ///
 /// # Safety
 /// This is a destructive operation. Frontend should confirm with user before calling.
-pub async fn delete_backup_async(save_name: &str, backup_name: &str) -> BackupResultT<()> {
+pub async fn delete_backup_async(save_name: &str, backup_name: &str) -> BackupResultT<DeleteBackupResult> {
     let save_name = save_name.to_string();
     let backup_name = backup_name.to_string();
     tokio::task::spawn_blocking(move || delete_backup(&save_name, &backup_name))
         .await
-        .map_err(|e| BackupError::FileOp(FileOpsError::Io(std::io::Error::new(
-            std::io::ErrorKind::Other,
+        .map_err(|e| BackupError::FileOp(FileOpsError::Io(std::io::Error::other(
             format!("Task join error: {}", e),
         ))))?
 }
@@ -568,14 +983,21 @@ pub async fn delete_backup_async(save_name: &str, backup_name: &str) -> BackupRe
 /// * `backup_name` - Name of the backup file to delete (e.g., "aaa_2024-12-28_14-30-45.tar.gz")
 ///
 /// # Returns
-/// `BackupResultT<()>` - Ok(()) on success
+/// `BackupResultT<DeleteBackupResult>` - Ok on success, with any cleanup warnings;
+/// `InvalidBackupName` if the name isn't a plain file name
+///
+/// # Behavior
+/// Also removes the backup's tags. That cleanup is best-effort: once the file is
+/// deleted, a tags database failure is recorded in the history and returned as a
+/// warning instead of an error.
 ///
 /// # Safety
 /// This is a destructive operation. Frontend should confirm with user before calling.
-pub fn delete_backup(save_name: &str, backup_name: &str) -> BackupResultT<()> {
+pub fn delete_backup(save_name: &str, backup_name: &str) -> BackupResultT<DeleteBackupResult> {
+    validate_backup_name(backup_name)?;
     let config = config_module::load_config()?;
     let backup_base_path = config.get_backup_path()?;
-    let save_backup_dir = get_save_backup_dir(&backup_base_path, save_name);
+    let save_backup_dir = get_save_backup_dir(&backup_base_path, save_name)?;
     let backup_path = save_backup_dir.join(backup_name);
 
     if !backup_path.exists() {
@@ -586,6 +1008,80 @@ pub fn delete_backup(save_name: &str, backup_name: &str) -> BackupResultT<()> {
     }
 
     delete_file(&backup_path)?;
+    remove_reports(&backup_path);
+    listing_cache::invalidate(save_name);
+    let warnings = crate::tags::clear_backup_tags_best_effort(save_name, backup_name)
+        .into_iter()
+        .collect();
+    Ok(DeleteBackupResult { warnings })
+}
+
+/// Checks that a backup name is a plain file name inside the save's backup directory.
+///
+/// # Returns
+/// `BackupResultT<()>` - `InvalidBackupName` if the name is empty, contains a path
+/// separator or starts with a dot
+fn validate_backup_name(backup_name: &str) -> BackupResultT<()> {
+    if backup_name.is_empty() || backup_name.contains(['/', '\\']) || backup_name.starts_with('.') {
+        return Err(BackupError::InvalidBackupName(backup_name.to_string()));
+    }
+    Ok(())
+}
+
+/// Renames a backup archive, keeping its tags and reports.
+///
+/// # Arguments
+/// * `save_name` - Relative path of the save (e.g., "sandbox/aaa")
+/// * `backup_name` - Current name of the backup file
+/// * `new_backup_name` - New name; must end with `.tar.gz` and contain no path separators
+///
+/// # Returns
+/// `BackupResultT<()>` - `InvalidBackupName` for a bad current or new name,
+/// `DestinationExists` if another backup has it
+///
+/// # Behavior
+/// Tags move to the new name with `tags::rename_backup_target`. If that fails, the
+/// archive is renamed back so tags and files stay consistent.
+pub fn rename_backup(save_name: &str, backup_name: &str, new_backup_name: &str) -> BackupResultT<()> {
+    validate_backup_name(backup_name)?;
+    let new_backup_name = new_backup_name.trim();
+    validate_backup_name(new_backup_name)?;
+    if !new_backup_name.ends_with(".tar.gz") {
+        return Err(BackupError::InvalidBackupName(new_backup_name.to_string()));
+    }
+
+    let config = config_module::load_config()?;
+    let backup_base_path = config.get_backup_path()?;
+    let save_backup_dir = get_save_backup_dir(&backup_base_path, save_name)?;
+    let backup_path = save_backup_dir.join(backup_name);
+    let new_backup_path = save_backup_dir.join(new_backup_name);
+
+    if !backup_path.is_file() {
+        return Err(BackupError::BackupNotFound(format!(
+            "{}/{}",
+            save_name, backup_name
+        )));
+    }
+    if backup_name == new_backup_name {
+        return Ok(());
+    }
+    if new_backup_path.exists() {
+        return Err(FileOpsError::DestinationExists(new_backup_path).into());
+    }
+
+    fs::rename(&backup_path, &new_backup_path).map_err(FileOpsError::Io)?;
+    if let Err(e) = crate::tags::rename_backup_target(save_name, backup_name, new_backup_name) {
+        let _ = fs::rename(&new_backup_path, &backup_path);
+        return Err(FileOpsError::Io(std::io::Error::other(format!(
+            "Failed to move tags to the new name: {}",
+            e
+        )))
+        .into());
+    }
+    for suffix in REPORT_SUFFIXES {
+        let _ = fs::rename(report_path(&backup_path, suffix), report_path(&new_backup_path, suffix));
+    }
+    listing_cache::invalidate(save_name);
     Ok(())
 }
 
@@ -594,7 +1090,7 @@ mod tests {
     use super::*;
     use crate::config as config_module;
     use crate::config::Config;
-    use serial_test::serial;
+    use crate::test_support::TestEnv;
     use std::fs::{self, File};
     use std::io::Write;
     use std::path::Path;
@@ -641,8 +1137,18 @@ mod tests {
     #[test]
     fn test_get_save_backup_dir() {
         let base = Path::new("/backups");
-        let save_dir = get_save_backup_dir(base, "Survival");
+        let save_dir = get_save_backup_dir(base, "Survival").unwrap();
         assert_eq!(save_dir, Path::new("/backups/Survival"));
+        assert_eq!(get_save_backup_dir(base, "Survival/MySave").unwrap(), Path::new("/backups/Survival/MySave"));
+
+        // Names that would leave the backup directory
+        for unsafe_name in ["", "../../somewhere", "Survival/../../x", "/etc", ".."] {
+            assert!(
+                matches!(get_save_backup_dir(base, unsafe_name), Err(FileOpsError::UnsafeSavePath(_))),
+                "{:?}",
+                unsafe_name
+            );
+        }
     }
 
     #[test]
@@ -666,8 +1172,8 @@ mod tests {
     }
 
     #[test]
-    #[serial]
     fn test_create_backup_success() {
+        let _env = TestEnv::new();
         let save_base = TempDir::new().unwrap();
         let backup_base = TempDir::new().unwrap();
 
@@ -685,8 +1191,8 @@ mod tests {
     }
 
     #[test]
-    #[serial]
     fn test_create_backup_save_not_found() {
+        let _env = TestEnv::new();
         let save_base = TempDir::new().unwrap();
         let backup_base = TempDir::new().unwrap();
 
@@ -696,6 +1202,52 @@ mod tests {
         assert!(matches!(result, Err(BackupError::SaveNotFound(_))));
     }
 
+    #[test]
+    fn test_create_backup_cancelled() {
+        let _env = TestEnv::new();
+        let save_base = TempDir::new().unwrap();
+        let backup_base = TempDir::new().unwrap();
+
+        let save_dir = save_base.path().join("Survival");
+        create_test_save(&save_dir);
+
+        setup_test_config(save_base.path(), backup_base.path());
+
+        let registry = crate::tasks::TaskRegistry::new();
+        let task = registry.start(crate::tasks::TaskKind::Backup, "Survival", true);
+        registry.cancel(task.id());
+
+        let result = create_backup_with_task("Survival", &task);
+        assert!(matches!(
+            result,
+            Err(BackupError::FileOp(FileOpsError::Cancelled))
+        ));
+
+        // Neither a backup nor a temporary file is left behind
+        let save_backup_dir = get_save_backup_dir(backup_base.path(), "Survival").unwrap();
+        assert_eq!(fs::read_dir(&save_backup_dir).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_create_backup_reports_progress() {
+        let _env = TestEnv::new();
+        let save_base = TempDir::new().unwrap();
+        let backup_base = TempDir::new().unwrap();
+
+        let save_dir = save_base.path().join("Survival");
+        create_test_save(&save_dir);
+
+        setup_test_config(save_base.path(), backup_base.path());
+
+        let registry = crate::tasks::TaskRegistry::new();
+        let task = registry.start(crate::tasks::TaskKind::Backup, "Survival", true);
+        create_backup_with_task("Survival", &task).unwrap();
+
+        let info = &registry.list_active()[0];
+        assert!(info.progress_total > 0);
+        assert_eq!(info.progress_done, info.progress_total);
+    }
+
     #[test]
     fn test_garbage_collection_with_retention_limit() {
         let temp_dir = TempDir::new().unwrap();
@@ -712,10 +1264,11 @@ mod tests {
         }
 
         // Set retention to 3
-        let (retained, deleted) = garbage_collection(temp_dir.path(), 3).unwrap();
+        let (retained, deleted) = garbage_collection(temp_dir.path(), 3, &|_| false).unwrap();
 
         assert_eq!(retained, 3);
-        assert_eq!(deleted, 2);
+        assert_eq!(deleted.len(), 2);
+        assert!(deleted.iter().all(|name| !temp_dir.path().join(name).exists()));
 
         // Verify only 3 backups remain
         let remaining = list_backup_files(temp_dir.path()).unwrap();
@@ -738,10 +1291,10 @@ mod tests {
         }
 
         // Set retention to 5 (more than existing)
-        let (retained, deleted) = garbage_collection(temp_dir.path(), 5).unwrap();
+        let (retained, deleted) = garbage_collection(temp_dir.path(), 5, &|_| false).unwrap();
 
         assert_eq!(retained, 2);
-        assert_eq!(deleted, 0);
+        assert!(deleted.is_empty());
 
         // Verify all backups remain
         let remaining = list_backup_files(temp_dir.path()).unwrap();
@@ -749,8 +1302,8 @@ mod tests {
     }
 
     #[test]
-    #[serial]
     fn test_list_backups_empty() {
+        let _env = TestEnv::new();
         let save_base = TempDir::new().unwrap();
         let backup_base = TempDir::new().unwrap();
 
@@ -761,8 +1314,8 @@ mod tests {
     }
 
     #[test]
-    #[serial]
     fn test_list_backups_with_data() {
+        let _env = TestEnv::new();
         let save_base = TempDir::new().unwrap();
         let backup_base = TempDir::new().unwrap();
 
@@ -784,8 +1337,8 @@ mod tests {
     }
 
     #[test]
-    #[serial]
     fn test_get_backup_info_success() {
+        let _env = TestEnv::new();
         let save_base = TempDir::new().unwrap();
         let backup_base = TempDir::new().unwrap();
 
@@ -809,8 +1362,192 @@ mod tests {
     }
 
     #[test]
-    #[serial]
+    fn test_partial_backup_is_reported_and_cleaned_up() {
+        let _env = TestEnv::new();
+        let save_base = TempDir::new().unwrap();
+        let backup_base = TempDir::new().unwrap();
+        create_test_save(&save_base.path().join("Survival"));
+        setup_test_config(save_base.path(), backup_base.path());
+
+        let result = create_backup("Survival").unwrap();
+        assert!(!result.partial);
+        let backup_path = backup_base.path().join("Survival").join(&result.backup_name);
+        assert!(!skipped_report_path(&backup_path).exists());
+
+        let skipped = vec![SkippedFile {
+            path: "map/chunk_1.bin".to_string(),
+            reason: "file is locked".to_string(),
+        }];
+        write_skipped_report(&backup_path, &skipped).unwrap();
+
+        let info = get_backup_info("Survival", &result.backup_name).unwrap();
+        assert!(info.partial);
+        assert_eq!(info.skipped_files, skipped);
+        // The report is not listed as a backup of its own
+        let backups = list_backups("Survival").unwrap();
+        assert_eq!(backups.len(), 1);
+        assert!(backups[0].partial);
+
+        delete_backup("Survival", &result.backup_name).unwrap();
+        assert!(!skipped_report_path(&backup_path).exists());
+
+        // Reports left behind by a crash are removed before the next backup
+        write_skipped_report(&backup_path, &skipped).unwrap();
+        cleanup_temp_files(&backup_base.path().join("Survival"));
+        assert!(!skipped_report_path(&backup_path).exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_create_backup_skips_unreadable_file() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let _env = TestEnv::new();
+        let save_base = TempDir::new().unwrap();
+        let backup_base = TempDir::new().unwrap();
+        let save_dir = save_base.path().join("Survival");
+        create_test_save(&save_dir);
+        let config = Config {
+            locked_file_attempts: 2,
+            locked_file_retry_delay_ms: 1,
+            ..Config::with_paths(
+                save_base.path().to_str().unwrap().to_string(),
+                backup_base.path().to_str().unwrap().to_string(),
+            )
+        };
+        config_module::save_config(&config).unwrap();
+
+        let unreadable = save_dir.join("map/pchunk_0_0.dat");
+        fs::set_permissions(&unreadable, fs::Permissions::from_mode(0o000)).unwrap();
+        let readable = File::open(&unreadable).is_ok();
+        let result = create_backup("Survival");
+        fs::set_permissions(&unreadable, fs::Permissions::from_mode(0o644)).unwrap();
+
+        // Permissions don't apply when running as root
+        if readable {
+            return;
+        }
+        let result = result.unwrap();
+        assert!(result.partial);
+        assert_eq!(result.skipped_files.len(), 1);
+        assert_eq!(result.skipped_files[0].path, "map/pchunk_0_0.dat");
+
+        let info = get_backup_info("Survival", &result.backup_name).unwrap();
+        assert!(info.partial);
+        assert_eq!(info.skipped_files, result.skipped_files);
+    }
+
+    #[test]
+    fn test_create_backup_records_server_flush() {
+        use crate::rcon::tests::{MockBehavior, MockServer};
+        use crate::rcon::FlushFailure;
+
+        let _env = TestEnv::new();
+        let save_base = TempDir::new().unwrap();
+        let backup_base = TempDir::new().unwrap();
+        let save_dir = save_base.path().join("Multiplayer/servertest");
+        create_test_save(&save_dir);
+        setup_test_config(save_base.path(), backup_base.path());
+        crate::secrets::set_secret(&rcon::rcon_password_key("Multiplayer/servertest"), "wrong").unwrap();
+
+        // A rejected password doesn't abort the backup
+        let server = MockServer::start("hunter2", MockBehavior::Normal, Some(&save_dir));
+        config_module::modify_config(|config| {
+            config.rcon = vec![server.config("Multiplayer/servertest")];
+            Ok(())
+        })
+        .unwrap();
+        let result = create_backup("Multiplayer/servertest").unwrap();
+        server.finish();
+        let flush = result.server_flush.clone().unwrap();
+        assert!(!flush.flushed);
+        assert_eq!(flush.failure, Some(FlushFailure::AuthFailed));
+        assert!(backup_base.path().join("Multiplayer/servertest").join(&result.backup_name).exists());
+
+        let info = get_backup_info("Multiplayer/servertest", &result.backup_name).unwrap();
+        assert_eq!(info.server_flush, Some(flush));
+
+        delete_backup("Multiplayer/servertest", &result.backup_name).unwrap();
+        let flush_report = report_path(
+            &backup_base.path().join("Multiplayer/servertest").join(&result.backup_name),
+            FLUSH_REPORT_SUFFIX,
+        );
+        assert!(!flush_report.exists());
+
+        // Saves without RCON settings record nothing
+        create_test_save(&save_base.path().join("Survival"));
+        assert_eq!(create_backup("Survival").unwrap().server_flush, None);
+    }
+
+    #[test]
+    fn test_prune_backups_and_list_backed_up_saves() {
+        let _env = TestEnv::new();
+        let save_base = TempDir::new().unwrap();
+        let backup_base = TempDir::new().unwrap();
+        create_test_save(&save_base.path().join("Survival/Pruned"));
+        setup_test_config(save_base.path(), backup_base.path());
+
+        let created = create_backup("Survival/Pruned").unwrap();
+        let save_backup_dir = backup_base.path().join("Survival/Pruned");
+        for name in ["2000-01-01_00-00-00.tar.gz", "2000-01-02_00-00-00.tar.gz"] {
+            fs::copy(save_backup_dir.join(&created.backup_name), save_backup_dir.join(name)).unwrap();
+        }
+        // Undo snapshots are not backups of their own
+        fs::create_dir_all(backup_base.path().join("Survival/Pruned_undo")).unwrap();
+        fs::write(backup_base.path().join("Survival/Pruned_undo/undo.tar.gz"), b"x").unwrap();
+        assert_eq!(list_backed_up_saves().unwrap(), vec!["Survival/Pruned".to_string()]);
+
+        config_module::modify_config(|config| {
+            config.retention_count = 1;
+            Ok(())
+        })
+        .unwrap();
+
+        let dry_run = prune_backups("Survival/Pruned", true).unwrap();
+        assert_eq!(dry_run.deleted.len(), 2);
+        assert_eq!(dry_run.retained_count, 1);
+        assert_eq!(count_backups("Survival/Pruned").unwrap(), 3);
+
+        let pruned = prune_backups("Survival/Pruned", false).unwrap();
+        assert_eq!(pruned.deleted, dry_run.deleted);
+        assert_eq!(count_backups("Survival/Pruned").unwrap(), 1);
+    }
+
+    #[test]
+    fn test_protected_backups_survive_prune() {
+        let _env = TestEnv::new();
+        let save_base = TempDir::new().unwrap();
+        let backup_base = TempDir::new().unwrap();
+        create_test_save(&save_base.path().join("Survival"));
+        setup_test_config(save_base.path(), backup_base.path());
+
+        let created = create_backup("Survival").unwrap();
+        let save_backup_dir = backup_base.path().join("Survival");
+        let copies = ["2000-01-01_00-00-00.tar.gz", "2000-01-02_00-00-00.tar.gz", "2000-01-03_00-00-00.tar.gz"];
+        for name in copies {
+            fs::copy(save_backup_dir.join(&created.backup_name), save_backup_dir.join(name)).unwrap();
+        }
+        crate::tags::create_tag("keep-forever".to_string(), "#FF0000".to_string(), None, None).unwrap();
+        crate::tags::set_tag_protected("keep-forever".to_string(), true).unwrap();
+        crate::tags::add_tags_to_backup("Survival", copies[0], vec!["keep-forever".to_string()]).unwrap();
+        config_module::modify_config(|config| {
+            config.retention_count = 1;
+            Ok(())
+        })
+        .unwrap();
+
+        // The protected backup is kept on top of the one backup the quota allows
+        let pruned = prune_backups("Survival", false).unwrap();
+        assert_eq!(pruned.deleted.len(), 2);
+        assert!(!pruned.deleted.iter().any(|name| name == copies[0]));
+        assert_eq!(pruned.retained_count, 2);
+        assert!(save_backup_dir.join(copies[0]).exists());
+        assert_eq!(count_backups("Survival").unwrap(), 2);
+    }
+
+    #[test]
     fn test_get_backup_info_not_found() {
+        let _env = TestEnv::new();
         let save_base = TempDir::new().unwrap();
         let backup_base = TempDir::new().unwrap();
 
@@ -821,8 +1558,8 @@ mod tests {
     }
 
     #[test]
-    #[serial]
     fn test_count_backups() {
+        let _env = TestEnv::new();
         let save_base = TempDir::new().unwrap();
         let backup_base = TempDir::new().unwrap();
 
@@ -843,8 +1580,33 @@ mod tests {
     }
 
     #[test]
-    #[serial]
+    fn test_get_backup_stats() {
+        let _env = TestEnv::new();
+        let save_base = TempDir::new().unwrap();
+        let backup_base = TempDir::new().unwrap();
+
+        let save_dir = save_base.path().join("Survival");
+        create_test_save(&save_dir);
+
+        setup_test_config(save_base.path(), backup_base.path());
+
+        assert_eq!(get_backup_stats("Survival").unwrap(), BackupStats::default());
+
+        create_backup("Survival").unwrap();
+        let stats = get_backup_stats("Survival").unwrap();
+        assert_eq!(stats.backup_count, 1);
+        assert!(stats.total_bytes > 0);
+        assert!(stats.last_backup_at.is_some());
+
+        // Stats computed from a listing must agree with the metadata-only scan
+        let listed = BackupStats::from_backups(&list_backups("Survival").unwrap());
+        assert_eq!(listed.backup_count, stats.backup_count);
+        assert_eq!(listed.total_bytes, stats.total_bytes);
+    }
+
+    #[test]
     fn test_list_saves_with_backups() {
+        let _env = TestEnv::new();
         let save_base = TempDir::new().unwrap();
         let backup_base = TempDir::new().unwrap();
 
@@ -874,6 +1636,11 @@ mod tests {
             backup_name: "Survival_2024-12-28_10-00-00".to_string(),
             retained_count: 5,
             deleted_count: 2,
+            deleted_backups: vec!["a.tar.gz".to_string(), "b.tar.gz".to_string()],
+            size_bytes: 1024,
+            partial: false,
+            skipped_files: Vec::new(),
+            server_flush: None,
         };
 
         let json = serde_json::to_string(&result).unwrap();
@@ -883,6 +1650,8 @@ mod tests {
         assert_eq!(parsed.backup_name, result.backup_name);
         assert_eq!(parsed.retained_count, 5);
         assert_eq!(parsed.deleted_count, 2);
+        assert_eq!(parsed.deleted_backups, result.deleted_backups);
+        assert_eq!(parsed.size_bytes, 1024);
     }
 
     #[test]
@@ -896,6 +1665,9 @@ mod tests {
             save_name: "Survival".to_string(),
             tags: Vec::new(),
             thumb_data: None,
+            partial: false,
+            skipped_files: Vec::new(),
+            server_flush: None,
         };
 
         let json = serde_json::to_string(&info).unwrap();
@@ -916,8 +1688,8 @@ mod tests {
     }
 
     #[test]
-    #[serial]
     fn test_multiple_backups_with_gc() {
+        let _env = TestEnv::new();
         let save_base = TempDir::new().unwrap();
         let backup_base = TempDir::new().unwrap();
 
@@ -951,8 +1723,8 @@ mod tests {
     }
 
     #[test]
-    #[serial]
     fn test_delete_backup_success() {
+        let _env = TestEnv::new();
         let save_base = TempDir::new().unwrap();
         let backup_base = TempDir::new().unwrap();
 
@@ -976,8 +1748,8 @@ mod tests {
     }
 
     #[test]
-    #[serial]
     fn test_delete_backup_not_found() {
+        let _env = TestEnv::new();
         let save_base = TempDir::new().unwrap();
         let backup_base = TempDir::new().unwrap();
 
@@ -988,8 +1760,109 @@ mod tests {
     }
 
     #[test]
-    #[serial]
+    fn test_delete_backup_rejects_unsafe_names() {
+        let _env = TestEnv::new();
+        let save_base = TempDir::new().unwrap();
+        let backup_base = TempDir::new().unwrap();
+        create_test_save(&save_base.path().join("Survival"));
+        setup_test_config(save_base.path(), backup_base.path());
+        create_backup("Survival").unwrap();
+        fs::write(backup_base.path().join("outside.tar.gz"), b"other").unwrap();
+
+        for bad in ["../outside.tar.gz", "..\\outside.tar.gz", "", ".", ".hidden.tar.gz"] {
+            assert!(
+                matches!(delete_backup("Survival", bad), Err(BackupError::InvalidBackupName(_))),
+                "{}",
+                bad
+            );
+        }
+        assert!(backup_base.path().join("outside.tar.gz").exists());
+        assert_eq!(count_backups("Survival").unwrap(), 1);
+    }
+
+    #[test]
+    fn test_rename_backup_keeps_tags() {
+        let _env = TestEnv::new();
+        let save_base = TempDir::new().unwrap();
+        let backup_base = TempDir::new().unwrap();
+        create_test_save(&save_base.path().join("Survival"));
+        setup_test_config(save_base.path(), backup_base.path());
+
+        let original = create_backup("Survival").unwrap().backup_name;
+        crate::tags::create_tag("keep".to_string(), "#FF0000".to_string(), None, None).unwrap();
+        crate::tags::add_tags_to_backup("Survival", &original, vec!["keep".to_string()]).unwrap();
+        let tag_names = |name: &str| {
+            list_backups("Survival")
+                .unwrap()
+                .into_iter()
+                .find(|b| b.name == name)
+                .map(|b| b.tags.into_iter().map(|t| t.name).collect::<Vec<_>>())
+        };
+
+        rename_backup("Survival", &original, "before-mods.tar.gz").unwrap();
+        assert_eq!(tag_names("before-mods.tar.gz"), Some(vec!["keep".to_string()]));
+        assert_eq!(tag_names(&original), None);
+
+        rename_backup("Survival", "before-mods.tar.gz", &original).unwrap();
+        assert_eq!(tag_names(&original), Some(vec!["keep".to_string()]));
+        assert!(crate::tags::get_backup_tags("Survival", "before-mods.tar.gz").unwrap().is_empty());
+
+        for bad in ["before-mods", "../escape.tar.gz", ".tar.gz", ".hidden.tar.gz"] {
+            assert!(
+                matches!(rename_backup("Survival", &original, bad), Err(BackupError::InvalidBackupName(_))),
+                "{}",
+                bad
+            );
+        }
+        // The current name can't point outside the save's backup directory either
+        fs::write(backup_base.path().join("outside.tar.gz"), b"other").unwrap();
+        for bad in ["../outside.tar.gz", "", ".hidden.tar.gz"] {
+            assert!(
+                matches!(rename_backup("Survival", bad, "moved.tar.gz"), Err(BackupError::InvalidBackupName(_))),
+                "{}",
+                bad
+            );
+        }
+        assert!(backup_base.path().join("outside.tar.gz").exists());
+        fs::write(backup_base.path().join("Survival/taken.tar.gz"), b"other").unwrap();
+        assert!(matches!(
+            rename_backup("Survival", &original, "taken.tar.gz"),
+            Err(BackupError::FileOp(FileOpsError::DestinationExists(_)))
+        ));
+        assert!(matches!(
+            rename_backup("Survival", "missing.tar.gz", "new.tar.gz"),
+            Err(BackupError::BackupNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_list_backups_finds_tags_of_nfd_file_names() {
+        let _env = TestEnv::new();
+        let save_base = TempDir::new().unwrap();
+        let backup_base = TempDir::new().unwrap();
+        create_test_save(&save_base.path().join("Survival"));
+        setup_test_config(save_base.path(), backup_base.path());
+
+        // macOS-style decomposed name on disk
+        let nfd_name = "Cafe\u{301}_2024.tar.gz";
+        let original = create_backup("Survival").unwrap().backup_name;
+        fs::rename(
+            backup_base.path().join("Survival").join(&original),
+            backup_base.path().join("Survival").join(nfd_name),
+        )
+        .unwrap();
+        crate::tags::create_tag("keep".to_string(), "#FF0000".to_string(), None, None).unwrap();
+        crate::tags::add_tags_to_backup("Survival", nfd_name, vec!["keep".to_string()]).unwrap();
+
+        let backups = list_backups("Survival").unwrap();
+        assert_eq!(backups.len(), 1);
+        assert_eq!(backups[0].name, nfd_name);
+        assert_eq!(backups[0].tags.iter().map(|t| t.name.as_str()).collect::<Vec<_>>(), ["keep"]);
+    }
+
+    #[test]
     fn test_delete_one_of_multiple_backups() {
+        let _env = TestEnv::new();
         let save_base = TempDir::new().unwrap();
         let backup_base = TempDir::new().unwrap();
 
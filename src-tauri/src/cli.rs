@@ -0,0 +1,845 @@
+//! Headless command-line mode.
+//!
+//! Running the binary with a subcommand performs one operation and exits without
+//! creating a window, e.g. on a dedicated server without a display:
+//! - `backup --save <save> [--silent]` creates a backup; `--silent` is for OS
+//!   schedules (see `os_schedule`): nothing is printed on success and the backup
+//!   is recorded as scheduled
+//! - `list --save <save>` lists a save's backups
+//! - `restore --save <save> --backup <name> --yes` restores a backup
+//! - `prune [--save <save>] [--dry-run]` applies the retention policy
+//!
+//! `--config-dir <dir>` replaces the platform config directory. Operations go
+//! through the same code as the GUI commands, so they are recorded in the
+//! operation history and journaled the same way.
+//!
+//! # JSON output
+//! With `--json`, stdout carries exactly one JSON document, serialized from the
+//! same types the Tauri commands return; messages and warnings go to stderr:
+//! - `backup`: `BackupResult`
+//! - `list`: `BackupListing` (`{ generation, backups: [BackupInfo] }`)
+//! - `restore`: `RestoreResult`
+//! - `prune`: array of `PruneResult`, one per save
+//! - any error: `ErrorPayload` (`{ code, message, details }`), with the usual exit code
+//!
+//! Renaming a field of these types breaks scripts; the snapshot tests below fail
+//! when that happens.
+//!
+//! Without a subcommand the GUI starts as usual, and launch arguments such as
+//! `--backup <save>` keep being handled by `instance`. On Windows, release builds
+//! have no console of their own; output is visible when redirected.
+
+use crate::backup::{self, BackupInfo};
+use crate::config as config_module;
+use crate::dashboard::DashboardState;
+use crate::errors::{ErrorCode, ErrorPayload};
+use crate::file_ops;
+use crate::history::OperationTrigger;
+use crate::journal;
+use crate::metrics;
+use crate::operations::{self, OperationQueue};
+use crate::tasks::TaskRegistry;
+use crate::webhooks;
+use chrono::{DateTime, Local};
+use serde::Serialize;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Exit code of a successful run.
+pub const EXIT_OK: i32 = 0;
+
+/// Exit code for invalid arguments or input (unknown save or backup, missing `--yes`).
+pub const EXIT_VALIDATION: i32 = 2;
+
+/// Exit code for operations that failed.
+pub const EXIT_FAILED: i32 = 3;
+
+/// Longest time the CLI waits for webhook deliveries before exiting.
+const WEBHOOK_WAIT: Duration = Duration::from_secs(30);
+
+/// Usage text printed by `help` and with argument errors.
+pub const USAGE: &str = "\
+Usage: pz-backup-tool [--config-dir <dir>] [--json] <command> [options]
+
+Commands:
+  backup  --save <save> [--silent]           Create a backup of a save
+  list    --save <save>                      List the backups of a save
+  restore --save <save> --backup <name> --yes
+                                             Replace a save with a backup
+  prune   [--save <save>] [--dry-run]        Delete backups beyond the retention count
+  help                                       Show this message
+
+Saves are relative paths below the saves directory, e.g. \"Multiplayer/servertest\".
+--json prints the result (or the error) as JSON on stdout.
+Exit codes: 0 success, 2 invalid arguments or input, 3 operation failed.";
+
+/// Subcommand to run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CliCommand {
+    /// Back up a save; `silent` runs are scheduled and print nothing on success
+    Backup { save: String, silent: bool },
+    /// List a save's backups
+    List { save: String },
+    /// Restore a backup; only constructed when confirmed with `--yes`
+    Restore { save: String, backup: String },
+    /// Prune one save, or every save with backups
+    Prune { save: Option<String>, dry_run: bool },
+    /// Print the usage text
+    Help,
+}
+
+/// Parsed command line of a headless run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CliArgs {
+    /// Config directory given with `--config-dir`
+    pub config_dir: Option<PathBuf>,
+    /// Print results and errors as JSON (`--json`)
+    pub json: bool,
+    /// Subcommand to run
+    pub command: CliCommand,
+}
+
+/// Error type for headless runs.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CliError {
+    /// Arguments couldn't be parsed
+    Usage(String),
+    /// Arguments parsed, but the input is invalid or unconfirmed
+    Validation(ErrorPayload),
+    /// The operation itself failed
+    Failed(ErrorPayload),
+}
+
+impl CliError {
+    /// Returns the process exit code for this error.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            CliError::Usage(_) | CliError::Validation(_) => EXIT_VALIDATION,
+            CliError::Failed(_) => EXIT_FAILED,
+        }
+    }
+
+    /// Returns the error as printed in JSON mode.
+    pub fn payload(&self) -> ErrorPayload {
+        match self {
+            CliError::Usage(msg) => ErrorPayload::new(ErrorCode::InvalidValue, msg.clone()),
+            CliError::Validation(payload) | CliError::Failed(payload) => payload.clone(),
+        }
+    }
+}
+
+impl std::fmt::Display for CliError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CliError::Usage(msg) => write!(f, "{}", msg),
+            CliError::Validation(payload) | CliError::Failed(payload) => {
+                write!(f, "{}", payload.message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CliError {}
+
+/// Errors about the user's input are validation errors; everything else failed.
+impl From<ErrorPayload> for CliError {
+    fn from(err: ErrorPayload) -> Self {
+        match err.code {
+            ErrorCode::SaveNotFound
+            | ErrorCode::BackupNotFound
+            | ErrorCode::InvalidBackupName
+            | ErrorCode::InvalidValue => CliError::Validation(err),
+            _ => CliError::Failed(err),
+        }
+    }
+}
+
+impl From<io::Error> for CliError {
+    fn from(err: io::Error) -> Self {
+        CliError::Failed(ErrorPayload::new(
+            ErrorCode::Io,
+            format!("Failed to write output: {}", err),
+        ))
+    }
+}
+
+/// Returns true if `arg` names a subcommand (including `help`).
+fn is_subcommand(arg: &str) -> bool {
+    matches!(arg, "backup" | "list" | "restore" | "prune" | "help")
+}
+
+/// Returns the value of a flag given as `--flag <value>` or `--flag=<value>`.
+fn flag_value<I: Iterator<Item = String>>(
+    flag: &str,
+    inline: Option<&str>,
+    args: &mut I,
+) -> Result<String, CliError> {
+    let value = match inline {
+        Some(value) => Some(value.to_string()),
+        None => args.next(),
+    };
+    value
+        .filter(|v| !v.trim().is_empty() && !v.starts_with("--"))
+        .ok_or_else(|| CliError::Usage(format!("Missing value for {}", flag)))
+}
+
+/// Parses the command line (without the program name).
+///
+/// # Arguments
+/// * `args` - Arguments after the program name
+///
+/// # Returns
+/// `Result<Option<CliArgs>, CliError>` - The headless run to perform, `None` if the
+/// arguments don't start with a subcommand (the GUI should start), or a usage error
+///
+/// # Behavior
+/// - `--config-dir` may appear before or after the subcommand
+/// - Values are accepted as the next argument or as `--flag=<value>`
+/// - `--help`/`-h` anywhere prints the usage
+/// - `restore` without `--yes` is refused as a validation error
+pub fn parse_args<I, S>(args: I) -> Result<Option<CliArgs>, CliError>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut args = args.into_iter().map(|a| a.as_ref().to_string()).peekable();
+    let mut config_dir = None;
+    let mut json = false;
+
+    // Global options before the subcommand
+    let subcommand = loop {
+        let Some(arg) = args.peek().cloned() else {
+            return match (&config_dir, json) {
+                (None, false) => Ok(None),
+                _ => Err(CliError::Usage("A command is required".to_string())),
+            };
+        };
+        let (flag, inline) = split_flag(&arg);
+        match flag {
+            "--config-dir" => {
+                args.next();
+                config_dir = Some(PathBuf::from(flag_value(flag, inline, &mut args)?));
+            }
+            "--json" if inline.is_none() => {
+                args.next();
+                json = true;
+            }
+            "--help" | "-h" => break "help".to_string(),
+            _ if is_subcommand(&arg) => {
+                args.next();
+                break arg;
+            }
+            // Launch arguments for the GUI
+            _ if config_dir.is_none() && !json => return Ok(None),
+            _ => return Err(CliError::Usage(format!("Unknown command: {}", arg))),
+        }
+    };
+
+    let mut save = None;
+    let mut backup = None;
+    let mut yes = false;
+    let mut dry_run = false;
+    let mut silent = false;
+    let mut help = subcommand == "help";
+
+    while let Some(arg) = args.next() {
+        let (flag, inline) = split_flag(&arg);
+        match (subcommand.as_str(), flag) {
+            (_, "--config-dir") => {
+                config_dir = Some(PathBuf::from(flag_value(flag, inline, &mut args)?));
+            }
+            (_, "--help" | "-h") => help = true,
+            (_, "--json") if inline.is_none() => json = true,
+            ("backup" | "list" | "restore" | "prune", "--save") => {
+                save = Some(flag_value(flag, inline, &mut args)?);
+            }
+            ("restore", "--backup") => backup = Some(flag_value(flag, inline, &mut args)?),
+            ("restore", "--yes" | "-y") if inline.is_none() => yes = true,
+            ("prune", "--dry-run") if inline.is_none() => dry_run = true,
+            ("backup", "--silent") if inline.is_none() => silent = true,
+            _ if arg.starts_with('-') => {
+                return Err(CliError::Usage(format!(
+                    "Unknown option for {}: {}",
+                    subcommand, arg
+                )))
+            }
+            _ => return Err(CliError::Usage(format!("Unexpected argument: {}", arg))),
+        }
+    }
+
+    let require_save = |save: Option<String>| {
+        save.ok_or_else(|| CliError::Usage(format!("{} requires --save <save>", subcommand)))
+    };
+    let command = match subcommand.as_str() {
+        _ if help => CliCommand::Help,
+        "backup" => CliCommand::Backup {
+            save: require_save(save)?,
+            silent,
+        },
+        "list" => CliCommand::List {
+            save: require_save(save)?,
+        },
+        "restore" => {
+            let save = require_save(save)?;
+            let backup = backup
+                .ok_or_else(|| CliError::Usage("restore requires --backup <name>".to_string()))?;
+            if !yes {
+                return Err(CliError::Validation(ErrorPayload::new(
+                    ErrorCode::InvalidValue,
+                    format!(
+                        "Restoring replaces the current \"{}\" save; pass --yes to confirm",
+                        save
+                    ),
+                )));
+            }
+            CliCommand::Restore { save, backup }
+        }
+        "prune" => CliCommand::Prune { save, dry_run },
+        _ => CliCommand::Help,
+    };
+
+    Ok(Some(CliArgs {
+        config_dir,
+        json,
+        command,
+    }))
+}
+
+/// Splits `--flag=value` into the flag and its inline value.
+fn split_flag(arg: &str) -> (&str, Option<&str>) {
+    match arg.split_once('=') {
+        Some((flag, value)) if flag.starts_with("--") => (flag, Some(value)),
+        _ => (arg, None),
+    }
+}
+
+/// Runs a parsed command, writing its output to `out`.
+///
+/// # Arguments
+/// * `args` - Parsed command line; `json` selects JSON output
+/// * `out` - Receives the output
+///
+/// # Returns
+/// `Result<(), CliError>` - Ok if the operation succeeded
+///
+/// # Behavior
+/// The config directory must already be selected (see `run_from_env`). Errors are
+/// returned, not written, so the caller can print them in the selected format.
+pub fn run(args: &CliArgs, out: &mut dyn Write) -> Result<(), CliError> {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .map_err(|e| {
+            CliError::Failed(ErrorPayload::new(
+                ErrorCode::Internal,
+                format!("Failed to start runtime: {}", e),
+            ))
+        })?;
+    let dashboard = DashboardState::new();
+    let tasks = TaskRegistry::new().with_metrics_sink(metrics::record_point_best_effort);
+    let queue = OperationQueue::new(1);
+
+    match &args.command {
+        CliCommand::Help => writeln!(out, "{}", USAGE)?,
+        CliCommand::Backup { save, silent } => {
+            let trigger = if *silent { OperationTrigger::Scheduled } else { OperationTrigger::Manual };
+            let result = runtime
+                .block_on(operations::run_backup(
+                    save,
+                    trigger,
+                    &dashboard,
+                    &tasks,
+                    &queue,
+                ))
+                .map_err(ErrorPayload::from)?;
+            if args.json {
+                return write_json(out, &result);
+            }
+            if *silent {
+                return Ok(());
+            }
+            writeln!(
+                out,
+                "Created backup {} of {} ({})",
+                result.backup_name,
+                save,
+                file_ops::format_size(result.size_bytes)
+            )?;
+            writeln!(
+                out,
+                "Retained {} backup(s), deleted {}",
+                result.retained_count, result.deleted_count
+            )?;
+            if result.partial {
+                writeln!(
+                    out,
+                    "Partial backup: {} locked file(s) were left out:",
+                    result.skipped_files.len()
+                )?;
+                for file in &result.skipped_files {
+                    writeln!(out, "  {} ({})", file.path, file.reason)?;
+                }
+            }
+        }
+        CliCommand::List { save } => {
+            let listing = backup::list_backups_cached(save).map_err(ErrorPayload::from)?;
+            if args.json {
+                return write_json(out, &listing);
+            }
+            write_backup_list(out, save, &listing.backups)?;
+        }
+        CliCommand::Restore { save, backup } => {
+            let result = runtime
+                .block_on(operations::run_restore(
+                    save,
+                    backup,
+                    OperationTrigger::Manual,
+                    &dashboard,
+                    &tasks,
+                    &queue,
+                ))
+                .map_err(ErrorPayload::from)?;
+            if args.json {
+                return write_json(out, &result);
+            }
+            writeln!(out, "Restored {} from {}", result.save_name, result.backup_name)?;
+            if let Some(snapshot) = &result.undo_snapshot_path {
+                writeln!(out, "Undo snapshot of the replaced save: {}", snapshot)?;
+            }
+        }
+        CliCommand::Prune { save, dry_run } => {
+            let saves = match save {
+                Some(save) => vec![save.clone()],
+                None => backup::list_backed_up_saves().map_err(ErrorPayload::from)?,
+            };
+            let results = saves
+                .iter()
+                .map(|save| operations::run_prune(save, *dry_run, OperationTrigger::Manual))
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(ErrorPayload::from)?;
+            if args.json {
+                return write_json(out, &results);
+            }
+            if results.is_empty() {
+                writeln!(out, "No backups to prune")?;
+            }
+            for result in &results {
+                let verb = if result.dry_run { "Would delete" } else { "Deleted" };
+                writeln!(
+                    out,
+                    "{}: {} {} backup(s), keeping {}",
+                    result.save_name,
+                    verb,
+                    result.deleted.len(),
+                    result.retained_count
+                )?;
+                for name in &result.deleted {
+                    writeln!(out, "  {}", name)?;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes one JSON document followed by a newline.
+fn write_json<T: Serialize + ?Sized>(out: &mut dyn Write, value: &T) -> Result<(), CliError> {
+    let json = serde_json::to_string_pretty(value).map_err(io::Error::from)?;
+    writeln!(out, "{}", json)?;
+    Ok(())
+}
+
+/// Writes a save's backups as a table, newest first.
+fn write_backup_list(out: &mut dyn Write, save: &str, backups: &[BackupInfo]) -> io::Result<()> {
+    if backups.is_empty() {
+        return writeln!(out, "No backups of {}", save);
+    }
+
+    writeln!(out, "{:<20}  {:>10}  NAME", "CREATED", "SIZE")?;
+    for info in backups {
+        let created = DateTime::parse_from_rfc3339(&info.created_at)
+            .map(|dt| dt.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S").to_string())
+            .unwrap_or_else(|_| info.created_at.clone());
+        let mut line = format!("{:<20}  {:>10}  {}", created, info.size_formatted, info.name);
+        if info.partial {
+            line.push_str("  [partial]");
+        }
+        if !info.tags.is_empty() {
+            let tags: Vec<&str> = info.tags.iter().map(|t| t.name.as_str()).collect();
+            line.push_str(&format!("  tags: {}", tags.join(", ")));
+        }
+        writeln!(out, "{}", line)?;
+    }
+    writeln!(out, "{} backup(s)", backups.len())
+}
+
+/// Runs the headless mode if the process arguments ask for it.
+///
+/// # Returns
+/// `Option<i32>` - Exit code of the headless run, or `None` if the GUI should start
+///
+/// # Behavior
+/// Selects the config directory, finishes operations a crash interrupted (like the
+/// GUI does at startup), runs the command and prints errors to stderr.
+pub fn run_from_env() -> Option<i32> {
+    let raw_args: Vec<String> = std::env::args().skip(1).collect();
+    let args = match parse_args(&raw_args) {
+        Ok(Some(args)) => args,
+        Ok(None) => return None,
+        Err(e) => {
+            // The command line didn't parse, so look for --json directly
+            if raw_args.iter().any(|a| a == "--json") {
+                print_json_error(&e);
+            } else {
+                eprintln!("error: {}", e);
+                if matches!(e, CliError::Usage(_)) {
+                    eprintln!("\n{}", USAGE);
+                }
+            }
+            return Some(e.exit_code());
+        }
+    };
+
+    if args.command != CliCommand::Help {
+        let storage = match args.config_dir.clone() {
+            Some(dir) => config_module::init_storage_at(Some(dir)),
+            None => config_module::init_storage(),
+        };
+        if storage.ephemeral {
+            eprintln!(
+                "warning: settings won't persist: {}",
+                storage.reason.as_deref().unwrap_or_default()
+            );
+        }
+
+        match journal::recover_interrupted() {
+            Ok(reports) => {
+                for report in reports {
+                    eprintln!(
+                        "Recovered interrupted {} of {}: {:?}",
+                        report.operation, report.subject, report.action
+                    );
+                }
+            }
+            Err(e) => eprintln!("warning: failed to recover interrupted operations: {}", e),
+        }
+    }
+
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    let code = match run(&args, &mut out) {
+        Ok(()) => EXIT_OK,
+        Err(e) => {
+            if args.json {
+                print_json_error(&e);
+            } else {
+                eprintln!("error: {}", e);
+            }
+            e.exit_code()
+        }
+    };
+
+    // Give webhook notifications a chance to go out before the process exits
+    if !webhooks::wait_for_deliveries(WEBHOOK_WAIT) {
+        eprintln!("warning: some webhook notifications were not delivered");
+    }
+    config_module::discard_session_dir();
+    Some(code)
+}
+
+/// Prints an error as a JSON `ErrorPayload` on stdout.
+fn print_json_error(err: &CliError) {
+    let payload = err.payload();
+    match serde_json::to_string_pretty(&payload) {
+        Ok(json) => println!("{}", json),
+        Err(_) => eprintln!("error: {}", payload.message),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backup::{BackupResult, PruneResult};
+    use crate::file_ops::SkippedFile;
+    use crate::listing_cache::BackupListing;
+    use crate::restore::RestoreResult;
+    use crate::tags::Tag;
+    use crate::test_support::TestEnv;
+    use serde_json::json;
+    use std::fs;
+
+    fn parse(args: &[&str]) -> Result<Option<CliArgs>, CliError> {
+        parse_args(args.iter().copied())
+    }
+
+    fn cli_args(args: &[&str]) -> CliArgs {
+        parse(args).unwrap().unwrap()
+    }
+
+    fn command(args: &[&str]) -> CliCommand {
+        cli_args(args).command
+    }
+
+    fn run_to_string(args: &[&str]) -> String {
+        let mut out = Vec::new();
+        run(&cli_args(args), &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_parse_leaves_launch_arguments_to_the_gui() {
+        assert_eq!(parse(&[]).unwrap(), None);
+        assert_eq!(parse(&["--backup", "Survival/A", "--silent"]).unwrap(), None);
+        assert!(matches!(parse(&["--config-dir", "/tmp/x"]), Err(CliError::Usage(_))));
+    }
+
+    #[test]
+    fn test_parse_subcommands() {
+        assert_eq!(
+            command(&["backup", "--save", "Multiplayer/servertest"]),
+            CliCommand::Backup {
+                save: "Multiplayer/servertest".to_string(),
+                silent: false
+            }
+        );
+        assert_eq!(
+            command(&["backup", "--silent", "--save", "Survival/A"]),
+            CliCommand::Backup {
+                save: "Survival/A".to_string(),
+                silent: true
+            }
+        );
+        assert_eq!(
+            command(&["list", "--save=Survival/A"]),
+            CliCommand::List {
+                save: "Survival/A".to_string()
+            }
+        );
+        assert_eq!(
+            command(&["restore", "--save", "Survival/A", "--backup", "b.tar.gz", "--yes"]),
+            CliCommand::Restore {
+                save: "Survival/A".to_string(),
+                backup: "b.tar.gz".to_string()
+            }
+        );
+        assert_eq!(
+            command(&["prune", "--dry-run"]),
+            CliCommand::Prune {
+                save: None,
+                dry_run: true
+            }
+        );
+        assert_eq!(command(&["--help"]), CliCommand::Help);
+        assert_eq!(command(&["backup", "-h"]), CliCommand::Help);
+    }
+
+    #[test]
+    fn test_parse_config_dir_before_or_after_command() {
+        let before = parse(&["--config-dir", "/cfg", "list", "--save", "A"]).unwrap().unwrap();
+        let after = parse(&["list", "--save", "A", "--config-dir=/cfg"]).unwrap().unwrap();
+        assert_eq!(before, after);
+        assert_eq!(before.config_dir, Some(PathBuf::from("/cfg")));
+    }
+
+    #[test]
+    fn test_parse_errors_and_exit_codes() {
+        let cases: &[&[&str]] = &[
+            &["backup"],
+            &["backup", "--save"],
+            &["list", "--save", "A", "--dry-run"],
+            &["restore", "--save", "A", "--yes"],
+            &["prune", "extra"],
+            &["--config-dir", "/cfg", "frobnicate"],
+        ];
+        for args in cases {
+            let err = parse(args).unwrap_err();
+            assert!(matches!(err, CliError::Usage(_)), "{:?}: {:?}", args, err);
+            assert_eq!(err.exit_code(), EXIT_VALIDATION);
+        }
+
+        // Restore refuses to run unconfirmed
+        let err = parse(&["restore", "--save", "A", "--backup", "b.tar.gz"]).unwrap_err();
+        assert!(matches!(err, CliError::Validation(_)) && err.to_string().contains("--yes"));
+        assert_eq!(err.exit_code(), EXIT_VALIDATION);
+        assert_eq!(err.payload().code, ErrorCode::InvalidValue);
+
+        let failed = CliError::from(ErrorPayload::new(ErrorCode::Io, "disk"));
+        assert_eq!(failed.exit_code(), EXIT_FAILED);
+    }
+
+    #[test]
+    fn test_parse_json_flag() {
+        assert!(cli_args(&["--json", "list", "--save", "A"]).json);
+        assert!(cli_args(&["backup", "--save", "A", "--json"]).json);
+        assert!(!cli_args(&["backup", "--save", "A"]).json);
+        assert!(matches!(parse(&["--json"]), Err(CliError::Usage(_))));
+    }
+
+    #[test]
+    fn test_run_list() {
+        let env = TestEnv::with_paths();
+        assert_eq!(run_to_string(&["list", "--save", "Survival/Cli"]), "No backups of Survival/Cli\n");
+
+        let save_dir = env.save_dir().join("Survival/Cli");
+        fs::create_dir_all(save_dir.join("map")).unwrap();
+        fs::write(save_dir.join("map/chunk.bin"), b"chunk").unwrap();
+        let created = backup::create_backup("Survival/Cli").unwrap();
+
+        let output = run_to_string(&["list", "--save", "Survival/Cli"]);
+        assert!(output.starts_with("CREATED"), "{}", output);
+        assert!(output.contains(&created.backup_name), "{}", output);
+        assert!(output.ends_with("1 backup(s)\n"), "{}", output);
+
+        // JSON mode prints the listing the Tauri command returns, and nothing else
+        let output = run_to_string(&["list", "--save", "Survival/Cli", "--json"]);
+        let listing: BackupListing = serde_json::from_str(&output).unwrap();
+        assert_eq!(listing.backups.len(), 1);
+        assert_eq!(listing.backups[0].name, created.backup_name);
+    }
+
+    // Snapshots of the JSON contract. A failure here means scripts parsing
+    // `--json` output break: update the documentation along with the snapshot.
+
+    #[test]
+    fn test_backup_result_json_snapshot() {
+        let result = BackupResult {
+            backup_path: "/backups/Survival/A/2024-12-28_14-30-45.tar.gz".to_string(),
+            backup_name: "2024-12-28_14-30-45.tar.gz".to_string(),
+            retained_count: 3,
+            deleted_count: 1,
+            deleted_backups: vec!["2024-12-27_09-00-00.tar.gz".to_string()],
+            size_bytes: 2048,
+            partial: true,
+            skipped_files: vec![SkippedFile {
+                path: "map/chunk.bin".to_string(),
+                reason: "locked".to_string(),
+            }],
+            server_flush: None,
+        };
+        assert_eq!(
+            serde_json::to_value(&result).unwrap(),
+            json!({
+                "backup_path": "/backups/Survival/A/2024-12-28_14-30-45.tar.gz",
+                "backup_name": "2024-12-28_14-30-45.tar.gz",
+                "retained_count": 3,
+                "deleted_count": 1,
+                "deleted_backups": ["2024-12-27_09-00-00.tar.gz"],
+                "size_bytes": 2048,
+                "partial": true,
+                "skipped_files": [{ "path": "map/chunk.bin", "reason": "locked" }]
+            })
+        );
+    }
+
+    #[test]
+    fn test_backup_listing_json_snapshot() {
+        let listing = BackupListing {
+            generation: 7,
+            backups: vec![BackupInfo {
+                name: "2024-12-28_14-30-45.tar.gz".to_string(),
+                path: "/backups/Survival/A/2024-12-28_14-30-45.tar.gz".to_string(),
+                size_bytes: 2048,
+                size_formatted: "2.00 KB".to_string(),
+                created_at: "2024-12-28T14:30:45+00:00".to_string(),
+                save_name: "Survival/A".to_string(),
+                tags: vec![Tag {
+                    name: "keep".to_string(),
+                    color: "#00FF00".to_string(),
+                    description: None,
+                    icon: None,
+                    group: None,
+                    aliases: Vec::new(),
+                    pinned: false,
+                    protected: false,
+                    sort_index: 0,
+                    created_at: None,
+                    updated_at: None,
+                }],
+                thumb_data: None,
+                partial: false,
+                skipped_files: Vec::new(),
+                server_flush: None,
+            }],
+        };
+        assert_eq!(
+            serde_json::to_value(&listing).unwrap(),
+            json!({
+                "generation": 7,
+                "backups": [{
+                    "name": "2024-12-28_14-30-45.tar.gz",
+                    "path": "/backups/Survival/A/2024-12-28_14-30-45.tar.gz",
+                    "size_bytes": 2048,
+                    "size_formatted": "2.00 KB",
+                    "created_at": "2024-12-28T14:30:45+00:00",
+                    "save_name": "Survival/A",
+                    "tags": [{ "name": "keep", "color": "#00FF00", "sort_index": 0, "text_color": "#000000" }],
+                    "partial": false,
+                    "skipped_files": []
+                }]
+            })
+        );
+    }
+
+    #[test]
+    fn test_restore_and_prune_json_snapshots() {
+        let restored = RestoreResult {
+            save_path: "/saves/Survival/A".to_string(),
+            save_name: "Survival/A".to_string(),
+            backup_path: "/backups/Survival/A/b.tar.gz".to_string(),
+            backup_name: "b.tar.gz".to_string(),
+            undo_snapshot_path: None,
+            has_undo_snapshot: false,
+        };
+        assert_eq!(
+            serde_json::to_value(&restored).unwrap(),
+            json!({
+                "save_path": "/saves/Survival/A",
+                "save_name": "Survival/A",
+                "backup_path": "/backups/Survival/A/b.tar.gz",
+                "backup_name": "b.tar.gz",
+                "undo_snapshot_path": null,
+                "has_undo_snapshot": false
+            })
+        );
+
+        let pruned = PruneResult {
+            save_name: "Survival/A".to_string(),
+            retained_count: 1,
+            deleted: vec!["old.tar.gz".to_string()],
+            dry_run: true,
+        };
+        assert_eq!(
+            serde_json::to_value(&pruned).unwrap(),
+            json!({
+                "save_name": "Survival/A",
+                "retained_count": 1,
+                "deleted": ["old.tar.gz"],
+                "dry_run": true
+            })
+        );
+    }
+
+    #[test]
+    fn test_error_json_snapshot() {
+        let err = CliError::from(ErrorPayload::from(crate::backup::BackupError::SaveNotFound(
+            "Survival/A".to_string(),
+        )));
+        assert_eq!(err.exit_code(), EXIT_VALIDATION);
+        assert_eq!(
+            serde_json::to_value(err.payload()).unwrap(),
+            json!({
+                "code": "SaveNotFound",
+                "message": "Save directory not found: Survival/A",
+                "details": { "name": "Survival/A" }
+            })
+        );
+
+        let usage = CliError::Usage("Missing value for --save".to_string());
+        assert_eq!(
+            serde_json::to_value(usage.payload()).unwrap(),
+            json!({ "code": "InvalidValue", "message": "Missing value for --save", "details": null })
+        );
+    }
+}
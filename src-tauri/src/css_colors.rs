@@ -0,0 +1,352 @@
+//! CSS named colors, `rgb()` / `rgba()` parsing and hue math for tag colors.
+//!
+//! Names follow CSS Color Module Level 4 (the 140 classic names, their `grey`
+//! spellings and `rebeccapurple`) and match case-insensitively. `transparent` and
+//! `currentcolor` aren't colors a tag can have and are not accepted.
+
+/// Named colors as `(name, "#RRGGBB")`, sorted by name for binary search.
+const NAMED_COLORS: &[(&str, &str)] = &[
+    ("aliceblue", "#F0F8FF"),
+    ("antiquewhite", "#FAEBD7"),
+    ("aqua", "#00FFFF"),
+    ("aquamarine", "#7FFFD4"),
+    ("azure", "#F0FFFF"),
+    ("beige", "#F5F5DC"),
+    ("bisque", "#FFE4C4"),
+    ("black", "#000000"),
+    ("blanchedalmond", "#FFEBCD"),
+    ("blue", "#0000FF"),
+    ("blueviolet", "#8A2BE2"),
+    ("brown", "#A52A2A"),
+    ("burlywood", "#DEB887"),
+    ("cadetblue", "#5F9EA0"),
+    ("chartreuse", "#7FFF00"),
+    ("chocolate", "#D2691E"),
+    ("coral", "#FF7F50"),
+    ("cornflowerblue", "#6495ED"),
+    ("cornsilk", "#FFF8DC"),
+    ("crimson", "#DC143C"),
+    ("cyan", "#00FFFF"),
+    ("darkblue", "#00008B"),
+    ("darkcyan", "#008B8B"),
+    ("darkgoldenrod", "#B8860B"),
+    ("darkgray", "#A9A9A9"),
+    ("darkgreen", "#006400"),
+    ("darkgrey", "#A9A9A9"),
+    ("darkkhaki", "#BDB76B"),
+    ("darkmagenta", "#8B008B"),
+    ("darkolivegreen", "#556B2F"),
+    ("darkorange", "#FF8C00"),
+    ("darkorchid", "#9932CC"),
+    ("darkred", "#8B0000"),
+    ("darksalmon", "#E9967A"),
+    ("darkseagreen", "#8FBC8F"),
+    ("darkslateblue", "#483D8B"),
+    ("darkslategray", "#2F4F4F"),
+    ("darkslategrey", "#2F4F4F"),
+    ("darkturquoise", "#00CED1"),
+    ("darkviolet", "#9400D3"),
+    ("deeppink", "#FF1493"),
+    ("deepskyblue", "#00BFFF"),
+    ("dimgray", "#696969"),
+    ("dimgrey", "#696969"),
+    ("dodgerblue", "#1E90FF"),
+    ("firebrick", "#B22222"),
+    ("floralwhite", "#FFFAF0"),
+    ("forestgreen", "#228B22"),
+    ("fuchsia", "#FF00FF"),
+    ("gainsboro", "#DCDCDC"),
+    ("ghostwhite", "#F8F8FF"),
+    ("gold", "#FFD700"),
+    ("goldenrod", "#DAA520"),
+    ("gray", "#808080"),
+    ("green", "#008000"),
+    ("greenyellow", "#ADFF2F"),
+    ("grey", "#808080"),
+    ("honeydew", "#F0FFF0"),
+    ("hotpink", "#FF69B4"),
+    ("indianred", "#CD5C5C"),
+    ("indigo", "#4B0082"),
+    ("ivory", "#FFFFF0"),
+    ("khaki", "#F0E68C"),
+    ("lavender", "#E6E6FA"),
+    ("lavenderblush", "#FFF0F5"),
+    ("lawngreen", "#7CFC00"),
+    ("lemonchiffon", "#FFFACD"),
+    ("lightblue", "#ADD8E6"),
+    ("lightcoral", "#F08080"),
+    ("lightcyan", "#E0FFFF"),
+    ("lightgoldenrodyellow", "#FAFAD2"),
+    ("lightgray", "#D3D3D3"),
+    ("lightgreen", "#90EE90"),
+    ("lightgrey", "#D3D3D3"),
+    ("lightpink", "#FFB6C1"),
+    ("lightsalmon", "#FFA07A"),
+    ("lightseagreen", "#20B2AA"),
+    ("lightskyblue", "#87CEFA"),
+    ("lightslategray", "#778899"),
+    ("lightslategrey", "#778899"),
+    ("lightsteelblue", "#B0C4DE"),
+    ("lightyellow", "#FFFFE0"),
+    ("lime", "#00FF00"),
+    ("limegreen", "#32CD32"),
+    ("linen", "#FAF0E6"),
+    ("magenta", "#FF00FF"),
+    ("maroon", "#800000"),
+    ("mediumaquamarine", "#66CDAA"),
+    ("mediumblue", "#0000CD"),
+    ("mediumorchid", "#BA55D3"),
+    ("mediumpurple", "#9370DB"),
+    ("mediumseagreen", "#3CB371"),
+    ("mediumslateblue", "#7B68EE"),
+    ("mediumspringgreen", "#00FA9A"),
+    ("mediumturquoise", "#48D1CC"),
+    ("mediumvioletred", "#C71585"),
+    ("midnightblue", "#191970"),
+    ("mintcream", "#F5FFFA"),
+    ("mistyrose", "#FFE4E1"),
+    ("moccasin", "#FFE4B5"),
+    ("navajowhite", "#FFDEAD"),
+    ("navy", "#000080"),
+    ("oldlace", "#FDF5E6"),
+    ("olive", "#808000"),
+    ("olivedrab", "#6B8E23"),
+    ("orange", "#FFA500"),
+    ("orangered", "#FF4500"),
+    ("orchid", "#DA70D6"),
+    ("palegoldenrod", "#EEE8AA"),
+    ("palegreen", "#98FB98"),
+    ("paleturquoise", "#AFEEEE"),
+    ("palevioletred", "#DB7093"),
+    ("papayawhip", "#FFEFD5"),
+    ("peachpuff", "#FFDAB9"),
+    ("peru", "#CD853F"),
+    ("pink", "#FFC0CB"),
+    ("plum", "#DDA0DD"),
+    ("powderblue", "#B0E0E6"),
+    ("purple", "#800080"),
+    ("rebeccapurple", "#663399"),
+    ("red", "#FF0000"),
+    ("rosybrown", "#BC8F8F"),
+    ("royalblue", "#4169E1"),
+    ("saddlebrown", "#8B4513"),
+    ("salmon", "#FA8072"),
+    ("sandybrown", "#F4A460"),
+    ("seagreen", "#2E8B57"),
+    ("seashell", "#FFF5EE"),
+    ("sienna", "#A0522D"),
+    ("silver", "#C0C0C0"),
+    ("skyblue", "#87CEEB"),
+    ("slateblue", "#6A5ACD"),
+    ("slategray", "#708090"),
+    ("slategrey", "#708090"),
+    ("snow", "#FFFAFA"),
+    ("springgreen", "#00FF7F"),
+    ("steelblue", "#4682B4"),
+    ("tan", "#D2B48C"),
+    ("teal", "#008080"),
+    ("thistle", "#D8BFD8"),
+    ("tomato", "#FF6347"),
+    ("turquoise", "#40E0D0"),
+    ("violet", "#EE82EE"),
+    ("wheat", "#F5DEB3"),
+    ("white", "#FFFFFF"),
+    ("whitesmoke", "#F5F5F5"),
+    ("yellow", "#FFFF00"),
+    ("yellowgreen", "#9ACD32"),
+];
+
+/// Returns the `#RRGGBB` value of a CSS color name, ignoring case.
+pub fn named_color(name: &str) -> Option<&'static str> {
+    let name = name.to_ascii_lowercase();
+    NAMED_COLORS
+        .binary_search_by(|(n, _)| n.cmp(&name.as_str()))
+        .ok()
+        .map(|i| NAMED_COLORS[i].1)
+}
+
+/// Parses `rgb(r, g, b)` or `rgba(r, g, b, a)` into hex.
+///
+/// # Arguments
+/// * `input` - Color function; channels are integers 0-255, alpha is 0-1 or a percentage
+///
+/// # Returns
+/// `Option<String>` - `#RRGGBB`, or `#RRGGBBAA` when alpha is below 1; None if the
+/// syntax or a value is invalid
+///
+/// # Behavior
+/// Both functions accept three or four arguments, separated by commas or (CSS Color 4)
+/// spaces with an optional `/` before alpha.
+pub fn parse_rgb(input: &str) -> Option<String> {
+    let lower = input.trim().to_ascii_lowercase();
+    let args = lower
+        .strip_prefix("rgba")
+        .or_else(|| lower.strip_prefix("rgb"))?
+        .trim_start()
+        .strip_prefix('(')?
+        .strip_suffix(')')?;
+
+    let parts: Vec<&str> = args
+        .split(|c: char| c == ',' || c == '/' || c.is_whitespace())
+        .filter(|p| !p.is_empty())
+        .collect();
+    if parts.len() != 3 && parts.len() != 4 {
+        return None;
+    }
+
+    let mut hex = String::from("#");
+    for channel in &parts[..3] {
+        let value: u8 = channel.parse().ok()?;
+        hex.push_str(&format!("{:02X}", value));
+    }
+    if let Some(alpha) = parts.get(3) {
+        let alpha = parse_alpha(alpha)?;
+        if alpha < 1.0 {
+            hex.push_str(&format!("{:02X}", (alpha * 255.0).round() as u8));
+        }
+    }
+    Some(hex)
+}
+
+/// Parses an alpha value given as 0-1 or as a percentage.
+fn parse_alpha(value: &str) -> Option<f64> {
+    let alpha = match value.strip_suffix('%') {
+        Some(percent) => percent.parse::<f64>().ok()? / 100.0,
+        None => value.parse::<f64>().ok()?,
+    };
+    (0.0..=1.0).contains(&alpha).then_some(alpha)
+}
+
+/// Parses `#RGB`, `#RRGGBB` or `#RRGGBBAA` into its color channels, ignoring alpha.
+pub fn parse_hex(color: &str) -> Option<(u8, u8, u8)> {
+    let hex = color.trim().strip_prefix('#')?;
+    if !hex.is_ascii() {
+        return None;
+    }
+    let channel = |s: &str| u8::from_str_radix(s, 16).ok();
+    match hex.len() {
+        3 => {
+            let digit = |i: usize| channel(&hex[i..i + 1]).map(|v| v * 17);
+            Some((digit(0)?, digit(1)?, digit(2)?))
+        }
+        6 | 8 => Some((channel(&hex[0..2])?, channel(&hex[2..4])?, channel(&hex[4..6])?)),
+        _ => None,
+    }
+}
+
+/// Returns the HSL hue of a hex color in degrees, or None for greys and invalid colors.
+///
+/// Colors with less than 15% saturation count as grey: their hue isn't visible.
+pub fn hue(color: &str) -> Option<f64> {
+    let (r, g, b) = parse_hex(color)?;
+    let (r, g, b) = (r as f64 / 255.0, g as f64 / 255.0, b as f64 / 255.0);
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let delta = max - min;
+    let lightness = (max + min) / 2.0;
+    if delta == 0.0 || delta / (1.0 - (2.0 * lightness - 1.0).abs()) < 0.15 {
+        return None;
+    }
+
+    let hue = if max == r {
+        60.0 * ((g - b) / delta).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((b - r) / delta + 2.0)
+    } else {
+        60.0 * ((r - g) / delta + 4.0)
+    };
+    Some(hue)
+}
+
+/// Converts HSL (hue in degrees, saturation and lightness 0-1) to `#RRGGBB`.
+pub fn hsl_to_hex(hue: f64, saturation: f64, lightness: f64) -> String {
+    let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+    let sector = hue.rem_euclid(360.0) / 60.0;
+    let x = chroma * (1.0 - (sector.rem_euclid(2.0) - 1.0).abs());
+    let (r, g, b) = match sector as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    let m = lightness - chroma / 2.0;
+    let channel = |v: f64| ((v + m) * 255.0).round() as u8;
+    format!("#{:02X}{:02X}{:02X}", channel(r), channel(g), channel(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_named_colors_are_sorted() {
+        assert!(NAMED_COLORS.windows(2).all(|w| w[0].0 < w[1].0));
+        assert_eq!(NAMED_COLORS.len(), 148);
+    }
+
+    #[test]
+    fn test_named_color() {
+        let cases = [
+            ("red", Some("#FF0000")),
+            ("Red", Some("#FF0000")),
+            ("REBECCAPURPLE", Some("#663399")),
+            ("grey", Some("#808080")),
+            ("lightgoldenrodyellow", Some("#FAFAD2")),
+            ("transparent", None),
+            ("reddish", None),
+            ("", None),
+        ];
+        for (name, expected) in cases {
+            assert_eq!(named_color(name), expected, "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_parse_rgb() {
+        let cases = [
+            ("rgb(255, 87, 51)", Some("#FF5733")),
+            ("rgb(255,87,51)", Some("#FF5733")),
+            ("  RGB( 0 , 0 , 0 ) ", Some("#000000")),
+            ("rgb(255 87 51)", Some("#FF5733")),
+            ("rgba(255, 87, 51, 1)", Some("#FF5733")),
+            ("rgba(255, 87, 51, 0.5)", Some("#FF573380")),
+            ("rgb(255 87 51 / 50%)", Some("#FF573380")),
+            ("rgba(0, 0, 0, 0)", Some("#00000000")),
+            ("rgb(256, 0, 0)", None),
+            ("rgb(-1, 0, 0)", None),
+            ("rgb(255, 87)", None),
+            ("rgb(255, 87, 51, 0.5, 1)", None),
+            ("rgba(255, 87, 51, 1.5)", None),
+            ("rgb(10%, 0, 0)", None),
+            ("rgb(255, 87, 51", None),
+            ("hsl(0, 100%, 50%)", None),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(parse_rgb(input).as_deref(), expected, "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_hue_round_trip() {
+        assert_eq!(parse_hex("#f0a"), Some((255, 0, 170)));
+        assert_eq!(parse_hex("#3B82F680"), Some((59, 130, 246)));
+        assert_eq!(parse_hex("#12345"), None);
+
+        assert_eq!(hue("#FF0000"), Some(0.0));
+        assert_eq!(hue("#00FF00"), Some(120.0));
+        assert_eq!(hue("#0000FF"), Some(240.0));
+        assert_eq!(hue("#808080"), None);
+        assert_eq!(hue("#6B7280"), None);
+        assert_eq!(hue("red"), None);
+
+        for degrees in [0.0, 45.0, 120.0, 200.0, 300.0, 359.0] {
+            let color = hsl_to_hex(degrees, 0.7, 0.55);
+            let back = hue(&color).unwrap();
+            assert!((back - degrees).abs() < 1.0, "{} -> {} -> {}", degrees, color, back);
+        }
+        assert_eq!(hsl_to_hex(0.0, 1.0, 0.5), "#FF0000");
+        assert_eq!(hsl_to_hex(360.0, 1.0, 0.5), "#FF0000");
+    }
+}
@@ -0,0 +1,441 @@
+//! In-memory state backing the home screen dashboard.
+//!
+//! This module provides:
+//! - `DashboardState`, updated by commands as they run
+//! - `DashboardSummary`, assembled from that state without scanning directories
+//!
+//! Every piece of the summary is optional: anything that hasn't been observed yet
+//! (or can't be determined) is reported as `null` instead of failing the summary.
+
+use crate::backup::{get_backup_stats, BackupInfo, BackupStats};
+use crate::config as config_module;
+use crate::file_ops::get_available_space;
+use crate::keys::SaveKey;
+use crate::update_checker::UpdateInfo;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::{Mutex, MutexGuard};
+
+/// State of automatic backups.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum AutoBackupState {
+    /// Automatic backups are scheduled
+    Running,
+    /// Automatic backups are temporarily paused
+    Paused,
+    /// Automatic backups are turned off
+    Disabled,
+}
+
+/// Auto-backup status reported by the scheduler.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AutoBackupStatus {
+    /// Current state
+    pub state: AutoBackupState,
+    /// ISO 8601 timestamp of the next scheduled run
+    pub next_run_at: Option<String>,
+}
+
+/// Outcome of the most recent backup/restore operation.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct OperationRecord {
+    /// Operation name (e.g., "create_backup")
+    pub operation: String,
+    /// Save the operation ran on
+    pub save_name: String,
+    /// Whether the operation succeeded
+    pub success: bool,
+    /// Error message if the operation failed
+    pub message: Option<String>,
+    /// ISO 8601 timestamp when the operation finished
+    pub finished_at: String,
+}
+
+/// Warning shown on the dashboard.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "type")]
+pub enum DashboardWarning {
+    /// A save folder is inside a cloud-synced directory
+    SyncRisk { save_name: String },
+    /// A backup failed integrity verification
+    FailedVerification {
+        save_name: String,
+        backup_name: String,
+    },
+    /// A newer application version is available
+    UpdateAvailable { version: String },
+    /// The latest release's tag isn't a version, so whether it is newer is unknown
+    UnknownReleaseVersion { tag: String },
+    /// The config directory is read-only; settings won't persist this session
+    EphemeralStorage { reason: String },
+    /// tags.json couldn't be read and was restored from this backup
+    TagsRecovered { backup_file: String },
+}
+
+/// The save whose last backup is the oldest.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StaleSave {
+    /// Relative path of the save
+    pub save_name: String,
+    /// ISO 8601 timestamp of its newest backup
+    pub last_backup_at: String,
+    /// Seconds elapsed since that backup
+    pub seconds_since_backup: i64,
+}
+
+/// Dashboard summary returned to the frontend.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DashboardSummary {
+    /// Number of saves found in the save folder
+    pub monitored_save_count: Option<usize>,
+    /// Number of backups across all known saves
+    pub total_backup_count: Option<usize>,
+    /// Disk usage of backups across all known saves in bytes
+    pub total_backup_bytes: Option<u64>,
+    /// The save that has gone the longest without a backup
+    pub stalest_save: Option<StaleSave>,
+    /// Auto-backup status
+    pub auto_backup: Option<AutoBackupStatus>,
+    /// Outcome of the most recent operation
+    pub last_operation: Option<OperationRecord>,
+    /// Free space on the backup volume in bytes
+    pub backup_disk_free_bytes: Option<u64>,
+    /// Active warnings
+    pub warnings: Vec<DashboardWarning>,
+}
+
+/// Mutable dashboard data guarded by `DashboardState`.
+#[derive(Debug, Default)]
+struct DashboardData {
+    monitored_save_count: Option<usize>,
+    backup_stats: HashMap<SaveKey, BackupStats>,
+    auto_backup: Option<AutoBackupStatus>,
+    last_operation: Option<OperationRecord>,
+    warnings: Vec<DashboardWarning>,
+}
+
+/// Dashboard state managed by Tauri.
+///
+/// Commands record what they observe (save listings, backup statistics, operation
+/// results, update checks) so that `get_dashboard_summary` never touches the disk.
+#[derive(Debug, Default)]
+pub struct DashboardState {
+    data: Mutex<DashboardData>,
+}
+
+impl DashboardState {
+    /// Creates an empty dashboard state.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Locks the data, recovering from a poisoned lock (the data stays usable).
+    fn lock(&self) -> MutexGuard<'_, DashboardData> {
+        self.data.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Records the number of saves found in the save folder.
+    pub fn record_save_count(&self, count: usize) {
+        self.lock().monitored_save_count = Some(count);
+    }
+
+    /// Records backup statistics for a save.
+    pub fn record_backup_stats(&self, save_name: &str, stats: BackupStats) {
+        self.lock().backup_stats.insert(SaveKey::new(save_name), stats);
+    }
+
+    /// Records backup statistics for a save from a backup listing.
+    pub fn record_backup_listing(&self, save_name: &str, backups: &[BackupInfo]) {
+        self.record_backup_stats(save_name, BackupStats::from_backups(backups));
+    }
+
+    /// Re-reads backup statistics for a save after its backups changed.
+    ///
+    /// If the statistics can't be read the save is forgotten, so the totals
+    /// never report stale numbers.
+    pub fn refresh_backup_stats(&self, save_name: &str) {
+        match get_backup_stats(save_name) {
+            Ok(stats) => self.record_backup_stats(save_name, stats),
+            Err(_) => {
+                self.lock().backup_stats.remove(&SaveKey::new(save_name));
+            }
+        }
+    }
+
+    /// Records the outcome of an operation.
+    pub fn record_operation<T, E: Display>(
+        &self,
+        operation: &str,
+        save_name: &str,
+        result: &Result<T, E>,
+    ) {
+        self.lock().last_operation = Some(OperationRecord {
+            operation: operation.to_string(),
+            save_name: save_name.to_string(),
+            success: result.is_ok(),
+            message: result.as_ref().err().map(|e| e.to_string()),
+            finished_at: Utc::now().to_rfc3339(),
+        });
+    }
+
+    /// Records the auto-backup status.
+    pub fn set_auto_backup_status(&self, status: Option<AutoBackupStatus>) {
+        self.lock().auto_backup = status;
+    }
+
+    /// Records the result of an update check, raising or clearing the update warning.
+    pub fn record_update_check(&self, info: &UpdateInfo) {
+        let mut data = self.lock();
+        data.warnings.retain(|w| {
+            !matches!(
+                w,
+                DashboardWarning::UpdateAvailable { .. } | DashboardWarning::UnknownReleaseVersion { .. }
+            )
+        });
+        if info.has_update {
+            data.warnings.push(DashboardWarning::UpdateAvailable {
+                version: info.latest_version.clone(),
+            });
+        }
+    }
+
+    /// Adds a warning (duplicates are ignored).
+    pub fn add_warning(&self, warning: DashboardWarning) {
+        let mut data = self.lock();
+        if !data.warnings.contains(&warning) {
+            data.warnings.push(warning);
+        }
+    }
+
+    /// Removes a warning.
+    pub fn remove_warning(&self, warning: &DashboardWarning) {
+        self.lock().warnings.retain(|w| w != warning);
+    }
+
+    /// Assembles the summary from the recorded state.
+    ///
+    /// # Arguments
+    /// * `now` - Current time, used for the stale save age
+    /// * `backup_disk_free_bytes` - Free space on the backup volume, if known
+    pub fn summarize(&self, now: DateTime<Utc>, backup_disk_free_bytes: Option<u64>) -> DashboardSummary {
+        let data = self.lock();
+
+        let (total_backup_count, total_backup_bytes) = if data.backup_stats.is_empty() {
+            (None, None)
+        } else {
+            (
+                Some(data.backup_stats.values().map(|s| s.backup_count).sum()),
+                Some(data.backup_stats.values().map(|s| s.total_bytes).sum()),
+            )
+        };
+
+        let stalest_save = data
+            .backup_stats
+            .iter()
+            .filter_map(|(name, stats)| stats.last_backup_at.map(|at| (name, at)))
+            .min_by(|a, b| a.1.cmp(&b.1).then_with(|| a.0.cmp(b.0)))
+            .map(|(name, at)| StaleSave {
+                save_name: name.to_string(),
+                last_backup_at: at.to_rfc3339(),
+                seconds_since_backup: (now - at).num_seconds().max(0),
+            });
+
+        DashboardSummary {
+            monitored_save_count: data.monitored_save_count,
+            total_backup_count,
+            total_backup_bytes,
+            stalest_save,
+            auto_backup: data.auto_backup.clone(),
+            last_operation: data.last_operation.clone(),
+            backup_disk_free_bytes,
+            warnings: data.warnings.clone(),
+        }
+    }
+}
+
+/// Builds the dashboard summary.
+///
+/// # Arguments
+/// * `state` - Dashboard state recorded by commands
+///
+/// # Returns
+/// `DashboardSummary` - Summary; unknown pieces are `None`
+///
+/// # Behavior
+/// - Only the config file is read; no directory is scanned
+/// - Free disk space is `None` if the backup path isn't configured or can't be queried
+pub fn get_dashboard_summary(state: &DashboardState) -> DashboardSummary {
+    let backup_disk_free_bytes = config_module::load_config()
+        .ok()
+        .and_then(|config| config.get_backup_path().ok())
+        .and_then(|path| get_available_space(&path).ok());
+
+    state.summarize(Utc::now(), backup_disk_free_bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::update_checker::UpdateChannel;
+    use chrono::TimeZone;
+
+    fn at(hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 12, 28, hour, 0, 0).unwrap()
+    }
+
+    fn update_info(has_update: bool) -> UpdateInfo {
+        UpdateInfo {
+            has_update,
+            current_version: "1.0.8".to_string(),
+            latest_version: "1.1.0".to_string(),
+            release_url: String::new(),
+            release_notes: String::new(),
+            release_notes_html: String::new(),
+            published_at: String::new(),
+            channel: UpdateChannel::Stable,
+            is_prerelease: false,
+            assets: Vec::new(),
+            asset: None,
+            intermediate_releases: Vec::new(),
+            skipped: false,
+        }
+    }
+
+    #[test]
+    fn test_empty_summary_is_unknown() {
+        let state = DashboardState::new();
+        let summary = state.summarize(at(12), None);
+
+        assert_eq!(summary.monitored_save_count, None);
+        assert_eq!(summary.total_backup_count, None);
+        assert_eq!(summary.total_backup_bytes, None);
+        assert_eq!(summary.stalest_save, None);
+        assert_eq!(summary.auto_backup, None);
+        assert_eq!(summary.last_operation, None);
+        assert!(summary.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_summary_aggregates_backup_stats() {
+        let state = DashboardState::new();
+        state.record_backup_stats(
+            "Survival/Fresh",
+            BackupStats {
+                backup_count: 3,
+                total_bytes: 300,
+                last_backup_at: Some(at(11)),
+            },
+        );
+        state.record_backup_stats(
+            "Survival/Old",
+            BackupStats {
+                backup_count: 2,
+                total_bytes: 200,
+                last_backup_at: Some(at(8)),
+            },
+        );
+        state.record_backup_stats("Builder/Empty", BackupStats::default());
+
+        let summary = state.summarize(at(12), Some(1024));
+
+        assert_eq!(summary.total_backup_count, Some(5));
+        assert_eq!(summary.total_backup_bytes, Some(500));
+        let stale = summary.stalest_save.unwrap();
+        assert_eq!(stale.save_name, "Survival/Old");
+        assert_eq!(stale.seconds_since_backup, 4 * 3600);
+    }
+
+    #[test]
+    fn test_update_warning_is_raised_and_cleared() {
+        let state = DashboardState::new();
+
+        state.record_update_check(&update_info(true));
+        state.record_update_check(&update_info(true));
+        assert_eq!(
+            state.summarize(at(12), None).warnings,
+            vec![DashboardWarning::UpdateAvailable {
+                version: "1.1.0".to_string()
+            }]
+        );
+
+        state.record_update_check(&update_info(false));
+        assert!(state.summarize(at(12), None).warnings.is_empty());
+
+        // A later successful check clears an unknown release version
+        state.add_warning(DashboardWarning::UnknownReleaseVersion {
+            tag: "nightly".to_string(),
+        });
+        state.record_update_check(&update_info(false));
+        assert!(state.summarize(at(12), None).warnings.is_empty());
+    }
+
+    #[test]
+    fn test_record_failed_operation() {
+        let state = DashboardState::new();
+        let result: Result<(), String> = Err("Save not found".to_string());
+        state.record_operation("create_backup", "Survival/MySave", &result);
+
+        let record = state.summarize(at(12), None).last_operation.unwrap();
+        assert!(!record.success);
+        assert_eq!(record.message.as_deref(), Some("Save not found"));
+    }
+
+    #[test]
+    fn test_summary_serialization_snapshot() {
+        let state = DashboardState::new();
+        state.record_save_count(2);
+        state.record_backup_stats(
+            "Survival/MySave",
+            BackupStats {
+                backup_count: 4,
+                total_bytes: 4096,
+                last_backup_at: Some(at(10)),
+            },
+        );
+        state.set_auto_backup_status(Some(AutoBackupStatus {
+            state: AutoBackupState::Paused,
+            next_run_at: None,
+        }));
+        state.add_warning(DashboardWarning::SyncRisk {
+            save_name: "Survival/MySave".to_string(),
+        });
+
+        let mut summary = state.summarize(at(12), Some(1_000_000));
+        summary.last_operation = Some(OperationRecord {
+            operation: "create_backup".to_string(),
+            save_name: "Survival/MySave".to_string(),
+            success: true,
+            message: None,
+            finished_at: "2024-12-28T10:00:00+00:00".to_string(),
+        });
+
+        let json = serde_json::to_value(&summary).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "monitored_save_count": 2,
+                "total_backup_count": 4,
+                "total_backup_bytes": 4096,
+                "stalest_save": {
+                    "save_name": "Survival/MySave",
+                    "last_backup_at": "2024-12-28T10:00:00+00:00",
+                    "seconds_since_backup": 7200
+                },
+                "auto_backup": { "state": "Paused", "next_run_at": null },
+                "last_operation": {
+                    "operation": "create_backup",
+                    "save_name": "Survival/MySave",
+                    "success": true,
+                    "message": null,
+                    "finished_at": "2024-12-28T10:00:00+00:00"
+                },
+                "backup_disk_free_bytes": 1000000,
+                "warnings": [
+                    { "type": "SyncRisk", "save_name": "Survival/MySave" }
+                ]
+            })
+        );
+    }
+}
@@ -0,0 +1,264 @@
+//! Discord formatting of webhook notifications.
+//!
+//! Webhooks with the `Discord` format receive an embed message instead of the raw
+//! `WebhookPayload`: a title summarizing the event, a color by outcome and fields
+//! for the save, backup, size and duration. Text is rendered in the configured
+//! display language.
+//!
+//! Project Zomboid's in-game date isn't read from saves, so embeds have no field for it.
+
+use crate::file_ops::format_size;
+use crate::i18n::{self, keys, Locale};
+use crate::webhooks::{WebhookEvent, WebhookPayload};
+use serde_json::{json, Value};
+
+/// Embed color of a successful operation (green).
+pub const COLOR_SUCCESS: u32 = 0x2ECC71;
+
+/// Embed color of a warning, such as a partial backup or low disk space (amber).
+pub const COLOR_WARNING: u32 = 0xF1C40F;
+
+/// Embed color of a failed operation (red).
+pub const COLOR_FAILURE: u32 = 0xE74C3C;
+
+/// Name the messages are posted under.
+const USERNAME: &str = "PZ Backup Tool";
+
+/// Renders a payload as a Discord webhook message.
+///
+/// # Arguments
+/// * `payload` - Payload of the event
+/// * `locale` - Display language of the text
+///
+/// # Returns
+/// `Value` - Message JSON with a single embed
+pub fn render_message(payload: &WebhookPayload, locale: Locale) -> Value {
+    let t = |key: &str, args: &[(&str, &str)]| i18n::translate(locale, key, args);
+    let save_name = payload.save_name.as_deref().unwrap_or_default();
+    let save = save_display_name(save_name);
+    let details = &payload.details;
+
+    let mut fields = Vec::new();
+    let mut field = |name_key: &str, value: String, inline: bool| {
+        fields.push(json!({ "name": t(name_key, &[]), "value": value, "inline": inline }));
+    };
+    if !save_name.is_empty() {
+        field(keys::DISCORD_FIELD_SAVE, save_name.to_string(), true);
+    }
+
+    let (title, color) = match payload.event {
+        WebhookEvent::BackupFinished => {
+            if let Some(size) = details["size_bytes"].as_u64() {
+                field(keys::DISCORD_FIELD_SIZE, format_size(size), true);
+            }
+            if let Some(duration_ms) = payload.duration_ms {
+                field(keys::DISCORD_FIELD_DURATION, format_duration(duration_ms), true);
+            }
+            if let Some(backup_name) = &payload.backup_name {
+                field(keys::DISCORD_FIELD_BACKUP, backup_name.clone(), false);
+            }
+            let skipped = details["skipped_files"].as_array().map_or(0, Vec::len);
+            if skipped > 0 {
+                field(keys::DISCORD_FIELD_SKIPPED, skipped.to_string(), true);
+                (t(keys::DISCORD_BACKUP_PARTIAL, &[("save", save)]), COLOR_WARNING)
+            } else {
+                (t(keys::DISCORD_BACKUP_FINISHED, &[("save", save)]), COLOR_SUCCESS)
+            }
+        }
+        WebhookEvent::BackupFailed => {
+            if let Some(duration_ms) = payload.duration_ms {
+                field(keys::DISCORD_FIELD_DURATION, format_duration(duration_ms), true);
+            }
+            if let Some(message) = details["message"].as_str() {
+                field(keys::DISCORD_FIELD_ERROR, message.to_string(), false);
+            }
+            (t(keys::DISCORD_BACKUP_FAILED, &[("save", save)]), COLOR_FAILURE)
+        }
+        WebhookEvent::PruneCompleted => {
+            let count = details["deleted"].as_array().map_or(0, Vec::len).to_string();
+            (
+                t(keys::DISCORD_PRUNE_COMPLETED, &[("save", save), ("count", &count)]),
+                COLOR_SUCCESS,
+            )
+        }
+        WebhookEvent::DiskSpaceLow => {
+            if let Some(free) = details["free_bytes"].as_u64() {
+                field(keys::DISCORD_FIELD_FREE_SPACE, format_size(free), true);
+            }
+            if let Some(threshold) = details["threshold_bytes"].as_u64() {
+                field(keys::DISCORD_FIELD_THRESHOLD, format_size(threshold), true);
+            }
+            if let Some(path) = details["path"].as_str() {
+                field(keys::DISCORD_FIELD_LOCATION, path.to_string(), false);
+            }
+            (t(keys::DISCORD_DISK_SPACE_LOW, &[]), COLOR_WARNING)
+        }
+    };
+
+    json!({
+        "username": USERNAME,
+        "embeds": [{
+            "title": title,
+            "color": color,
+            "timestamp": payload.timestamp.to_rfc3339(),
+            "fields": fields,
+        }],
+    })
+}
+
+/// Returns the save's folder name (e.g., "servertest" for "Multiplayer/servertest").
+fn save_display_name(save_name: &str) -> &str {
+    save_name
+        .trim_end_matches(['/', '\\'])
+        .rsplit(['/', '\\'])
+        .next()
+        .unwrap_or(save_name)
+}
+
+/// Formats a duration compactly (e.g., "850ms", "34s", "2m 05s", "1h 02m").
+pub fn format_duration(duration_ms: u64) -> String {
+    let seconds = duration_ms / 1000;
+    match seconds {
+        0 => format!("{}ms", duration_ms),
+        1..=59 => format!("{}s", seconds),
+        60..=3599 => format!("{}m {:02}s", seconds / 60, seconds % 60),
+        _ => format!("{}h {:02}m", seconds / 3600, seconds % 3600 / 60),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::OperationOutcome;
+    use chrono::{TimeZone, Utc};
+
+    fn payload(event: WebhookEvent, details: Value) -> WebhookPayload {
+        WebhookPayload {
+            event,
+            timestamp: Utc.with_ymd_and_hms(2024, 12, 28, 14, 30, 45).unwrap(),
+            save_name: Some("Multiplayer/servertest".to_string()),
+            backup_name: None,
+            outcome: Some(OperationOutcome::Success),
+            duration_ms: Some(34_200),
+            details,
+        }
+    }
+
+    #[test]
+    fn test_finished_backup_snapshot() {
+        let mut finished = payload(
+            WebhookEvent::BackupFinished,
+            json!({ "size_bytes": 1_288_490_189u64, "skipped_files": [] }),
+        );
+        finished.backup_name = Some("2024-12-28_14-30-45.tar.gz".to_string());
+
+        assert_eq!(
+            render_message(&finished, Locale::En),
+            json!({
+                "username": "PZ Backup Tool",
+                "embeds": [{
+                    "title": "✅ Backup of servertest completed",
+                    "color": COLOR_SUCCESS,
+                    "timestamp": "2024-12-28T14:30:45+00:00",
+                    "fields": [
+                        { "name": "Save", "value": "Multiplayer/servertest", "inline": true },
+                        { "name": "Size", "value": "1.20 GB", "inline": true },
+                        { "name": "Duration", "value": "34s", "inline": true },
+                        { "name": "Backup", "value": "2024-12-28_14-30-45.tar.gz", "inline": false }
+                    ]
+                }]
+            })
+        );
+    }
+
+    #[test]
+    fn test_partial_backup_is_amber() {
+        let partial = payload(
+            WebhookEvent::BackupFinished,
+            json!({ "size_bytes": 10, "skipped_files": [{ "path": "map/a.bin", "reason": "locked" }] }),
+        );
+        let embed = &render_message(&partial, Locale::En)["embeds"][0];
+        assert_eq!(embed["color"], COLOR_WARNING);
+        assert_eq!(embed["title"], "⚠️ Backup of servertest completed with skipped files");
+        assert_eq!(embed["fields"][3], json!({ "name": "Skipped files", "value": "1", "inline": true }));
+    }
+
+    #[test]
+    fn test_failed_backup_snapshot() {
+        let mut failed = payload(
+            WebhookEvent::BackupFailed,
+            json!({ "code": "InsufficientSpace", "message": "Not enough disk space", "details": null }),
+        );
+        failed.outcome = Some(OperationOutcome::Failure);
+        failed.duration_ms = Some(850);
+
+        assert_eq!(
+            render_message(&failed, Locale::En),
+            json!({
+                "username": "PZ Backup Tool",
+                "embeds": [{
+                    "title": "❌ Backup of servertest failed",
+                    "color": COLOR_FAILURE,
+                    "timestamp": "2024-12-28T14:30:45+00:00",
+                    "fields": [
+                        { "name": "Save", "value": "Multiplayer/servertest", "inline": true },
+                        { "name": "Duration", "value": "850ms", "inline": true },
+                        { "name": "Error", "value": "Not enough disk space", "inline": false }
+                    ]
+                }]
+            })
+        );
+    }
+
+    #[test]
+    fn test_prune_and_disk_space_snapshots() {
+        let prune = payload(WebhookEvent::PruneCompleted, json!({ "deleted": ["a.tar.gz", "b.tar.gz"] }));
+        assert_eq!(
+            render_message(&prune, Locale::En)["embeds"][0],
+            json!({
+                "title": "🧹 Removed 2 old backup(s) of servertest",
+                "color": COLOR_SUCCESS,
+                "timestamp": "2024-12-28T14:30:45+00:00",
+                "fields": [{ "name": "Save", "value": "Multiplayer/servertest", "inline": true }]
+            })
+        );
+
+        let mut low = payload(
+            WebhookEvent::DiskSpaceLow,
+            json!({ "path": "/backups", "free_bytes": 524_288_000u64, "threshold_bytes": 1_073_741_824u64 }),
+        );
+        low.save_name = None;
+        low.outcome = None;
+        low.duration_ms = None;
+        assert_eq!(
+            render_message(&low, Locale::En)["embeds"][0],
+            json!({
+                "title": "⚠️ Backup disk space is low",
+                "color": COLOR_WARNING,
+                "timestamp": "2024-12-28T14:30:45+00:00",
+                "fields": [
+                    { "name": "Free space", "value": "500.00 MB", "inline": true },
+                    { "name": "Warning threshold", "value": "1.00 GB", "inline": true },
+                    { "name": "Location", "value": "/backups", "inline": false }
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn test_text_follows_locale() {
+        let prune = payload(WebhookEvent::PruneCompleted, json!({ "deleted": ["a.tar.gz"] }));
+        let embed = &render_message(&prune, Locale::ZhCn)["embeds"][0];
+        assert_eq!(embed["title"], "🧹 已删除 servertest 的 1 个旧备份");
+        assert_eq!(embed["fields"][0]["name"], "存档");
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(0), "0ms");
+        assert_eq!(format_duration(999), "999ms");
+        assert_eq!(format_duration(34_999), "34s");
+        assert_eq!(format_duration(125_000), "2m 05s");
+        assert_eq!(format_duration(3_720_000), "1h 02m");
+    }
+}
@@ -0,0 +1,243 @@
+//! Classification of files and folders dropped onto the application window.
+//!
+//! This module provides:
+//! - Classification of each dropped path (backup archive, foreign archive, save directory)
+//! - Suggested follow-up actions for the frontend dialog
+//! - The payload of the `files-dropped` event
+
+use crate::config::{is_save_root_file, looks_like_save_directory};
+use crate::file_ops::{list_tar_gz_root_files, normalize_path_for_display};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Name of the event emitted after files are dropped onto the window.
+pub const FILES_DROPPED_EVENT: &str = "files-dropped";
+
+/// Kind of a dropped path.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum DroppedItemKind {
+    /// A tar.gz archive with a save at its root, as written by this tool
+    BackupArchive,
+    /// A readable tar.gz archive that doesn't have a save at its root
+    ForeignArchive,
+    /// A directory that looks like a Project Zomboid save
+    SaveDirectory,
+    /// Anything else; the drop is rejected
+    Unknown,
+}
+
+/// Action the frontend can offer for a dropped path.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum SuggestedAction {
+    /// Import the archive as a backup
+    ImportBackup,
+    /// Copy the directory into the save folder
+    ImportSave,
+}
+
+/// Classification of a single dropped path.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DroppedItem {
+    /// Dropped path (normalized for display)
+    pub path: String,
+    /// Detected kind
+    pub kind: DroppedItemKind,
+    /// Actions the frontend can offer, empty for rejected items
+    pub suggested_actions: Vec<SuggestedAction>,
+}
+
+/// Payload of the `files-dropped` event.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FilesDroppedPayload {
+    /// Classification of each dropped path, in drop order
+    pub items: Vec<DroppedItem>,
+    /// Number of items rejected as `Unknown`
+    pub rejected_count: usize,
+}
+
+/// Classifies a single dropped path.
+///
+/// # Arguments
+/// * `path` - Dropped file or directory
+///
+/// # Returns
+/// `DroppedItemKind` - Detected kind (`Unknown` for missing or unsupported paths)
+///
+/// # Behavior
+/// - Directories are save directories if they contain save files (e.g., `map_meta.bin`)
+/// - `.tar.gz` / `.tgz` files are backup archives if a save sits at the archive root,
+///   foreign archives if they are readable but don't
+/// - Unreadable archives and all other files are `Unknown`
+pub fn classify_path(path: &Path) -> DroppedItemKind {
+    if path.is_dir() {
+        return if looks_like_save_directory(path) {
+            DroppedItemKind::SaveDirectory
+        } else {
+            DroppedItemKind::Unknown
+        };
+    }
+
+    if !path.is_file() || !is_archive_name(path) {
+        return DroppedItemKind::Unknown;
+    }
+
+    match list_tar_gz_root_files(path) {
+        Ok(names) if names.iter().any(|name| is_save_root_file(name)) => {
+            DroppedItemKind::BackupArchive
+        }
+        Ok(_) => DroppedItemKind::ForeignArchive,
+        Err(_) => DroppedItemKind::Unknown,
+    }
+}
+
+/// Returns the actions the frontend can offer for a dropped item kind.
+pub fn suggested_actions(kind: DroppedItemKind) -> Vec<SuggestedAction> {
+    match kind {
+        DroppedItemKind::BackupArchive | DroppedItemKind::ForeignArchive => {
+            vec![SuggestedAction::ImportBackup]
+        }
+        DroppedItemKind::SaveDirectory => vec![SuggestedAction::ImportSave],
+        DroppedItemKind::Unknown => Vec::new(),
+    }
+}
+
+/// Classifies all paths of a drop and builds the `files-dropped` event payload.
+///
+/// # Arguments
+/// * `paths` - Dropped paths, in drop order
+///
+/// # Returns
+/// `FilesDroppedPayload` - Per-path classification and the number of rejected paths
+///
+/// # Behavior
+/// - Reads every dropped archive to the end, so large drops take a while; call it on a
+///   blocking thread rather than the window's event loop
+pub fn classify_drop(paths: &[PathBuf]) -> FilesDroppedPayload {
+    let items: Vec<DroppedItem> = paths
+        .iter()
+        .map(|path| {
+            let kind = classify_path(path);
+            DroppedItem {
+                path: normalize_path_for_display(path),
+                kind,
+                suggested_actions: suggested_actions(kind),
+            }
+        })
+        .collect();
+
+    let rejected_count = items
+        .iter()
+        .filter(|item| item.kind == DroppedItemKind::Unknown)
+        .count();
+
+    FilesDroppedPayload {
+        items,
+        rejected_count,
+    }
+}
+
+/// Returns true if the file name has a supported archive extension.
+fn is_archive_name(path: &Path) -> bool {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    name.ends_with(".tar.gz") || name.ends_with(".tgz")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file_ops::create_tar_gz;
+    use std::fs::{self, File};
+    use tempfile::TempDir;
+
+    fn create_test_save(save_dir: &Path) {
+        fs::create_dir_all(save_dir).unwrap();
+        File::create(save_dir.join("map_meta.bin")).unwrap();
+        File::create(save_dir.join("map_p.bin")).unwrap();
+    }
+
+    #[test]
+    fn test_classify_save_directory() {
+        let temp = TempDir::new().unwrap();
+        let save_dir = temp.path().join("MySave");
+        create_test_save(&save_dir);
+
+        assert_eq!(classify_path(&save_dir), DroppedItemKind::SaveDirectory);
+    }
+
+    #[test]
+    fn test_classify_backup_archive() {
+        let temp = TempDir::new().unwrap();
+        let save_dir = temp.path().join("MySave");
+        create_test_save(&save_dir);
+        let archive = temp.path().join("2024-12-28_14-30-45.tar.gz");
+        create_tar_gz(&save_dir, &archive).unwrap();
+
+        assert_eq!(classify_path(&archive), DroppedItemKind::BackupArchive);
+    }
+
+    #[test]
+    fn test_classify_foreign_archive() {
+        let temp = TempDir::new().unwrap();
+        let other_dir = temp.path().join("notes");
+        fs::create_dir_all(&other_dir).unwrap();
+        fs::write(other_dir.join("readme.txt"), "hello").unwrap();
+        let archive = temp.path().join("notes.tgz");
+        create_tar_gz(&other_dir, &archive).unwrap();
+
+        assert_eq!(classify_path(&archive), DroppedItemKind::ForeignArchive);
+    }
+
+    #[test]
+    fn test_classify_rejects_unknown_types() {
+        let temp = TempDir::new().unwrap();
+
+        let text_file = temp.path().join("notes.txt");
+        fs::write(&text_file, "hello").unwrap();
+        assert_eq!(classify_path(&text_file), DroppedItemKind::Unknown);
+
+        let fake_archive = temp.path().join("broken.tar.gz");
+        fs::write(&fake_archive, "not an archive").unwrap();
+        assert_eq!(classify_path(&fake_archive), DroppedItemKind::Unknown);
+
+        let empty_dir = temp.path().join("empty");
+        fs::create_dir_all(&empty_dir).unwrap();
+        assert_eq!(classify_path(&empty_dir), DroppedItemKind::Unknown);
+
+        assert_eq!(
+            classify_path(&temp.path().join("missing")),
+            DroppedItemKind::Unknown
+        );
+        assert!(suggested_actions(DroppedItemKind::Unknown).is_empty());
+    }
+
+    #[test]
+    fn test_classify_mixed_drop() {
+        let temp = TempDir::new().unwrap();
+        let save_dir = temp.path().join("MySave");
+        create_test_save(&save_dir);
+        let archive = temp.path().join("2024-12-28_14-30-45.tar.gz");
+        create_tar_gz(&save_dir, &archive).unwrap();
+        let text_file = temp.path().join("notes.txt");
+        fs::write(&text_file, "hello").unwrap();
+
+        let payload = classify_drop(&[archive, save_dir, text_file]);
+
+        assert_eq!(payload.items.len(), 3);
+        assert_eq!(payload.rejected_count, 1);
+        assert_eq!(payload.items[0].kind, DroppedItemKind::BackupArchive);
+        assert_eq!(
+            payload.items[0].suggested_actions,
+            vec![SuggestedAction::ImportBackup]
+        );
+        assert_eq!(payload.items[1].kind, DroppedItemKind::SaveDirectory);
+        assert_eq!(
+            payload.items[1].suggested_actions,
+            vec![SuggestedAction::ImportSave]
+        );
+        assert_eq!(payload.items[2].kind, DroppedItemKind::Unknown);
+        assert!(payload.items[2].suggested_actions.is_empty());
+    }
+}
@@ -0,0 +1,673 @@
+//! Structured errors returned by Tauri commands.
+//!
+//! Every command returns `CommandResult<T>`. The frontend decides what to show from
+//! `ErrorPayload::code`; `message` is an advisory English description and may change.
+
+use crate::backup::BackupError;
+use crate::config::ConfigError;
+use crate::file_ops::FileOpsError;
+use crate::history::HistoryError;
+use crate::inventory::InventoryError;
+use crate::journal::JournalError;
+use crate::metadata::MetadataError;
+use crate::metrics::MetricsError;
+use crate::mods::ModsError;
+use crate::os_schedule::OsScheduleError;
+use crate::restore::RestoreError;
+use crate::secrets::SecretsError;
+use crate::tags::TagsError;
+use crate::update_checker::UpdateError;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::fmt;
+use std::io;
+use std::path::Path;
+
+/// Machine-readable error code, the contract with the frontend.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum ErrorCode {
+    /// Unexpected error without a more specific code
+    Internal,
+    /// Generic I/O failure
+    Io,
+    /// Access to a file or directory was denied
+    PermissionDenied,
+    /// Not enough disk space
+    InsufficientSpace,
+    /// File is locked by another process (e.g., the game or a sync client)
+    SaveBusy,
+    /// Backup archive is damaged or truncated
+    CorruptArchive,
+    /// Stored data (tags, history) couldn't be parsed
+    CorruptData,
+    /// Path does not exist
+    PathNotFound,
+    /// Destination already exists
+    AlreadyExists,
+    /// Path is not a directory
+    NotADirectory,
+    /// Operation was cancelled
+    Cancelled,
+    /// Timed out waiting for another process to release a data file
+    StoreBusy,
+    /// Config file couldn't be parsed
+    InvalidConfig,
+    /// Config directory couldn't be determined
+    ConfigDirNotFound,
+    /// Feature needs persistent settings, but the config directory is read-only
+    EphemeralStorage,
+    /// Invalid value supplied by the user
+    InvalidValue,
+    /// HTTP API request without a valid token
+    Unauthorized,
+    /// Save directory not found
+    SaveNotFound,
+    /// Backup not found
+    BackupNotFound,
+    /// Backup name doesn't match the expected format
+    InvalidBackupName,
+    /// Save name isn't a relative path inside the save directory
+    InvalidSaveName,
+    /// Undo snapshot couldn't be created
+    UndoSnapshotFailed,
+    /// Project Zomboid is running
+    GameRunning,
+    /// Tag not found
+    TagNotFound,
+    /// Tag already exists
+    DuplicateTag,
+    /// Smart collection (saved tag query) not found
+    CollectionNotFound,
+    /// Tag color isn't a valid hex color
+    InvalidColor,
+    /// Tag name is empty, too long or contains control characters
+    InvalidTagName,
+    /// Tag icon is too long or contains whitespace
+    InvalidIcon,
+    /// Network is unavailable or the update server can't be reached
+    Offline,
+    /// Update request failed after connecting (e.g., the response broke off)
+    UpdateRequestFailed,
+    /// Update server returned an error
+    UpdateServerError,
+    /// GitHub's API rate limit is used up until `details.resets_at`
+    RateLimited,
+    /// Update server response couldn't be parsed
+    InvalidUpdateResponse,
+    /// A release tag or requested version isn't a comparable version
+    InvalidVersion,
+    /// Configured or environment proxy URL is invalid
+    InvalidProxy,
+    /// Configured or environment release API URL or repository is invalid
+    InvalidUpdateSource,
+    /// The release has no update asset for this platform
+    NoUpdateAsset,
+    /// Downloaded update doesn't match its published checksum
+    ChecksumMismatch,
+    /// The OS scheduler is unavailable or rejected a registration
+    SchedulerFailed,
+}
+
+/// Error returned by every Tauri command.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ErrorPayload {
+    /// Error code the frontend switches on
+    pub code: ErrorCode,
+    /// Advisory English message
+    pub message: String,
+    /// Structured context (e.g., `{ "path": ... }`, `{ "name": ... }`)
+    pub details: Option<Value>,
+}
+
+impl ErrorPayload {
+    /// Creates a payload without details.
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        ErrorPayload {
+            code,
+            message: message.into(),
+            details: None,
+        }
+    }
+
+    /// Adds structured details.
+    pub fn with_details(mut self, details: Value) -> Self {
+        self.details = Some(details);
+        self
+    }
+
+    /// Replaces the message, keeping code and details (used for wrapped errors).
+    fn with_message(mut self, message: impl Into<String>) -> Self {
+        self.message = message.into();
+        self
+    }
+}
+
+impl fmt::Display for ErrorPayload {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for ErrorPayload {}
+
+/// Result type for Tauri commands.
+pub type CommandResult<T> = Result<T, ErrorPayload>;
+
+/// Maps an I/O error kind to an error code.
+fn io_error_code(err: &io::Error) -> ErrorCode {
+    if crate::file_ops::is_sharing_violation(err) {
+        return ErrorCode::SaveBusy;
+    }
+
+    match err.kind() {
+        io::ErrorKind::PermissionDenied | io::ErrorKind::ReadOnlyFilesystem => {
+            ErrorCode::PermissionDenied
+        }
+        io::ErrorKind::StorageFull | io::ErrorKind::QuotaExceeded => ErrorCode::InsufficientSpace,
+        io::ErrorKind::ResourceBusy => ErrorCode::SaveBusy,
+        // Decompression and tar parsing report damaged archives this way
+        io::ErrorKind::InvalidData | io::ErrorKind::UnexpectedEof => ErrorCode::CorruptArchive,
+        io::ErrorKind::NotFound => ErrorCode::PathNotFound,
+        _ => ErrorCode::Io,
+    }
+}
+
+fn path_details(path: &Path) -> Value {
+    json!({ "path": path.to_string_lossy() })
+}
+
+fn name_details(name: &str) -> Value {
+    json!({ "name": name })
+}
+
+impl From<&FileOpsError> for ErrorPayload {
+    fn from(err: &FileOpsError) -> Self {
+        let message = err.to_string();
+        match err {
+            FileOpsError::Io(e) => ErrorPayload::new(io_error_code(e), message),
+            FileOpsError::SourceNotFound(path) => {
+                ErrorPayload::new(ErrorCode::PathNotFound, message).with_details(path_details(path))
+            }
+            FileOpsError::DestinationExists(path) => {
+                ErrorPayload::new(ErrorCode::AlreadyExists, message).with_details(path_details(path))
+            }
+            FileOpsError::NotADirectory(path) => {
+                ErrorPayload::new(ErrorCode::NotADirectory, message).with_details(path_details(path))
+            }
+            FileOpsError::Cancelled => ErrorPayload::new(ErrorCode::Cancelled, message),
+            FileOpsError::LockTimeout(path) => {
+                ErrorPayload::new(ErrorCode::StoreBusy, message).with_details(path_details(path))
+            }
+            FileOpsError::UnsafeArchivePath(path) => {
+                ErrorPayload::new(ErrorCode::CorruptArchive, message).with_details(path_details(path))
+            }
+            FileOpsError::UnsafeSavePath(name) => {
+                ErrorPayload::new(ErrorCode::InvalidSaveName, message).with_details(json!({ "save_name": name }))
+            }
+        }
+    }
+}
+
+impl From<&ConfigError> for ErrorPayload {
+    fn from(err: &ConfigError) -> Self {
+        let message = err.to_string();
+        match err {
+            ConfigError::FileOp(e) => ErrorPayload::from(e).with_message(message),
+            ConfigError::Json(_) => ErrorPayload::new(ErrorCode::InvalidConfig, message),
+            ConfigError::ConfigDirNotFound => ErrorPayload::new(ErrorCode::ConfigDirNotFound, message),
+            ConfigError::InvalidValue(_) => ErrorPayload::new(ErrorCode::InvalidValue, message),
+            ConfigError::EphemeralStorage(_) => ErrorPayload::new(ErrorCode::EphemeralStorage, message),
+        }
+    }
+}
+
+impl From<&BackupError> for ErrorPayload {
+    fn from(err: &BackupError) -> Self {
+        let message = err.to_string();
+        match err {
+            BackupError::FileOp(e) => ErrorPayload::from(e).with_message(message),
+            BackupError::Config(e) => ErrorPayload::from(e).with_message(message),
+            BackupError::SaveNotFound(name) => {
+                ErrorPayload::new(ErrorCode::SaveNotFound, message).with_details(name_details(name))
+            }
+            BackupError::InvalidBackupName(name) => {
+                ErrorPayload::new(ErrorCode::InvalidBackupName, message)
+                    .with_details(name_details(name))
+            }
+            BackupError::BackupNotFound(name) => {
+                ErrorPayload::new(ErrorCode::BackupNotFound, message).with_details(name_details(name))
+            }
+        }
+    }
+}
+
+impl From<&RestoreError> for ErrorPayload {
+    fn from(err: &RestoreError) -> Self {
+        let message = err.to_string();
+        match err {
+            RestoreError::FileOp(e) => ErrorPayload::from(e).with_message(message),
+            RestoreError::Backup(e) => ErrorPayload::from(e).with_message(message),
+            RestoreError::Config(e) => ErrorPayload::from(e).with_message(message),
+            RestoreError::Journal(e) => ErrorPayload::from(e).with_message(message),
+            RestoreError::SaveNotFound(name) | RestoreError::CurrentSaveNotFound(name) => {
+                ErrorPayload::new(ErrorCode::SaveNotFound, message).with_details(name_details(name))
+            }
+            RestoreError::BackupNotFound(name) => {
+                ErrorPayload::new(ErrorCode::BackupNotFound, message).with_details(name_details(name))
+            }
+            RestoreError::UndoSnapshotFailed(_) => {
+                ErrorPayload::new(ErrorCode::UndoSnapshotFailed, message)
+            }
+            RestoreError::GameRunning(process_name) => {
+                ErrorPayload::new(ErrorCode::GameRunning, message)
+                    .with_details(json!({ "process_name": process_name }))
+            }
+        }
+    }
+}
+
+impl From<&TagsError> for ErrorPayload {
+    fn from(err: &TagsError) -> Self {
+        let message = err.to_string();
+        match err {
+            TagsError::FileOp(e) => ErrorPayload::from(e).with_message(message),
+            TagsError::Json(_) => ErrorPayload::new(ErrorCode::CorruptData, message),
+            TagsError::TagNotFound(name) => {
+                ErrorPayload::new(ErrorCode::TagNotFound, message).with_details(name_details(name))
+            }
+            TagsError::CollectionNotFound(name) => ErrorPayload::new(ErrorCode::CollectionNotFound, message)
+                .with_details(name_details(name)),
+            TagsError::InvalidColor(color) => ErrorPayload::new(ErrorCode::InvalidColor, message)
+                .with_details(json!({ "color": color })),
+            TagsError::InvalidName(name) => ErrorPayload::new(ErrorCode::InvalidTagName, message)
+                .with_details(json!({ "name": name })),
+            TagsError::InvalidIcon(icon) => ErrorPayload::new(ErrorCode::InvalidIcon, message)
+                .with_details(json!({ "icon": icon })),
+            TagsError::InvalidOrder(_) => ErrorPayload::new(ErrorCode::InvalidValue, message),
+            TagsError::DuplicateTag(name) => {
+                ErrorPayload::new(ErrorCode::DuplicateTag, message).with_details(name_details(name))
+            }
+            TagsError::UnsupportedSchema(version) => {
+                ErrorPayload::new(ErrorCode::CorruptData, message)
+                    .with_details(json!({ "version": version }))
+            }
+            // The failing change's code, with its position in the batch
+            TagsError::BatchOpFailed { index, source } => {
+                let inner = ErrorPayload::from(source.as_ref());
+                let mut details = match inner.details {
+                    Some(Value::Object(map)) => map,
+                    _ => serde_json::Map::new(),
+                };
+                details.insert("op_index".to_string(), json!(index));
+                ErrorPayload::new(inner.code, message).with_details(Value::Object(details))
+            }
+        }
+    }
+}
+
+impl From<&HistoryError> for ErrorPayload {
+    fn from(err: &HistoryError) -> Self {
+        let message = err.to_string();
+        match err {
+            HistoryError::FileOp(e) => ErrorPayload::from(e).with_message(message),
+            HistoryError::Config(e) => ErrorPayload::from(e).with_message(message),
+            HistoryError::Json(_) => ErrorPayload::new(ErrorCode::CorruptData, message),
+        }
+    }
+}
+
+impl From<&JournalError> for ErrorPayload {
+    fn from(err: &JournalError) -> Self {
+        let message = err.to_string();
+        match err {
+            JournalError::FileOp(e) => ErrorPayload::from(e).with_message(message),
+            JournalError::Config(e) => ErrorPayload::from(e).with_message(message),
+            JournalError::Json(_) => ErrorPayload::new(ErrorCode::CorruptData, message),
+            JournalError::MissingPath(_) | JournalError::UnknownOperation(_) => {
+                ErrorPayload::new(ErrorCode::Internal, message)
+            }
+        }
+    }
+}
+
+impl From<&MetricsError> for ErrorPayload {
+    fn from(err: &MetricsError) -> Self {
+        let message = err.to_string();
+        match err {
+            MetricsError::FileOp(e) => ErrorPayload::from(e).with_message(message),
+            MetricsError::Config(e) => ErrorPayload::from(e).with_message(message),
+            MetricsError::Json(_) => ErrorPayload::new(ErrorCode::CorruptData, message),
+        }
+    }
+}
+
+impl From<&MetadataError> for ErrorPayload {
+    fn from(err: &MetadataError) -> Self {
+        let message = err.to_string();
+        match err {
+            MetadataError::FileOp(e) => ErrorPayload::from(e).with_message(message),
+            MetadataError::Tags(e) => ErrorPayload::from(e).with_message(message),
+            MetadataError::Json(_) | MetadataError::InvalidBundle(_) => {
+                ErrorPayload::new(ErrorCode::CorruptData, message)
+            }
+            MetadataError::UnsupportedVersion(version) => {
+                ErrorPayload::new(ErrorCode::CorruptData, message)
+                    .with_details(json!({ "version": version }))
+            }
+        }
+    }
+}
+
+impl From<&ModsError> for ErrorPayload {
+    fn from(err: &ModsError) -> Self {
+        let message = err.to_string();
+        match err {
+            ModsError::FileOp(e) => ErrorPayload::from(e).with_message(message),
+            ModsError::Config(e) => ErrorPayload::from(e).with_message(message),
+            ModsError::Json(_) => ErrorPayload::new(ErrorCode::CorruptData, message),
+        }
+    }
+}
+
+impl From<&SecretsError> for ErrorPayload {
+    fn from(err: &SecretsError) -> Self {
+        let message = err.to_string();
+        match err {
+            SecretsError::FileOp(e) => ErrorPayload::from(e).with_message(message),
+            SecretsError::Config(e) => ErrorPayload::from(e).with_message(message),
+            SecretsError::Json(_) => ErrorPayload::new(ErrorCode::CorruptData, message),
+        }
+    }
+}
+
+impl From<&OsScheduleError> for ErrorPayload {
+    fn from(err: &OsScheduleError) -> Self {
+        let message = err.to_string();
+        match err {
+            OsScheduleError::InvalidSpec(_) => ErrorPayload::new(ErrorCode::InvalidValue, message),
+            OsScheduleError::Unsupported => ErrorPayload::new(ErrorCode::SchedulerFailed, message),
+            OsScheduleError::SchedulerFailed { command, .. } => {
+                ErrorPayload::new(ErrorCode::SchedulerFailed, message)
+                    .with_details(json!({ "command": command }))
+            }
+            OsScheduleError::FileOp(e) => ErrorPayload::from(e).with_message(message),
+            OsScheduleError::Config(e) => ErrorPayload::from(e).with_message(message),
+        }
+    }
+}
+
+impl From<&InventoryError> for ErrorPayload {
+    fn from(err: &InventoryError) -> Self {
+        let message = err.to_string();
+        match err {
+            InventoryError::FileOp(e) => ErrorPayload::from(e).with_message(message),
+            InventoryError::Config(e) => ErrorPayload::from(e).with_message(message),
+            InventoryError::Backup(e) => ErrorPayload::from(e).with_message(message),
+            InventoryError::History(e) => ErrorPayload::from(e).with_message(message),
+            InventoryError::Json(_) => ErrorPayload::new(ErrorCode::Internal, message),
+        }
+    }
+}
+
+impl From<&UpdateError> for ErrorPayload {
+    fn from(err: &UpdateError) -> Self {
+        let message = err.to_string();
+        match err {
+            UpdateError::Client(_) => ErrorPayload::new(ErrorCode::Internal, message),
+            UpdateError::Offline(_) => ErrorPayload::new(ErrorCode::Offline, message),
+            UpdateError::Request(_) => ErrorPayload::new(ErrorCode::UpdateRequestFailed, message),
+            UpdateError::InvalidProxy { source, .. } => ErrorPayload::new(ErrorCode::InvalidProxy, message)
+                .with_details(json!({ "source": source })),
+            UpdateError::InvalidSource { source, .. } => {
+                ErrorPayload::new(ErrorCode::InvalidUpdateSource, message)
+                    .with_details(json!({ "source": source }))
+            }
+            UpdateError::Status(status) => ErrorPayload::new(ErrorCode::UpdateServerError, message)
+                .with_details(json!({ "status": status })),
+            UpdateError::RateLimited { resets_at } => ErrorPayload::new(ErrorCode::RateLimited, message)
+                .with_details(json!({ "resets_at": resets_at.to_rfc3339() })),
+            UpdateError::InvalidResponse(_) => {
+                ErrorPayload::new(ErrorCode::InvalidUpdateResponse, message)
+            }
+            UpdateError::InvalidVersion(version) => ErrorPayload::new(ErrorCode::InvalidVersion, message)
+                .with_details(json!({ "version": version })),
+            UpdateError::NoAsset(platform) => ErrorPayload::new(ErrorCode::NoUpdateAsset, message)
+                .with_details(json!({ "platform": platform })),
+            UpdateError::FileOp(e) => ErrorPayload::from(e).with_message(message),
+            UpdateError::ChecksumMismatch { expected, actual } => {
+                ErrorPayload::new(ErrorCode::ChecksumMismatch, message)
+                    .with_details(json!({ "expected": expected, "actual": actual }))
+            }
+        }
+    }
+}
+
+/// Implements `From<E>` for owned errors in terms of the `From<&E>` mapping above.
+macro_rules! impl_from_owned {
+    ($($error:ty),* $(,)?) => {
+        $(
+            impl From<$error> for ErrorPayload {
+                fn from(err: $error) -> Self {
+                    ErrorPayload::from(&err)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_owned!(
+    FileOpsError,
+    ConfigError,
+    BackupError,
+    RestoreError,
+    TagsError,
+    HistoryError,
+    JournalError,
+    MetricsError,
+    MetadataError,
+    ModsError,
+    SecretsError,
+    OsScheduleError,
+    InventoryError,
+    UpdateError,
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn json_error() -> serde_json::Error {
+        serde_json::from_str::<Value>("{").unwrap_err()
+    }
+
+    fn io_error(kind: io::ErrorKind) -> FileOpsError {
+        FileOpsError::Io(io::Error::new(kind, "test"))
+    }
+
+    fn assert_codes(cases: Vec<(ErrorPayload, ErrorCode)>) {
+        for (payload, expected) in cases {
+            assert_eq!(payload.code, expected, "wrong code for: {}", payload.message);
+            assert!(!payload.message.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_file_ops_error_mapping() {
+        let path = PathBuf::from("/saves/Survival/MySave");
+        assert_codes(vec![
+            (io_error(io::ErrorKind::PermissionDenied).into(), ErrorCode::PermissionDenied),
+            (io_error(io::ErrorKind::StorageFull).into(), ErrorCode::InsufficientSpace),
+            (io_error(io::ErrorKind::ResourceBusy).into(), ErrorCode::SaveBusy),
+            (io_error(io::ErrorKind::InvalidData).into(), ErrorCode::CorruptArchive),
+            (io_error(io::ErrorKind::UnexpectedEof).into(), ErrorCode::CorruptArchive),
+            (io_error(io::ErrorKind::NotFound).into(), ErrorCode::PathNotFound),
+            (io_error(io::ErrorKind::Other).into(), ErrorCode::Io),
+            (FileOpsError::SourceNotFound(path.clone()).into(), ErrorCode::PathNotFound),
+            (FileOpsError::DestinationExists(path.clone()).into(), ErrorCode::AlreadyExists),
+            (FileOpsError::NotADirectory(path.clone()).into(), ErrorCode::NotADirectory),
+            (FileOpsError::Cancelled.into(), ErrorCode::Cancelled),
+            (FileOpsError::LockTimeout(path.clone()).into(), ErrorCode::StoreBusy),
+            (FileOpsError::UnsafeArchivePath(path.clone()).into(), ErrorCode::CorruptArchive),
+            (FileOpsError::UnsafeSavePath("../x".into()).into(), ErrorCode::InvalidSaveName),
+        ]);
+
+        let payload = ErrorPayload::from(FileOpsError::SourceNotFound(path));
+        assert_eq!(payload.details, Some(json!({ "path": "/saves/Survival/MySave" })));
+    }
+
+    #[test]
+    fn test_config_and_backup_error_mapping() {
+        assert_codes(vec![
+            (ConfigError::FileOp(FileOpsError::Cancelled).into(), ErrorCode::Cancelled),
+            (ConfigError::Json(json_error()).into(), ErrorCode::InvalidConfig),
+            (ConfigError::ConfigDirNotFound.into(), ErrorCode::ConfigDirNotFound),
+            (ConfigError::InvalidValue("x".into()).into(), ErrorCode::InvalidValue),
+            (ConfigError::EphemeralStorage("x".into()).into(), ErrorCode::EphemeralStorage),
+            (
+                BackupError::FileOp(io_error(io::ErrorKind::StorageFull)).into(),
+                ErrorCode::InsufficientSpace,
+            ),
+            (
+                BackupError::Config(ConfigError::ConfigDirNotFound).into(),
+                ErrorCode::ConfigDirNotFound,
+            ),
+            (BackupError::SaveNotFound("A".into()).into(), ErrorCode::SaveNotFound),
+            (BackupError::InvalidBackupName("A".into()).into(), ErrorCode::InvalidBackupName),
+            (BackupError::BackupNotFound("A".into()).into(), ErrorCode::BackupNotFound),
+        ]);
+    }
+
+    #[test]
+    fn test_restore_error_mapping() {
+        assert_codes(vec![
+            (RestoreError::FileOp(FileOpsError::Cancelled).into(), ErrorCode::Cancelled),
+            (
+                RestoreError::Backup(BackupError::BackupNotFound("b".into())).into(),
+                ErrorCode::BackupNotFound,
+            ),
+            (
+                RestoreError::Config(ConfigError::InvalidValue("x".into())).into(),
+                ErrorCode::InvalidValue,
+            ),
+            (RestoreError::SaveNotFound("A".into()).into(), ErrorCode::SaveNotFound),
+            (RestoreError::BackupNotFound("b".into()).into(), ErrorCode::BackupNotFound),
+            (RestoreError::CurrentSaveNotFound("A".into()).into(), ErrorCode::SaveNotFound),
+            (RestoreError::UndoSnapshotFailed("x".into()).into(), ErrorCode::UndoSnapshotFailed),
+            (RestoreError::GameRunning("java".into()).into(), ErrorCode::GameRunning),
+            (
+                RestoreError::Journal(JournalError::FileOp(FileOpsError::Cancelled)).into(),
+                ErrorCode::Cancelled,
+            ),
+        ]);
+
+        // Wrapped errors keep the inner code but the outer message
+        let err = RestoreError::Backup(BackupError::BackupNotFound("b".into()));
+        let payload = ErrorPayload::from(&err);
+        assert_eq!(payload.message, err.to_string());
+        assert_eq!(payload.details, Some(json!({ "name": "b" })));
+    }
+
+    #[test]
+    fn test_tags_history_and_update_error_mapping() {
+        assert_codes(vec![
+            (TagsError::FileOp(FileOpsError::Cancelled).into(), ErrorCode::Cancelled),
+            (TagsError::Json(json_error()).into(), ErrorCode::CorruptData),
+            (TagsError::TagNotFound("t".into()).into(), ErrorCode::TagNotFound),
+            (TagsError::CollectionNotFound("c".into()).into(), ErrorCode::CollectionNotFound),
+            (TagsError::InvalidColor("red".into()).into(), ErrorCode::InvalidColor),
+            (TagsError::InvalidName(" ".into()).into(), ErrorCode::InvalidTagName),
+            (TagsError::InvalidIcon("toolong".into()).into(), ErrorCode::InvalidIcon),
+            (TagsError::DuplicateTag("t".into()).into(), ErrorCode::DuplicateTag),
+            (TagsError::InvalidOrder("x".into()).into(), ErrorCode::InvalidValue),
+            (TagsError::UnsupportedSchema(2).into(), ErrorCode::CorruptData),
+            (
+                TagsError::BatchOpFailed { index: 1, source: Box::new(TagsError::TagNotFound("t".into())) }
+                    .into(),
+                ErrorCode::TagNotFound,
+            ),
+            (HistoryError::FileOp(FileOpsError::Cancelled).into(), ErrorCode::Cancelled),
+            (
+                HistoryError::Config(ConfigError::ConfigDirNotFound).into(),
+                ErrorCode::ConfigDirNotFound,
+            ),
+            (HistoryError::Json(json_error()).into(), ErrorCode::CorruptData),
+            (UpdateError::Client("x".into()).into(), ErrorCode::Internal),
+            (UpdateError::Offline("x".into()).into(), ErrorCode::Offline),
+            (UpdateError::Request("x".into()).into(), ErrorCode::UpdateRequestFailed),
+            (
+                UpdateError::InvalidProxy { source: "HTTPS_PROXY".into(), message: "x".into() }.into(),
+                ErrorCode::InvalidProxy,
+            ),
+            (
+                UpdateError::InvalidSource { source: "update_repo".into(), message: "x".into() }.into(),
+                ErrorCode::InvalidUpdateSource,
+            ),
+            (UpdateError::Status(503).into(), ErrorCode::UpdateServerError),
+            (
+                UpdateError::RateLimited { resets_at: chrono::Utc::now() }.into(),
+                ErrorCode::RateLimited,
+            ),
+            (UpdateError::InvalidResponse("x".into()).into(), ErrorCode::InvalidUpdateResponse),
+            (UpdateError::InvalidVersion("x".into()).into(), ErrorCode::InvalidVersion),
+            (UpdateError::NoAsset("macos aarch64".into()).into(), ErrorCode::NoUpdateAsset),
+            (UpdateError::FileOp(FileOpsError::Cancelled).into(), ErrorCode::Cancelled),
+            (
+                UpdateError::ChecksumMismatch { expected: "a".into(), actual: "b".into() }.into(),
+                ErrorCode::ChecksumMismatch,
+            ),
+            (OsScheduleError::InvalidSpec("x".into()).into(), ErrorCode::InvalidValue),
+            (
+                OsScheduleError::SchedulerFailed { command: "schtasks".into(), message: "x".into() }.into(),
+                ErrorCode::SchedulerFailed,
+            ),
+        ]);
+    }
+
+    #[test]
+    fn test_payload_serialization() {
+        let payload = ErrorPayload::from(TagsError::DuplicateTag("Important".into()));
+        let value = serde_json::to_value(&payload).unwrap();
+        assert_eq!(value["code"], json!("DuplicateTag"));
+        assert_eq!(value["message"], json!(payload.message));
+        assert_eq!(value["details"], json!({ "name": "Important" }));
+
+        let payload = ErrorPayload::from(TagsError::InvalidName("two\nlines".into()));
+        let value = serde_json::to_value(&payload).unwrap();
+        assert_eq!(value["code"], json!("InvalidTagName"));
+        assert_eq!(value["message"], json!("Invalid tag name: \"two\\nlines\" (use 1 to 64 characters without line breaks)"));
+        assert_eq!(value["details"], json!({ "name": "two\nlines" }));
+
+        let payload = ErrorPayload::from(TagsError::BatchOpFailed {
+            index: 2,
+            source: Box::new(TagsError::DuplicateTag("Important".into())),
+        });
+        assert_eq!(payload.code, ErrorCode::DuplicateTag);
+        assert_eq!(payload.details, Some(json!({ "name": "Important", "op_index": 2 })));
+    }
+
+    #[test]
+    fn test_tags_error_payload_round_trip() {
+        let errors = vec![
+            TagsError::FileOp(io_error(io::ErrorKind::StorageFull)),
+            TagsError::Json(json_error()),
+            TagsError::TagNotFound("t".into()),
+            TagsError::CollectionNotFound("c".into()),
+            TagsError::InvalidColor("red".into()),
+            TagsError::InvalidName(" ".into()),
+            TagsError::InvalidIcon("toolong".into()),
+            TagsError::InvalidOrder("x".into()),
+            TagsError::DuplicateTag("t".into()),
+            TagsError::UnsupportedSchema(2),
+            TagsError::BatchOpFailed { index: 0, source: Box::new(TagsError::DuplicateTag("t".into())) },
+        ];
+        for err in errors {
+            let payload = ErrorPayload::from(&err);
+            // The message stays the Display text used for logging
+            assert_eq!(payload.message, err.to_string());
+            let json = serde_json::to_string(&payload).unwrap();
+            let parsed: ErrorPayload = serde_json::from_str(&json).unwrap();
+            assert_eq!(parsed, payload, "{}", json);
+        }
+    }
+}
@@ -4,12 +4,16 @@
 //! - Recursive directory copying
 //! - Recursive directory deletion
 //! - Directory size calculation
+//! - Locked read-modify-write of JSON files (`LockedJsonStore`)
 
-use serde::{Serialize, Serializer};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize, Serializer};
 use std::fmt;
-use std::fs;
-use std::io::{self, Read, Write};
+use std::fs::{self, File, OpenOptions, TryLockError};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::marker::PhantomData;
 use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 use flate2::{write::GzEncoder, Compression, read::GzDecoder};
 use tar::Builder;
 
@@ -20,6 +24,12 @@ pub enum FileOpsError {
     SourceNotFound(PathBuf),
     DestinationExists(PathBuf),
     NotADirectory(PathBuf),
+    Cancelled,
+    LockTimeout(PathBuf),
+    /// Archive entry pointing outside the extraction directory (absolute or `..` path)
+    UnsafeArchivePath(PathBuf),
+    /// Save name that isn't a relative path inside the save directory
+    UnsafeSavePath(String),
 }
 
 impl fmt::Display for FileOpsError {
@@ -35,6 +45,16 @@ impl fmt::Display for FileOpsError {
             FileOpsError::NotADirectory(path) => {
                 write!(f, "Path is not a directory: {}", path.display())
             }
+            FileOpsError::Cancelled => write!(f, "Operation was cancelled"),
+            FileOpsError::LockTimeout(path) => {
+                write!(f, "Timed out waiting for the lock on {}", path.display())
+            }
+            FileOpsError::UnsafeArchivePath(path) => {
+                write!(f, "Archive entry escapes the destination: {}", path.display())
+            }
+            FileOpsError::UnsafeSavePath(name) => {
+                write!(f, "Save name must be a relative path without \"..\": {}", name)
+            }
         }
     }
 }
@@ -338,7 +358,97 @@ pub fn normalize_path_for_display(path: &Path) -> String {
     }
 }
 
-/// Opens the parent directory of the given path in the system file manager.
+/// Launches the platform file manager for a path.
+///
+/// Kept behind a trait so callers that resolve paths (see `crate::reveal`) can be
+/// tested without spawning real processes.
+pub trait FileManagerLauncher {
+    /// Reveals `path` in the file manager. The path is known to exist.
+    fn reveal(&self, path: &Path) -> FileOpsResult<()>;
+}
+
+/// Launcher that spawns the native file manager of the current platform.
+///
+/// # Behavior
+/// - On macOS: Uses `open -R` to reveal the file/directory in Finder
+/// - On Windows: Uses `explorer /select,` to select the file/directory in Explorer
+/// - On Linux: Asks the file manager to select the entry over D-Bus
+///   (`org.freedesktop.FileManager1.ShowItems`), falling back to `xdg-open` of the
+///   parent directory
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemFileManager;
+
+impl FileManagerLauncher for SystemFileManager {
+    fn reveal(&self, path: &Path) -> FileOpsResult<()> {
+        #[cfg(target_os = "macos")]
+        {
+            std::process::Command::new("open")
+                .arg("-R")
+                .arg(path)
+                .spawn()
+                .map_err(FileOpsError::Io)?;
+        }
+
+        #[cfg(target_os = "windows")]
+        {
+            std::process::Command::new("explorer")
+                .arg("/select,")
+                .arg(path)
+                .spawn()
+                .map_err(FileOpsError::Io)?;
+        }
+
+        #[cfg(target_os = "linux")]
+        {
+            // File managers implementing org.freedesktop.FileManager1 (Nautilus,
+            // Dolphin, Nemo, ...) open the parent folder with the entry selected
+            let shown = std::process::Command::new("dbus-send")
+                .args([
+                    "--session",
+                    "--print-reply",
+                    "--reply-timeout=2000",
+                    "--dest=org.freedesktop.FileManager1",
+                    "--type=method_call",
+                    "/org/freedesktop/FileManager1",
+                    "org.freedesktop.FileManager1.ShowItems",
+                ])
+                .arg(format!("array:string:{}", file_uri(path)))
+                .arg("string:")
+                .stdout(std::process::Stdio::null())
+                .stderr(std::process::Stdio::null())
+                .status()
+                .is_ok_and(|status| status.success());
+
+            // Fallback: open the parent directory and let the user spot the entry
+            if !shown {
+                let target = path.parent().unwrap_or(path);
+                std::process::Command::new("xdg-open")
+                    .arg(target)
+                    .spawn()
+                    .map_err(FileOpsError::Io)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Builds the `file://` URI of a path, percent-encoding everything but unreserved
+/// characters and `/` (so commas can't split a `dbus-send` array argument).
+#[cfg(any(target_os = "linux", test))]
+fn file_uri(path: &Path) -> String {
+    let mut uri = String::from("file://");
+    for byte in path.to_string_lossy().bytes() {
+        if byte.is_ascii_alphanumeric() || b"/-._~".contains(&byte) {
+            uri.push(byte as char);
+        } else {
+            uri.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    uri
+}
+
+/// Opens the given path in the system file manager.
 ///
 /// # Arguments
 /// * `path` - Path to the file or directory
@@ -347,9 +457,8 @@ pub fn normalize_path_for_display(path: &Path) -> String {
 /// `FileOpsResult<()>` - Ok(()) on success, Err on failure
 ///
 /// # Behavior
-/// - On macOS: Uses `open -R` to reveal the file/directory in Finder
-/// - On Windows: Uses `explorer /select` to select the file/directory in Explorer
-/// - On Linux: Attempts to use `dbus` for GNOME/KDE, falls back to `xdg-open` for the parent directory
+/// - Returns `SourceNotFound` if the path doesn't exist instead of opening some other folder
+/// - Delegates to [`SystemFileManager`] for the platform-specific launch
 ///
 /// # Example
 /// ```no_run
@@ -359,84 +468,23 @@ pub fn normalize_path_for_display(path: &Path) -> String {
 /// show_in_file_manager(Path::new("/path/to/backup")).unwrap();
 /// ```
 pub fn show_in_file_manager(path: &Path) -> FileOpsResult<()> {
+    show_in_file_manager_with(&SystemFileManager, path)
+}
+
+/// Opens the given path using a specific launcher.
+///
+/// # Arguments
+/// * `launcher` - Launcher used to spawn the file manager
+/// * `path` - Path to the file or directory
+///
+/// # Returns
+/// `FileOpsResult<()>` - Ok(()) on success, `SourceNotFound` if the path doesn't exist
+pub fn show_in_file_manager_with(launcher: &dyn FileManagerLauncher, path: &Path) -> FileOpsResult<()> {
     if !path.exists() {
         return Err(FileOpsError::SourceNotFound(path.to_path_buf()));
     }
 
-    #[cfg(target_os = "macos")]
-    {
-        std::process::Command::new("open")
-            .arg("-R")
-            .arg(path)
-            .spawn()
-            .map_err(FileOpsError::Io)?;
-    }
-
-    #[cfg(target_os = "windows")]
-    {
-        std::process::Command::new("explorer")
-            .arg("/select,")
-            .arg(path)
-            .spawn()
-            .map_err(FileOpsError::Io)?;
-    }
-
-    #[cfg(target_os = "linux")]
-    {
-        // Try different methods for Linux depending on the desktop environment
-        let path_str = path.to_string_lossy().to_string();
-
-        // Try dbus for GNOME (nautilus/Files)
-        let gnome_result = std::process::Command::new("dbus-send")
-            .args([
-                "--session",
-                "--dest=org.gnome.Nautilus",
-                "--type=method_call",
-                "/org/gnome/Nautilus",
-                "org.gtk.Actions.Activate",
-                &format!("array:string:'show-item'", "string:"),
-                &format!("array:string:'file://{}'", path_str),
-                "array:string:",
-            ])
-            .spawn();
-
-        if gnome_result.is_ok() {
-            return Ok(());
-        }
-
-        // Try dbus for KDE (dolphin)
-        let kde_result = std::process::Command::new("dbus-send")
-            .args([
-                "--session",
-                "--dest=org.kde.dolphin",
-                "--type=method_call",
-                "/dolphin",
-                "org.freedesktop.Application.Activate",
-                &format!("array:string:'select'", "string:"),
-                &format!("array:string:'{}'", path_str),
-                "array:string:",
-            ])
-            .spawn();
-
-        if kde_result.is_ok() {
-            return Ok(());
-        }
-
-        // Fallback: open the parent directory
-        if let Some(parent) = path.parent() {
-            std::process::Command::new("xdg-open")
-                .arg(parent)
-                .spawn()
-                .map_err(FileOpsError::Io)?;
-        } else {
-            std::process::Command::new("xdg-open")
-                .arg(path)
-                .spawn()
-                .map_err(FileOpsError::Io)?;
-        }
-    }
-
-    Ok(())
+    launcher.reveal(path)
 }
 
 /// Creates a compressed tar.gz archive of a directory with atomic commit.
@@ -467,6 +515,184 @@ pub fn show_in_file_manager(path: &Path) -> FileOpsResult<()> {
 /// ).unwrap();
 /// ```
 pub fn create_tar_gz(src_dir: &Path, dst_file: &Path) -> FileOpsResult<()> {
+    create_tar_gz_with_observer(src_dir, dst_file, &())
+}
+
+/// Receives progress for long-running archive operations and can cancel them.
+pub trait ArchiveObserver {
+    /// Called after each archive entry; `total` is the number of entries to write.
+    fn on_progress(&self, _done: u64, _total: u64) {}
+
+    /// Checked before each archive entry; returning true aborts the operation.
+    fn is_cancelled(&self) -> bool {
+        false
+    }
+}
+
+/// Observer that ignores progress and never cancels.
+impl ArchiveObserver for () {}
+
+/// Windows error codes for files locked by another process.
+#[cfg(windows)]
+const LOCKED_FILE_OS_ERRORS: [i32; 2] = [32, 33]; // ERROR_SHARING_VIOLATION, ERROR_LOCK_VIOLATION
+
+/// Returns true if the error means another process holds the file open or locked.
+///
+/// Only Windows reports this distinctly; elsewhere it is always false.
+pub fn is_sharing_violation(err: &io::Error) -> bool {
+    #[cfg(windows)]
+    {
+        err.raw_os_error()
+            .is_some_and(|code| LOCKED_FILE_OS_ERRORS.contains(&code))
+    }
+    #[cfg(not(windows))]
+    {
+        let _ = err;
+        false
+    }
+}
+
+/// Returns true if reading a file may succeed once another process lets go of it.
+fn is_retryable_read_error(err: &io::Error) -> bool {
+    is_sharing_violation(err)
+        || matches!(
+            err.kind(),
+            io::ErrorKind::PermissionDenied | io::ErrorKind::ResourceBusy
+        )
+}
+
+/// How archive creation treats files another process holds locked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LockedFileRetry {
+    /// Attempts to open each file, including the first (at least 1)
+    pub attempts: u32,
+    /// Pause between two attempts
+    pub delay: Duration,
+}
+
+impl Default for LockedFileRetry {
+    fn default() -> Self {
+        LockedFileRetry {
+            attempts: 5,
+            delay: Duration::from_millis(200),
+        }
+    }
+}
+
+/// A file left out of an archive because it couldn't be read.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SkippedFile {
+    /// Path relative to the archived directory, with `/` separators
+    pub path: String,
+    /// Error of the last attempt
+    pub reason: String,
+}
+
+/// A generated file written to the root of an archive (e.g., a manifest).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtraArchiveFile {
+    /// File name at the archive root
+    pub name: String,
+    /// File contents
+    pub contents: Vec<u8>,
+}
+
+/// Opens the files written into an archive.
+pub trait FileOpener {
+    /// Opens `path` for reading.
+    fn open(&self, path: &Path) -> io::Result<File>;
+}
+
+/// Opener that reads straight from the file system.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemFileOpener;
+
+impl FileOpener for SystemFileOpener {
+    fn open(&self, path: &Path) -> io::Result<File> {
+        let mut file = File::open(path)?;
+        // A whole-file lock only shows up on read, so probe one byte
+        let mut probe = [0u8; 1];
+        if file.read(&mut probe)? > 0 {
+            file.seek(SeekFrom::Start(0))?;
+        }
+        Ok(file)
+    }
+}
+
+/// Opens a file, retrying while it is locked.
+///
+/// # Returns
+/// `io::Result<File>` - The open file, or the error of the last attempt
+fn open_with_retry(opener: &dyn FileOpener, path: &Path, retry: &LockedFileRetry) -> io::Result<File> {
+    let attempts = retry.attempts.max(1);
+    let mut attempt = 1;
+    loop {
+        match opener.open(path) {
+            Err(err) if attempt < attempts && is_retryable_read_error(&err) => {
+                std::thread::sleep(retry.delay);
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Creates a compressed tar.gz archive, leaving out files that stay locked.
+///
+/// # Arguments
+/// * `src_dir` - Source directory to compress
+/// * `dst_file` - Destination .tar.gz file path
+/// * `observer` - Receives per-entry progress and is polled for cancellation
+/// * `retry` - Attempts and delay for files another process holds locked
+/// * `extras` - Generated files appended at the archive root
+///
+/// # Returns
+/// `FileOpsResult<Vec<SkippedFile>>` - Files left out of the archive; empty if it is complete
+///
+/// # Behavior
+/// Same as `create_tar_gz_with_observer`, except that a file that still can't be
+/// opened after the last attempt is skipped instead of failing the archive. Only
+/// lock and permission errors are retried and skipped; any other error, or a read
+/// failing after the file was opened, still fails the archive.
+pub fn create_tar_gz_skipping_locked(
+    src_dir: &Path,
+    dst_file: &Path,
+    observer: &dyn ArchiveObserver,
+    retry: &LockedFileRetry,
+    extras: &[ExtraArchiveFile],
+) -> FileOpsResult<Vec<SkippedFile>> {
+    create_tar_gz_in(src_dir, dst_file, observer, Some(retry), &SystemFileOpener, extras)
+}
+
+/// Creates a compressed tar.gz archive, reporting progress to an observer.
+///
+/// # Arguments
+/// * `src_dir` - Source directory to compress
+/// * `dst_file` - Destination .tar.gz file path
+/// * `observer` - Receives per-entry progress and is polled for cancellation
+///
+/// # Returns
+/// `FileOpsResult<()>` - Ok(()) on success, `Cancelled` if the observer cancelled
+///
+/// # Behavior
+/// Same as `create_tar_gz`. On failure or cancellation the temporary file is removed.
+pub fn create_tar_gz_with_observer(
+    src_dir: &Path,
+    dst_file: &Path,
+    observer: &dyn ArchiveObserver,
+) -> FileOpsResult<()> {
+    create_tar_gz_in(src_dir, dst_file, observer, None, &SystemFileOpener, &[]).map(|_| ())
+}
+
+/// Shared implementation of archive creation; without `retry`, any unreadable file fails it.
+fn create_tar_gz_in(
+    src_dir: &Path,
+    dst_file: &Path,
+    observer: &dyn ArchiveObserver,
+    retry: Option<&LockedFileRetry>,
+    opener: &dyn FileOpener,
+    extras: &[ExtraArchiveFile],
+) -> FileOpsResult<Vec<SkippedFile>> {
     if !src_dir.exists() {
         return Err(FileOpsError::SourceNotFound(src_dir.to_path_buf()));
     }
@@ -485,21 +711,106 @@ pub fn create_tar_gz(src_dir: &Path, dst_file: &Path) -> FileOpsResult<()> {
     // Create a temporary file path for atomic write
     let temp_file = dst_file.with_extension("tar.gz.tmp");
 
-    // Create the tar.gz file to temporary location
-    let gz_file = fs::File::create(&temp_file)?;
+    let skipped = match write_tar_gz(src_dir, &temp_file, observer, retry, opener, extras) {
+        Ok(skipped) => skipped,
+        Err(err) => {
+            let _ = fs::remove_file(&temp_file);
+            return Err(err);
+        }
+    };
+
+    // Atomically rename the temporary file to the final destination
+    // fs::rename is atomic on POSIX systems when files are on the same filesystem
+    fs::rename(&temp_file, dst_file)?;
+
+    Ok(skipped)
+}
+
+/// Writes the archive for `create_tar_gz_with_observer` to the temporary file.
+fn write_tar_gz(
+    src_dir: &Path,
+    temp_file: &Path,
+    observer: &dyn ArchiveObserver,
+    retry: Option<&LockedFileRetry>,
+    opener: &dyn FileOpener,
+    extras: &[ExtraArchiveFile],
+) -> FileOpsResult<Vec<SkippedFile>> {
+    let mut entries = Vec::new();
+    collect_archive_entries(src_dir, Path::new(""), &mut entries)?;
+    let total = entries.len() as u64;
+
+    let gz_file = fs::File::create(temp_file)?;
     let encoder = GzEncoder::new(gz_file, Compression::default());
     let mut tar = Builder::new(encoder);
+    let mut skipped = Vec::new();
+
+    // Entries are stored under "./", matching `Builder::append_dir_all(".", ..)`
+    tar.append_dir(".", src_dir)?;
+
+    for (index, (path, relative_path, is_dir)) in entries.iter().enumerate() {
+        if observer.is_cancelled() {
+            return Err(FileOpsError::Cancelled);
+        }
+
+        let name = Path::new(".").join(relative_path);
+        if *is_dir {
+            tar.append_dir(&name, path)?;
+        } else {
+            let opened = match retry {
+                Some(retry) => open_with_retry(opener, path, retry),
+                None => opener.open(path),
+            };
+            match opened {
+                Ok(mut file) => tar.append_file(&name, &mut file)?,
+                Err(err) if retry.is_some() && is_retryable_read_error(&err) => {
+                    skipped.push(SkippedFile {
+                        path: relative_path.to_string_lossy().replace('\\', "/"),
+                        reason: err.to_string(),
+                    });
+                }
+                Err(err) => return Err(err.into()),
+            }
+        }
 
-    // Add the source directory to the archive
-    tar.append_dir_all(".", src_dir)?;
+        observer.on_progress(index as u64 + 1, total);
+    }
+
+    let mtime = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    for extra in extras {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(extra.contents.len() as u64);
+        header.set_mode(0o644);
+        header.set_mtime(mtime);
+        header.set_cksum();
+        tar.append_data(&mut header, Path::new(".").join(&extra.name), extra.contents.as_slice())?;
+    }
 
     // Finish the archive (this flushes and completes the gzip stream)
     let encoder = tar.into_inner()?;
     encoder.finish()?;
 
-    // Atomically rename the temporary file to the final destination
-    // fs::rename is atomic on POSIX systems when files are on the same filesystem
-    fs::rename(&temp_file, dst_file)?;
+    Ok(skipped)
+}
+
+/// Collects (absolute path, relative path, is_dir) for every entry below `dir`.
+fn collect_archive_entries(
+    dir: &Path,
+    relative_dir: &Path,
+    entries: &mut Vec<(PathBuf, PathBuf, bool)>,
+) -> FileOpsResult<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let relative_path = relative_dir.join(entry.file_name());
+        let is_dir = path.is_dir();
+
+        entries.push((path.clone(), relative_path.clone(), is_dir));
+        if is_dir {
+            collect_archive_entries(&path, &relative_path, entries)?;
+        }
+    }
 
     Ok(())
 }
@@ -517,6 +828,12 @@ pub fn create_tar_gz(src_dir: &Path, dst_file: &Path) -> FileOpsResult<()> {
 /// - Returns error if source file doesn't exist
 /// - Returns error if destination already exists
 /// - Creates parent directories if needed
+/// - Streams entries one at a time through a fixed buffer; each file is closed
+///   before the next entry is read, so memory and open handles stay constant
+/// - Rejects entries with absolute or `..` paths before writing anything for them
+/// - Skips links and special files, which saves never contain
+/// - Runs of zeros are written as holes, so sparse files stay sparse
+/// - Removes the partially extracted destination on failure
 ///
 /// # Example
 /// ```no_run
@@ -544,13 +861,133 @@ pub fn extract_tar_gz(src_file: &Path, dst_dir: &Path) -> FileOpsResult<()> {
         }
     }
 
-    // Open the gz file and create a decoder
-    let gz_file = fs::File::open(src_file)?;
-    let decoder = flate2::read::GzDecoder::new(gz_file);
+    fs::create_dir_all(dst_dir)?;
+    let result = extract_entries(src_file, dst_dir);
+    if result.is_err() {
+        // Don't leave a half-restored save behind
+        let _ = fs::remove_dir_all(dst_dir);
+    }
+    result
+}
+
+/// Buffer size for streaming archive entries to disk.
+const EXTRACT_BUFFER_SIZE: usize = 64 * 1024;
+
+/// Streams every entry of a tar.gz archive into an existing directory.
+fn extract_entries(src_file: &Path, dst_dir: &Path) -> FileOpsResult<()> {
+    let gz_file = File::open(src_file)?;
+    let decoder = GzDecoder::new(io::BufReader::new(gz_file));
     let mut archive = tar::Archive::new(decoder);
+    let mut buffer = vec![0u8; EXTRACT_BUFFER_SIZE];
 
-    // Extract the archive
-    archive.unpack(dst_dir)?;
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let entry_path = entry.path()?.into_owned();
+        let relative = match sanitize_entry_path(&entry_path)? {
+            Some(relative) => relative,
+            // The archive root ("./")
+            None => continue,
+        };
+        let target = dst_dir.join(relative);
+
+        let entry_type = entry.header().entry_type();
+        if entry_type.is_dir() {
+            fs::create_dir_all(&target)?;
+        } else if entry_type.is_file() || entry_type.is_gnu_sparse() {
+            // Sparse entries read back expanded; `write_entry` restores the holes
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            let modified = entry.header().mtime().ok();
+            write_entry(&mut entry, &target, &mut buffer, modified)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns an entry's path relative to the extraction directory.
+///
+/// # Returns
+/// `FileOpsResult<Option<PathBuf>>` - None for the archive root, error for paths
+/// that are absolute or contain `..`
+fn sanitize_entry_path(path: &Path) -> FileOpsResult<Option<PathBuf>> {
+    let mut relative = PathBuf::new();
+    for component in path.components() {
+        match component {
+            std::path::Component::Normal(part) => relative.push(part),
+            std::path::Component::CurDir => {}
+            std::path::Component::ParentDir
+            | std::path::Component::RootDir
+            | std::path::Component::Prefix(_) => {
+                return Err(FileOpsError::UnsafeArchivePath(path.to_path_buf()));
+            }
+        }
+    }
+
+    Ok((!relative.as_os_str().is_empty()).then_some(relative))
+}
+
+/// Checks that a save name stays inside the save and backup directories.
+///
+/// # Arguments
+/// * `save_name` - Relative path of a save (e.g., "Survival/MySave")
+///
+/// # Returns
+/// `FileOpsResult<()>` - `UnsafeSavePath` if the name is empty, absolute or contains
+/// `..`, a root or a drive prefix
+pub fn validate_save_name(save_name: &str) -> FileOpsResult<()> {
+    let path = Path::new(save_name);
+    let safe = !save_name.is_empty()
+        && !path.is_absolute()
+        && path
+            .components()
+            .all(|c| matches!(c, std::path::Component::Normal(_) | std::path::Component::CurDir));
+    if safe {
+        Ok(())
+    } else {
+        Err(FileOpsError::UnsafeSavePath(save_name.to_string()))
+    }
+}
+
+/// Copies one archive entry to a new file, keeping zero runs sparse.
+///
+/// The file is closed when this returns, before the next entry is read.
+fn write_entry(
+    entry: &mut impl Read,
+    target: &Path,
+    buffer: &mut [u8],
+    modified: Option<u64>,
+) -> FileOpsResult<()> {
+    let mut file = File::create(target)?;
+    let mut length: u64 = 0;
+    let mut pending_hole = false;
+
+    loop {
+        let read = match entry.read(buffer) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e.into()),
+        };
+        let chunk = &buffer[..read];
+        if chunk.iter().all(|&b| b == 0) {
+            file.seek(SeekFrom::Current(read as i64))?;
+            pending_hole = true;
+        } else {
+            file.write_all(chunk)?;
+            pending_hole = false;
+        }
+        length += read as u64;
+    }
+
+    // A trailing hole has to be materialized as file length
+    if pending_hole {
+        file.set_len(length)?;
+    }
+    if let Some(seconds) = modified {
+        let _ = file.set_modified(std::time::UNIX_EPOCH + Duration::from_secs(seconds));
+    }
 
     Ok(())
 }
@@ -580,6 +1017,44 @@ pub fn extract_tar_gz(src_file: &Path, dst_dir: &Path) -> FileOpsResult<()> {
 /// ).unwrap();
 /// ```
 pub fn read_file_from_tar_gz_base64(src_file: &Path, file_path: &str) -> FileOpsResult<Option<String>> {
+    let buffer = match read_file_from_tar_gz(src_file, file_path)? {
+        Some(buffer) => buffer,
+        None => return Ok(None),
+    };
+
+    // Determine MIME type based on file extension
+    let mime_type = if file_path.to_lowercase().ends_with(".png") {
+        "image/png"
+    } else if file_path.to_lowercase().ends_with(".jpg") || file_path.to_lowercase().ends_with(".jpeg") {
+        "image/jpeg"
+    } else if file_path.to_lowercase().ends_with(".gif") {
+        "image/gif"
+    } else if file_path.to_lowercase().ends_with(".webp") {
+        "image/webp"
+    } else {
+        "image/png"
+    };
+
+    // Encode to base64
+    use base64::Engine;
+    let base64_engine = base64::engine::general_purpose::STANDARD;
+    let base64_string = base64_engine.encode(&buffer);
+
+    Ok(Some(format!("data:{};base64,{}", mime_type, base64_string)))
+}
+
+/// Reads a file from a tar.gz archive.
+///
+/// # Arguments
+/// * `src_file` - Source .tar.gz file path
+/// * `file_path` - Path of the file within the archive (e.g., "mods_manifest.json")
+///
+/// # Returns
+/// `FileOpsResult<Option<Vec<u8>>>` - File contents, or None if the archive doesn't have it
+///
+/// # Behavior
+/// A leading "./" on archive entries is ignored.
+pub fn read_file_from_tar_gz(src_file: &Path, file_path: &str) -> FileOpsResult<Option<Vec<u8>>> {
     if !src_file.exists() {
         return Err(FileOpsError::SourceNotFound(src_file.to_path_buf()));
     }
@@ -594,40 +1069,59 @@ pub fn read_file_from_tar_gz_base64(src_file: &Path, file_path: &str) -> FileOps
         let mut entry = entry?;
         let path = entry.path()?;
 
-        // Check if this is the file we're looking for
         // The path in tar might start with "./", so we need to handle that
         let entry_path = path.to_string_lossy();
         let normalized_path = entry_path.strip_prefix("./").unwrap_or(&entry_path);
 
         if normalized_path == file_path {
-            // Read the file content
             let mut buffer = Vec::new();
             entry.read_to_end(&mut buffer)?;
+            return Ok(Some(buffer));
+        }
+    }
 
-            // Determine MIME type based on file extension
-            let mime_type = if file_path.to_lowercase().ends_with(".png") {
-                "image/png"
-            } else if file_path.to_lowercase().ends_with(".jpg") || file_path.to_lowercase().ends_with(".jpeg") {
-                "image/jpeg"
-            } else if file_path.to_lowercase().ends_with(".gif") {
-                "image/gif"
-            } else if file_path.to_lowercase().ends_with(".webp") {
-                "image/webp"
-            } else {
-                "image/png"
-            };
+    // File not found in archive
+    Ok(None)
+}
+
+/// Lists the names of regular files stored at the root of a tar.gz archive.
+///
+/// # Arguments
+/// * `src_file` - Source .tar.gz file path
+///
+/// # Returns
+/// `FileOpsResult<Vec<String>>` - File names at the archive root, in archive order
+///
+/// # Behavior
+/// - Entries in subdirectories are skipped
+/// - A leading "./" (as written by `create_tar_gz`) is ignored
+/// - Returns an error if the file is not a readable tar.gz archive
+pub fn list_tar_gz_root_files(src_file: &Path) -> FileOpsResult<Vec<String>> {
+    if !src_file.exists() {
+        return Err(FileOpsError::SourceNotFound(src_file.to_path_buf()));
+    }
 
-            // Encode to base64
-            use base64::Engine;
-            let base64_engine = base64::engine::general_purpose::STANDARD;
-            let base64_string = base64_engine.encode(&buffer);
+    let gz_file = fs::File::open(src_file)?;
+    let decoder = GzDecoder::new(gz_file);
+    let mut archive = tar::Archive::new(decoder);
 
-            return Ok(Some(format!("data:{};base64,{}", mime_type, base64_string)));
+    let mut names = Vec::new();
+    for entry in archive.entries()? {
+        let entry = entry?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+
+        let path = entry.path()?;
+        let entry_path = path.to_string_lossy();
+        let normalized_path = entry_path.strip_prefix("./").unwrap_or(&entry_path);
+
+        if !normalized_path.is_empty() && !normalized_path.contains('/') {
+            names.push(normalized_path.to_string());
         }
     }
 
-    // File not found in archive
-    Ok(None)
+    Ok(names)
 }
 
 /// Gets the size of a file.
@@ -646,6 +1140,363 @@ pub fn get_file_size(path: &Path) -> FileOpsResult<u64> {
     Ok(metadata.len())
 }
 
+/// Gets the free space available to the current user on the volume containing a path.
+///
+/// # Arguments
+/// * `path` - Any existing path on the volume
+///
+/// # Returns
+/// `FileOpsResult<u64>` - Available space in bytes on success, Err on failure
+pub fn get_available_space(path: &Path) -> FileOpsResult<u64> {
+    if !path.exists() {
+        return Err(FileOpsError::SourceNotFound(path.to_path_buf()));
+    }
+
+    available_space(path)
+}
+
+#[cfg(unix)]
+fn available_space(path: &Path) -> FileOpsResult<u64> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let c_path = CString::new(path.as_os_str().as_bytes())
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+
+    if unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) } != 0 {
+        return Err(io::Error::last_os_error().into());
+    }
+
+    // Field widths differ between platforms
+    #[allow(clippy::unnecessary_cast)]
+    let available = stat.f_bavail as u64 * stat.f_frsize as u64;
+    Ok(available)
+}
+
+#[cfg(target_os = "windows")]
+fn available_space(path: &Path) -> FileOpsResult<u64> {
+    use windows::core::HSTRING;
+    use windows::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
+
+    let mut available: u64 = 0;
+    unsafe {
+        GetDiskFreeSpaceExW(
+            &HSTRING::from(path.as_os_str()),
+            Some(&mut available as *mut u64),
+            None,
+            None,
+        )
+    }
+    .map_err(|e| io::Error::other(e.to_string()))?;
+
+    Ok(available)
+}
+
+/// Runs blocking filesystem work on the async runtime's blocking pool.
+///
+/// # Arguments
+/// * `f` - Blocking operation (directory walks, file reads beyond metadata)
+///
+/// # Returns
+/// `Result<T, E>` - The operation's result, or an I/O error if the task panicked
+///
+/// # Behavior
+/// Async commands await this instead of running the work inline, so the command
+/// threads stay free for other commands.
+pub async fn run_blocking<T, E, F>(f: F) -> Result<T, E>
+where
+    T: Send + 'static,
+    E: From<FileOpsError> + Send + 'static,
+    F: FnOnce() -> Result<T, E> + Send + 'static,
+{
+    tokio::task::spawn_blocking(f).await.unwrap_or_else(|e| {
+        Err(FileOpsError::Io(io::Error::other(format!("Task join error: {}", e))).into())
+    })
+}
+
+/// Default time to wait for a store lock held by another thread or process.
+const DEFAULT_LOCK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Interval between lock attempts while waiting.
+const LOCK_RETRY_INTERVAL: Duration = Duration::from_millis(10);
+
+/// JSON file guarded by an OS advisory lock.
+///
+/// Every read-modify-write holds an exclusive lock on a `<file>.lock` sidecar for its
+/// whole duration, so concurrent writers (threads or processes) can't lose each
+/// other's updates. Writes go to a temp file that is renamed over the original.
+/// A missing file reads as `T::default()`.
+///
+/// With `with_backups`, each write first copies the previous file to `<file>.bak`,
+/// shifting older copies to `<file>.bak.2`, `<file>.bak.3` and so on; `recover`
+/// restores the newest one that still parses.
+#[derive(Debug, Clone)]
+pub struct LockedJsonStore<T> {
+    path: PathBuf,
+    timeout: Duration,
+    backups: usize,
+    _marker: PhantomData<fn() -> T>,
+}
+
+/// Held store lock, released on drop.
+struct StoreLock {
+    file: File,
+}
+
+impl Drop for StoreLock {
+    fn drop(&mut self) {
+        let _ = self.file.unlock();
+    }
+}
+
+impl<T: Serialize + DeserializeOwned + Default> LockedJsonStore<T> {
+    /// Creates a store for a JSON file.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        LockedJsonStore {
+            path: path.into(),
+            timeout: DEFAULT_LOCK_TIMEOUT,
+            backups: 0,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Keeps this many copies of the previous file, newest first (0 = none).
+    pub fn with_backups(mut self, generations: usize) -> Self {
+        self.backups = generations;
+        self
+    }
+
+    /// Sets how long to wait for the lock before failing with `LockTimeout`.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Returns the path of the JSON file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the path of a backup generation: 1 is `<file>.bak`, n is `<file>.bak.n`.
+    pub fn backup_path(&self, generation: usize) -> PathBuf {
+        let mut name = self.path.file_name().unwrap_or_default().to_os_string();
+        name.push(".bak");
+        if generation > 1 {
+            name.push(format!(".{}", generation));
+        }
+        self.path.with_file_name(name)
+    }
+
+    /// Returns the path of the lock sidecar file.
+    fn lock_path(&self) -> PathBuf {
+        let mut name = self.path.file_name().unwrap_or_default().to_os_string();
+        name.push(".lock");
+        self.path.with_file_name(name)
+    }
+
+    /// Acquires the lock, polling until the timeout expires.
+    fn lock(&self, exclusive: bool) -> FileOpsResult<StoreLock> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(self.lock_path())?;
+
+        let deadline = Instant::now() + self.timeout;
+        loop {
+            let attempt = if exclusive {
+                file.try_lock()
+            } else {
+                file.try_lock_shared()
+            };
+            match attempt {
+                Ok(()) => return Ok(StoreLock { file }),
+                Err(TryLockError::WouldBlock) if Instant::now() < deadline => {
+                    std::thread::sleep(LOCK_RETRY_INTERVAL);
+                }
+                Err(TryLockError::WouldBlock) => {
+                    return Err(FileOpsError::LockTimeout(self.path.clone()))
+                }
+                Err(TryLockError::Error(e)) => return Err(FileOpsError::Io(e)),
+            }
+        }
+    }
+
+    /// Reads the file without locking.
+    fn read_unlocked<E>(&self) -> Result<T, E>
+    where
+        E: From<FileOpsError> + From<serde_json::Error>,
+    {
+        match fs::read_to_string(&self.path) {
+            Ok(content) => Ok(serde_json::from_str(&content)?),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(T::default()),
+            Err(e) => Err(FileOpsError::Io(e).into()),
+        }
+    }
+
+    /// Writes formatted JSON to a temp file and renames it over the original.
+    ///
+    /// `current_is_valid` says whether the file on disk parses; only a valid file is
+    /// rotated into the backups, so a corrupted one can't push out good copies.
+    fn write_unlocked<E>(&self, value: &T, current_is_valid: bool) -> Result<(), E>
+    where
+        E: From<FileOpsError> + From<serde_json::Error>,
+    {
+        let json = serde_json::to_string_pretty(value)?;
+        if self.backups > 0 && current_is_valid && self.path.exists() {
+            // A backup that can't be written must not block the write itself
+            if let Err(e) = self.rotate_backups() {
+                eprintln!("[WARN] Failed to back up {}: {}", self.path.display(), e);
+            }
+        }
+        Ok(write_file_atomic(&self.path, json.as_bytes())?)
+    }
+
+    /// Shifts every backup one generation older and copies the file to `<file>.bak`.
+    fn rotate_backups(&self) -> io::Result<()> {
+        for generation in (1..self.backups).rev() {
+            match fs::rename(self.backup_path(generation), self.backup_path(generation + 1)) {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+                _ => {}
+            }
+        }
+        fs::copy(&self.path, self.backup_path(1)).map(|_| ())
+    }
+
+    /// Returns whether a file exists and parses as `T`.
+    fn parses(path: &Path) -> bool {
+        fs::read_to_string(path)
+            .ok()
+            .is_some_and(|content| serde_json::from_str::<T>(&content).is_ok())
+    }
+
+    /// Reads the current value under a shared lock.
+    ///
+    /// # Returns
+    /// `Result<T, E>` - Stored value, `T::default()` if the file doesn't exist, or an
+    /// error if the file is corrupted or the lock times out
+    pub fn load<E>(&self) -> Result<T, E>
+    where
+        E: From<FileOpsError> + From<serde_json::Error>,
+    {
+        let _lock = self.lock(false)?;
+        self.read_unlocked()
+    }
+
+    /// Replaces the stored value under an exclusive lock.
+    pub fn save<E>(&self, value: &T) -> Result<(), E>
+    where
+        E: From<FileOpsError> + From<serde_json::Error>,
+    {
+        let _lock = self.lock(true)?;
+        let current_is_valid = self.backups > 0 && Self::parses(&self.path);
+        self.write_unlocked(value, current_is_valid)
+    }
+
+    /// Replaces a file that doesn't parse with its newest backup that does.
+    ///
+    /// # Returns
+    /// `Result<Option<PathBuf>, E>` - The backup restored, or None if the file parses,
+    /// is missing, or no backup generation parses either
+    ///
+    /// # Behavior
+    /// Runs under the exclusive lock. The backups are left in place, so a later
+    /// recovery can still fall back to them.
+    pub fn recover<E>(&self) -> Result<Option<PathBuf>, E>
+    where
+        E: From<FileOpsError> + From<serde_json::Error>,
+    {
+        let _lock = self.lock(true)?;
+        if !self.path.exists() || Self::parses(&self.path) {
+            return Ok(None);
+        }
+        for generation in 1..=self.backups {
+            let backup = self.backup_path(generation);
+            if Self::parses(&backup) {
+                let content = fs::read(&backup).map_err(FileOpsError::Io)?;
+                write_file_atomic(&self.path, &content)?;
+                return Ok(Some(backup));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Reads, modifies and writes the value under one exclusive lock.
+    ///
+    /// # Arguments
+    /// * `f` - Closure applied to the current value
+    ///
+    /// # Returns
+    /// `Result<R, E>` - The closure's result
+    ///
+    /// # Behavior
+    /// - The lock is held from the read until after the write
+    /// - If the closure fails, nothing is written
+    pub fn modify<R, E, F>(&self, f: F) -> Result<R, E>
+    where
+        E: From<FileOpsError> + From<serde_json::Error>,
+        F: FnOnce(&mut T) -> Result<R, E>,
+    {
+        let _lock = self.lock(true)?;
+        let mut value = self.read_unlocked::<E>()?;
+        let result = f(&mut value)?;
+        self.write_unlocked::<E>(&value, true)?;
+        Ok(result)
+    }
+}
+
+/// Writes a file through a `<file>.tmp` sibling that is renamed over the target.
+///
+/// # Arguments
+/// * `path` - Path of the file to write
+/// * `contents` - Bytes to write
+///
+/// # Returns
+/// `FileOpsResult<()>` - Ok(()) on success, Err on failure
+///
+/// # Behavior
+/// Readers see either the previous file or the complete new one. The temp file is
+/// synced before the rename and removed if any step fails. A temp file left behind
+/// by a crash is never read and is truncated by the next write.
+///
+/// `fs::rename` replaces an existing target on Windows too (`MoveFileExW` with
+/// `MOVEFILE_REPLACE_EXISTING`), so no remove-then-rename window exists. On Unix the
+/// directory is synced afterwards so the rename itself survives a power loss.
+pub fn write_file_atomic(path: &Path, contents: &[u8]) -> FileOpsResult<()> {
+    let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(".tmp");
+    let tmp_path = path.with_file_name(tmp_name);
+
+    let write = || -> io::Result<()> {
+        let mut file = File::create(&tmp_path)?;
+        file.write_all(contents)?;
+        file.sync_all()?;
+        fs::rename(&tmp_path, path)?;
+        sync_parent_dir(path);
+        Ok(())
+    };
+    write().map_err(|e| {
+        let _ = fs::remove_file(&tmp_path);
+        FileOpsError::Io(e)
+    })
+}
+
+/// Syncs the directory containing `path` (best-effort, Unix only).
+fn sync_parent_dir(path: &Path) {
+    #[cfg(unix)]
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        if let Ok(dir) = File::open(parent) {
+            let _ = dir.sync_all();
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+}
+
 /// Deletes a file.
 ///
 /// # Arguments
@@ -665,6 +1516,7 @@ pub fn delete_file(path: &Path) -> FileOpsResult<()> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::ConfigError;
     use std::fs::{self, File};
     use tempfile::TempDir;
 
@@ -789,6 +1641,36 @@ mod tests {
         assert!(matches!(result, Err(FileOpsError::NotADirectory(_))));
     }
 
+    #[test]
+    fn test_get_available_space() {
+        let temp_dir = TempDir::new().unwrap();
+        assert!(get_available_space(temp_dir.path()).unwrap() > 0);
+
+        let result = get_available_space(&temp_dir.path().join("missing"));
+        assert!(matches!(result, Err(FileOpsError::SourceNotFound(_))));
+    }
+
+    /// Launcher that fails the test if it is ever invoked.
+    struct PanickingLauncher;
+
+    impl FileManagerLauncher for PanickingLauncher {
+        fn reveal(&self, path: &Path) -> FileOpsResult<()> {
+            panic!("launcher should not be called for {}", path.display());
+        }
+    }
+
+    #[test]
+    fn test_file_uri_escapes_reserved_characters() {
+        assert_eq!(file_uri(Path::new("/saves/My Save,1/a.tar.gz")), "file:///saves/My%20Save%2C1/a.tar.gz");
+        assert_eq!(file_uri(Path::new("/saves/Caf\u{E9}")), "file:///saves/Caf%C3%A9");
+    }
+
+    #[test]
+    fn test_show_in_file_manager_not_found() {
+        let result = show_in_file_manager_with(&PanickingLauncher, Path::new("/nonexistent/path"));
+        assert!(matches!(result, Err(FileOpsError::SourceNotFound(_))));
+    }
+
     #[test]
     fn test_format_size_bytes() {
         assert_eq!(format_size(0), "0 B");
@@ -860,4 +1742,454 @@ mod tests {
         let deep_file = dst_dir.join("level_0/level_1/level_2/level_3/level_4/level_5/level_6/level_7/level_8/level_9/file_9.txt");
         assert!(deep_file.exists());
     }
+
+    #[derive(Debug, Default, Serialize, serde::Deserialize)]
+    struct Counter {
+        value: u64,
+        #[serde(default)]
+        writers: Vec<String>,
+    }
+
+    /// Builds a tar.gz whose single entry has a raw (unvalidated) path.
+    fn archive_with_raw_path(dst: &Path, raw_path: &str) {
+        let mut header = tar::Header::new_old();
+        let name = &mut header.as_old_mut().name;
+        name[..raw_path.len()].copy_from_slice(raw_path.as_bytes());
+        header.set_size(4);
+        header.set_mode(0o644);
+        header.set_entry_type(tar::EntryType::Regular);
+        header.set_cksum();
+
+        let encoder = GzEncoder::new(File::create(dst).unwrap(), Compression::fast());
+        let mut builder = Builder::new(encoder);
+        builder.append(&header, &b"evil"[..]).unwrap();
+        builder.into_inner().unwrap().finish().unwrap();
+    }
+
+    #[test]
+    fn test_extract_rejects_path_traversal() {
+        let temp_dir = TempDir::new().unwrap();
+
+        for raw_path in ["../escaped.txt", "nested/../../escaped.txt", "/tmp/escaped.txt"] {
+            let archive = temp_dir.path().join("evil.tar.gz");
+            archive_with_raw_path(&archive, raw_path);
+            let dst = temp_dir.path().join("restore/save");
+
+            let result = extract_tar_gz(&archive, &dst);
+            assert!(
+                matches!(result, Err(FileOpsError::UnsafeArchivePath(_))),
+                "{}: {:?}",
+                raw_path,
+                result
+            );
+            // Nothing written, and the partial destination is gone
+            assert!(!temp_dir.path().join("escaped.txt").exists());
+            assert!(!dst.exists());
+        }
+    }
+
+    #[test]
+    fn test_sanitize_entry_path() {
+        assert_eq!(sanitize_entry_path(Path::new("./")).unwrap(), None);
+        assert_eq!(
+            sanitize_entry_path(Path::new("./map/./chunk.bin")).unwrap(),
+            Some(PathBuf::from("map/chunk.bin"))
+        );
+        assert!(sanitize_entry_path(Path::new("map/../../x")).is_err());
+    }
+
+    /// Generates a save with `small_files` tiny files and one sparse file of `sparse_len` bytes.
+    fn generate_large_save(dir: &Path, small_files: usize, sparse_len: u64) {
+        for i in 0..small_files {
+            let chunk_dir = dir.join(format!("map/{}", i / 1000));
+            if i % 1000 == 0 {
+                fs::create_dir_all(&chunk_dir).unwrap();
+            }
+            fs::write(chunk_dir.join(format!("map_{}.bin", i)), i.to_le_bytes()).unwrap();
+        }
+
+        // Data at both ends with a large hole in between
+        let mut sparse = File::create(dir.join("world.bin")).unwrap();
+        sparse.write_all(b"head").unwrap();
+        sparse.seek(SeekFrom::Start(sparse_len - 4)).unwrap();
+        sparse.write_all(b"tail").unwrap();
+    }
+
+    /// Round-trips a generated save and checks every file.
+    fn assert_round_trip(small_files: usize, sparse_len: u64) {
+        let temp_dir = TempDir::new().unwrap();
+        let save = temp_dir.path().join("save");
+        generate_large_save(&save, small_files, sparse_len);
+
+        let archive = temp_dir.path().join("save.tar.gz");
+        create_tar_gz(&save, &archive).unwrap();
+        let restored = temp_dir.path().join("restored");
+        extract_tar_gz(&archive, &restored).unwrap();
+
+        for i in 0..small_files {
+            let path = restored.join(format!("map/{}/map_{}.bin", i / 1000, i));
+            assert_eq!(fs::read(path).unwrap(), i.to_le_bytes());
+        }
+
+        let world = restored.join("world.bin");
+        assert_eq!(fs::metadata(&world).unwrap().len(), sparse_len);
+        let mut file = File::open(&world).unwrap();
+        let mut edge = [0u8; 4];
+        file.read_exact(&mut edge).unwrap();
+        assert_eq!(&edge, b"head");
+        file.seek(SeekFrom::Start(sparse_len - 4)).unwrap();
+        file.read_exact(&mut edge).unwrap();
+        assert_eq!(&edge, b"tail");
+
+        #[cfg(unix)]
+        {
+            // The hole is restored as a hole, not as written zeros
+            use std::os::unix::fs::MetadataExt;
+            let allocated = fs::metadata(&world).unwrap().blocks() * 512;
+            assert!(allocated < sparse_len / 2, "{} bytes allocated", allocated);
+        }
+    }
+
+    #[test]
+    fn test_extract_round_trip_many_files_and_sparse_file() {
+        assert_round_trip(3_000, 64 * 1024 * 1024);
+    }
+
+    /// Peak resident memory of this process in KiB (Linux only).
+    fn peak_rss_kib() -> Option<u64> {
+        let status = fs::read_to_string("/proc/self/status").ok()?;
+        let line = status.lines().find(|l| l.starts_with("VmHWM:"))?;
+        line.split_whitespace().nth(1)?.parse().ok()
+    }
+
+    /// Full-size restore: 40k files plus a 3 GiB sparse file. Slow; run with `--ignored`.
+    #[test]
+    #[ignore]
+    fn test_extract_large_save_within_memory_envelope() {
+        let before = peak_rss_kib();
+        assert_round_trip(40_000, 3 * 1024 * 1024 * 1024);
+
+        if let (Some(before), Some(after)) = (before, peak_rss_kib()) {
+            let growth_mib = after.saturating_sub(before) / 1024;
+            assert!(growth_mib < 64, "peak memory grew by {} MiB", growth_mib);
+        }
+    }
+
+    /// Opener that fails a file with a lock error until it has been opened `failures` times.
+    struct FlakyOpener {
+        locked: PathBuf,
+        failures: u32,
+        attempts: std::cell::Cell<u32>,
+    }
+
+    impl FlakyOpener {
+        fn new(locked: PathBuf, failures: u32) -> Self {
+            FlakyOpener {
+                locked,
+                failures,
+                attempts: std::cell::Cell::new(0),
+            }
+        }
+    }
+
+    impl FileOpener for FlakyOpener {
+        fn open(&self, path: &Path) -> io::Result<File> {
+            if path == self.locked {
+                self.attempts.set(self.attempts.get() + 1);
+                if self.attempts.get() <= self.failures {
+                    return Err(io::Error::new(io::ErrorKind::PermissionDenied, "file is locked"));
+                }
+            }
+            SystemFileOpener.open(path)
+        }
+    }
+
+    fn quick_retry(attempts: u32) -> LockedFileRetry {
+        LockedFileRetry {
+            attempts,
+            delay: Duration::from_millis(1),
+        }
+    }
+
+    #[test]
+    fn test_archive_retries_locked_file() {
+        let src_dir = create_test_structure();
+        let dst = TempDir::new().unwrap();
+        let archive = dst.path().join("save.tar.gz");
+        let opener = FlakyOpener::new(src_dir.path().join("subdir/file2.txt"), 2);
+
+        let skipped =
+            create_tar_gz_in(src_dir.path(), &archive, &(), Some(&quick_retry(3)), &opener, &[]).unwrap();
+        assert!(skipped.is_empty());
+        assert_eq!(opener.attempts.get(), 3);
+
+        let restored = dst.path().join("restored");
+        extract_tar_gz(&archive, &restored).unwrap();
+        assert_eq!(fs::read(restored.join("subdir/file2.txt")).unwrap(), b"world test content");
+    }
+
+    #[test]
+    fn test_archive_with_extra_files() {
+        let src_dir = create_test_structure();
+        let dst = TempDir::new().unwrap();
+        let archive = dst.path().join("save.tar.gz");
+        let extras = [ExtraArchiveFile {
+            name: "manifest.json".to_string(),
+            contents: b"{\"mods\":[]}".to_vec(),
+        }];
+
+        create_tar_gz_skipping_locked(src_dir.path(), &archive, &(), &quick_retry(1), &extras).unwrap();
+
+        assert!(list_tar_gz_root_files(&archive).unwrap().contains(&"manifest.json".to_string()));
+        assert_eq!(
+            read_file_from_tar_gz(&archive, "manifest.json").unwrap(),
+            Some(b"{\"mods\":[]}".to_vec())
+        );
+        assert_eq!(
+            read_file_from_tar_gz(&archive, "file1.txt").unwrap(),
+            Some(fs::read(src_dir.path().join("file1.txt")).unwrap())
+        );
+        assert_eq!(read_file_from_tar_gz(&archive, "missing.json").unwrap(), None);
+    }
+
+    #[test]
+    fn test_archive_skips_file_that_stays_locked() {
+        let src_dir = create_test_structure();
+        let dst = TempDir::new().unwrap();
+        let archive = dst.path().join("save.tar.gz");
+        let opener = FlakyOpener::new(src_dir.path().join("subdir/file2.txt"), u32::MAX);
+
+        let skipped =
+            create_tar_gz_in(src_dir.path(), &archive, &(), Some(&quick_retry(4)), &opener, &[]).unwrap();
+        assert_eq!(opener.attempts.get(), 4);
+        assert_eq!(skipped.len(), 1);
+        assert_eq!(skipped[0].path, "subdir/file2.txt");
+        assert!(skipped[0].reason.contains("locked"));
+
+        // Everything else is archived
+        let restored = dst.path().join("restored");
+        extract_tar_gz(&archive, &restored).unwrap();
+        assert!(!restored.join("subdir/file2.txt").exists());
+        assert_eq!(fs::read(restored.join("subdir/nested/file3.txt")).unwrap(), b"nested data here");
+    }
+
+    #[test]
+    fn test_archive_without_retry_fails_on_locked_file() {
+        let src_dir = create_test_structure();
+        let dst = TempDir::new().unwrap();
+        let archive = dst.path().join("save.tar.gz");
+        let opener = FlakyOpener::new(src_dir.path().join("file1.txt"), 1);
+
+        let result = create_tar_gz_in(src_dir.path(), &archive, &(), None, &opener, &[]);
+        assert!(matches!(result, Err(FileOpsError::Io(ref e)) if e.kind() == io::ErrorKind::PermissionDenied));
+        assert_eq!(opener.attempts.get(), 1);
+        assert!(!archive.exists());
+        assert!(!archive.with_extension("tar.gz.tmp").exists());
+    }
+
+    #[test]
+    fn test_archive_does_not_skip_other_errors() {
+        struct MissingOpener(PathBuf);
+        impl FileOpener for MissingOpener {
+            fn open(&self, path: &Path) -> io::Result<File> {
+                if path == self.0 {
+                    return Err(io::Error::from(io::ErrorKind::NotFound));
+                }
+                SystemFileOpener.open(path)
+            }
+        }
+
+        let src_dir = create_test_structure();
+        let dst = TempDir::new().unwrap();
+        let archive = dst.path().join("save.tar.gz");
+        let opener = MissingOpener(src_dir.path().join("file1.txt"));
+
+        let result = create_tar_gz_in(src_dir.path(), &archive, &(), Some(&quick_retry(5)), &opener, &[]);
+        assert!(matches!(result, Err(FileOpsError::Io(ref e)) if e.kind() == io::ErrorKind::NotFound));
+        assert!(!archive.exists());
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_archive_skips_file_held_open_exclusively() {
+        use std::os::windows::fs::OpenOptionsExt;
+
+        let src_dir = create_test_structure();
+        let dst = TempDir::new().unwrap();
+        let archive = dst.path().join("save.tar.gz");
+
+        // No sharing: every other open fails with ERROR_SHARING_VIOLATION
+        let _held = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .share_mode(0)
+            .open(src_dir.path().join("subdir/file2.txt"))
+            .unwrap();
+
+        let skipped =
+            create_tar_gz_skipping_locked(src_dir.path(), &archive, &(), &quick_retry(2), &[]).unwrap();
+        assert_eq!(skipped.len(), 1);
+        assert_eq!(skipped[0].path, "subdir/file2.txt");
+
+        let result = create_tar_gz(src_dir.path(), &dst.path().join("strict.tar.gz"));
+        assert!(matches!(result, Err(FileOpsError::Io(ref e)) if is_sharing_violation(e)));
+    }
+
+    /// Increments the counter `times` times, each in its own locked modify.
+    fn hammer_store(path: &Path, times: usize, writer: &str) {
+        let store: LockedJsonStore<Counter> =
+            LockedJsonStore::new(path).with_timeout(Duration::from_secs(30));
+        for _ in 0..times {
+            store
+                .modify(|counter: &mut Counter| {
+                    counter.value += 1;
+                    if !counter.writers.iter().any(|w| w == writer) {
+                        counter.writers.push(writer.to_string());
+                    }
+                    Ok::<_, ConfigError>(())
+                })
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn test_locked_store_missing_file_and_failed_modify() {
+        let temp_dir = TempDir::new().unwrap();
+        let store: LockedJsonStore<Counter> = LockedJsonStore::new(temp_dir.path().join("c.json"));
+        assert_eq!(store.load::<ConfigError>().unwrap().value, 0);
+
+        store.save::<ConfigError>(&Counter { value: 7, writers: Vec::new() }).unwrap();
+
+        // A failing closure leaves the file untouched
+        let result = store.modify(|counter: &mut Counter| {
+            counter.value = 100;
+            Err::<(), _>(ConfigError::InvalidValue("rejected".to_string()))
+        });
+        assert!(matches!(result, Err(ConfigError::InvalidValue(_))));
+        assert_eq!(store.load::<ConfigError>().unwrap().value, 7);
+        assert!(!temp_dir.path().join("c.json.tmp").exists());
+    }
+
+    #[test]
+    fn test_locked_store_backup_generations() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("c.json");
+        let store: LockedJsonStore<Counter> = LockedJsonStore::new(&path).with_backups(2);
+        let value = |path: &Path| -> Counter { serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap() };
+
+        for i in 1..=4 {
+            store.save::<ConfigError>(&Counter { value: i, writers: Vec::new() }).unwrap();
+        }
+        assert_eq!(store.backup_path(1), temp_dir.path().join("c.json.bak"));
+        assert_eq!(value(&store.backup_path(1)).value, 3);
+        assert_eq!(value(&store.backup_path(2)).value, 2);
+        assert!(!store.backup_path(3).exists());
+
+        // A valid file needs no recovery
+        assert_eq!(store.recover::<ConfigError>().unwrap(), None);
+
+        // A corrupted file is restored from the newest valid generation
+        fs::write(store.backup_path(1), "{").unwrap();
+        fs::write(&path, "").unwrap();
+        assert_eq!(store.recover::<ConfigError>().unwrap(), Some(store.backup_path(2)));
+        assert_eq!(store.load::<ConfigError>().unwrap().value, 2);
+
+        // A corrupted file isn't rotated into the backups
+        fs::write(&path, "").unwrap();
+        store.save::<ConfigError>(&Counter { value: 5, writers: Vec::new() }).unwrap();
+        assert_eq!(fs::read_to_string(store.backup_path(1)).unwrap(), "{");
+
+        fs::write(&path, "").unwrap();
+        fs::write(store.backup_path(2), "").unwrap();
+        assert_eq!(store.recover::<ConfigError>().unwrap(), None);
+    }
+
+    #[test]
+    fn test_write_file_atomic() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("report.csv");
+        fs::write(&path, "old").unwrap();
+
+        write_file_atomic(&path, b"new").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "new");
+        assert!(!temp_dir.path().join("report.csv.tmp").exists());
+
+        // A missing directory fails without leaving anything behind
+        let missing = temp_dir.path().join("missing").join("report.csv");
+        assert!(matches!(write_file_atomic(&missing, b"new"), Err(FileOpsError::Io(_))));
+        assert!(!missing.with_file_name("report.csv.tmp").exists());
+    }
+
+    #[test]
+    fn test_locked_store_times_out() {
+        let temp_dir = TempDir::new().unwrap();
+        let store: LockedJsonStore<Counter> = LockedJsonStore::new(temp_dir.path().join("c.json"))
+            .with_timeout(Duration::from_millis(50));
+
+        let _held = store.lock(true).unwrap();
+        let result = store.load::<ConfigError>();
+        assert!(matches!(result, Err(ConfigError::FileOp(FileOpsError::LockTimeout(_)))));
+    }
+
+    #[test]
+    fn test_locked_store_threads_lose_no_updates() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("counter.json");
+
+        let handles: Vec<_> = (0..8)
+            .map(|i| {
+                let path = path.clone();
+                std::thread::spawn(move || hammer_store(&path, 25, &format!("thread{}", i)))
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let counter: Counter = LockedJsonStore::new(&path).load::<ConfigError>().unwrap();
+        assert_eq!(counter.value, 8 * 25);
+        assert_eq!(counter.writers.len(), 8);
+    }
+
+    /// Environment variable pointing the child worker below at the shared store.
+    const STORE_WORKER_ENV: &str = "PZ_LOCKED_STORE_WORKER_PATH";
+
+    /// Worker run in child processes by `test_locked_store_processes_lose_no_updates`.
+    #[test]
+    #[ignore]
+    fn locked_store_process_worker() {
+        if let Ok(path) = std::env::var(STORE_WORKER_ENV) {
+            hammer_store(Path::new(&path), 25, &format!("process{}", std::process::id()));
+        }
+    }
+
+    #[test]
+    fn test_locked_store_processes_lose_no_updates() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("counter.json");
+        // Test names don't include the crate name
+        let module = module_path!().split_once("::").map_or("", |(_, rest)| rest);
+        let worker = format!("{}::locked_store_process_worker", module);
+        let exe = std::env::current_exe().unwrap();
+
+        let children: Vec<_> = (0..4)
+            .map(|_| {
+                std::process::Command::new(&exe)
+                    .args([worker.as_str(), "--exact", "--ignored", "--test-threads=1"])
+                    .env(STORE_WORKER_ENV, &path)
+                    .stdout(std::process::Stdio::null())
+                    .spawn()
+                    .unwrap()
+            })
+            .collect();
+        // Hammer from this process at the same time
+        hammer_store(&path, 25, "parent");
+        for mut child in children {
+            assert!(child.wait().unwrap().success());
+        }
+
+        let counter: Counter = LockedJsonStore::new(&path).load::<ConfigError>().unwrap();
+        assert_eq!(counter.value, 5 * 25);
+        assert_eq!(counter.writers.len(), 5);
+    }
 }
@@ -0,0 +1,603 @@
+//! Persistent operation history (audit log) of backups and restores.
+//!
+//! This module provides:
+//! - An append-only `history.jsonl` log in the config directory, one record per line
+//! - Size-capped rotation (the previous log is kept as `history.1.jsonl`)
+//! - Filtered, paginated queries over the log
+//!
+//! Corrupt lines (e.g., a half-written record after a crash) are skipped on read
+//! and reported through `HistoryPage::skipped_lines`.
+//!
+//! Error policy: mutating operations always propagate their errors. Only functions
+//! named `*_best_effort` may downgrade an error, and only for follow-up work after the
+//! primary operation succeeded; they report the suppressed error through
+//! `record_suppressed_error` instead of discarding it.
+
+use crate::backup::{BackupResult, BackupResultT};
+use crate::config as config_module;
+use crate::config::ConfigError;
+use crate::file_ops::FileOpsError;
+use crate::i18n;
+use crate::keys::SaveKey;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize, Serializer};
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::fs::{self, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+/// History file name.
+const HISTORY_FILE_NAME: &str = "history.jsonl";
+
+/// Rotated history file name.
+const ROTATED_HISTORY_FILE_NAME: &str = "history.1.jsonl";
+
+/// Size at which the history file is rotated (1 MiB).
+const MAX_HISTORY_BYTES: u64 = 1024 * 1024;
+
+/// Type of a recorded operation.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum OperationType {
+    Backup,
+    Restore,
+    Prune,
+    Delete,
+    Import,
+    Export,
+    /// Startup recovery of an operation interrupted by a crash
+    Recovery,
+    /// Delivery of a webhook notification
+    Webhook,
+}
+
+/// What started an operation.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum OperationTrigger {
+    /// Started by the user
+    Manual,
+    /// Started by a schedule
+    Scheduled,
+    /// Started by the application itself (e.g., garbage collection after a backup)
+    System,
+}
+
+/// Outcome of a recorded operation.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum OperationOutcome {
+    Success,
+    Failure,
+    /// Follow-up work failed after the primary operation succeeded; the error was not surfaced
+    Suppressed,
+}
+
+/// A single history record.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HistoryRecord {
+    /// When the operation finished
+    pub timestamp: DateTime<Utc>,
+    /// Operation type
+    pub operation: OperationType,
+    /// Relative path of the save
+    pub save_name: String,
+    /// Backup involved, if any
+    pub backup_name: Option<String>,
+    /// What started the operation
+    pub trigger: OperationTrigger,
+    /// Duration in milliseconds
+    pub duration_ms: u64,
+    /// Bytes written or read, if known
+    pub bytes: Option<u64>,
+    /// Outcome
+    pub outcome: OperationOutcome,
+    /// Error or informational message
+    pub message: Option<String>,
+}
+
+impl HistoryRecord {
+    /// Creates a successful record with no duration; use the `with_*` methods to fill in details.
+    pub fn new(operation: OperationType, save_name: &str, trigger: OperationTrigger) -> Self {
+        HistoryRecord {
+            timestamp: Utc::now(),
+            operation,
+            save_name: save_name.to_string(),
+            backup_name: None,
+            trigger,
+            duration_ms: 0,
+            bytes: None,
+            outcome: OperationOutcome::Success,
+            message: None,
+        }
+    }
+
+    /// Sets the backup name.
+    pub fn with_backup(mut self, backup_name: &str) -> Self {
+        self.backup_name = Some(backup_name.to_string());
+        self
+    }
+
+    /// Sets the number of bytes involved.
+    pub fn with_bytes(mut self, bytes: Option<u64>) -> Self {
+        self.bytes = bytes;
+        self
+    }
+
+    /// Sets the message.
+    pub fn with_message(mut self, message: String) -> Self {
+        self.message = Some(message);
+        self
+    }
+
+    /// Completes the record with the elapsed time and the operation's result.
+    pub fn finish<T, E: Display>(mut self, started: Instant, result: &Result<T, E>) -> Self {
+        self.timestamp = Utc::now();
+        self.duration_ms = started.elapsed().as_millis() as u64;
+        if let Err(e) = result {
+            self.outcome = OperationOutcome::Failure;
+            self.message = Some(e.to_string());
+        }
+        self
+    }
+}
+
+/// Filter for history queries. All fields are optional; unset fields match everything.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HistoryFilter {
+    /// Only records for this save
+    #[serde(default)]
+    pub save_name: Option<String>,
+    /// Only records of this operation type
+    #[serde(default)]
+    pub operation: Option<OperationType>,
+    /// Only records with this outcome
+    #[serde(default)]
+    pub outcome: Option<OperationOutcome>,
+    /// Only records at or after this time
+    #[serde(default)]
+    pub from: Option<DateTime<Utc>>,
+    /// Only records at or before this time
+    #[serde(default)]
+    pub to: Option<DateTime<Utc>>,
+}
+
+impl HistoryFilter {
+    /// Returns true if the record passes the filter.
+    pub fn matches(&self, record: &HistoryRecord) -> bool {
+        self.save_name.as_ref().is_none_or(|s| *s == record.save_name)
+            && self.operation.is_none_or(|o| o == record.operation)
+            && self.outcome.is_none_or(|o| o == record.outcome)
+            && self.from.is_none_or(|from| record.timestamp >= from)
+            && self.to.is_none_or(|to| record.timestamp <= to)
+    }
+}
+
+/// A page of history records.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HistoryPage {
+    /// Matching records, newest first
+    pub records: Vec<HistoryRecord>,
+    /// Total number of matching records (before pagination)
+    pub total: usize,
+    /// Number of corrupt lines skipped while reading
+    pub skipped_lines: usize,
+}
+
+/// Error type for history operations.
+#[derive(Debug)]
+pub enum HistoryError {
+    /// File operation error
+    FileOp(FileOpsError),
+    /// Config error
+    Config(ConfigError),
+    /// JSON serialization error
+    Json(serde_json::Error),
+}
+
+impl From<FileOpsError> for HistoryError {
+    fn from(err: FileOpsError) -> Self {
+        HistoryError::FileOp(err)
+    }
+}
+
+impl From<std::io::Error> for HistoryError {
+    fn from(err: std::io::Error) -> Self {
+        HistoryError::FileOp(FileOpsError::Io(err))
+    }
+}
+
+impl From<ConfigError> for HistoryError {
+    fn from(err: ConfigError) -> Self {
+        HistoryError::Config(err)
+    }
+}
+
+impl From<serde_json::Error> for HistoryError {
+    fn from(err: serde_json::Error) -> Self {
+        HistoryError::Json(err)
+    }
+}
+
+impl std::fmt::Display for HistoryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HistoryError::FileOp(err) => write!(f, "File operation error: {}", err),
+            HistoryError::Config(err) => write!(f, "Config error: {}", err),
+            HistoryError::Json(err) => write!(f, "JSON error: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for HistoryError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            HistoryError::FileOp(err) => Some(err),
+            HistoryError::Config(err) => Some(err),
+            HistoryError::Json(err) => Some(err),
+        }
+    }
+}
+
+impl Serialize for HistoryError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+/// Result type for history operations.
+pub type HistoryResult<T> = Result<T, HistoryError>;
+
+/// Returns the path to the history file.
+pub fn get_history_file_path() -> HistoryResult<PathBuf> {
+    Ok(config_module::get_config_dir()?.join(HISTORY_FILE_NAME))
+}
+
+/// Appends a record to the history log.
+///
+/// # Arguments
+/// * `record` - Record to append
+///
+/// # Returns
+/// `HistoryResult<()>` - Ok(()) on success
+///
+/// # Behavior
+/// - Rotates the log to `history.1.jsonl` once it exceeds 1 MiB
+/// - Flushes after every record
+pub fn append_record(record: &HistoryRecord) -> HistoryResult<()> {
+    append_record_to(&get_history_file_path()?, record, MAX_HISTORY_BYTES)
+}
+
+/// Appends a record, logging failures to stderr.
+///
+/// History is informational; a failure to write it must never fail the operation itself.
+/// As the history is the sink for suppressed errors, stderr is the only place left to report it.
+pub fn append_record_best_effort(record: &HistoryRecord) {
+    if let Err(e) = append_record(record) {
+        eprintln!("Failed to write history record: {}", e);
+    }
+}
+
+/// Records an error suppressed by a `*_best_effort` wrapper.
+///
+/// # Arguments
+/// * `operation` - Primary operation the failed follow-up work belongs to
+/// * `save_name` - Relative path of the save
+/// * `backup_name` - Backup involved, if any
+/// * `context` - What failed (e.g., "Failed to remove tags of deleted backup")
+/// * `error` - Suppressed error
+///
+/// # Behavior
+/// Logs the error to stderr and appends a `Suppressed` record with a `System` trigger,
+/// so suppressions can be counted with `HistoryFilter::outcome`.
+pub fn record_suppressed_error(
+    operation: OperationType,
+    save_name: &str,
+    backup_name: Option<&str>,
+    context: &str,
+    error: &dyn Display,
+) {
+    let message = format!("{}: {}", context, error);
+    eprintln!("{} ({})", message, save_name);
+
+    let mut record = HistoryRecord::new(operation, save_name, OperationTrigger::System).with_message(message);
+    if let Some(backup_name) = backup_name {
+        record = record.with_backup(backup_name);
+    }
+    record.outcome = OperationOutcome::Suppressed;
+    append_record_best_effort(&record);
+}
+
+/// Records a backup creation, plus a prune record if garbage collection removed backups.
+pub fn record_backup(
+    save_name: &str,
+    trigger: OperationTrigger,
+    started: Instant,
+    result: &BackupResultT<BackupResult>,
+) {
+    let mut record = HistoryRecord::new(OperationType::Backup, save_name, trigger);
+    if let Ok(backup) = result {
+        record = record
+            .with_backup(&backup.backup_name)
+            .with_bytes(Some(backup.size_bytes));
+    }
+    append_record_best_effort(&record.finish(started, result));
+
+    if let Ok(backup) = result {
+        if backup.deleted_count > 0 {
+            let prune = HistoryRecord::new(OperationType::Prune, save_name, OperationTrigger::System)
+                .with_message(i18n::t(
+                    i18n::keys::HISTORY_PRUNE_SUMMARY,
+                    &[("count", &backup.deleted_count.to_string())],
+                ));
+            append_record_best_effort(&prune);
+        }
+    }
+}
+
+/// Queries the history log.
+///
+/// # Arguments
+/// * `filter` - Record filter
+/// * `offset` - Number of matching records to skip (newest first)
+/// * `limit` - Maximum number of records to return
+///
+/// # Returns
+/// `HistoryResult<HistoryPage>` - Matching records, newest first
+pub fn get_operation_history(
+    filter: &HistoryFilter,
+    offset: usize,
+    limit: usize,
+) -> HistoryResult<HistoryPage> {
+    let (records, skipped_lines) = read_history(&get_history_file_path()?)?;
+
+    let matching: Vec<HistoryRecord> = records
+        .into_iter()
+        .rev()
+        .filter(|r| filter.matches(r))
+        .collect();
+    let total = matching.len();
+
+    Ok(HistoryPage {
+        records: matching.into_iter().skip(offset).take(limit).collect(),
+        total,
+        skipped_lines,
+    })
+}
+
+/// Returns the timestamp of the last successful restore for every save.
+///
+/// # Returns
+/// `HistoryResult<HashMap<String, String>>` - Map of canonical save key to ISO 8601 timestamp
+pub fn get_last_restores() -> HistoryResult<HashMap<String, String>> {
+    let (records, _) = read_history(&get_history_file_path()?)?;
+
+    let mut last_restores = HashMap::new();
+    for record in records.into_iter().filter(|r| {
+        r.operation == OperationType::Restore && r.outcome == OperationOutcome::Success
+    }) {
+        last_restores.insert(
+            SaveKey::new(&record.save_name).into(),
+            record.timestamp.to_rfc3339(),
+        );
+    }
+
+    Ok(last_restores)
+}
+
+/// Appends a record to a specific history file, rotating it if it grew too large.
+fn append_record_to(path: &Path, record: &HistoryRecord, max_bytes: u64) -> HistoryResult<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let line = serde_json::to_string(record)?;
+
+    if let Ok(metadata) = fs::metadata(path) {
+        if metadata.len() > 0 && metadata.len() + line.len() as u64 + 1 > max_bytes {
+            fs::rename(path, rotated_path(path))?;
+        }
+    }
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .read(true)
+        .append(true)
+        .open(path)?;
+
+    // A crash may have left a partial line; terminate it so the new record stays parseable
+    let needs_newline = ends_without_newline(&mut file)?;
+
+    let mut writer = BufWriter::new(file);
+    if needs_newline {
+        writer.write_all(b"\n")?;
+    }
+    writer.write_all(line.as_bytes())?;
+    writer.write_all(b"\n")?;
+    writer.flush()?;
+
+    Ok(())
+}
+
+/// Returns true if the file is non-empty and its last byte isn't a newline.
+fn ends_without_newline(file: &mut fs::File) -> std::io::Result<bool> {
+    let len = file.metadata()?.len();
+    if len == 0 {
+        return Ok(false);
+    }
+
+    file.seek(SeekFrom::End(-1))?;
+    let mut last = [0u8; 1];
+    file.read_exact(&mut last)?;
+    Ok(last[0] != b'\n')
+}
+
+/// Reads all records (oldest first) from the rotated and current history files.
+///
+/// Returns the records and the number of corrupt lines that were skipped.
+fn read_history(path: &Path) -> HistoryResult<(Vec<HistoryRecord>, usize)> {
+    let mut records = Vec::new();
+    let mut skipped = 0;
+
+    for file_path in [rotated_path(path), path.to_path_buf()] {
+        if !file_path.exists() {
+            continue;
+        }
+
+        let reader = BufReader::new(fs::File::open(&file_path)?);
+        for line in reader.split(b'\n') {
+            let line = line?;
+            if line.iter().all(|b| b.is_ascii_whitespace()) {
+                continue;
+            }
+            match serde_json::from_slice::<HistoryRecord>(&line) {
+                Ok(record) => records.push(record),
+                Err(_) => skipped += 1,
+            }
+        }
+    }
+
+    Ok((records, skipped))
+}
+
+/// Returns the rotated file path next to a history file.
+fn rotated_path(path: &Path) -> PathBuf {
+    path.with_file_name(ROTATED_HISTORY_FILE_NAME)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use tempfile::TempDir;
+
+    fn record_at(
+        hour: u32,
+        operation: OperationType,
+        save_name: &str,
+        outcome: OperationOutcome,
+    ) -> HistoryRecord {
+        let mut record = HistoryRecord::new(operation, save_name, OperationTrigger::Manual);
+        record.timestamp = Utc.with_ymd_and_hms(2024, 12, 28, hour, 0, 0).unwrap();
+        record.outcome = outcome;
+        record
+    }
+
+    fn write_sample_history(path: &Path) {
+        let records = [
+            record_at(9, OperationType::Backup, "Survival/A", OperationOutcome::Success),
+            record_at(10, OperationType::Restore, "Survival/A", OperationOutcome::Success),
+            record_at(11, OperationType::Backup, "Survival/B", OperationOutcome::Failure),
+            record_at(12, OperationType::Delete, "Survival/A", OperationOutcome::Success),
+        ];
+        for record in &records {
+            append_record_to(path, record, MAX_HISTORY_BYTES).unwrap();
+        }
+    }
+
+    fn filtered(path: &Path, filter: &HistoryFilter) -> Vec<HistoryRecord> {
+        let (records, _) = read_history(path).unwrap();
+        records.into_iter().rev().filter(|r| filter.matches(r)).collect()
+    }
+
+    #[test]
+    fn test_append_and_read_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join(HISTORY_FILE_NAME);
+
+        let record = HistoryRecord::new(OperationType::Backup, "Survival/A", OperationTrigger::Manual)
+            .with_backup("2024-12-28_14-30-45.tar.gz")
+            .with_bytes(Some(1024))
+            .finish(Instant::now(), &Ok::<(), String>(()));
+        append_record_to(&path, &record, MAX_HISTORY_BYTES).unwrap();
+
+        let (records, skipped) = read_history(&path).unwrap();
+        assert_eq!(records, vec![record]);
+        assert_eq!(skipped, 0);
+    }
+
+    #[test]
+    fn test_filter_by_save_type_outcome_and_dates() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join(HISTORY_FILE_NAME);
+        write_sample_history(&path);
+
+        let by_save = HistoryFilter {
+            save_name: Some("Survival/A".to_string()),
+            ..Default::default()
+        };
+        let records = filtered(&path, &by_save);
+        assert_eq!(records.len(), 3);
+        // Newest first
+        assert_eq!(records[0].operation, OperationType::Delete);
+
+        let by_type = HistoryFilter {
+            operation: Some(OperationType::Backup),
+            ..Default::default()
+        };
+        assert_eq!(filtered(&path, &by_type).len(), 2);
+
+        let by_outcome = HistoryFilter {
+            outcome: Some(OperationOutcome::Failure),
+            ..Default::default()
+        };
+        let records = filtered(&path, &by_outcome);
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].save_name, "Survival/B");
+
+        let by_dates = HistoryFilter {
+            from: Some(Utc.with_ymd_and_hms(2024, 12, 28, 10, 0, 0).unwrap()),
+            to: Some(Utc.with_ymd_and_hms(2024, 12, 28, 11, 0, 0).unwrap()),
+            ..Default::default()
+        };
+        let records = filtered(&path, &by_dates);
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].operation, OperationType::Backup);
+        assert_eq!(records[1].operation, OperationType::Restore);
+    }
+
+    #[test]
+    fn test_corrupt_tail_is_skipped() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join(HISTORY_FILE_NAME);
+        write_sample_history(&path);
+
+        // Simulate a crash in the middle of writing a record
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(br#"{"timestamp":"2024-12-28T13:00:00Z","operation":"Bac"#).unwrap();
+        drop(file);
+
+        let (records, skipped) = read_history(&path).unwrap();
+        assert_eq!(records.len(), 4);
+        assert_eq!(skipped, 1);
+
+        // The next record must not be glued onto the partial line
+        let record = record_at(14, OperationType::Backup, "Survival/A", OperationOutcome::Success);
+        append_record_to(&path, &record, MAX_HISTORY_BYTES).unwrap();
+
+        let (records, skipped) = read_history(&path).unwrap();
+        assert_eq!(records.len(), 5);
+        assert_eq!(records.last(), Some(&record));
+        assert_eq!(skipped, 1);
+    }
+
+    #[test]
+    fn test_rotation_keeps_previous_log() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join(HISTORY_FILE_NAME);
+
+        // A tiny cap forces a rotation on every append after the first
+        for hour in 0..3 {
+            let record = record_at(hour, OperationType::Backup, "Survival/A", OperationOutcome::Success);
+            append_record_to(&path, &record, 16).unwrap();
+        }
+
+        assert!(rotated_path(&path).exists());
+        let (records, _) = read_history(&path).unwrap();
+        // Only the current and the previous log are kept
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[1].timestamp.format("%H").to_string(), "02");
+    }
+}
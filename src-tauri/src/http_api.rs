@@ -0,0 +1,586 @@
+//! Optional HTTP API for automation (e.g., a server dashboard or scripts on the LAN).
+//!
+//! Off by default. When `http_api_enabled` is set, the app listens on
+//! `http_api_bind` and serves:
+//! - `GET /status` - Dashboard summary
+//! - `GET /saves` - Save entries
+//! - `GET /saves/{save}/backups` - Backup listing of a save
+//! - `POST /saves/{save}/backup` - Creates a backup of a save
+//! - `POST /prune` - Applies the retention policy (`?save=<save>` for one save,
+//!   `?dry_run=true` to only report)
+//!
+//! `{save}` is the save's relative path, with its slash percent-encoded or not
+//! (`Survival%2FMySave` or `Survival/MySave`).
+//!
+//! Every request needs an `Authorization: Bearer <http_api_token>` header; the API
+//! refuses to start without a token. Responses use the JSON shapes the Tauri
+//! commands return, errors are an `ErrorPayload`. Backups and prunes run on the
+//! operation queue, like the ones started from the UI.
+//!
+//! Connections carry a single request each (`Connection: close`).
+
+use crate::backup::{self, BackupError};
+use crate::config;
+use crate::dashboard::{self, DashboardState};
+use crate::errors::{ErrorCode, ErrorPayload};
+use crate::file_ops;
+use crate::history::OperationTrigger;
+use crate::operations::{self, OperationQueue};
+use crate::tasks::TaskRegistry;
+use serde::Serialize;
+use std::io;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+/// Default address of the HTTP API.
+pub const DEFAULT_BIND_ADDRESS: &str = "127.0.0.1:8731";
+
+/// Maximum size of a request line and headers.
+const MAX_HEAD_BYTES: usize = 16 * 1024;
+
+/// Maximum request body; endpoints take no body, it is read and discarded.
+const MAX_BODY_BYTES: usize = 64 * 1024;
+
+/// Time a client has to send its request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Connections served at once; further connections wait to be accepted.
+const MAX_CONNECTIONS: usize = 16;
+
+/// State the API operates on, provided by the running app.
+///
+/// Implemented for the Tauri app handle, so requests share the dashboard, task
+/// registry and operation queue with the UI.
+pub trait ApiContext: Send + Sync + 'static {
+    /// Dashboard state to read and update.
+    fn dashboard(&self) -> &DashboardState;
+    /// Task registry operations register with.
+    fn tasks(&self) -> &TaskRegistry;
+    /// Queue heavy operations run on.
+    fn queue(&self) -> &OperationQueue;
+}
+
+/// Error type for starting the HTTP API.
+#[derive(Debug)]
+pub enum HttpApiError {
+    /// No token is configured
+    MissingToken,
+    /// Bind address isn't a valid socket address
+    InvalidBindAddress(String),
+    /// Binding the address failed
+    Bind(io::Error),
+}
+
+impl std::fmt::Display for HttpApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HttpApiError::MissingToken => {
+                write!(f, "HTTP API is enabled but no http_api_token is configured")
+            }
+            HttpApiError::InvalidBindAddress(addr) => write!(f, "Invalid HTTP API bind address: {}", addr),
+            HttpApiError::Bind(e) => write!(f, "Failed to bind HTTP API: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for HttpApiError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            HttpApiError::Bind(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// A bound, not yet serving API listener.
+#[derive(Debug)]
+pub struct HttpApi {
+    listener: TcpListener,
+    token: String,
+}
+
+impl HttpApi {
+    /// Binds the API listener.
+    ///
+    /// # Arguments
+    /// * `bind_address` - Socket address to listen on (e.g., "0.0.0.0:8731")
+    /// * `token` - Bearer token requests must present
+    ///
+    /// # Returns
+    /// `Result<HttpApi, HttpApiError>` - Bound listener, or `MissingToken` if
+    /// `token` is empty
+    pub async fn bind(bind_address: &str, token: &str) -> Result<Self, HttpApiError> {
+        let token = token.trim();
+        if token.is_empty() {
+            return Err(HttpApiError::MissingToken);
+        }
+        let addr: SocketAddr = bind_address
+            .trim()
+            .parse()
+            .map_err(|_| HttpApiError::InvalidBindAddress(bind_address.to_string()))?;
+        let listener = TcpListener::bind(addr).await.map_err(HttpApiError::Bind)?;
+        Ok(HttpApi {
+            listener,
+            token: token.to_string(),
+        })
+    }
+
+    /// Returns the address the API listens on.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    /// Serves requests until the runtime shuts down.
+    ///
+    /// # Arguments
+    /// * `context` - State requests operate on
+    ///
+    /// # Behavior
+    /// Every connection is handled on its own task, so a long backup doesn't hold
+    /// up status requests; concurrent operations wait on the operation queue. At most
+    /// `MAX_CONNECTIONS` are served at once; the next is accepted when one closes.
+    pub async fn serve<C: ApiContext>(self, context: Arc<C>) {
+        let token: Arc<str> = Arc::from(self.token);
+        let connections = Arc::new(tokio::sync::Semaphore::new(MAX_CONNECTIONS));
+        loop {
+            let Ok(permit) = Arc::clone(&connections).acquire_owned().await else {
+                return;
+            };
+            let Ok((stream, _)) = self.listener.accept().await else {
+                continue;
+            };
+            let (context, token) = (Arc::clone(&context), Arc::clone(&token));
+            tokio::spawn(async move {
+                serve_connection(stream, &token, context.as_ref()).await;
+                drop(permit);
+            });
+        }
+    }
+}
+
+/// Parsed request line and the headers the API uses.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Request {
+    method: String,
+    path: String,
+    query: String,
+    authorization: Option<String>,
+    content_length: usize,
+}
+
+/// JSON response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Response {
+    status: u16,
+    body: String,
+}
+
+impl Response {
+    fn json<T: Serialize + ?Sized>(value: &T) -> Self {
+        match serde_json::to_string(value) {
+            Ok(body) => Response { status: 200, body },
+            Err(e) => Response::error(500, ErrorPayload::new(ErrorCode::Internal, e.to_string())),
+        }
+    }
+
+    fn error(status: u16, payload: ErrorPayload) -> Self {
+        Response {
+            status,
+            body: serde_json::to_string(&payload).unwrap_or_default(),
+        }
+    }
+
+    /// Maps a failed operation to a status matching its error code.
+    fn from_payload(payload: ErrorPayload) -> Self {
+        let status = match payload.code {
+            ErrorCode::SaveNotFound | ErrorCode::BackupNotFound => 404,
+            ErrorCode::InvalidBackupName | ErrorCode::InvalidSaveName | ErrorCode::InvalidValue => 400,
+            ErrorCode::SaveBusy | ErrorCode::StoreBusy | ErrorCode::GameRunning => 409,
+            _ => 500,
+        };
+        Response::error(status, payload)
+    }
+
+    fn to_http(&self) -> String {
+        format!(
+            "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            self.status,
+            reason_phrase(self.status),
+            self.body.len(),
+            self.body
+        )
+    }
+}
+
+fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        409 => "Conflict",
+        413 => "Payload Too Large",
+        431 => "Request Header Fields Too Large",
+        _ => "Internal Server Error",
+    }
+}
+
+/// Reads one request, handles it and writes the response.
+async fn serve_connection<S, C>(mut stream: S, token: &str, context: &C)
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+    C: ApiContext,
+{
+    let response = match tokio::time::timeout(REQUEST_TIMEOUT, read_request(&mut stream)).await {
+        Ok(Ok(request)) => handle_request(&request, token, context).await,
+        Ok(Err(response)) => response,
+        Err(_) => return,
+    };
+    let _ = stream.write_all(response.to_http().as_bytes()).await;
+    let _ = stream.shutdown().await;
+}
+
+/// Reads the request head and discards the body.
+///
+/// Malformed requests are returned as the error response to send.
+async fn read_request<S: AsyncRead + Unpin>(stream: &mut S) -> Result<Request, Response> {
+    let bad_request = |message: &str| Response::error(400, ErrorPayload::new(ErrorCode::InvalidValue, message));
+
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 1024];
+    let head_end = loop {
+        if let Some(pos) = buffer.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos;
+        }
+        if buffer.len() > MAX_HEAD_BYTES {
+            return Err(Response::error(
+                431,
+                ErrorPayload::new(ErrorCode::InvalidValue, "Request headers are too large"),
+            ));
+        }
+        match stream.read(&mut chunk).await {
+            Ok(0) | Err(_) => return Err(bad_request("Incomplete request")),
+            Ok(n) => buffer.extend_from_slice(&chunk[..n]),
+        }
+    };
+
+    let head = std::str::from_utf8(&buffer[..head_end]).map_err(|_| bad_request("Malformed request"))?;
+    let request = parse_head(head).ok_or_else(|| bad_request("Malformed request"))?;
+    if request.content_length > MAX_BODY_BYTES {
+        return Err(Response::error(
+            413,
+            ErrorPayload::new(ErrorCode::InvalidValue, "Request body is too large"),
+        ));
+    }
+
+    // Drain the body so closing the connection doesn't reset it before the response
+    let mut remaining = request.content_length.saturating_sub(buffer.len() - head_end - 4);
+    while remaining > 0 {
+        match stream.read(&mut chunk).await {
+            Ok(0) | Err(_) => break,
+            Ok(n) => remaining = remaining.saturating_sub(n),
+        }
+    }
+    Ok(request)
+}
+
+/// Parses the request line and headers.
+fn parse_head(head: &str) -> Option<Request> {
+    let mut lines = head.split("\r\n");
+    let mut request_line = lines.next()?.split(' ');
+    let method = request_line.next()?.to_string();
+    let target = request_line.next()?;
+    if !request_line.next()?.starts_with("HTTP/1.") {
+        return None;
+    }
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+
+    let mut request = Request {
+        method,
+        path: path.to_string(),
+        query: query.to_string(),
+        authorization: None,
+        content_length: 0,
+    };
+    for line in lines {
+        let (name, value) = line.split_once(':')?;
+        let value = value.trim();
+        if name.eq_ignore_ascii_case("authorization") {
+            request.authorization = Some(value.to_string());
+        } else if name.eq_ignore_ascii_case("content-length") {
+            request.content_length = value.parse().ok()?;
+        }
+    }
+    Some(request)
+}
+
+/// Returns true if the `Authorization` header carries the token.
+fn is_authorized(authorization: Option<&str>, token: &str) -> bool {
+    let Some(presented) = authorization.and_then(|value| {
+        let (scheme, credentials) = value.split_once(' ')?;
+        scheme.eq_ignore_ascii_case("bearer").then(|| credentials.trim())
+    }) else {
+        return false;
+    };
+    // Compare every byte so the time taken doesn't reveal a matching prefix
+    presented.len() == token.len()
+        && presented
+            .bytes()
+            .zip(token.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// Endpoint a request path resolves to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Route {
+    Status,
+    Saves,
+    SaveBackups(String),
+    CreateBackup(String),
+    Prune,
+}
+
+/// Resolves a method and path to an endpoint.
+///
+/// # Returns
+/// `Result<Route, Response>` - Route, or the 404/405 response to send; 400 for a save
+/// name that would leave the save directory (see `file_ops::validate_save_name`)
+fn route(method: &str, path: &str) -> Result<Route, Response> {
+    let not_found = || {
+        Response::error(
+            404,
+            ErrorPayload::new(ErrorCode::InvalidValue, format!("Unknown endpoint: {}", path)),
+        )
+    };
+    let save_route = |suffix: &str| {
+        path.strip_prefix("/saves/")
+            .and_then(|rest| rest.strip_suffix(suffix))
+            .map(percent_decode)
+            .filter(|save| !save.is_empty())
+    };
+
+    let (route, expected_method) = if path == "/status" {
+        (Route::Status, "GET")
+    } else if path == "/saves" {
+        (Route::Saves, "GET")
+    } else if path == "/prune" {
+        (Route::Prune, "POST")
+    } else if let Some(save) = save_route("/backups") {
+        (Route::SaveBackups(save), "GET")
+    } else if let Some(save) = save_route("/backup") {
+        (Route::CreateBackup(save), "POST")
+    } else {
+        return Err(not_found());
+    };
+
+    if method != expected_method {
+        return Err(Response::error(
+            405,
+            ErrorPayload::new(
+                ErrorCode::InvalidValue,
+                format!("{} requires {}", path, expected_method),
+            ),
+        ));
+    }
+    if let Route::SaveBackups(save) | Route::CreateBackup(save) = &route {
+        file_ops::validate_save_name(save).map_err(|e| Response::from_payload(ErrorPayload::from(e)))?;
+    }
+    Ok(route)
+}
+
+/// Decodes `%XX` escapes in a path or query value.
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = (bytes[i] == b'%')
+            .then(|| value.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Returns the decoded value of a query parameter.
+fn query_param(query: &str, name: &str) -> Option<String> {
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('=').or(Some((pair, ""))))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| percent_decode(value))
+}
+
+/// Authorizes and dispatches a request.
+async fn handle_request<C: ApiContext>(request: &Request, token: &str, context: &C) -> Response {
+    if !is_authorized(request.authorization.as_deref(), token) {
+        return Response::error(
+            401,
+            ErrorPayload::new(ErrorCode::Unauthorized, "Missing or invalid bearer token"),
+        );
+    }
+    let route = match route(&request.method, &request.path) {
+        Ok(route) => route,
+        Err(response) => return response,
+    };
+
+    let result = match route {
+        Route::Status => Ok(Response::json(&dashboard::get_dashboard_summary(context.dashboard()))),
+        Route::Saves => file_ops::run_blocking(config::list_save_entries)
+            .await
+            .map_err(ErrorPayload::from)
+            .map(|entries| {
+                context.dashboard().record_save_count(entries.len());
+                Response::json(&entries)
+            }),
+        Route::SaveBackups(save) => {
+            let listing = {
+                let save = save.clone();
+                file_ops::run_blocking(move || backup::list_backups_cached(&save)).await
+            };
+            listing.map_err(ErrorPayload::from).map(|listing| {
+                context.dashboard().record_backup_listing(&save, &listing.backups);
+                Response::json(&listing)
+            })
+        }
+        Route::CreateBackup(save) => operations::run_backup(
+            &save,
+            OperationTrigger::Manual,
+            context.dashboard(),
+            context.tasks(),
+            context.queue(),
+        )
+        .await
+        .map(|result| Response::json(&result))
+        .map_err(ErrorPayload::from),
+        Route::Prune => {
+            let save = query_param(&request.query, "save").filter(|s| !s.is_empty());
+            let dry_run = query_param(&request.query, "dry_run").is_some_and(|v| v == "true" || v == "1");
+            context
+                .queue()
+                .run(move || prune(save, dry_run))
+                .await
+                .map(|results| Response::json(&results))
+                .map_err(ErrorPayload::from)
+        }
+    };
+    result.unwrap_or_else(Response::from_payload)
+}
+
+/// Prunes one save, or every save with backups.
+fn prune(save: Option<String>, dry_run: bool) -> Result<Vec<backup::PruneResult>, BackupError> {
+    let saves = match save {
+        Some(save) => vec![save],
+        None => backup::list_backed_up_saves()?,
+    };
+    saves
+        .iter()
+        .map(|save| operations::run_prune(save, dry_run, OperationTrigger::Manual))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_head() {
+        let request = parse_head(
+            "POST /prune?dry_run=true HTTP/1.1\r\nHost: nas\r\nauthorization: Bearer abc\r\nContent-Length: 2",
+        )
+        .unwrap();
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/prune");
+        assert_eq!(request.query, "dry_run=true");
+        assert_eq!(request.authorization.as_deref(), Some("Bearer abc"));
+        assert_eq!(request.content_length, 2);
+
+        assert!(parse_head("GET /status").is_none());
+        assert!(parse_head("GET /status SPDY/3").is_none());
+        assert!(parse_head("GET /status HTTP/1.1\r\nbroken header").is_none());
+    }
+
+    #[test]
+    fn test_is_authorized() {
+        assert!(is_authorized(Some("Bearer secret"), "secret"));
+        assert!(is_authorized(Some("bearer secret"), "secret"));
+        assert!(!is_authorized(Some("Bearer secre"), "secret"));
+        assert!(!is_authorized(Some("Bearer secrets"), "secret"));
+        assert!(!is_authorized(Some("Basic secret"), "secret"));
+        assert!(!is_authorized(None, "secret"));
+    }
+
+    #[test]
+    fn test_route() {
+        assert_eq!(route("GET", "/status").unwrap(), Route::Status);
+        assert_eq!(route("GET", "/saves").unwrap(), Route::Saves);
+        assert_eq!(
+            route("GET", "/saves/Survival%2FMy%20Save/backups").unwrap(),
+            Route::SaveBackups("Survival/My Save".to_string())
+        );
+        assert_eq!(
+            route("POST", "/saves/Survival/MySave/backup").unwrap(),
+            Route::CreateBackup("Survival/MySave".to_string())
+        );
+        assert_eq!(route("POST", "/prune").unwrap(), Route::Prune);
+
+        assert_eq!(route("POST", "/status").unwrap_err().status, 405);
+        assert_eq!(route("GET", "/saves/Survival/MySave/backup").unwrap_err().status, 405);
+        assert_eq!(route("GET", "/saves//backups").unwrap_err().status, 404);
+        // Decoded names may not leave the save directory
+        assert_eq!(route("POST", "/saves/..%2F..%2Fsomewhere/backup").unwrap_err().status, 400);
+        assert_eq!(route("GET", "/saves/Survival/../../x/backups").unwrap_err().status, 400);
+        assert_eq!(route("GET", "/saves/%2Fetc/backups").unwrap_err().status, 400);
+        assert_eq!(route("GET", "/nope").unwrap_err().status, 404);
+    }
+
+    #[test]
+    fn test_query_param() {
+        assert_eq!(query_param("save=Survival%2FA&dry_run=true", "save").as_deref(), Some("Survival/A"));
+        assert_eq!(query_param("save=Survival%2FA&dry_run=true", "dry_run").as_deref(), Some("true"));
+        assert_eq!(query_param("dry_run", "dry_run").as_deref(), Some(""));
+        assert_eq!(query_param("", "save"), None);
+        assert_eq!(percent_decode("100%"), "100%");
+    }
+
+    #[test]
+    fn test_error_status() {
+        let status = |code| Response::from_payload(ErrorPayload::new(code, "x")).status;
+        assert_eq!(status(ErrorCode::SaveNotFound), 404);
+        assert_eq!(status(ErrorCode::InvalidBackupName), 400);
+        assert_eq!(status(ErrorCode::GameRunning), 409);
+        assert_eq!(status(ErrorCode::Io), 500);
+    }
+
+    #[test]
+    fn test_bind_requires_token() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        runtime.block_on(async {
+            assert!(matches!(HttpApi::bind("127.0.0.1:0", "  ").await, Err(HttpApiError::MissingToken)));
+            assert!(matches!(
+                HttpApi::bind("localhost", "secret").await,
+                Err(HttpApiError::InvalidBindAddress(_))
+            ));
+            let api = HttpApi::bind("127.0.0.1:0", "secret").await.unwrap();
+            assert_ne!(api.local_addr().unwrap().port(), 0);
+        });
+    }
+}
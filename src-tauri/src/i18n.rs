@@ -0,0 +1,268 @@
+//! Localization of user-facing text composed in the backend.
+//!
+//! This module provides:
+//! - Message key constants (`keys`), referenced by code instead of string literals
+//! - Embedded key → template catalogs for each supported locale (`locales/*.json`)
+//! - Template rendering with `{name}` placeholders and per-message fallback to English
+//!
+//! Only display text goes through this module; error codes and log files stay in English.
+
+use crate::config as config_module;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// Message keys. Every key must exist in every locale catalog.
+pub mod keys {
+    /// Prune record added to the history after a backup; args: `count`
+    pub const HISTORY_PRUNE_SUMMARY: &str = "history.prune_summary";
+
+    /// Recovery record for an interrupted operation that was finished; args: `operation`
+    pub const HISTORY_RECOVERY_ROLLED_FORWARD: &str = "history.recovery_rolled_forward";
+
+    /// Recovery record for an interrupted operation that was undone; args: `operation`
+    pub const HISTORY_RECOVERY_ROLLED_BACK: &str = "history.recovery_rolled_back";
+
+    /// Recovery record for an interrupted operation that couldn't be recovered; args: `operation`, `error`
+    pub const HISTORY_RECOVERY_FAILED: &str = "history.recovery_failed";
+
+    /// Webhook record for a delivered notification; args: `event`, `url`, `attempts`
+    pub const HISTORY_WEBHOOK_DELIVERED: &str = "history.webhook_delivered";
+
+    /// Webhook record for a notification that couldn't be delivered; args: `event`, `url`, `attempts`, `error`
+    pub const HISTORY_WEBHOOK_FAILED: &str = "history.webhook_failed";
+
+    /// Discord title of a finished backup; args: `save`
+    pub const DISCORD_BACKUP_FINISHED: &str = "discord.backup_finished";
+
+    /// Discord title of a backup that left locked files out; args: `save`
+    pub const DISCORD_BACKUP_PARTIAL: &str = "discord.backup_partial";
+
+    /// Discord title of a failed backup; args: `save`
+    pub const DISCORD_BACKUP_FAILED: &str = "discord.backup_failed";
+
+    /// Discord title of a prune; args: `save`, `count`
+    pub const DISCORD_PRUNE_COMPLETED: &str = "discord.prune_completed";
+
+    /// Discord title of a low disk space warning
+    pub const DISCORD_DISK_SPACE_LOW: &str = "discord.disk_space_low";
+
+    /// Field names of Discord embeds; no args
+    pub const DISCORD_FIELD_SAVE: &str = "discord.field_save";
+    pub const DISCORD_FIELD_BACKUP: &str = "discord.field_backup";
+    pub const DISCORD_FIELD_SIZE: &str = "discord.field_size";
+    pub const DISCORD_FIELD_DURATION: &str = "discord.field_duration";
+    pub const DISCORD_FIELD_SKIPPED: &str = "discord.field_skipped";
+    pub const DISCORD_FIELD_ERROR: &str = "discord.field_error";
+    pub const DISCORD_FIELD_FREE_SPACE: &str = "discord.field_free_space";
+    pub const DISCORD_FIELD_THRESHOLD: &str = "discord.field_threshold";
+    pub const DISCORD_FIELD_LOCATION: &str = "discord.field_location";
+
+    /// All keys, used to check the catalogs for completeness.
+    pub const ALL: &[&str] = &[
+        HISTORY_PRUNE_SUMMARY,
+        HISTORY_RECOVERY_ROLLED_FORWARD,
+        HISTORY_RECOVERY_ROLLED_BACK,
+        HISTORY_RECOVERY_FAILED,
+        HISTORY_WEBHOOK_DELIVERED,
+        HISTORY_WEBHOOK_FAILED,
+        DISCORD_BACKUP_FINISHED,
+        DISCORD_BACKUP_PARTIAL,
+        DISCORD_BACKUP_FAILED,
+        DISCORD_PRUNE_COMPLETED,
+        DISCORD_DISK_SPACE_LOW,
+        DISCORD_FIELD_SAVE,
+        DISCORD_FIELD_BACKUP,
+        DISCORD_FIELD_SIZE,
+        DISCORD_FIELD_DURATION,
+        DISCORD_FIELD_SKIPPED,
+        DISCORD_FIELD_ERROR,
+        DISCORD_FIELD_FREE_SPACE,
+        DISCORD_FIELD_THRESHOLD,
+        DISCORD_FIELD_LOCATION,
+    ];
+}
+
+/// Supported display language.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum Locale {
+    /// English (fallback for missing messages)
+    #[default]
+    #[serde(rename = "en")]
+    En,
+    /// Simplified Chinese
+    #[serde(rename = "zh-CN")]
+    ZhCn,
+}
+
+impl Locale {
+    /// All supported locales.
+    pub const ALL: [Locale; 2] = [Locale::En, Locale::ZhCn];
+
+    /// Parses a language tag (e.g., "en-US", "zh-CN", "zh_CN", "zh").
+    ///
+    /// # Returns
+    /// `Option<Locale>` - Supported locale, or None for unsupported languages
+    pub fn parse(tag: &str) -> Option<Locale> {
+        let tag = tag.trim().replace('_', "-").to_ascii_lowercase();
+        let language = tag.split('-').next().unwrap_or_default();
+        match language {
+            "en" => Some(Locale::En),
+            // Traditional Chinese variants aren't translated; Simplified reads better than English
+            "zh" => Some(Locale::ZhCn),
+            _ => None,
+        }
+    }
+
+    /// Returns the language tag used in the config and catalog file names.
+    pub fn tag(&self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::ZhCn => "zh-CN",
+        }
+    }
+
+    /// Returns the embedded catalog source.
+    fn source(&self) -> &'static str {
+        match self {
+            Locale::En => include_str!("../locales/en.json"),
+            Locale::ZhCn => include_str!("../locales/zh-CN.json"),
+        }
+    }
+
+    /// Returns the parsed catalog, parsing it on first use.
+    ///
+    /// A malformed catalog is treated as empty so messages fall back to English.
+    fn catalog(&self) -> &'static HashMap<String, String> {
+        static EN: OnceLock<HashMap<String, String>> = OnceLock::new();
+        static ZH_CN: OnceLock<HashMap<String, String>> = OnceLock::new();
+
+        let cell = match self {
+            Locale::En => &EN,
+            Locale::ZhCn => &ZH_CN,
+        };
+        cell.get_or_init(|| serde_json::from_str(self.source()).unwrap_or_default())
+    }
+}
+
+/// Returns the locale selected in the config (English if unset or unsupported).
+pub fn current_locale() -> Locale {
+    config_module::load_config()
+        .ok()
+        .and_then(|config| config.locale)
+        .and_then(|tag| Locale::parse(&tag))
+        .unwrap_or_default()
+}
+
+/// Renders a message in a locale.
+///
+/// # Arguments
+/// * `locale` - Display language
+/// * `key` - Message key from `keys`
+/// * `args` - Placeholder values, e.g. `&[("count", "3")]` for `{count}`
+///
+/// # Returns
+/// `String` - Rendered message; the English template if the locale lacks the key,
+/// or the key itself if no catalog has it
+pub fn translate(locale: Locale, key: &str, args: &[(&str, &str)]) -> String {
+    let template = locale
+        .catalog()
+        .get(key)
+        .or_else(|| Locale::En.catalog().get(key))
+        .map(String::as_str)
+        .unwrap_or(key);
+
+    render(template, args)
+}
+
+/// Renders a message in the locale selected in the config.
+pub fn t(key: &str, args: &[(&str, &str)]) -> String {
+    translate(current_locale(), key, args)
+}
+
+/// Replaces `{name}` placeholders with argument values.
+fn render(template: &str, args: &[(&str, &str)]) -> String {
+    args.iter().fold(template.to_string(), |text, (name, value)| {
+        text.replace(&format!("{{{}}}", name), value)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the placeholder names in a template.
+    fn placeholders(template: &str) -> Vec<String> {
+        let mut names: Vec<String> = template
+            .split('{')
+            .skip(1)
+            .filter_map(|part| part.split_once('}').map(|(name, _)| name.to_string()))
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn test_every_key_renders_in_every_locale() {
+        for locale in Locale::ALL {
+            let catalog = locale.catalog();
+            assert!(!catalog.is_empty(), "catalog {} failed to parse", locale.tag());
+
+            for key in keys::ALL {
+                let template = catalog
+                    .get(*key)
+                    .unwrap_or_else(|| panic!("{} is missing {}", locale.tag(), key));
+                assert_eq!(
+                    placeholders(template),
+                    placeholders(&Locale::En.catalog()[*key]),
+                    "{} uses different placeholders for {}",
+                    locale.tag(),
+                    key
+                );
+
+                let args: Vec<(String, String)> = placeholders(template)
+                    .into_iter()
+                    .map(|name| (name, "1".to_string()))
+                    .collect();
+                let args: Vec<(&str, &str)> =
+                    args.iter().map(|(n, v)| (n.as_str(), v.as_str())).collect();
+                let rendered = translate(locale, key, &args);
+                assert!(!rendered.contains('{'), "{}: {} left a placeholder", locale.tag(), key);
+            }
+        }
+    }
+
+    #[test]
+    fn test_catalogs_have_no_unknown_keys() {
+        for locale in Locale::ALL {
+            for key in locale.catalog().keys() {
+                assert!(keys::ALL.contains(&key.as_str()), "{} has unknown key {}", locale.tag(), key);
+            }
+        }
+    }
+
+    #[test]
+    fn test_translate_and_fallback() {
+        let args = [("count", "3")];
+        assert_eq!(
+            translate(Locale::En, keys::HISTORY_PRUNE_SUMMARY, &args),
+            "Removed 3 old backup(s)"
+        );
+        assert_eq!(
+            translate(Locale::ZhCn, keys::HISTORY_PRUNE_SUMMARY, &args),
+            "已删除 3 个旧备份"
+        );
+
+        // Unknown keys render as the key itself
+        assert_eq!(translate(Locale::ZhCn, "missing.key", &[]), "missing.key");
+    }
+
+    #[test]
+    fn test_parse_locale_tags() {
+        assert_eq!(Locale::parse("en-US"), Some(Locale::En));
+        assert_eq!(Locale::parse("zh-CN"), Some(Locale::ZhCn));
+        assert_eq!(Locale::parse("zh_cn"), Some(Locale::ZhCn));
+        assert_eq!(Locale::parse("zh"), Some(Locale::ZhCn));
+        assert_eq!(Locale::parse("fr"), None);
+    }
+}
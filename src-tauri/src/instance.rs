@@ -0,0 +1,435 @@
+//! Launch arguments and forwarding them to an already running instance.
+//!
+//! This module provides:
+//! - Parsing of launch arguments (`--backup <save>`, `--backup-all`, `--show`, `--silent`)
+//! - A loopback listener in the first instance that receives arguments from later launches
+//! - Forwarding of arguments from a second launch to the running instance
+//!
+//! The running instance publishes its port and a per-session token in `instance.json`
+//! in the config directory; forwarded requests without the token are rejected.
+//! Argument errors are written to `logs/launch.log`, since there may be no console.
+
+use crate::config as config_module;
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::RandomState;
+use std::fs::{self, OpenOptions};
+use std::hash::{BuildHasher, Hasher};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// File in the config directory describing the running instance.
+const INSTANCE_FILE_NAME: &str = "instance.json";
+
+/// Log file (in the log directory) for launch argument errors.
+const LAUNCH_LOG_FILE_NAME: &str = "launch.log";
+
+/// Timeout for connecting to and talking with the running instance.
+const FORWARD_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Maximum size of a forwarded request line.
+const MAX_REQUEST_BYTES: u64 = 64 * 1024;
+
+/// Usage text included in argument errors.
+pub const USAGE: &str =
+    "Usage: pz-backup-tool [--backup <save>]... [--backup-all] [--show] [--silent]";
+
+/// Action requested through launch arguments.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LaunchAction {
+    /// Back up a single save (relative path, e.g., "Survival/MySave")
+    Backup(String),
+    /// Back up every save
+    BackupAll,
+    /// Bring the window to the front
+    Show,
+}
+
+/// Parsed launch arguments.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LaunchRequest {
+    /// Requested actions, in argument order
+    pub actions: Vec<LaunchAction>,
+    /// Keep the window hidden unless `--show` is given
+    pub silent: bool,
+}
+
+impl LaunchRequest {
+    /// Returns true if nothing was requested.
+    pub fn is_empty(&self) -> bool {
+        self.actions.is_empty() && !self.silent
+    }
+
+    /// Returns true if the window should be shown and focused.
+    pub fn should_show_window(&self) -> bool {
+        !self.silent || self.actions.contains(&LaunchAction::Show)
+    }
+
+    /// Returns true if all saves should be backed up.
+    pub fn backs_up_all(&self) -> bool {
+        self.actions.contains(&LaunchAction::BackupAll)
+    }
+
+    /// Resolves the saves to back up, in order and without duplicates.
+    ///
+    /// # Arguments
+    /// * `all_saves` - Relative paths of all saves, used for `--backup-all`
+    pub fn backup_targets(&self, all_saves: &[String]) -> Vec<String> {
+        let mut targets: Vec<String> = Vec::new();
+        let mut push = |save: &str| {
+            if !targets.iter().any(|t| t == save) {
+                targets.push(save.to_string());
+            }
+        };
+
+        for action in &self.actions {
+            match action {
+                LaunchAction::Backup(save) => push(save),
+                LaunchAction::BackupAll => all_saves.iter().for_each(|s| push(s)),
+                LaunchAction::Show => {}
+            }
+        }
+
+        targets
+    }
+}
+
+/// Error type for launch argument parsing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LaunchArgsError {
+    /// Flag isn't supported
+    UnknownFlag(String),
+    /// Flag requires a value
+    MissingValue(String),
+    /// Positional argument isn't supported
+    UnexpectedArgument(String),
+}
+
+impl std::fmt::Display for LaunchArgsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LaunchArgsError::UnknownFlag(flag) => write!(f, "Unknown flag: {}. {}", flag, USAGE),
+            LaunchArgsError::MissingValue(flag) => {
+                write!(f, "Missing value for {}. {}", flag, USAGE)
+            }
+            LaunchArgsError::UnexpectedArgument(arg) => {
+                write!(f, "Unexpected argument: {}. {}", arg, USAGE)
+            }
+        }
+    }
+}
+
+impl std::error::Error for LaunchArgsError {}
+
+/// Parses launch arguments (without the program name).
+///
+/// # Arguments
+/// * `args` - Arguments after the program name
+///
+/// # Returns
+/// `Result<LaunchRequest, LaunchArgsError>` - Parsed request or the first invalid argument
+///
+/// # Behavior
+/// - `--backup` accepts its value as the next argument or as `--backup=<save>`
+/// - macOS process serial arguments (`-psn_*`) are ignored
+pub fn parse_args<I, S>(args: I) -> Result<LaunchRequest, LaunchArgsError>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut request = LaunchRequest::default();
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        let arg = arg.as_ref();
+        match arg {
+            "--backup" => {
+                let save = args
+                    .next()
+                    .map(|s| s.as_ref().to_string())
+                    .filter(|s| !s.trim().is_empty() && !s.starts_with("--"))
+                    .ok_or_else(|| LaunchArgsError::MissingValue(arg.to_string()))?;
+                request.actions.push(LaunchAction::Backup(save));
+            }
+            "--backup-all" => request.actions.push(LaunchAction::BackupAll),
+            "--show" => request.actions.push(LaunchAction::Show),
+            "--silent" => request.silent = true,
+            _ if arg.starts_with("--backup=") => {
+                let save = &arg["--backup=".len()..];
+                if save.trim().is_empty() {
+                    return Err(LaunchArgsError::MissingValue("--backup".to_string()));
+                }
+                request.actions.push(LaunchAction::Backup(save.to_string()));
+            }
+            _ if arg.starts_with("-psn_") => {}
+            _ if arg.starts_with('-') => return Err(LaunchArgsError::UnknownFlag(arg.to_string())),
+            _ => return Err(LaunchArgsError::UnexpectedArgument(arg.to_string())),
+        }
+    }
+
+    Ok(request)
+}
+
+/// Parses launch arguments, logging errors and falling back to an empty request.
+pub fn parse_args_or_log<I, S>(args: I) -> LaunchRequest
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    parse_args(args).unwrap_or_else(|e| {
+        write_launch_log(&e.to_string());
+        LaunchRequest::default()
+    })
+}
+
+/// Appends a line to the launch log, ignoring failures.
+pub fn write_launch_log(message: &str) {
+    let Ok(log_dir) = config_module::get_log_dir() else {
+        return;
+    };
+    if fs::create_dir_all(&log_dir).is_err() {
+        return;
+    }
+
+    if let Ok(mut file) = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(log_dir.join(LAUNCH_LOG_FILE_NAME))
+    {
+        let _ = writeln!(file, "[{}] {}", Local::now().to_rfc3339(), message);
+    }
+}
+
+/// Contents of `instance.json`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct InstanceInfo {
+    /// Loopback port of the running instance
+    pub port: u16,
+    /// Token that forwarded requests must present
+    pub token: String,
+}
+
+/// A request forwarded from a second launch.
+#[derive(Debug, Serialize, Deserialize)]
+struct ForwardedRequest {
+    token: String,
+    args: Vec<String>,
+}
+
+/// Returns the path to `instance.json`.
+fn get_instance_file_path() -> Option<PathBuf> {
+    config_module::get_config_dir()
+        .ok()
+        .map(|dir| dir.join(INSTANCE_FILE_NAME))
+}
+
+/// Generates a random per-session token.
+fn generate_token() -> String {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u128(
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or_default(),
+    );
+    hasher.write_u32(std::process::id());
+    format!("{:016x}{:016x}", hasher.finish(), RandomState::new().build_hasher().finish())
+}
+
+/// Forwards arguments to the running instance, if there is one.
+///
+/// # Arguments
+/// * `args` - Arguments after the program name
+///
+/// # Returns
+/// `bool` - true if a running instance accepted the arguments (this process should exit)
+pub fn forward_to_running_instance(args: &[String]) -> bool {
+    let Some(path) = get_instance_file_path() else {
+        return false;
+    };
+    let Some(info) = fs::read_to_string(&path)
+        .ok()
+        .and_then(|json| serde_json::from_str::<InstanceInfo>(&json).ok())
+    else {
+        return false;
+    };
+
+    forward_to(&SocketAddr::from(([127, 0, 0, 1], info.port)), &info.token, args)
+}
+
+/// Sends arguments to a listener and waits for its acknowledgement.
+fn forward_to(addr: &SocketAddr, token: &str, args: &[String]) -> bool {
+    let Ok(mut stream) = TcpStream::connect_timeout(addr, FORWARD_TIMEOUT) else {
+        return false;
+    };
+    let _ = stream.set_read_timeout(Some(FORWARD_TIMEOUT));
+    let _ = stream.set_write_timeout(Some(FORWARD_TIMEOUT));
+
+    let request = ForwardedRequest {
+        token: token.to_string(),
+        args: args.to_vec(),
+    };
+    let Ok(line) = serde_json::to_string(&request) else {
+        return false;
+    };
+    if writeln!(stream, "{}", line).is_err() {
+        return false;
+    }
+
+    let mut reply = String::new();
+    BufReader::new(stream).read_line(&mut reply).is_ok() && reply.trim() == "ok"
+}
+
+/// Starts listening for arguments forwarded by later launches.
+///
+/// # Arguments
+/// * `handler` - Called with the parsed request of every valid forwarded launch
+///
+/// # Behavior
+/// - Binds a loopback port and publishes it with a fresh token in `instance.json`
+/// - Serves connections on a background thread for the lifetime of the process
+/// - Invalid arguments are logged and handled as an empty request (show the window)
+pub fn start_listener<F>(handler: F) -> std::io::Result<()>
+where
+    F: Fn(LaunchRequest) + Send + 'static,
+{
+    let listener = TcpListener::bind(("127.0.0.1", 0))?;
+    let info = InstanceInfo {
+        port: listener.local_addr()?.port(),
+        token: generate_token(),
+    };
+
+    if let Some(path) = get_instance_file_path() {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, serde_json::to_string(&info)?)?;
+    }
+
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            if let Some(args) = serve_connection(stream, &info.token) {
+                handler(parse_args_or_log(&args));
+            }
+        }
+    });
+
+    Ok(())
+}
+
+/// Reads one forwarded request, acknowledges it and returns its arguments.
+///
+/// Requests with a wrong token or malformed content are rejected and return `None`.
+fn serve_connection(stream: TcpStream, token: &str) -> Option<Vec<String>> {
+    let _ = stream.set_read_timeout(Some(FORWARD_TIMEOUT));
+    let mut writer = stream.try_clone().ok()?;
+
+    let mut line = String::new();
+    BufReader::new(stream.take(MAX_REQUEST_BYTES))
+        .read_line(&mut line)
+        .ok()?;
+
+    let request = serde_json::from_str::<ForwardedRequest>(&line)
+        .ok()
+        .filter(|r| r.token == token);
+
+    let reply = if request.is_some() { "ok" } else { "rejected" };
+    let _ = writeln!(writer, "{}", reply);
+
+    request.map(|r| r.args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+
+    fn parse(args: &[&str]) -> Result<LaunchRequest, LaunchArgsError> {
+        parse_args(args.iter().copied())
+    }
+
+    #[test]
+    fn test_parse_backup_flags() {
+        let request = parse(&["--backup", "Survival/MySave", "--backup=Builder/Base", "--silent"]).unwrap();
+        assert_eq!(
+            request.actions,
+            vec![
+                LaunchAction::Backup("Survival/MySave".to_string()),
+                LaunchAction::Backup("Builder/Base".to_string()),
+            ]
+        );
+        assert!(request.silent);
+        assert!(!request.should_show_window());
+    }
+
+    #[test]
+    fn test_parse_show_and_empty() {
+        let request = parse(&[]).unwrap();
+        assert!(request.is_empty());
+        assert!(request.should_show_window());
+
+        let request = parse(&["--silent", "--show"]).unwrap();
+        assert!(request.should_show_window());
+
+        // macOS process serial numbers are ignored
+        assert!(parse(&["-psn_0_12345"]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_parse_rejects_unknown_flags() {
+        let err = parse(&["--backup-everything"]).unwrap_err();
+        assert_eq!(err, LaunchArgsError::UnknownFlag("--backup-everything".to_string()));
+        assert!(err.to_string().contains(USAGE));
+
+        assert_eq!(
+            parse(&["--backup"]).unwrap_err(),
+            LaunchArgsError::MissingValue("--backup".to_string())
+        );
+        assert_eq!(
+            parse(&["--backup", "--silent"]).unwrap_err(),
+            LaunchArgsError::MissingValue("--backup".to_string())
+        );
+        assert_eq!(
+            parse(&["MySave"]).unwrap_err(),
+            LaunchArgsError::UnexpectedArgument("MySave".to_string())
+        );
+    }
+
+    #[test]
+    fn test_backup_targets_mapping() {
+        let all_saves = vec!["Survival/A".to_string(), "Survival/B".to_string()];
+
+        let request = parse(&["--backup", "Survival/B", "--backup-all", "--backup", "Builder/C"]).unwrap();
+        assert!(request.backs_up_all());
+        assert_eq!(
+            request.backup_targets(&all_saves),
+            vec!["Survival/B", "Survival/A", "Builder/C"]
+        );
+
+        let request = parse(&["--show"]).unwrap();
+        assert!(request.backup_targets(&all_saves).is_empty());
+    }
+
+    #[test]
+    fn test_forwarding_round_trip() {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (sender, receiver) = mpsc::channel();
+
+        std::thread::spawn(move || {
+            for stream in listener.incoming().take(2).flatten() {
+                sender.send(serve_connection(stream, "secret")).unwrap();
+            }
+        });
+
+        let args = vec!["--backup".to_string(), "Survival/MySave".to_string()];
+        assert!(forward_to(&addr, "secret", &args));
+        assert_eq!(receiver.recv().unwrap(), Some(args.clone()));
+
+        // A wrong token is rejected
+        assert!(!forward_to(&addr, "guess", &args));
+        assert_eq!(receiver.recv().unwrap(), None);
+    }
+}
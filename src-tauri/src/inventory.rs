@@ -0,0 +1,628 @@
+//! Inventory report of saves, backups and tags.
+//!
+//! This module provides:
+//! - Assembly of the inventory from the backup listings (served from the listing
+//!   cache), the tags database and the operation history
+//! - JSON output keeping the nested save → backups structure
+//! - CSV output with one row per backup, for spreadsheets
+//!
+//! Reports are written atomically, so a script reading the file never sees half a report.
+//! Backups aren't verified in this tree; `partial` is the closest status and is exported
+//! with the number of files the backup is missing.
+
+use crate::backup::{self, BackupError, BackupInfo};
+use crate::config::{self as config_module, ConfigError, SaveEntry};
+use crate::file_ops::{self, FileOpsError};
+use crate::history::{self, HistoryError, HistoryFilter, OperationOutcome, OperationTrigger, OperationType};
+use crate::keys::SaveKey;
+use crate::tags::Tag;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize, Serializer};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+/// Columns of the CSV report, in order.
+pub const CSV_COLUMNS: [&str; 12] = [
+    "save",
+    "game_mode",
+    "save_tags",
+    "save_exists",
+    "last_restore_at",
+    "backup",
+    "created_at",
+    "size_bytes",
+    "trigger",
+    "partial",
+    "backup_tags",
+    "path",
+];
+
+/// Separator between tag names in a CSV cell.
+const CSV_TAG_SEPARATOR: &str = "; ";
+
+/// Output format of an inventory report.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum InventoryFormat {
+    /// Nested saves with their backups
+    Json,
+    /// One row per backup
+    Csv,
+}
+
+/// Saves included in an inventory report.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum InventoryScope {
+    /// Every save in the saves directory or with backups
+    #[default]
+    All,
+    /// A single save, by relative path (e.g., "Survival/MySave")
+    Save(String),
+}
+
+impl InventoryScope {
+    /// Returns true if the save is part of the scope.
+    pub fn includes(&self, relative_path: &str) -> bool {
+        match self {
+            InventoryScope::All => true,
+            InventoryScope::Save(save) => SaveKey::new(save) == SaveKey::new(relative_path),
+        }
+    }
+}
+
+/// A backup in the inventory.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BackupInventory {
+    /// Name of the backup file
+    pub name: String,
+    /// Full path to the backup
+    pub path: String,
+    /// Size in bytes
+    pub size_bytes: u64,
+    /// ISO 8601 timestamp when the backup was created
+    pub created_at: String,
+    /// What started the backup, if the operation history still has it
+    pub trigger: Option<OperationTrigger>,
+    /// Whether some save files are missing from the backup
+    pub partial: bool,
+    /// Number of save files missing from the backup
+    pub skipped_file_count: usize,
+    /// Names of the backup's tags
+    pub tags: Vec<String>,
+}
+
+/// A save in the inventory.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SaveInventory {
+    /// Relative path of the save (e.g., "Survival/MySave")
+    pub relative_path: String,
+    /// Game mode, empty for flat saves or saves known only from their backups
+    pub game_mode: String,
+    /// Whether the save still exists in the saves directory
+    pub exists: bool,
+    /// Names of the save's tags
+    pub tags: Vec<String>,
+    /// ISO 8601 timestamp of the last successful restore
+    pub last_restore_at: Option<String>,
+    /// Backups, newest first
+    pub backups: Vec<BackupInventory>,
+}
+
+/// Complete inventory report.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct InventoryReport {
+    /// When the report was assembled
+    pub generated_at: DateTime<Utc>,
+    /// Saves sorted by relative path
+    pub saves: Vec<SaveInventory>,
+}
+
+impl InventoryReport {
+    /// Returns the total number of backups.
+    pub fn backup_count(&self) -> usize {
+        self.saves.iter().map(|save| save.backups.len()).sum()
+    }
+}
+
+/// Result of writing an inventory report.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct InventoryExportResult {
+    /// Path of the written report
+    pub path: String,
+    /// Format of the report
+    pub format: InventoryFormat,
+    /// Number of saves in the report
+    pub save_count: usize,
+    /// Number of backups in the report
+    pub backup_count: usize,
+    /// Number of data rows (CSV, excluding the header) or backup entries (JSON) written
+    pub rows_written: usize,
+}
+
+/// Error type for inventory operations.
+#[derive(Debug)]
+pub enum InventoryError {
+    /// File operation error
+    FileOp(FileOpsError),
+    /// Config error
+    Config(ConfigError),
+    /// Backup listing error
+    Backup(BackupError),
+    /// Operation history error
+    History(HistoryError),
+    /// JSON serialization error
+    Json(serde_json::Error),
+}
+
+impl From<FileOpsError> for InventoryError {
+    fn from(err: FileOpsError) -> Self {
+        InventoryError::FileOp(err)
+    }
+}
+
+impl From<ConfigError> for InventoryError {
+    fn from(err: ConfigError) -> Self {
+        InventoryError::Config(err)
+    }
+}
+
+impl From<BackupError> for InventoryError {
+    fn from(err: BackupError) -> Self {
+        InventoryError::Backup(err)
+    }
+}
+
+impl From<HistoryError> for InventoryError {
+    fn from(err: HistoryError) -> Self {
+        InventoryError::History(err)
+    }
+}
+
+impl From<serde_json::Error> for InventoryError {
+    fn from(err: serde_json::Error) -> Self {
+        InventoryError::Json(err)
+    }
+}
+
+impl std::fmt::Display for InventoryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InventoryError::FileOp(err) => write!(f, "File operation error: {}", err),
+            InventoryError::Config(err) => write!(f, "Config error: {}", err),
+            InventoryError::Backup(err) => write!(f, "Backup error: {}", err),
+            InventoryError::History(err) => write!(f, "History error: {}", err),
+            InventoryError::Json(err) => write!(f, "JSON error: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for InventoryError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            InventoryError::FileOp(err) => Some(err),
+            InventoryError::Config(err) => Some(err),
+            InventoryError::Backup(err) => Some(err),
+            InventoryError::History(err) => Some(err),
+            InventoryError::Json(err) => Some(err),
+        }
+    }
+}
+
+impl Serialize for InventoryError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+/// Result type for inventory operations.
+pub type InventoryResult<T> = Result<T, InventoryError>;
+
+/// Assembles and writes an inventory report.
+///
+/// # Arguments
+/// * `path` - File to write the report to (replaced if it exists)
+/// * `format` - JSON or CSV
+/// * `scope` - Every save or a single one
+///
+/// # Returns
+/// `InventoryResult<InventoryExportResult>` - Counts of saves, backups and rows written
+///
+/// # Behavior
+/// The report is written to a temp file next to `path` and renamed over it.
+pub fn export_inventory(
+    path: &Path,
+    format: InventoryFormat,
+    scope: &InventoryScope,
+) -> InventoryResult<InventoryExportResult> {
+    let report = assemble_inventory(scope)?;
+
+    let (contents, rows_written) = match format {
+        InventoryFormat::Json => (serde_json::to_string_pretty(&report)?, report.backup_count()),
+        InventoryFormat::Csv => render_csv(&report),
+    };
+    file_ops::write_file_atomic(path, contents.as_bytes())?;
+
+    Ok(InventoryExportResult {
+        path: file_ops::normalize_path_for_display(path),
+        format,
+        save_count: report.saves.len(),
+        backup_count: report.backup_count(),
+        rows_written,
+    })
+}
+
+/// Assembles the inventory of the saves in scope.
+///
+/// # Arguments
+/// * `scope` - Every save or a single one
+///
+/// # Returns
+/// `InventoryResult<InventoryReport>` - Saves sorted by relative path
+///
+/// # Behavior
+/// - Saves come from the saves directory and from the backup directories, so saves
+///   deleted from the game are included with `exists: false`
+/// - Backup listings come from the listing cache; the trigger of each backup is
+///   looked up in the operation history
+pub fn assemble_inventory(scope: &InventoryScope) -> InventoryResult<InventoryReport> {
+    let mut saves: BTreeMap<SaveKey, SaveInventory> = BTreeMap::new();
+
+    for entry in config_module::list_save_entries()? {
+        if scope.includes(&entry.relative_path) {
+            saves.insert(SaveKey::new(&entry.relative_path), save_inventory(&entry));
+        }
+    }
+    for relative_path in backup::list_backed_up_saves()? {
+        if scope.includes(&relative_path) {
+            saves
+                .entry(SaveKey::new(&relative_path))
+                .or_insert_with(|| missing_save_inventory(&relative_path));
+        }
+    }
+
+    let triggers = backup_triggers()?;
+    for (key, save) in saves.iter_mut() {
+        save.backups = backup::list_backups_cached(&save.relative_path)?
+            .backups
+            .iter()
+            .map(|info| {
+                let trigger = triggers.get(&(key.clone(), info.name.clone())).copied();
+                backup_inventory(info, trigger)
+            })
+            .collect();
+    }
+
+    Ok(InventoryReport {
+        generated_at: Utc::now(),
+        saves: saves.into_values().collect(),
+    })
+}
+
+fn save_inventory(entry: &SaveEntry) -> SaveInventory {
+    SaveInventory {
+        relative_path: entry.relative_path.clone(),
+        game_mode: entry.game_mode.clone(),
+        exists: true,
+        tags: tag_names(&entry.tags),
+        last_restore_at: entry.last_restore_at.clone(),
+        backups: Vec::new(),
+    }
+}
+
+/// Inventory entry of a save that only exists in the backup directory.
+fn missing_save_inventory(relative_path: &str) -> SaveInventory {
+    SaveInventory {
+        relative_path: relative_path.to_string(),
+        game_mode: String::new(),
+        exists: false,
+        tags: crate::tags::get_save_tags(relative_path)
+            .map(|tags| tag_names(&tags))
+            .unwrap_or_default(),
+        last_restore_at: None,
+        backups: Vec::new(),
+    }
+}
+
+fn backup_inventory(info: &BackupInfo, trigger: Option<OperationTrigger>) -> BackupInventory {
+    BackupInventory {
+        name: info.name.clone(),
+        path: info.path.clone(),
+        size_bytes: info.size_bytes,
+        created_at: info.created_at.clone(),
+        trigger,
+        partial: info.partial,
+        skipped_file_count: info.skipped_files.len(),
+        tags: tag_names(&info.tags),
+    }
+}
+
+fn tag_names(tags: &[Tag]) -> Vec<String> {
+    tags.iter().map(|tag| tag.name.clone()).collect()
+}
+
+/// Returns the trigger of every successful backup in the history, keyed by save and backup name.
+fn backup_triggers() -> InventoryResult<HashMap<(SaveKey, String), OperationTrigger>> {
+    let filter = HistoryFilter {
+        operation: Some(OperationType::Backup),
+        outcome: Some(OperationOutcome::Success),
+        ..Default::default()
+    };
+    let page = history::get_operation_history(&filter, 0, usize::MAX)?;
+
+    let mut triggers = HashMap::new();
+    // Records are newest first; keep the newest record of a reused backup name
+    for record in page.records {
+        if let Some(backup_name) = record.backup_name {
+            triggers
+                .entry((SaveKey::new(&record.save_name), backup_name))
+                .or_insert(record.trigger);
+        }
+    }
+    Ok(triggers)
+}
+
+/// Renders a report as CSV.
+///
+/// # Returns
+/// `(String, usize)` - CSV text with a header row, and the number of data rows
+///
+/// # Behavior
+/// One row per backup, with the save's columns repeated. A save without backups
+/// gets a single row with empty backup columns. Tag names are joined with "; ".
+pub fn render_csv(report: &InventoryReport) -> (String, usize) {
+    let mut csv = String::new();
+    push_csv_row(&mut csv, CSV_COLUMNS.iter().map(|column| column.to_string()));
+
+    let mut rows = 0;
+    for save in &report.saves {
+        let save_columns = [
+            save.relative_path.clone(),
+            save.game_mode.clone(),
+            save.tags.join(CSV_TAG_SEPARATOR),
+            save.exists.to_string(),
+            save.last_restore_at.clone().unwrap_or_default(),
+        ];
+
+        if save.backups.is_empty() {
+            push_csv_row(&mut csv, save_columns.iter().cloned().chain(vec![String::new(); 7]));
+            rows += 1;
+        }
+        for backup in &save.backups {
+            let backup_columns = [
+                backup.name.clone(),
+                backup.created_at.clone(),
+                backup.size_bytes.to_string(),
+                backup.trigger.map(|t| format!("{:?}", t)).unwrap_or_default(),
+                backup.partial.to_string(),
+                backup.tags.join(CSV_TAG_SEPARATOR),
+                backup.path.clone(),
+            ];
+            push_csv_row(&mut csv, save_columns.iter().cloned().chain(backup_columns));
+            rows += 1;
+        }
+    }
+    (csv, rows)
+}
+
+pub(crate) fn push_csv_row(csv: &mut String, fields: impl IntoIterator<Item = String>) {
+    let fields: Vec<String> = fields.into_iter().map(|field| escape_csv_field(&field)).collect();
+    csv.push_str(&fields.join(","));
+    csv.push_str("\r\n");
+}
+
+/// Escapes a CSV field (RFC 4180).
+///
+/// Fields containing a comma, quote or line break are quoted, with quotes doubled.
+/// Fields a spreadsheet would evaluate as a formula (starting with `=`, `+`, `-`,
+/// `@`, tab or carriage return) are prefixed with `'`.
+pub fn escape_csv_field(field: &str) -> String {
+    let field = if field.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        format!("'{}", field)
+    } else {
+        field.to_string()
+    };
+
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::HistoryRecord;
+    use crate::tags;
+    use crate::test_support::TestEnv;
+    use chrono::TimeZone;
+    use serde_json::{json, Value};
+    use std::fs;
+
+    fn backup(name: &str, trigger: Option<OperationTrigger>, tags: &[&str]) -> BackupInventory {
+        BackupInventory {
+            name: name.to_string(),
+            path: format!("/backups/Survival/A/{}", name),
+            size_bytes: 1024,
+            created_at: "2024-12-28T14:30:45+00:00".to_string(),
+            trigger,
+            partial: false,
+            skipped_file_count: 0,
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+        }
+    }
+
+    fn save(relative_path: &str, backups: Vec<BackupInventory>) -> SaveInventory {
+        SaveInventory {
+            relative_path: relative_path.to_string(),
+            game_mode: "Survival".to_string(),
+            exists: true,
+            tags: Vec::new(),
+            last_restore_at: None,
+            backups,
+        }
+    }
+
+    fn report(saves: Vec<SaveInventory>) -> InventoryReport {
+        InventoryReport {
+            generated_at: Utc.with_ymd_and_hms(2024, 12, 28, 15, 0, 0).unwrap(),
+            saves,
+        }
+    }
+
+    /// Creates a save in the saves directory and fake backups of it.
+    fn create_save_with_backups(env: &TestEnv, relative_path: &str, backups: &[&str]) {
+        fs::create_dir_all(env.save_dir().join(relative_path).join("map")).unwrap();
+        fs::write(env.save_dir().join(relative_path).join("map").join("map_0_0.bin"), b"map").unwrap();
+        let backup_dir = env.backup_dir().join(relative_path);
+        fs::create_dir_all(&backup_dir).unwrap();
+        for name in backups {
+            fs::write(backup_dir.join(name), b"archive").unwrap();
+        }
+    }
+
+    #[test]
+    fn test_escape_csv_field() {
+        assert_eq!(escape_csv_field("Survival/A"), "Survival/A");
+        assert_eq!(escape_csv_field(""), "");
+        assert_eq!(escape_csv_field("keep, forever"), "\"keep, forever\"");
+        assert_eq!(escape_csv_field("the \"good\" one"), "\"the \"\"good\"\" one\"");
+        assert_eq!(escape_csv_field("line\nbreak"), "\"line\nbreak\"");
+        assert_eq!(escape_csv_field("=HYPERLINK(\"x\")"), "\"'=HYPERLINK(\"\"x\"\")\"");
+        assert_eq!(escape_csv_field("+1"), "'+1");
+        assert_eq!(escape_csv_field("@home"), "'@home");
+        assert_eq!(escape_csv_field("酒店"), "酒店");
+    }
+
+    #[test]
+    fn test_render_csv_one_row_per_backup() {
+        let mut with_backups = save(
+            "Survival/A",
+            vec![
+                backup("2.tar.gz", Some(OperationTrigger::Scheduled), &["keep", "before, horde"]),
+                backup("1.tar.gz", None, &[]),
+            ],
+        );
+        with_backups.tags = vec!["main".to_string()];
+        let mut without_backups = save("Survival/B", Vec::new());
+        without_backups.last_restore_at = Some("2024-12-27T10:00:00+00:00".to_string());
+
+        let (csv, rows) = render_csv(&report(vec![with_backups, without_backups]));
+        assert_eq!(rows, 3);
+        assert_eq!(
+            csv,
+            "save,game_mode,save_tags,save_exists,last_restore_at,backup,created_at,size_bytes,trigger,partial,backup_tags,path\r\n\
+             Survival/A,Survival,main,true,,2.tar.gz,2024-12-28T14:30:45+00:00,1024,Scheduled,false,\"keep; before, horde\",/backups/Survival/A/2.tar.gz\r\n\
+             Survival/A,Survival,main,true,,1.tar.gz,2024-12-28T14:30:45+00:00,1024,,false,,/backups/Survival/A/1.tar.gz\r\n\
+             Survival/B,Survival,,true,2024-12-27T10:00:00+00:00,,,,,,,\r\n"
+        );
+    }
+
+    #[test]
+    fn test_json_keeps_nesting() {
+        let report = report(vec![save(
+            "Survival/A",
+            vec![backup("1.tar.gz", Some(OperationTrigger::Manual), &["keep"])],
+        )]);
+
+        assert_eq!(
+            serde_json::to_value(&report).unwrap(),
+            json!({
+                "generated_at": "2024-12-28T15:00:00Z",
+                "saves": [{
+                    "relative_path": "Survival/A",
+                    "game_mode": "Survival",
+                    "exists": true,
+                    "tags": [],
+                    "last_restore_at": null,
+                    "backups": [{
+                        "name": "1.tar.gz",
+                        "path": "/backups/Survival/A/1.tar.gz",
+                        "size_bytes": 1024,
+                        "created_at": "2024-12-28T14:30:45+00:00",
+                        "trigger": "Manual",
+                        "partial": false,
+                        "skipped_file_count": 0,
+                        "tags": ["keep"]
+                    }]
+                }]
+            })
+        );
+    }
+
+    #[test]
+    fn test_assemble_joins_tags_triggers_and_deleted_saves() {
+        let env = TestEnv::with_paths();
+        create_save_with_backups(&env, "Survival/A", &["2024-12-28_10-00-00.tar.gz"]);
+        // Deleted from the game, but its backups remain
+        fs::create_dir_all(env.backup_dir().join("Survival/Gone")).unwrap();
+        fs::write(env.backup_dir().join("Survival/Gone/old.tar.gz"), b"archive").unwrap();
+
+        tags::create_tag("keep".to_string(), "#FF5733".to_string(), None, None).unwrap();
+        tags::add_tags_to_backup("Survival/A", "2024-12-28_10-00-00.tar.gz", vec!["keep".to_string()])
+            .unwrap();
+        history::append_record(
+            &HistoryRecord::new(OperationType::Backup, "Survival/A", OperationTrigger::Scheduled)
+                .with_backup("2024-12-28_10-00-00.tar.gz"),
+        )
+        .unwrap();
+
+        let report = assemble_inventory(&InventoryScope::All).unwrap();
+        let paths: Vec<&str> = report.saves.iter().map(|s| s.relative_path.as_str()).collect();
+        assert_eq!(paths, vec!["Survival/A", "Survival/Gone"]);
+
+        let a = &report.saves[0];
+        assert!(a.exists);
+        assert_eq!(a.game_mode, "Survival");
+        assert_eq!(a.backups[0].tags, vec!["keep"]);
+        assert_eq!(a.backups[0].trigger, Some(OperationTrigger::Scheduled));
+
+        let gone = &report.saves[1];
+        assert!(!gone.exists);
+        assert_eq!(gone.backups.len(), 1);
+        assert_eq!(gone.backups[0].trigger, None);
+    }
+
+    #[test]
+    fn test_scope_filters_saves() {
+        let env = TestEnv::with_paths();
+        create_save_with_backups(&env, "Survival/A", &["a1.tar.gz", "a2.tar.gz"]);
+        create_save_with_backups(&env, "Survival/B", &["b1.tar.gz"]);
+
+        let all = assemble_inventory(&InventoryScope::All).unwrap();
+        assert_eq!(all.saves.len(), 2);
+        assert_eq!(all.backup_count(), 3);
+
+        let scoped = assemble_inventory(&InventoryScope::Save("Survival\\B".to_string())).unwrap();
+        assert_eq!(scoped.saves.len(), 1);
+        assert_eq!(scoped.saves[0].relative_path, "Survival/B");
+        assert_eq!(scoped.backup_count(), 1);
+
+        let unknown = assemble_inventory(&InventoryScope::Save("Survival/C".to_string())).unwrap();
+        assert!(unknown.saves.is_empty());
+    }
+
+    #[test]
+    fn test_export_writes_both_formats() {
+        let env = TestEnv::with_paths();
+        create_save_with_backups(&env, "Survival/A", &["a1.tar.gz", "a2.tar.gz"]);
+        create_save_with_backups(&env, "Survival/B", &[]);
+
+        let csv_path = env.root().join("inventory.csv");
+        let result = export_inventory(&csv_path, InventoryFormat::Csv, &InventoryScope::All).unwrap();
+        assert_eq!((result.save_count, result.backup_count, result.rows_written), (2, 2, 3));
+        let csv = fs::read_to_string(&csv_path).unwrap();
+        assert_eq!(csv.lines().count(), 4);
+        assert!(csv.starts_with("save,game_mode,"));
+        assert!(!env.root().join("inventory.csv.tmp").exists());
+
+        let json_path = env.root().join("inventory.json");
+        let result = export_inventory(&json_path, InventoryFormat::Json, &InventoryScope::All).unwrap();
+        assert_eq!((result.save_count, result.backup_count, result.rows_written), (2, 2, 2));
+        let json: Value = serde_json::from_str(&fs::read_to_string(&json_path).unwrap()).unwrap();
+        assert_eq!(json["saves"][0]["backups"].as_array().unwrap().len(), 2);
+        assert_eq!(json["saves"][1]["backups"], json!([]));
+    }
+}
@@ -0,0 +1,555 @@
+//! Crash-safe journal for multi-step filesystem operations.
+//!
+//! This module provides:
+//! - A `journal.json` file in the config directory holding one intent record per
+//!   running multi-step operation (operation, subject, steps, work paths)
+//! - `run_journaled`, which records the intent, runs the steps, tracks progress
+//!   and removes the record on completion
+//! - Startup recovery (`recover_interrupted`), which finishes or undoes every
+//!   operation a crash left behind
+//!
+//! Every recoverable operation implements `RecoveryHandler` and is listed in
+//! `registered_handlers`. Step handlers must be idempotent: after a crash the
+//! journal only knows which steps were completed, not whether the next one had
+//! already started.
+//!
+//! Only the restore swap (`restore::RestoreSwap`) is journaled. Save rename and
+//! archive-save have no handlers: the backend has no multi-step operation for
+//! either. Saves are renamed outside the app and `tags::rename_save_target` only
+//! moves the tags afterwards, and there is no archive-save operation. Both need a
+//! handler here once they become backend operations.
+
+use crate::config as config_module;
+use crate::config::ConfigError;
+use crate::file_ops::{FileOpsError, LockedJsonStore};
+use crate::history::{self, HistoryRecord, OperationOutcome, OperationTrigger, OperationType};
+use crate::i18n;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize, Serializer};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// Journal file name.
+const JOURNAL_FILE_NAME: &str = "journal.json";
+
+/// Event emitted after startup recovery handled at least one interrupted operation.
+pub const JOURNAL_RECOVERED_EVENT: &str = "journal-recovered";
+
+/// Intent record of a running multi-step operation.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct JournalEntry {
+    /// Unique id of this run
+    pub id: String,
+    /// Operation name, matching `RecoveryHandler::operation`
+    pub operation: String,
+    /// What the operation works on (e.g., the relative path of a save)
+    pub subject: String,
+    /// Step names, in execution order
+    pub steps: Vec<String>,
+    /// Number of steps completed so far
+    pub completed_steps: usize,
+    /// Named paths the steps work with (targets, temp and work directories)
+    pub paths: BTreeMap<String, PathBuf>,
+    /// When the operation started
+    pub started_at: DateTime<Utc>,
+}
+
+impl JournalEntry {
+    /// Creates an entry with no completed steps.
+    pub fn new(operation: &str, subject: &str, steps: &[&str]) -> Self {
+        JournalEntry {
+            id: format!(
+                "{}-{}",
+                Utc::now().timestamp_nanos_opt().unwrap_or_default(),
+                std::process::id()
+            ),
+            operation: operation.to_string(),
+            subject: subject.to_string(),
+            steps: steps.iter().map(|s| s.to_string()).collect(),
+            completed_steps: 0,
+            paths: BTreeMap::new(),
+            started_at: Utc::now(),
+        }
+    }
+
+    /// Adds a named path.
+    pub fn with_path(mut self, name: &str, path: PathBuf) -> Self {
+        self.paths.insert(name.to_string(), path);
+        self
+    }
+
+    /// Returns a named path.
+    pub fn path(&self, name: &str) -> Result<&PathBuf, JournalError> {
+        self.paths
+            .get(name)
+            .ok_or_else(|| JournalError::MissingPath(name.to_string()))
+    }
+}
+
+/// Contents of `journal.json`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Journal {
+    /// Operations that started and haven't finished
+    pub entries: Vec<JournalEntry>,
+}
+
+/// Forward and backward step handlers of a recoverable operation.
+pub trait RecoveryHandler: Sync {
+    /// Operation name stored in journal entries.
+    fn operation(&self) -> &'static str;
+
+    /// Returns true if an interrupted run should be finished rather than undone.
+    fn can_roll_forward(&self, entry: &JournalEntry) -> bool;
+
+    /// Runs step `step`. Must succeed when repeated after a crash.
+    fn forward(&self, entry: &JournalEntry, step: usize) -> JournalResult<()>;
+
+    /// Undoes step `step`. Must succeed when the step ran partially or not at all.
+    fn backward(&self, entry: &JournalEntry, step: usize) -> JournalResult<()>;
+}
+
+/// Returns the handlers of every recoverable operation.
+pub fn registered_handlers() -> Vec<&'static dyn RecoveryHandler> {
+    vec![&crate::restore::RestoreSwap]
+}
+
+/// What recovery did with an interrupted operation.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum RecoveryAction {
+    /// The remaining steps were run
+    RolledForward,
+    /// The completed steps were undone
+    RolledBack,
+    /// Recovery failed; the entry is kept for the next launch
+    Failed,
+}
+
+/// Report of one recovered operation, sent with `JOURNAL_RECOVERED_EVENT`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RecoveryReport {
+    /// Operation name
+    pub operation: String,
+    /// What the operation worked on
+    pub subject: String,
+    /// Action taken
+    pub action: RecoveryAction,
+    /// Steps completed before the interruption
+    pub completed_steps: usize,
+    /// Total number of steps
+    pub total_steps: usize,
+    /// Error, if recovery failed
+    pub error: Option<String>,
+}
+
+/// Error type for journal operations.
+#[derive(Debug)]
+pub enum JournalError {
+    /// File operation error
+    FileOp(FileOpsError),
+    /// Config error
+    Config(ConfigError),
+    /// JSON serialization error
+    Json(serde_json::Error),
+    /// Entry lacks a path its handler needs
+    MissingPath(String),
+    /// No handler is registered for the entry's operation
+    UnknownOperation(String),
+}
+
+impl From<FileOpsError> for JournalError {
+    fn from(err: FileOpsError) -> Self {
+        JournalError::FileOp(err)
+    }
+}
+
+impl From<std::io::Error> for JournalError {
+    fn from(err: std::io::Error) -> Self {
+        JournalError::FileOp(FileOpsError::Io(err))
+    }
+}
+
+impl From<ConfigError> for JournalError {
+    fn from(err: ConfigError) -> Self {
+        JournalError::Config(err)
+    }
+}
+
+impl From<serde_json::Error> for JournalError {
+    fn from(err: serde_json::Error) -> Self {
+        JournalError::Json(err)
+    }
+}
+
+impl std::fmt::Display for JournalError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JournalError::FileOp(err) => write!(f, "File operation error: {}", err),
+            JournalError::Config(err) => write!(f, "Config error: {}", err),
+            JournalError::Json(err) => write!(f, "JSON error: {}", err),
+            JournalError::MissingPath(name) => write!(f, "Journal entry has no '{}' path", name),
+            JournalError::UnknownOperation(name) => {
+                write!(f, "No recovery handler for operation: {}", name)
+            }
+        }
+    }
+}
+
+impl std::error::Error for JournalError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            JournalError::FileOp(err) => Some(err),
+            JournalError::Config(err) => Some(err),
+            JournalError::Json(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl Serialize for JournalError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+/// Result type for journal operations.
+pub type JournalResult<T> = Result<T, JournalError>;
+
+/// Returns the store for `journal.json`.
+fn journal_store() -> JournalResult<LockedJsonStore<Journal>> {
+    Ok(LockedJsonStore::new(
+        config_module::get_config_dir()?.join(JOURNAL_FILE_NAME),
+    ))
+}
+
+/// Runs a multi-step operation under the journal.
+///
+/// # Arguments
+/// * `handler` - Step handlers of the operation
+/// * `entry` - Intent record describing this run
+///
+/// # Returns
+/// `JournalResult<()>` - Ok(()) once every step completed
+///
+/// # Behavior
+/// - Writes the entry before the first step and updates it after every step
+/// - On a step error before the roll-forward point, undoes the steps run so far
+///   (including the failed one) and removes the entry
+/// - On a step error after it, or if undoing fails, keeps the entry so startup
+///   recovery finishes the operation
+/// - Removes the entry after the last step
+pub fn run_journaled(handler: &dyn RecoveryHandler, entry: JournalEntry) -> JournalResult<()> {
+    run_journaled_in(&journal_store()?, handler, entry, None)
+}
+
+/// Runs a journaled operation against a specific store.
+///
+/// `crash_after` simulates a crash: after that many steps the run stops without
+/// cleaning up, exactly as if the process had died.
+fn run_journaled_in(
+    store: &LockedJsonStore<Journal>,
+    handler: &dyn RecoveryHandler,
+    mut entry: JournalEntry,
+    crash_after: Option<usize>,
+) -> JournalResult<()> {
+    store.modify(|journal: &mut Journal| {
+        journal.entries.push(entry.clone());
+        Ok::<_, JournalError>(())
+    })?;
+
+    for step in 0..entry.steps.len() {
+        if crash_after == Some(step) {
+            return Err(simulated_crash());
+        }
+
+        if let Err(e) = handler.forward(&entry, step) {
+            // Past the roll-forward point, undoing could lose data; leave it to recovery
+            if !handler.can_roll_forward(&entry) && roll_back(handler, &entry, step).is_ok() {
+                remove_entry(store, &entry.id)?;
+            }
+            return Err(e);
+        }
+
+        entry.completed_steps = step + 1;
+        set_completed_steps(store, &entry.id, entry.completed_steps)?;
+    }
+
+    remove_entry(store, &entry.id)
+}
+
+/// Runs a journaled operation that stops as if the process died after `steps` steps.
+#[cfg(test)]
+pub(crate) fn run_until_crash(
+    handler: &dyn RecoveryHandler,
+    entry: JournalEntry,
+    steps: usize,
+) -> JournalResult<()> {
+    run_journaled_in(&journal_store()?, handler, entry, Some(steps))
+}
+
+/// Undoes steps `0..=last` in reverse order.
+fn roll_back(handler: &dyn RecoveryHandler, entry: &JournalEntry, last: usize) -> JournalResult<()> {
+    for step in (0..=last).rev() {
+        handler.backward(entry, step)?;
+    }
+    Ok(())
+}
+
+/// Finishes the remaining steps of an interrupted entry.
+fn roll_forward(handler: &dyn RecoveryHandler, entry: &JournalEntry) -> JournalResult<()> {
+    for step in entry.completed_steps..entry.steps.len() {
+        handler.forward(entry, step)?;
+    }
+    Ok(())
+}
+
+fn set_completed_steps(store: &LockedJsonStore<Journal>, id: &str, completed: usize) -> JournalResult<()> {
+    store.modify(|journal: &mut Journal| {
+        if let Some(entry) = journal.entries.iter_mut().find(|e| e.id == id) {
+            entry.completed_steps = completed;
+        }
+        Ok::<_, JournalError>(())
+    })
+}
+
+fn remove_entry(store: &LockedJsonStore<Journal>, id: &str) -> JournalResult<()> {
+    store.modify(|journal: &mut Journal| {
+        journal.entries.retain(|e| e.id != id);
+        Ok::<_, JournalError>(())
+    })
+}
+
+fn simulated_crash() -> JournalError {
+    JournalError::FileOp(FileOpsError::Io(std::io::Error::other("Simulated crash")))
+}
+
+/// Recovers every operation left in the journal by a previous run.
+///
+/// # Returns
+/// `JournalResult<Vec<RecoveryReport>>` - One report per interrupted operation
+///
+/// # Behavior
+/// - Rolls an entry forward if its handler says the remaining steps are safe,
+///   otherwise rolls back the completed steps and the one that may have started
+/// - Removes recovered entries; failed ones stay for the next launch
+/// - Records each recovery in the operation history
+///
+/// Must run before any new journaled operation starts (i.e., during startup).
+pub fn recover_interrupted() -> JournalResult<Vec<RecoveryReport>> {
+    let reports = recover_in(&journal_store()?, &registered_handlers())?;
+    for report in &reports {
+        record_recovery(report);
+    }
+    Ok(reports)
+}
+
+/// Recovers the entries of a specific store with the given handlers.
+fn recover_in(
+    store: &LockedJsonStore<Journal>,
+    handlers: &[&dyn RecoveryHandler],
+) -> JournalResult<Vec<RecoveryReport>> {
+    store.modify(|journal: &mut Journal| {
+        let mut reports = Vec::new();
+        let mut remaining = Vec::new();
+
+        for entry in journal.entries.drain(..) {
+            let (action, result) = match handlers.iter().find(|h| h.operation() == entry.operation) {
+                Some(handler) if handler.can_roll_forward(&entry) => {
+                    (RecoveryAction::RolledForward, roll_forward(*handler, &entry))
+                }
+                Some(handler) => {
+                    let last = entry.completed_steps.min(entry.steps.len().saturating_sub(1));
+                    (RecoveryAction::RolledBack, roll_back(*handler, &entry, last))
+                }
+                None => (
+                    RecoveryAction::Failed,
+                    Err(JournalError::UnknownOperation(entry.operation.clone())),
+                ),
+            };
+
+            let error = result.err().map(|e| e.to_string());
+            reports.push(RecoveryReport {
+                operation: entry.operation.clone(),
+                subject: entry.subject.clone(),
+                action: if error.is_some() { RecoveryAction::Failed } else { action },
+                completed_steps: entry.completed_steps,
+                total_steps: entry.steps.len(),
+                error: error.clone(),
+            });
+            if error.is_some() {
+                remaining.push(entry);
+            }
+        }
+
+        journal.entries = remaining;
+        Ok::<_, JournalError>(reports)
+    })
+}
+
+/// Appends a history record for a recovered operation.
+fn record_recovery(report: &RecoveryReport) {
+    let args = [
+        ("operation", report.operation.as_str()),
+        ("error", report.error.as_deref().unwrap_or_default()),
+    ];
+    let key = match report.action {
+        RecoveryAction::RolledForward => i18n::keys::HISTORY_RECOVERY_ROLLED_FORWARD,
+        RecoveryAction::RolledBack => i18n::keys::HISTORY_RECOVERY_ROLLED_BACK,
+        RecoveryAction::Failed => i18n::keys::HISTORY_RECOVERY_FAILED,
+    };
+
+    let mut record = HistoryRecord::new(OperationType::Recovery, &report.subject, OperationTrigger::System)
+        .with_message(i18n::t(key, &args));
+    if report.action == RecoveryAction::Failed {
+        record.outcome = OperationOutcome::Failure;
+    }
+    history::append_record_best_effort(&record);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::Path;
+    use std::sync::Mutex;
+    use tempfile::TempDir;
+
+    /// Two-file operation: writes `a`, then `b`. Consistent states are neither or both.
+    struct PairWrite {
+        fail_at: Option<usize>,
+        calls: Mutex<Vec<String>>,
+    }
+
+    impl PairWrite {
+        fn new(fail_at: Option<usize>) -> Self {
+            PairWrite {
+                fail_at,
+                calls: Mutex::new(Vec::new()),
+            }
+        }
+
+        fn file(entry: &JournalEntry, step: usize) -> PathBuf {
+            entry.path(["a", "b"][step]).unwrap().clone()
+        }
+    }
+
+    impl RecoveryHandler for PairWrite {
+        fn operation(&self) -> &'static str {
+            "pair_write"
+        }
+
+        fn can_roll_forward(&self, entry: &JournalEntry) -> bool {
+            entry.completed_steps >= 1
+        }
+
+        fn forward(&self, entry: &JournalEntry, step: usize) -> JournalResult<()> {
+            self.calls.lock().unwrap().push(format!("forward {}", step));
+            if self.fail_at == Some(step) {
+                return Err(std::io::Error::other("injected").into());
+            }
+            fs::write(Self::file(entry, step), "done")?;
+            Ok(())
+        }
+
+        fn backward(&self, entry: &JournalEntry, step: usize) -> JournalResult<()> {
+            self.calls.lock().unwrap().push(format!("backward {}", step));
+            let _ = fs::remove_file(Self::file(entry, step));
+            Ok(())
+        }
+    }
+
+    fn setup(dir: &Path) -> (LockedJsonStore<Journal>, JournalEntry) {
+        let store = LockedJsonStore::new(dir.join(JOURNAL_FILE_NAME));
+        let entry = JournalEntry::new("pair_write", "Survival/MySave", &["write_a", "write_b"])
+            .with_path("a", dir.join("a"))
+            .with_path("b", dir.join("b"));
+        (store, entry)
+    }
+
+    fn pending(store: &LockedJsonStore<Journal>) -> Vec<JournalEntry> {
+        store.load::<JournalError>().unwrap().entries
+    }
+
+    #[test]
+    fn test_completed_run_leaves_no_entry() {
+        let temp_dir = TempDir::new().unwrap();
+        let (store, entry) = setup(temp_dir.path());
+
+        run_journaled_in(&store, &PairWrite::new(None), entry, None).unwrap();
+
+        assert!(pending(&store).is_empty());
+        assert!(temp_dir.path().join("a").exists());
+        assert!(temp_dir.path().join("b").exists());
+    }
+
+    #[test]
+    fn test_step_error_rolls_back_immediately() {
+        let temp_dir = TempDir::new().unwrap();
+        let (store, entry) = setup(temp_dir.path());
+        let handler = PairWrite::new(Some(0));
+
+        assert!(run_journaled_in(&store, &handler, entry, None).is_err());
+
+        assert!(pending(&store).is_empty());
+        assert!(!temp_dir.path().join("a").exists());
+        assert_eq!(*handler.calls.lock().unwrap(), ["forward 0", "backward 0"]);
+    }
+
+    #[test]
+    fn test_step_error_past_roll_forward_point_is_left_to_recovery() {
+        let temp_dir = TempDir::new().unwrap();
+        let (store, entry) = setup(temp_dir.path());
+
+        assert!(run_journaled_in(&store, &PairWrite::new(Some(1)), entry, None).is_err());
+        assert_eq!(pending(&store)[0].completed_steps, 1);
+
+        let reports = recover_in(&store, &[&PairWrite::new(None)]).unwrap();
+        assert_eq!(reports[0].action, RecoveryAction::RolledForward);
+        assert!(temp_dir.path().join("b").exists());
+        assert!(pending(&store).is_empty());
+    }
+
+    #[test]
+    fn test_recovery_after_crash_at_every_step_is_consistent() {
+        for crash_after in 0..2 {
+            let temp_dir = TempDir::new().unwrap();
+            let (store, entry) = setup(temp_dir.path());
+            let handler = PairWrite::new(None);
+
+            assert!(run_journaled_in(&store, &handler, entry, Some(crash_after)).is_err());
+            let left = pending(&store);
+            assert_eq!(left.len(), 1);
+            assert_eq!(left[0].completed_steps, crash_after);
+
+            let reports = recover_in(&store, &[&handler]).unwrap();
+            assert_eq!(reports.len(), 1);
+            let expected = if crash_after == 0 {
+                RecoveryAction::RolledBack
+            } else {
+                RecoveryAction::RolledForward
+            };
+            assert_eq!(reports[0].action, expected);
+            assert!(pending(&store).is_empty());
+
+            // Neither or both files
+            let a = temp_dir.path().join("a").exists();
+            let b = temp_dir.path().join("b").exists();
+            assert_eq!(a, b, "inconsistent after crash at step {}", crash_after);
+        }
+    }
+
+    #[test]
+    fn test_unknown_operation_is_kept() {
+        let temp_dir = TempDir::new().unwrap();
+        let (store, entry) = setup(temp_dir.path());
+
+        assert!(run_journaled_in(&store, &PairWrite::new(None), entry, Some(1)).is_err());
+        let reports = recover_in(&store, &[]).unwrap();
+
+        assert_eq!(reports[0].action, RecoveryAction::Failed);
+        assert!(reports[0].error.as_ref().unwrap().contains("pair_write"));
+        assert_eq!(pending(&store).len(), 1);
+    }
+}
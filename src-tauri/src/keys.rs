@@ -0,0 +1,179 @@
+//! Canonical keys for saves and backups.
+//!
+//! Save relative paths and backup names are used as keys in tags.json, the dashboard
+//! and the history. The same save can reach the backend spelled differently (Windows
+//! separators, a trailing slash, NFD text from macOS file dialogs), so keys are only
+//! built through `SaveKey::new` / `BackupKey::new`, which normalize:
+//! - `\` separators to `/`, with repeated separators collapsed
+//! - No trailing separator
+//! - Unicode NFC
+//!
+//! Case is preserved: save folders on Linux are case-sensitive.
+
+use crate::unicode_nfc;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Normalizes a save path or backup name into its canonical key form.
+///
+/// # Arguments
+/// * `raw` - Path or name as received
+///
+/// # Returns
+/// `String` - Canonical form; normalizing it again returns it unchanged
+pub fn normalize(raw: &str) -> String {
+    let composed = unicode_nfc::nfc(raw);
+
+    let mut key = String::with_capacity(composed.len());
+    for c in composed.chars() {
+        let c = if c == '\\' { '/' } else { c };
+        if c == '/' && key.ends_with('/') {
+            continue;
+        }
+        key.push(c);
+    }
+
+    while key.ends_with('/') {
+        key.pop();
+    }
+    key
+}
+
+/// Defines a string newtype that can only hold normalized keys.
+macro_rules! canonical_key {
+    ($(#[$meta:meta])* $name:ident) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+        #[serde(from = "String", into = "String")]
+        pub struct $name(String);
+
+        impl $name {
+            /// Creates a key, normalizing the raw value.
+            pub fn new(raw: &str) -> Self {
+                $name(normalize(raw))
+            }
+
+            /// Returns the canonical string.
+            pub fn as_str(&self) -> &str {
+                &self.0
+            }
+        }
+
+        // Stored keys are renormalized on load
+        impl From<String> for $name {
+            fn from(raw: String) -> Self {
+                $name::new(&raw)
+            }
+        }
+
+        impl From<&str> for $name {
+            fn from(raw: &str) -> Self {
+                $name::new(raw)
+            }
+        }
+
+        impl From<$name> for String {
+            fn from(key: $name) -> Self {
+                key.0
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(&self.0)
+            }
+        }
+    };
+}
+
+canonical_key!(
+    /// Key for a save, from its relative path (e.g., "Survival/MySave").
+    SaveKey
+);
+
+canonical_key!(
+    /// Key for a backup, from its file name (e.g., "MySave_2024-12-28_14-30-45.tar.gz").
+    BackupKey
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Inputs covering separators, unicode forms and edge cases.
+    const SAMPLES: &[&str] = &[
+        "",
+        "/",
+        "Survival/MySave",
+        "Survival\\MySave\\",
+        "Survival//MySave///",
+        "\\\\server\\share\\Save",
+        "Caf\u{E9}",
+        "Cafe\u{301}/",
+        "\u{438}\u{306}\u{43B}",
+        "e\u{323}\u{302}\\x",
+        "\u{1100}\u{1161}\u{11A8}",
+        "\u{928}\u{93C}",
+        "\u{F900}",
+        "Mixed Case / With Spaces",
+        "trailing\\/\\/",
+    ];
+
+    #[test]
+    fn test_normalize_is_idempotent() {
+        for sample in SAMPLES {
+            let once = normalize(sample);
+            assert_eq!(normalize(&once), once, "not idempotent for {:?}", sample);
+        }
+
+        // Every combination of two samples, to cover separator and mark boundaries
+        for a in SAMPLES {
+            for b in SAMPLES {
+                let joined = format!("{}{}", a, b);
+                let once = normalize(&joined);
+                assert_eq!(normalize(&once), once, "not idempotent for {:?}", joined);
+            }
+        }
+    }
+
+    #[test]
+    fn test_known_problem_pairs_collapse() {
+        let pairs = [
+            ("Survival/MySave", "Survival\\MySave"),
+            ("Survival/MySave", "Survival/MySave/"),
+            ("Survival/MySave", "Survival\\\\MySave\\"),
+            ("Survival/Caf\u{E9}", "Survival/Cafe\u{301}"),
+            ("\u{439}", "\u{438}\u{306}"),
+            ("Survival/\u{929}", "Survival/\u{928}\u{93C}"),
+        ];
+        for (a, b) in pairs {
+            assert_eq!(SaveKey::new(a), SaveKey::new(b), "{:?} vs {:?}", a, b);
+        }
+
+        assert_eq!(
+            BackupKey::new("Caf\u{E9}_2024.tar.gz"),
+            BackupKey::new("Cafe\u{301}_2024.tar.gz")
+        );
+    }
+
+    #[test]
+    fn test_case_is_preserved() {
+        assert_ne!(SaveKey::new("Survival/MySave"), SaveKey::new("survival/mysave"));
+        assert_eq!(SaveKey::new("Survival\\MySave").as_str(), "Survival/MySave");
+    }
+
+    #[test]
+    fn test_whitespace_is_preserved() {
+        // "MySave " is a different folder from "MySave" on Linux and macOS
+        assert_ne!(SaveKey::new("Survival/MySave"), SaveKey::new("Survival/MySave "));
+        assert_ne!(SaveKey::new("Survival/MySave"), SaveKey::new(" Survival/MySave"));
+        assert_eq!(SaveKey::new("Survival/My Save/").as_str(), "Survival/My Save");
+    }
+
+    #[test]
+    fn test_deserialization_normalizes() {
+        let key: SaveKey = serde_json::from_str("\"Survival\\\\MySave\\\\\"").unwrap();
+        assert_eq!(key.as_str(), "Survival/MySave");
+        assert_eq!(serde_json::to_string(&key).unwrap(), "\"Survival/MySave\"");
+    }
+}
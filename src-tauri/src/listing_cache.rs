@@ -0,0 +1,302 @@
+//! In-memory cache of detailed backup listings.
+//!
+//! Assembling a listing reads every archive's metadata, joins its tags and
+//! extracts its thumbnail. The result is cached per save and keyed on a
+//! generation counter:
+//! - Backup mutations (create, delete, garbage collection) call `invalidate`
+//! - Tag changes reach the cache through a tags change hook, installed on first use
+//! - Changes made outside the app are caught by comparing the backup
+//!   directory's modification time
+//!
+//! Generations only grow during a run, so the frontend can compare the
+//! generation of a listing with the one it already shows.
+
+use crate::backup::{BackupInfo, BackupResultT};
+use crate::keys::SaveKey;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard, Once};
+use std::time::SystemTime;
+
+/// Detailed backup listing returned to the frontend.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupListing {
+    /// Generation of the save's backups when the listing was assembled
+    pub generation: u64,
+    /// Backups sorted by creation time (newest first)
+    pub backups: Vec<BackupInfo>,
+}
+
+/// A cached listing.
+#[derive(Debug, Clone)]
+struct CachedListing {
+    generation: u64,
+    dir_modified: Option<SystemTime>,
+    backups: Vec<BackupInfo>,
+}
+
+/// Generation counters and listings.
+///
+/// Listings are keyed by backup directory so that changing the backup path (or
+/// tests using separate directories) never serves another directory's listing.
+#[derive(Debug, Default)]
+struct ListingCache {
+    generations: HashMap<SaveKey, u64>,
+    listings: HashMap<PathBuf, CachedListing>,
+}
+
+static CACHE: Mutex<Option<ListingCache>> = Mutex::new(None);
+
+fn cache() -> MutexGuard<'static, Option<ListingCache>> {
+    CACHE.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn with_cache<R>(f: impl FnOnce(&mut ListingCache) -> R) -> R {
+    f(cache().get_or_insert_with(ListingCache::default))
+}
+
+/// Returns the current generation of a save's backups.
+pub fn generation(save_name: &str) -> u64 {
+    let key = SaveKey::new(save_name);
+    with_cache(|cache| cache.generations.get(&key).copied().unwrap_or_default())
+}
+
+/// Bumps a save's generation, so its cached listing is assembled again on the next request.
+///
+/// # Arguments
+/// * `save_name` - Relative path of the save whose backups or tags changed
+///
+/// # Returns
+/// `u64` - New generation
+pub fn invalidate(save_name: &str) -> u64 {
+    invalidate_key(SaveKey::new(save_name))
+}
+
+fn invalidate_key(key: SaveKey) -> u64 {
+    with_cache(|cache| {
+        let generation = cache.generations.entry(key).or_default();
+        *generation += 1;
+        *generation
+    })
+}
+
+/// Invalidates the listings of saves whose tags changed; the tags change hook.
+fn on_tags_changed(saves: &[SaveKey]) {
+    for save in saves {
+        invalidate_key(save.clone());
+    }
+}
+
+/// Registers `on_tags_changed` with the tags store, once.
+fn install_tags_hook() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| crate::tags::register_change_hook(on_tags_changed));
+}
+
+/// Returns the cached listing of a save, assembling it on a miss.
+///
+/// # Arguments
+/// * `save_name` - Relative path of the save
+/// * `save_backup_dir` - Backup directory of the save
+/// * `assemble` - Builds the full listing from disk
+///
+/// # Returns
+/// `BackupResultT<BackupListing>` - Listing with the generation it belongs to
+///
+/// # Behavior
+/// - Serves the cached listing if neither the generation nor the directory's
+///   modification time changed; a changed modification time bumps the generation
+/// - Otherwise assembles the listing; it is only cached if no invalidation
+///   happened while it was being assembled
+pub fn get_or_assemble(
+    save_name: &str,
+    save_backup_dir: &Path,
+    assemble: impl FnOnce() -> BackupResultT<Vec<BackupInfo>>,
+) -> BackupResultT<BackupListing> {
+    install_tags_hook();
+
+    let key = SaveKey::new(save_name);
+    let dir_modified = fs::metadata(save_backup_dir).and_then(|m| m.modified()).ok();
+
+    let (generation, cached) = with_cache(|cache| {
+        let generation = cache.generations.entry(key.clone()).or_default();
+        match cache.listings.get(save_backup_dir) {
+            Some(c) if c.generation == *generation && c.dir_modified == dir_modified => {
+                (*generation, Some(c.backups.clone()))
+            }
+            // Changed outside the app: the listing changes, so must the generation
+            Some(c) if c.generation == *generation => {
+                *generation += 1;
+                (*generation, None)
+            }
+            _ => (*generation, None),
+        }
+    });
+    if let Some(backups) = cached {
+        return Ok(BackupListing { generation, backups });
+    }
+
+    let backups = assemble()?;
+
+    with_cache(|cache| {
+        if cache.generations.get(&key).copied().unwrap_or_default() == generation {
+            cache.listings.insert(
+                save_backup_dir.to_path_buf(),
+                CachedListing {
+                    generation,
+                    dir_modified,
+                    backups: backups.clone(),
+                },
+            );
+        }
+    });
+
+    Ok(BackupListing { generation, backups })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backup::{self, BackupError};
+    use crate::tags;
+    use crate::test_support::TestEnv;
+    use std::cell::Cell;
+    use tempfile::TempDir;
+
+    fn info(name: &str) -> BackupInfo {
+        BackupInfo {
+            name: name.to_string(),
+            path: String::new(),
+            size_bytes: 0,
+            size_formatted: String::new(),
+            created_at: String::new(),
+            save_name: String::new(),
+            tags: Vec::new(),
+            thumb_data: None,
+            partial: false,
+            skipped_files: Vec::new(),
+            server_flush: None,
+        }
+    }
+
+    #[test]
+    fn test_hits_until_invalidated() {
+        let dir = TempDir::new().unwrap();
+        let assembled = Cell::new(0);
+        let assemble = || {
+            assembled.set(assembled.get() + 1);
+            Ok(vec![info("a.tar.gz")])
+        };
+
+        let first = get_or_assemble("Cache/Hits", dir.path(), assemble).unwrap();
+        let second = get_or_assemble("Cache/Hits", dir.path(), assemble).unwrap();
+        assert_eq!(assembled.get(), 1);
+        assert_eq!(first.generation, second.generation);
+
+        let bumped = invalidate("Cache\\Hits\\");
+        let third = get_or_assemble("Cache/Hits", dir.path(), assemble).unwrap();
+        assert_eq!(assembled.get(), 2);
+        assert_eq!(third.generation, bumped);
+        assert!(third.generation > first.generation);
+    }
+
+    #[test]
+    fn test_invalidation_during_assembly_is_not_cached() {
+        let dir = TempDir::new().unwrap();
+        let racing = get_or_assemble("Cache/Race", dir.path(), || {
+            invalidate("Cache/Race");
+            Ok(vec![info("stale.tar.gz")])
+        })
+        .unwrap();
+
+        let fresh = get_or_assemble("Cache/Race", dir.path(), || Ok(vec![info("fresh.tar.gz")])).unwrap();
+        assert_eq!(fresh.backups[0].name, "fresh.tar.gz");
+        assert!(fresh.generation > racing.generation);
+    }
+
+    #[test]
+    fn test_errors_are_not_cached() {
+        let dir = TempDir::new().unwrap();
+        let failed = get_or_assemble("Cache/Errors", dir.path(), || {
+            Err(BackupError::SaveNotFound("x".to_string()))
+        });
+        assert!(failed.is_err());
+
+        let listing = get_or_assemble("Cache/Errors", dir.path(), || Ok(vec![info("a.tar.gz")])).unwrap();
+        assert_eq!(listing.backups.len(), 1);
+    }
+
+    #[test]
+    fn test_external_change_bumps_generation() {
+        let dir = TempDir::new().unwrap();
+        let first = get_or_assemble("Cache/External", dir.path(), || Ok(Vec::new())).unwrap();
+
+        // Added by another program: only the directory's modification time changes
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        fs::write(dir.path().join("copied.tar.gz"), b"x").unwrap();
+
+        let second = get_or_assemble("Cache/External", dir.path(), || Ok(vec![info("copied.tar.gz")])).unwrap();
+        assert_eq!(second.backups.len(), 1);
+        assert!(second.generation > first.generation);
+    }
+
+    /// Creates a save under the env's save directory and one backup of it.
+    fn save_with_backup(env: &TestEnv, save_name: &str) -> String {
+        let save_dir = env.save_dir().join(save_name);
+        fs::create_dir_all(save_dir.join("map")).unwrap();
+        fs::write(save_dir.join("save.bin"), b"game state").unwrap();
+        backup::create_backup(save_name).unwrap().backup_name
+    }
+
+    #[test]
+    fn test_tag_changes_invalidate_listing() {
+        let env = TestEnv::with_paths();
+        let backup_name = save_with_backup(&env, "Cache/Tagged");
+        let other_backup = save_with_backup(&env, "Cache/Untagged");
+        tags::create_tag("keep".to_string(), "#00FF00".to_string(), None, None).unwrap();
+
+        let before = backup::list_backups_cached("Cache/Tagged").unwrap();
+        let other_before = backup::list_backups_cached("Cache/Untagged").unwrap();
+        assert!(before.backups[0].tags.is_empty());
+
+        tags::add_tags_to_backup("Cache/Tagged", &backup_name, vec!["keep".to_string()]).unwrap();
+        let tagged = backup::list_backups_cached("Cache/Tagged").unwrap();
+        assert!(tagged.generation > before.generation);
+        assert_eq!(tagged.backups[0].tags[0].name, "keep");
+
+        // Saves whose tags didn't change keep their generation
+        let other = backup::list_backups_cached("Cache/Untagged").unwrap();
+        assert_eq!(other.generation, other_before.generation);
+        assert_eq!(other.backups[0].name, other_backup);
+
+        // Deleting the tag definition changes every listing that showed it
+        tags::delete_tag("keep".to_string()).unwrap();
+        let untagged = backup::list_backups_cached("Cache/Tagged").unwrap();
+        assert!(untagged.generation > tagged.generation);
+        assert!(untagged.backups[0].tags.is_empty());
+    }
+
+    #[test]
+    fn test_backup_mutations_invalidate_listing_and_tags() {
+        let env = TestEnv::with_paths();
+        let backup_name = save_with_backup(&env, "Cache/Mutated");
+        tags::create_tag("old".to_string(), "#FF0000".to_string(), None, None).unwrap();
+        tags::add_tags_to_backup("Cache/Mutated", &backup_name, vec!["old".to_string()]).unwrap();
+
+        let listed = backup::list_backups_cached("Cache/Mutated").unwrap();
+        assert_eq!(listed.backups.len(), 1);
+
+        backup::delete_backup("Cache/Mutated", &backup_name).unwrap();
+        let after_delete = backup::list_backups_cached("Cache/Mutated").unwrap();
+        assert!(after_delete.generation > listed.generation);
+        assert!(after_delete.backups.is_empty());
+        assert!(tags::get_backup_tags("Cache/Mutated", &backup_name).unwrap().is_empty());
+
+        save_with_backup(&env, "Cache/Mutated");
+        let after_create = backup::list_backups_cached("Cache/Mutated").unwrap();
+        assert!(after_create.generation > after_delete.generation);
+        assert_eq!(after_create.backups.len(), 1);
+    }
+}
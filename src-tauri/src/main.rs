@@ -2,5 +2,9 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 fn main() {
+    // Subcommands run headless and exit without creating a window
+    if let Some(code) = pz_backup_tool_lib::cli::run_from_env() {
+        std::process::exit(code);
+    }
     pz_backup_tool_lib::run()
 }
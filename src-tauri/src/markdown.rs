@@ -0,0 +1,936 @@
+//! Renders release notes from GitHub-flavored markdown to HTML for the update dialog.
+//!
+//! Release notes are written by whoever controls the release source, so the output is
+//! sanitized by construction rather than filtered afterwards: all text is escaped, only
+//! `ALLOWED_TAGS` are produced, raw HTML in the markdown is reduced to
+//! `INLINE_HTML_TAGS` without attributes (`<script>` and `<style>` are dropped with
+//! their content), and links only point to `http`, `https` and `mailto` URLs.
+//!
+//! It covers what release notes use: ATX and setext headings, paragraphs, bullet,
+//! numbered and task lists (nested by indentation), block quotes, fenced and indented
+//! code, rules, emphasis, strikethrough, inline code, links and bare URLs. Tables are
+//! left as text and images become links to the image.
+
+/// Every tag the renderer produces.
+pub const ALLOWED_TAGS: &[&str] = &[
+    "a",
+    "blockquote",
+    "br",
+    "code",
+    "del",
+    "em",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "hr",
+    "kbd",
+    "li",
+    "ol",
+    "p",
+    "pre",
+    "strong",
+    "sub",
+    "sup",
+    "ul",
+];
+
+/// Raw HTML tags kept from the markdown, without attributes, as `(tag, rendered as)`.
+const INLINE_HTML_TAGS: &[(&str, &str)] = &[
+    ("b", "strong"),
+    ("br", "br"),
+    ("code", "code"),
+    ("del", "del"),
+    ("em", "em"),
+    ("i", "em"),
+    ("kbd", "kbd"),
+    ("s", "del"),
+    ("strong", "strong"),
+    ("sub", "sub"),
+    ("sup", "sup"),
+];
+
+/// Raw HTML elements dropped together with their content.
+const DROPPED_HTML_ELEMENTS: &[&str] = &["script", "style"];
+
+/// URL schemes links may use.
+const ALLOWED_URL_SCHEMES: &[&str] = &["http", "https", "mailto"];
+
+/// Lists, quotes, links and emphasis nested deeper than this are rendered as text.
+const MAX_NESTING: usize = 16;
+
+/// Block of a markdown document.
+#[derive(Debug)]
+enum Block {
+    Heading(usize, String),
+    Paragraph(String),
+    Code(String),
+    Quote(Vec<Block>),
+    List {
+        /// Number of the first item of a numbered list; None for bullets
+        start: Option<u64>,
+        items: Vec<Vec<Block>>,
+        /// No blank lines between items, so their paragraphs aren't wrapped in `<p>`
+        tight: bool,
+    },
+    Rule,
+}
+
+/// List item marker at the start of a line.
+#[derive(Debug, Clone, Copy)]
+struct ListMarker {
+    /// Number of a numbered item
+    number: Option<u64>,
+    /// Bullet character, or the delimiter after the number ('.' or ')')
+    kind: char,
+    /// Bytes up to the item content, including the spaces after the marker
+    width: usize,
+}
+
+/// Renders markdown to sanitized HTML.
+///
+/// # Arguments
+/// * `markdown` - GitHub-flavored markdown, e.g. a release body
+///
+/// # Returns
+/// `String` - HTML using only `ALLOWED_TAGS`, safe to insert into the page
+pub fn render_html(markdown: &str) -> String {
+    let normalized = markdown.replace("\r\n", "\n").replace('\r', "\n").replace('\t', "    ");
+    let lines: Vec<&str> = normalized.lines().collect();
+    let mut html = String::with_capacity(markdown.len() * 2);
+    render_blocks(&parse_blocks(&lines, 0), false, &mut html);
+    html
+}
+
+/// Splits a line into its indentation in spaces and the rest.
+fn split_indent(line: &str) -> (usize, &str) {
+    let rest = line.trim_start_matches(' ');
+    (line.len() - rest.len(), rest)
+}
+
+/// Removes up to `indent` leading spaces.
+fn strip_indent(line: &str, indent: usize) -> &str {
+    let (spaces, _) = split_indent(line);
+    &line[spaces.min(indent)..]
+}
+
+fn parse_blocks(lines: &[&str], depth: usize) -> Vec<Block> {
+    let mut blocks = Vec::new();
+    let mut i = 0;
+
+    while i < lines.len() {
+        let (indent, rest) = split_indent(lines[i]);
+        if rest.is_empty() {
+            i += 1;
+            continue;
+        }
+
+        if indent >= 4 {
+            let mut code = Vec::new();
+            while i < lines.len() {
+                let (indent, rest) = split_indent(lines[i]);
+                if !rest.is_empty() && indent < 4 {
+                    break;
+                }
+                code.push(strip_indent(lines[i], 4));
+                i += 1;
+            }
+            while code.last().is_some_and(|line| line.trim().is_empty()) {
+                code.pop();
+            }
+            blocks.push(Block::Code(code.join("\n")));
+            continue;
+        }
+
+        if let Some((fence, fence_len)) = fence_start(rest) {
+            let mut code = Vec::new();
+            i += 1;
+            while i < lines.len() {
+                let closing = lines[i].trim();
+                if closing.len() >= fence_len && closing.chars().all(|c| c == fence) {
+                    i += 1;
+                    break;
+                }
+                code.push(strip_indent(lines[i], indent));
+                i += 1;
+            }
+            blocks.push(Block::Code(code.join("\n")));
+            continue;
+        }
+
+        if let Some((level, text)) = atx_heading(rest) {
+            blocks.push(Block::Heading(level, text.to_string()));
+            i += 1;
+            continue;
+        }
+
+        if is_rule(rest) {
+            blocks.push(Block::Rule);
+            i += 1;
+            continue;
+        }
+
+        if depth < MAX_NESTING && rest.starts_with('>') {
+            let mut quoted = Vec::new();
+            while i < lines.len() {
+                let (indent, rest) = split_indent(lines[i]);
+                let Some(inner) = rest.strip_prefix('>').filter(|_| indent < 4) else {
+                    break;
+                };
+                quoted.push(inner.strip_prefix(' ').unwrap_or(inner));
+                i += 1;
+            }
+            blocks.push(Block::Quote(parse_blocks(&quoted, depth + 1)));
+            continue;
+        }
+
+        if let Some(marker) = list_marker(rest).filter(|_| depth < MAX_NESTING) {
+            let (block, end) = parse_list(lines, i, marker, depth);
+            blocks.push(block);
+            i = end;
+            continue;
+        }
+
+        // Paragraph, up to a blank line or the start of another block
+        let mut text = vec![rest.trim()];
+        i += 1;
+        let mut heading = None;
+        while i < lines.len() {
+            let (indent, rest) = split_indent(lines[i]);
+            if rest.is_empty() {
+                break;
+            }
+            if indent < 4 {
+                let underline = rest.trim_end();
+                if underline.chars().all(|c| c == '=') {
+                    heading = Some(1);
+                } else if underline.chars().all(|c| c == '-') {
+                    heading = Some(2);
+                }
+                if heading.is_some() {
+                    i += 1;
+                    break;
+                }
+                if interrupts_paragraph(rest) {
+                    break;
+                }
+            }
+            text.push(rest.trim());
+            i += 1;
+        }
+        let text = text.join("\n");
+        blocks.push(match heading {
+            Some(level) => Block::Heading(level, text),
+            None => Block::Paragraph(text),
+        });
+    }
+    blocks
+}
+
+/// Parses the list starting at `lines[start]`; returns it and the index after it.
+fn parse_list(lines: &[&str], start: usize, marker: ListMarker, depth: usize) -> (Block, usize) {
+    let (indent, rest) = split_indent(lines[start]);
+    let mut content_indent = indent + marker.width;
+    let mut items = vec![vec![rest.get(marker.width..).unwrap_or("")]];
+    let mut tight = true;
+    let mut blank = false;
+    let mut i = start + 1;
+
+    while i < lines.len() {
+        let line = lines[i];
+        let (indent, rest) = split_indent(line);
+        let item = items.last_mut().expect("a list has an item");
+        if rest.is_empty() {
+            blank = true;
+            item.push("");
+        } else if indent >= content_indent {
+            tight &= !blank;
+            item.push(&line[content_indent..]);
+            blank = false;
+        } else if let Some(next) = list_marker(rest).filter(|next| indent < 4 && next.kind == marker.kind) {
+            tight &= !blank;
+            content_indent = indent + next.width;
+            items.push(vec![rest.get(next.width..).unwrap_or("")]);
+            blank = false;
+        } else if !blank && !interrupts_paragraph(rest) {
+            // Lazy continuation of the item's paragraph
+            item.push(rest);
+        } else {
+            break;
+        }
+        i += 1;
+    }
+
+    let items = items
+        .iter()
+        .map(|item| {
+            let mut blocks = parse_blocks(item, depth + 1);
+            if let Some(Block::Paragraph(text)) = blocks.first_mut() {
+                mark_task(text);
+            }
+            blocks
+        })
+        .collect();
+    (
+        Block::List {
+            start: marker.number,
+            items,
+            tight,
+        },
+        i,
+    )
+}
+
+/// Replaces the `[ ]` / `[x]` of a task list item with a check box character.
+fn mark_task(text: &mut String) {
+    for (task, mark) in [("[ ] ", "\u{2610} "), ("[x] ", "\u{2611} "), ("[X] ", "\u{2611} ")] {
+        if text.starts_with(task) {
+            text.replace_range(..task.len(), mark);
+            return;
+        }
+    }
+}
+
+/// Fence character and length of a line opening a fenced code block.
+fn fence_start(rest: &str) -> Option<(char, usize)> {
+    let fence = rest.chars().next().filter(|c| matches!(c, '`' | '~'))?;
+    let len = rest.chars().take_while(|&c| c == fence).count();
+    // The info string of a backtick fence can't hold backticks
+    let valid = len >= 3 && (fence == '~' || !rest[len..].contains('`'));
+    valid.then_some((fence, len))
+}
+
+/// Level and text of an ATX heading (`## Title ##`).
+fn atx_heading(rest: &str) -> Option<(usize, &str)> {
+    let level = rest.chars().take_while(|&c| c == '#').count();
+    let text = &rest[level..];
+    if !(1..=6).contains(&level) || !(text.is_empty() || text.starts_with(' ')) {
+        return None;
+    }
+    let text = text.trim();
+    let without_closing = text.trim_end_matches('#');
+    if without_closing.is_empty() || without_closing.ends_with(' ') {
+        return Some((level, without_closing.trim_end()));
+    }
+    Some((level, text))
+}
+
+/// True for a thematic break: three or more `-`, `*` or `_`, optionally spaced.
+fn is_rule(rest: &str) -> bool {
+    let mut chars = rest.chars().filter(|c| *c != ' ');
+    let Some(first) = chars.next().filter(|c| matches!(c, '-' | '*' | '_')) else {
+        return false;
+    };
+    let mut count = 1;
+    for c in chars {
+        if c != first {
+            return false;
+        }
+        count += 1;
+    }
+    count >= 3
+}
+
+fn list_marker(rest: &str) -> Option<ListMarker> {
+    let bytes = rest.as_bytes();
+    let (number, marker_len) = match bytes.first()? {
+        b'-' | b'*' | b'+' => (None, 1),
+        b'0'..=b'9' => {
+            let digits = bytes.iter().take_while(|b| b.is_ascii_digit()).count();
+            if digits > 9 || !matches!(bytes.get(digits), Some(b'.' | b')')) {
+                return None;
+            }
+            (rest[..digits].parse().ok(), digits + 1)
+        }
+        _ => return None,
+    };
+    let after = &rest[marker_len..];
+    if !(after.is_empty() || after.starts_with(' ')) {
+        return None;
+    }
+    let spaces = split_indent(after).0;
+    // Content indented 5+ spaces past the marker is indented code; only one space counts
+    let width = marker_len + if (1..=4).contains(&spaces) { spaces } else { 1 };
+    Some(ListMarker {
+        number,
+        kind: rest[marker_len - 1..].chars().next()?,
+        width: width.min(rest.len()),
+    })
+}
+
+/// True if a line starts a block that ends a preceding paragraph.
+fn interrupts_paragraph(rest: &str) -> bool {
+    rest.starts_with('>')
+        || fence_start(rest).is_some()
+        || atx_heading(rest).is_some()
+        || is_rule(rest)
+        || list_marker(rest).is_some_and(|marker| {
+            marker.number.is_none_or(|number| number == 1) && !rest[marker.width..].trim().is_empty()
+        })
+}
+
+fn render_blocks(blocks: &[Block], tight: bool, html: &mut String) {
+    for block in blocks {
+        match block {
+            Block::Heading(level, text) => {
+                html.push_str(&format!("<h{}>{}</h{}>", level, render_inline(text, 0), level));
+            }
+            Block::Paragraph(text) if tight => html.push_str(&render_inline(text, 0)),
+            Block::Paragraph(text) => {
+                html.push_str("<p>");
+                html.push_str(&render_inline(text, 0));
+                html.push_str("</p>");
+            }
+            Block::Code(code) => {
+                html.push_str("<pre><code>");
+                html.push_str(&escape(code));
+                html.push_str("</code></pre>");
+            }
+            Block::Quote(inner) => {
+                html.push_str("<blockquote>\n");
+                render_blocks(inner, false, html);
+                html.push_str("</blockquote>");
+            }
+            Block::List { start, items, tight } => {
+                let tag = match start {
+                    Some(1) => "<ol>".to_string(),
+                    Some(number) => format!("<ol start=\"{}\">", number),
+                    None => "<ul>".to_string(),
+                };
+                html.push_str(&tag);
+                html.push('\n');
+                for item in items {
+                    html.push_str("<li>");
+                    render_blocks(item, *tight, html);
+                    // Blocks end with a newline; keep `<li>text</li>` on one line
+                    if html.ends_with('\n') {
+                        html.pop();
+                    }
+                    html.push_str("</li>\n");
+                }
+                html.push_str(if start.is_some() { "</ol>" } else { "</ul>" });
+            }
+            Block::Rule => html.push_str("<hr>"),
+        }
+        html.push('\n');
+    }
+}
+
+/// Escapes text for HTML content and attribute values.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Returns the URL if links may point to it.
+fn safe_url(url: &str) -> Option<&str> {
+    let url = url.trim();
+    let (scheme, _) = url.split_once(':')?;
+    let allowed = ALLOWED_URL_SCHEMES
+        .iter()
+        .any(|allowed| scheme.eq_ignore_ascii_case(allowed));
+    (allowed && !url.chars().any(|c| c.is_control() || c.is_whitespace())).then_some(url)
+}
+
+fn render_inline(text: &str, depth: usize) -> String {
+    let mut inline = Inline {
+        html: String::with_capacity(text.len()),
+        depth,
+        in_link: false,
+        open_tags: Vec::new(),
+    };
+    inline.render(text);
+    inline.finish()
+}
+
+/// Renders the inline content of one block.
+struct Inline {
+    html: String,
+    depth: usize,
+    /// Inside a link label, where links and bare URLs aren't linked again
+    in_link: bool,
+    /// Raw HTML tags opened and not closed yet, innermost last
+    open_tags: Vec<&'static str>,
+}
+
+impl Inline {
+    /// Renders nested content (emphasis, link labels) in a renderer of its own.
+    fn nested(&self, text: &str, in_link: bool) -> String {
+        if self.depth >= MAX_NESTING {
+            return escape(text);
+        }
+        let mut inline = Inline {
+            html: String::with_capacity(text.len()),
+            depth: self.depth + 1,
+            in_link,
+            open_tags: Vec::new(),
+        };
+        inline.render(text);
+        inline.finish()
+    }
+
+    /// Closes the raw HTML tags left open, so the output stays balanced.
+    fn finish(mut self) -> String {
+        while let Some(tag) = self.open_tags.pop() {
+            self.html.push_str(&format!("</{}>", tag));
+        }
+        self.html
+    }
+
+    fn render(&mut self, text: &str) {
+        let mut prev: Option<char> = None;
+        let mut rest = text;
+
+        while let Some(c) = rest.chars().next() {
+            let consumed = match c {
+                '\\' => self.backslash(rest),
+                '`' => Some(self.code_span(rest)),
+                '<' => self.raw_html(rest),
+                '[' | '!' if !self.in_link => self.link(rest),
+                '*' | '_' | '~' => self.emphasis(rest, prev),
+                'h' | 'H' if !self.in_link && prev.is_none_or(|p| p.is_whitespace() || "(*_~".contains(p)) => {
+                    self.bare_url(rest)
+                }
+                '\n' => {
+                    self.html.push_str("<br>\n");
+                    Some(1)
+                }
+                _ => None,
+            };
+            let consumed = consumed.unwrap_or_else(|| {
+                self.html.push_str(&escape(&rest[..c.len_utf8()]));
+                c.len_utf8()
+            });
+            prev = rest[..consumed].chars().next_back();
+            rest = &rest[consumed..];
+        }
+    }
+
+    fn backslash(&mut self, rest: &str) -> Option<usize> {
+        match rest[1..].chars().next()? {
+            '\n' => self.html.push_str("<br>\n"),
+            c if c.is_ascii_punctuation() => self.html.push_str(&escape(&rest[1..2])),
+            _ => return None,
+        }
+        Some(2)
+    }
+
+    /// Renders a code span, or the opening backticks if they are never closed.
+    fn code_span(&mut self, rest: &str) -> usize {
+        let ticks = rest.chars().take_while(|&c| c == '`').count();
+        let mut search = ticks;
+        while let Some(found) = rest[search..].find('`') {
+            let start = search + found;
+            let run = rest[start..].chars().take_while(|&c| c == '`').count();
+            if run == ticks {
+                let code = rest[ticks..start].replace('\n', " ");
+                let stripped = code
+                    .strip_prefix(' ')
+                    .and_then(|code| code.strip_suffix(' '))
+                    .filter(|code| !code.trim().is_empty())
+                    .unwrap_or(&code);
+                self.html.push_str("<code>");
+                self.html.push_str(&escape(stripped));
+                self.html.push_str("</code>");
+                return start + run;
+            }
+            search = start + run;
+        }
+        self.html.push_str(&rest[..ticks]);
+        ticks
+    }
+
+    /// Handles `<`: autolinks, comments and raw HTML tags.
+    fn raw_html(&mut self, rest: &str) -> Option<usize> {
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            return comment.find("-->").map(|end| 4 + end + 3);
+        }
+
+        let end = rest.find('>')?;
+        let inner = &rest[1..end];
+        if !self.in_link && !inner.is_empty() && !inner.contains(|c: char| c.is_whitespace() || c == '<') {
+            let url = if inner.contains(':') {
+                safe_url(inner).map(str::to_string)
+            } else if is_email(inner) {
+                Some(format!("mailto:{}", inner))
+            } else {
+                None
+            };
+            if let Some(url) = url {
+                self.push_link(&url, &escape(inner));
+                return Some(end + 1);
+            }
+        }
+
+        let (closing, name_start) = match inner.strip_prefix('/') {
+            Some(_) => (true, 2),
+            None => (false, 1),
+        };
+        let name_len = rest[name_start..]
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric() || *c == '-')
+            .count();
+        let name = rest[name_start..name_start + name_len].to_ascii_lowercase();
+        let after_name = rest[name_start + name_len..].chars().next();
+        if !name.starts_with(|c: char| c.is_ascii_alphabetic())
+            || !after_name.is_some_and(|c| c.is_whitespace() || c == '/' || c == '>')
+        {
+            return None;
+        }
+        let tag_end = tag_end(rest)?;
+
+        if DROPPED_HTML_ELEMENTS.contains(&name.as_str()) {
+            if closing {
+                return Some(tag_end);
+            }
+            // Drop everything up to and including the closing tag, or the rest if unclosed
+            let lower = rest.to_ascii_lowercase();
+            let close = format!("</{}", name);
+            return Some(match lower[tag_end..].find(&close) {
+                Some(found) => {
+                    let close_start = tag_end + found;
+                    close_start + rest[close_start..].find('>').map_or(rest.len() - close_start, |e| e + 1)
+                }
+                None => rest.len(),
+            });
+        }
+
+        if let Some(&(_, tag)) = INLINE_HTML_TAGS.iter().find(|(raw, _)| *raw == name) {
+            if tag == "br" {
+                self.html.push_str("<br>");
+            } else if !closing {
+                self.html.push_str(&format!("<{}>", tag));
+                self.open_tags.push(tag);
+            } else if self.open_tags.last() == Some(&tag) {
+                self.open_tags.pop();
+                self.html.push_str(&format!("</{}>", tag));
+            }
+        }
+        // Other tags are dropped, their content stays as text
+        Some(tag_end)
+    }
+
+    /// Renders `[label](url)` and `![alt](url)`; images become links.
+    fn link(&mut self, rest: &str) -> Option<usize> {
+        let label_start = if rest.starts_with('!') { 2 } else { 1 };
+        if !rest[label_start - 1..].starts_with('[') {
+            return None;
+        }
+        let label_end = label_start + matching_bracket(&rest[label_start..])?;
+        let label = &rest[label_start..label_end];
+        let (url, len) = link_destination(&rest[label_end + 1..])?;
+        let consumed = label_end + 1 + len;
+
+        let label_html = if label.is_empty() {
+            escape(&url)
+        } else {
+            self.nested(label, true)
+        };
+        match safe_url(&url) {
+            Some(url) => self.push_link(url, &label_html),
+            // Unsafe links keep their text
+            None => self.html.push_str(&label_html),
+        }
+        Some(consumed)
+    }
+
+    fn emphasis(&mut self, rest: &str, prev: Option<char>) -> Option<usize> {
+        let delimiter = rest.chars().next()?;
+        let run = rest.chars().take_while(|&c| c == delimiter).count();
+        let opens = rest[run..].chars().next().is_some_and(|c| !c.is_whitespace())
+            && (delimiter != '_' || !prev.is_some_and(char::is_alphanumeric));
+        if !opens {
+            return None;
+        }
+
+        let candidates: &[(usize, &str)] = match delimiter {
+            '~' if run == 2 => &[(2, "del")],
+            '~' => &[],
+            _ if run >= 2 => &[(2, "strong"), (1, "em")],
+            _ => &[(1, "em")],
+        };
+        for &(len, tag) in candidates {
+            let inner_start = len;
+            if let Some(close) = find_closer(&rest[inner_start..], delimiter, len) {
+                let inner = &rest[inner_start..inner_start + close];
+                let inner_html = self.nested(inner, self.in_link);
+                self.html.push_str(&format!("<{}>{}</{}>", tag, inner_html, tag));
+                return Some(inner_start + close + len);
+            }
+        }
+        None
+    }
+
+    /// Links a bare `http://` or `https://` URL.
+    fn bare_url(&mut self, rest: &str) -> Option<usize> {
+        let lower = rest.get(..8)?.to_ascii_lowercase();
+        let scheme_len = if lower.starts_with("https://") {
+            8
+        } else if lower.starts_with("http://") {
+            7
+        } else {
+            return None;
+        };
+        let end = rest
+            .find(|c: char| c.is_whitespace() || c == '<')
+            .unwrap_or(rest.len());
+        let mut url = &rest[..end];
+        loop {
+            let trimmed = url.trim_end_matches(|c: char| ".,:;!?\"'*_~".contains(c));
+            let unbalanced = trimmed.ends_with(')') && trimmed.matches('(').count() < trimmed.matches(')').count();
+            let trimmed = if unbalanced { &trimmed[..trimmed.len() - 1] } else { trimmed };
+            if trimmed.len() == url.len() {
+                break;
+            }
+            url = trimmed;
+        }
+        if url.len() <= scheme_len {
+            return None;
+        }
+        let url = safe_url(url)?;
+        self.push_link(url, &escape(url));
+        Some(url.len())
+    }
+
+    fn push_link(&mut self, url: &str, label_html: &str) {
+        self.html.push_str(&format!("<a href=\"{}\">{}</a>", escape(url), label_html));
+    }
+}
+
+/// Byte index of the `>` ending a tag, skipping quoted attribute values.
+fn tag_end(rest: &str) -> Option<usize> {
+    let mut quote = None;
+    for (i, c) in rest.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), c) if c == q => quote = None,
+            (None, '>') => return Some(i + 1),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Index of the `]` closing a link label that starts at the beginning of `text`.
+fn matching_bracket(text: &str) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut escaped = false;
+    for (i, c) in text.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '[' => depth += 1,
+            ']' if depth == 0 => return Some(i),
+            ']' => depth -= 1,
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Parses `(url "title")` after a link label; returns the URL and the bytes used.
+fn link_destination(text: &str) -> Option<(String, usize)> {
+    let inner = text.strip_prefix('(')?;
+    let trimmed = inner.trim_start();
+    let mut offset = 1 + inner.len() - trimmed.len();
+
+    let url = if let Some(angled) = trimmed.strip_prefix('<') {
+        let end = angled.find(['>', '\n'])?;
+        if !angled[end..].starts_with('>') {
+            return None;
+        }
+        offset += end + 2;
+        angled[..end].to_string()
+    } else {
+        let mut depth = 0usize;
+        let mut end = trimmed.len();
+        for (i, c) in trimmed.char_indices() {
+            match c {
+                c if c.is_whitespace() => {
+                    end = i;
+                    break;
+                }
+                '(' => depth += 1,
+                ')' if depth == 0 => {
+                    end = i;
+                    break;
+                }
+                ')' => depth -= 1,
+                _ => {}
+            }
+        }
+        offset += end;
+        trimmed[..end].to_string()
+    };
+
+    let after = &text[offset..];
+    let after_trimmed = after.trim_start();
+    offset += after.len() - after_trimmed.len();
+    let title_close = match after_trimmed.chars().next()? {
+        '"' => Some('"'),
+        '\'' => Some('\''),
+        '(' => Some(')'),
+        _ => None,
+    };
+    if let Some(close) = title_close {
+        let title_end = after_trimmed[1..].find(close)? + 2;
+        let after_title = &after_trimmed[title_end..];
+        let after_title_trimmed = after_title.trim_start();
+        offset += title_end + after_title.len() - after_title_trimmed.len();
+    }
+
+    text[offset..].starts_with(')').then_some((url, offset + 1))
+}
+
+/// Offset of the delimiter run of length `len` closing emphasis in `text`.
+///
+/// The closer must follow a non-space character and not be part of a longer run of
+/// the delimiter; an `_` closer must not be followed by a letter or digit.
+fn find_closer(text: &str, delimiter: char, len: usize) -> Option<usize> {
+    let mut search = 0;
+    while let Some(found) = text[search..].find(delimiter) {
+        let start = search + found;
+        let run = text[start..].chars().take_while(|&c| c == delimiter).count();
+        let before = text[..start].chars().next_back();
+        let after = text[start + run..].chars().next();
+        if start > 0
+            && run == len
+            && before.is_some_and(|c| !c.is_whitespace())
+            && (delimiter != '_' || !after.is_some_and(char::is_alphanumeric))
+        {
+            return Some(start);
+        }
+        search = start + run;
+    }
+    None
+}
+
+/// True for a plausible email address in an autolink (`<user@example.com>`).
+fn is_email(text: &str) -> bool {
+    let Some((user, domain)) = text.split_once('@') else {
+        return false;
+    };
+    let valid = |part: &str, extra: &str| {
+        !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric() || extra.contains(c))
+    };
+    valid(user, ".!#$%&'*+/=?^_`{|}~-") && valid(domain, ".-") && domain.contains('.')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Names of all tags in rendered HTML.
+    fn tags(html: &str) -> Vec<String> {
+        html.split('<')
+            .skip(1)
+            .map(|tag| {
+                tag.trim_start_matches('/')
+                    .chars()
+                    .take_while(|c| c.is_ascii_alphanumeric())
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_renders_headings_lists_and_links() {
+        let html = render_html(
+            "## What's Changed\n\n\
+             * Fix **restore** of `Multiplayer` saves by @someone in https://github.com/o/r/pull/12.\n\
+             * Add [docs](https://example.com/docs \"Docs\")\n  \
+               1. nested\n  \
+               2. items\n\n\
+             **Full Changelog**: https://github.com/o/r/compare/v1.0.0...v1.1.0",
+        );
+
+        assert_eq!(
+            html,
+            "<h2>What&#39;s Changed</h2>\n\
+             <ul>\n\
+             <li>Fix <strong>restore</strong> of <code>Multiplayer</code> saves by @someone in \
+             <a href=\"https://github.com/o/r/pull/12\">https://github.com/o/r/pull/12</a>.</li>\n\
+             <li>Add <a href=\"https://example.com/docs\">docs</a>\n\
+             <ol>\n<li>nested</li>\n<li>items</li>\n</ol></li>\n\
+             </ul>\n\
+             <p><strong>Full Changelog</strong>: \
+             <a href=\"https://github.com/o/r/compare/v1.0.0...v1.1.0\">https://github.com/o/r/compare/v1.0.0...v1.1.0</a></p>\n"
+        );
+    }
+
+    #[test]
+    fn test_renders_other_blocks() {
+        let html = render_html(
+            "Title\n===\n\n> quoted *note*\n\n```rust\nlet x = 1 < 2;\n```\n\n---\n\n3. third\n4. fourth\n\n- [x] done\n- [ ] todo",
+        );
+
+        assert!(html.contains("<h1>Title</h1>"), "{}", html);
+        assert!(html.contains("<blockquote>\n<p>quoted <em>note</em></p>\n</blockquote>"), "{}", html);
+        assert!(html.contains("<pre><code>let x = 1 &lt; 2;</code></pre>"), "{}", html);
+        assert!(html.contains("<hr>"), "{}", html);
+        assert!(html.contains("<ol start=\"3\">\n<li>third</li>"), "{}", html);
+        assert!(html.contains("<li>\u{2611} done</li>\n<li>\u{2610} todo</li>"), "{}", html);
+    }
+
+    #[test]
+    fn test_emphasis_rules() {
+        assert_eq!(render_html("snake_case_name"), "<p>snake_case_name</p>\n");
+        assert_eq!(render_html("*a **b** c*"), "<p><em>a <strong>b</strong> c</em></p>\n");
+        assert_eq!(render_html("~~gone~~ 2 * 3 * 4"), "<p><del>gone</del> 2 * 3 * 4</p>\n");
+        assert_eq!(render_html("line one\nline two"), "<p>line one<br>\nline two</p>\n");
+        assert_eq!(render_html("\\*not emphasis\\*"), "<p>*not emphasis*</p>\n");
+    }
+
+    #[test]
+    fn test_script_injection_is_neutralized() {
+        let html = render_html(
+            "Hello <script>alert(document.cookie)</script> world\n\n\
+             <style>body { display: none }</style>\n\n\
+             <img src=x onerror=alert(1)> <b onclick=\"alert(1)\">bold</b> <iframe src=\"https://evil\">\n\n\
+             [click](javascript:alert(1)) [data](DATA:text/html,<script>x</script>) <javascript:alert(1)>\n\n\
+             [q](https://example.com/\"onmouseover=\"alert(1)) &lt;script&gt;",
+        );
+
+        for tag in tags(&html) {
+            assert!(ALLOWED_TAGS.contains(&tag.as_str()), "<{}> in {}", tag, html);
+        }
+        for injected in ["alert(document", "display: none", "onerror", "onclick", "iframe", "href=\"javascript", "href=\"DATA"] {
+            assert!(!html.contains(injected), "{} in {}", injected, html);
+        }
+        assert!(html.contains("<strong>bold</strong>"), "{}", html);
+        // Unsafe links keep their text, an unsafe autolink stays escaped text
+        assert!(html.contains("<p>click data &lt;javascript:alert(1)&gt;</p>"), "{}", html);
+        assert!(html.contains("&quot;onmouseover=&quot;"), "{}", html);
+        assert!(html.contains("&amp;lt;script&amp;gt;"), "{}", html);
+    }
+
+    #[test]
+    fn test_raw_tags_stay_balanced() {
+        assert_eq!(render_html("<b>open **x</b> y**"), "<p><strong>open <strong>x y</strong></strong></p>\n");
+        assert_eq!(render_html("</sup>stray <kbd>Ctrl"), "<p>stray <kbd>Ctrl</kbd></p>\n");
+    }
+
+    #[test]
+    fn test_deep_nesting_is_bounded() {
+        let quotes = format!("{}deep", "> ".repeat(10_000));
+        let html = render_html(&quotes);
+        assert_eq!(html.matches("<blockquote>").count(), MAX_NESTING);
+
+        let brackets = format!("{}x{}", "[".repeat(2_000), "](https://example.com)".repeat(2_000));
+        render_html(&brackets);
+        let stars = format!("{}x{}", "*a ".repeat(2_000), " b*".repeat(2_000));
+        render_html(&stars);
+    }
+}
@@ -0,0 +1,664 @@
+//! Metadata bundles: tags and tag assignments moved between machines.
+//!
+//! This module provides:
+//! - Export of the tags database to a standalone bundle file
+//! - Merge-import of a bundle into the local tags database, with a report
+//!   itemizing every conflict and how it was resolved, optionally as a dry run
+//!
+//! Merging is deterministic: importing the same bundle again changes nothing and
+//! reports the same conflicts.
+
+use crate::file_ops::{self, FileOpsError};
+use crate::tags::{self, Tag, TagAssociation, TagTarget, TagsDatabase, TagsError};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize, Serializer};
+use std::fs;
+use std::path::Path;
+
+/// Format marker of a metadata bundle.
+pub const BUNDLE_FORMAT: &str = "pz-backup-metadata";
+
+/// Bundle version written by `export_metadata_bundle`.
+pub const BUNDLE_VERSION: u32 = 1;
+
+/// Tags and tag assignments exported from one machine.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetadataBundle {
+    /// Always `BUNDLE_FORMAT`
+    pub format: String,
+    /// Bundle version
+    pub version: u32,
+    /// When the bundle was exported
+    pub exported_at: DateTime<Utc>,
+    /// Tag definitions
+    #[serde(default)]
+    pub tags: Vec<Tag>,
+    /// Tag assignments of backups and saves
+    #[serde(default)]
+    pub associations: Vec<TagAssociation>,
+}
+
+impl MetadataBundle {
+    /// Creates a bundle from a tags database.
+    pub fn from_db(db: &TagsDatabase) -> Self {
+        MetadataBundle {
+            format: BUNDLE_FORMAT.to_string(),
+            version: BUNDLE_VERSION,
+            exported_at: Utc::now(),
+            tags: db.tags().to_vec(),
+            associations: db.associations().to_vec(),
+        }
+    }
+}
+
+/// How one kind of conflict between local and incoming values is resolved.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum MergeStrategy {
+    /// Combine both sides; a color can't be combined, so the local one is kept
+    #[default]
+    Union,
+    /// Keep the local value untouched
+    #[serde(alias = "KeepLocal")]
+    PreferLocal,
+    /// Replace the local value with the incoming one
+    PreferImported,
+}
+
+/// Strategy for each kind of conflict.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct MergeOptions {
+    /// Tags defined on both sides with different colors
+    #[serde(default)]
+    pub tag_colors: MergeStrategy,
+    /// Targets tagged on both sides with different tags; targets without local tags
+    /// always receive the incoming ones
+    #[serde(default)]
+    pub tagged_targets: MergeStrategy,
+}
+
+/// What a conflict was about.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "type")]
+pub enum ConflictSubject {
+    /// A tag exists on both sides with different colors
+    TagColor {
+        name: String,
+        local_color: String,
+        incoming_color: String,
+    },
+    /// An incoming tag has an invalid color
+    InvalidTag { name: String, color: String },
+    /// An incoming assignment uses a tag defined on neither side
+    UnknownTag { target: TagTarget, name: String },
+    /// A target has different tags on both sides
+    TaggedTarget {
+        target: TagTarget,
+        local_tags: Vec<String>,
+        incoming_tags: Vec<String>,
+    },
+}
+
+/// How a conflict was resolved.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ConflictResolution {
+    /// The local value was kept and the incoming one dropped
+    KeptLocal,
+    /// The incoming value replaced the local one
+    TookImported,
+    /// Both sides were combined
+    Merged,
+    /// The incoming value was left out
+    Skipped,
+}
+
+/// A conflict found while merging, with its resolution.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct MetadataConflict {
+    pub subject: ConflictSubject,
+    pub resolution: ConflictResolution,
+}
+
+/// Result of importing a metadata bundle.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct MetadataImportReport {
+    /// Names of the tags created locally
+    pub tags_added: Vec<String>,
+    /// Number of targets that had no local tags and were assigned some
+    pub associations_added: usize,
+    /// Number of tag names added to targets, new or existing
+    pub assignments_added: usize,
+    /// Every conflict, in bundle order (tags first, then assignments)
+    pub conflicts: Vec<MetadataConflict>,
+}
+
+/// Result of exporting a metadata bundle.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct MetadataExportResult {
+    /// Path of the written bundle
+    pub path: String,
+    /// Number of tags exported
+    pub tag_count: usize,
+    /// Number of tagged targets exported
+    pub association_count: usize,
+}
+
+/// Error type for metadata bundle operations.
+#[derive(Debug)]
+pub enum MetadataError {
+    /// File operation error
+    FileOp(FileOpsError),
+    /// Tags database error
+    Tags(TagsError),
+    /// JSON serialization error
+    Json(serde_json::Error),
+    /// The file isn't a metadata bundle
+    InvalidBundle(String),
+    /// The bundle was written by a newer version
+    UnsupportedVersion(u32),
+}
+
+impl From<FileOpsError> for MetadataError {
+    fn from(err: FileOpsError) -> Self {
+        MetadataError::FileOp(err)
+    }
+}
+
+impl From<TagsError> for MetadataError {
+    fn from(err: TagsError) -> Self {
+        MetadataError::Tags(err)
+    }
+}
+
+impl From<serde_json::Error> for MetadataError {
+    fn from(err: serde_json::Error) -> Self {
+        MetadataError::Json(err)
+    }
+}
+
+impl std::fmt::Display for MetadataError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MetadataError::FileOp(err) => write!(f, "File operation error: {}", err),
+            MetadataError::Tags(err) => write!(f, "Tags error: {}", err),
+            MetadataError::Json(err) => write!(f, "JSON error: {}", err),
+            MetadataError::InvalidBundle(format) => {
+                write!(f, "Not a metadata bundle (format: {:?})", format)
+            }
+            MetadataError::UnsupportedVersion(version) => write!(
+                f,
+                "Metadata bundle version {} is newer than the supported version {}",
+                version, BUNDLE_VERSION
+            ),
+        }
+    }
+}
+
+impl std::error::Error for MetadataError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            MetadataError::FileOp(err) => Some(err),
+            MetadataError::Tags(err) => Some(err),
+            MetadataError::Json(err) => Some(err),
+            MetadataError::InvalidBundle(_) | MetadataError::UnsupportedVersion(_) => None,
+        }
+    }
+}
+
+impl Serialize for MetadataError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+/// Result type for metadata bundle operations.
+pub type MetadataResult<T> = Result<T, MetadataError>;
+
+/// Writes the local tags and tag assignments to a bundle file.
+///
+/// # Arguments
+/// * `path` - File to write the bundle to (replaced if it exists)
+///
+/// # Returns
+/// `MetadataResult<MetadataExportResult>` - Number of tags and tagged targets exported
+pub fn export_metadata_bundle(path: &Path) -> MetadataResult<MetadataExportResult> {
+    let bundle = MetadataBundle::from_db(&tags::load_tags_db()?);
+    let json = serde_json::to_string_pretty(&bundle)?;
+    file_ops::write_file_atomic(path, json.as_bytes())?;
+
+    Ok(MetadataExportResult {
+        path: file_ops::normalize_path_for_display(path),
+        tag_count: bundle.tags.len(),
+        association_count: bundle.associations.len(),
+    })
+}
+
+/// Reads and validates a bundle file.
+pub fn read_metadata_bundle(path: &Path) -> MetadataResult<MetadataBundle> {
+    let content = fs::read_to_string(path).map_err(FileOpsError::Io)?;
+    let bundle: MetadataBundle = serde_json::from_str(&content)?;
+
+    if bundle.format != BUNDLE_FORMAT {
+        return Err(MetadataError::InvalidBundle(bundle.format));
+    }
+    if bundle.version > BUNDLE_VERSION {
+        return Err(MetadataError::UnsupportedVersion(bundle.version));
+    }
+    Ok(bundle)
+}
+
+/// Merges a bundle file into the local tags database.
+///
+/// # Arguments
+/// * `path` - Bundle written by `export_metadata_bundle`
+/// * `options` - How each kind of conflict is resolved
+/// * `dry_run` - Only compute the report, for a summary to confirm first
+///
+/// # Returns
+/// `MetadataResult<MetadataImportReport>` - What was (or would be) added and every
+/// conflict with its resolution
+///
+/// # Behavior
+/// The whole merge is one write to tags.json (see `merge_bundle` for the rules). A dry
+/// run merges into a copy and writes nothing; importing afterwards gives the same
+/// report unless the tags changed in between.
+pub fn import_metadata_bundle(
+    path: &Path,
+    options: MergeOptions,
+    dry_run: bool,
+) -> MetadataResult<MetadataImportReport> {
+    let bundle = read_metadata_bundle(path)?;
+    if dry_run {
+        let mut db = tags::load_tags_db()?;
+        return Ok(merge_bundle(&mut db, &bundle, options));
+    }
+
+    let result = tags::with_tags_db(|db| Ok(merge_bundle(db, &bundle, options)));
+    Ok(tags::announce(result, tags::TagsChangeKind::Imported, None)?)
+}
+
+/// Merges a bundle into a tags database.
+///
+/// # Behavior
+/// - Tags missing locally are created
+/// - Tags with the same name and different colors keep the local color, unless
+///   `tag_colors` is `PreferImported` and the incoming color is valid
+/// - Incoming tags with an invalid color are skipped, along with their assignments
+///   unless the tag exists locally
+/// - Targets without local tags receive the incoming tags
+/// - Targets tagged differently on both sides get the union of their tag names
+///   (`Union`), keep their local tags (`PreferLocal`) or take the incoming ones
+///   (`PreferImported`); for a union, local tag order is preserved and new names are
+///   appended in bundle order
+pub fn merge_bundle(
+    db: &mut TagsDatabase,
+    bundle: &MetadataBundle,
+    options: MergeOptions,
+) -> MetadataImportReport {
+    let mut report = MetadataImportReport::default();
+
+    for tag in &bundle.tags {
+        match db.tag(&tag.name) {
+            Some(local) if local.color != tag.color => {
+                let (name, local_color) = (local.name.clone(), local.color.clone());
+                let took_imported = options.tag_colors == MergeStrategy::PreferImported
+                    && db.update_tag_color(&name, tag.color.clone()).is_ok();
+                report.conflicts.push(MetadataConflict {
+                    subject: ConflictSubject::TagColor {
+                        name: tag.name.clone(),
+                        local_color,
+                        incoming_color: tag.color.clone(),
+                    },
+                    resolution: if took_imported {
+                        ConflictResolution::TookImported
+                    } else {
+                        ConflictResolution::KeptLocal
+                    },
+                });
+            }
+            Some(_) => {}
+            None => match db
+                .create_tag(tag.name.clone(), tag.color.clone(), tag.description.clone(), tag.icon.clone())
+                .and_then(|()| db.set_tag_group(&tag.name, tag.group.clone()))
+            {
+                Ok(()) => report.tags_added.push(tag.name.clone()),
+                Err(_) => report.conflicts.push(MetadataConflict {
+                    subject: ConflictSubject::InvalidTag {
+                        name: tag.name.clone(),
+                        color: tag.color.clone(),
+                    },
+                    resolution: ConflictResolution::Skipped,
+                }),
+            },
+        }
+    }
+
+    for association in &bundle.associations {
+        let target = &association.target;
+        let mut incoming = Vec::new();
+        for name in &association.tag_names {
+            // Aliases and case variants resolve to the local tag name
+            match db.canonical_name(name) {
+                None => report.conflicts.push(MetadataConflict {
+                    subject: ConflictSubject::UnknownTag {
+                        target: target.clone(),
+                        name: name.clone(),
+                    },
+                    resolution: ConflictResolution::Skipped,
+                }),
+                Some(local) if !incoming.iter().any(|n| n == local) => incoming.push(local.to_string()),
+                Some(_) => {}
+            }
+        }
+
+        let local: Vec<String> = db
+            .association(target)
+            .map(|a| a.tag_names.clone())
+            .unwrap_or_default();
+        let missing: Vec<String> = incoming
+            .iter()
+            .filter(|name| !local.contains(name))
+            .cloned()
+            .collect();
+        let extra = local.iter().any(|name| !incoming.contains(name));
+        if incoming.is_empty() || (missing.is_empty() && !extra) {
+            continue;
+        }
+
+        if local.is_empty() {
+            report.associations_added += 1;
+            report.assignments_added += missing.len();
+            db.association_or_insert(target).tag_names.extend(missing);
+            continue;
+        }
+
+        let resolution = match options.tagged_targets {
+            MergeStrategy::Union => ConflictResolution::Merged,
+            MergeStrategy::PreferLocal => ConflictResolution::KeptLocal,
+            MergeStrategy::PreferImported => ConflictResolution::TookImported,
+        };
+        report.conflicts.push(MetadataConflict {
+            subject: ConflictSubject::TaggedTarget {
+                target: target.clone(),
+                local_tags: local,
+                incoming_tags: incoming.clone(),
+            },
+            resolution,
+        });
+        match resolution {
+            ConflictResolution::Merged => {
+                report.assignments_added += missing.len();
+                db.association_or_insert(target).tag_names.extend(missing);
+            }
+            ConflictResolution::TookImported => {
+                report.assignments_added += missing.len();
+                db.association_or_insert(target).tag_names = incoming;
+            }
+            _ => {}
+        }
+    }
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestEnv;
+
+    fn tag(name: &str, color: &str) -> Tag {
+        Tag {
+            name: name.to_string(),
+            color: color.to_string(),
+            description: None,
+            icon: None,
+            group: None,
+            aliases: Vec::new(),
+            pinned: false,
+            protected: false,
+            sort_index: 0,
+            created_at: None,
+            updated_at: None,
+        }
+    }
+
+    fn association(target: TagTarget, names: &[&str]) -> TagAssociation {
+        TagAssociation {
+            target,
+            tag_names: names.iter().map(|n| n.to_string()).collect(),
+            created_at: None,
+            updated_at: None,
+        }
+    }
+
+    fn bundle(tags: Vec<Tag>, associations: Vec<TagAssociation>) -> MetadataBundle {
+        MetadataBundle {
+            format: BUNDLE_FORMAT.to_string(),
+            version: BUNDLE_VERSION,
+            exported_at: Utc::now(),
+            tags,
+            associations,
+        }
+    }
+
+    fn names(db: &TagsDatabase, target: &TagTarget) -> Vec<String> {
+        db.tags_for(target).into_iter().map(|t| t.name).collect()
+    }
+
+    /// Local database: "keep" (red) on backup 1, "main" on the save.
+    fn local_db() -> TagsDatabase {
+        let mut db = TagsDatabase::default();
+        db.create_tag("keep".to_string(), "#FF0000".to_string(), None, None).unwrap();
+        db.create_tag("main".to_string(), "#00FF00".to_string(), None, None).unwrap();
+        db.add_tags_to(&TagTarget::backup("Multiplayer/server", "1.tar.gz"), vec!["keep".to_string()])
+            .unwrap();
+        db.add_tags_to(&TagTarget::save("Multiplayer/server"), vec!["main".to_string()])
+            .unwrap();
+        db
+    }
+
+    /// A friend's bundle: "keep" in blue, a new "horde" tag, and overlapping assignments.
+    fn friend_bundle() -> MetadataBundle {
+        bundle(
+            vec![
+                tag("keep", "#0000FF"),
+                tag("horde", "#FFAA00"),
+                tag("broken", "reddish"),
+            ],
+            vec![
+                association(TagTarget::backup("Multiplayer/server", "1.tar.gz"), &["horde", "keep"]),
+                association(TagTarget::backup("Multiplayer\\server", "2.tar.gz"), &["horde", "broken"]),
+                association(TagTarget::save("Multiplayer/server"), &["main"]),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_union_merge_reports_every_conflict() {
+        let mut db = local_db();
+        let report = merge_bundle(&mut db, &friend_bundle(), MergeOptions::default());
+
+        assert_eq!(report.tags_added, vec!["horde"]);
+        assert_eq!(report.associations_added, 1);
+        assert_eq!(report.assignments_added, 2);
+        assert_eq!(
+            report.conflicts,
+            vec![
+                MetadataConflict {
+                    subject: ConflictSubject::TagColor {
+                        name: "keep".to_string(),
+                        local_color: "#FF0000".to_string(),
+                        incoming_color: "#0000FF".to_string(),
+                    },
+                    resolution: ConflictResolution::KeptLocal,
+                },
+                MetadataConflict {
+                    subject: ConflictSubject::InvalidTag {
+                        name: "broken".to_string(),
+                        color: "reddish".to_string(),
+                    },
+                    resolution: ConflictResolution::Skipped,
+                },
+                MetadataConflict {
+                    subject: ConflictSubject::TaggedTarget {
+                        target: TagTarget::backup("Multiplayer/server", "1.tar.gz"),
+                        local_tags: vec!["keep".to_string()],
+                        incoming_tags: vec!["horde".to_string(), "keep".to_string()],
+                    },
+                    resolution: ConflictResolution::Merged,
+                },
+                MetadataConflict {
+                    subject: ConflictSubject::UnknownTag {
+                        target: TagTarget::backup("Multiplayer/server", "2.tar.gz"),
+                        name: "broken".to_string(),
+                    },
+                    resolution: ConflictResolution::Skipped,
+                },
+            ]
+        );
+
+        // Local colors win; local order is kept and new names are appended
+        assert_eq!(db.tag("keep").unwrap().color, "#FF0000");
+        assert_eq!(names(&db, &TagTarget::backup("Multiplayer/server", "1.tar.gz")), vec!["keep", "horde"]);
+        assert_eq!(names(&db, &TagTarget::backup("Multiplayer/server", "2.tar.gz")), vec!["horde"]);
+        assert_eq!(names(&db, &TagTarget::save("Multiplayer/server")), vec!["main"]);
+    }
+
+    #[test]
+    fn test_prefer_local_strategy() {
+        let mut db = local_db();
+        let options = MergeOptions {
+            tag_colors: MergeStrategy::PreferLocal,
+            tagged_targets: MergeStrategy::PreferLocal,
+        };
+        let report = merge_bundle(&mut db, &friend_bundle(), options);
+
+        assert_eq!(report.associations_added, 1);
+        assert_eq!(report.assignments_added, 1);
+        let tagged = report
+            .conflicts
+            .iter()
+            .find(|c| matches!(c.subject, ConflictSubject::TaggedTarget { .. }))
+            .unwrap();
+        assert_eq!(tagged.resolution, ConflictResolution::KeptLocal);
+        assert_eq!(names(&db, &TagTarget::backup("Multiplayer/server", "1.tar.gz")), vec!["keep"]);
+        // Untagged targets still receive the incoming tags
+        assert_eq!(names(&db, &TagTarget::backup("Multiplayer/server", "2.tar.gz")), vec!["horde"]);
+    }
+
+    #[test]
+    fn test_prefer_imported_strategy() {
+        let mut db = local_db();
+        db.add_tags_to(&TagTarget::save("Multiplayer/server"), vec!["keep".to_string()])
+            .unwrap();
+        let options = MergeOptions {
+            tag_colors: MergeStrategy::PreferImported,
+            tagged_targets: MergeStrategy::PreferImported,
+        };
+        let report = merge_bundle(&mut db, &friend_bundle(), options);
+
+        let resolutions: Vec<ConflictResolution> = report
+            .conflicts
+            .iter()
+            .filter(|c| !matches!(c.subject, ConflictSubject::InvalidTag { .. } | ConflictSubject::UnknownTag { .. }))
+            .map(|c| c.resolution)
+            .collect();
+        assert_eq!(resolutions, vec![ConflictResolution::TookImported; 3]);
+        assert_eq!(db.tag("keep").unwrap().color, "#0000FF");
+        assert_eq!(names(&db, &TagTarget::backup("Multiplayer/server", "1.tar.gz")), vec!["horde", "keep"]);
+        // Local-only names are dropped when the incoming tags win
+        assert_eq!(names(&db, &TagTarget::save("Multiplayer/server")), vec!["main"]);
+    }
+
+    #[test]
+    fn test_incoming_names_resolve_through_aliases() {
+        let mut db = local_db();
+        db.add_tag_alias("keep", "Save Me").unwrap();
+        let incoming = bundle(
+            vec![tag("save me", "#FF0000")],
+            vec![association(TagTarget::backup("Multiplayer/server", "3.tar.gz"), &["save me"])],
+        );
+
+        let report = merge_bundle(&mut db, &incoming, MergeOptions::default());
+        assert!(report.tags_added.is_empty());
+        assert!(report.conflicts.is_empty());
+        let association = db.association(&TagTarget::backup("Multiplayer/server", "3.tar.gz")).unwrap();
+        assert_eq!(association.tag_names, vec!["keep"]);
+    }
+
+    #[test]
+    fn test_import_is_idempotent() {
+        let env = TestEnv::new();
+        tags::save_tags_db(&local_db()).unwrap();
+        let path = env.root().join("friend.json");
+        fs::write(&path, serde_json::to_string(&friend_bundle()).unwrap()).unwrap();
+
+        let first = import_metadata_bundle(&path, MergeOptions::default(), false).unwrap();
+        let after_first = fs::read_to_string(tags::get_tags_db_path().unwrap()).unwrap();
+        let second = import_metadata_bundle(&path, MergeOptions::default(), false).unwrap();
+        let after_second = fs::read_to_string(tags::get_tags_db_path().unwrap()).unwrap();
+
+        assert_eq!(after_first, after_second);
+        assert_eq!(first.tags_added, vec!["horde"]);
+        assert!(second.tags_added.is_empty());
+        assert_eq!((second.associations_added, second.assignments_added), (0, 0));
+        // Conflicts that remain unresolvable are reported again, identically
+        let remaining: Vec<&MetadataConflict> = first
+            .conflicts
+            .iter()
+            .filter(|c| !matches!(c.subject, ConflictSubject::TaggedTarget { .. }))
+            .collect();
+        assert_eq!(second.conflicts.iter().collect::<Vec<_>>(), remaining);
+    }
+
+    #[test]
+    fn test_dry_run_matches_import_without_writing() {
+        let env = TestEnv::new();
+        tags::save_tags_db(&local_db()).unwrap();
+        let path = env.root().join("friend.json");
+        fs::write(&path, serde_json::to_string(&friend_bundle()).unwrap()).unwrap();
+        let before = fs::read_to_string(tags::get_tags_db_path().unwrap()).unwrap();
+
+        let preview = import_metadata_bundle(&path, MergeOptions::default(), true).unwrap();
+        assert_eq!(fs::read_to_string(tags::get_tags_db_path().unwrap()).unwrap(), before);
+        assert!(tags::load_tags_db().unwrap().tag("horde").is_none());
+
+        let imported = import_metadata_bundle(&path, MergeOptions::default(), false).unwrap();
+        assert_eq!(preview, imported);
+    }
+
+    #[test]
+    fn test_export_round_trip() {
+        let env = TestEnv::new();
+        tags::save_tags_db(&local_db()).unwrap();
+        let path = env.root().join("bundle.json");
+
+        let result = export_metadata_bundle(&path).unwrap();
+        assert_eq!((result.tag_count, result.association_count), (2, 2));
+
+        // Importing our own bundle changes nothing and reports nothing
+        let report = import_metadata_bundle(&path, MergeOptions::default(), false).unwrap();
+        assert_eq!(report, MetadataImportReport::default());
+    }
+
+    #[test]
+    fn test_rejects_foreign_and_newer_files() {
+        let env = TestEnv::new();
+        let path = env.root().join("bundle.json");
+
+        let mut foreign = bundle(Vec::new(), Vec::new());
+        foreign.format = "something-else".to_string();
+        fs::write(&path, serde_json::to_string(&foreign).unwrap()).unwrap();
+        assert!(matches!(read_metadata_bundle(&path), Err(MetadataError::InvalidBundle(_))));
+
+        let mut newer = bundle(Vec::new(), Vec::new());
+        newer.version = BUNDLE_VERSION + 1;
+        fs::write(&path, serde_json::to_string(&newer).unwrap()).unwrap();
+        assert!(matches!(read_metadata_bundle(&path), Err(MetadataError::UnsupportedVersion(_))));
+
+        fs::write(&path, "{}").unwrap();
+        assert!(matches!(read_metadata_bundle(&path), Err(MetadataError::Json(_))));
+    }
+}
@@ -0,0 +1,341 @@
+//! Local performance metrics of finished operations.
+//!
+//! This module provides:
+//! - A rolling `metrics.json` store in the config directory (last 500 operations)
+//! - Aggregates per operation kind: p50/p95 duration, average throughput,
+//!   compression ratio and queue wait, plus the raw points for a chart
+//!
+//! Points are produced by `TaskRegistry::finish` from what the operation reported
+//! through its `TaskHandle`. Metrics never leave the machine.
+
+use crate::config as config_module;
+use crate::config::ConfigError;
+use crate::file_ops::{FileOpsError, LockedJsonStore};
+use crate::tasks::TaskKind;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize, Serializer};
+use std::collections::VecDeque;
+use std::path::PathBuf;
+
+/// Metrics file name.
+const METRICS_FILE_NAME: &str = "metrics.json";
+
+/// Number of operations kept in the store.
+pub const MAX_METRIC_POINTS: usize = 500;
+
+/// Measurements of one finished operation.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MetricPoint {
+    /// Operation kind
+    pub kind: TaskKind,
+    /// What the operation worked on (e.g., save name)
+    pub subject: String,
+    /// When the operation finished
+    pub finished_at: DateTime<Utc>,
+    /// Time spent running, excluding the queue wait (milliseconds)
+    pub duration_ms: u64,
+    /// Time spent waiting for a queue worker (milliseconds)
+    pub queue_wait_ms: u64,
+    /// Size of the save data processed, if reported
+    pub uncompressed_bytes: Option<u64>,
+    /// Size of the archive written or read, if reported
+    pub compressed_bytes: Option<u64>,
+    /// Whether the operation succeeded
+    pub success: bool,
+}
+
+impl MetricPoint {
+    /// Returns the throughput over the save data in bytes per second.
+    pub fn throughput_bytes_per_sec(&self) -> Option<f64> {
+        let bytes = self.uncompressed_bytes?;
+        // Sub-millisecond operations count as one millisecond
+        Some(bytes as f64 * 1000.0 / self.duration_ms.max(1) as f64)
+    }
+
+    /// Returns archive size / save data size.
+    pub fn compression_ratio(&self) -> Option<f64> {
+        match (self.compressed_bytes, self.uncompressed_bytes) {
+            (Some(compressed), Some(uncompressed)) if uncompressed > 0 => {
+                Some(compressed as f64 / uncompressed as f64)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Contents of `metrics.json`, oldest point first.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MetricsLog {
+    #[serde(default)]
+    pub points: VecDeque<MetricPoint>,
+}
+
+/// Aggregates over the matching successful operations.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct PerformanceMetrics {
+    /// Number of matching successful operations
+    pub count: usize,
+    /// Number of matching failed operations (excluded from the aggregates)
+    pub failed_count: usize,
+    /// Median duration in milliseconds
+    pub p50_duration_ms: Option<u64>,
+    /// 95th percentile duration in milliseconds
+    pub p95_duration_ms: Option<u64>,
+    /// Average throughput in bytes per second
+    pub avg_throughput_bytes_per_sec: Option<f64>,
+    /// Average compression ratio (archive size / save data size)
+    pub avg_compression_ratio: Option<f64>,
+    /// Average queue wait in milliseconds
+    pub avg_queue_wait_ms: Option<f64>,
+    /// Matching points, newest first, including failures
+    pub points: Vec<MetricPoint>,
+}
+
+/// Error type for metrics operations.
+#[derive(Debug)]
+pub enum MetricsError {
+    /// File operation error
+    FileOp(FileOpsError),
+    /// Config error
+    Config(ConfigError),
+    /// JSON serialization error
+    Json(serde_json::Error),
+}
+
+impl From<FileOpsError> for MetricsError {
+    fn from(err: FileOpsError) -> Self {
+        MetricsError::FileOp(err)
+    }
+}
+
+impl From<ConfigError> for MetricsError {
+    fn from(err: ConfigError) -> Self {
+        MetricsError::Config(err)
+    }
+}
+
+impl From<serde_json::Error> for MetricsError {
+    fn from(err: serde_json::Error) -> Self {
+        MetricsError::Json(err)
+    }
+}
+
+impl std::fmt::Display for MetricsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MetricsError::FileOp(err) => write!(f, "File operation error: {}", err),
+            MetricsError::Config(err) => write!(f, "Config error: {}", err),
+            MetricsError::Json(err) => write!(f, "JSON error: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for MetricsError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            MetricsError::FileOp(err) => Some(err),
+            MetricsError::Config(err) => Some(err),
+            MetricsError::Json(err) => Some(err),
+        }
+    }
+}
+
+impl Serialize for MetricsError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+/// Result type for metrics operations.
+pub type MetricsResult<T> = Result<T, MetricsError>;
+
+/// Returns the path to the metrics file.
+pub fn get_metrics_file_path() -> MetricsResult<PathBuf> {
+    Ok(config_module::get_config_dir()?.join(METRICS_FILE_NAME))
+}
+
+fn metrics_store() -> MetricsResult<LockedJsonStore<MetricsLog>> {
+    Ok(LockedJsonStore::new(get_metrics_file_path()?))
+}
+
+/// Appends a point, dropping the oldest ones beyond `MAX_METRIC_POINTS`.
+pub fn record_point(point: &MetricPoint) -> MetricsResult<()> {
+    record_point_in(&metrics_store()?, point)
+}
+
+fn record_point_in(store: &LockedJsonStore<MetricsLog>, point: &MetricPoint) -> MetricsResult<()> {
+    store.modify(|log: &mut MetricsLog| {
+        log.points.push_back(point.clone());
+        while log.points.len() > MAX_METRIC_POINTS {
+            log.points.pop_front();
+        }
+        Ok::<_, MetricsError>(())
+    })
+}
+
+/// Appends a point, logging failures to stderr; the task registry's metrics sink.
+///
+/// Metrics are diagnostic; failing to store them must never fail the operation.
+pub fn record_point_best_effort(point: &MetricPoint) {
+    if let Err(e) = record_point(point) {
+        eprintln!("Failed to record performance metrics: {}", e);
+    }
+}
+
+/// Returns aggregates and recent points from the metrics store.
+///
+/// # Arguments
+/// * `kind` - Only operations of this kind (all kinds if None)
+/// * `since` - Only operations finished at or after this time (all if None)
+///
+/// # Returns
+/// `MetricsResult<PerformanceMetrics>` - Aggregates over successful operations and
+/// the matching points, newest first
+pub fn get_performance_metrics(
+    kind: Option<TaskKind>,
+    since: Option<DateTime<Utc>>,
+) -> MetricsResult<PerformanceMetrics> {
+    let log: MetricsLog = metrics_store()?.load::<MetricsError>()?;
+    Ok(summarize(log.points.iter(), kind, since))
+}
+
+/// Computes aggregates over points (see `get_performance_metrics`).
+pub fn summarize<'a>(
+    points: impl Iterator<Item = &'a MetricPoint>,
+    kind: Option<TaskKind>,
+    since: Option<DateTime<Utc>>,
+) -> PerformanceMetrics {
+    let mut matching: Vec<MetricPoint> = points
+        .filter(|p| kind.is_none_or(|k| k == p.kind))
+        .filter(|p| since.is_none_or(|since| p.finished_at >= since))
+        .cloned()
+        .collect();
+    matching.sort_by(|a, b| b.finished_at.cmp(&a.finished_at));
+
+    let succeeded: Vec<&MetricPoint> = matching.iter().filter(|p| p.success).collect();
+    let mut durations: Vec<u64> = succeeded.iter().map(|p| p.duration_ms).collect();
+    durations.sort_unstable();
+
+    PerformanceMetrics {
+        count: succeeded.len(),
+        failed_count: matching.len() - succeeded.len(),
+        p50_duration_ms: percentile(&durations, 50),
+        p95_duration_ms: percentile(&durations, 95),
+        avg_throughput_bytes_per_sec: average(succeeded.iter().filter_map(|p| p.throughput_bytes_per_sec())),
+        avg_compression_ratio: average(succeeded.iter().filter_map(|p| p.compression_ratio())),
+        avg_queue_wait_ms: average(succeeded.iter().map(|p| p.queue_wait_ms as f64)),
+        points: matching,
+    }
+}
+
+/// Nearest-rank percentile of sorted values.
+fn percentile(sorted: &[u64], percent: usize) -> Option<u64> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let rank = (percent * sorted.len()).div_ceil(100).max(1);
+    Some(sorted[rank - 1])
+}
+
+fn average(values: impl Iterator<Item = f64>) -> Option<f64> {
+    let (sum, count) = values.fold((0.0, 0usize), |(sum, count), v| (sum + v, count + 1));
+    (count > 0).then(|| sum / count as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use tempfile::TempDir;
+
+    fn point(kind: TaskKind, duration_ms: u64, minutes_ago: i64) -> MetricPoint {
+        MetricPoint {
+            kind,
+            subject: "Survival/MySave".to_string(),
+            finished_at: Utc::now() - Duration::minutes(minutes_ago),
+            duration_ms,
+            queue_wait_ms: 0,
+            uncompressed_bytes: None,
+            compressed_bytes: None,
+            success: true,
+        }
+    }
+
+    #[test]
+    fn test_percentiles_use_nearest_rank() {
+        let values: Vec<u64> = (1..=20).map(|i| i * 10).collect();
+        assert_eq!(percentile(&values, 50), Some(100));
+        assert_eq!(percentile(&values, 95), Some(190));
+        assert_eq!(percentile(&[7], 50), Some(7));
+        assert_eq!(percentile(&[7], 95), Some(7));
+        assert_eq!(percentile(&[], 50), None);
+    }
+
+    #[test]
+    fn test_summarize_aggregates_successful_points() {
+        let mut fast = point(TaskKind::Backup, 1000, 3);
+        fast.uncompressed_bytes = Some(4_000_000);
+        fast.compressed_bytes = Some(1_000_000);
+        fast.queue_wait_ms = 100;
+        let mut slow = point(TaskKind::Backup, 4000, 2);
+        slow.uncompressed_bytes = Some(8_000_000);
+        slow.compressed_bytes = Some(4_000_000);
+        slow.queue_wait_ms = 300;
+        let mut failed = point(TaskKind::Backup, 60_000, 1);
+        failed.success = false;
+        let restore = point(TaskKind::Restore, 500, 1);
+
+        let points = [fast, slow, failed, restore];
+        let summary = summarize(points.iter(), Some(TaskKind::Backup), None);
+
+        assert_eq!(summary.count, 2);
+        assert_eq!(summary.failed_count, 1);
+        assert_eq!(summary.p50_duration_ms, Some(1000));
+        assert_eq!(summary.p95_duration_ms, Some(4000));
+        // (4 MB/s + 2 MB/s) / 2
+        assert_eq!(summary.avg_throughput_bytes_per_sec, Some(3_000_000.0));
+        // (0.25 + 0.5) / 2
+        assert_eq!(summary.avg_compression_ratio, Some(0.375));
+        assert_eq!(summary.avg_queue_wait_ms, Some(200.0));
+
+        // Raw points include the failure, newest first
+        assert_eq!(summary.points.len(), 3);
+        assert!(!summary.points[0].success);
+    }
+
+    #[test]
+    fn test_summarize_filters_by_time_and_handles_empty() {
+        let points = [point(TaskKind::Backup, 100, 120), point(TaskKind::Backup, 200, 5)];
+
+        let recent = summarize(points.iter(), None, Some(Utc::now() - Duration::minutes(60)));
+        assert_eq!(recent.count, 1);
+        assert_eq!(recent.p50_duration_ms, Some(200));
+
+        let empty = summarize(points.iter(), Some(TaskKind::Restore), None);
+        assert_eq!(empty, PerformanceMetrics::default());
+    }
+
+    #[test]
+    fn test_store_keeps_last_points_across_reloads() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join(METRICS_FILE_NAME);
+        let store = LockedJsonStore::new(&path);
+
+        for i in 0..(MAX_METRIC_POINTS + 25) {
+            let mut p = point(TaskKind::Backup, i as u64, 0);
+            p.subject = format!("save{}", i);
+            record_point_in(&store, &p).unwrap();
+        }
+
+        let log: MetricsLog = LockedJsonStore::new(&path).load::<MetricsError>().unwrap();
+        assert_eq!(log.points.len(), MAX_METRIC_POINTS);
+        assert_eq!(log.points.front().unwrap().subject, "save25");
+        assert_eq!(
+            log.points.back().unwrap().subject,
+            format!("save{}", MAX_METRIC_POINTS + 24)
+        );
+    }
+}
@@ -0,0 +1,649 @@
+//! Mod manifests of backups and restore-time compatibility checks.
+//!
+//! This module provides:
+//! - Extraction of a save's mod list from its `mods.txt`
+//! - An index of installed mods: Steam Workshop content (`.../workshop/content/108600`)
+//!   and the local `Zomboid/mods` folder, keyed by the `id` in each `mod.info`
+//! - `mods_manifest.json`, written into every backup of a save that lists mods, with
+//!   each mod's Workshop ID and the timestamp and size of its installed content
+//! - A compatibility report comparing a backup's manifest with the installed mods
+//!
+//! Nothing is downloaded; the report links missing mods to their Workshop pages.
+
+use crate::backup::get_save_backup_dir;
+use crate::config::{self as config_module, Config, ConfigError};
+use crate::file_ops::{read_file_from_tar_gz, ExtraArchiveFile, FileOpsError};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize, Serializer};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// File name of the manifest at the root of a backup archive.
+pub const MODS_MANIFEST_FILE_NAME: &str = "mods_manifest.json";
+
+/// Manifest version written by `capture_manifest`.
+pub const MODS_MANIFEST_VERSION: u32 = 1;
+
+/// Steam app ID of Project Zomboid.
+const STEAM_APP_ID: &str = "108600";
+
+/// File in a save directory listing the save's mods.
+const SAVE_MODS_FILE_NAME: &str = "mods.txt";
+
+/// Mod descriptor file in every mod folder.
+const MOD_INFO_FILE_NAME: &str = "mod.info";
+
+/// Returns the Steam Workshop page of an item.
+pub fn workshop_url(workshop_id: &str) -> String {
+    format!("https://steamcommunity.com/sharedfiles/filedetails/?id={}", workshop_id)
+}
+
+/// A mod as recorded in a backup's manifest.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ManifestMod {
+    /// Mod ID from `mods.txt` (e.g., "Hydrocraft")
+    pub id: String,
+    /// Workshop item the mod was installed from, if it came from the Workshop
+    pub workshop_id: Option<String>,
+    /// Newest modification time of the installed content at backup time
+    pub installed_modified_at: Option<DateTime<Utc>>,
+    /// Size of the installed content at backup time
+    pub installed_size_bytes: Option<u64>,
+}
+
+/// Contents of `mods_manifest.json`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ModsManifest {
+    /// Manifest version
+    pub version: u32,
+    /// When the manifest was captured
+    pub captured_at: DateTime<Utc>,
+    /// Mods of the save, in `mods.txt` order
+    pub mods: Vec<ManifestMod>,
+}
+
+/// An installed mod.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstalledMod {
+    /// Workshop item the mod belongs to (None for the local mods folder)
+    pub workshop_id: Option<String>,
+    /// Newest modification time of the mod's content
+    pub modified_at: Option<DateTime<Utc>>,
+    /// Size of the mod's content
+    pub size_bytes: u64,
+}
+
+/// A manifest mod that isn't installed.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct MissingMod {
+    /// Mod ID
+    pub id: String,
+    /// Workshop item to subscribe to, if known
+    pub workshop_id: Option<String>,
+    /// Workshop page of the item, if known
+    pub workshop_url: Option<String>,
+}
+
+/// A manifest mod whose installed content changed since the backup.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct UpdatedMod {
+    /// Mod ID
+    pub id: String,
+    /// Workshop item of the installed mod
+    pub workshop_id: Option<String>,
+    /// Content timestamp recorded in the manifest (the backup time if unknown)
+    pub backed_up_modified_at: DateTime<Utc>,
+    /// Content timestamp of the installed mod
+    pub installed_modified_at: DateTime<Utc>,
+}
+
+/// Comparison of a backup's mods with the installed ones.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ModCompatibilityReport {
+    /// Whether the backup has a mod manifest (older backups and mod-less saves don't)
+    pub has_manifest: bool,
+    /// Number of mods in the manifest
+    pub mod_count: usize,
+    /// Mods of the backup that aren't installed
+    pub missing: Vec<MissingMod>,
+    /// Mods installed with content newer than at backup time
+    pub updated: Vec<UpdatedMod>,
+}
+
+impl ModCompatibilityReport {
+    /// Returns true if every mod is installed and unchanged.
+    pub fn is_compatible(&self) -> bool {
+        self.missing.is_empty() && self.updated.is_empty()
+    }
+}
+
+/// Error type for mod manifest operations.
+#[derive(Debug)]
+pub enum ModsError {
+    /// File operation error
+    FileOp(FileOpsError),
+    /// Config error
+    Config(ConfigError),
+    /// JSON serialization error
+    Json(serde_json::Error),
+}
+
+impl From<FileOpsError> for ModsError {
+    fn from(err: FileOpsError) -> Self {
+        ModsError::FileOp(err)
+    }
+}
+
+impl From<ConfigError> for ModsError {
+    fn from(err: ConfigError) -> Self {
+        ModsError::Config(err)
+    }
+}
+
+impl From<serde_json::Error> for ModsError {
+    fn from(err: serde_json::Error) -> Self {
+        ModsError::Json(err)
+    }
+}
+
+impl std::fmt::Display for ModsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ModsError::FileOp(err) => write!(f, "File operation error: {}", err),
+            ModsError::Config(err) => write!(f, "Config error: {}", err),
+            ModsError::Json(err) => write!(f, "JSON error: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for ModsError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ModsError::FileOp(err) => Some(err),
+            ModsError::Config(err) => Some(err),
+            ModsError::Json(err) => Some(err),
+        }
+    }
+}
+
+impl Serialize for ModsError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+/// Result type for mod manifest operations.
+pub type ModsResult<T> = Result<T, ModsError>;
+
+/// Parses the mod IDs out of a save's `mods.txt`.
+///
+/// # Arguments
+/// * `content` - Contents of `mods.txt`
+///
+/// # Returns
+/// `Vec<String>` - Mod IDs in file order, without duplicates
+///
+/// # Behavior
+/// Only `mod = <id>,` lines inside the `mods { ... }` block count. The `\` prefix
+/// of Build 42 mod IDs is dropped.
+pub fn parse_save_mods(content: &str) -> Vec<String> {
+    let mut mods: Vec<String> = Vec::new();
+    let mut section = "";
+    let mut in_block = false;
+
+    for line in content.lines() {
+        let line = line.trim();
+        match line {
+            "{" => in_block = true,
+            "}" => {
+                in_block = false;
+                section = "";
+            }
+            _ if !in_block => section = line,
+            _ if section == "mods" => {
+                let Some((key, value)) = line.split_once('=') else {
+                    continue;
+                };
+                if key.trim() != "mod" {
+                    continue;
+                }
+                let id = value.trim().trim_end_matches(',').trim().trim_start_matches('\\');
+                if !id.is_empty() && !mods.iter().any(|m| m == id) {
+                    mods.push(id.to_string());
+                }
+            }
+            _ => {}
+        }
+    }
+    mods
+}
+
+/// Reads the mod IDs of a save directory; empty if it has no `mods.txt`.
+pub fn read_save_mods(save_dir: &Path) -> Vec<String> {
+    fs::read_to_string(save_dir.join(SAVE_MODS_FILE_NAME))
+        .map(|content| parse_save_mods(&content))
+        .unwrap_or_default()
+}
+
+/// Returns the Workshop content directory of Project Zomboid, if it exists.
+///
+/// # Behavior
+/// Uses `workshop_content_path` from the config when set; otherwise tries the
+/// default Steam library location of the platform.
+pub fn locate_workshop_content_dir(config: &Config) -> Option<PathBuf> {
+    if let Some(path) = &config.workshop_content_path {
+        let path = PathBuf::from(path);
+        return path.is_dir().then_some(path);
+    }
+    default_workshop_content_dirs()
+        .into_iter()
+        .find(|path| path.is_dir())
+}
+
+/// Default Workshop content directories of the platform, most likely first.
+fn default_workshop_content_dirs() -> Vec<PathBuf> {
+    let content = |steam: PathBuf| steam.join("steamapps").join("workshop").join("content").join(STEAM_APP_ID);
+
+    let mut dirs = Vec::new();
+    if cfg!(windows) {
+        dirs.push(content(PathBuf::from(r"C:\Program Files (x86)\Steam")));
+        dirs.push(content(PathBuf::from(r"C:\Program Files\Steam")));
+    } else if let Some(home) = dirs::home_dir() {
+        if cfg!(target_os = "macos") {
+            dirs.push(content(home.join("Library/Application Support/Steam")));
+        } else {
+            dirs.push(content(home.join(".steam/steam")));
+            dirs.push(content(home.join(".local/share/Steam")));
+            dirs.push(content(home.join(".var/app/com.valvesoftware.Steam/.local/share/Steam")));
+        }
+    }
+    dirs
+}
+
+/// Returns the local mods folder (`Zomboid/mods`, next to `Zomboid/Saves`).
+fn local_mods_dir(config: &Config) -> Option<PathBuf> {
+    let save_path = config.get_save_path().ok()?;
+    Some(save_path.parent()?.join("mods"))
+}
+
+/// Indexes the installed copies of a save's mods by mod ID.
+///
+/// # Arguments
+/// * `workshop_dir` - Workshop content directory (`<workshop id>/mods/<mod>/mod.info`)
+/// * `local_mods_dir` - Local mods folder (`<mod>/mod.info`)
+/// * `mod_ids` - Mods to index (e.g., the save's mods)
+///
+/// # Returns
+/// `HashMap<String, InstalledMod>` - Installed mods among `mod_ids`; Workshop copies win
+/// over local ones
+///
+/// # Behavior
+/// `mod.info` is looked up in the mod folder and one level below it, where Build 42
+/// keeps version folders (e.g., `<mod>/42/mod.info`). Only the `mod.info` files of other
+/// mods are read; the content of a folder is walked for its size and timestamp only if
+/// it holds one of `mod_ids`. Missing directories index as empty.
+pub fn index_installed_mods(
+    workshop_dir: Option<&Path>,
+    local_mods_dir: Option<&Path>,
+    mod_ids: &[String],
+) -> HashMap<String, InstalledMod> {
+    let mut folders = HashMap::new();
+
+    if let Some(dir) = local_mods_dir {
+        index_mods_folder(dir, None, &mut folders);
+    }
+    if let Some(dir) = workshop_dir {
+        for item in read_dir_paths(dir) {
+            let Some(workshop_id) = item.file_name().map(|n| n.to_string_lossy().to_string()) else {
+                continue;
+            };
+            if !item.is_dir() || !workshop_id.chars().all(|c| c.is_ascii_digit()) {
+                continue;
+            }
+            index_mods_folder(&item.join("mods"), Some(&workshop_id), &mut folders);
+        }
+    }
+
+    let mut stats = HashMap::new();
+    mod_ids
+        .iter()
+        .filter_map(|id| {
+            let (mod_dir, workshop_id) = folders.get(id)?;
+            let (size_bytes, modified_at) = *stats.entry(mod_dir.clone()).or_insert_with(|| content_stats(mod_dir));
+            let installed = InstalledMod {
+                workshop_id: workshop_id.clone(),
+                modified_at,
+                size_bytes,
+            };
+            Some((id.clone(), installed))
+        })
+        .collect()
+}
+
+/// Maps the mod IDs of every mod folder directly below `dir` to the folder and its
+/// Workshop ID.
+fn index_mods_folder(
+    dir: &Path,
+    workshop_id: Option<&str>,
+    folders: &mut HashMap<String, (PathBuf, Option<String>)>,
+) {
+    for mod_dir in read_dir_paths(dir).into_iter().filter(|p| p.is_dir()) {
+        let mut ids = read_mod_info_id(&mod_dir.join(MOD_INFO_FILE_NAME)).into_iter().collect::<Vec<_>>();
+        for version_dir in read_dir_paths(&mod_dir).into_iter().filter(|p| p.is_dir()) {
+            ids.extend(read_mod_info_id(&version_dir.join(MOD_INFO_FILE_NAME)));
+        }
+        for id in ids {
+            folders.insert(id, (mod_dir.clone(), workshop_id.map(str::to_string)));
+        }
+    }
+}
+
+/// Reads the `id=` line of a `mod.info` file.
+fn read_mod_info_id(path: &Path) -> Option<String> {
+    let content = fs::read_to_string(path).ok()?;
+    content.lines().find_map(|line| {
+        let (key, value) = line.split_once('=')?;
+        let id = value.trim().trim_start_matches('\\');
+        (key.trim().eq_ignore_ascii_case("id") && !id.is_empty()).then(|| id.to_string())
+    })
+}
+
+/// Returns the total size and newest modification time of the files below a directory.
+fn content_stats(dir: &Path) -> (u64, Option<DateTime<Utc>>) {
+    let mut size = 0;
+    let mut newest: Option<DateTime<Utc>> = None;
+    for path in read_dir_paths(dir) {
+        if path.is_dir() {
+            let (dir_size, dir_newest) = content_stats(&path);
+            size += dir_size;
+            newest = newest.max(dir_newest);
+        } else if let Ok(metadata) = fs::metadata(&path) {
+            size += metadata.len();
+            newest = newest.max(metadata.modified().ok().map(DateTime::<Utc>::from));
+        }
+    }
+    (size, newest)
+}
+
+fn read_dir_paths(dir: &Path) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)
+        .map(|entries| entries.filter_map(Result::ok).map(|e| e.path()).collect())
+        .unwrap_or_default();
+    paths.sort();
+    paths
+}
+
+/// Builds the manifest of a save's mods.
+///
+/// # Arguments
+/// * `save_mods` - Mod IDs of the save
+/// * `installed` - Installed mods (see `index_installed_mods`)
+///
+/// # Returns
+/// `ModsManifest` - Mods in save order, with the installed content's Workshop ID,
+/// timestamp and size where the mod is installed
+pub fn build_manifest(save_mods: &[String], installed: &HashMap<String, InstalledMod>) -> ModsManifest {
+    ModsManifest {
+        version: MODS_MANIFEST_VERSION,
+        captured_at: Utc::now(),
+        mods: save_mods
+            .iter()
+            .map(|id| {
+                let installed = installed.get(id);
+                ManifestMod {
+                    id: id.clone(),
+                    workshop_id: installed.and_then(|m| m.workshop_id.clone()),
+                    installed_modified_at: installed.and_then(|m| m.modified_at),
+                    installed_size_bytes: installed.map(|m| m.size_bytes),
+                }
+            })
+            .collect(),
+    }
+}
+
+/// Returns the manifest file to add to a backup of `save_dir`, if the save uses mods.
+///
+/// Capturing is best-effort: unreadable Workshop content only leaves fields empty.
+pub fn capture_manifest(save_dir: &Path, config: &Config) -> Option<ExtraArchiveFile> {
+    let save_mods = read_save_mods(save_dir);
+    if save_mods.is_empty() {
+        return None;
+    }
+
+    let workshop_dir = locate_workshop_content_dir(config);
+    let local_dir = local_mods_dir(config);
+    let installed = index_installed_mods(workshop_dir.as_deref(), local_dir.as_deref(), &save_mods);
+    let manifest = build_manifest(&save_mods, &installed);
+
+    match serde_json::to_vec_pretty(&manifest) {
+        Ok(contents) => Some(ExtraArchiveFile {
+            name: MODS_MANIFEST_FILE_NAME.to_string(),
+            contents,
+        }),
+        Err(e) => {
+            eprintln!("Failed to serialize the mod manifest: {}", e);
+            None
+        }
+    }
+}
+
+/// Compares a manifest with the installed mods.
+///
+/// # Behavior
+/// - A mod is missing if no installed mod has its ID
+/// - A mod is updated if its installed content is newer than recorded in the
+///   manifest, or than the backup itself when the manifest has no timestamp
+pub fn compare_manifest(
+    manifest: &ModsManifest,
+    installed: &HashMap<String, InstalledMod>,
+) -> ModCompatibilityReport {
+    let mut report = ModCompatibilityReport {
+        has_manifest: true,
+        mod_count: manifest.mods.len(),
+        ..Default::default()
+    };
+
+    for recorded in &manifest.mods {
+        match installed.get(&recorded.id) {
+            None => report.missing.push(MissingMod {
+                id: recorded.id.clone(),
+                workshop_id: recorded.workshop_id.clone(),
+                workshop_url: recorded.workshop_id.as_deref().map(workshop_url),
+            }),
+            Some(current) => {
+                let backed_up = recorded.installed_modified_at.unwrap_or(manifest.captured_at);
+                if let Some(installed_at) = current.modified_at.filter(|t| *t > backed_up) {
+                    report.updated.push(UpdatedMod {
+                        id: recorded.id.clone(),
+                        workshop_id: current.workshop_id.clone(),
+                        backed_up_modified_at: backed_up,
+                        installed_modified_at: installed_at,
+                    });
+                }
+            }
+        }
+    }
+    report
+}
+
+/// Checks a backup's mods against the installed ones before restoring it.
+///
+/// # Arguments
+/// * `save_name` - Relative path of the save (e.g., "Multiplayer/servertest")
+/// * `backup_name` - Name of the backup file
+///
+/// # Returns
+/// `ModsResult<ModCompatibilityReport>` - Missing and updated mods; `has_manifest`
+/// is false for backups without a manifest
+pub fn check_backup_mods(save_name: &str, backup_name: &str) -> ModsResult<ModCompatibilityReport> {
+    let config = config_module::load_config()?;
+    let backup_dir = get_save_backup_dir(&config.get_backup_path()?, save_name)?;
+
+    let Some(contents) = read_file_from_tar_gz(&backup_dir.join(backup_name), MODS_MANIFEST_FILE_NAME)? else {
+        return Ok(ModCompatibilityReport::default());
+    };
+    let manifest: ModsManifest = serde_json::from_slice(&contents)?;
+
+    let workshop_dir = locate_workshop_content_dir(&config);
+    let local_dir = local_mods_dir(&config);
+    let mod_ids: Vec<String> = manifest.mods.iter().map(|m| m.id.clone()).collect();
+    let installed = index_installed_mods(workshop_dir.as_deref(), local_dir.as_deref(), &mod_ids);
+    Ok(compare_manifest(&manifest, &installed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestEnv;
+    use std::time::{Duration, SystemTime};
+    use tempfile::TempDir;
+
+    const MODS_TXT: &str = "VERSION = 1,\n\nmods\n{\n    mod = Hydrocraft,\n    mod = \\Brita,\n    mod = Hydrocraft,\n}\n\nmaps\n{\n    map = Muldraugh, KY,\n}\n";
+
+    /// Creates `<root>/<workshop id>/mods/<folder>/mod.info` with `id=<mod id>`.
+    fn workshop_mod(root: &Path, workshop_id: &str, folder: &str, mod_id: &str) -> PathBuf {
+        let dir = root.join(workshop_id).join("mods").join(folder);
+        fs::create_dir_all(dir.join("media")).unwrap();
+        fs::write(dir.join(MOD_INFO_FILE_NAME), format!("name={}\nid={}\n", folder, mod_id)).unwrap();
+        fs::write(dir.join("media").join("items.txt"), "item").unwrap();
+        dir
+    }
+
+    fn set_modified(path: &Path, time: SystemTime) {
+        fs::File::options().write(true).open(path).unwrap().set_modified(time).unwrap();
+    }
+
+    #[test]
+    fn test_parse_save_mods() {
+        assert_eq!(parse_save_mods(MODS_TXT), vec!["Hydrocraft", "Brita"]);
+        assert!(parse_save_mods("VERSION = 1,\nmods\n{\n}\n").is_empty());
+        assert!(parse_save_mods("").is_empty());
+    }
+
+    #[test]
+    fn test_index_workshop_and_local_mods() {
+        let workshop = TempDir::new().unwrap();
+        workshop_mod(workshop.path(), "498441420", "Hydrocraft", "Hydrocraft");
+        // Build 42 layout: mod.info in a version folder
+        let brita = workshop.path().join("2200148440/mods/Brita/42");
+        fs::create_dir_all(&brita).unwrap();
+        fs::write(brita.join(MOD_INFO_FILE_NAME), "id=\\Brita\n").unwrap();
+        // Not a Workshop item
+        fs::create_dir_all(workshop.path().join("notes")).unwrap();
+
+        let local = TempDir::new().unwrap();
+        fs::create_dir_all(local.path().join("MyMod")).unwrap();
+        fs::write(local.path().join("MyMod").join(MOD_INFO_FILE_NAME), "id=MyMod\n").unwrap();
+
+        let ids: Vec<String> = ["Hydrocraft", "Brita", "MyMod"].iter().map(|m| m.to_string()).collect();
+        let installed = index_installed_mods(Some(workshop.path()), Some(local.path()), &ids);
+        assert_eq!(installed.len(), 3);
+        assert_eq!(installed["Hydrocraft"].workshop_id.as_deref(), Some("498441420"));
+        assert_eq!(installed["Hydrocraft"].size_bytes, "name=Hydrocraft\nid=Hydrocraft\n".len() as u64 + 4);
+        assert!(installed["Hydrocraft"].modified_at.is_some());
+        assert_eq!(installed["Brita"].workshop_id.as_deref(), Some("2200148440"));
+        assert_eq!(installed["MyMod"].workshop_id, None);
+
+        // Mods the save doesn't use are left out
+        let only_brita = index_installed_mods(Some(workshop.path()), Some(local.path()), &ids[1..2]);
+        assert_eq!(only_brita.keys().collect::<Vec<_>>(), vec!["Brita"]);
+
+        assert!(index_installed_mods(Some(&workshop.path().join("missing")), None, &ids).is_empty());
+    }
+
+    #[test]
+    fn test_compare_reports_missing_and_updated_mods() {
+        let workshop = TempDir::new().unwrap();
+        let hydrocraft = workshop_mod(workshop.path(), "498441420", "Hydrocraft", "Hydrocraft");
+        workshop_mod(workshop.path(), "2200148440", "Brita", "Brita");
+        workshop_mod(workshop.path(), "2392709985", "Tsarslib", "tsarslib");
+
+        let old = SystemTime::now() - Duration::from_secs(3600);
+        for file in ["mod.info", "media/items.txt"] {
+            set_modified(&hydrocraft.join(file), old);
+        }
+        let mods: Vec<String> = ["Hydrocraft", "Brita", "tsarslib"].iter().map(|m| m.to_string()).collect();
+        let manifest = build_manifest(&mods, &index_installed_mods(Some(workshop.path()), None, &mods));
+        assert_eq!(manifest.mods[0].workshop_id.as_deref(), Some("498441420"));
+
+        // Later: Brita is unsubscribed and Hydrocraft updated
+        fs::remove_dir_all(workshop.path().join("2200148440")).unwrap();
+        fs::write(hydrocraft.join("media").join("items.txt"), "new items").unwrap();
+        set_modified(&hydrocraft.join("media/items.txt"), SystemTime::now() + Duration::from_secs(60));
+
+        let report = compare_manifest(&manifest, &index_installed_mods(Some(workshop.path()), None, &mods));
+        assert!(report.has_manifest);
+        assert_eq!(report.mod_count, 3);
+        assert!(!report.is_compatible());
+        assert_eq!(
+            report.missing,
+            vec![MissingMod {
+                id: "Brita".to_string(),
+                workshop_id: Some("2200148440".to_string()),
+                workshop_url: Some(
+                    "https://steamcommunity.com/sharedfiles/filedetails/?id=2200148440".to_string()
+                ),
+            }]
+        );
+        assert_eq!(report.updated.len(), 1);
+        assert_eq!(report.updated[0].id, "Hydrocraft");
+        assert_eq!(report.updated[0].backed_up_modified_at, manifest.mods[0].installed_modified_at.unwrap());
+    }
+
+    #[test]
+    fn test_mods_missing_at_backup_time_have_no_workshop_link() {
+        let manifest = build_manifest(&["Unknown".to_string()], &HashMap::new());
+        assert_eq!(manifest.mods[0].installed_size_bytes, None);
+
+        let report = compare_manifest(&manifest, &HashMap::new());
+        assert_eq!(report.missing[0].workshop_url, None);
+    }
+
+    #[test]
+    fn test_backup_carries_manifest_and_restore_check_reads_it() {
+        let env = TestEnv::with_paths();
+        let workshop = env.root().join("workshop");
+        workshop_mod(&workshop, "498441420", "Hydrocraft", "Hydrocraft");
+        config_module::modify_config(|config| {
+            config.workshop_content_path = Some(workshop.to_string_lossy().to_string());
+            Ok(())
+        })
+        .unwrap();
+
+        let save_dir = env.save_dir().join("Survival/Modded");
+        fs::create_dir_all(save_dir.join("map")).unwrap();
+        fs::write(save_dir.join("map/map_0_0.bin"), b"map").unwrap();
+        fs::write(save_dir.join(SAVE_MODS_FILE_NAME), MODS_TXT).unwrap();
+        let result = crate::backup::create_backup("Survival/Modded").unwrap();
+
+        let report = check_backup_mods("Survival/Modded", &result.backup_name).unwrap();
+        assert!(report.has_manifest);
+        assert_eq!(report.mod_count, 2);
+        assert!(report.updated.is_empty());
+        assert_eq!(report.missing.len(), 1);
+        assert_eq!(report.missing[0].id, "Brita");
+
+        // The manifest never ends up in the restored save
+        crate::restore::restore_backup("Survival/Modded", &result.backup_name).unwrap();
+        assert!(!save_dir.join(MODS_MANIFEST_FILE_NAME).exists());
+        assert!(save_dir.join(SAVE_MODS_FILE_NAME).exists());
+    }
+
+    #[test]
+    fn test_saves_without_mods_get_no_manifest() {
+        let env = TestEnv::with_paths();
+        let save_dir = env.save_dir().join("Survival/Vanilla");
+        fs::create_dir_all(&save_dir).unwrap();
+        fs::write(save_dir.join("map_t.bin"), b"t").unwrap();
+        let result = crate::backup::create_backup("Survival/Vanilla").unwrap();
+
+        assert_eq!(
+            check_backup_mods("Survival/Vanilla", &result.backup_name).unwrap(),
+            ModCompatibilityReport::default()
+        );
+    }
+}
@@ -0,0 +1,343 @@
+//! Operations shared by commands and other entry points (e.g., launch arguments).
+//!
+//! Each operation registers with the task registry, records its outcome in the
+//! operation history and updates the dashboard state.
+//!
+//! Heavy filesystem work (archive creation and extraction) runs on the
+//! `OperationQueue`, a dedicated worker pool sized by `max_concurrent_operations`,
+//! so it can neither block the async command threads nor crowd out lighter
+//! blocking work such as directory listings.
+
+use crate::backup::{self, BackupResult, BackupResultT, PruneResult};
+use crate::dashboard::DashboardState;
+use crate::errors::{ErrorCode, ErrorPayload};
+use crate::file_ops::{self, FileOpsError};
+use crate::history::{self, HistoryRecord, OperationTrigger, OperationType};
+use crate::i18n;
+use crate::restore::{self, RestoreResult, RestoreResultT};
+use crate::tags;
+use crate::tasks::{TaskKind, TaskRegistry};
+use crate::webhooks::{self, AppEvent};
+use std::path::Path;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tokio::sync::oneshot;
+
+/// Job executed by a queue worker.
+type Job = Box<dyn FnOnce() + Send + 'static>;
+
+/// Worker pool for heavy blocking operations, managed by Tauri.
+#[derive(Debug)]
+pub struct OperationQueue {
+    sender: Mutex<Sender<Job>>,
+    workers: usize,
+}
+
+impl OperationQueue {
+    /// Starts a queue with `workers` threads (at least one).
+    pub fn new(workers: usize) -> Self {
+        let workers = workers.max(1);
+        let (sender, receiver) = mpsc::channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+
+        for i in 0..workers {
+            let receiver = Arc::clone(&receiver);
+            let _ = std::thread::Builder::new()
+                .name(format!("operation-worker-{}", i))
+                .spawn(move || worker_loop(&receiver));
+        }
+
+        OperationQueue {
+            sender: Mutex::new(sender),
+            workers,
+        }
+    }
+
+    /// Returns the number of worker threads.
+    pub fn workers(&self) -> usize {
+        self.workers
+    }
+
+    /// Runs a blocking operation on a queue worker and waits for its result.
+    ///
+    /// # Arguments
+    /// * `f` - Blocking operation
+    ///
+    /// # Returns
+    /// `Result<T, E>` - The operation's result, or an I/O error if the worker
+    /// panicked or the queue is shut down
+    ///
+    /// # Behavior
+    /// Operations beyond the worker count wait in FIFO order.
+    pub async fn run<T, E, F>(&self, f: F) -> Result<T, E>
+    where
+        T: Send + 'static,
+        E: From<FileOpsError> + Send + 'static,
+        F: FnOnce() -> Result<T, E> + Send + 'static,
+    {
+        let (result_sender, result_receiver) = oneshot::channel();
+        let job: Job = Box::new(move || {
+            let _ = result_sender.send(f());
+        });
+
+        let queued = self
+            .sender
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .send(job)
+            .is_ok();
+        if !queued {
+            return Err(worker_error("Operation queue is shut down"));
+        }
+
+        result_receiver
+            .await
+            .unwrap_or_else(|_| Err(worker_error("Operation worker stopped unexpectedly")))
+    }
+}
+
+/// Receives and runs jobs until the queue is dropped.
+fn worker_loop(receiver: &Mutex<Receiver<Job>>) {
+    loop {
+        let job = match receiver.lock().unwrap_or_else(|e| e.into_inner()).recv() {
+            Ok(job) => job,
+            Err(_) => return,
+        };
+        // A panicking job drops its result sender; keep the worker alive
+        let _ = panic::catch_unwind(AssertUnwindSafe(job));
+    }
+}
+
+fn worker_error<E: From<FileOpsError>>(message: &str) -> E {
+    FileOpsError::Io(std::io::Error::other(message.to_string())).into()
+}
+
+/// Creates a backup of a save as a tracked, cancellable task.
+///
+/// # Arguments
+/// * `save_name` - Relative path of the save to backup (e.g., "Survival/MySave")
+/// * `trigger` - What started the backup, recorded in the history and used to pick
+///   the auto-tag rule applied to the new backup
+/// * `dashboard` - Dashboard state to update
+/// * `tasks` - Task registry the backup registers with
+/// * `queue` - Queue the archive creation runs on
+///
+/// # Returns
+/// `BackupResultT<BackupResult>` - Information about the created backup
+pub async fn run_backup(
+    save_name: &str,
+    trigger: OperationTrigger,
+    dashboard: &DashboardState,
+    tasks: &TaskRegistry,
+    queue: &OperationQueue,
+) -> BackupResultT<BackupResult> {
+    let started = Instant::now();
+    let task = tasks.start(TaskKind::Backup, save_name, true);
+    let result = {
+        let save_name = save_name.to_string();
+        let task = task.clone();
+        queue
+            .run(move || {
+                task.mark_running();
+                backup::create_backup_with_task(&save_name, &task)
+            })
+            .await
+    };
+    tasks.finish(&task, &result);
+    history::record_backup(save_name, trigger, started, &result);
+    if let Ok(backup) = &result {
+        tags::apply_auto_tags_best_effort(save_name, &backup.backup_name, trigger);
+    }
+    dashboard.record_operation("create_backup", save_name, &result);
+    if result.is_ok() {
+        dashboard.refresh_backup_stats(save_name);
+    }
+    emit_backup_events(save_name, started, &result);
+    result
+}
+
+/// Notifies webhooks of a finished or failed backup and of garbage collection.
+fn emit_backup_events(save_name: &str, started: Instant, result: &BackupResultT<BackupResult>) {
+    let duration_ms = started.elapsed().as_millis() as u64;
+    match result {
+        Ok(backup) => {
+            webhooks::emit(AppEvent::BackupFinished {
+                save_name: save_name.to_string(),
+                result: backup.clone(),
+                duration_ms,
+            });
+            if !backup.deleted_backups.is_empty() {
+                webhooks::emit(AppEvent::PruneCompleted {
+                    save_name: save_name.to_string(),
+                    deleted: backup.deleted_backups.clone(),
+                });
+            }
+            if let Some(backup_dir) = Path::new(&backup.backup_path).parent() {
+                webhooks::check_disk_space(backup_dir);
+            }
+        }
+        Err(e) => {
+            let error = ErrorPayload::from(e);
+            if error.code != ErrorCode::Cancelled {
+                webhooks::emit(AppEvent::BackupFailed {
+                    save_name: save_name.to_string(),
+                    error,
+                    duration_ms,
+                });
+            }
+        }
+    }
+}
+
+/// Restores a backup as a tracked task.
+///
+/// # Arguments
+/// * `save_name` - Relative path of the save to restore (e.g., "Survival/MySave")
+/// * `backup_name` - Name of the backup to restore
+/// * `trigger` - What started the restore, recorded in the history
+/// * `dashboard` - Dashboard state to update
+/// * `tasks` - Task registry the restore registers with
+/// * `queue` - Queue the extraction runs on
+///
+/// # Returns
+/// `RestoreResultT<RestoreResult>` - Information about the restore
+///
+/// # Behavior
+/// With `inherit_tags_on_restore` set, a successful restore adds the backup's tags
+/// to the save.
+pub async fn run_restore(
+    save_name: &str,
+    backup_name: &str,
+    trigger: OperationTrigger,
+    dashboard: &DashboardState,
+    tasks: &TaskRegistry,
+    queue: &OperationQueue,
+) -> RestoreResultT<RestoreResult> {
+    let started = Instant::now();
+    // Restores aren't cancellable: stopping halfway would leave a broken save
+    let task = tasks.start(TaskKind::Restore, save_name, false);
+    let result = {
+        let (save_name, backup_name, task) = (save_name.to_string(), backup_name.to_string(), task.clone());
+        queue
+            .run(move || {
+                task.mark_running();
+                let result = restore::restore_backup(&save_name, &backup_name);
+                if let Ok(restored) = &result {
+                    restore::report_restore_bytes(&task, restored);
+                }
+                result
+            })
+            .await
+    };
+    tasks.finish(&task, &result);
+    if result.is_ok() {
+        tags::inherit_tags_on_restore_best_effort(save_name, backup_name);
+    }
+    let bytes = result
+        .as_ref()
+        .ok()
+        .and_then(|r| file_ops::get_file_size(Path::new(&r.backup_path)).ok());
+    history::append_record_best_effort(
+        &HistoryRecord::new(OperationType::Restore, save_name, trigger)
+            .with_backup(backup_name)
+            .with_bytes(bytes)
+            .finish(started, &result),
+    );
+    dashboard.record_operation("restore_backup", save_name, &result);
+    result
+}
+
+/// Applies the retention policy to a save's backups and records the prune.
+///
+/// # Arguments
+/// * `save_name` - Relative path of the save
+/// * `dry_run` - Only report what would be deleted; nothing is recorded
+/// * `trigger` - What started the prune, recorded in the history
+///
+/// # Returns
+/// `BackupResultT<PruneResult>` - Backups kept and deleted
+pub fn run_prune(save_name: &str, dry_run: bool, trigger: OperationTrigger) -> BackupResultT<PruneResult> {
+    let started = Instant::now();
+    let result = backup::prune_backups(save_name, dry_run);
+    let pruned = result.as_ref().map_or(0, |r| r.deleted.len());
+    if !dry_run && (pruned > 0 || result.is_err()) {
+        history::append_record_best_effort(
+            &HistoryRecord::new(OperationType::Prune, save_name, trigger)
+                .with_message(i18n::t(
+                    i18n::keys::HISTORY_PRUNE_SUMMARY,
+                    &[("count", &pruned.to_string())],
+                ))
+                .finish(started, &result),
+        );
+    }
+    if let Ok(pruned) = &result {
+        if !dry_run && !pruned.deleted.is_empty() {
+            webhooks::emit(AppEvent::PruneCompleted {
+                save_name: save_name.to_string(),
+                deleted: pruned.deleted.clone(),
+            });
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_queue_runs_jobs_and_survives_panics() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let queue = OperationQueue::new(1);
+
+        runtime.block_on(async {
+            let value: Result<u32, FileOpsError> = queue.run(|| Ok(42)).await;
+            assert_eq!(value.unwrap(), 42);
+
+            let panicked: Result<u32, FileOpsError> = queue.run(|| panic!("boom")).await;
+            assert!(matches!(panicked, Err(FileOpsError::Io(_))));
+
+            // The worker is still available after the panic
+            let value: Result<u32, FileOpsError> = queue.run(|| Ok(7)).await;
+            assert_eq!(value.unwrap(), 7);
+        });
+    }
+
+    #[test]
+    fn test_slow_backup_does_not_block_light_commands() {
+        // A single async thread: inline blocking work would stall everything else
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        let queue = OperationQueue::new(1);
+
+        runtime.block_on(async {
+            // The backup holds the worker until the light command has finished,
+            // so it only succeeds if the light command ran while it was busy
+            let (release_tx, release_rx) = mpsc::channel::<()>();
+            let slow_backup = queue.run(move || {
+                release_rx
+                    .recv_timeout(Duration::from_secs(10))
+                    .map_err(|_| FileOpsError::Io(std::io::Error::other("light command was blocked")))
+            });
+
+            let light_command = async move {
+                let listed: Result<usize, FileOpsError> =
+                    file_ops::run_blocking(|| Ok(std::fs::read_dir(".")?.count())).await;
+                listed.unwrap();
+                release_tx.send(()).unwrap();
+            };
+
+            let (backup, ()) = tokio::join!(slow_backup, light_command);
+            backup.expect("light command should complete while a backup is running");
+        });
+    }
+
+}
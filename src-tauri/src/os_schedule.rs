@@ -0,0 +1,1024 @@
+//! OS-level scheduled backups that run while the app is closed.
+//!
+//! A schedule registers a job with the platform scheduler that runs the headless
+//! CLI (`<exe> --config-dir <dir> backup --save <save> --silent`):
+//! - Linux: systemd user timer and service in `~/.config/systemd/user`
+//! - macOS: launchd agent in `~/Library/LaunchAgents`
+//! - Windows: Task Scheduler task created with `schtasks`
+//!
+//! The config's `os_schedules` records what should be registered;
+//! `list_os_schedules` compares it with what the scheduler actually has and
+//! reports drift. Unit, plist and task definitions come from pure functions; the
+//! registration itself goes through `SchedulerBackend`.
+
+use crate::config::{self as config_module, ConfigError};
+use crate::file_ops::{self, FileOpsError};
+use crate::keys::SaveKey;
+use serde::{Deserialize, Serialize, Serializer};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Prefix of every job id, and the systemd unit and task names.
+pub const JOB_ID_PREFIX: &str = "pz-backup-tool-";
+
+/// Prefix of launchd labels, followed by the job id.
+const LAUNCHD_LABEL_PREFIX: &str = "com.woxqaq.";
+
+/// Weekday names as used by systemd and schtasks, indexed from Sunday.
+const WEEKDAYS: [(&str, &str); 7] = [
+    ("Sun", "SUN"),
+    ("Mon", "MON"),
+    ("Tue", "TUE"),
+    ("Wed", "WED"),
+    ("Thu", "THU"),
+    ("Fri", "FRI"),
+    ("Sat", "SAT"),
+];
+
+/// An OS schedule recorded in the config.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct OsScheduleConfig {
+    /// Relative path of the save (e.g., "Survival/A")
+    pub save_name: String,
+    /// Cron-like spec the job was registered with (e.g., "30 3 * * *")
+    pub spec: String,
+}
+
+/// Error type for OS schedule operations.
+#[derive(Debug)]
+pub enum OsScheduleError {
+    /// The spec isn't in the supported cron subset
+    InvalidSpec(String),
+    /// The platform has no supported scheduler
+    Unsupported,
+    /// A scheduler command failed
+    SchedulerFailed { command: String, message: String },
+    /// File operation error
+    FileOp(FileOpsError),
+    /// Config error
+    Config(ConfigError),
+}
+
+impl From<FileOpsError> for OsScheduleError {
+    fn from(err: FileOpsError) -> Self {
+        OsScheduleError::FileOp(err)
+    }
+}
+
+impl From<std::io::Error> for OsScheduleError {
+    fn from(err: std::io::Error) -> Self {
+        OsScheduleError::FileOp(FileOpsError::Io(err))
+    }
+}
+
+impl From<ConfigError> for OsScheduleError {
+    fn from(err: ConfigError) -> Self {
+        OsScheduleError::Config(err)
+    }
+}
+
+impl std::fmt::Display for OsScheduleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OsScheduleError::InvalidSpec(msg) => write!(f, "Invalid schedule: {}", msg),
+            OsScheduleError::Unsupported => write!(f, "No supported OS scheduler on this platform"),
+            OsScheduleError::SchedulerFailed { command, message } => {
+                write!(f, "Scheduler command `{}` failed: {}", command, message)
+            }
+            OsScheduleError::FileOp(err) => write!(f, "File operation error: {}", err),
+            OsScheduleError::Config(err) => write!(f, "Config error: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for OsScheduleError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            OsScheduleError::FileOp(err) => Some(err),
+            OsScheduleError::Config(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl Serialize for OsScheduleError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+/// Result type for OS schedule operations.
+pub type OsScheduleResult<T> = Result<T, OsScheduleError>;
+
+/// When a job runs, parsed from a cron-like spec.
+///
+/// Supported are the fields `minute hour day-of-month month day-of-week` with
+/// day-of-month and month `*`:
+/// - `30 3 * * *` - daily at 03:30
+/// - `0 22 * * 1,5` (or `mon,fri`) - at 22:00 on the listed weekdays
+/// - `15 * * * *` - hourly at minute 15
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScheduleSpec {
+    /// Minute of the hour (0-59)
+    pub minute: u8,
+    /// Hour of the day (0-23); None runs every hour
+    pub hour: Option<u8>,
+    /// Weekdays (0 = Sunday), sorted; empty runs every day
+    pub weekdays: Vec<u8>,
+}
+
+impl ScheduleSpec {
+    /// Parses a cron-like spec.
+    pub fn parse(spec: &str) -> OsScheduleResult<Self> {
+        let invalid = |msg: &str| OsScheduleError::InvalidSpec(format!("{} in \"{}\"", msg, spec));
+        let fields: Vec<&str> = spec.split_whitespace().collect();
+        let [minute, hour, day, month, weekdays] = fields[..] else {
+            return Err(invalid("expected 5 fields"));
+        };
+        if day != "*" || month != "*" {
+            return Err(invalid("day of month and month must be *"));
+        }
+
+        let minute = parse_number(minute, 59).ok_or_else(|| invalid("minute must be 0-59"))?;
+        let hour = match hour {
+            "*" => None,
+            hour => Some(parse_number(hour, 23).ok_or_else(|| invalid("hour must be 0-23 or *"))?),
+        };
+        let mut days = Vec::new();
+        if weekdays != "*" {
+            if hour.is_none() {
+                return Err(invalid("hourly schedules can't be limited to weekdays"));
+            }
+            for day in weekdays.split(',') {
+                let day = parse_weekday(day).ok_or_else(|| invalid("weekdays must be 0-7 or sun-sat"))?;
+                if !days.contains(&day) {
+                    days.push(day);
+                }
+            }
+            days.sort_unstable();
+        }
+
+        Ok(ScheduleSpec {
+            minute,
+            hour,
+            weekdays: days,
+        })
+    }
+
+    /// Returns the spec as an `OnCalendar=` expression.
+    pub fn systemd_on_calendar(&self) -> String {
+        let days: Vec<&str> = self.weekdays.iter().map(|&d| WEEKDAYS[d as usize].0).collect();
+        let prefix = if days.is_empty() { String::new() } else { format!("{} ", days.join(",")) };
+        let hour = self.hour.map_or_else(|| "*".to_string(), |h| format!("{:02}", h));
+        format!("{}*-*-* {}:{:02}:00", prefix, hour, self.minute)
+    }
+}
+
+fn parse_number(field: &str, max: u8) -> Option<u8> {
+    field.parse::<u8>().ok().filter(|&n| n <= max)
+}
+
+fn parse_weekday(field: &str) -> Option<u8> {
+    if let Some(day) = parse_number(field, 7) {
+        return Some(day % 7);
+    }
+    WEEKDAYS
+        .iter()
+        .position(|(name, _)| name.eq_ignore_ascii_case(field))
+        .map(|day| day as u8)
+}
+
+/// A job to register with the OS scheduler.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScheduleJob {
+    /// Job id, see `job_id`
+    pub id: String,
+    /// Relative path of the save
+    pub save_name: String,
+    /// When the job runs
+    pub spec: ScheduleSpec,
+    /// Executable of the app
+    pub program: PathBuf,
+    /// Config directory the job runs with
+    pub config_dir: PathBuf,
+}
+
+impl ScheduleJob {
+    /// Returns the command line of the job, starting with the program.
+    pub fn command_line(&self) -> Vec<String> {
+        vec![
+            self.program.to_string_lossy().into_owned(),
+            "--config-dir".to_string(),
+            self.config_dir.to_string_lossy().into_owned(),
+            "backup".to_string(),
+            "--save".to_string(),
+            self.save_name.clone(),
+            "--silent".to_string(),
+        ]
+    }
+
+    /// Returns the launchd label of the job.
+    pub fn launchd_label(&self) -> String {
+        format!("{}{}", LAUNCHD_LABEL_PREFIX, self.id)
+    }
+}
+
+/// Returns the job id of a save's schedule.
+///
+/// # Behavior
+/// The id is `pz-backup-tool-<slug>-<hash>`: a readable ASCII slug of the save and
+/// the first 8 hex digits of the SHA-256 of its `SaveKey`, so saves that slug the
+/// same still get different ids. Ids are valid systemd unit names, launchd labels
+/// and task names.
+pub fn job_id(save_name: &str) -> String {
+    let key = SaveKey::new(save_name);
+    let mut slug = String::new();
+    for c in key.as_str().chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+        if slug.len() >= 40 {
+            break;
+        }
+    }
+    let slug = slug.trim_end_matches('-');
+    let hash: String = Sha256::digest(key.as_str().as_bytes())[..4]
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    format!("{}{}-{}", JOB_ID_PREFIX, slug, hash)
+}
+
+/// Quotes a systemd `ExecStart=` argument.
+fn systemd_quote(arg: &str) -> String {
+    let escaped = arg.replace('\\', "\\\\").replace('"', "\\\"").replace('%', "%%");
+    format!("\"{}\"", escaped)
+}
+
+/// Escapes a save name for a systemd `Description=`, where `%` starts a specifier.
+fn systemd_description(save_name: &str) -> String {
+    save_name.replace('%', "%%")
+}
+
+/// Renders the systemd service that runs the backup.
+pub fn render_systemd_service(job: &ScheduleJob) -> String {
+    let exec: Vec<String> = job.command_line().iter().map(|a| systemd_quote(a)).collect();
+    format!(
+        "[Unit]\n\
+         Description=Project Zomboid backup of {save}\n\
+         \n\
+         [Service]\n\
+         Type=oneshot\n\
+         ExecStart={exec}\n",
+        save = systemd_description(&job.save_name),
+        exec = exec.join(" "),
+    )
+}
+
+/// Renders the systemd timer that starts the service.
+///
+/// `Persistent=true` runs a backup missed while the machine was off at the next boot.
+pub fn render_systemd_timer(job: &ScheduleJob) -> String {
+    format!(
+        "[Unit]\n\
+         Description=Scheduled Project Zomboid backup of {save}\n\
+         \n\
+         [Timer]\n\
+         OnCalendar={calendar}\n\
+         Persistent=true\n\
+         \n\
+         [Install]\n\
+         WantedBy=timers.target\n",
+        save = systemd_description(&job.save_name),
+        calendar = job.spec.systemd_on_calendar(),
+    )
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Renders the launchd agent plist of the job.
+pub fn render_launchd_plist(job: &ScheduleJob) -> String {
+    let mut plist = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <!DOCTYPE plist PUBLIC \"-//Apple//DTD PLIST 1.0//EN\" \"http://www.apple.com/DTDs/PropertyList-1.0.dtd\">\n\
+         <plist version=\"1.0\">\n\
+         <dict>\n",
+    );
+    plist.push_str(&format!("    <key>Label</key>\n    <string>{}</string>\n", xml_escape(&job.launchd_label())));
+    plist.push_str("    <key>ProgramArguments</key>\n    <array>\n");
+    for arg in job.command_line() {
+        plist.push_str(&format!("        <string>{}</string>\n", xml_escape(&arg)));
+    }
+    plist.push_str("    </array>\n");
+
+    // One calendar interval per weekday; launchd numbers weekdays from Sunday = 0
+    let interval = |weekday: Option<u8>, indent: &str| {
+        let mut dict = format!("{}<dict>\n", indent);
+        if let Some(day) = weekday {
+            dict.push_str(&format!("{}    <key>Weekday</key>\n{}    <integer>{}</integer>\n", indent, indent, day));
+        }
+        if let Some(hour) = job.spec.hour {
+            dict.push_str(&format!("{}    <key>Hour</key>\n{}    <integer>{}</integer>\n", indent, indent, hour));
+        }
+        dict.push_str(&format!(
+            "{}    <key>Minute</key>\n{}    <integer>{}</integer>\n{}</dict>\n",
+            indent, indent, job.spec.minute, indent
+        ));
+        dict
+    };
+    plist.push_str("    <key>StartCalendarInterval</key>\n");
+    if job.spec.weekdays.is_empty() {
+        plist.push_str(&interval(None, "    "));
+    } else {
+        plist.push_str("    <array>\n");
+        for &day in &job.spec.weekdays {
+            plist.push_str(&interval(Some(day), "        "));
+        }
+        plist.push_str("    </array>\n");
+    }
+    plist.push_str("</dict>\n</plist>\n");
+    plist
+}
+
+/// Quotes an argument for a Windows command line.
+///
+/// Backslashes are only special before a quote, so runs of them are doubled before
+/// an escaped quote and before the closing quote.
+fn windows_quote(arg: &str) -> String {
+    if !arg.is_empty() && !arg.contains([' ', '\t', '"']) {
+        return arg.to_string();
+    }
+    let mut quoted = String::from("\"");
+    let mut backslashes = 0;
+    for c in arg.chars() {
+        match c {
+            '\\' => backslashes += 1,
+            '"' => {
+                quoted.push_str(&"\\".repeat(backslashes * 2 + 1));
+                backslashes = 0;
+            }
+            _ => {
+                quoted.push_str(&"\\".repeat(backslashes));
+                backslashes = 0;
+            }
+        }
+        if c != '\\' {
+            quoted.push(c);
+        }
+    }
+    quoted.push_str(&"\\".repeat(backslashes * 2));
+    quoted.push('"');
+    quoted
+}
+
+/// Returns the `schtasks` arguments that create (or replace) the job's task.
+pub fn render_schtasks_create_args(job: &ScheduleJob) -> Vec<String> {
+    let command: Vec<String> = job.command_line().iter().map(|a| windows_quote(a)).collect();
+    let mut args = vec![
+        "/Create".to_string(),
+        "/F".to_string(),
+        "/TN".to_string(),
+        job.id.clone(),
+        "/TR".to_string(),
+        command.join(" "),
+    ];
+    let time = format!("{:02}:{:02}", job.spec.hour.unwrap_or(0), job.spec.minute);
+    match (job.spec.hour, job.spec.weekdays.is_empty()) {
+        (None, _) => args.extend(["/SC".to_string(), "HOURLY".to_string()]),
+        (Some(_), true) => args.extend(["/SC".to_string(), "DAILY".to_string()]),
+        (Some(_), false) => {
+            let days: Vec<&str> = job.spec.weekdays.iter().map(|&d| WEEKDAYS[d as usize].1).collect();
+            args.extend(["/SC".to_string(), "WEEKLY".to_string(), "/D".to_string(), days.join(",")]);
+        }
+    }
+    args.extend(["/ST".to_string(), time]);
+    args
+}
+
+/// Registers jobs with an OS scheduler.
+pub trait SchedulerBackend {
+    /// Registers a job, replacing a job with the same id.
+    fn install(&self, job: &ScheduleJob) -> OsScheduleResult<()>;
+    /// Unregisters a job; removing a job that isn't registered succeeds.
+    fn remove(&self, id: &str) -> OsScheduleResult<()>;
+    /// Returns the ids of the registered jobs with `JOB_ID_PREFIX`.
+    fn registered(&self) -> OsScheduleResult<Vec<String>>;
+}
+
+/// Runs a scheduler command, failing if it exits unsuccessfully.
+fn run_command(program: &str, args: &[String]) -> OsScheduleResult<String> {
+    let command = format!("{} {}", program, args.join(" "));
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .map_err(|e| OsScheduleError::SchedulerFailed {
+            command: command.clone(),
+            message: e.to_string(),
+        })?;
+    if !output.status.success() {
+        return Err(OsScheduleError::SchedulerFailed {
+            command,
+            message: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        });
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Returns the ids of the files in `dir` named `<id><suffix>`.
+fn ids_in_dir(dir: &Path, prefix: &str, suffix: &str) -> OsScheduleResult<Vec<String>> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err.into()),
+    };
+    let mut ids: Vec<String> = entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let id = name.strip_prefix(prefix)?.strip_suffix(suffix)?;
+            id.starts_with(JOB_ID_PREFIX).then(|| id.to_string())
+        })
+        .collect();
+    ids.sort();
+    Ok(ids)
+}
+
+/// systemd user timers.
+pub struct SystemdBackend {
+    /// Directory of the user units (`~/.config/systemd/user`)
+    pub unit_dir: PathBuf,
+}
+
+impl SchedulerBackend for SystemdBackend {
+    fn install(&self, job: &ScheduleJob) -> OsScheduleResult<()> {
+        std::fs::create_dir_all(&self.unit_dir)?;
+        file_ops::write_file_atomic(
+            &self.unit_dir.join(format!("{}.service", job.id)),
+            render_systemd_service(job).as_bytes(),
+        )?;
+        file_ops::write_file_atomic(
+            &self.unit_dir.join(format!("{}.timer", job.id)),
+            render_systemd_timer(job).as_bytes(),
+        )?;
+        run_command("systemctl", &["--user".into(), "daemon-reload".into()])?;
+        run_command(
+            "systemctl",
+            &["--user".into(), "enable".into(), "--now".into(), format!("{}.timer", job.id)],
+        )?;
+        Ok(())
+    }
+
+    fn remove(&self, id: &str) -> OsScheduleResult<()> {
+        // Fails if the timer was never enabled; the unit files are what count
+        let _ = run_command(
+            "systemctl",
+            &["--user".into(), "disable".into(), "--now".into(), format!("{}.timer", id)],
+        );
+        for suffix in ["timer", "service"] {
+            match std::fs::remove_file(self.unit_dir.join(format!("{}.{}", id, suffix))) {
+                Err(err) if err.kind() != std::io::ErrorKind::NotFound => return Err(err.into()),
+                _ => {}
+            }
+        }
+        run_command("systemctl", &["--user".into(), "daemon-reload".into()])?;
+        Ok(())
+    }
+
+    fn registered(&self) -> OsScheduleResult<Vec<String>> {
+        ids_in_dir(&self.unit_dir, "", ".timer")
+    }
+}
+
+/// launchd user agents.
+pub struct LaunchdBackend {
+    /// Directory of the user agents (`~/Library/LaunchAgents`)
+    pub agents_dir: PathBuf,
+}
+
+impl LaunchdBackend {
+    fn plist_path(&self, id: &str) -> PathBuf {
+        self.agents_dir.join(format!("{}{}.plist", LAUNCHD_LABEL_PREFIX, id))
+    }
+}
+
+impl SchedulerBackend for LaunchdBackend {
+    fn install(&self, job: &ScheduleJob) -> OsScheduleResult<()> {
+        let path = self.plist_path(&job.id);
+        std::fs::create_dir_all(&self.agents_dir)?;
+        // A changed agent must be unloaded before launchd picks up the new plist
+        let _ = run_command("launchctl", &["unload".into(), path.to_string_lossy().into_owned()]);
+        file_ops::write_file_atomic(&path, render_launchd_plist(job).as_bytes())?;
+        run_command("launchctl", &["load".into(), "-w".into(), path.to_string_lossy().into_owned()])?;
+        Ok(())
+    }
+
+    fn remove(&self, id: &str) -> OsScheduleResult<()> {
+        let path = self.plist_path(id);
+        if !path.exists() {
+            return Ok(());
+        }
+        let _ = run_command("launchctl", &["unload".into(), "-w".into(), path.to_string_lossy().into_owned()]);
+        std::fs::remove_file(&path)?;
+        Ok(())
+    }
+
+    fn registered(&self) -> OsScheduleResult<Vec<String>> {
+        ids_in_dir(&self.agents_dir, LAUNCHD_LABEL_PREFIX, ".plist")
+    }
+}
+
+/// Windows Task Scheduler through `schtasks`.
+pub struct SchtasksBackend;
+
+impl SchedulerBackend for SchtasksBackend {
+    fn install(&self, job: &ScheduleJob) -> OsScheduleResult<()> {
+        run_command("schtasks", &render_schtasks_create_args(job))?;
+        Ok(())
+    }
+
+    fn remove(&self, id: &str) -> OsScheduleResult<()> {
+        if !self.registered()?.iter().any(|registered| registered == id) {
+            return Ok(());
+        }
+        run_command("schtasks", &["/Delete".into(), "/F".into(), "/TN".into(), id.to_string()])?;
+        Ok(())
+    }
+
+    fn registered(&self) -> OsScheduleResult<Vec<String>> {
+        let output = run_command("schtasks", &["/Query".into(), "/FO".into(), "CSV".into(), "/NH".into()])?;
+        Ok(parse_schtasks_query(&output))
+    }
+}
+
+/// Extracts the ids of our tasks from `schtasks /Query /FO CSV /NH` output.
+fn parse_schtasks_query(output: &str) -> Vec<String> {
+    let mut ids: Vec<String> = output
+        .lines()
+        .filter_map(|line| line.split(',').next())
+        .map(|name| name.trim().trim_matches('"').trim_start_matches('\\').to_string())
+        .filter(|name| name.starts_with(JOB_ID_PREFIX))
+        .collect();
+    ids.sort();
+    ids.dedup();
+    ids
+}
+
+/// Returns the scheduler backend of the platform.
+pub fn platform_backend() -> OsScheduleResult<Box<dyn SchedulerBackend>> {
+    #[cfg(target_os = "linux")]
+    {
+        let dir = dirs::config_dir().ok_or(OsScheduleError::Unsupported)?;
+        Ok(Box::new(SystemdBackend {
+            unit_dir: dir.join("systemd").join("user"),
+        }))
+    }
+    #[cfg(target_os = "macos")]
+    {
+        let home = dirs::home_dir().ok_or(OsScheduleError::Unsupported)?;
+        Ok(Box::new(LaunchdBackend {
+            agents_dir: home.join("Library").join("LaunchAgents"),
+        }))
+    }
+    #[cfg(windows)]
+    {
+        Ok(Box::new(SchtasksBackend))
+    }
+    #[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+    {
+        Err(OsScheduleError::Unsupported)
+    }
+}
+
+/// Whether a schedule's registration matches the config.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ScheduleState {
+    /// In the config and registered
+    InSync,
+    /// In the config, but not registered (e.g., removed by hand)
+    NotRegistered,
+    /// Registered, but not in the config (e.g., left over from another config dir)
+    NotInConfig,
+}
+
+/// An OS schedule and its state.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct OsScheduleStatus {
+    /// Job id
+    pub id: String,
+    /// Save the job backs up; None for jobs that aren't in the config
+    pub save_name: Option<String>,
+    /// Spec from the config; None for jobs that aren't in the config
+    pub spec: Option<String>,
+    /// Whether the registration matches the config
+    pub state: ScheduleState,
+}
+
+impl OsScheduleStatus {
+    /// Returns true if the scheduler and the config disagree.
+    pub fn has_drift(&self) -> bool {
+        self.state != ScheduleState::InSync
+    }
+}
+
+/// Registers a scheduled backup of a save with the OS scheduler.
+///
+/// # Arguments
+/// * `save_name` - Relative path of the save
+/// * `spec` - Cron-like spec, see `ScheduleSpec`
+///
+/// # Returns
+/// `OsScheduleResult<OsScheduleStatus>` - The registered schedule
+///
+/// # Behavior
+/// The job runs this executable with the current config directory. An existing
+/// schedule of the save is replaced.
+pub fn install_os_schedule(save_name: &str, spec: &str) -> OsScheduleResult<OsScheduleStatus> {
+    let program = std::env::current_exe()?;
+    let config_dir = config_module::get_config_dir()?;
+    install_os_schedule_with(platform_backend()?.as_ref(), save_name, spec, &program, &config_dir)
+}
+
+/// `install_os_schedule` with an explicit backend, program and config directory.
+pub fn install_os_schedule_with(
+    backend: &dyn SchedulerBackend,
+    save_name: &str,
+    spec: &str,
+    program: &Path,
+    config_dir: &Path,
+) -> OsScheduleResult<OsScheduleStatus> {
+    // The name ends up in unit files and command lines, where a newline starts a new directive
+    file_ops::validate_save_name(save_name)?;
+    if save_name.chars().any(char::is_control) {
+        return Err(FileOpsError::UnsafeSavePath(save_name.to_string()).into());
+    }
+    let job = ScheduleJob {
+        id: job_id(save_name),
+        save_name: SaveKey::new(save_name).to_string(),
+        spec: ScheduleSpec::parse(spec)?,
+        program: program.to_path_buf(),
+        config_dir: config_dir.to_path_buf(),
+    };
+    backend.install(&job)?;
+
+    let key = SaveKey::new(save_name);
+    let entry = OsScheduleConfig {
+        save_name: job.save_name.clone(),
+        spec: spec.trim().to_string(),
+    };
+    config_module::modify_config(|config| {
+        config.os_schedules.retain(|s| SaveKey::new(&s.save_name) != key);
+        config.os_schedules.push(entry.clone());
+        Ok(())
+    })?;
+
+    Ok(OsScheduleStatus {
+        id: job.id,
+        save_name: Some(entry.save_name),
+        spec: Some(entry.spec),
+        state: ScheduleState::InSync,
+    })
+}
+
+/// Removes the scheduled backup of a save from the OS scheduler and the config.
+///
+/// # Returns
+/// `OsScheduleResult<bool>` - true if the config had a schedule for the save
+pub fn remove_os_schedule(save_name: &str) -> OsScheduleResult<bool> {
+    remove_os_schedule_with(platform_backend()?.as_ref(), save_name)
+}
+
+/// `remove_os_schedule` with an explicit backend.
+pub fn remove_os_schedule_with(backend: &dyn SchedulerBackend, save_name: &str) -> OsScheduleResult<bool> {
+    backend.remove(&job_id(save_name))?;
+    let key = SaveKey::new(save_name);
+    let removed = config_module::modify_config(|config| {
+        let before = config.os_schedules.len();
+        config.os_schedules.retain(|s| SaveKey::new(&s.save_name) != key);
+        Ok(config.os_schedules.len() != before)
+    })?;
+    Ok(removed)
+}
+
+/// Lists the OS schedules of the config and the scheduler, flagging drift.
+pub fn list_os_schedules() -> OsScheduleResult<Vec<OsScheduleStatus>> {
+    list_os_schedules_with(platform_backend()?.as_ref())
+}
+
+/// `list_os_schedules` with an explicit backend.
+///
+/// # Behavior
+/// Config entries come first, in config order, followed by registered jobs the
+/// config doesn't know, sorted by id.
+pub fn list_os_schedules_with(backend: &dyn SchedulerBackend) -> OsScheduleResult<Vec<OsScheduleStatus>> {
+    let config = config_module::load_config()?;
+    let mut registered = backend.registered()?;
+
+    let mut statuses: Vec<OsScheduleStatus> = config
+        .os_schedules
+        .iter()
+        .map(|schedule| {
+            let id = job_id(&schedule.save_name);
+            let state = match registered.iter().position(|r| *r == id) {
+                Some(index) => {
+                    registered.remove(index);
+                    ScheduleState::InSync
+                }
+                None => ScheduleState::NotRegistered,
+            };
+            OsScheduleStatus {
+                id,
+                save_name: Some(schedule.save_name.clone()),
+                spec: Some(schedule.spec.clone()),
+                state,
+            }
+        })
+        .collect();
+
+    registered.sort();
+    statuses.extend(registered.into_iter().map(|id| OsScheduleStatus {
+        id,
+        save_name: None,
+        spec: None,
+        state: ScheduleState::NotInConfig,
+    }));
+    Ok(statuses)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestEnv;
+    use std::cell::RefCell;
+
+    /// Scheduler keeping jobs in memory.
+    #[derive(Default)]
+    struct FakeBackend {
+        jobs: RefCell<Vec<ScheduleJob>>,
+        extra: RefCell<Vec<String>>,
+    }
+
+    impl SchedulerBackend for FakeBackend {
+        fn install(&self, job: &ScheduleJob) -> OsScheduleResult<()> {
+            let mut jobs = self.jobs.borrow_mut();
+            jobs.retain(|j| j.id != job.id);
+            jobs.push(job.clone());
+            Ok(())
+        }
+
+        fn remove(&self, id: &str) -> OsScheduleResult<()> {
+            self.jobs.borrow_mut().retain(|j| j.id != id);
+            self.extra.borrow_mut().retain(|j| j != id);
+            Ok(())
+        }
+
+        fn registered(&self) -> OsScheduleResult<Vec<String>> {
+            let mut ids: Vec<String> = self.jobs.borrow().iter().map(|j| j.id.clone()).collect();
+            ids.extend(self.extra.borrow().iter().cloned());
+            Ok(ids)
+        }
+    }
+
+    fn job(spec: &str) -> ScheduleJob {
+        ScheduleJob {
+            id: job_id("Survival/Muldraugh 100%"),
+            save_name: "Survival/Muldraugh 100%".to_string(),
+            spec: ScheduleSpec::parse(spec).unwrap(),
+            program: PathBuf::from("/opt/PZ Backup/pz-backup-tool"),
+            config_dir: PathBuf::from("/home/user/.config/pz-backup-tool"),
+        }
+    }
+
+    #[test]
+    fn test_parse_spec() {
+        assert_eq!(
+            ScheduleSpec::parse("30 3 * * *").unwrap(),
+            ScheduleSpec { minute: 30, hour: Some(3), weekdays: vec![] }
+        );
+        assert_eq!(
+            ScheduleSpec::parse(" 0 22 * * fri,1,7 ").unwrap(),
+            ScheduleSpec { minute: 0, hour: Some(22), weekdays: vec![0, 1, 5] }
+        );
+        assert_eq!(
+            ScheduleSpec::parse("15 * * * *").unwrap(),
+            ScheduleSpec { minute: 15, hour: None, weekdays: vec![] }
+        );
+
+        for spec in ["", "30 3 * *", "60 3 * * *", "0 24 * * *", "0 3 1 * *", "0 3 * 6 *", "0 * * * 1", "0 3 * * funday"] {
+            assert!(matches!(ScheduleSpec::parse(spec), Err(OsScheduleError::InvalidSpec(_))), "{:?}", spec);
+        }
+    }
+
+    #[test]
+    fn test_job_id() {
+        let id = job_id("Survival/Muldraugh 100%");
+        assert!(id.starts_with("pz-backup-tool-survival-muldraugh-100-"), "{}", id);
+        assert_eq!(id.len(), "pz-backup-tool-survival-muldraugh-100-".len() + 8);
+        assert_eq!(job_id("Survival\\Muldraugh 100%/"), id);
+        // Same slug, different save
+        assert_ne!(job_id("Survival/Muldraugh-100"), id);
+    }
+
+    #[test]
+    fn test_systemd_snapshot() {
+        let job = job("0 22 * * 1,5");
+        assert_eq!(
+            render_systemd_service(&job),
+            "[Unit]\n\
+             Description=Project Zomboid backup of Survival/Muldraugh 100%%\n\
+             \n\
+             [Service]\n\
+             Type=oneshot\n\
+             ExecStart=\"/opt/PZ Backup/pz-backup-tool\" \"--config-dir\" \"/home/user/.config/pz-backup-tool\" \
+             \"backup\" \"--save\" \"Survival/Muldraugh 100%%\" \"--silent\"\n"
+        );
+        assert_eq!(
+            render_systemd_timer(&job),
+            "[Unit]\n\
+             Description=Scheduled Project Zomboid backup of Survival/Muldraugh 100%%\n\
+             \n\
+             [Timer]\n\
+             OnCalendar=Mon,Fri *-*-* 22:00:00\n\
+             Persistent=true\n\
+             \n\
+             [Install]\n\
+             WantedBy=timers.target\n"
+        );
+        assert_eq!(ScheduleSpec::parse("5 * * * *").unwrap().systemd_on_calendar(), "*-*-* *:05:00");
+    }
+
+    #[test]
+    fn test_launchd_snapshot() {
+        let daily = job("30 3 * * *");
+        let label = daily.launchd_label();
+        assert_eq!(
+            render_launchd_plist(&daily),
+            format!(
+                "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+                 <!DOCTYPE plist PUBLIC \"-//Apple//DTD PLIST 1.0//EN\" \"http://www.apple.com/DTDs/PropertyList-1.0.dtd\">\n\
+                 <plist version=\"1.0\">\n\
+                 <dict>\n\
+                 \x20   <key>Label</key>\n\
+                 \x20   <string>{label}</string>\n\
+                 \x20   <key>ProgramArguments</key>\n\
+                 \x20   <array>\n\
+                 \x20       <string>/opt/PZ Backup/pz-backup-tool</string>\n\
+                 \x20       <string>--config-dir</string>\n\
+                 \x20       <string>/home/user/.config/pz-backup-tool</string>\n\
+                 \x20       <string>backup</string>\n\
+                 \x20       <string>--save</string>\n\
+                 \x20       <string>Survival/Muldraugh 100%</string>\n\
+                 \x20       <string>--silent</string>\n\
+                 \x20   </array>\n\
+                 \x20   <key>StartCalendarInterval</key>\n\
+                 \x20   <dict>\n\
+                 \x20       <key>Hour</key>\n\
+                 \x20       <integer>3</integer>\n\
+                 \x20       <key>Minute</key>\n\
+                 \x20       <integer>30</integer>\n\
+                 \x20   </dict>\n\
+                 </dict>\n\
+                 </plist>\n"
+            )
+        );
+
+        let weekly = render_launchd_plist(&job("0 22 * * sun,sat"));
+        assert!(weekly.contains(
+            "    <key>StartCalendarInterval</key>\n    <array>\n        <dict>\n            <key>Weekday</key>\n            <integer>0</integer>\n"
+        ));
+        assert!(weekly.contains("<key>Weekday</key>\n            <integer>6</integer>\n            <key>Hour</key>\n            <integer>22</integer>\n"));
+    }
+
+    #[test]
+    fn test_schtasks_snapshot() {
+        let mut job = job("0 22 * * 1,5");
+        job.program = PathBuf::from(r"C:\Program Files\PZ Backup\pz-backup-tool.exe");
+        job.config_dir = PathBuf::from(r"C:\Users\user\AppData\Roaming\pz-backup-tool");
+        let command = "\"C:\\Program Files\\PZ Backup\\pz-backup-tool.exe\" --config-dir \
+                       C:\\Users\\user\\AppData\\Roaming\\pz-backup-tool backup --save \"Survival/Muldraugh 100%\" --silent";
+        assert_eq!(
+            render_schtasks_create_args(&job),
+            ["/Create", "/F", "/TN", &job.id, "/TR", command, "/SC", "WEEKLY", "/D", "MON,FRI", "/ST", "22:00"]
+        );
+
+        job.spec = ScheduleSpec::parse("30 3 * * *").unwrap();
+        assert_eq!(render_schtasks_create_args(&job)[6..], ["/SC", "DAILY", "/ST", "03:30"]);
+        job.spec = ScheduleSpec::parse("15 * * * *").unwrap();
+        assert_eq!(render_schtasks_create_args(&job)[6..], ["/SC", "HOURLY", "/ST", "00:15"]);
+
+        // A trailing backslash must not escape the closing quote
+        job.spec = ScheduleSpec::parse("30 3 * * *").unwrap();
+        job.config_dir = PathBuf::from(r"C:\Users\PZ User\config\");
+        let command = r#""C:\Program Files\PZ Backup\pz-backup-tool.exe" --config-dir "C:\Users\PZ User\config\\" backup --save "Survival/Muldraugh 100%" --silent"#;
+        assert_eq!(render_schtasks_create_args(&job)[5], command);
+    }
+
+    #[test]
+    fn test_windows_quote() {
+        assert_eq!(windows_quote(r"C:\a\b"), r"C:\a\b");
+        assert_eq!(windows_quote(""), r#""""#);
+        assert_eq!(windows_quote(r"C:\a b\"), r#""C:\a b\\""#);
+        assert_eq!(windows_quote(r#"a\"b c"#), r#""a\\\"b c""#);
+        assert_eq!(windows_quote(r"a\\b c"), r#""a\\b c""#);
+    }
+
+    #[test]
+    fn test_parse_schtasks_query() {
+        let output = "\"\\Microsoft\\Windows\\Defrag\\ScheduledDefrag\",\"N/A\",\"Ready\"\r\n\
+                      \"\\pz-backup-tool-survival-a-0123abcd\",\"12/29/2024 3:30:00 AM\",\"Ready\"\r\n\
+                      \"\\pz-backup-tool-survival-a-0123abcd\",\"N/A\",\"Ready\"\r\n";
+        assert_eq!(parse_schtasks_query(output), vec!["pz-backup-tool-survival-a-0123abcd".to_string()]);
+    }
+
+    #[test]
+    fn test_systemd_backend_lists_unit_files() {
+        let env = TestEnv::new();
+        let unit_dir = env.root().join("systemd");
+        std::fs::create_dir_all(&unit_dir).unwrap();
+        for name in ["pz-backup-tool-a-1.timer", "pz-backup-tool-a-1.service", "other.timer"] {
+            std::fs::write(unit_dir.join(name), "").unwrap();
+        }
+        let backend = SystemdBackend { unit_dir };
+        assert_eq!(backend.registered().unwrap(), vec!["pz-backup-tool-a-1".to_string()]);
+
+        let missing = SystemdBackend { unit_dir: env.root().join("missing") };
+        assert!(missing.registered().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_install_list_remove() {
+        let _env = TestEnv::with_paths();
+        let backend = FakeBackend::default();
+        let program = Path::new("/opt/pz-backup-tool");
+        let config_dir = Path::new("/config");
+
+        let status = install_os_schedule_with(&backend, "Survival\\A", "30 3 * * *", program, config_dir).unwrap();
+        assert_eq!(status.save_name.as_deref(), Some("Survival/A"));
+        assert_eq!(backend.jobs.borrow()[0].command_line()[1..3], ["--config-dir", "/config"]);
+
+        // Replacing keeps one entry
+        install_os_schedule_with(&backend, "Survival/A", "0 4 * * *", program, config_dir).unwrap();
+        let config = config_module::load_config().unwrap();
+        assert_eq!(
+            config.os_schedules,
+            vec![OsScheduleConfig { save_name: "Survival/A".into(), spec: "0 4 * * *".into() }]
+        );
+        assert_eq!(backend.jobs.borrow().len(), 1);
+
+        // Names that could break out of a unit file or command line register nothing
+        for save in ["", "/etc", "Survival/../A", "Survival/A\nExecStartPre=/bin/sh", "Survival/A\r"] {
+            let result = install_os_schedule_with(&backend, save, "30 3 * * *", program, config_dir);
+            assert!(matches!(result, Err(OsScheduleError::FileOp(FileOpsError::UnsafeSavePath(_)))), "{:?}", save);
+        }
+        assert_eq!(backend.jobs.borrow().len(), 1);
+
+        // Invalid specs register nothing
+        assert!(install_os_schedule_with(&backend, "Survival/B", "tomorrow", program, config_dir).is_err());
+        assert_eq!(config_module::load_config().unwrap().os_schedules.len(), 1);
+
+        let listed = list_os_schedules_with(&backend).unwrap();
+        assert_eq!(listed.len(), 1);
+        assert!(!listed[0].has_drift());
+
+        assert!(remove_os_schedule_with(&backend, "Survival/A").unwrap());
+        assert!(!remove_os_schedule_with(&backend, "Survival/A").unwrap());
+        assert!(backend.jobs.borrow().is_empty());
+        assert!(list_os_schedules_with(&backend).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_list_flags_drift() {
+        let _env = TestEnv::with_paths();
+        let backend = FakeBackend::default();
+        install_os_schedule_with(&backend, "Survival/A", "30 3 * * *", Path::new("/pz"), Path::new("/c")).unwrap();
+        install_os_schedule_with(&backend, "Survival/B", "30 3 * * *", Path::new("/pz"), Path::new("/c")).unwrap();
+
+        // Removed from the scheduler by hand, and a job the config doesn't know
+        backend.jobs.borrow_mut().retain(|j| j.save_name != "Survival/B");
+        backend.extra.borrow_mut().push("pz-backup-tool-old-0000ffff".to_string());
+
+        let listed = list_os_schedules_with(&backend).unwrap();
+        let states: Vec<(Option<&str>, ScheduleState)> =
+            listed.iter().map(|s| (s.save_name.as_deref(), s.state)).collect();
+        assert_eq!(
+            states,
+            vec![
+                (Some("Survival/A"), ScheduleState::InSync),
+                (Some("Survival/B"), ScheduleState::NotRegistered),
+                (None, ScheduleState::NotInConfig),
+            ]
+        );
+        assert_eq!(listed[2].id, "pz-backup-tool-old-0000ffff");
+        assert!(listed[1].has_drift() && listed[2].has_drift());
+    }
+}
@@ -0,0 +1,584 @@
+//! RCON flush of dedicated servers before a backup.
+//!
+//! Backing up a running server captures a torn world unless it writes its state
+//! first. For saves with an `RconConfig`, the backup connects over the Source RCON
+//! protocol, issues `save`, and waits until the save directory stops changing
+//! before archiving. The password comes from the secrets store (`secrets`), under
+//! `rcon_password_key`.
+//!
+//! A failed flush doesn't abort the backup: it is taken anyway and its
+//! `ServerFlush` records why it was taken without flush.
+
+use crate::config::Config;
+use crate::keys::SaveKey;
+use crate::secrets;
+use serde::{Deserialize, Serialize, Serializer};
+use std::io::{self, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::path::Path;
+use std::time::{Duration, Instant, SystemTime};
+
+/// Packet type of an authentication request.
+const SERVERDATA_AUTH: i32 = 3;
+/// Packet type of an authentication response.
+const SERVERDATA_AUTH_RESPONSE: i32 = 2;
+/// Packet type of a command.
+const SERVERDATA_EXECCOMMAND: i32 = 2;
+/// Packet type of a command response.
+const SERVERDATA_RESPONSE_VALUE: i32 = 0;
+
+/// Largest packet accepted from a server (the protocol limits bodies to 4096 bytes).
+const MAX_PACKET_SIZE: i32 = 4096 + 10;
+
+/// Server command that writes the world to disk.
+const SAVE_COMMAND: &str = "save";
+
+/// Default RCON port of Project Zomboid servers.
+fn default_rcon_port() -> u16 {
+    27015
+}
+
+/// Default time a flush may take, in seconds.
+fn default_flush_timeout_secs() -> u64 {
+    30
+}
+
+/// RCON settings of a dedicated server save.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RconConfig {
+    /// Relative path of the server save (e.g., "Multiplayer/servertest")
+    pub save_name: String,
+    /// Host of the server
+    pub host: String,
+    /// RCON port (`RCONPort` in the server's ini)
+    #[serde(default = "default_rcon_port")]
+    pub port: u16,
+    /// Time the server gets to finish writing its save, in seconds
+    #[serde(default = "default_flush_timeout_secs")]
+    pub flush_timeout_secs: u64,
+}
+
+/// Why a backup was taken without flush.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum FlushFailure {
+    /// The server rejected the password
+    AuthFailed,
+    /// The server didn't answer, or kept writing, within the timeout
+    Timeout,
+    /// The server couldn't be reached
+    Connection,
+    /// No password is stored for the save
+    MissingPassword,
+    /// The server sent something that isn't an RCON packet
+    Protocol,
+}
+
+/// Outcome of the flush before a server backup.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ServerFlush {
+    /// Whether the server wrote its save before archiving started
+    pub flushed: bool,
+    /// Why the backup was taken without flush
+    #[serde(default)]
+    pub failure: Option<FlushFailure>,
+    /// Error message of the failed flush
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+impl ServerFlush {
+    fn succeeded() -> Self {
+        ServerFlush {
+            flushed: true,
+            failure: None,
+            message: None,
+        }
+    }
+
+    fn failed(failure: FlushFailure, message: String) -> Self {
+        ServerFlush {
+            flushed: false,
+            failure: Some(failure),
+            message: Some(message),
+        }
+    }
+}
+
+/// Error type for RCON operations.
+#[derive(Debug)]
+pub enum RconError {
+    /// Connection or I/O error
+    Io(io::Error),
+    /// The server didn't answer in time
+    Timeout,
+    /// The server rejected the password
+    AuthFailed,
+    /// The server sent a malformed packet
+    InvalidResponse(String),
+}
+
+impl From<io::Error> for RconError {
+    fn from(err: io::Error) -> Self {
+        match err.kind() {
+            io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock => RconError::Timeout,
+            _ => RconError::Io(err),
+        }
+    }
+}
+
+impl std::fmt::Display for RconError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RconError::Io(err) => write!(f, "RCON connection error: {}", err),
+            RconError::Timeout => write!(f, "RCON server didn't respond in time"),
+            RconError::AuthFailed => write!(f, "RCON authentication failed"),
+            RconError::InvalidResponse(msg) => write!(f, "Invalid RCON response: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for RconError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            RconError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl Serialize for RconError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl RconError {
+    /// Returns how a flush that failed with this error is recorded.
+    pub fn flush_failure(&self) -> FlushFailure {
+        match self {
+            RconError::Io(_) => FlushFailure::Connection,
+            RconError::Timeout => FlushFailure::Timeout,
+            RconError::AuthFailed => FlushFailure::AuthFailed,
+            RconError::InvalidResponse(_) => FlushFailure::Protocol,
+        }
+    }
+}
+
+/// Result type for RCON operations.
+pub type RconResult<T> = Result<T, RconError>;
+
+/// A packet of the Source RCON protocol.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Packet {
+    id: i32,
+    kind: i32,
+    body: String,
+}
+
+/// Writes a packet: size, id and type as little-endian i32, then the
+/// null-terminated body and an empty null-terminated string.
+fn write_packet(writer: &mut impl Write, packet: &Packet) -> io::Result<()> {
+    let body = packet.body.as_bytes();
+    let size = (body.len() + 10) as i32;
+    let mut buf = Vec::with_capacity(body.len() + 14);
+    buf.extend_from_slice(&size.to_le_bytes());
+    buf.extend_from_slice(&packet.id.to_le_bytes());
+    buf.extend_from_slice(&packet.kind.to_le_bytes());
+    buf.extend_from_slice(body);
+    buf.extend_from_slice(&[0, 0]);
+    writer.write_all(&buf)?;
+    writer.flush()
+}
+
+/// Reads one packet.
+fn read_packet(reader: &mut impl Read) -> RconResult<Packet> {
+    let mut size = [0u8; 4];
+    reader.read_exact(&mut size)?;
+    let size = i32::from_le_bytes(size);
+    if !(10..=MAX_PACKET_SIZE).contains(&size) {
+        return Err(RconError::InvalidResponse(format!("packet size {}", size)));
+    }
+
+    let mut buf = vec![0u8; size as usize];
+    reader.read_exact(&mut buf)?;
+    let id = i32::from_le_bytes([buf[0], buf[1], buf[2], buf[3]]);
+    let kind = i32::from_le_bytes([buf[4], buf[5], buf[6], buf[7]]);
+    let body = &buf[8..];
+    let end = body.iter().position(|&b| b == 0).unwrap_or(body.len());
+    Ok(Packet {
+        id,
+        kind,
+        body: String::from_utf8_lossy(&body[..end]).into_owned(),
+    })
+}
+
+/// A connection to an RCON server.
+pub struct RconClient {
+    stream: TcpStream,
+    next_id: i32,
+}
+
+impl RconClient {
+    /// Connects to a server.
+    ///
+    /// # Arguments
+    /// * `host` - Host name or address
+    /// * `port` - RCON port
+    /// * `timeout` - Limit for connecting and for every read and write
+    pub fn connect(host: &str, port: u16, timeout: Duration) -> RconResult<Self> {
+        let addr = (host, port)
+            .to_socket_addrs()?
+            .next()
+            .ok_or_else(|| RconError::Io(io::Error::new(io::ErrorKind::NotFound, format!("{} not found", host))))?;
+        let stream = TcpStream::connect_timeout(&addr, timeout)?;
+        stream.set_read_timeout(Some(timeout))?;
+        stream.set_write_timeout(Some(timeout))?;
+        Ok(RconClient { stream, next_id: 1 })
+    }
+
+    fn send(&mut self, kind: i32, body: &str) -> RconResult<i32> {
+        let id = self.next_id;
+        self.next_id += 1;
+        write_packet(&mut self.stream, &Packet { id, kind, body: body.to_string() })?;
+        Ok(id)
+    }
+
+    /// Authenticates with the server's RCON password.
+    ///
+    /// # Returns
+    /// `RconResult<()>` - `RconError::AuthFailed` if the password was rejected
+    pub fn authenticate(&mut self, password: &str) -> RconResult<()> {
+        let id = self.send(SERVERDATA_AUTH, password)?;
+        loop {
+            // Servers may send an empty response value before the auth response
+            let packet = read_packet(&mut self.stream)?;
+            if packet.kind != SERVERDATA_AUTH_RESPONSE {
+                continue;
+            }
+            return match packet.id {
+                -1 => Err(RconError::AuthFailed),
+                reply if reply == id => Ok(()),
+                reply => Err(RconError::InvalidResponse(format!("auth response for request {}", reply))),
+            };
+        }
+    }
+
+    /// Runs a command and returns the server's response.
+    pub fn exec(&mut self, command: &str) -> RconResult<String> {
+        let id = self.send(SERVERDATA_EXECCOMMAND, command)?;
+        loop {
+            let packet = read_packet(&mut self.stream)?;
+            if packet.kind == SERVERDATA_RESPONSE_VALUE && packet.id == id {
+                return Ok(packet.body);
+            }
+        }
+    }
+}
+
+/// Timing of a flush.
+#[derive(Debug, Clone, Copy)]
+pub struct FlushSettings {
+    /// Limit for the whole flush, including waiting for the save to settle
+    pub timeout: Duration,
+    /// How long the save directory must stay unchanged to count as written
+    pub quiet_period: Duration,
+    /// Interval between checks of the save directory
+    pub poll_interval: Duration,
+}
+
+impl FlushSettings {
+    /// Returns the settings for a configured server.
+    pub fn for_server(rcon: &RconConfig) -> Self {
+        FlushSettings {
+            timeout: Duration::from_secs(rcon.flush_timeout_secs.max(1)),
+            quiet_period: Duration::from_secs(2),
+            poll_interval: Duration::from_millis(250),
+        }
+    }
+}
+
+/// Returns the secrets store key of a save's RCON password.
+pub fn rcon_password_key(save_name: &str) -> String {
+    format!("rcon:{}", SaveKey::new(save_name))
+}
+
+/// Returns the RCON settings of a save, if it is a configured server.
+pub fn find_rcon_config<'a>(config: &'a Config, save_name: &str) -> Option<&'a RconConfig> {
+    let key = SaveKey::new(save_name);
+    config.rcon.iter().find(|rcon| SaveKey::new(&rcon.save_name) == key)
+}
+
+/// Makes a server write its save and waits until it is on disk.
+///
+/// # Arguments
+/// * `rcon` - Server settings
+/// * `password` - RCON password
+/// * `save_dir` - Save directory the server writes to
+/// * `settings` - Timing of the flush
+///
+/// # Behavior
+/// Sends `save`, then polls the newest modification time in `save_dir` until it
+/// hasn't changed for `quiet_period`. Fails with `RconError::Timeout` if the server
+/// doesn't answer, or the directory doesn't settle, within `timeout`.
+pub fn flush_server_save(
+    rcon: &RconConfig,
+    password: &str,
+    save_dir: &Path,
+    settings: &FlushSettings,
+) -> RconResult<()> {
+    let started = Instant::now();
+    let mut client = RconClient::connect(&rcon.host, rcon.port, settings.timeout)?;
+    client.authenticate(password)?;
+    client.exec(SAVE_COMMAND)?;
+    wait_until_quiet(save_dir, started, settings)
+}
+
+/// Waits until no file in `save_dir` has been modified for `quiet_period`.
+fn wait_until_quiet(save_dir: &Path, started: Instant, settings: &FlushSettings) -> RconResult<()> {
+    let mut newest = newest_modification(save_dir);
+    let mut last_change = Instant::now();
+    loop {
+        if last_change.elapsed() >= settings.quiet_period {
+            return Ok(());
+        }
+        if started.elapsed() >= settings.timeout {
+            return Err(RconError::Timeout);
+        }
+        std::thread::sleep(settings.poll_interval);
+        let current = newest_modification(save_dir);
+        if current != newest {
+            newest = current;
+            last_change = Instant::now();
+        }
+    }
+}
+
+/// Returns the newest modification time of the files under `dir`.
+fn newest_modification(dir: &Path) -> Option<SystemTime> {
+    let mut newest = None;
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.filter_map(Result::ok) {
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if metadata.is_dir() {
+                pending.push(entry.path());
+            } else if let Ok(modified) = metadata.modified() {
+                newest = newest.max(Some(modified));
+            }
+        }
+    }
+    newest
+}
+
+/// Flushes a save's server before it is backed up.
+///
+/// # Returns
+/// `Option<ServerFlush>` - None if the save has no RCON settings; otherwise whether
+/// the flush succeeded. Failures are recorded, never returned as errors.
+pub fn flush_before_backup(config: &Config, save_name: &str, save_dir: &Path) -> Option<ServerFlush> {
+    let rcon = find_rcon_config(config, save_name)?;
+    Some(flush_with_settings(rcon, save_name, save_dir, &FlushSettings::for_server(rcon)))
+}
+
+fn flush_with_settings(rcon: &RconConfig, save_name: &str, save_dir: &Path, settings: &FlushSettings) -> ServerFlush {
+    let password = match secrets::get_secret(&rcon_password_key(save_name)) {
+        Ok(Some(password)) => password,
+        Ok(None) => {
+            return ServerFlush::failed(
+                FlushFailure::MissingPassword,
+                format!("No RCON password stored for {}", save_name),
+            )
+        }
+        Err(err) => return ServerFlush::failed(FlushFailure::MissingPassword, err.to_string()),
+    };
+
+    match flush_server_save(rcon, &password, save_dir, settings) {
+        Ok(()) => ServerFlush::succeeded(),
+        Err(err) => {
+            eprintln!("[WARN] RCON flush of {} failed, backing up without flush: {}", save_name, err);
+            ServerFlush::failed(err.flush_failure(), err.to_string())
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::test_support::TestEnv;
+    use std::net::TcpListener;
+    use std::sync::mpsc;
+    use std::thread::JoinHandle;
+
+    /// How a mock server treats a connection.
+    #[derive(Clone, Copy)]
+    pub(crate) enum MockBehavior {
+        /// Checks the password, answers commands and writes to the save on `save`
+        Normal,
+        /// Accepts the connection and never answers
+        Silent,
+    }
+
+    /// A mock RCON server handling one connection.
+    pub(crate) struct MockServer {
+        pub(crate) port: u16,
+        commands: mpsc::Receiver<String>,
+        handle: JoinHandle<()>,
+    }
+
+    impl MockServer {
+        pub(crate) fn start(password: &str, behavior: MockBehavior, save_dir: Option<&Path>) -> Self {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            let port = listener.local_addr().unwrap().port();
+            let password = password.to_string();
+            let save_dir = save_dir.map(Path::to_path_buf);
+            let (sender, commands) = mpsc::channel();
+
+            let handle = std::thread::spawn(move || {
+                let Ok((mut stream, _)) = listener.accept() else {
+                    return;
+                };
+                if let MockBehavior::Silent = behavior {
+                    std::thread::sleep(Duration::from_millis(500));
+                    return;
+                }
+                // Auth: empty response value, then the auth response
+                let Ok(auth) = read_packet(&mut stream) else {
+                    return;
+                };
+                let reply_id = if auth.kind == SERVERDATA_AUTH && auth.body == password { auth.id } else { -1 };
+                let empty = Packet { id: auth.id, kind: SERVERDATA_RESPONSE_VALUE, body: String::new() };
+                let _ = write_packet(&mut stream, &empty);
+                let auth_reply = Packet { id: reply_id, kind: SERVERDATA_AUTH_RESPONSE, body: String::new() };
+                let _ = write_packet(&mut stream, &auth_reply);
+                if reply_id == -1 {
+                    return;
+                }
+
+                while let Ok(command) = read_packet(&mut stream) {
+                    let _ = sender.send(command.body.clone());
+                    if command.body == SAVE_COMMAND {
+                        if let Some(dir) = &save_dir {
+                            std::fs::write(dir.join("map_t.bin"), b"flushed").unwrap();
+                        }
+                    }
+                    let reply = Packet { id: command.id, kind: SERVERDATA_RESPONSE_VALUE, body: "World saved".into() };
+                    let _ = write_packet(&mut stream, &reply);
+                }
+            });
+
+            MockServer { port, commands, handle }
+        }
+
+        pub(crate) fn config(&self, save_name: &str) -> RconConfig {
+            RconConfig {
+                save_name: save_name.to_string(),
+                host: "127.0.0.1".to_string(),
+                port: self.port,
+                flush_timeout_secs: 5,
+            }
+        }
+
+        /// Returns the commands the server received.
+        pub(crate) fn finish(self) -> Vec<String> {
+            self.handle.join().unwrap();
+            self.commands.try_iter().collect()
+        }
+    }
+
+    fn fast_settings(timeout: Duration) -> FlushSettings {
+        FlushSettings {
+            timeout,
+            quiet_period: Duration::from_millis(100),
+            poll_interval: Duration::from_millis(20),
+        }
+    }
+
+    #[test]
+    fn test_packet_roundtrip() {
+        let packet = Packet { id: 7, kind: SERVERDATA_EXECCOMMAND, body: "save".into() };
+        let mut buf = Vec::new();
+        write_packet(&mut buf, &packet).unwrap();
+        assert_eq!(&buf[..4], &14i32.to_le_bytes());
+        assert_eq!(read_packet(&mut buf.as_slice()).unwrap(), packet);
+
+        let mut oversized = (MAX_PACKET_SIZE + 1).to_le_bytes().to_vec();
+        oversized.extend_from_slice(&[0; 16]);
+        assert!(matches!(read_packet(&mut oversized.as_slice()), Err(RconError::InvalidResponse(_))));
+    }
+
+    #[test]
+    fn test_authenticate() {
+        let server = MockServer::start("hunter2", MockBehavior::Normal, None);
+        let mut client = RconClient::connect("127.0.0.1", server.port, Duration::from_secs(5)).unwrap();
+        client.authenticate("hunter2").unwrap();
+        assert_eq!(client.exec("players").unwrap(), "World saved");
+        drop(client);
+        assert_eq!(server.finish(), vec!["players".to_string()]);
+
+        let server = MockServer::start("hunter2", MockBehavior::Normal, None);
+        let mut client = RconClient::connect("127.0.0.1", server.port, Duration::from_secs(5)).unwrap();
+        assert!(matches!(client.authenticate("wrong"), Err(RconError::AuthFailed)));
+        drop(client);
+        assert!(server.finish().is_empty());
+    }
+
+    #[test]
+    fn test_flush_sends_save_and_waits() {
+        let env = TestEnv::new();
+        let save_dir = env.root().join("servertest");
+        std::fs::create_dir_all(&save_dir).unwrap();
+
+        let server = MockServer::start("hunter2", MockBehavior::Normal, Some(&save_dir));
+        let rcon = server.config("Multiplayer/servertest");
+        flush_server_save(&rcon, "hunter2", &save_dir, &fast_settings(Duration::from_secs(5))).unwrap();
+
+        assert!(save_dir.join("map_t.bin").exists());
+        assert_eq!(server.finish(), vec!["save".to_string()]);
+    }
+
+    #[test]
+    fn test_flush_timeout() {
+        let env = TestEnv::new();
+        let server = MockServer::start("hunter2", MockBehavior::Silent, None);
+        let rcon = server.config("Multiplayer/servertest");
+
+        let result = flush_server_save(&rcon, "hunter2", env.root(), &fast_settings(Duration::from_millis(200)));
+        let err = result.unwrap_err();
+        assert!(matches!(err, RconError::Timeout), "{:?}", err);
+        assert_eq!(err.flush_failure(), FlushFailure::Timeout);
+        server.finish();
+    }
+
+    #[test]
+    fn test_flush_failures_are_recorded() {
+        let env = TestEnv::new();
+        let server = MockServer::start("hunter2", MockBehavior::Normal, None);
+        let rcon = server.config("Multiplayer/servertest");
+        let settings = fast_settings(Duration::from_secs(5));
+
+        // No password stored: the server isn't contacted
+        let flush = flush_with_settings(&rcon, "Multiplayer/servertest", env.root(), &settings);
+        assert_eq!(flush.failure, Some(FlushFailure::MissingPassword));
+        assert!(!flush.flushed);
+
+        secrets::set_secret(&rcon_password_key("Multiplayer/servertest"), "wrong").unwrap();
+        let flush = flush_with_settings(&rcon, "Multiplayer/servertest", env.root(), &settings);
+        assert_eq!(flush.failure, Some(FlushFailure::AuthFailed));
+        server.finish();
+
+        let server = MockServer::start("hunter2", MockBehavior::Normal, None);
+        let rcon = server.config("Multiplayer/servertest");
+        secrets::set_secret(&rcon_password_key("Multiplayer\\servertest"), "hunter2").unwrap();
+        let flush = flush_with_settings(&rcon, "Multiplayer/servertest", env.root(), &settings);
+        assert_eq!(flush, ServerFlush::succeeded());
+        server.finish();
+    }
+}
@@ -10,6 +10,8 @@ use crate::backup::{get_save_backup_dir, BackupError};
 use crate::config as config_module;
 use crate::config::ConfigError;
 use crate::file_ops::{create_tar_gz, delete_dir_recursive, extract_tar_gz, FileOpsError};
+use crate::journal::{self, JournalEntry, JournalError, JournalResult, RecoveryHandler};
+use crate::tasks::TaskHandle;
 use serde::{Deserialize, Serialize, Serializer};
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -76,6 +78,8 @@ pub enum RestoreError {
     UndoSnapshotFailed(String),
     /// Game is currently running
     GameRunning(String),
+    /// Journaled swap of the save directory failed
+    Journal(JournalError),
 }
 
 impl From<FileOpsError> for RestoreError {
@@ -96,6 +100,12 @@ impl From<ConfigError> for RestoreError {
     }
 }
 
+impl From<JournalError> for RestoreError {
+    fn from(err: JournalError) -> Self {
+        RestoreError::Journal(err)
+    }
+}
+
 impl std::fmt::Display for RestoreError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -113,6 +123,7 @@ impl std::fmt::Display for RestoreError {
             RestoreError::GameRunning(process_name) => {
                 write!(f, "Project Zomboid is currently running ({}). Please close the game before restoring.", process_name)
             }
+            RestoreError::Journal(err) => write!(f, "{}", err),
         }
     }
 }
@@ -123,6 +134,7 @@ impl std::error::Error for RestoreError {
             RestoreError::FileOp(err) => Some(err),
             RestoreError::Backup(err) => Some(err),
             RestoreError::Config(err) => Some(err),
+            RestoreError::Journal(err) => Some(err),
             _ => None,
         }
     }
@@ -330,7 +342,7 @@ fn check_game_running_linux() -> GameProcessCheckResult {
 
     // Try pgrep for ProjectZomboid
     if let Ok(output) = Command::new("pgrep")
-        .args(&["-x", "ProjectZomboid"])
+        .args(["-x", "ProjectZomboid"])
         .output()
     {
         if !output.stdout.is_empty() {
@@ -343,7 +355,7 @@ fn check_game_running_linux() -> GameProcessCheckResult {
 
     // Try pgrep for lowercase version
     if let Ok(output) = Command::new("pgrep")
-        .args(&["-x", "projectzomboid"])
+        .args(["-x", "projectzomboid"])
         .output()
     {
         if !output.stdout.is_empty() {
@@ -356,7 +368,7 @@ fn check_game_running_linux() -> GameProcessCheckResult {
 
     // Fallback to ps for Java processes running Zomboid
     if let Ok(output) = Command::new("ps")
-        .args(&["-ax", "-o", "comm="])
+        .args(["-ax", "-o", "comm="])
         .output()
     {
         let stdout = String::from_utf8_lossy(&output.stdout);
@@ -455,6 +467,136 @@ fn create_undo_snapshot(
     }))
 }
 
+/// Journal operation name of `swap_in_archive`.
+const RESTORE_SWAP_OPERATION: &str = "restore_swap";
+
+/// Steps of `swap_in_archive`, in order.
+const RESTORE_SWAP_STEPS: [&str; 4] = ["extract", "move_aside", "swap_in", "cleanup"];
+
+/// Index of the first step after which an interrupted swap is finished rather than undone.
+const RESTORE_SWAP_ROLL_FORWARD_POINT: usize = 3;
+
+/// Recovery handler for journaled restores.
+///
+/// Paths: `archive` (backup to restore), `target` (save directory), `staging`
+/// (extraction directory) and `aside` (previous save while swapping). Staging and
+/// aside are hidden siblings of the target, so the swap is two renames on one filesystem.
+pub struct RestoreSwap;
+
+impl RecoveryHandler for RestoreSwap {
+    fn operation(&self) -> &'static str {
+        RESTORE_SWAP_OPERATION
+    }
+
+    // Once the restored save is in place, only the cleanup is left
+    fn can_roll_forward(&self, entry: &JournalEntry) -> bool {
+        entry.completed_steps >= RESTORE_SWAP_ROLL_FORWARD_POINT
+    }
+
+    fn forward(&self, entry: &JournalEntry, step: usize) -> JournalResult<()> {
+        let target = entry.path("target")?;
+        let staging = entry.path("staging")?;
+        let aside = entry.path("aside")?;
+
+        match step {
+            0 => {
+                // A crash may have left a partial extraction
+                remove_dir_if_exists(staging)?;
+                extract_tar_gz(entry.path("archive")?, staging)?;
+                // The mod manifest describes the backup; it isn't part of the save
+                let manifest = staging.join(crate::mods::MODS_MANIFEST_FILE_NAME);
+                if manifest.is_file() {
+                    fs::remove_file(manifest)?;
+                }
+            }
+            1 => {
+                if target.exists() && !aside.exists() {
+                    fs::rename(target, aside)?;
+                }
+            }
+            2 => {
+                if staging.exists() && !target.exists() {
+                    fs::rename(staging, target)?;
+                }
+            }
+            _ => remove_dir_if_exists(aside)?,
+        }
+        Ok(())
+    }
+
+    fn backward(&self, entry: &JournalEntry, step: usize) -> JournalResult<()> {
+        let target = entry.path("target")?;
+        let staging = entry.path("staging")?;
+        let aside = entry.path("aside")?;
+
+        match step {
+            0 => remove_dir_if_exists(staging)?,
+            1 => {
+                if aside.exists() && !target.exists() {
+                    fs::rename(aside, target)?;
+                }
+            }
+            2 => {
+                if !staging.exists() && target.exists() {
+                    fs::rename(target, staging)?;
+                }
+            }
+            // The previous save is gone once cleanup started; nothing to undo
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+fn remove_dir_if_exists(path: &Path) -> JournalResult<()> {
+    if path.exists() {
+        delete_dir_recursive(path)?;
+    }
+    Ok(())
+}
+
+/// Builds the journal entry for replacing `save_dir` with the contents of `archive`.
+fn restore_swap_entry(save_name: &str, archive: &Path, save_dir: &Path) -> JournalEntry {
+    let entry = JournalEntry::new(RESTORE_SWAP_OPERATION, save_name, &RESTORE_SWAP_STEPS);
+    let dir_name = save_dir
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let sibling = |suffix: &str| save_dir.with_file_name(format!(".{}.{}-{}", dir_name, suffix, entry.id));
+
+    let staging = sibling("restoring");
+    let aside = sibling("previous");
+    entry
+        .with_path("archive", archive.to_path_buf())
+        .with_path("target", save_dir.to_path_buf())
+        .with_path("staging", staging)
+        .with_path("aside", aside)
+}
+
+/// Replaces a save directory with the contents of an archive, under the journal.
+///
+/// # Arguments
+/// * `save_name` - Relative path of the save, recorded as the journal subject
+/// * `archive` - Backup or undo snapshot to restore
+/// * `save_dir` - Save directory to replace (need not exist)
+///
+/// # Returns
+/// `RestoreResultT<()>` - Ok(()) once the restored save is in place
+///
+/// # Behavior
+/// 1. Extracts the archive to a hidden staging directory next to the save
+/// 2. Renames the current save aside
+/// 3. Renames the staging directory to the save directory
+/// 4. Deletes the previous save
+///
+/// A failure before step 4 is undone and leaves the current save untouched; a failure
+/// in step 4 is returned and the cleanup is retried on the next launch. A crash at any
+/// point is repaired by `journal::recover_interrupted` on the next launch.
+fn swap_in_archive(save_name: &str, archive: &Path, save_dir: &Path) -> RestoreResultT<()> {
+    journal::run_journaled(&RestoreSwap, restore_swap_entry(save_name, archive, save_dir))?;
+    Ok(())
+}
+
 /// Restores a backup to the save directory with undo snapshot creation (async version).
 ///
 /// # Arguments
@@ -481,8 +623,7 @@ pub async fn restore_backup_async(save_name: &str, backup_name: &str) -> Restore
     let backup_name = backup_name.to_string();
     tokio::task::spawn_blocking(move || restore_backup(&save_name, &backup_name))
         .await
-        .map_err(|e| RestoreError::FileOp(FileOpsError::Io(std::io::Error::new(
-            std::io::ErrorKind::Other,
+        .map_err(|e| RestoreError::FileOp(FileOpsError::Io(std::io::Error::other(
             format!("Task join error: {}", e),
         ))))?
 }
@@ -499,8 +640,7 @@ pub async fn restore_backup_async(save_name: &str, backup_name: &str) -> Restore
 /// # Behavior
 /// 1. Validates the backup file exists
 /// 2. Creates an "Undo snapshot" of the current save state (if it exists)
-/// 3. Clears the current save directory
-/// 4. Extracts the backup tar.gz file to the save directory
+/// 3. Extracts the backup next to the save directory and swaps it in (see `swap_in_archive`)
 ///
 /// # Safety
 /// - Creates undo snapshot before any destructive operations
@@ -523,7 +663,7 @@ pub fn restore_backup(save_name: &str, backup_name: &str) -> RestoreResultT<Rest
     let backup_base_path = config.get_backup_path()?;
 
     let save_dir = save_path.join(save_name);
-    let backup_save_dir = get_save_backup_dir(&backup_base_path, save_name);
+    let backup_save_dir = get_save_backup_dir(&backup_base_path, save_name)?;
     let backup_file = backup_save_dir.join(backup_name);
 
     // Validate backup file exists
@@ -543,13 +683,8 @@ pub fn restore_backup(save_name: &str, backup_name: &str) -> RestoreResultT<Rest
     let undo_snapshot_dir = get_undo_snapshot_dir(&backup_base_path, save_name);
     let undo_snapshot = create_undo_snapshot(&save_dir, &undo_snapshot_dir)?;
 
-    // Clear current save directory if it exists
-    if save_dir.exists() {
-        delete_dir_recursive(&save_dir)?;
-    }
-
-    // Extract the backup tar.gz to save directory
-    extract_tar_gz(&backup_file, &save_dir)?;
+    // Replace the save directory with the extracted backup
+    swap_in_archive(save_name, &backup_file, &save_dir)?;
 
     Ok(RestoreResult {
         save_path: save_dir.to_string_lossy().to_string(),
@@ -561,6 +696,17 @@ pub fn restore_backup(save_name: &str, backup_name: &str) -> RestoreResultT<Rest
     })
 }
 
+/// Reports the sizes of a finished restore to its task, for performance metrics.
+///
+/// # Arguments
+/// * `task` - Task of the restore
+/// * `restored` - Result of the restore
+pub fn report_restore_bytes(task: &TaskHandle, restored: &RestoreResult) {
+    let archive_bytes = crate::file_ops::get_file_size(Path::new(&restored.backup_path)).ok();
+    let save_bytes = crate::file_ops::get_dir_size(Path::new(&restored.save_path)).ok();
+    task.set_bytes(save_bytes, archive_bytes);
+}
+
 /// Lists all undo snapshots for a specific save.
 ///
 /// # Arguments
@@ -640,8 +786,7 @@ pub async fn restore_from_undo_snapshot_async(
     let snapshot_name = snapshot_name.to_string();
     tokio::task::spawn_blocking(move || restore_from_undo_snapshot(&save_name, &snapshot_name))
         .await
-        .map_err(|e| RestoreError::FileOp(FileOpsError::Io(std::io::Error::new(
-            std::io::ErrorKind::Other,
+        .map_err(|e| RestoreError::FileOp(FileOpsError::Io(std::io::Error::other(
             format!("Task join error: {}", e),
         ))))?
 }
@@ -658,8 +803,7 @@ pub async fn restore_from_undo_snapshot_async(
 /// # Behavior
 /// 1. Checks if Project Zomboid is running (blocks if yes)
 /// 2. Validates the undo snapshot tar.gz file exists
-/// 3. Clears the current save directory
-/// 4. Extracts the snapshot tar.gz file to the save directory
+/// 3. Extracts the snapshot next to the save directory and swaps it in (see `swap_in_archive`)
 pub fn restore_from_undo_snapshot(
     save_name: &str,
     snapshot_name: &str,
@@ -676,6 +820,7 @@ pub fn restore_from_undo_snapshot(
     let save_path = config.get_save_path()?;
     let backup_base_path = config.get_backup_path()?;
 
+    crate::file_ops::validate_save_name(save_name)?;
     let save_dir = save_path.join(save_name);
     let undo_snapshot_dir = get_undo_snapshot_dir(&backup_base_path, save_name);
     let snapshot_file = undo_snapshot_dir.join(snapshot_name);
@@ -693,13 +838,8 @@ pub fn restore_from_undo_snapshot(
         )));
     }
 
-    // Clear current save directory if it exists
-    if save_dir.exists() {
-        delete_dir_recursive(&save_dir)?;
-    }
-
-    // Extract the snapshot tar.gz to save directory
-    extract_tar_gz(&snapshot_file, &save_dir)?;
+    // Replace the save directory with the extracted snapshot
+    swap_in_archive(save_name, &snapshot_file, &save_dir)?;
 
     Ok(RestoreResult {
         save_path: save_dir.to_string_lossy().to_string(),
@@ -728,8 +868,7 @@ pub async fn delete_undo_snapshot_async(save_name: &str, snapshot_name: &str) ->
     let snapshot_name = snapshot_name.to_string();
     tokio::task::spawn_blocking(move || delete_undo_snapshot(&save_name, &snapshot_name))
         .await
-        .map_err(|e| RestoreError::FileOp(FileOpsError::Io(std::io::Error::new(
-            std::io::ErrorKind::Other,
+        .map_err(|e| RestoreError::FileOp(FileOpsError::Io(std::io::Error::other(
             format!("Task join error: {}", e),
         ))))?
 }
@@ -766,7 +905,7 @@ mod tests {
     use crate::backup::create_backup;
     use crate::config as config_module;
     use crate::config::Config;
-    use serial_test::serial;
+    use crate::test_support::TestEnv;
     use std::fs::{self, File};
     use std::io::Write;
     use tempfile::TempDir;
@@ -855,8 +994,8 @@ mod tests {
     }
 
     #[test]
-    #[serial]
     fn test_restore_backup_creates_undo_snapshot() {
+        let _env = TestEnv::new();
         let save_base = TempDir::new().unwrap();
         let backup_base = TempDir::new().unwrap();
 
@@ -893,8 +1032,8 @@ mod tests {
     }
 
     #[test]
-    #[serial]
     fn test_restore_backup_when_save_not_exists() {
+        let _env = TestEnv::new();
         let save_base = TempDir::new().unwrap();
         let backup_base = TempDir::new().unwrap();
 
@@ -923,8 +1062,8 @@ mod tests {
     }
 
     #[test]
-    #[serial]
     fn test_restore_backup_not_found() {
+        let _env = TestEnv::new();
         let save_base = TempDir::new().unwrap();
         let backup_base = TempDir::new().unwrap();
 
@@ -935,8 +1074,8 @@ mod tests {
     }
 
     #[test]
-    #[serial]
     fn test_list_undo_snapshots() {
+        let _env = TestEnv::new();
         let save_base = TempDir::new().unwrap();
         let backup_base = TempDir::new().unwrap();
 
@@ -965,8 +1104,8 @@ mod tests {
     }
 
     #[test]
-    #[serial]
     fn test_list_undo_snapshots_empty() {
+        let _env = TestEnv::new();
         let save_base = TempDir::new().unwrap();
         let backup_base = TempDir::new().unwrap();
 
@@ -977,8 +1116,8 @@ mod tests {
     }
 
     #[test]
-    #[serial]
     fn test_restore_from_undo_snapshot() {
+        let _env = TestEnv::new();
         let save_base = TempDir::new().unwrap();
         let backup_base = TempDir::new().unwrap();
 
@@ -1016,8 +1155,8 @@ mod tests {
     }
 
     #[test]
-    #[serial]
     fn test_delete_undo_snapshot() {
+        let _env = TestEnv::new();
         let save_base = TempDir::new().unwrap();
         let backup_base = TempDir::new().unwrap();
 
@@ -1052,8 +1191,8 @@ mod tests {
     }
 
     #[test]
-    #[serial]
     fn test_delete_undo_snapshot_not_found() {
+        let _env = TestEnv::new();
         let save_base = TempDir::new().unwrap();
         let backup_base = TempDir::new().unwrap();
 
@@ -1064,8 +1203,8 @@ mod tests {
     }
 
     #[test]
-    #[serial]
     fn test_restore_from_undo_snapshot_not_found() {
+        let _env = TestEnv::new();
         let save_base = TempDir::new().unwrap();
         let backup_base = TempDir::new().unwrap();
 
@@ -1129,8 +1268,8 @@ mod tests {
     }
 
     #[test]
-    #[serial]
     fn test_full_restore_cycle() {
+        let _env = TestEnv::new();
         let save_base = TempDir::new().unwrap();
         let backup_base = TempDir::new().unwrap();
 
@@ -1174,4 +1313,98 @@ mod tests {
         restore_backup("Survival", &backup_v1.backup_name).unwrap();
         assert_eq!(read_save_content(&save_dir), v1_content);
     }
+
+    /// Lists the hidden work directories next to a save.
+    fn leftover_work_dirs(save_dir: &Path) -> Vec<String> {
+        fs::read_dir(save_dir.parent().unwrap())
+            .unwrap()
+            .flatten()
+            .map(|e| e.file_name().to_string_lossy().to_string())
+            .filter(|n| n.starts_with('.'))
+            .collect()
+    }
+
+    #[test]
+    fn test_restore_leaves_no_work_dirs_or_journal_entry() {
+        let env = TestEnv::with_paths();
+        let save_dir = env.save_dir().join("Survival/MySave");
+        create_test_save(&save_dir);
+
+        let backup = create_backup("Survival/MySave").unwrap();
+        restore_backup("Survival/MySave", &backup.backup_name).unwrap();
+
+        assert!(leftover_work_dirs(&save_dir).is_empty());
+        assert!(journal::recover_interrupted().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_restore_swap_crash_at_every_step_recovers_consistently() {
+        for crash_after in 0..=RESTORE_SWAP_STEPS.len() - 1 {
+            let env = TestEnv::with_paths();
+            let save_dir = env.save_dir().join("Survival/MySave");
+            create_test_save(&save_dir);
+    
+            let backup = create_backup("Survival/MySave").unwrap();
+            let backup_file = get_save_backup_dir(&env.backup_dir(), "Survival/MySave").unwrap().join(&backup.backup_name);
+            modify_save_content(&save_dir, "current state");
+
+            let entry = restore_swap_entry("Survival/MySave", &backup_file, &save_dir);
+            assert!(journal::run_until_crash(&RestoreSwap, entry, crash_after).is_err());
+
+            let reports = journal::recover_interrupted().unwrap();
+            assert_eq!(reports.len(), 1);
+
+            // Either the restore finished or the current save is back, never a mix
+            let expected = if crash_after >= RESTORE_SWAP_ROLL_FORWARD_POINT {
+                assert_eq!(reports[0].action, journal::RecoveryAction::RolledForward);
+                "game state"
+            } else {
+                assert_eq!(reports[0].action, journal::RecoveryAction::RolledBack);
+                "current state"
+            };
+            assert_eq!(read_save_content(&save_dir), expected, "crash after {} steps", crash_after);
+            assert!(save_dir.join("map/pchunk_0_0.dat").exists());
+            assert!(leftover_work_dirs(&save_dir).is_empty(), "crash after {} steps", crash_after);
+            assert!(journal::recover_interrupted().unwrap().is_empty());
+        }
+    }
+
+    #[test]
+    fn test_restore_swap_crash_inside_a_step_recovers_consistently() {
+        let env = TestEnv::with_paths();
+        let save_dir = env.save_dir().join("Survival/MySave");
+        create_test_save(&save_dir);
+
+        let backup = create_backup("Survival/MySave").unwrap();
+        let backup_file = get_save_backup_dir(&env.backup_dir(), "Survival/MySave").unwrap().join(&backup.backup_name);
+        modify_save_content(&save_dir, "current state");
+
+        // The swap-in rename happened, but the crash came before the journal recorded it
+        let entry = restore_swap_entry("Survival/MySave", &backup_file, &save_dir);
+        let staging = entry.path("staging").unwrap().clone();
+        journal::run_until_crash(&RestoreSwap, entry, 2).unwrap_err();
+        fs::rename(&staging, &save_dir).unwrap();
+
+        let reports = journal::recover_interrupted().unwrap();
+        assert_eq!(reports[0].action, journal::RecoveryAction::RolledBack);
+        assert_eq!(read_save_content(&save_dir), "current state");
+        assert!(leftover_work_dirs(&save_dir).is_empty());
+    }
+
+    #[test]
+    fn test_failed_extraction_keeps_current_save() {
+        let env = TestEnv::with_paths();
+        let save_dir = env.save_dir().join("Survival/MySave");
+        create_test_save(&save_dir);
+
+        let backup_dir = get_save_backup_dir(&env.backup_dir(), "Survival/MySave").unwrap();
+        fs::create_dir_all(&backup_dir).unwrap();
+        fs::write(backup_dir.join("broken.tar.gz"), b"not an archive").unwrap();
+
+        assert!(restore_backup("Survival/MySave", "broken.tar.gz").is_err());
+
+        assert_eq!(read_save_content(&save_dir), "game state");
+        assert!(leftover_work_dirs(&save_dir).is_empty());
+        assert!(journal::recover_interrupted().unwrap().is_empty());
+    }
 }
@@ -0,0 +1,263 @@
+//! Revealing saves, backups and application directories in the system file manager.
+//!
+//! This module provides:
+//! - Resolution of a logical location (save, backup, config dir, ...) to a concrete path
+//! - Opening the resolved path in the platform file manager
+
+use crate::backup::get_save_backup_dir;
+use crate::config as config_module;
+use crate::config::{Config, ConfigError, ConfigResult};
+use crate::file_ops::{show_in_file_manager_with, FileManagerLauncher, SystemFileManager};
+use serde::{Deserialize, Serialize};
+use std::path::{Component, Path, PathBuf};
+
+/// Kind of location that can be revealed in the file manager.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum PathKind {
+    /// A save directory; identifier is its relative path (e.g., "Survival/MySave")
+    Save,
+    /// A backup archive; identifier is "<save relative path>/<backup file name>"
+    Backup,
+    /// The backup root, or a save's backup folder when an identifier is given
+    BackupDirectory,
+    /// The application config directory
+    ConfigDirectory,
+    /// The application log directory
+    LogDirectory,
+}
+
+/// Resolves a location to a concrete path using the given configuration.
+///
+/// # Arguments
+/// * `config` - Configuration providing the save and backup roots
+/// * `kind` - Kind of location to resolve
+/// * `identifier` - Save/backup identifier (see [`PathKind`] for the expected format)
+///
+/// # Returns
+/// `ConfigResult<PathBuf>` - Resolved path (not checked for existence)
+///
+/// # Behavior
+/// - `Save` and `Backup` require an identifier
+/// - Identifiers must be relative and may not contain `..` components
+pub fn resolve_path_with_config(
+    config: &Config,
+    kind: PathKind,
+    identifier: Option<&str>,
+) -> ConfigResult<PathBuf> {
+    match kind {
+        PathKind::Save => {
+            let relative_path = require_identifier(kind, identifier)?;
+            Ok(config.get_save_path()?.join(relative_path))
+        }
+        PathKind::Backup => {
+            let identifier = require_identifier(kind, identifier)?;
+            Ok(config.get_backup_path()?.join(identifier))
+        }
+        PathKind::BackupDirectory => {
+            let backup_base_path = config.get_backup_path()?;
+            match identifier.filter(|id| !id.trim().is_empty()) {
+                Some(save_name) => {
+                    validate_identifier(save_name)?;
+                    Ok(get_save_backup_dir(&backup_base_path, save_name)?)
+                }
+                None => Ok(backup_base_path),
+            }
+        }
+        PathKind::ConfigDirectory => config_module::get_config_dir(),
+        PathKind::LogDirectory => config_module::get_log_dir(),
+    }
+}
+
+/// Resolves a location to a concrete path using the persisted configuration.
+pub fn resolve_path(kind: PathKind, identifier: Option<&str>) -> ConfigResult<PathBuf> {
+    let config = config_module::load_config()?;
+    resolve_path_with_config(&config, kind, identifier)
+}
+
+/// Reveals a save, backup or application directory in the system file manager.
+///
+/// # Arguments
+/// * `kind` - Kind of location to reveal
+/// * `identifier` - Save/backup identifier (see [`PathKind`] for the expected format)
+///
+/// # Returns
+/// `ConfigResult<()>` - Ok(()) on success, error if the path can't be resolved or doesn't exist
+///
+/// # Example
+/// ```no_run
+/// use pz_backup_tool_lib::reveal::{reveal_in_file_manager, PathKind};
+///
+/// reveal_in_file_manager(PathKind::Save, Some("Survival/MySave")).unwrap();
+/// ```
+pub fn reveal_in_file_manager(kind: PathKind, identifier: Option<&str>) -> ConfigResult<()> {
+    reveal_in_file_manager_with(&SystemFileManager, kind, identifier)
+}
+
+/// Reveals a location using a specific launcher.
+pub fn reveal_in_file_manager_with(
+    launcher: &dyn FileManagerLauncher,
+    kind: PathKind,
+    identifier: Option<&str>,
+) -> ConfigResult<()> {
+    let path = resolve_path(kind, identifier)?;
+    show_in_file_manager_with(launcher, &path)?;
+    Ok(())
+}
+
+/// Returns the identifier or an error if the kind requires one.
+fn require_identifier(kind: PathKind, identifier: Option<&str>) -> ConfigResult<&str> {
+    let identifier = identifier
+        .filter(|id| !id.trim().is_empty())
+        .ok_or_else(|| ConfigError::InvalidValue(format!("{:?} requires an identifier", kind)))?;
+    validate_identifier(identifier)?;
+    Ok(identifier)
+}
+
+/// Rejects identifiers that would escape the save or backup root.
+fn validate_identifier(identifier: &str) -> ConfigResult<()> {
+    let escapes_root = Path::new(identifier)
+        .components()
+        .any(|c| !matches!(c, Component::Normal(_) | Component::CurDir));
+
+    if escapes_root {
+        return Err(ConfigError::InvalidValue(format!(
+            "Identifier must be a relative path inside the managed folder: {}",
+            identifier
+        )));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file_ops::{FileOpsError, FileOpsResult};
+    use crate::test_support::TestEnv;
+    use std::cell::RefCell;
+    use std::fs;
+    use tempfile::TempDir;
+
+    /// Launcher that records the paths it was asked to reveal.
+    #[derive(Default)]
+    struct RecordingLauncher {
+        revealed: RefCell<Vec<PathBuf>>,
+    }
+
+    impl FileManagerLauncher for RecordingLauncher {
+        fn reveal(&self, path: &Path) -> FileOpsResult<()> {
+            self.revealed.borrow_mut().push(path.to_path_buf());
+            Ok(())
+        }
+    }
+
+    fn test_config(save_dir: &Path, backup_dir: &Path) -> Config {
+        Config::with_paths(
+            save_dir.to_str().unwrap().to_string(),
+            backup_dir.to_str().unwrap().to_string(),
+        )
+    }
+
+    #[test]
+    fn test_resolve_save_path() {
+        let config = test_config(Path::new("/saves"), Path::new("/backups"));
+        let path = resolve_path_with_config(&config, PathKind::Save, Some("Survival/MySave")).unwrap();
+        assert_eq!(path, Path::new("/saves/Survival/MySave"));
+    }
+
+    #[test]
+    fn test_resolve_backup_path() {
+        let config = test_config(Path::new("/saves"), Path::new("/backups"));
+        let path = resolve_path_with_config(
+            &config,
+            PathKind::Backup,
+            Some("Survival/MySave/2024-12-28_14-30-45.tar.gz"),
+        )
+        .unwrap();
+        assert_eq!(path, Path::new("/backups/Survival/MySave/2024-12-28_14-30-45.tar.gz"));
+    }
+
+    #[test]
+    fn test_resolve_backup_directory() {
+        let config = test_config(Path::new("/saves"), Path::new("/backups"));
+
+        let root = resolve_path_with_config(&config, PathKind::BackupDirectory, None).unwrap();
+        assert_eq!(root, Path::new("/backups"));
+
+        let save_dir =
+            resolve_path_with_config(&config, PathKind::BackupDirectory, Some("Survival/MySave")).unwrap();
+        assert_eq!(save_dir, Path::new("/backups/Survival/MySave"));
+    }
+
+    #[test]
+    fn test_resolve_config_and_log_directories() {
+        let _env = TestEnv::new();
+        let config = Config::new();
+
+        let config_dir = resolve_path_with_config(&config, PathKind::ConfigDirectory, None).unwrap();
+        assert_eq!(config_dir, config_module::get_config_dir().unwrap());
+
+        let log_dir = resolve_path_with_config(&config, PathKind::LogDirectory, None).unwrap();
+        assert_eq!(log_dir, config_dir.join("logs"));
+    }
+
+    #[test]
+    fn test_resolve_requires_identifier() {
+        let config = test_config(Path::new("/saves"), Path::new("/backups"));
+
+        let result = resolve_path_with_config(&config, PathKind::Save, None);
+        assert!(matches!(result, Err(ConfigError::InvalidValue(_))));
+
+        let result = resolve_path_with_config(&config, PathKind::Backup, Some("  "));
+        assert!(matches!(result, Err(ConfigError::InvalidValue(_))));
+    }
+
+    #[test]
+    fn test_resolve_rejects_escaping_identifier() {
+        let config = test_config(Path::new("/saves"), Path::new("/backups"));
+
+        let result = resolve_path_with_config(&config, PathKind::Save, Some("../outside"));
+        assert!(matches!(result, Err(ConfigError::InvalidValue(_))));
+
+        let result = resolve_path_with_config(&config, PathKind::BackupDirectory, Some("/etc"));
+        assert!(matches!(result, Err(ConfigError::InvalidValue(_))));
+    }
+
+    #[test]
+    fn test_reveal_existing_save() {
+        let _env = TestEnv::new();
+        let save_base = TempDir::new().unwrap();
+        let backup_base = TempDir::new().unwrap();
+        let save_dir = save_base.path().join("Survival/MySave");
+        fs::create_dir_all(&save_dir).unwrap();
+
+        config_module::save_config(&test_config(save_base.path(), backup_base.path())).unwrap();
+
+        let launcher = RecordingLauncher::default();
+        reveal_in_file_manager_with(&launcher, PathKind::Save, Some("Survival/MySave")).unwrap();
+
+        assert_eq!(launcher.revealed.borrow().as_slice(), &[save_dir]);
+    }
+
+    #[test]
+    fn test_reveal_missing_backup_fails_without_launching() {
+        let _env = TestEnv::new();
+        let save_base = TempDir::new().unwrap();
+        let backup_base = TempDir::new().unwrap();
+
+        config_module::save_config(&test_config(save_base.path(), backup_base.path())).unwrap();
+
+        let launcher = RecordingLauncher::default();
+        let result = reveal_in_file_manager_with(
+            &launcher,
+            PathKind::Backup,
+            Some("Survival/MySave/missing.tar.gz"),
+        );
+
+        assert!(matches!(
+            result,
+            Err(ConfigError::FileOp(FileOpsError::SourceNotFound(_)))
+        ));
+        assert!(launcher.revealed.borrow().is_empty());
+    }
+}
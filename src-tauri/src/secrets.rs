@@ -0,0 +1,174 @@
+//! Secrets store for credentials kept out of config.json.
+//!
+//! Secrets (e.g., RCON passwords) live in `secrets.json` in the config directory,
+//! keyed by name, so the config can be shared or attached to a bug report without
+//! leaking them. On Unix the file is readable only by the user. Secrets are never
+//! returned to the frontend.
+
+use crate::config::{get_config_dir, ConfigError};
+use crate::file_ops::{FileOpsError, LockedJsonStore};
+use serde::{Deserialize, Serialize, Serializer};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// Secrets file name.
+const SECRETS_FILE_NAME: &str = "secrets.json";
+
+/// Contents of `secrets.json`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct SecretsFile {
+    #[serde(default)]
+    secrets: BTreeMap<String, String>,
+}
+
+/// Error type for secrets operations.
+#[derive(Debug)]
+pub enum SecretsError {
+    /// File operation error
+    FileOp(FileOpsError),
+    /// Config error
+    Config(ConfigError),
+    /// JSON serialization error
+    Json(serde_json::Error),
+}
+
+impl From<FileOpsError> for SecretsError {
+    fn from(err: FileOpsError) -> Self {
+        SecretsError::FileOp(err)
+    }
+}
+
+impl From<ConfigError> for SecretsError {
+    fn from(err: ConfigError) -> Self {
+        SecretsError::Config(err)
+    }
+}
+
+impl From<serde_json::Error> for SecretsError {
+    fn from(err: serde_json::Error) -> Self {
+        SecretsError::Json(err)
+    }
+}
+
+impl std::fmt::Display for SecretsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SecretsError::FileOp(err) => write!(f, "File operation error: {}", err),
+            SecretsError::Config(err) => write!(f, "Config error: {}", err),
+            SecretsError::Json(err) => write!(f, "JSON error: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for SecretsError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SecretsError::FileOp(err) => Some(err),
+            SecretsError::Config(err) => Some(err),
+            SecretsError::Json(err) => Some(err),
+        }
+    }
+}
+
+impl Serialize for SecretsError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+/// Result type for secrets operations.
+pub type SecretsResult<T> = Result<T, SecretsError>;
+
+/// Returns the path to the secrets file.
+pub fn get_secrets_file_path() -> SecretsResult<PathBuf> {
+    Ok(get_config_dir()?.join(SECRETS_FILE_NAME))
+}
+
+fn secrets_store() -> SecretsResult<LockedJsonStore<SecretsFile>> {
+    Ok(LockedJsonStore::new(get_secrets_file_path()?))
+}
+
+/// Returns a secret, or None if it isn't set.
+pub fn get_secret(name: &str) -> SecretsResult<Option<String>> {
+    let file: SecretsFile = secrets_store()?.load::<SecretsError>()?;
+    Ok(file.secrets.get(name).cloned())
+}
+
+/// Sets a secret, replacing any previous value.
+pub fn set_secret(name: &str, value: &str) -> SecretsResult<()> {
+    let store = secrets_store()?;
+    store.modify(|file: &mut SecretsFile| {
+        file.secrets.insert(name.to_string(), value.to_string());
+        Ok::<_, SecretsError>(())
+    })?;
+    restrict_permissions(&store);
+    Ok(())
+}
+
+/// Removes a secret.
+///
+/// # Returns
+/// `SecretsResult<bool>` - true if the secret existed
+pub fn delete_secret(name: &str) -> SecretsResult<bool> {
+    let store = secrets_store()?;
+    let existed = store.modify(|file: &mut SecretsFile| Ok::<_, SecretsError>(file.secrets.remove(name).is_some()))?;
+    restrict_permissions(&store);
+    Ok(existed)
+}
+
+/// Returns true if a secret is set, without exposing its value.
+pub fn has_secret(name: &str) -> SecretsResult<bool> {
+    Ok(get_secret(name)?.is_some())
+}
+
+/// Makes the secrets file readable only by the user (best-effort, Unix only).
+fn restrict_permissions(store: &LockedJsonStore<SecretsFile>) {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = std::fs::set_permissions(store.path(), std::fs::Permissions::from_mode(0o600));
+    }
+    #[cfg(not(unix))]
+    let _ = store;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestEnv;
+
+    #[test]
+    fn test_set_get_delete() {
+        let _env = TestEnv::new();
+        assert_eq!(get_secret("rcon:Multiplayer/servertest").unwrap(), None);
+
+        set_secret("rcon:Multiplayer/servertest", "hunter2").unwrap();
+        set_secret("rcon:Multiplayer/other", "swordfish").unwrap();
+        assert_eq!(get_secret("rcon:Multiplayer/servertest").unwrap().as_deref(), Some("hunter2"));
+        assert!(has_secret("rcon:Multiplayer/other").unwrap());
+
+        assert!(delete_secret("rcon:Multiplayer/servertest").unwrap());
+        assert!(!delete_secret("rcon:Multiplayer/servertest").unwrap());
+        assert_eq!(get_secret("rcon:Multiplayer/servertest").unwrap(), None);
+        assert!(has_secret("rcon:Multiplayer/other").unwrap());
+    }
+
+    #[test]
+    fn test_secrets_stay_out_of_config() {
+        let env = TestEnv::new();
+        set_secret("rcon:Multiplayer/servertest", "hunter2").unwrap();
+
+        let config = std::fs::read_to_string(env.config_dir().join("config.json")).unwrap_or_default();
+        assert!(!config.contains("hunter2"));
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(get_secrets_file_path().unwrap()).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+    }
+}
@@ -5,23 +5,197 @@
 //! - Tag association with backups and saves
 //! - Tag database persistence (JSON format)
 //! - Tag CRUD operations
+//! - Change hooks, notified with the saves whose tags changed after every write
+//! - An in-process cache of the parsed database, so reads don't parse tags.json
+//!   each time; it is dropped on every write and re-read when the file's
+//!   modification time or size shows another process changed it. It is a
+//!   process-wide map keyed by the tags.json path rather than Tauri managed state,
+//!   since the CLI, the HTTP API and the backup worker read tags without an app handle
+//!
+//! Every change is one read-modify-write (`with_tags_db`) under a process-wide
+//! mutex and the tags.json file lock: the mutex queues threads of this process
+//! (a Tauri command and the backup worker, say) instead of having them poll the
+//! file lock, which still guards against other processes such as the CLI.
+//!
+//! Each write keeps the previous file as `tags.json.bak` (plus two older
+//! generations). A tags.json that no longer parses, such as one left empty by a
+//! crash, is restored from the newest backup that does, and the recovery is kept
+//! for the UI (`take_recovery`).
+//!
+//! `watch_tags_db` polls tags.json for edits made outside the app, such as bulk
+//! renames in a text editor, and reports the saves whose tags they changed.
+//! Writes of this process are remembered by their file stamp and skipped.
 
 use crate::config::{get_config_dir, ConfigError};
-use crate::file_ops::FileOpsError;
+use crate::css_colors;
+use crate::file_ops::{FileOpsError, LockedJsonStore};
+use crate::history::{self, OperationTrigger, OperationType};
+use crate::keys::{BackupKey, SaveKey};
+use crate::unicode_nfc;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::fs;
-use std::path::PathBuf;
+use std::cell::Cell;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard, RwLock};
+use std::time::{Duration, SystemTime};
 
 /// Tag database file name.
 const TAGS_DB_FILE_NAME: &str = "tags.json";
 
-/// Tag data structure with name and color.
+/// Key normalization version written by `migrate_tag_keys`.
+const KEY_VERSION: u32 = 1;
+
+/// Header of the tag section written by `export_tags_csv`.
+const TAG_CSV_COLUMNS: [&str; 5] = ["name", "color", "description", "backup_count", "save_count"];
+
+/// Header of the association section written by `export_tags_csv`.
+const ASSOCIATION_CSV_COLUMNS: [&str; 4] = ["target_type", "save", "backup", "tags"];
+
+/// Layout version of tags.json written by this build (see `migrate`).
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Previous versions of tags.json kept as backups.
+const TAGS_DB_BACKUPS: usize = 3;
+
+/// Event emitted at startup when tags.json was restored from a backup; payload is `TagsRecovery`.
+pub const TAGS_RECOVERED_EVENT: &str = "tags-recovered";
+
+/// Event emitted after each change to the tags database, by this app or another
+/// program; payload is `TagsChange`.
+pub const TAGS_CHANGED_EVENT: &str = "tags-changed";
+
+/// Interval at which `watch_tags_db` checks tags.json.
+pub const TAGS_WATCH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Colors suggested for new tags, in order, before hues are spaced out (the
+/// frontend's `DEFAULT_TAG_COLORS`).
+pub const TAG_COLOR_PALETTE: &[&str] = &[
+    "#EF4444", "#F97316", "#EAB308", "#22C55E", "#3B82F6", "#A855F7", "#EC4899", "#6B7280",
+];
+
+/// Longest tag name, in chars.
+pub const MAX_TAG_NAME_CHARS: usize = 64;
+
+/// Longest tag icon, in chars (a flag or skin-toned emoji takes 2).
+pub const MAX_ICON_CHARS: usize = 4;
+
+/// Color reported for tag names that are used in associations but not defined.
+const DANGLING_TAG_COLOR: &str = "#808080";
+
+/// Most tags kept in the recently used list.
+pub const MAX_RECENT_TAGS: usize = 10;
+
+/// Most orphaned associations removed without an explicit confirmation.
+pub const MAX_UNCONFIRMED_ORPHANS: usize = 10;
+
+/// Source of the `created_at` / `updated_at` timestamps of tags and associations.
+pub trait Clock {
+    /// Returns the current time.
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// The system clock, used outside tests.
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// Returns the current time as stored in tags.json (RFC 3339, UTC, whole seconds).
+fn timestamp() -> String {
+    #[cfg(test)]
+    let now = test_clock::now().unwrap_or_else(|| SystemClock.now());
+    #[cfg(not(test))]
+    let now = SystemClock.now();
+
+    now.to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+}
+
+/// Per-thread clock override used by tests, like `config::test_config_dir`.
+#[cfg(test)]
+pub(crate) mod test_clock {
+    use super::Clock;
+    use chrono::{DateTime, Utc};
+    use std::cell::RefCell;
+
+    thread_local! {
+        static OVERRIDE: RefCell<Option<Box<dyn Clock>>> = const { RefCell::new(None) };
+    }
+
+    /// Returns the current thread's clock reading, if a clock is set.
+    pub fn now() -> Option<DateTime<Utc>> {
+        OVERRIDE.with(|o| o.borrow().as_ref().map(|clock| clock.now()))
+    }
+
+    /// Sets the current thread's clock, returning the previous one.
+    pub fn set(clock: Option<Box<dyn Clock>>) -> Option<Box<dyn Clock>> {
+        OVERRIDE.with(|o| o.replace(clock))
+    }
+}
+
+/// Tag data structure with name, color and an optional description.
+///
+/// Serialized with a computed `text_color` (see `text_color`), which tags.json leaves out.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(remote = "Self")]
 pub struct Tag {
     /// Tag name (unique identifier)
     pub name: String,
     /// Tag color (hex color code like #FF5733)
     pub color: String,
+    /// Why the tag exists (e.g., "before mod update"); absent in files written
+    /// before descriptions existed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Emoji or short symbol shown before the name (at most `MAX_ICON_CHARS` chars)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+    /// Group the tag is listed under; groups exist only while a tag uses them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<String>,
+    /// Other names the tag is found by (e.g., a friend's name for it in imports)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
+    /// Favorite tags, listed before all others
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub pinned: bool,
+    /// Backups carrying a protected tag are never deleted by retention pruning
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub protected: bool,
+    /// Position in the tag list; ties (e.g., tags from older files) sort by name
+    #[serde(default)]
+    pub sort_index: u32,
+    /// When the tag was created (RFC 3339); absent in files written before timestamps
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<String>,
+    /// When the tag was last renamed or edited (RFC 3339)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<String>,
+}
+
+impl Serialize for Tag {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct WithTextColor<'a> {
+            #[serde(flatten, serialize_with = "serialize_fields")]
+            tag: &'a Tag,
+            text_color: &'static str,
+        }
+        fn serialize_fields<S: serde::Serializer>(tag: &&Tag, serializer: S) -> Result<S::Ok, S::Error> {
+            Tag::serialize(tag, serializer)
+        }
+
+        WithTextColor { tag: self, text_color: text_color(&self.color) }.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Tag {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Tag::deserialize(deserializer)
+    }
 }
 
 /// Tag association type for different targets.
@@ -29,676 +203,6160 @@ pub struct Tag {
 #[serde(tag = "type")]
 pub enum TagTarget {
     /// Backup tag association
-    Backup { save_name: String, backup_name: String },
+    Backup { save_name: SaveKey, backup_name: BackupKey },
     /// Save tag association
-    Save { relative_path: String },
+    Save { relative_path: SaveKey },
+}
+
+impl TagTarget {
+    /// Creates a backup target with canonical keys.
+    pub fn backup(save_name: &str, backup_name: &str) -> Self {
+        TagTarget::Backup {
+            save_name: SaveKey::new(save_name),
+            backup_name: BackupKey::new(backup_name),
+        }
+    }
+
+    /// Creates a save target with a canonical key.
+    pub fn save(relative_path: &str) -> Self {
+        TagTarget::Save {
+            relative_path: SaveKey::new(relative_path),
+        }
+    }
+
+    /// Returns the save the target belongs to.
+    pub fn save_key(&self) -> &SaveKey {
+        match self {
+            TagTarget::Backup { save_name, .. } => save_name,
+            TagTarget::Save { relative_path } => relative_path,
+        }
+    }
 }
 
 /// Tag database containing all tags and associations.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// Lookups go through in-memory indices (tag name → position, target → position)
+/// that aren't serialized. They are built when the database is deserialized and
+/// kept up to date by every method that changes `tags` or `associations`; both
+/// lists are private to this module (read them with `tags()` and `associations()`),
+/// so a name or target missing from an index is absent.
+///
+/// Tag names are unique case-insensitively: lookups try the exact name first, then
+/// the case-folded one, and resolve to the name as stored. Older databases may
+/// still hold names differing only in case (see `conflicting_tags`).
+///
+/// Aliases share that namespace: no alias equals a tag name or another alias
+/// case-insensitively, and every lookup by name also accepts an alias, which
+/// resolves to its tag. Associations always store the tag's name.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(from = "StoredTagsDatabase")]
 pub struct TagsDatabase {
     /// All defined tags (unique by name)
-    #[serde(default)]
-    pub tags: Vec<Tag>,
+    tags: Vec<Tag>,
     /// Tag associations with targets
+    associations: Vec<TagAssociation>,
+    /// Key normalization version the targets were last migrated with (0 = never)
     #[serde(default)]
-    pub associations: Vec<TagAssociation>,
-}
-
-impl Default for TagsDatabase {
-    fn default() -> Self {
-        TagsDatabase {
-            tags: Vec::new(),
-            associations: Vec::new(),
-        }
-    }
+    pub key_version: u32,
+    /// Layout version the file was written with (0 = before versioning)
+    #[serde(default)]
+    pub schema_version: u32,
+    /// Names of the tags the user added most recently, most recent first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub recent_tags: Vec<String>,
+    /// Tags added to new backups, at most one rule per trigger
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub auto_tag_rules: Vec<AutoTagRule>,
+    /// Deleted tags that can still be restored, oldest deletion first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub trash: Vec<DeletedTag>,
+    /// Saved tag queries, in creation order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub collections: Vec<SmartCollection>,
+    /// Tag name → position in `tags`
+    #[serde(skip)]
+    tag_index: HashMap<String, usize>,
+    /// Case-folded tag name → position in `tags`
+    #[serde(skip)]
+    folded_tag_index: HashMap<String, usize>,
+    /// Case-folded alias → position in `tags`
+    #[serde(skip)]
+    alias_index: HashMap<String, usize>,
+    /// Target → position in `associations`
+    #[serde(skip)]
+    association_index: HashMap<TagTarget, usize>,
 }
 
-/// Tag association linking targets to tags.
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct TagAssociation {
-    /// Target object (backup or save)
-    pub target: TagTarget,
-    /// Associated tag names
+/// Fields of tags.json, deserialized before the indices of `TagsDatabase` are built.
+#[derive(Deserialize)]
+struct StoredTagsDatabase {
     #[serde(default)]
-    pub tag_names: Vec<String>,
+    tags: Vec<Tag>,
+    #[serde(default)]
+    associations: Vec<TagAssociation>,
+    #[serde(default)]
+    key_version: u32,
+    #[serde(default)]
+    schema_version: u32,
+    #[serde(default)]
+    recent_tags: Vec<String>,
+    #[serde(default)]
+    auto_tag_rules: Vec<AutoTagRule>,
+    #[serde(default)]
+    trash: Vec<DeletedTag>,
+    #[serde(default)]
+    collections: Vec<SmartCollection>,
 }
 
-/// Error type for tag operations.
-#[derive(Debug)]
-pub enum TagsError {
-    /// File operation error
-    FileOp(FileOpsError),
-    /// JSON serialization/deserialization error
-    Json(serde_json::Error),
-    /// Tag not found
-    TagNotFound(String),
-    /// Invalid color format
-    InvalidColor(String),
-    /// Duplicate tag name
-    DuplicateTag(String),
+impl From<StoredTagsDatabase> for TagsDatabase {
+    fn from(stored: StoredTagsDatabase) -> Self {
+        let mut db = TagsDatabase {
+            tags: stored.tags,
+            associations: stored.associations,
+            key_version: stored.key_version,
+            schema_version: stored.schema_version,
+            recent_tags: stored.recent_tags,
+            auto_tag_rules: stored.auto_tag_rules,
+            trash: stored.trash,
+            collections: stored.collections,
+            ..TagsDatabase::default()
+        };
+        db.rebuild_indices();
+        db
+    }
 }
 
-impl From<FileOpsError> for TagsError {
-    fn from(err: FileOpsError) -> Self {
-        TagsError::FileOp(err)
+impl TagsDatabase {
+    /// Returns all defined tags, in stored order.
+    pub fn tags(&self) -> &[Tag] {
+        &self.tags
     }
-}
 
-impl From<ConfigError> for TagsError {
-    fn from(err: ConfigError) -> Self {
-        match err {
-            ConfigError::FileOp(e) => TagsError::FileOp(e),
-            _ => TagsError::FileOp(FileOpsError::Io(std::io::Error::new(
-                std::io::ErrorKind::Other,
-                format!("Config error: {}", err),
-            ))),
+    /// Returns all tag associations, in stored order.
+    pub fn associations(&self) -> &[TagAssociation] {
+        &self.associations
+    }
+
+    /// Rebuilds the lookup indices from `tags` and `associations`.
+    ///
+    /// The first entry wins for duplicate names or targets, matching a linear search.
+    pub fn rebuild_indices(&mut self) {
+        self.tag_index.clear();
+        self.folded_tag_index.clear();
+        self.alias_index.clear();
+        for (i, tag) in self.tags.iter().enumerate() {
+            self.tag_index.entry(tag.name.clone()).or_insert(i);
+            self.folded_tag_index.entry(fold_tag_name(&tag.name)).or_insert(i);
+            for alias in &tag.aliases {
+                self.alias_index.entry(fold_tag_name(alias)).or_insert(i);
+            }
         }
+        self.rebuild_association_index();
     }
-}
 
-impl From<serde_json::Error> for TagsError {
-    fn from(err: serde_json::Error) -> Self {
-        TagsError::Json(err)
+    fn rebuild_association_index(&mut self) {
+        self.association_index.clear();
+        for (i, association) in self.associations.iter().enumerate() {
+            self.association_index
+                .entry(association.target.clone())
+                .or_insert(i);
+        }
     }
-}
 
-impl std::fmt::Display for TagsError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            TagsError::FileOp(err) => write!(f, "File operation error: {}", err),
-            TagsError::Json(err) => write!(f, "JSON error: {}", err),
-            TagsError::TagNotFound(name) => write!(f, "Tag not found: {}", name),
-            TagsError::InvalidColor(color) => write!(f, "Invalid color format: {}", color),
-            TagsError::DuplicateTag(name) => write!(f, "Tag already exists: {}", name),
+    /// Merges associations whose targets are equal, e.g. after key normalization
+    /// turned "Survival\\MySave" and "Survival/MySave" into the same key.
+    ///
+    /// # Returns
+    /// `usize` - Number of associations merged into an earlier one
+    pub fn merge_duplicate_associations(&mut self) -> usize {
+        let mut positions: HashMap<TagTarget, usize> = HashMap::new();
+        let mut merged: Vec<TagAssociation> = Vec::with_capacity(self.associations.len());
+        let mut count = 0;
+
+        for association in self.associations.drain(..) {
+            match positions.get(&association.target) {
+                Some(&i) => {
+                    count += 1;
+                    // The merged target was first tagged when either entry was
+                    let kept = &mut merged[i];
+                    let earlier = match (&association.created_at, &kept.created_at) {
+                        (Some(a), Some(k)) => a < k,
+                        (Some(_), None) => true,
+                        (None, _) => false,
+                    };
+                    if earlier {
+                        kept.created_at = association.created_at;
+                    }
+                    let names = &mut kept.tag_names;
+                    for name in association.tag_names {
+                        if !names.contains(&name) {
+                            names.push(name);
+                        }
+                    }
+                }
+                None => {
+                    positions.insert(association.target.clone(), merged.len());
+                    merged.push(association);
+                }
+            }
         }
+
+        self.associations = merged;
+        self.rebuild_association_index();
+        count
     }
-}
 
-impl std::error::Error for TagsError {
-    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
-        match self {
-            TagsError::FileOp(err) => Some(err),
-            TagsError::Json(err) => Some(err),
-            _ => None,
+    /// Returns true if a target has several associations or an association repeats a name.
+    pub fn needs_compaction(&self) -> bool {
+        let mut targets = HashSet::new();
+        self.associations.iter().any(|association| {
+            let mut names = HashSet::new();
+            !targets.insert(&association.target)
+                || !association.tag_names.iter().all(|name| names.insert(name))
+        })
+    }
+
+    /// Merges associations with equal targets, removes repeated names within an
+    /// association and drops associations left without tags.
+    ///
+    /// # Returns
+    /// `CompactionStats` - What was merged and removed
+    pub fn compact(&mut self) -> CompactionStats {
+        let merged_associations = self.merge_duplicate_associations();
+
+        let mut removed_duplicate_names = 0;
+        for association in &mut self.associations {
+            let mut seen = HashSet::new();
+            let before = association.tag_names.len();
+            association.tag_names.retain(|name| seen.insert(name.clone()));
+            removed_duplicate_names += before - association.tag_names.len();
         }
+
+        let before = self.associations.len();
+        self.associations.retain(|a| !a.tag_names.is_empty());
+        let removed_associations = before - self.associations.len();
+        self.rebuild_association_index();
+
+        CompactionStats { merged_associations, removed_duplicate_names, removed_associations }
     }
-}
 
-impl Serialize for TagsError {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: serde::Serializer,
-    {
-        serializer.serialize_str(&self.to_string())
+    /// Removes undefined tag names from associations and merges duplicate associations.
+    ///
+    /// # Returns
+    /// `RepairSummary` - What was fixed; duplicate tag names are left for the user
+    pub fn repair(&mut self) -> RepairSummary {
+        let merged_associations = self.merge_duplicate_associations();
+        let mut removed_tag_names = Vec::new();
+        for i in 0..self.associations.len() {
+            let (known, dangling): (Vec<String>, Vec<String>) = self.associations[i]
+                .tag_names
+                .iter()
+                .cloned()
+                .partition(|name| self.has_tag(name));
+            if !dangling.is_empty() {
+                let association = &mut self.associations[i];
+                association.tag_names = known;
+                association.touch();
+                removed_tag_names.push((association.target.clone(), dangling));
+            }
+        }
+        let before = self.associations.len();
+        self.associations.retain(|a| !a.tag_names.is_empty());
+        let removed_associations = before - self.associations.len();
+        self.rebuild_association_index();
+
+        RepairSummary { removed_tag_names, merged_associations, removed_associations }
     }
-}
 
-/// Result type for tag operations.
-pub type TagsResult<T> = Result<T, TagsError>;
+    /// Returns the position of a tag, matching the exact name before the case-folded
+    /// one, then aliases.
+    fn tag_position(&self, name: &str) -> Option<usize> {
+        if let Some(&i) = self.tag_index.get(name) {
+            return Some(i);
+        }
+        let folded = fold_tag_name(name);
+        self.folded_tag_index
+            .get(&folded)
+            .or_else(|| self.alias_index.get(&folded))
+            .copied()
+    }
 
-/// Result type for tag operations used in Tauri commands (serialized).
-pub type TagsResultT<T> = Result<T, String>;
+    /// Returns the stored name of a tag, resolving differences in case.
+    pub fn canonical_name(&self, name: &str) -> Option<&str> {
+        self.tag_position(name).map(|i| self.tags[i].name.as_str())
+    }
 
-/// Returns the full path to the tags database file.
-pub fn get_tags_db_path() -> TagsResult<PathBuf> {
-    let config_dir = get_config_dir()?;
-    Ok(config_dir.join(TAGS_DB_FILE_NAME))
-}
+    /// Returns groups of stored tag names that differ only in case, in tag order.
+    pub fn conflicting_tags(&self) -> Vec<Vec<String>> {
+        let mut groups: Vec<(String, Vec<String>)> = Vec::new();
+        for tag in &self.tags {
+            let folded = fold_tag_name(&tag.name);
+            match groups.iter_mut().find(|(f, _)| *f == folded) {
+                Some((_, names)) => names.push(tag.name.clone()),
+                None => groups.push((folded, vec![tag.name.clone()])),
+            }
+        }
+        groups
+            .into_iter()
+            .map(|(_, names)| names)
+            .filter(|names| names.len() > 1)
+            .collect()
+    }
 
-/// Loads the tags database from disk.
-///
-/// # Returns
-/// `TagsResult<TagsDatabase>` - Loaded database, or default if file doesn't exist
-///
-/// # Behavior
-/// - If tags.json exists, loads and parses it
-/// - If tags.json doesn't exist, returns default empty database
-/// - If tags.json is corrupted, returns error
-pub fn load_tags_db() -> TagsResult<TagsDatabase> {
-    let db_path = get_tags_db_path()?;
+    /// Returns the position of an association.
+    fn association_position(&self, target: &TagTarget) -> Option<usize> {
+        self.association_index.get(target).copied()
+    }
 
-    if !db_path.exists() {
-        // Tags database doesn't exist yet, return default
-        return Ok(TagsDatabase::default());
+    /// Returns a tag by name.
+    pub fn tag(&self, name: &str) -> Option<&Tag> {
+        self.tag_position(name).map(|i| &self.tags[i])
     }
 
-    let content = fs::read_to_string(&db_path)
-        .map_err(FileOpsError::Io)?;
+    /// Returns true if a tag with this name exists.
+    pub fn has_tag(&self, name: &str) -> bool {
+        self.tag_position(name).is_some()
+    }
 
-    let db: TagsDatabase = serde_json::from_str(&content)?;
+    /// Adds a tag (the caller checks for duplicates).
+    pub fn add_tag(&mut self, tag: Tag) {
+        self.tag_index
+            .entry(tag.name.clone())
+            .or_insert(self.tags.len());
+        self.folded_tag_index
+            .entry(fold_tag_name(&tag.name))
+            .or_insert(self.tags.len());
+        for alias in &tag.aliases {
+            self.alias_index
+                .entry(fold_tag_name(alias))
+                .or_insert(self.tags.len());
+        }
+        self.tags.push(tag);
+    }
 
-    Ok(db)
-}
+    /// Removes a tag and strips it from all associations.
+    ///
+    /// # Returns
+    /// `bool` - true if the tag existed
+    pub fn remove_tag(&mut self, name: &str) -> bool {
+        let Some(i) = self.tag_position(name) else {
+            return false;
+        };
 
-/// Saves the tags database to disk.
-///
-/// # Arguments
-/// * `db` - Database to save
-///
-/// # Returns
-/// `TagsResult<()>` - Ok(()) on success
-///
-/// # Behavior
-/// - Creates config directory if it doesn't exist
-/// - Overwrites existing tags.json
-/// - Writes formatted JSON for readability
-pub fn save_tags_db(db: &TagsDatabase) -> TagsResult<()> {
-    let db_path = get_tags_db_path()?;
+        let name = self.tags[i].name.clone();
+        self.tags.retain(|t| t.name != name);
+        self.recent_tags.retain(|t| *t != name);
+        for association in &mut self.associations {
+            association.tag_names.retain(|t| *t != name);
+        }
+        self.associations.retain(|a| !a.tag_names.is_empty());
+        self.rebuild_indices();
+        true
+    }
 
-    // Create config directory if it doesn't exist
-    if let Some(parent) = db_path.parent() {
-        fs::create_dir_all(parent)
-            .map_err(FileOpsError::Io)?;
+    /// Returns the association for a target.
+    pub fn association(&self, target: &TagTarget) -> Option<&TagAssociation> {
+        self.association_position(target).map(|i| &self.associations[i])
     }
 
-    // Serialize to formatted JSON
-    let json = serde_json::to_string_pretty(db)?;
+    /// Returns the association for a target, if any.
+    pub fn association_mut(&mut self, target: &TagTarget) -> Option<&mut TagAssociation> {
+        self.association_position(target)
+            .map(|i| &mut self.associations[i])
+    }
 
-    // Write to file
-    fs::write(&db_path, json)
-        .map_err(FileOpsError::Io)?;
+    /// Returns the association for a target, creating an empty one if needed.
+    pub fn association_or_insert(&mut self, target: &TagTarget) -> &mut TagAssociation {
+        let i = match self.association_position(target) {
+            Some(i) => i,
+            None => {
+                let now = timestamp();
+                self.associations.push(TagAssociation {
+                    target: target.clone(),
+                    tag_names: Vec::new(),
+                    created_at: Some(now.clone()),
+                    updated_at: Some(now),
+                });
+                let i = self.associations.len() - 1;
+                self.association_index.insert(target.clone(), i);
+                i
+            }
+        };
+        &mut self.associations[i]
+    }
 
-    Ok(())
-}
+    /// Removes associations without tags.
+    pub fn remove_empty_associations(&mut self) {
+        let before = self.associations.len();
+        self.associations.retain(|a| !a.tag_names.is_empty());
+        if self.associations.len() != before {
+            self.rebuild_association_index();
+        }
+    }
 
-/// Validates a hex color string.
-///
-/// # Arguments
-/// * `color` - Color string to validate
-///
-/// # Returns
-/// `TagsResult<()>` - Ok(()) if valid, Err otherwise
-///
-/// # Behavior
-/// - Accepts formats: #RGB, #RRGGBB, #RRGGBBAA
-fn validate_color(color: &str) -> TagsResult<()> {
-    let color = color.trim();
+    /// Creates a tag after validating its name and color (see `normalize_color`).
+    ///
+    /// # Returns
+    /// `TagsResult<()>` - Error if the color is invalid or the name is taken; names
+    /// of deleted tags are free, and creating one purges it from the trash
+    pub fn create_tag(
+        &mut self,
+        name: String,
+        color: String,
+        description: Option<String>,
+        icon: Option<String>,
+    ) -> TagsResult<()> {
+        let name = validate_tag_name(&name)?;
+        let color = normalize_color(&color)?;
+        let icon = normalize_icon(icon)?;
 
-    if !color.starts_with('#') {
-        return Err(TagsError::InvalidColor(color.to_string()));
+        if self.has_tag(&name) {
+            return Err(TagsError::DuplicateTag(name));
+        }
+        self.remove_from_trash(&name);
+        let now = timestamp();
+        self.add_tag(Tag {
+            name,
+            color,
+            description: normalize_text(description),
+            icon,
+            group: None,
+            aliases: Vec::new(),
+            pinned: false,
+            protected: false,
+            sort_index: self.next_sort_index(),
+            created_at: Some(now.clone()),
+            updated_at: Some(now),
+        });
+        Ok(())
     }
 
-    let hex_part = &color[1..];
-    let len = hex_part.len();
+    /// Records that the tag at position `i` was edited.
+    fn touch_tag(&mut self, i: usize) {
+        self.tags[i].updated_at = Some(timestamp());
+    }
 
-    // Valid lengths: 3 (RGB), 6 (RRGGBB), 8 (RRGGBBAA)
-    if len != 3 && len != 6 && len != 8 {
-        return Err(TagsError::InvalidColor(color.to_string()));
+    /// Returns the sort index that places a new tag after all existing ones.
+    fn next_sort_index(&self) -> u32 {
+        self.tags.iter().map(|t| t.sort_index.saturating_add(1)).max().unwrap_or(0)
     }
 
-    // Check all characters are valid hex
-    if !hex_part.chars().all(|c| c.is_ascii_hexdigit()) {
-        return Err(TagsError::InvalidColor(color.to_string()));
+    /// Deletes a tag and strips it from all associations, keeping it and its targets
+    /// in the trash until it is restored or purged.
+    ///
+    /// # Returns
+    /// `TagsResult<()>` - Error if the tag doesn't exist
+    pub fn delete_tag(&mut self, name: &str) -> TagsResult<()> {
+        let Some(i) = self.tag_position(name) else {
+            return Err(TagsError::TagNotFound(name.to_string()));
+        };
+        let tag = self.tags[i].clone();
+        let targets = self
+            .associations
+            .iter()
+            .filter(|a| a.tag_names.contains(&tag.name))
+            .map(|a| a.target.clone())
+            .collect();
+
+        self.remove_tag(&tag.name);
+        // Only the latest deletion of a name can be restored
+        self.remove_from_trash(&tag.name);
+        self.trash.push(DeletedTag {
+            tag,
+            targets,
+            deleted_at: Utc::now(),
+        });
+        Ok(())
+    }
+
+    /// Drops the trash entry of a tag name (case-insensitive), if any.
+    fn remove_from_trash(&mut self, name: &str) {
+        let folded = fold_tag_name(name);
+        self.trash.retain(|deleted| fold_tag_name(&deleted.tag.name) != folded);
+    }
+
+    /// Restores a deleted tag and re-adds it to the targets it had.
+    ///
+    /// # Arguments
+    /// * `name` - Name of the deleted tag (case-insensitive)
+    /// * `existing` - Targets found on disk; targets checked and not found are skipped
+    ///
+    /// # Returns
+    /// `TagsResult<usize>` - Number of targets the tag was re-added to; `TagNotFound`
+    /// if the trash has no such tag, `DuplicateTag` if a tag of that name exists again
+    ///
+    /// # Behavior
+    /// Aliases another tag took in the meantime are dropped.
+    pub fn restore_deleted_tag(&mut self, name: &str, existing: &ExistingTargets) -> TagsResult<usize> {
+        let folded = fold_tag_name(name);
+        let Some(j) = self
+            .trash
+            .iter()
+            .rposition(|deleted| deleted.tag.name == name)
+            .or_else(|| self.trash.iter().rposition(|deleted| fold_tag_name(&deleted.tag.name) == folded))
+        else {
+            return Err(TagsError::TagNotFound(name.to_string()));
+        };
+        if self.tag_position(&self.trash[j].tag.name).is_some() {
+            return Err(TagsError::DuplicateTag(self.trash[j].tag.name.clone()));
+        }
+
+        let DeletedTag { mut tag, targets, .. } = self.trash.remove(j);
+        tag.aliases.retain(|alias| self.tag_position(alias).is_none());
+        let name = tag.name.clone();
+        self.add_tag(tag);
+
+        let mut restored = 0;
+        for target in targets.iter().filter(|target| !existing.is_orphaned(target)) {
+            let association = self.association_or_insert(target);
+            if !association.tag_names.contains(&name) {
+                association.tag_names.push(name.clone());
+                association.touch();
+            }
+            restored += 1;
+        }
+        Ok(restored)
+    }
+
+    /// Permanently removes tags deleted at or before `cutoff` from the trash.
+    ///
+    /// # Returns
+    /// `Vec<String>` - Names of the purged tags
+    pub fn purge_deleted_tags(&mut self, cutoff: DateTime<Utc>) -> Vec<String> {
+        let (purged, kept) = std::mem::take(&mut self.trash)
+            .into_iter()
+            .partition::<Vec<_>, _>(|deleted| deleted.deleted_at <= cutoff);
+        self.trash = kept;
+        purged.into_iter().map(|deleted| deleted.tag.name).collect()
+    }
+
+    /// Renames a tag, keeping its color and rewriting it in all associations.
+    ///
+    /// # Returns
+    /// `TagsResult<()>` - Error if the tag doesn't exist or the new name is taken;
+    /// nothing changes then
+    pub fn rename_tag(&mut self, old_name: &str, new_name: String) -> TagsResult<()> {
+        let Some(i) = self.tag_position(old_name) else {
+            return Err(TagsError::TagNotFound(old_name.to_string()));
+        };
+        let new_name = validate_tag_name(&new_name)?;
+        let old_name = self.tags[i].name.clone();
+        if new_name == old_name {
+            return Ok(());
+        }
+        // Changing only the case of a name is allowed, as is taking one of its aliases
+        if self.tag_position(&new_name).is_some_and(|j| j != i) {
+            return Err(TagsError::DuplicateTag(new_name));
+        }
+        let folded = fold_tag_name(&new_name);
+        self.tags[i].aliases.retain(|a| fold_tag_name(a) != folded);
+
+        for rule in &mut self.auto_tag_rules {
+            for name in rule.tag_names.iter_mut().filter(|t| **t == old_name) {
+                *name = new_name.clone();
+            }
+        }
+        for collection in &mut self.collections {
+            let names = collection.include_tags.iter_mut().chain(&mut collection.exclude_tags);
+            for name in names.filter(|t| **t == old_name) {
+                *name = new_name.clone();
+            }
+        }
+        for name in self.recent_tags.iter_mut().filter(|t| **t == old_name) {
+            *name = new_name.clone();
+        }
+        for association in &mut self.associations {
+            if !association.tag_names.contains(&old_name) {
+                continue;
+            }
+            association.touch();
+            // A stale entry may already carry the new name; keep only one
+            if association.tag_names.contains(&new_name) {
+                association.tag_names.retain(|t| *t != old_name);
+            } else {
+                for name in association.tag_names.iter_mut().filter(|t| **t == old_name) {
+                    *name = new_name.clone();
+                }
+            }
+        }
+        self.tags[i].name = new_name;
+        self.touch_tag(i);
+        self.rebuild_indices();
+        Ok(())
+    }
+
+    /// Changes a tag's color after validating it (see `normalize_color`).
+    ///
+    /// # Returns
+    /// `TagsResult<()>` - Error if the color is invalid or the tag doesn't exist
+    pub fn update_tag_color(&mut self, name: &str, color: String) -> TagsResult<()> {
+        let color = normalize_color(&color)?;
+
+        let Some(i) = self.tag_position(name) else {
+            return Err(TagsError::TagNotFound(name.to_string()));
+        };
+        self.tags[i].color = color;
+        self.touch_tag(i);
+        Ok(())
+    }
+
+    /// Sets or clears a tag's description.
+    ///
+    /// # Returns
+    /// `TagsResult<()>` - Error if the tag doesn't exist
+    pub fn update_tag_description(&mut self, name: &str, description: Option<String>) -> TagsResult<()> {
+        let Some(i) = self.tag_position(name) else {
+            return Err(TagsError::TagNotFound(name.to_string()));
+        };
+        self.tags[i].description = normalize_text(description);
+        self.touch_tag(i);
+        Ok(())
+    }
+
+    /// Sets or clears a tag's icon after validating it.
+    ///
+    /// # Returns
+    /// `TagsResult<()>` - Error if the icon is invalid or the tag doesn't exist
+    pub fn update_tag_icon(&mut self, name: &str, icon: Option<String>) -> TagsResult<()> {
+        let icon = normalize_icon(icon)?;
+        let Some(i) = self.tag_position(name) else {
+            return Err(TagsError::TagNotFound(name.to_string()));
+        };
+        self.tags[i].icon = icon;
+        self.touch_tag(i);
+        Ok(())
+    }
+
+    /// Adds another name the tag is found by.
+    ///
+    /// # Returns
+    /// `TagsResult<()>` - `TagNotFound` if the tag doesn't exist, `InvalidName` if the
+    /// alias isn't a valid tag name, `DuplicateTag` if it is already a tag name or alias
+    pub fn add_tag_alias(&mut self, name: &str, alias: &str) -> TagsResult<()> {
+        let Some(i) = self.tag_position(name) else {
+            return Err(TagsError::TagNotFound(name.to_string()));
+        };
+        let alias = validate_tag_name(alias)?;
+        if self.tag_position(&alias).is_some() {
+            return Err(TagsError::DuplicateTag(alias));
+        }
+
+        self.alias_index.insert(fold_tag_name(&alias), i);
+        self.tags[i].aliases.push(alias);
+        self.touch_tag(i);
+        Ok(())
+    }
+
+    /// Removes an alias of a tag, matching it case-insensitively.
+    ///
+    /// # Returns
+    /// `TagsResult<bool>` - True if the tag had the alias; `TagNotFound` if the tag
+    /// doesn't exist
+    pub fn remove_tag_alias(&mut self, name: &str, alias: &str) -> TagsResult<bool> {
+        let Some(i) = self.tag_position(name) else {
+            return Err(TagsError::TagNotFound(name.to_string()));
+        };
+        let folded = fold_tag_name(alias.trim());
+        let aliases = &mut self.tags[i].aliases;
+        let before = aliases.len();
+        aliases.retain(|a| fold_tag_name(a) != folded);
+        if aliases.len() == before {
+            return Ok(false);
+        }
+        self.touch_tag(i);
+        self.rebuild_indices();
+        Ok(true)
+    }
+
+    /// Pins a tag so it is listed first, or unpins it.
+    ///
+    /// # Returns
+    /// `TagsResult<()>` - Error if the tag doesn't exist
+    pub fn set_tag_pinned(&mut self, name: &str, pinned: bool) -> TagsResult<()> {
+        let Some(i) = self.tag_position(name) else {
+            return Err(TagsError::TagNotFound(name.to_string()));
+        };
+        self.tags[i].pinned = pinned;
+        self.touch_tag(i);
+        Ok(())
+    }
+
+    /// Protects a tag's backups from retention pruning, or lifts the protection.
+    ///
+    /// # Returns
+    /// `TagsResult<()>` - Error if the tag doesn't exist
+    pub fn set_tag_protected(&mut self, name: &str, protected: bool) -> TagsResult<()> {
+        let Some(i) = self.tag_position(name) else {
+            return Err(TagsError::TagNotFound(name.to_string()));
+        };
+        self.tags[i].protected = protected;
+        self.touch_tag(i);
+        Ok(())
+    }
+
+    /// Returns true if any tag of the target is protected.
+    pub fn is_protected(&self, target: &TagTarget) -> bool {
+        self.association(target)
+            .is_some_and(|association| self.tags_of(association).iter().any(|tag| tag.protected))
+    }
+
+    /// Moves a tag into a group, or out of any group if `group` is None or blank.
+    ///
+    /// # Returns
+    /// `TagsResult<()>` - Error if the tag doesn't exist
+    pub fn set_tag_group(&mut self, name: &str, group: Option<String>) -> TagsResult<()> {
+        let Some(i) = self.tag_position(name) else {
+            return Err(TagsError::TagNotFound(name.to_string()));
+        };
+        self.tags[i].group = normalize_text(group);
+        self.touch_tag(i);
+        Ok(())
+    }
+
+    /// Reassigns sort indices so tags are listed in the given order.
+    ///
+    /// # Returns
+    /// `TagsResult<()>` - `TagNotFound` for unknown names, `InvalidOrder` if the list
+    /// repeats a tag or leaves one out
+    pub fn reorder_tags(&mut self, names_in_order: &[String]) -> TagsResult<()> {
+        let mut positions = Vec::with_capacity(names_in_order.len());
+        let mut seen = BTreeSet::new();
+        for name in names_in_order {
+            let Some(i) = self.tag_position(name) else {
+                return Err(TagsError::TagNotFound(name.clone()));
+            };
+            if !seen.insert(i) {
+                return Err(TagsError::InvalidOrder(format!("{} is listed more than once", name)));
+            }
+            positions.push(i);
+        }
+        if let Some(missing) = (0..self.tags.len()).find(|i| !seen.contains(i)) {
+            return Err(TagsError::InvalidOrder(format!("{} is missing", self.tags[missing].name)));
+        }
+
+        for (index, i) in positions.into_iter().enumerate() {
+            self.tags[i].sort_index = index as u32;
+        }
+        Ok(())
+    }
+
+    /// Creates or replaces the auto-tag rule of a trigger; an empty list removes it.
+    ///
+    /// # Returns
+    /// `TagsResult<()>` - `TagNotFound` if any tag doesn't exist; nothing changes then
+    pub fn set_auto_tag_rule(&mut self, trigger: OperationTrigger, tag_names: Vec<String>) -> TagsResult<()> {
+        let canonical = self.canonical_names(&tag_names)?;
+
+        self.remove_auto_tag_rule(trigger);
+        if !canonical.is_empty() {
+            self.auto_tag_rules.push(AutoTagRule { trigger, tag_names: canonical });
+        }
+        Ok(())
+    }
+
+    /// Applies one change of a batch (see `apply_tag_batch`).
+    ///
+    /// # Returns
+    /// `TagsResult<()>` - The error of the underlying change; the database may then be
+    /// partly changed, so callers must discard it
+    pub fn apply_op(&mut self, op: TagOp) -> TagsResult<()> {
+        match op {
+            TagOp::CreateTag { name, color, description, icon } => {
+                self.create_tag(name, color, description, icon)
+            }
+            TagOp::DeleteTag { name } => self.delete_tag(&name),
+            TagOp::AddToTarget { target, tags } => {
+                self.add_tags_to(&target, tags.clone())?;
+                self.record_recent_tags(&tags);
+                Ok(())
+            }
+            TagOp::RemoveFromTarget { target, tags } => {
+                self.remove_tags_from(&target, &tags);
+                Ok(())
+            }
+            TagOp::Rename { old_name, new_name } => self.rename_tag(&old_name, new_name),
+        }
+    }
+
+    /// Resolves tag names and aliases to stored names, dropping duplicates.
+    ///
+    /// # Returns
+    /// `TagsResult<Vec<String>>` - Names in first-seen order; `TagNotFound` for the
+    /// first name that isn't a tag
+    fn canonical_names(&self, tag_names: &[String]) -> TagsResult<Vec<String>> {
+        let mut canonical: Vec<String> = Vec::with_capacity(tag_names.len());
+        for tag in tag_names {
+            match self.canonical_name(tag) {
+                Some(name) if canonical.iter().any(|c| c == name) => {}
+                Some(name) => canonical.push(name.to_string()),
+                None => return Err(TagsError::TagNotFound(tag.clone())),
+            }
+        }
+        Ok(canonical)
+    }
+
+    /// Returns the smart collection with this name (case-insensitive).
+    pub fn collection(&self, name: &str) -> Option<&SmartCollection> {
+        let folded = fold_tag_name(name.trim());
+        self.collections.iter().find(|c| fold_tag_name(&c.name) == folded)
+    }
+
+    /// Creates a smart collection, or replaces the one with the same name.
+    ///
+    /// # Returns
+    /// `TagsResult<()>` - `InvalidName` for a blank or overlong name, `TagNotFound` if
+    /// any included or excluded tag doesn't exist; nothing changes then
+    pub fn save_collection(&mut self, collection: SmartCollection) -> TagsResult<()> {
+        let collection = SmartCollection {
+            name: validate_tag_name(&collection.name)?,
+            include_tags: self.canonical_names(&collection.include_tags)?,
+            exclude_tags: self.canonical_names(&collection.exclude_tags)?,
+            ..collection
+        };
+
+        match self.collections.iter().position(|c| fold_tag_name(&c.name) == fold_tag_name(&collection.name)) {
+            Some(i) => self.collections[i] = collection,
+            None => self.collections.push(collection),
+        }
+        Ok(())
+    }
+
+    /// Removes a smart collection.
+    ///
+    /// # Returns
+    /// `bool` - true if the collection existed
+    pub fn remove_collection(&mut self, name: &str) -> bool {
+        let folded = fold_tag_name(name.trim());
+        let before = self.collections.len();
+        self.collections.retain(|c| fold_tag_name(&c.name) != folded);
+        self.collections.len() != before
+    }
+
+    /// Lists the targets a smart collection matches, in association order.
+    pub fn evaluate_collection(&self, collection: &SmartCollection) -> Vec<TagTarget> {
+        self.associations
+            .iter()
+            .filter(|association| {
+                let names: HashSet<&str> = association
+                    .tag_names
+                    .iter()
+                    .map(|name| self.canonical_name(name).unwrap_or(name))
+                    .collect();
+                collection.matches(&association.target, &names)
+            })
+            .map(|association| association.target.clone())
+            .collect()
+    }
+
+    /// Removes the auto-tag rule of a trigger.
+    ///
+    /// # Returns
+    /// `bool` - true if a rule existed
+    pub fn remove_auto_tag_rule(&mut self, trigger: OperationTrigger) -> bool {
+        let before = self.auto_tag_rules.len();
+        self.auto_tag_rules.retain(|rule| rule.trigger != trigger);
+        self.auto_tag_rules.len() != before
+    }
+
+    /// Returns the rule tags for a trigger, split into existing tags and names whose
+    /// tag has since been deleted.
+    pub fn auto_tags_for(&self, trigger: OperationTrigger) -> (Vec<String>, Vec<String>) {
+        let mut existing = Vec::new();
+        let mut deleted = Vec::new();
+        for rule in self.auto_tag_rules.iter().filter(|rule| rule.trigger == trigger) {
+            for name in &rule.tag_names {
+                match self.canonical_name(name) {
+                    Some(canonical) => existing.push(canonical.to_string()),
+                    None => deleted.push(name.clone()),
+                }
+            }
+        }
+        (existing, deleted)
+    }
+
+    /// Returns the distinct groups used by tags, sorted by name.
+    pub fn tag_groups(&self) -> Vec<String> {
+        let groups: BTreeSet<&String> = self.tags.iter().filter_map(|t| t.group.as_ref()).collect();
+        groups.into_iter().cloned().collect()
+    }
+
+    /// Returns the tags in a group, in creation order.
+    pub fn tags_in_group(&self, group: &str) -> Vec<Tag> {
+        self.tags.iter().filter(|t| t.group.as_deref() == Some(group)).cloned().collect()
+    }
+
+    /// Adds existing tags to a target (avoiding duplicates).
+    ///
+    /// Names are stored as the tags spell them, whatever case the caller used.
+    ///
+    /// # Returns
+    /// `TagsResult<()>` - Error if any tag doesn't exist; nothing is added then
+    pub fn add_tags_to(&mut self, target: &TagTarget, tags: Vec<String>) -> TagsResult<()> {
+        // Validate all tags exist
+        let mut canonical = Vec::with_capacity(tags.len());
+        for tag in &tags {
+            match self.canonical_name(tag) {
+                Some(name) => canonical.push(name.to_string()),
+                None => return Err(TagsError::TagNotFound(tag.clone())),
+            }
+        }
+        let tags = canonical;
+
+        let association = self.association_or_insert(target);
+        let before = association.tag_names.len();
+        for tag in tags {
+            if !association.tag_names.contains(&tag) {
+                association.tag_names.push(tag);
+            }
+        }
+        if association.tag_names.len() != before {
+            association.touch();
+        }
+        Ok(())
+    }
+
+    /// Replaces all tags of a target, keeping the given order.
+    ///
+    /// Names are stored as the tags spell them; repeated names are kept once. An empty
+    /// list removes the target's association.
+    ///
+    /// # Returns
+    /// `TagsResult<Vec<String>>` - Tags the target didn't have before; error if any tag
+    /// doesn't exist, and nothing changes then
+    pub fn set_tags_of(&mut self, target: &TagTarget, tags: &[String]) -> TagsResult<Vec<String>> {
+        let mut canonical: Vec<String> = Vec::with_capacity(tags.len());
+        for tag in tags {
+            match self.canonical_name(tag) {
+                Some(name) if canonical.iter().any(|c| c == name) => {}
+                Some(name) => canonical.push(name.to_string()),
+                None => return Err(TagsError::TagNotFound(tag.clone())),
+            }
+        }
+
+        let previous = self.association(target).map(|a| a.tag_names.clone()).unwrap_or_default();
+        let added = canonical.iter().filter(|t| !previous.contains(t)).cloned().collect();
+        if canonical.is_empty() {
+            self.remove_target(target);
+        } else if canonical != previous {
+            let association = self.association_or_insert(target);
+            association.tag_names = canonical;
+            association.touch();
+        }
+        Ok(added)
+    }
+
+    /// Moves tags to the front of the recently used list, in the given order.
+    ///
+    /// Names are resolved to the stored tag names; unknown names are ignored. The
+    /// list keeps each tag once and at most `MAX_RECENT_TAGS` entries.
+    pub fn record_recent_tags(&mut self, tags: &[String]) {
+        let mut used: Vec<String> = Vec::with_capacity(tags.len());
+        for tag in tags {
+            if let Some(name) = self.canonical_name(tag) {
+                if !used.iter().any(|u| u == name) {
+                    used.push(name.to_string());
+                }
+            }
+        }
+        self.recent_tags.retain(|t| !used.contains(t));
+        used.append(&mut self.recent_tags);
+        used.truncate(MAX_RECENT_TAGS);
+        self.recent_tags = used;
+    }
+
+    /// Returns the recently used tags, most recent first.
+    pub fn recent_tags(&self) -> Vec<Tag> {
+        self.recent_tags
+            .iter()
+            .filter_map(|name| self.tag(name).cloned())
+            .collect()
+    }
+
+    /// Finds tags whose name or an alias matches a query, best matches first.
+    ///
+    /// # Returns
+    /// `Vec<Tag>` - At most `limit` tags; for a blank query, pinned tags and then the
+    /// most used ones
+    ///
+    /// # Behavior
+    /// Matching is case-insensitive. An exact match ranks above a prefix match, which
+    /// ranks above a substring match, which ranks above the query's characters merely
+    /// appearing in order. Within a rank, shorter names come first, then pinned and
+    /// more used tags.
+    pub fn search_tags(&self, query: &str, limit: usize) -> Vec<Tag> {
+        let mut uses: HashMap<&str, usize> = HashMap::new();
+        for association in &self.associations {
+            for name in &association.tag_names {
+                *uses.entry(self.canonical_name(name).unwrap_or(name)).or_default() += 1;
+            }
+        }
+        let uses_of = |tag: &Tag| uses.get(tag.name.as_str()).copied().unwrap_or(0);
+
+        // (rank, length of the best matching name or alias, tag)
+        let query = fold_tag_name(query.trim());
+        let mut ranked: Vec<(u8, usize, &Tag)> = if query.is_empty() {
+            self.tags.iter().map(|tag| (0, 0, tag)).collect()
+        } else {
+            self.tags
+                .iter()
+                .filter_map(|tag| {
+                    std::iter::once(&tag.name)
+                        .chain(&tag.aliases)
+                        .filter_map(|name| {
+                            let name = fold_tag_name(name);
+                            match_rank(&query, &name).map(|rank| (rank, name.chars().count()))
+                        })
+                        .min()
+                        .map(|(rank, length)| (rank, length, tag))
+                })
+                .collect()
+        };
+
+        ranked.sort_by_cached_key(|(rank, length, tag)| {
+            (*rank, *length, !tag.pinned, std::cmp::Reverse(uses_of(tag)), tag.sort_index, tag.name.clone())
+        });
+        ranked.into_iter().take(limit).map(|(_, _, tag)| tag.clone()).collect()
+    }
+
+    /// Removes tags from a target, dropping its association if it becomes empty.
+    pub fn remove_tags_from(&mut self, target: &TagTarget, tags: &[String]) {
+        let canonical: Vec<String> = tags
+            .iter()
+            .filter_map(|tag| self.canonical_name(tag).map(str::to_string))
+            .collect();
+        if let Some(association) = self.association_mut(target) {
+            let before = association.tag_names.len();
+            association.tag_names.retain(|t| !tags.contains(t) && !canonical.contains(t));
+            if association.tag_names.len() != before {
+                association.touch();
+            }
+        }
+        self.remove_empty_associations();
+    }
+
+    /// Removes tags from every backup of a save, dropping associations that become empty.
+    ///
+    /// # Returns
+    /// `usize` - Number of backup associations that lost at least one tag
+    pub fn remove_tags_from_save_backups(&mut self, save_name: &str, tags: &[String]) -> usize {
+        let save_name = SaveKey::new(save_name);
+        let canonical: Vec<String> = tags
+            .iter()
+            .filter_map(|tag| self.canonical_name(tag).map(str::to_string))
+            .collect();
+        let mut modified = 0;
+        for association in &mut self.associations {
+            if !matches!(&association.target, TagTarget::Backup { save_name: s, .. } if *s == save_name) {
+                continue;
+            }
+            let before = association.tag_names.len();
+            association.tag_names.retain(|t| !tags.contains(t) && !canonical.contains(t));
+            if association.tag_names.len() != before {
+                association.touch();
+                modified += 1;
+            }
+        }
+        self.remove_empty_associations();
+        modified
+    }
+
+    /// Removes all tags from a target.
+    pub fn clear_tags(&mut self, target: &TagTarget) {
+        self.remove_target(target);
+    }
+
+    /// Drops a target's association, whatever tags it had.
+    ///
+    /// # Returns
+    /// `bool` - True if the target had an association
+    pub fn remove_target(&mut self, target: &TagTarget) -> bool {
+        let Some(i) = self.association_index.get(target).copied() else {
+            return false;
+        };
+        self.associations.remove(i);
+        self.rebuild_association_index();
+        true
+    }
+
+    /// Moves a target's tags to another target, merging them into tags it already has.
+    pub fn rename_target(&mut self, old: &TagTarget, new: &TagTarget) {
+        if old == new {
+            return;
+        }
+        let Some(i) = self.association_index.get(old).copied() else {
+            return;
+        };
+
+        let moved = self.associations.remove(i).tag_names;
+        self.rebuild_association_index();
+        let association = self.association_or_insert(new);
+        for name in moved {
+            if !association.tag_names.contains(&name) {
+                association.tag_names.push(name);
+            }
+        }
+    }
+
+    /// Adds the defined tags of one target to another, keeping the tags it already has.
+    ///
+    /// # Returns
+    /// `Vec<String>` - Tags the receiving target didn't have yet; names without a tag
+    /// definition are skipped
+    pub fn copy_tags_between(&mut self, from: &TagTarget, to: &TagTarget) -> Vec<String> {
+        let names: Vec<String> = self.tags_for(from).into_iter().map(|t| t.name).collect();
+        let existing = self.association(to).map(|a| a.tag_names.clone()).unwrap_or_default();
+        let added: Vec<String> = names.into_iter().filter(|n| !existing.contains(n)).collect();
+        if !added.is_empty() {
+            let association = self.association_or_insert(to);
+            association.tag_names.extend(added.iter().cloned());
+            association.touch();
+        }
+        added
+    }
+
+    /// Lists the associations whose target is checked in `existing` and not found.
+    pub fn orphaned_associations(&self, existing: &ExistingTargets) -> OrphanReport {
+        let mut report = OrphanReport::default();
+        for association in &self.associations {
+            if !existing.is_orphaned(&association.target) {
+                continue;
+            }
+            match &association.target {
+                TagTarget::Backup { save_name, backup_name } => report
+                    .backups
+                    .push((save_name.to_string(), backup_name.to_string())),
+                TagTarget::Save { relative_path } => report.saves.push(relative_path.to_string()),
+            }
+        }
+        report.backups.sort();
+        report.saves.sort();
+        report
+    }
+
+    /// Removes the associations whose target is checked in `existing` and not found.
+    ///
+    /// # Returns
+    /// `OrphanReport` - The removed associations
+    pub fn remove_orphaned_associations(&mut self, existing: &ExistingTargets) -> OrphanReport {
+        let report = self.orphaned_associations(existing);
+        if !report.is_empty() {
+            self.associations.retain(|a| !existing.is_orphaned(&a.target));
+            self.rebuild_association_index();
+        }
+        report
+    }
+
+    /// Returns the tags associated with a target, in association order.
+    pub fn tags_for(&self, target: &TagTarget) -> Vec<Tag> {
+        self.association(target)
+            .map(|association| self.tags_of(association))
+            .unwrap_or_default()
+    }
+
+    /// Returns true if the target's association has at least one tag name.
+    pub fn is_tagged(&self, target: &TagTarget) -> bool {
+        self.association(target)
+            .is_some_and(|association| !association.tag_names.is_empty())
+    }
+
+    /// Returns the defined tags of an association, in association order.
+    fn tags_of(&self, association: &TagAssociation) -> Vec<Tag> {
+        association
+            .tag_names
+            .iter()
+            .filter_map(|name| self.tag(name).cloned())
+            .collect()
+    }
+
+    /// Returns the tags of every tagged backup of a save, by backup name.
+    pub fn backup_tags_of_save(&self, save_name: &str) -> HashMap<String, Vec<Tag>> {
+        let save_name = SaveKey::new(save_name);
+        self.associations
+            .iter()
+            .filter_map(|association| match &association.target {
+                TagTarget::Backup { save_name: s, backup_name } if *s == save_name => {
+                    Some((backup_name.as_str().to_string(), self.tags_of(association)))
+                }
+                _ => None,
+            })
+            .filter(|(_, tags)| !tags.is_empty())
+            .collect()
+    }
+
+    /// Returns the tags of every tagged save, by relative path.
+    pub fn all_save_tags(&self) -> HashMap<String, Vec<Tag>> {
+        self.associations
+            .iter()
+            .filter_map(|association| match &association.target {
+                TagTarget::Save { relative_path } => {
+                    Some((relative_path.as_str().to_string(), self.tags_of(association)))
+                }
+                TagTarget::Backup { .. } => None,
+            })
+            .filter(|(_, tags)| !tags.is_empty())
+            .collect()
+    }
+
+    /// Returns the `(save_name, backup_name)` pairs of backups carrying a tag, sorted.
+    pub fn backups_with_tag(&self, name: &str) -> Vec<(String, String)> {
+        let name = self.canonical_name(name).unwrap_or(name);
+        let mut backups: Vec<(String, String)> = self
+            .associations
+            .iter()
+            .filter(|association| association.tag_names.iter().any(|t| t == name))
+            .filter_map(|association| match &association.target {
+                TagTarget::Backup { save_name, backup_name } => {
+                    Some((save_name.as_str().to_string(), backup_name.as_str().to_string()))
+                }
+                TagTarget::Save { .. } => None,
+            })
+            .collect();
+        backups.sort();
+        backups
+    }
+
+    /// Returns the relative paths of saves carrying a tag, sorted.
+    pub fn saves_with_tag(&self, name: &str) -> Vec<String> {
+        let name = self.canonical_name(name).unwrap_or(name);
+        let mut saves: Vec<String> = self
+            .associations
+            .iter()
+            .filter(|association| association.tag_names.iter().any(|t| t == name))
+            .filter_map(|association| match &association.target {
+                TagTarget::Save { relative_path } => Some(relative_path.as_str().to_string()),
+                TagTarget::Backup { .. } => None,
+            })
+            .collect();
+        saves.sort();
+        saves
+    }
+
+    /// Counts how often each tag is used, including names with no tag definition.
+    ///
+    /// # Returns
+    /// `Vec<TagUsage>` - Defined tags in list order (unused ones with zero counts),
+    /// followed by dangling names sorted by name
+    pub fn tag_usage(&self) -> Vec<TagUsage> {
+        let mut counts: HashMap<&str, (usize, usize)> = HashMap::new();
+        for association in &self.associations {
+            for name in &association.tag_names {
+                let name = self.canonical_name(name).unwrap_or(name);
+                let entry = counts.entry(name).or_default();
+                match association.target {
+                    TagTarget::Backup { .. } => entry.0 += 1,
+                    TagTarget::Save { .. } => entry.1 += 1,
+                }
+            }
+        }
+
+        let mut tags = self.tags.clone();
+        tags.sort_by(|a, b| (a.sort_index, &a.name).cmp(&(b.sort_index, &b.name)));
+        let mut usage: Vec<TagUsage> = tags
+            .into_iter()
+            .map(|tag| {
+                let (backup_count, save_count) = counts.remove(tag.name.as_str()).unwrap_or_default();
+                TagUsage { tag, backup_count, save_count, dangling: false }
+            })
+            .collect();
+
+        let mut dangling: Vec<(&str, (usize, usize))> = counts.into_iter().collect();
+        dangling.sort();
+        usage.extend(dangling.into_iter().map(|(name, (backup_count, save_count))| TagUsage {
+            tag: Tag {
+                name: name.to_string(),
+                color: DANGLING_TAG_COLOR.to_string(),
+                description: None,
+                icon: None,
+                group: None,
+                aliases: Vec::new(),
+                pinned: false,
+                protected: false,
+                sort_index: 0,
+                created_at: None,
+                updated_at: None,
+            },
+            backup_count,
+            save_count,
+            dangling: true,
+        }));
+        usage
+    }
+
+    /// Picks a color for a new tag that stands apart from the existing tags.
+    ///
+    /// # Returns
+    /// `String` - The first `TAG_COLOR_PALETTE` color no tag uses; once all are used,
+    /// the hue halfway across the widest gap between the hues of existing tags
+    pub fn suggest_color(&self) -> String {
+        let used: HashSet<String> = self.tags.iter().map(|t| t.color.trim().to_ascii_uppercase()).collect();
+        if let Some(color) = TAG_COLOR_PALETTE.iter().find(|c| !used.contains(**c)) {
+            return color.to_string();
+        }
+
+        let mut hues: Vec<f64> = self.tags.iter().filter_map(|t| css_colors::hue(&t.color)).collect();
+        if hues.is_empty() {
+            return TAG_COLOR_PALETTE[0].to_string();
+        }
+        hues.sort_by(f64::total_cmp);
+        // The gap after the last hue wraps around to the first
+        let (start, gap) = hues
+            .iter()
+            .enumerate()
+            .map(|(i, &hue)| {
+                let next = hues.get(i + 1).copied().unwrap_or(hues[0] + 360.0);
+                (hue, next - hue)
+            })
+            .fold((0.0, f64::MIN), |best, gap| if gap.1 > best.1 { gap } else { best });
+        css_colors::hsl_to_hex(start + gap / 2.0, 0.7, 0.55)
+    }
+
+    /// Summarizes how tags are used, in one pass over the associations.
+    ///
+    /// # Returns
+    /// `TagStatistics` - Totals plus per-tag counts, ordered like `tag_usage`
+    pub fn tag_statistics(&self) -> TagStatistics {
+        let mut stats = TagStatistics {
+            total_tags: self.tags.len(),
+            ..TagStatistics::default()
+        };
+        let mut counts: HashMap<&str, TagCounts> = HashMap::new();
+        for association in &self.associations {
+            if association.tag_names.is_empty() {
+                stats.untagged_associations += 1;
+                continue;
+            }
+            match association.target {
+                TagTarget::Backup { .. } => stats.tagged_backups += 1,
+                TagTarget::Save { .. } => stats.tagged_saves += 1,
+            }
+            for name in &association.tag_names {
+                let canonical = self.canonical_name(name);
+                let entry = counts
+                    .entry(canonical.unwrap_or(name))
+                    .or_insert_with(|| TagCounts {
+                        name: canonical.unwrap_or(name).to_string(),
+                        dangling: canonical.is_none(),
+                        ..TagCounts::default()
+                    });
+                match &association.target {
+                    TagTarget::Backup { save_name, .. } => {
+                        entry.backup_count += 1;
+                        *entry.backups_per_save.entry(save_name.as_str().to_string()).or_default() += 1;
+                    }
+                    TagTarget::Save { .. } => entry.save_count += 1,
+                }
+            }
+        }
+
+        let mut tags: Vec<&Tag> = self.tags.iter().collect();
+        tags.sort_by(|a, b| (a.sort_index, &a.name).cmp(&(b.sort_index, &b.name)));
+        stats.tags = tags
+            .into_iter()
+            .map(|tag| {
+                counts.remove(tag.name.as_str()).unwrap_or_else(|| TagCounts {
+                    name: tag.name.clone(),
+                    ..TagCounts::default()
+                })
+            })
+            .collect();
+        // Ties go to the tag listed first
+        stats.most_used_tag = stats
+            .tags
+            .iter()
+            .filter(|t| t.backup_count + t.save_count > 0)
+            .rev()
+            .max_by_key(|t| t.backup_count + t.save_count)
+            .map(|t| t.name.clone());
+
+        let mut dangling: Vec<TagCounts> = counts.into_values().collect();
+        dangling.sort_by(|a, b| a.name.cmp(&b.name));
+        stats.dangling_tag_names = dangling.len();
+        stats.tags.extend(dangling);
+        stats
+    }
+}
+
+/// Backups and saves found on disk, for `prune_orphaned_associations`.
+///
+/// Only what was actually checked is listed: associations of saves missing from
+/// `backups`, and all save associations when `saves` is None, are left alone.
+#[derive(Debug, Clone, Default)]
+pub struct ExistingTargets {
+    /// Backup names per save, for each save whose backup directory could be read
+    pub backups: HashMap<SaveKey, HashSet<BackupKey>>,
+    /// Save relative paths, if the saves directory could be read
+    pub saves: Option<HashSet<SaveKey>>,
+}
+
+impl ExistingTargets {
+    /// Returns true if the target was checked and not found.
+    pub fn is_orphaned(&self, target: &TagTarget) -> bool {
+        match target {
+            TagTarget::Backup { save_name, backup_name } => self
+                .backups
+                .get(save_name)
+                .is_some_and(|names| !names.contains(backup_name)),
+            TagTarget::Save { relative_path } => self
+                .saves
+                .as_ref()
+                .is_some_and(|saves| !saves.contains(relative_path)),
+        }
+    }
+}
+
+/// Associations removed (or, pending confirmation, to be removed) by
+/// `prune_orphaned_associations`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OrphanReport {
+    /// `(save_name, backup_name)` pairs of deleted backups, sorted
+    pub backups: Vec<(String, String)>,
+    /// Relative paths of deleted saves, sorted
+    pub saves: Vec<String>,
+    /// True if nothing was removed because more than `MAX_UNCONFIRMED_ORPHANS`
+    /// associations are orphaned and the removal wasn't confirmed
+    pub needs_confirmation: bool,
+}
+
+impl OrphanReport {
+    /// Number of orphaned associations in the report.
+    pub fn len(&self) -> usize {
+        self.backups.len() + self.saves.len()
+    }
+
+    /// Returns true if no association is orphaned.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Inconsistencies found in a tags database by `validate_database`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DatabaseReport {
+    /// Targets whose association names undefined tags, with those names, in file order
+    pub dangling_tag_names: Vec<(TagTarget, Vec<String>)>,
+    /// Targets with more than one association, with the number of associations
+    pub duplicate_associations: Vec<(TagTarget, usize)>,
+    /// Tag names defined more than once
+    pub duplicate_tag_names: Vec<String>,
+}
+
+impl DatabaseReport {
+    /// Returns true if nothing was found.
+    pub fn is_clean(&self) -> bool {
+        self.dangling_tag_names.is_empty()
+            && self.duplicate_associations.is_empty()
+            && self.duplicate_tag_names.is_empty()
+    }
+}
+
+/// What `compact_tags_db` merged and removed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompactionStats {
+    /// Associations merged into an earlier one for the same target
+    pub merged_associations: usize,
+    /// Repeated tag names removed within an association
+    pub removed_duplicate_names: usize,
+    /// Associations dropped because they had no tags
+    pub removed_associations: usize,
+}
+
+/// What `repair_database` fixed.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RepairSummary {
+    /// Undefined tag names removed, per target
+    pub removed_tag_names: Vec<(TagTarget, Vec<String>)>,
+    /// Associations merged into an earlier one for the same target
+    pub merged_associations: usize,
+    /// Associations dropped because no tag was left on them
+    pub removed_associations: usize,
+}
+
+/// Tags added automatically to every new backup started by a trigger.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AutoTagRule {
+    /// What starts the backups the rule applies to
+    pub trigger: OperationTrigger,
+    /// Tags to add; names of tags deleted after the rule was set are skipped
+    pub tag_names: Vec<String>,
+}
+
+/// Order of the tags listed by `get_all_tags`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum TagSort {
+    /// By sort index, so new tags come last until reordered
+    #[default]
+    Position,
+    /// By group, then name; ungrouped tags last
+    Group,
+    /// Newest first; tags from files without timestamps last, by sort index
+    Created,
+}
+
+/// One change of a tag batch, applied in order by `apply_tag_batch`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op")]
+pub enum TagOp {
+    /// Creates a tag (see `create_tag`)
+    CreateTag {
+        name: String,
+        color: String,
+        #[serde(default)]
+        description: Option<String>,
+        #[serde(default)]
+        icon: Option<String>,
+    },
+    /// Deletes a tag into the trash (see `delete_tag`)
+    DeleteTag { name: String },
+    /// Adds existing tags, including ones created earlier in the batch, to a target
+    AddToTarget { target: TagTarget, tags: Vec<String> },
+    /// Removes tags from a target; unknown tags are ignored
+    RemoveFromTarget { target: TagTarget, tags: Vec<String> },
+    /// Renames a tag (see `rename_tag`)
+    Rename { old_name: String, new_name: String },
+}
+
+/// Kind of target a smart collection lists.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TargetKind {
+    /// Backups only
+    Backup,
+    /// Saves only
+    Save,
+}
+
+/// A saved tag query, such as "backups of Survival tagged milestone but not auto".
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SmartCollection {
+    /// Collection name (unique case-insensitively)
+    pub name: String,
+    /// Tags a target must all carry; empty matches every tagged target
+    #[serde(default)]
+    pub include_tags: Vec<String>,
+    /// Tags a target must carry none of; they win over `include_tags`
+    #[serde(default)]
+    pub exclude_tags: Vec<String>,
+    /// Lists only backups or only saves; None lists both
+    #[serde(default)]
+    pub target_kind: Option<TargetKind>,
+    /// Lists only this save and its backups
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub save_name: Option<SaveKey>,
+}
+
+impl SmartCollection {
+    /// Returns true if the collection lists a target carrying these tag names.
+    ///
+    /// Names of tags deleted since the collection was saved match no target, so an
+    /// included one empties the collection and an excluded one excludes nothing.
+    pub fn matches(&self, target: &TagTarget, tag_names: &HashSet<&str>) -> bool {
+        let kind_matches = matches!(
+            (self.target_kind, target),
+            (None, _)
+                | (Some(TargetKind::Backup), TagTarget::Backup { .. })
+                | (Some(TargetKind::Save), TagTarget::Save { .. })
+        );
+        kind_matches
+            && self.save_name.as_ref().is_none_or(|save| save == target.save_key())
+            && !self.exclude_tags.iter().any(|t| tag_names.contains(t.as_str()))
+            && self.include_tags.iter().all(|t| tag_names.contains(t.as_str()))
+    }
+}
+
+/// How many backups and saves carry a tag.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TagUsage {
+    /// The tag; for dangling names, a placeholder with a grey color
+    pub tag: Tag,
+    /// Number of backups carrying the tag
+    pub backup_count: usize,
+    /// Number of saves carrying the tag
+    pub save_count: usize,
+    /// True if associations use this name but the tag itself is missing from tags.json
+    pub dangling: bool,
+}
+
+/// Usage counts of one tag name, part of `TagStatistics`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TagCounts {
+    /// Tag name
+    pub name: String,
+    /// Number of backups carrying the tag
+    pub backup_count: usize,
+    /// Number of saves carrying the tag
+    pub save_count: usize,
+    /// Number of backups carrying the tag, by save relative path
+    pub backups_per_save: BTreeMap<String, usize>,
+    /// True if associations use this name but the tag itself is missing from tags.json
+    pub dangling: bool,
+}
+
+/// Overview of tag usage for the dashboard.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TagStatistics {
+    /// Number of defined tags
+    pub total_tags: usize,
+    /// Number of backups with at least one tag
+    pub tagged_backups: usize,
+    /// Number of saves with at least one tag
+    pub tagged_saves: usize,
+    /// Defined tag on the most backups and saves, None if no tag is used
+    pub most_used_tag: Option<String>,
+    /// Associations without tags; writes drop these, so only hand edits leave them
+    pub untagged_associations: usize,
+    /// Number of names used in associations but missing from the tag list
+    pub dangling_tag_names: usize,
+    /// Defined tags in list order (unused ones with zero counts), then dangling names
+    /// sorted by name
+    pub tags: Vec<TagCounts>,
+}
+
+/// A deleted tag kept in the trash, with the targets it was removed from.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeletedTag {
+    /// The tag as it was when deleted
+    pub tag: Tag,
+    /// Targets that carried the tag
+    pub targets: Vec<TagTarget>,
+    /// When the tag was deleted
+    pub deleted_at: DateTime<Utc>,
+}
+
+/// Tag association linking targets to tags.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TagAssociation {
+    /// Target object (backup or save)
+    pub target: TagTarget,
+    /// Associated tag names
+    #[serde(default)]
+    pub tag_names: Vec<String>,
+    /// When the target was first tagged (RFC 3339); absent in older files
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<String>,
+    /// When tags were last added to or removed from the target (RFC 3339)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<String>,
+}
+
+impl TagAssociation {
+    /// Records that tags were added to or removed from the target.
+    fn touch(&mut self) {
+        self.updated_at = Some(timestamp());
+    }
+}
+
+/// Error type for tag operations.
+#[derive(Debug)]
+pub enum TagsError {
+    /// File operation error
+    FileOp(FileOpsError),
+    /// JSON serialization/deserialization error
+    Json(serde_json::Error),
+    /// Tag not found
+    TagNotFound(String),
+    /// Smart collection not found
+    CollectionNotFound(String),
+    /// Invalid color format
+    InvalidColor(String),
+    /// Tag name is empty, too long or contains control characters
+    InvalidName(String),
+    /// Icon is too long or not a symbol
+    InvalidIcon(String),
+    /// Reorder list doesn't name every tag exactly once
+    InvalidOrder(String),
+    /// Duplicate tag name
+    DuplicateTag(String),
+    /// tags.json was written by a newer version with this schema version
+    UnsupportedSchema(u32),
+    /// A change of a batch failed, so none of the batch was applied
+    BatchOpFailed { index: usize, source: Box<TagsError> },
+}
+
+impl From<FileOpsError> for TagsError {
+    fn from(err: FileOpsError) -> Self {
+        TagsError::FileOp(err)
+    }
+}
+
+impl From<ConfigError> for TagsError {
+    fn from(err: ConfigError) -> Self {
+        match err {
+            ConfigError::FileOp(e) => TagsError::FileOp(e),
+            _ => TagsError::FileOp(FileOpsError::Io(std::io::Error::other(
+                format!("Config error: {}", err),
+            ))),
+        }
+    }
+}
+
+impl From<serde_json::Error> for TagsError {
+    fn from(err: serde_json::Error) -> Self {
+        TagsError::Json(err)
+    }
+}
+
+impl std::fmt::Display for TagsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TagsError::FileOp(err) => write!(f, "File operation error: {}", err),
+            TagsError::Json(err) => write!(f, "JSON error: {}", err),
+            TagsError::TagNotFound(name) => write!(f, "Tag not found: {}", name),
+            TagsError::CollectionNotFound(name) => write!(f, "Smart collection not found: {}", name),
+            TagsError::InvalidColor(color) => write!(f, "Invalid color format: {}", color),
+            TagsError::InvalidName(name) => write!(
+                f,
+                "Invalid tag name: {:?} (use 1 to {} characters without line breaks)",
+                name, MAX_TAG_NAME_CHARS
+            ),
+            TagsError::InvalidIcon(icon) => write!(
+                f,
+                "Invalid icon: {} (use an emoji or up to {} characters)",
+                icon, MAX_ICON_CHARS
+            ),
+            TagsError::DuplicateTag(name) => write!(f, "Tag already exists: {}", name),
+            TagsError::InvalidOrder(reason) => write!(f, "Invalid tag order: {}", reason),
+            TagsError::UnsupportedSchema(version) => write!(
+                f,
+                "tags.json uses schema version {}, newer than the supported version {}; update the app to read it",
+                version, SCHEMA_VERSION
+            ),
+            TagsError::BatchOpFailed { index, source } => {
+                write!(f, "Change {} of the batch failed, nothing was applied: {}", index + 1, source)
+            }
+        }
+    }
+}
+
+impl std::error::Error for TagsError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            TagsError::FileOp(err) => Some(err),
+            TagsError::Json(err) => Some(err),
+            TagsError::BatchOpFailed { source, .. } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl Serialize for TagsError {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+/// Result type for tag operations.
+pub type TagsResult<T> = Result<T, TagsError>;
+
+/// Returns the full path to the tags database file.
+pub fn get_tags_db_path() -> TagsResult<PathBuf> {
+    let config_dir = get_config_dir()?;
+    Ok(config_dir.join(TAGS_DB_FILE_NAME))
+}
+
+/// Upgrades a parsed tags.json to the current layout.
+///
+/// # Arguments
+/// * `db_json` - File contents; `null` (no file) gives an empty database
+///
+/// # Returns
+/// `TagsResult<TagsDatabase>` - Database at `SCHEMA_VERSION`, `UnsupportedSchema` if the
+/// file is from a newer version, or `Json` if it doesn't have the expected layout
+///
+/// # Behavior
+/// Each step upgrades one version. Version 0 covers every file written before
+/// versioning: fields added since then default when absent, so its step only
+/// replaces `null` lists, which older builds could write.
+pub fn migrate(mut db_json: serde_json::Value) -> TagsResult<TagsDatabase> {
+    if db_json.is_null() {
+        return Ok(TagsDatabase {
+            schema_version: SCHEMA_VERSION,
+            ..TagsDatabase::default()
+        });
+    }
+
+    let version = db_json
+        .get("schema_version")
+        .and_then(serde_json::Value::as_u64)
+        .unwrap_or(0);
+    let version = u32::try_from(version).unwrap_or(u32::MAX);
+    if version > SCHEMA_VERSION {
+        return Err(TagsError::UnsupportedSchema(version));
+    }
+
+    if version < 1 {
+        if let Some(fields) = db_json.as_object_mut() {
+            for list in ["tags", "associations"] {
+                if fields.get(list).is_some_and(serde_json::Value::is_null) {
+                    fields.remove(list);
+                }
+            }
+        }
+    }
+
+    let mut db: TagsDatabase = serde_json::from_value(db_json)?;
+    db.schema_version = SCHEMA_VERSION;
+    Ok(db)
+}
+
+/// Serializes a database for tags.json, stamped with the current schema version.
+fn to_stored_json(db: &TagsDatabase) -> TagsResult<serde_json::Value> {
+    let mut value = serde_json::to_value(db)?;
+    value["schema_version"] = SCHEMA_VERSION.into();
+
+    // Text colors follow from the tag colors, so they aren't stored
+    let strip = |tag: &mut serde_json::Value| {
+        if let Some(tag) = tag.as_object_mut() {
+            tag.remove("text_color");
+        }
+    };
+    if let Some(tags) = value.get_mut("tags").and_then(|t| t.as_array_mut()) {
+        tags.iter_mut().for_each(strip);
+    }
+    if let Some(trash) = value.get_mut("trash").and_then(|t| t.as_array_mut()) {
+        trash.iter_mut().filter_map(|entry| entry.get_mut("tag")).for_each(strip);
+    }
+    Ok(value)
+}
+
+/// Loads the tags database from disk.
+///
+/// # Returns
+/// `TagsResult<TagsDatabase>` - Loaded database, or default if file doesn't exist
+///
+/// # Behavior
+/// - If tags.json exists, loads and parses it, upgrading older layouts (see `migrate`)
+/// - If tags.json doesn't exist, returns default empty database
+/// - If tags.json doesn't parse, restores the newest backup that does and records
+///   the recovery for `take_recovery`
+/// - If no backup parses either, or tags.json is from a newer version, returns error
+pub fn load_tags_db() -> TagsResult<TagsDatabase> {
+    Ok(cached_db()?.as_ref().clone())
+}
+
+/// Runs a read-only closure against the cached database, without copying it.
+///
+/// # Returns
+/// `TagsResult<R>` - The closure's result, or an error if tags.json can't be read
+pub fn read_tags_db<R>(f: impl FnOnce(&TagsDatabase) -> R) -> TagsResult<R> {
+    let db = cached_db()?;
+    Ok(f(&db))
+}
+
+/// Checks a database for inconsistencies left by hand edits or older versions.
+///
+/// # Arguments
+/// * `db` - Database as loaded, before duplicate associations are merged
+///
+/// # Returns
+/// `DatabaseReport` - Undefined tag names per association, targets with several
+/// associations and tag names defined more than once
+pub fn validate_database(db: &TagsDatabase) -> DatabaseReport {
+    let mut report = DatabaseReport::default();
+
+    let mut names = HashSet::new();
+    for tag in &db.tags {
+        if !names.insert(tag.name.as_str()) && !report.duplicate_tag_names.contains(&tag.name) {
+            report.duplicate_tag_names.push(tag.name.clone());
+        }
+    }
+
+    let mut counts: HashMap<&TagTarget, usize> = HashMap::new();
+    for association in &db.associations {
+        let count = counts.entry(&association.target).or_insert(0);
+        *count += 1;
+        if *count == 2 {
+            report.duplicate_associations.push((association.target.clone(), 0));
+        }
+
+        let dangling: Vec<String> = association
+            .tag_names
+            .iter()
+            .filter(|name| !db.has_tag(name))
+            .cloned()
+            .collect();
+        if !dangling.is_empty() {
+            report.dangling_tag_names.push((association.target.clone(), dangling));
+        }
+    }
+    for (target, count) in &mut report.duplicate_associations {
+        *count = counts[&*target];
+    }
+
+    report
+}
+
+/// Logs what `validate_database` found in a freshly read tags.json.
+fn log_database_report(report: &DatabaseReport) {
+    let describe = |target: &TagTarget| match target {
+        TagTarget::Backup { save_name, backup_name } => format!("backup {}/{}", save_name, backup_name),
+        TagTarget::Save { relative_path } => format!("save {}", relative_path),
+    };
+    for (target, names) in &report.dangling_tag_names {
+        eprintln!("[WARN] tags.json: {} carries undefined tags: {}", describe(target), names.join(", "));
+    }
+    for (target, count) in &report.duplicate_associations {
+        eprintln!("[WARN] tags.json: {} has {} associations", describe(target), count);
+    }
+    for name in &report.duplicate_tag_names {
+        eprintln!("[WARN] tags.json: tag {:?} is defined more than once", name);
+    }
+}
+
+/// Modification time and size identifying one version of tags.json.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FileStamp {
+    modified: SystemTime,
+    len: u64,
+}
+
+impl FileStamp {
+    /// Returns the stamp of a file, or None if it doesn't exist.
+    fn of(path: &Path) -> Option<Self> {
+        let metadata = std::fs::metadata(path).ok()?;
+        Some(FileStamp {
+            modified: metadata.modified().ok()?,
+            len: metadata.len(),
+        })
+    }
+}
+
+/// Parsed database and the stamp of the file it was read from.
+struct CachedDb {
+    stamp: Option<FileStamp>,
+    db: Arc<TagsDatabase>,
+}
+
+/// Cached databases by tags.json path, so tests with separate config dirs never
+/// replace or invalidate each other's entry. The app itself only has one path.
+static DB_CACHE: Mutex<BTreeMap<PathBuf, CachedDb>> = Mutex::new(BTreeMap::new());
+
+fn db_cache() -> MutexGuard<'static, BTreeMap<PathBuf, CachedDb>> {
+    DB_CACHE.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Drops the cached database of a tags.json; the next read parses it again.
+fn invalidate_db_cache(path: &Path) {
+    db_cache().remove(path);
+}
+
+/// Returns the parsed database, reading tags.json only if it changed since the last read.
+///
+/// The stamp is taken before reading, so a write racing with the read leaves a stamp
+/// that no longer matches and the next call reads again.
+fn cached_db() -> TagsResult<Arc<TagsDatabase>> {
+    let store = tags_store()?;
+    let mut stamp = FileStamp::of(store.path());
+    if let Some(cached) = db_cache().get(store.path()) {
+        if cached.stamp == stamp {
+            return Ok(Arc::clone(&cached.db));
+        }
+    }
+
+    let mut db = match store.load::<TagsError>() {
+        Err(TagsError::Json(e)) => {
+            if !recover_from_backup(&store, &e)? {
+                return Err(TagsError::Json(e));
+            }
+            stamp = FileStamp::of(store.path());
+            migrate(store.load::<TagsError>()?)?
+        }
+        loaded => migrate(loaded?)?,
+    };
+    log_database_report(&validate_database(&db));
+    // Files with an older key layout are merged once by `migrate_tag_keys` instead
+    if db.key_version >= KEY_VERSION && db.needs_compaction() {
+        // A read inside this thread's own write would deadlock on the write lock; writes
+        // of other threads are waited for
+        if !WRITING.with(Cell::get) {
+            match compact_tags_db_in(&store) {
+                Ok(stats) => {
+                    eprintln!(
+                        "[WARN] Compacted tags.json: merged {} association(s), removed {} repeated tag name(s) and {} empty association(s)",
+                        stats.merged_associations, stats.removed_duplicate_names, stats.removed_associations
+                    );
+                    stamp = FileStamp::of(store.path());
+                    db = migrate(store.load::<TagsError>()?)?;
+                }
+                Err(e) => eprintln!("[WARN] Failed to compact tags.json: {}", e),
+            }
+        }
+    }
+    db.compact();
+    db.rebuild_indices();
+    let db = Arc::new(db);
+    db_cache().insert(
+        store.path().to_path_buf(),
+        CachedDb {
+            stamp,
+            db: Arc::clone(&db),
+        },
+    );
+    Ok(db)
+}
+
+/// Saves the tags database to disk.
+///
+/// # Arguments
+/// * `db` - Database to save
+///
+/// # Returns
+/// `TagsResult<()>` - Ok(()) on success
+///
+/// # Behavior
+/// - Creates config directory if it doesn't exist
+/// - Atomically replaces the existing tags.json while holding the tags lock
+/// - Writes formatted JSON for readability
+pub fn save_tags_db(db: &TagsDatabase) -> TagsResult<()> {
+    let store = tags_store()?;
+    let value = to_stored_json(db)?;
+    let saved = {
+        let _write = write_lock();
+        let saved = store.save::<TagsError>(&value);
+        if saved.is_ok() {
+            record_own_write(store.path());
+        }
+        saved
+    };
+    invalidate_db_cache(store.path());
+    saved?;
+    // The previous state isn't known; treat every tagged save as changed
+    notify_change_hooks(&all_saves(db));
+    Ok(())
+}
+
+/// Serializes writes to tags.json within this process.
+static WRITE_LOCK: Mutex<()> = Mutex::new(());
+
+thread_local! {
+    /// Whether this thread holds `WRITE_LOCK`.
+    static WRITING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Holds `WRITE_LOCK` and marks this thread as writing until dropped.
+struct WriteGuard {
+    _lock: MutexGuard<'static, ()>,
+}
+
+impl Drop for WriteGuard {
+    fn drop(&mut self) {
+        WRITING.with(|writing| writing.set(false));
+    }
+}
+
+fn write_lock() -> WriteGuard {
+    let lock = WRITE_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    WRITING.with(|writing| writing.set(true));
+    WriteGuard { _lock: lock }
+}
+
+/// Returns the locked store backing tags.json.
+///
+/// The file is read as plain JSON so `migrate` can inspect it before it is parsed.
+fn tags_store() -> TagsResult<LockedJsonStore<serde_json::Value>> {
+    Ok(LockedJsonStore::new(get_tags_db_path()?).with_backups(TAGS_DB_BACKUPS))
+}
+
+/// Restoration of a corrupted tags.json from one of its backups.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TagsRecovery {
+    /// Backup file the database was restored from
+    pub backup_file: String,
+    /// Why the corrupted file couldn't be read
+    pub error: String,
+}
+
+/// Latest recovery not yet reported to the UI.
+static LAST_RECOVERY: Mutex<Option<TagsRecovery>> = Mutex::new(None);
+
+/// Restores tags.json from its newest readable backup after it failed to parse.
+///
+/// # Arguments
+/// * `store` - Tags store whose file failed to parse
+/// * `error` - Parse error of the file
+///
+/// # Returns
+/// `TagsResult<bool>` - Whether a backup was restored; false if none parses either
+fn recover_from_backup(
+    store: &LockedJsonStore<serde_json::Value>,
+    error: &serde_json::Error,
+) -> TagsResult<bool> {
+    let Some(backup) = store.recover::<TagsError>()? else {
+        return Ok(false);
+    };
+    record_own_write(store.path());
+    eprintln!(
+        "[WARN] {} could not be read ({}); restored it from {}",
+        store.path().display(),
+        error,
+        backup.display()
+    );
+    *LAST_RECOVERY.lock().unwrap_or_else(|e| e.into_inner()) = Some(TagsRecovery {
+        backup_file: backup.display().to_string(),
+        error: error.to_string(),
+    });
+    Ok(true)
+}
+
+/// Returns the latest recovery of tags.json from a backup and clears it.
+pub fn take_recovery() -> Option<TagsRecovery> {
+    LAST_RECOVERY.lock().unwrap_or_else(|e| e.into_inner()).take()
+}
+
+/// Upgrades the stored JSON, applies `f` once and writes the result back.
+fn apply_to_stored<R>(
+    value: &mut serde_json::Value,
+    f: &mut Option<impl FnOnce(&mut TagsDatabase) -> TagsResult<R>>,
+) -> TagsResult<R> {
+    let mut db = migrate(std::mem::take(value))?;
+    let f = f.take().expect("transaction closure runs once");
+    let result = f(&mut db)?;
+    *value = to_stored_json(&db)?;
+    Ok(result)
+}
+
+/// Persistence behind the tags database.
+///
+/// Implemented by the locked tags.json store; tests substitute an in-memory store.
+pub trait TagsStore {
+    /// Loads the database, applies `f` and saves the result once if `f` succeeds.
+    fn transact<R>(&self, f: impl FnOnce(&mut TagsDatabase) -> TagsResult<R>) -> TagsResult<R>;
+}
+
+impl TagsStore for LockedJsonStore<serde_json::Value> {
+    fn transact<R>(&self, f: impl FnOnce(&mut TagsDatabase) -> TagsResult<R>) -> TagsResult<R> {
+        // The read-modify-write holds the file lock, so it always starts from disk
+        let _write = write_lock();
+        let mut f = Some(f);
+        let mut result = self.modify(|value: &mut serde_json::Value| apply_to_stored(value, &mut f));
+        // A file that doesn't parse fails before `f` runs, so it can run on the restored file
+        if let Err(TagsError::Json(e)) = &result {
+            if f.is_some() && recover_from_backup(self, e)? {
+                result = self.modify(|value: &mut serde_json::Value| apply_to_stored(value, &mut f));
+            }
+        }
+        if result.is_ok() {
+            record_own_write(self.path());
+        }
+        invalidate_db_cache(self.path());
+        result
+    }
+}
+
+/// Callback notified after a write with the saves whose tags (or backups' tags) changed.
+pub type TagsChangeHook = fn(&[SaveKey]);
+
+static CHANGE_HOOKS: RwLock<Vec<TagsChangeHook>> = RwLock::new(Vec::new());
+
+/// Registers a hook called after every successful write to the tags database.
+///
+/// Hooks run on the writing thread after the tags lock is released.
+pub fn register_change_hook(hook: TagsChangeHook) {
+    CHANGE_HOOKS
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .push(hook);
+}
+
+fn notify_change_hooks(saves: &[SaveKey]) {
+    if saves.is_empty() {
+        return;
+    }
+    for hook in CHANGE_HOOKS.read().unwrap_or_else(|e| e.into_inner()).iter() {
+        hook(saves);
+    }
+}
+
+/// Kind of change reported by `TAGS_CHANGED_EVENT`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TagsChangeKind {
+    /// A tag was created
+    TagCreated,
+    /// A tag was renamed, recolored or otherwise edited
+    TagUpdated,
+    /// Tags were reordered
+    TagsReordered,
+    /// A tag was moved to the trash
+    TagDeleted,
+    /// A deleted tag was restored
+    TagRestored,
+    /// Deleted tags were removed from the trash for good
+    TrashPurged,
+    /// Tags were added to, removed from or moved between backups or saves
+    TargetTagsChanged,
+    /// An auto-tag rule was set or removed
+    AutoTagRulesChanged,
+    /// A smart collection was saved or deleted
+    CollectionsChanged,
+    /// Several changes were applied together (`apply_tag_batch`)
+    Batch,
+    /// Tags were imported from a metadata bundle
+    Imported,
+    /// Stale or inconsistent entries were cleaned up
+    Cleanup,
+    /// Another program edited tags.json
+    External,
+}
+
+/// Payload of `TAGS_CHANGED_EVENT`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TagsChange {
+    /// What changed
+    pub kind: TagsChangeKind,
+    /// The backup or save whose tags changed, if the change was to a single one
+    #[serde(default)]
+    pub target: Option<TagTarget>,
+    /// For external edits, the saves whose tags (or backups' tags) changed
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub saves: Vec<SaveKey>,
+}
+
+impl TagsChange {
+    /// A change made by this app, optionally to a single target.
+    pub fn new(kind: TagsChangeKind, target: Option<TagTarget>) -> Self {
+        TagsChange { kind, target, saves: Vec::new() }
+    }
+
+    /// An edit of tags.json by another program, detected by `watch_tags_db`.
+    pub fn external(saves: Vec<SaveKey>) -> Self {
+        TagsChange { kind: TagsChangeKind::External, target: None, saves }
+    }
+}
+
+/// Callback notified after each successful change made through this module.
+pub type TagsChangeListener = Box<dyn Fn(&TagsChange) + Send + Sync>;
+
+static CHANGE_LISTENERS: RwLock<Vec<TagsChangeListener>> = RwLock::new(Vec::new());
+
+/// Registers a listener called after every successful tag mutation.
+///
+/// Unlike change hooks, listeners learn the kind of change, once per public call:
+/// a batch is reported once. They run on the calling thread.
+pub fn add_change_listener(listener: impl Fn(&TagsChange) + Send + Sync + 'static) {
+    CHANGE_LISTENERS
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .push(Box::new(listener));
+}
+
+/// Reports a change to the listeners if the mutation succeeded, and returns its result.
+pub(crate) fn announce<R>(result: TagsResult<R>, kind: TagsChangeKind, target: Option<&TagTarget>) -> TagsResult<R> {
+    if result.is_ok() {
+        let change = TagsChange::new(kind, target.cloned());
+        for listener in CHANGE_LISTENERS.read().unwrap_or_else(|e| e.into_inner()).iter() {
+            listener(&change);
+        }
+    }
+    result
+}
+
+/// Returns the saves whose displayed tags differ between two database states.
+///
+/// A save is affected if an association of it (or of one of its backups) changed,
+/// or if it uses a tag whose definition changed or was removed.
+fn changed_saves(before: &TagsDatabase, after: &TagsDatabase) -> Vec<SaveKey> {
+    let names = |db: &TagsDatabase| -> HashMap<TagTarget, Vec<String>> {
+        db.associations
+            .iter()
+            .map(|a| (a.target.clone(), a.tag_names.clone()))
+            .collect()
+    };
+    let (old, new) = (names(before), names(after));
+
+    let changed_tags: BTreeSet<&str> = before
+        .tags
+        .iter()
+        .filter(|tag| after.tag(&tag.name) != Some(*tag))
+        .map(|tag| tag.name.as_str())
+        .collect();
+
+    let mut saves = BTreeSet::new();
+    for (target, tag_names) in old.iter().chain(new.iter()) {
+        let association_changed = old.get(target) != new.get(target);
+        let uses_changed_tag = tag_names.iter().any(|n| changed_tags.contains(n.as_str()));
+        if association_changed || uses_changed_tag {
+            saves.insert(target.save_key().clone());
+        }
+    }
+    saves.into_iter().collect()
+}
+
+/// Returns every save with tag associations.
+fn all_saves(db: &TagsDatabase) -> Vec<SaveKey> {
+    let saves: BTreeSet<SaveKey> = db.associations.iter().map(|a| a.target.save_key().clone()).collect();
+    saves.into_iter().collect()
+}
+
+/// Stamp of tags.json after each write of this process, by path.
+static OWN_WRITES: Mutex<Option<HashMap<PathBuf, Option<FileStamp>>>> = Mutex::new(None);
+
+fn own_writes() -> MutexGuard<'static, Option<HashMap<PathBuf, Option<FileStamp>>>> {
+    OWN_WRITES.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Remembers the file as written by this process, so the watcher doesn't reload it.
+fn record_own_write(path: &Path) {
+    own_writes()
+        .get_or_insert_with(HashMap::new)
+        .insert(path.to_path_buf(), FileStamp::of(path));
+}
+
+/// Returns whether the file is as this process last wrote it.
+fn is_own_write(path: &Path, stamp: Option<FileStamp>) -> bool {
+    own_writes()
+        .as_ref()
+        .and_then(|writes| writes.get(path))
+        .is_some_and(|written| *written == stamp)
+}
+
+/// Detects changes made to tags.json by other programs.
+pub struct TagsDbWatcher {
+    path: PathBuf,
+    stamp: Option<FileStamp>,
+    /// Database at the last external change; None once this process wrote the file
+    known: Option<TagsDatabase>,
+}
+
+impl TagsDbWatcher {
+    /// Creates a watcher for the current tags.json.
+    pub fn new() -> TagsResult<Self> {
+        let path = get_tags_db_path()?;
+        let stamp = FileStamp::of(&path);
+        Ok(TagsDbWatcher {
+            path,
+            stamp,
+            known: None,
+        })
+    }
+
+    /// Checks tags.json once.
+    ///
+    /// # Returns
+    /// `TagsResult<Option<Vec<SaveKey>>>` - Saves whose tags another program changed, None
+    /// if the file is unchanged or was last written by this process, or an error if the
+    /// changed file can't be read
+    ///
+    /// # Behavior
+    /// - Change hooks are notified with the affected saves
+    /// - Without a previous external change to compare against, every tagged save counts
+    ///   as affected
+    /// - A file that doesn't parse is reported once and left alone, since the editor may
+    ///   still be writing it; the next command that reads it recovers it from a backup
+    pub fn poll(&mut self) -> TagsResult<Option<Vec<SaveKey>>> {
+        let stamp = FileStamp::of(&self.path);
+        if stamp == self.stamp {
+            return Ok(None);
+        }
+        self.stamp = stamp;
+        if is_own_write(&self.path, stamp) {
+            self.known = None;
+            return Ok(None);
+        }
+
+        let store = LockedJsonStore::<serde_json::Value>::new(&self.path);
+        let mut db = migrate(store.load::<TagsError>()?)?;
+        db.merge_duplicate_associations();
+        db.rebuild_indices();
+        let changed = match &self.known {
+            Some(known) => changed_saves(known, &db),
+            None => all_saves(&db),
+        };
+        self.known = Some(db);
+        notify_change_hooks(&changed);
+        Ok(Some(changed))
+    }
+}
+
+/// Watches tags.json for edits made outside the app.
+///
+/// # Arguments
+/// * `interval` - Time between checks (`TAGS_WATCH_INTERVAL` in the app)
+/// * `on_change` - Called with the affected saves after each external change
+///
+/// # Behavior
+/// Polls with a `TagsDbWatcher` on a background thread for the lifetime of the process.
+/// Failures to read a changed file are logged.
+pub fn watch_tags_db<F>(interval: Duration, on_change: F) -> TagsResult<()>
+where
+    F: Fn(Vec<SaveKey>) + Send + 'static,
+{
+    let mut watcher = TagsDbWatcher::new()?;
+    std::thread::spawn(move || loop {
+        std::thread::sleep(interval);
+        match watcher.poll() {
+            Ok(Some(saves)) => on_change(saves),
+            Ok(None) => {}
+            Err(e) => eprintln!("[WARN] Failed to reload tags.json after an external change: {}", e),
+        }
+    });
+    Ok(())
+}
+
+/// Applies any number of mutations to the tags database with a single load and save.
+///
+/// # Arguments
+/// * `f` - Closure performing the mutations (e.g., `TagsDatabase::add_tags_to`)
+///
+/// # Returns
+/// `TagsResult<R>` - The closure's result
+///
+/// # Behavior
+/// - Holds the tags lock for the whole batch
+/// - The closure sees a database with up-to-date indices
+/// - Nothing is saved if the closure fails
+///
+/// # Example
+/// ```ignore
+/// tags::with_tags_db(|db| {
+///     for backup_name in &deleted {
+///         db.clear_tags(&TagTarget::backup(save_name, backup_name));
+///     }
+///     db.add_tags_to(&TagTarget::save(save_name), vec!["archived".to_string()])
+/// })?;
+/// ```
+pub fn with_tags_db<R>(f: impl FnOnce(&mut TagsDatabase) -> TagsResult<R>) -> TagsResult<R> {
+    with_tags_db_in(&tags_store()?, f)
+}
+
+/// Same as `with_tags_db`, against a specific store.
+pub fn with_tags_db_in<S: TagsStore, R>(
+    store: &S,
+    f: impl FnOnce(&mut TagsDatabase) -> TagsResult<R>,
+) -> TagsResult<R> {
+    let mut changed = Vec::new();
+    let result = store.transact(|db| {
+        db.merge_duplicate_associations();
+        db.rebuild_indices();
+        let before = db.clone();
+        let result = f(db)?;
+        changed = changed_saves(&before, db);
+        Ok(result)
+    })?;
+
+    notify_change_hooks(&changed);
+    Ok(result)
+}
+
+/// Renormalizes the keys stored in tags.json, merging targets that now collide.
+///
+/// # Returns
+/// `TagsResult<usize>` - Number of merged associations (0 if already migrated)
+///
+/// # Behavior
+/// - Keys are normalized whenever the database is loaded; this persists the result once
+/// - Records `key_version` so later launches skip the rewrite
+pub fn migrate_tag_keys() -> TagsResult<usize> {
+    if load_tags_db()?.key_version >= KEY_VERSION {
+        return Ok(0);
+    }
+
+    let (merged, saves) = tags_store()?.transact(|db| {
+        let merged = db.merge_duplicate_associations();
+        db.key_version = KEY_VERSION;
+        Ok((merged, all_saves(db)))
+    })?;
+
+    if merged > 0 {
+        notify_change_hooks(&saves);
+        return announce(Ok(merged), TagsChangeKind::Cleanup, None);
+    }
+    Ok(merged)
+}
+
+/// Merges duplicate associations and repeated tag names, and rewrites tags.json.
+///
+/// # Returns
+/// `TagsResult<CompactionStats>` - Merged associations, removed repeated names and
+/// removed empty associations
+///
+/// # Behavior
+/// - Associations for the same target are merged into the first one, keeping the
+///   order in which names first appear
+/// - Loading tags.json runs this automatically when it finds duplicates; reads merge
+///   them in memory either way, so only the file changes
+pub fn compact_tags_db() -> TagsResult<CompactionStats> {
+    compact_tags_db_in(&tags_store()?)
+}
+
+fn compact_tags_db_in<S: TagsStore>(store: &S) -> TagsResult<CompactionStats> {
+    announce(store.transact(|db| Ok(db.compact())), TagsChangeKind::Cleanup, None)
+}
+
+/// Fixes the inconsistencies reported by `validate_database` that have a safe fix.
+///
+/// # Returns
+/// `TagsResult<RepairSummary>` - Removed undefined tag names and merged or emptied
+/// associations
+///
+/// # Behavior
+/// - Undefined tag names are removed from their associations; associations left
+///   without tags are dropped
+/// - Associations for the same target are merged into the first one
+/// - Tags defined more than once are left alone, since either copy may be the one to keep
+pub fn repair_database() -> TagsResult<RepairSummary> {
+    let (summary, saves) = tags_store()?.transact(|db| {
+        db.rebuild_indices();
+        let before = db.clone();
+        let summary = db.repair();
+        Ok((summary, changed_saves(&before, db)))
+    })?;
+
+    notify_change_hooks(&saves);
+    announce(Ok(summary), TagsChangeKind::Cleanup, None)
+}
+
+/// Validates a tag name and returns it trimmed.
+///
+/// # Arguments
+/// * `name` - Name as entered
+///
+/// # Returns
+/// `TagsResult<String>` - Trimmed name, or `InvalidName` if it's empty after trimming,
+/// longer than `MAX_TAG_NAME_CHARS` chars or contains control characters (including
+/// newlines and tabs)
+fn validate_tag_name(name: &str) -> TagsResult<String> {
+    let trimmed = name.trim();
+    if trimmed.is_empty()
+        || trimmed.chars().count() > MAX_TAG_NAME_CHARS
+        || trimmed.chars().any(char::is_control)
+    {
+        return Err(TagsError::InvalidName(name.to_string()));
+    }
+    Ok(trimmed.to_string())
+}
+
+/// Validates a hex color string.
+///
+/// # Arguments
+/// * `color` - Color string to validate
+///
+/// # Returns
+/// `TagsResult<()>` - Ok(()) if valid, Err otherwise
+///
+/// # Behavior
+/// - Accepts formats: #RGB, #RRGGBB, #RRGGBBAA
+fn validate_color(color: &str) -> TagsResult<()> {
+    let color = color.trim();
+
+    if !color.starts_with('#') {
+        return Err(TagsError::InvalidColor(color.to_string()));
+    }
+
+    let hex_part = &color[1..];
+    let len = hex_part.len();
+
+    // Valid lengths: 3 (RGB), 6 (RRGGBB), 8 (RRGGBBAA)
+    if len != 3 && len != 6 && len != 8 {
+        return Err(TagsError::InvalidColor(color.to_string()));
+    }
+
+    // Check all characters are valid hex
+    if !hex_part.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(TagsError::InvalidColor(color.to_string()));
+    }
+
+    Ok(())
+}
+
+/// Picks the text color that reads best on a tag color.
+///
+/// # Arguments
+/// * `color` - Tag color in a hex form accepted by `validate_color`
+///
+/// # Returns
+/// `&'static str` - `#000000` or `#FFFFFF`, whichever has the higher WCAG contrast
+/// ratio with the color; `#FFFFFF` if the color isn't valid hex
+///
+/// # Behavior
+/// Alpha is ignored: chips are drawn with the color as given. Black wins ties.
+pub fn text_color(color: &str) -> &'static str {
+    const WHITE: &str = "#FFFFFF";
+    const BLACK: &str = "#000000";
+
+    let color = color.trim();
+    if validate_color(color).is_err() {
+        return WHITE;
+    }
+    let hex = &color[1..];
+    let channel = |i: usize| -> f64 {
+        let value = if hex.len() == 3 {
+            u8::from_str_radix(&hex[i..i + 1], 16).map(|v| v * 17)
+        } else {
+            u8::from_str_radix(&hex[2 * i..2 * i + 2], 16)
+        };
+        // sRGB to linear light, as in the WCAG definition of relative luminance
+        let c = f64::from(value.unwrap_or(0)) / 255.0;
+        if c <= 0.03928 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    let luminance = 0.2126 * channel(0) + 0.7152 * channel(1) + 0.0722 * channel(2);
+
+    let contrast_with_black = (luminance + 0.05) / 0.05;
+    let contrast_with_white = 1.05 / (luminance + 0.05);
+    if contrast_with_black >= contrast_with_white {
+        BLACK
+    } else {
+        WHITE
+    }
+}
+
+/// Converts a color to the hex form stored on tags.
+///
+/// # Arguments
+/// * `color` - `#RGB`, `#RRGGBB`, `#RRGGBBAA`, a CSS color name or `rgb()` / `rgba()`
+///
+/// # Returns
+/// `TagsResult<String>` - Hex colors trimmed but otherwise unchanged; names and
+/// `rgb()` converted to `#RRGGBB` (or `#RRGGBBAA` with alpha). `InvalidColor` carries
+/// the original input.
+fn normalize_color(color: &str) -> TagsResult<String> {
+    let trimmed = color.trim();
+    if trimmed.starts_with('#') {
+        return validate_color(trimmed)
+            .map(|()| trimmed.to_string())
+            .map_err(|_| TagsError::InvalidColor(color.to_string()));
+    }
+    css_colors::named_color(trimmed)
+        .map(str::to_string)
+        .or_else(|| css_colors::parse_rgb(trimmed))
+        .ok_or_else(|| TagsError::InvalidColor(color.to_string()))
+}
+
+/// Validates and trims an icon; blank icons are stored as None.
+///
+/// # Behavior
+/// Icons may have at most `MAX_ICON_CHARS` chars and no whitespace or control
+/// characters. Chars are counted rather than graphemes, so joined emoji sequences
+/// (e.g., families) may be too long.
+fn normalize_icon(icon: Option<String>) -> TagsResult<Option<String>> {
+    let Some(icon) = icon.map(|i| i.trim().to_string()).filter(|i| !i.is_empty()) else {
+        return Ok(None);
+    };
+    if icon.chars().count() > MAX_ICON_CHARS || icon.chars().any(|c| c.is_whitespace() || c.is_control()) {
+        return Err(TagsError::InvalidIcon(icon));
+    }
+    Ok(Some(icon))
+}
+
+/// Folds a tag name for case-insensitive comparison.
+///
+/// Uses NFC plus Unicode lowercasing, which covers case folding except for a few
+/// multi-character mappings (e.g., "ß" and "SS" stay distinct).
+fn fold_tag_name(name: &str) -> String {
+    unicode_nfc::nfc(name).to_lowercase()
+}
+
+/// Ranks how well a folded name matches a folded query; lower is better.
+///
+/// # Returns
+/// `Option<u8>` - 0 for equal, 1 for a prefix, 2 for a substring, 3 if the query's
+/// characters appear in order; None if nothing matches
+fn match_rank(query: &str, name: &str) -> Option<u8> {
+    if name == query {
+        Some(0)
+    } else if name.starts_with(query) {
+        Some(1)
+    } else if name.contains(query) {
+        Some(2)
+    } else {
+        let mut chars = name.chars();
+        query.chars().all(|q| chars.any(|c| c == q)).then_some(3)
+    }
+}
+
+/// Trims a description or group name; blank values are stored as None.
+fn normalize_text(value: Option<String>) -> Option<String> {
+    value
+        .map(|d| d.trim().to_string())
+        .filter(|d| !d.is_empty())
+}
+
+/// Creates a new tag.
+///
+/// # Arguments
+/// * `name` - Tag name (must be unique)
+/// * `color` - Tag color (hex format like #FF5733)
+/// * `description` - Optional description of what the tag is for
+/// * `icon` - Optional emoji or short symbol (see `MAX_ICON_CHARS`)
+///
+/// # Returns
+/// `TagsResult<()>` - Ok(()) on success
+pub fn create_tag(
+    name: String,
+    color: String,
+    description: Option<String>,
+    icon: Option<String>,
+) -> TagsResult<()> {
+    announce(with_tags_db(|db| db.create_tag(name, color, description, icon)), TagsChangeKind::TagCreated, None)
+}
+
+/// Deletes a tag and removes all its associations.
+///
+/// # Arguments
+/// * `name` - Tag name to delete
+///
+/// # Returns
+/// `TagsResult<()>` - Ok(()) on success
+///
+/// # Behavior
+/// The tag moves to the trash along with the targets it had, so
+/// `restore_deleted_tag` can undo the deletion until `purge_deleted_tags` drops it.
+pub fn delete_tag(name: String) -> TagsResult<()> {
+    announce(with_tags_db(|db| db.delete_tag(&name)), TagsChangeKind::TagDeleted, None)
+}
+
+/// Lists the deleted tags that can still be restored.
+///
+/// # Returns
+/// `TagsResult<Vec<DeletedTag>>` - Trash entries, oldest deletion first
+pub fn get_deleted_tags() -> TagsResult<Vec<DeletedTag>> {
+    read_tags_db(|db| db.trash.clone())
+}
+
+/// Restores a deleted tag and re-adds it to the backups and saves it had.
+///
+/// # Arguments
+/// * `name` - Name of the deleted tag
+///
+/// # Returns
+/// `TagsResult<usize>` - Number of backups and saves tagged again; `TagNotFound` if
+/// the tag isn't in the trash, `DuplicateTag` if a tag of that name was created since
+///
+/// # Behavior
+/// Backups and saves deleted since are skipped (see `find_existing_targets`).
+pub fn restore_deleted_tag(name: String) -> TagsResult<usize> {
+    let existing = find_existing_targets()?;
+
+    announce(with_tags_db(|db| db.restore_deleted_tag(&name, &existing)), TagsChangeKind::TagRestored, None)
+}
+
+/// Permanently removes tags deleted at least `older_than_days` days ago.
+///
+/// # Arguments
+/// * `older_than_days` - Minimum age of the deletion; 0 empties the trash
+///
+/// # Returns
+/// `TagsResult<Vec<String>>` - Names of the purged tags
+pub fn purge_deleted_tags(older_than_days: u32) -> TagsResult<Vec<String>> {
+    let cutoff = Utc::now() - chrono::Duration::days(i64::from(older_than_days));
+
+    announce(with_tags_db(|db| Ok(db.purge_deleted_tags(cutoff))), TagsChangeKind::TrashPurged, None)
+}
+
+/// Renames a tag, updating every backup and save it is assigned to.
+///
+/// # Arguments
+/// * `old_name` - Current tag name
+/// * `new_name` - New tag name (must be unique)
+///
+/// # Returns
+/// `TagsResult<()>` - `TagNotFound` if `old_name` doesn't exist, `DuplicateTag`
+/// if `new_name` is taken
+///
+/// # Behavior
+/// The tag and all associations are rewritten in one tags.json save.
+pub fn rename_tag(old_name: String, new_name: String) -> TagsResult<()> {
+    announce(with_tags_db(|db| db.rename_tag(&old_name, new_name)), TagsChangeKind::TagUpdated, None)
+}
+
+/// Changes the color of a tag.
+///
+/// # Arguments
+/// * `name` - Tag name
+/// * `color` - New color (hex format like #FF5733)
+///
+/// # Returns
+/// `TagsResult<()>` - `TagNotFound` for unknown tags, `InvalidColor` for bad colors
+pub fn update_tag_color(name: String, color: String) -> TagsResult<()> {
+    announce(with_tags_db(|db| db.update_tag_color(&name, color)), TagsChangeKind::TagUpdated, None)
+}
+
+/// Sets or clears the description of a tag.
+///
+/// # Arguments
+/// * `name` - Tag name
+/// * `description` - New description; None or blank clears it
+///
+/// # Returns
+/// `TagsResult<()>` - `TagNotFound` for unknown tags
+pub fn update_tag_description(name: String, description: Option<String>) -> TagsResult<()> {
+    announce(with_tags_db(|db| db.update_tag_description(&name, description)), TagsChangeKind::TagUpdated, None)
+}
+
+/// Sets or clears the icon of a tag.
+///
+/// # Arguments
+/// * `name` - Tag name
+/// * `icon` - New icon; None or blank clears it
+///
+/// # Returns
+/// `TagsResult<()>` - `TagNotFound` for unknown tags, `InvalidIcon` for icons that
+/// are too long or contain whitespace
+pub fn update_tag_icon(name: String, icon: Option<String>) -> TagsResult<()> {
+    announce(with_tags_db(|db| db.update_tag_icon(&name, icon)), TagsChangeKind::TagUpdated, None)
+}
+
+/// Adds an alias to a tag, so lookups by the alias find the tag.
+///
+/// # Arguments
+/// * `name` - Tag name
+/// * `alias` - Other name for the tag
+///
+/// # Returns
+/// `TagsResult<()>` - `DuplicateTag` if the alias is already a tag name or alias
+///
+/// # Behavior
+/// Adding the alias to a backup or save stores the tag's name in the association.
+pub fn add_tag_alias(name: String, alias: String) -> TagsResult<()> {
+    announce(with_tags_db(|db| db.add_tag_alias(&name, &alias)), TagsChangeKind::TagUpdated, None)
+}
+
+/// Removes an alias from a tag.
+///
+/// # Arguments
+/// * `name` - Tag name
+/// * `alias` - Alias to remove (case-insensitive)
+///
+/// # Returns
+/// `TagsResult<bool>` - True if the tag had the alias
+pub fn remove_tag_alias(name: String, alias: String) -> TagsResult<bool> {
+    announce(with_tags_db(|db| db.remove_tag_alias(&name, &alias)), TagsChangeKind::TagUpdated, None)
+}
+
+/// Moves a tag into a group.
+///
+/// # Arguments
+/// * `name` - Tag name
+/// * `group` - Group name; None or blank removes the tag from its group
+///
+/// # Behavior
+/// Groups aren't stored separately: a group exists while at least one tag uses it.
+pub fn set_tag_group(name: String, group: Option<String>) -> TagsResult<()> {
+    announce(with_tags_db(|db| db.set_tag_group(&name, group)), TagsChangeKind::TagUpdated, None)
+}
+
+/// Returns the groups currently used by tags, sorted by name.
+pub fn get_tag_groups() -> TagsResult<Vec<String>> {
+    read_tags_db(|db| db.tag_groups())
+}
+
+/// Returns the tags in a group (empty for unknown groups).
+pub fn get_tags_in_group(group: &str) -> TagsResult<Vec<Tag>> {
+    read_tags_db(|db| db.tags_in_group(group.trim()))
+}
+
+/// Sets the order tags are listed in.
+///
+/// # Arguments
+/// * `names_in_order` - Every tag name, each exactly once
+///
+/// # Returns
+/// `TagsResult<()>` - `TagNotFound` for unknown names, `InvalidOrder` for lists with
+/// repeated or missing tags
+pub fn reorder_tags(names_in_order: Vec<String>) -> TagsResult<()> {
+    announce(with_tags_db(|db| db.reorder_tags(&names_in_order)), TagsChangeKind::TagsReordered, None)
+}
+
+/// Finds tags whose names differ only in case, left over from before names were
+/// unique case-insensitively.
+///
+/// # Returns
+/// `TagsResult<Vec<Vec<String>>>` - Groups of two or more conflicting names; empty if
+/// there are none
+pub fn find_conflicting_tags() -> TagsResult<Vec<Vec<String>>> {
+    read_tags_db(|db| db.conflicting_tags())
+}
+
+/// Returns all defined tags.
+///
+/// # Arguments
+/// * `sort` - Order within pinned and unpinned tags
+///
+/// # Returns
+/// `TagsResult<Vec<Tag>>` - List of all tags, pinned tags first
+pub fn get_all_tags(sort: TagSort) -> TagsResult<Vec<Tag>> {
+    let mut tags = load_tags_db()?.tags;
+    match sort {
+        TagSort::Position => {
+            tags.sort_by(|a, b| (!a.pinned, a.sort_index, &a.name).cmp(&(!b.pinned, b.sort_index, &b.name)))
+        }
+        TagSort::Group => tags.sort_by(|a, b| {
+            (!a.pinned, a.group.is_none(), &a.group, a.name.to_lowercase())
+                .cmp(&(!b.pinned, b.group.is_none(), &b.group, b.name.to_lowercase()))
+        }),
+        TagSort::Created => tags.sort_by_cached_key(|tag| {
+            let created = tag
+                .created_at
+                .as_deref()
+                .and_then(|at| DateTime::parse_from_rfc3339(at).ok());
+            (!tag.pinned, created.is_none(), std::cmp::Reverse(created), tag.sort_index, tag.name.clone())
+        }),
+    }
+    Ok(tags)
+}
+
+/// Returns the tags the user added to backups or saves most recently.
+///
+/// # Returns
+/// `TagsResult<Vec<Tag>>` - Up to `MAX_RECENT_TAGS` tags, most recent first
+///
+/// # Behavior
+/// Tags added by auto-tag rules or metadata imports don't count as used.
+pub fn get_recent_tags() -> TagsResult<Vec<Tag>> {
+    read_tags_db(|db| db.recent_tags())
+}
+
+/// Searches tags by name or alias for autocomplete.
+///
+/// # Arguments
+/// * `query` - Text typed so far (case-insensitive)
+/// * `limit` - Maximum number of tags returned
+///
+/// # Returns
+/// `TagsResult<Vec<Tag>>` - Prefix matches, then substring matches, then fuzzy ones;
+/// for a blank query, pinned tags and then the most used ones
+pub fn search_tags(query: &str, limit: usize) -> TagsResult<Vec<Tag>> {
+    read_tags_db(|db| db.search_tags(query, limit))
+}
+
+/// Pins a tag so `get_all_tags` lists it first, or unpins it.
+///
+/// # Arguments
+/// * `name` - Tag name
+/// * `pinned` - Whether the tag is pinned
+pub fn set_tag_pinned(name: String, pinned: bool) -> TagsResult<()> {
+    announce(with_tags_db(|db| db.set_tag_pinned(&name, pinned)), TagsChangeKind::TagUpdated, None)
+}
+
+/// Marks a tag as protected, so retention pruning never deletes backups carrying it.
+///
+/// # Arguments
+/// * `name` - Tag name
+/// * `protected` - Whether the tag's backups are exempt from pruning
+pub fn set_tag_protected(name: String, protected: bool) -> TagsResult<()> {
+    announce(with_tags_db(|db| db.set_tag_protected(&name, protected)), TagsChangeKind::TagUpdated, None)
+}
+
+/// Checks whether retention pruning must keep a backup.
+///
+/// # Arguments
+/// * `save_name` - Save name
+/// * `backup_name` - Backup name
+///
+/// # Returns
+/// `TagsResult<bool>` - True if any tag of the backup is protected
+pub fn is_backup_protected(save_name: &str, backup_name: &str) -> TagsResult<bool> {
+    let target = TagTarget::backup(save_name, backup_name);
+
+    read_tags_db(|db| db.is_protected(&target))
+}
+
+/// Adds tags to a backup.
+///
+/// # Arguments
+/// * `save_name` - Save name
+/// * `backup_name` - Backup name
+/// * `tags` - Tag names to add
+///
+/// # Returns
+/// `TagsResult<()>` - Ok(()) on success
+pub fn add_tags_to_backup(save_name: &str, backup_name: &str, tags: Vec<String>) -> TagsResult<()> {
+    if tags.is_empty() {
+        return Ok(());
+    }
+
+    let target = TagTarget::backup(save_name, backup_name);
+
+    let result = with_tags_db(|db| {
+        db.add_tags_to(&target, tags.clone())?;
+        db.record_recent_tags(&tags);
+        Ok(())
+    });
+    announce(result, TagsChangeKind::TargetTagsChanged, Some(&target))
+}
+
+/// Removes tags from a backup.
+///
+/// # Arguments
+/// * `save_name` - Save name
+/// * `backup_name` - Backup name
+/// * `tags` - Tag names to remove
+///
+/// # Returns
+/// `TagsResult<()>` - Ok(()) on success
+pub fn remove_tags_from_backup(save_name: &str, backup_name: &str, tags: Vec<String>) -> TagsResult<()> {
+    if tags.is_empty() {
+        return Ok(());
+    }
+
+    let target = TagTarget::backup(save_name, backup_name);
+
+    let result = with_tags_db(|db| {
+        db.remove_tags_from(&target, &tags);
+        Ok(())
+    });
+    announce(result, TagsChangeKind::TargetTagsChanged, Some(&target))
+}
+
+/// Replaces all tags of a backup.
+///
+/// # Arguments
+/// * `save_name` - Save name
+/// * `backup_name` - Backup name
+/// * `tags` - The backup's tags, in display order; empty removes them all
+///
+/// # Returns
+/// `TagsResult<()>` - Ok(()) on success, `TagNotFound` if any tag doesn't exist
+///
+/// # Behavior
+/// Writes tags.json once, so an editor can save its checkbox state without diffing it
+/// into add and remove calls. Newly added tags become recent tags.
+pub fn set_backup_tags(save_name: &str, backup_name: &str, tags: Vec<String>) -> TagsResult<()> {
+    set_target_tags(&TagTarget::backup(save_name, backup_name), &tags)
+}
+
+/// Replaces a target's tags and records the added ones as recent.
+fn set_target_tags(target: &TagTarget, tags: &[String]) -> TagsResult<()> {
+    let result = with_tags_db(|db| {
+        let added = db.set_tags_of(target, tags)?;
+        db.record_recent_tags(&added);
+        Ok(())
+    });
+    announce(result, TagsChangeKind::TargetTagsChanged, Some(target))
+}
+
+/// Returns how many backups and saves each tag is applied to.
+///
+/// # Returns
+/// `TagsResult<Vec<TagUsage>>` - One entry per tag, then one per dangling name
+/// (used in associations but not defined), flagged with `dangling`
+pub fn get_tag_usage() -> TagsResult<Vec<TagUsage>> {
+    read_tags_db(|db| db.tag_usage())
+}
+
+/// Suggests a color for a new tag, distinct from the colors of existing tags.
+///
+/// # Returns
+/// `TagsResult<String>` - `#RRGGBB` color to pre-fill the color picker with
+pub fn suggest_tag_color() -> TagsResult<String> {
+    read_tags_db(|db| db.suggest_color())
+}
+
+/// Rows written by `export_tags_csv`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TagsCsvExport {
+    /// Rows in the tag section
+    pub tag_rows: usize,
+    /// Rows in the association section
+    pub association_rows: usize,
+}
+
+/// Writes every tag and association to a CSV file, for spreadsheets.
+///
+/// # Arguments
+/// * `path` - File to write (replaced if it exists)
+///
+/// # Returns
+/// `TagsResult<TagsCsvExport>` - Number of rows in each section
+///
+/// # Behavior
+/// - Two sections, each with its header row, separated by an empty line: one row per
+///   defined tag with its usage counts, then one row per association in stored order
+/// - The tag list of an association is itself a CSV row of names, so names containing
+///   commas or quotes stay unambiguous
+/// - The file is written atomically
+pub fn export_tags_csv(path: PathBuf) -> TagsResult<TagsCsvExport> {
+    let (csv, export) = read_tags_db(render_tags_csv)?;
+    crate::file_ops::write_file_atomic(&path, csv.as_bytes())?;
+    Ok(export)
+}
+
+/// Renders the CSV written by `export_tags_csv`.
+fn render_tags_csv(db: &TagsDatabase) -> (String, TagsCsvExport) {
+    use crate::inventory::{escape_csv_field, push_csv_row};
+
+    let mut csv = String::new();
+    let mut export = TagsCsvExport::default();
+
+    push_csv_row(&mut csv, TAG_CSV_COLUMNS.iter().map(|column| column.to_string()));
+    for usage in db.tag_usage().into_iter().filter(|u| !u.dangling) {
+        let tag = usage.tag;
+        push_csv_row(
+            &mut csv,
+            [
+                tag.name,
+                tag.color,
+                tag.description.unwrap_or_default(),
+                usage.backup_count.to_string(),
+                usage.save_count.to_string(),
+            ],
+        );
+        export.tag_rows += 1;
+    }
+
+    csv.push_str("\r\n");
+    push_csv_row(&mut csv, ASSOCIATION_CSV_COLUMNS.iter().map(|column| column.to_string()));
+    for association in &db.associations {
+        let (kind, save, backup) = match &association.target {
+            TagTarget::Backup { save_name, backup_name } => ("Backup", save_name.to_string(), backup_name.to_string()),
+            TagTarget::Save { relative_path } => ("Save", relative_path.to_string(), String::new()),
+        };
+        let names: Vec<String> = association.tag_names.iter().map(|name| escape_csv_field(name)).collect();
+        push_csv_row(&mut csv, [kind.to_string(), save, backup, names.join(",")]);
+        export.association_rows += 1;
+    }
+
+    (csv, export)
+}
+
+/// Returns tag usage statistics for the dashboard.
+///
+/// # Returns
+/// `TagsResult<TagStatistics>` - Totals, the most used tag and per-tag counts split
+/// by backups and saves, with backup counts per save
+pub fn get_tag_statistics() -> TagsResult<TagStatistics> {
+    read_tags_db(|db| db.tag_statistics())
+}
+
+/// Returns the auto-tag rules, one per trigger at most.
+pub fn get_auto_tag_rules() -> TagsResult<Vec<AutoTagRule>> {
+    read_tags_db(|db| db.auto_tag_rules.clone())
+}
+
+/// Creates or replaces the auto-tag rule of a trigger.
+///
+/// # Arguments
+/// * `trigger` - What starts the backups to tag (e.g., `Scheduled` for OS schedules)
+/// * `tag_names` - Existing tags to add; an empty list removes the rule
+///
+/// # Returns
+/// `TagsResult<()>` - `TagNotFound` if any tag doesn't exist
+pub fn set_auto_tag_rule(trigger: OperationTrigger, tag_names: Vec<String>) -> TagsResult<()> {
+    announce(with_tags_db(|db| db.set_auto_tag_rule(trigger, tag_names)), TagsChangeKind::AutoTagRulesChanged, None)
+}
+
+/// Removes the auto-tag rule of a trigger.
+///
+/// # Returns
+/// `TagsResult<bool>` - true if a rule existed
+pub fn delete_auto_tag_rule(trigger: OperationTrigger) -> TagsResult<bool> {
+    announce(with_tags_db(|db| Ok(db.remove_auto_tag_rule(trigger))), TagsChangeKind::AutoTagRulesChanged, None)
+}
+
+/// Applies several tag changes with one tags.json write, all or nothing.
+///
+/// # Arguments
+/// * `ops` - Changes in the order to apply them; later ones see the effect of earlier
+///   ones (e.g., a tag created first can be added to a target next)
+///
+/// # Returns
+/// `TagsResult<()>` - `BatchOpFailed` with the index of the first failing change and
+/// its error; tags.json is left unchanged then
+pub fn apply_tag_batch(ops: Vec<TagOp>) -> TagsResult<()> {
+    if ops.is_empty() {
+        return Ok(());
+    }
+
+    let result = with_tags_db(|db| {
+        for (index, op) in ops.into_iter().enumerate() {
+            db.apply_op(op)
+                .map_err(|e| TagsError::BatchOpFailed { index, source: Box::new(e) })?;
+        }
+        Ok(())
+    });
+    announce(result, TagsChangeKind::Batch, None)
+}
+
+/// Returns all smart collections, in creation order.
+pub fn get_collections() -> TagsResult<Vec<SmartCollection>> {
+    read_tags_db(|db| db.collections.clone())
+}
+
+/// Creates a smart collection, or replaces the one with the same name.
+///
+/// # Arguments
+/// * `collection` - Name, included and excluded tags, and target filters
+///
+/// # Returns
+/// `TagsResult<()>` - `InvalidName` for a blank name, `TagNotFound` if any tag
+/// doesn't exist
+pub fn save_collection(collection: SmartCollection) -> TagsResult<()> {
+    announce(with_tags_db(|db| db.save_collection(collection)), TagsChangeKind::CollectionsChanged, None)
+}
+
+/// Deletes a smart collection; the tags it refers to are left alone.
+///
+/// # Returns
+/// `TagsResult<bool>` - true if the collection existed
+pub fn delete_collection(name: &str) -> TagsResult<bool> {
+    announce(with_tags_db(|db| Ok(db.remove_collection(name))), TagsChangeKind::CollectionsChanged, None)
+}
+
+/// Lists the backups and saves a smart collection matches.
+///
+/// # Arguments
+/// * `name` - Collection name (case-insensitive)
+///
+/// # Returns
+/// `TagsResult<Vec<TagTarget>>` - Matching targets; `CollectionNotFound` if there is
+/// no such collection
+///
+/// # Behavior
+/// A target must carry every included tag and none of the excluded ones, so an
+/// exclusion wins when a target carries both.
+pub fn evaluate_collection(name: &str) -> TagsResult<Vec<TagTarget>> {
+    read_tags_db(|db| match db.collection(name) {
+        Some(collection) => Ok(db.evaluate_collection(collection)),
+        None => Err(TagsError::CollectionNotFound(name.to_string())),
+    })?
+}
+
+/// Adds the tags of the trigger's auto-tag rule to a target.
+///
+/// # Arguments
+/// * `target` - New backup (or save) to tag
+/// * `trigger` - What started the backup
+///
+/// # Returns
+/// `TagsResult<Vec<String>>` - Tags added; empty if no rule applies
+///
+/// # Behavior
+/// - tags.json is only rewritten if a rule applies
+/// - Tags deleted since the rule was set are skipped with a warning
+pub fn apply_auto_tags(target: &TagTarget, trigger: OperationTrigger) -> TagsResult<Vec<String>> {
+    let (existing, deleted) = read_tags_db(|db| db.auto_tags_for(trigger))?;
+    if !deleted.is_empty() {
+        eprintln!(
+            "[WARN] Auto-tag rule for {:?} backups skips deleted tag(s): {}",
+            trigger,
+            deleted.join(", ")
+        );
+    }
+    if existing.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let result = with_tags_db(|db| {
+        // Re-resolve under the lock in case a tag was deleted meanwhile
+        let (names, _) = db.auto_tags_for(trigger);
+        db.add_tags_to(target, names.clone())?;
+        Ok(names)
+    });
+    announce(result, TagsChangeKind::TargetTagsChanged, Some(target))
+}
+
+/// Applies auto-tag rules to a new backup without failing the backup.
+///
+/// # Behavior
+/// Errors are recorded as suppressed backup failures in the history.
+pub fn apply_auto_tags_best_effort(save_name: &str, backup_name: &str, trigger: OperationTrigger) {
+    if let Err(e) = apply_auto_tags(&TagTarget::backup(save_name, backup_name), trigger) {
+        history::record_suppressed_error(
+            OperationType::Backup,
+            save_name,
+            Some(backup_name),
+            "Failed to apply auto-tag rules",
+            &e,
+        );
+    }
+}
+
+/// Adds a backup's tags to a save, such as the save the backup was just restored to.
+///
+/// # Arguments
+/// * `save_name` - Save the backup belongs to
+/// * `backup_name` - Backup whose tags are copied
+/// * `relative_path` - Save receiving the tags
+///
+/// # Returns
+/// `TagsResult<Vec<String>>` - Tags the save didn't have yet, in the backup's order
+///
+/// # Behavior
+/// - Tags the save already has are kept, and not repeated
+/// - Dangling names on the backup are skipped
+/// - Nothing is written if the save already has every tag
+pub fn copy_tags_from_backup_to_save(
+    save_name: &str,
+    backup_name: &str,
+    relative_path: &str,
+) -> TagsResult<Vec<String>> {
+    let (from, to) = (TagTarget::backup(save_name, backup_name), TagTarget::save(relative_path));
+    let unchanged = read_tags_db(|db| {
+        let existing = db.association(&to).map(|a| a.tag_names.clone()).unwrap_or_default();
+        db.tags_for(&from).iter().all(|t| existing.contains(&t.name))
+    })?;
+    if unchanged {
+        return Ok(Vec::new());
+    }
+    announce(with_tags_db(|db| Ok(db.copy_tags_between(&from, &to))), TagsChangeKind::TargetTagsChanged, Some(&to))
+}
+
+/// Copies a restored backup's tags to its save, if `inherit_tags_on_restore` is set.
+///
+/// # Behavior
+/// Errors are recorded as suppressed restore failures in the history.
+pub fn inherit_tags_on_restore_best_effort(save_name: &str, backup_name: &str) {
+    if !crate::config::load_config().is_ok_and(|c| c.inherit_tags_on_restore) {
+        return;
+    }
+    if let Err(e) = copy_tags_from_backup_to_save(save_name, backup_name, save_name) {
+        history::record_suppressed_error(
+            OperationType::Restore,
+            save_name,
+            Some(backup_name),
+            "Failed to copy backup tags to the restored save",
+            &e,
+        );
+    }
+}
+
+/// Adds tags to many backups with a single load and save of tags.json.
+///
+/// # Arguments
+/// * `targets` - `(save_name, backup_name)` pairs
+/// * `tags` - Tag names to add to every backup
+///
+/// # Returns
+/// `TagsResult<()>` - `TagNotFound` if any tag is unknown, in which case nothing is written
+pub fn add_tags_to_backups(targets: Vec<(String, String)>, tags: Vec<String>) -> TagsResult<()> {
+    if targets.is_empty() || tags.is_empty() {
+        return Ok(());
+    }
+
+    let result = with_tags_db(|db| {
+        if let Some(missing) = tags.iter().find(|tag| !db.has_tag(tag)) {
+            return Err(TagsError::TagNotFound(missing.clone()));
+        }
+        for (save_name, backup_name) in &targets {
+            db.add_tags_to(&TagTarget::backup(save_name, backup_name), tags.clone())?;
+        }
+        db.record_recent_tags(&tags);
+        Ok(())
+    });
+    announce(result, TagsChangeKind::TargetTagsChanged, None)
+}
+
+/// Removes tags from many backups with a single load and save of tags.json.
+///
+/// # Arguments
+/// * `targets` - `(save_name, backup_name)` pairs
+/// * `tags` - Tag names to remove from every backup
+///
+/// # Returns
+/// `TagsResult<()>` - Ok(()) on success; unknown tags and untagged backups are ignored
+pub fn remove_tags_from_backups(targets: Vec<(String, String)>, tags: Vec<String>) -> TagsResult<()> {
+    if targets.is_empty() || tags.is_empty() {
+        return Ok(());
+    }
+
+    let result = with_tags_db(|db| {
+        for (save_name, backup_name) in &targets {
+            db.remove_tags_from(&TagTarget::backup(save_name, backup_name), &tags);
+        }
+        Ok(())
+    });
+    announce(result, TagsChangeKind::TargetTagsChanged, None)
+}
+
+/// Removes tags from every backup of a save with a single load and save of tags.json.
+///
+/// # Arguments
+/// * `save_name` - Save name
+/// * `tags` - Tag names to remove; unknown names are ignored
+///
+/// # Returns
+/// `TagsResult<usize>` - Number of backup associations that were modified, error if
+/// the database can't be read or written
+pub fn remove_tags_from_all_backups_of_save(save_name: &str, tags: Vec<String>) -> TagsResult<usize> {
+    if tags.is_empty() {
+        return Ok(0);
+    }
+
+    let result = with_tags_db(|db| Ok(db.remove_tags_from_save_backups(save_name, &tags)));
+    announce(result, TagsChangeKind::TargetTagsChanged, None)
+}
+
+/// Returns all tags for a backup.
+///
+/// # Arguments
+/// * `save_name` - Save name
+/// * `backup_name` - Backup name
+///
+/// # Returns
+/// `TagsResult<Vec<Tag>>` - List of tags
+pub fn get_backup_tags(save_name: &str, backup_name: &str) -> TagsResult<Vec<Tag>> {
+    let target = TagTarget::backup(save_name, backup_name);
+
+    read_tags_db(|db| db.tags_for(&target))
+}
+
+/// Returns the tags of all backups of a save with one read of the database.
+///
+/// # Arguments
+/// * `save_name` - Save name
+///
+/// # Returns
+/// `TagsResult<HashMap<String, Vec<Tag>>>` - Tags by backup name; backups without
+/// tags are left out
+pub fn get_all_backup_tags_for_save(save_name: &str) -> TagsResult<HashMap<String, Vec<Tag>>> {
+    read_tags_db(|db| db.backup_tags_of_save(save_name))
+}
+
+/// Returns the tags of any mix of backups and saves with one read of the database.
+///
+/// # Arguments
+/// * `targets` - Backups and saves to look up
+///
+/// # Returns
+/// `TagsResult<Vec<(TagTarget, Vec<Tag>)>>` - One entry per target, in the given
+/// order; untagged targets get an empty list
+pub fn get_tags_for_targets(targets: Vec<TagTarget>) -> TagsResult<Vec<(TagTarget, Vec<Tag>)>> {
+    read_tags_db(|db| {
+        targets
+            .into_iter()
+            .map(|target| {
+                let tags = db.tags_for(&target);
+                (target, tags)
+            })
+            .collect()
+    })
+}
+
+/// Finds the backups carrying a tag, across all saves.
+///
+/// # Arguments
+/// * `tag_name` - Tag name
+///
+/// # Returns
+/// `TagsResult<Vec<(String, String)>>` - `(save_name, backup_name)` pairs sorted by save,
+/// then backup; empty if the tag is unused, `TagNotFound` if it doesn't exist
+pub fn find_backups_by_tag(tag_name: &str) -> TagsResult<Vec<(String, String)>> {
+    read_tags_db(|db| {
+        if !db.has_tag(tag_name) {
+            return Err(TagsError::TagNotFound(tag_name.to_string()));
+        }
+        Ok(db.backups_with_tag(tag_name))
+    })?
+}
+
+/// Finds the saves carrying a tag.
+///
+/// # Arguments
+/// * `tag_name` - Tag name
+///
+/// # Returns
+/// `TagsResult<Vec<String>>` - Sorted save relative paths; empty if no save carries the
+/// tag, `TagNotFound` if it doesn't exist
+pub fn find_saves_by_tag(tag_name: &str) -> TagsResult<Vec<String>> {
+    read_tags_db(|db| {
+        if !db.has_tag(tag_name) {
+            return Err(TagsError::TagNotFound(tag_name.to_string()));
+        }
+        Ok(db.saves_with_tag(tag_name))
+    })?
+}
+
+/// Filters existing backups down to those without tags.
+///
+/// # Arguments
+/// * `save_name` - Only keep backups of this save, if given
+/// * `backups` - `(save_name, backup_name)` pairs of the backups on disk
+///
+/// # Returns
+/// `TagsResult<Vec<(String, String)>>` - Candidates without tags, in the given order
+///
+/// # Behavior
+/// The caller lists the backups (see `get_untagged_backups_command`), so tags.rs never
+/// scans the backup directory and associations of deleted backups are never reported.
+pub fn get_untagged_backups(
+    save_name: Option<&str>,
+    backups: Vec<(String, String)>,
+) -> TagsResult<Vec<(String, String)>> {
+    let save_name = save_name.map(SaveKey::new);
+    read_tags_db(|db| {
+        backups
+            .into_iter()
+            .filter(|(save, _)| save_name.as_ref().is_none_or(|key| SaveKey::new(save) == *key))
+            .filter(|(save, backup)| !db.is_tagged(&TagTarget::backup(save, backup)))
+            .collect()
+    })
+}
+
+/// Filters existing saves down to those without tags.
+///
+/// # Arguments
+/// * `saves` - Relative paths of the saves on disk
+///
+/// # Returns
+/// `TagsResult<Vec<String>>` - Candidates without tags, in the given order
+pub fn get_untagged_saves(saves: Vec<String>) -> TagsResult<Vec<String>> {
+    read_tags_db(|db| {
+        saves
+            .into_iter()
+            .filter(|save| !db.is_tagged(&TagTarget::save(save)))
+            .collect()
+    })
+}
+
+/// Moves a backup's tags to its new name after the archive was renamed.
+///
+/// # Arguments
+/// * `save_name` - Save name
+/// * `old_backup_name` - Name the tags are stored under
+/// * `new_backup_name` - New name of the archive
+///
+/// # Returns
+/// `TagsResult<()>` - Ok(()) on success, error if the database can't be read or written
+///
+/// # Behavior
+/// If the new name already has tags (e.g., left over from a deleted backup of that
+/// name), the old name's tags are merged into them. tags.json is only rewritten if
+/// the old name has tags.
+pub fn rename_backup_target(save_name: &str, old_backup_name: &str, new_backup_name: &str) -> TagsResult<()> {
+    move_target_tags(
+        &TagTarget::backup(save_name, old_backup_name),
+        &TagTarget::backup(save_name, new_backup_name),
+    )
+}
+
+/// Moves a save's tags to its new relative path after the folder was renamed or moved.
+///
+/// # Arguments
+/// * `old_relative_path` - Path the tags are stored under
+/// * `new_relative_path` - New relative path of the save
+///
+/// # Returns
+/// `TagsResult<()>` - Ok(()) on success, error if the database can't be read or written
+///
+/// # Behavior
+/// Tags the new path already has are kept and the old path's tags merged into them.
+/// Only the save's own tags move: its backups stay in the backup directory of the
+/// old path, so their tags stay under it too.
+pub fn rename_save_target(old_relative_path: &str, new_relative_path: &str) -> TagsResult<()> {
+    move_target_tags(&TagTarget::save(old_relative_path), &TagTarget::save(new_relative_path))
+}
+
+/// Moves the tags of `old` to `new`; tags.json is only rewritten if `old` has tags.
+fn move_target_tags(old: &TagTarget, new: &TagTarget) -> TagsResult<()> {
+    if !read_tags_db(|db| db.association(old).is_some())? {
+        return Ok(());
+    }
+
+    let result = with_tags_db(|db| {
+        db.rename_target(old, new);
+        Ok(())
+    });
+    announce(result, TagsChangeKind::TargetTagsChanged, Some(new))
+}
+
+/// Drops the tag association of a backup or save.
+///
+/// # Arguments
+/// * `target` - Backup or save whose association is removed
+///
+/// # Returns
+/// `TagsResult<bool>` - True if the target had an association
+///
+/// # Behavior
+/// tags.json is only rewritten if the target has an association.
+pub fn remove_target(target: &TagTarget) -> TagsResult<bool> {
+    if !read_tags_db(|db| db.association(target).is_some())? {
+        return Ok(false);
+    }
+
+    announce(with_tags_db(|db| Ok(db.remove_target(target))), TagsChangeKind::TargetTagsChanged, Some(target))
+}
+
+/// Removes all tags from a backup.
+///
+/// # Arguments
+/// * `save_name` - Save name
+/// * `backup_name` - Backup name
+///
+/// # Returns
+/// `TagsResult<()>` - Ok(()) on success, error if the database can't be read or written
+pub fn clear_backup_tags(save_name: &str, backup_name: &str) -> TagsResult<()> {
+    remove_target(&TagTarget::backup(save_name, backup_name)).map(|_| ())
+}
+
+/// Removes all tags from a deleted backup without failing the deletion.
+///
+/// # Arguments
+/// * `save_name` - Save name
+/// * `backup_name` - Backup name
+///
+/// # Returns
+/// `Option<String>` - Warning for the user if the tags couldn't be removed
+///
+/// # Behavior
+/// Only for use after the backup file is gone. A failure leaves stale tags behind,
+/// so it is logged and recorded in the operation history as suppressed.
+pub fn clear_backup_tags_best_effort(save_name: &str, backup_name: &str) -> Option<String> {
+    let e = clear_backup_tags(save_name, backup_name).err()?;
+    history::record_suppressed_error(
+        OperationType::Delete,
+        save_name,
+        Some(backup_name),
+        "Failed to remove tags of deleted backup",
+        &e,
+    );
+    Some(format!("Backup deleted, but its tags could not be removed: {}", e))
+}
+
+/// Removes all tags from several backups of a save in one write.
+///
+/// # Arguments
+/// * `save_name` - Save name
+/// * `backup_names` - Backup names
+///
+/// # Returns
+/// `TagsResult<()>` - Ok(()) on success, error if the database can't be read or written
+pub fn clear_tags_of_backups(save_name: &str, backup_names: &[String]) -> TagsResult<()> {
+    if backup_names.is_empty() {
+        return Ok(());
+    }
+
+    let result = with_tags_db(|db| {
+        for backup_name in backup_names {
+            db.clear_tags(&TagTarget::backup(save_name, backup_name));
+        }
+        Ok(())
+    });
+    announce(result, TagsChangeKind::TargetTagsChanged, None)
+}
+
+/// Removes all tags from pruned backups without failing the backup that pruned them.
+///
+/// # Behavior
+/// Same policy as `clear_backup_tags_best_effort`; the suppressed error is recorded
+/// as a prune failure.
+pub fn clear_tags_of_backups_best_effort(save_name: &str, backup_names: &[String]) {
+    if let Err(e) = clear_tags_of_backups(save_name, backup_names) {
+        history::record_suppressed_error(
+            OperationType::Prune,
+            save_name,
+            None,
+            &format!("Failed to remove tags of {} pruned backup(s)", backup_names.len()),
+            &e,
+        );
+    }
+}
+
+/// Removes tag associations of backups and saves that no longer exist.
+///
+/// # Arguments
+/// * `existing` - Backups and saves found on disk; unchecked targets are kept
+/// * `confirm` - Allow removing more than `MAX_UNCONFIRMED_ORPHANS` associations
+///
+/// # Returns
+/// `TagsResult<OrphanReport>` - What was removed, or with `needs_confirmation` set,
+/// what would be removed
+///
+/// # Behavior
+/// A large removal more likely means the listing is wrong (e.g., a drive that
+/// isn't mounted) than that many files were deleted, so it waits for `confirm`.
+/// tags.json is only rewritten if something is removed.
+pub fn prune_orphaned_associations(existing: &ExistingTargets, confirm: bool) -> TagsResult<OrphanReport> {
+    let mut report = read_tags_db(|db| db.orphaned_associations(existing))?;
+    if report.is_empty() {
+        return Ok(report);
+    }
+    if report.len() > MAX_UNCONFIRMED_ORPHANS && !confirm {
+        report.needs_confirmation = true;
+        return Ok(report);
+    }
+
+    announce(with_tags_db(|db| Ok(db.remove_orphaned_associations(existing))), TagsChangeKind::Cleanup, None)
+}
+
+/// Lists the backups and saves that tag associations refer to, as found on disk.
+///
+/// # Behavior
+/// - Nothing is listed under a backup or save root that isn't a directory, so an
+///   unmounted drive or a missing configured path orphans nothing
+/// - A save's backups are only listed if its backup directory can be read; a
+///   missing directory lists none (the save's backups were all deleted)
+/// - A save exists if its folder does, whether or not it still looks like a save
+pub fn find_existing_targets() -> TagsResult<ExistingTargets> {
+    let (save_names, tagged_saves) = read_tags_db(|db| {
+        let mut save_names = BTreeSet::new();
+        let mut tagged_saves = Vec::new();
+        // Trashed tags are restored only to targets that still exist
+        let trashed = db.trash.iter().flat_map(|deleted| &deleted.targets);
+        for target in db.associations.iter().map(|a| &a.target).chain(trashed) {
+            match target {
+                TagTarget::Backup { save_name, .. } => {
+                    save_names.insert(save_name.clone());
+                }
+                TagTarget::Save { relative_path } => tagged_saves.push(relative_path.clone()),
+            }
+        }
+        (save_names, tagged_saves)
+    })?;
+
+    let config = crate::config::load_config()?;
+    let mut existing = ExistingTargets::default();
+
+    let backup_base_path = config.get_backup_path()?;
+    if backup_base_path.is_dir() {
+        for save_name in save_names {
+            // A stored name outside the backup directory is left alone, like an unreadable one
+            let Ok(dir) = crate::backup::get_save_backup_dir(&backup_base_path, save_name.as_str()) else {
+                continue;
+            };
+            if let Some(names) = list_backup_file_names(&dir) {
+                existing.backups.insert(save_name, names);
+            }
+        }
+    }
+
+    let save_path = config.get_save_path()?;
+    if save_path.is_dir() {
+        existing.saves = Some(
+            tagged_saves
+                .into_iter()
+                .filter(|save| save_path.join(save.as_str()).is_dir())
+                .collect(),
+        );
+    }
+
+    Ok(existing)
+}
+
+/// Reads the `.tar.gz` file names in a backup directory; None if it can't be read.
+fn list_backup_file_names(dir: &Path) -> Option<HashSet<BackupKey>> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Some(HashSet::new()),
+        Err(_) => return None,
+    };
+
+    let mut names = HashSet::new();
+    for entry in entries {
+        let name = entry.ok()?.file_name();
+        let name = name.to_string_lossy();
+        if name.ends_with(".tar.gz") {
+            names.insert(BackupKey::new(&name));
+        }
+    }
+    Some(names)
+}
+
+/// Removes tag associations of deleted backups and saves, checking the disk first.
+///
+/// # Arguments
+/// * `confirm` - Allow removing more than `MAX_UNCONFIRMED_ORPHANS` associations
+///
+/// # Returns
+/// `TagsResult<OrphanReport>` - See `prune_orphaned_associations`
+pub fn prune_orphaned_tags(confirm: bool) -> TagsResult<OrphanReport> {
+    prune_orphaned_associations(&find_existing_targets()?, confirm)
+}
+
+/// Removes tag associations of a save's deleted backups after listing its backups.
+///
+/// # Arguments
+/// * `save_name` - Save whose backups were listed
+/// * `backup_names` - Names of the listed backups
+///
+/// # Behavior
+/// Never confirms a large removal and skips the check entirely if the backup root
+/// isn't a directory. Failures are logged; listing must not fail because of them.
+pub fn prune_orphaned_backup_tags_best_effort<'a>(
+    save_name: &str,
+    backup_names: impl IntoIterator<Item = &'a str>,
+) {
+    let backup_root_available = crate::config::load_config()
+        .ok()
+        .and_then(|config| config.get_backup_path().ok())
+        .is_some_and(|path| path.is_dir());
+    if !backup_root_available {
+        return;
+    }
+
+    let mut existing = ExistingTargets::default();
+    existing.backups.insert(
+        SaveKey::new(save_name),
+        backup_names.into_iter().map(BackupKey::new).collect(),
+    );
+    match prune_orphaned_associations(&existing, false) {
+        Ok(report) if report.needs_confirmation => eprintln!(
+            "[WARN] {} backup(s) of {} have tags but no file; not removing them without confirmation",
+            report.len(),
+            save_name
+        ),
+        Ok(_) => {}
+        Err(e) => eprintln!("[WARN] Failed to prune tags of deleted backups of {}: {}", save_name, e),
+    }
+}
+
+/// Adds tags to a save.
+///
+/// # Arguments
+/// * `relative_path` - Save relative path
+/// * `tags` - Tag names to add
+///
+/// # Returns
+/// `TagsResult<()>` - Ok(()) on success
+pub fn add_tags_to_save(relative_path: &str, tags: Vec<String>) -> TagsResult<()> {
+    if tags.is_empty() {
+        return Ok(());
+    }
+
+    let target = TagTarget::save(relative_path);
+
+    let result = with_tags_db(|db| {
+        db.add_tags_to(&target, tags.clone())?;
+        db.record_recent_tags(&tags);
+        Ok(())
+    });
+    announce(result, TagsChangeKind::TargetTagsChanged, Some(&target))
+}
+
+/// Removes tags from a save.
+///
+/// # Arguments
+/// * `relative_path` - Save relative path
+/// * `tags` - Tag names to remove
+///
+/// # Returns
+/// `TagsResult<()>` - Ok(()) on success
+pub fn remove_tags_from_save(relative_path: &str, tags: Vec<String>) -> TagsResult<()> {
+    if tags.is_empty() {
+        return Ok(());
+    }
+
+    let target = TagTarget::save(relative_path);
+
+    let result = with_tags_db(|db| {
+        db.remove_tags_from(&target, &tags);
+        Ok(())
+    });
+    announce(result, TagsChangeKind::TargetTagsChanged, Some(&target))
+}
+
+/// Replaces all tags of a save.
+///
+/// # Arguments
+/// * `relative_path` - Save relative path
+/// * `tags` - The save's tags, in display order; empty removes them all
+///
+/// # Returns
+/// `TagsResult<()>` - Ok(()) on success, `TagNotFound` if any tag doesn't exist
+///
+/// # Behavior
+/// Same as `set_backup_tags`, for a save.
+pub fn set_save_tags(relative_path: &str, tags: Vec<String>) -> TagsResult<()> {
+    set_target_tags(&TagTarget::save(relative_path), &tags)
+}
+
+/// Returns all tags for a save.
+///
+/// # Arguments
+/// * `relative_path` - Save relative path
+///
+/// # Returns
+/// `TagsResult<Vec<Tag>>` - List of tags
+pub fn get_save_tags(relative_path: &str) -> TagsResult<Vec<Tag>> {
+    let target = TagTarget::save(relative_path);
+
+    read_tags_db(|db| db.tags_for(&target))
+}
+
+/// Returns the tags of all saves with one read of the database.
+///
+/// # Returns
+/// `TagsResult<HashMap<String, Vec<Tag>>>` - Tags by save relative path; saves without
+/// tags are left out
+pub fn get_all_save_tags() -> TagsResult<HashMap<String, Vec<Tag>>> {
+    read_tags_db(|db| db.all_save_tags())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TestEnv;
+
+    #[test]
+    fn test_validate_color_valid() {
+        assert!(validate_color("#FF5733").is_ok());
+        assert!(validate_color("#F53").is_ok());
+        assert!(validate_color("#FF5733AA").is_ok());
+        assert!(validate_color("#abc").is_ok());
+    }
+
+    #[test]
+    fn test_normalize_color() {
+        let cases = [
+            ("#FF5733", Ok("#FF5733")),
+            (" #abc ", Ok("#abc")),
+            ("red", Ok("#FF0000")),
+            ("DarkSlateGrey", Ok("#2F4F4F")),
+            ("rgb(255, 87, 51)", Ok("#FF5733")),
+            ("rgba(255, 87, 51, 0.5)", Ok("#FF573380")),
+            ("reddish", Err("reddish")),
+            ("rgb(300, 0, 0)", Err("rgb(300, 0, 0)")),
+            (" #GG5733", Err(" #GG5733")),
+        ];
+        for (input, expected) in cases {
+            match (normalize_color(input), expected) {
+                (Ok(color), Ok(hex)) => assert_eq!(color, hex, "{}", input),
+                (Err(TagsError::InvalidColor(original)), Err(raw)) => assert_eq!(original, raw),
+                (result, _) => panic!("{:?} -> {:?}", input, result),
+            }
+        }
+    }
+
+    #[test]
+    fn test_named_and_rgb_colors_are_stored_as_hex() {
+        let _env = TestEnv::new();
+        create_tag("named".to_string(), "tomato".to_string(), None, None).unwrap();
+        create_tag("picked".to_string(), "rgb(255, 87, 51)".to_string(), None, None).unwrap();
+        update_tag_color("named".to_string(), "rgba(0, 0, 255, 1)".to_string()).unwrap();
+
+        let tags = get_all_tags(TagSort::Position).unwrap();
+        assert_eq!(tags[0].color, "#0000FF");
+        assert_eq!(tags[1].color, "#FF5733");
+        assert!(matches!(
+            update_tag_color("named".to_string(), "not a color".to_string()),
+            Err(TagsError::InvalidColor(c)) if c == "not a color"
+        ));
+    }
+
+    #[test]
+    fn test_validate_color_invalid() {
+        assert!(validate_color("FF5733").is_err()); // Missing #
+        assert!(validate_color("#FF57").is_err()); // Invalid length
+        assert!(validate_color("#FF57333").is_err()); // Invalid length
+        assert!(validate_color("#GG5733").is_err()); // Invalid hex
+    }
+
+    #[test]
+    fn test_tags_database_default() {
+        let db = TagsDatabase::default();
+        assert_eq!(db.tags.len(), 0);
+        assert_eq!(db.associations.len(), 0);
+    }
+
+    #[test]
+    fn test_create_and_get_tag() {
+        let _env = TestEnv::new();
+        let result = create_tag("important".to_string(), "#FF0000".to_string(), None, None);
+        assert!(result.is_ok());
+
+        let tags = get_all_tags(TagSort::Position).unwrap();
+        assert_eq!(tags.len(), 1);
+        assert_eq!(tags[0].name, "important");
+        assert_eq!(tags[0].color, "#FF0000");
+    }
+
+    #[test]
+    fn test_create_duplicate_tag_fails() {
+        let _env = TestEnv::new();
+        let _ = create_tag("test".to_string(), "#FF0000".to_string(), None, None);
+        let result = create_tag("test".to_string(), "#00FF00".to_string(), None, None);
+        assert!(matches!(result, Err(TagsError::DuplicateTag(_))));
+    }
+
+    #[test]
+    fn test_delete_tag() {
+        let _env = TestEnv::new();
+        let _ = create_tag("to_delete".to_string(), "#FF0000".to_string(), None, None);
+        assert_eq!(get_all_tags(TagSort::Position).unwrap().len(), 1);
+
+        let result = delete_tag("to_delete".to_string());
+        assert!(result.is_ok());
+        assert_eq!(get_all_tags(TagSort::Position).unwrap().len(), 0);
+    }
+
+    #[test]
+    fn test_delete_nonexistent_tag_fails() {
+        let _env = TestEnv::new();
+        let result = delete_tag("nonexistent".to_string());
+        assert!(matches!(result, Err(TagsError::TagNotFound(_))));
+    }
+
+    /// Test clock reading a shared, manually advanced time.
+    struct SharedClock(std::rc::Rc<std::cell::Cell<DateTime<Utc>>>);
+
+    impl Clock for SharedClock {
+        fn now(&self) -> DateTime<Utc> {
+            self.0.get()
+        }
+    }
+
+    #[test]
+    fn test_timestamps_use_injected_clock() {
+        let _env = TestEnv::new();
+        let at = |s: &str| DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc);
+        let time = std::rc::Rc::new(std::cell::Cell::new(at("2024-01-01T10:00:00Z")));
+        let previous = test_clock::set(Some(Box::new(SharedClock(time.clone()))));
+        let stamps = |created: &str, updated: &str| (Some(created.to_string()), Some(updated.to_string()));
+
+        create_tag("old".to_string(), "#FF0000".to_string(), None, None).unwrap();
+        time.set(at("2024-01-02T10:00:00Z"));
+        create_tag("new".to_string(), "#00FF00".to_string(), None, None).unwrap();
+        time.set(at("2024-01-03T10:00:00Z"));
+        add_tags_to_backup("Survival/A", "1.tar.gz", vec!["old".to_string(), "new".to_string()]).unwrap();
+        time.set(at("2024-01-04T10:00:00Z"));
+        remove_tags_from_backup("Survival/A", "1.tar.gz", vec!["new".to_string()]).unwrap();
+        time.set(at("2024-01-05T10:00:00Z"));
+        rename_tag("old".to_string(), "renamed".to_string()).unwrap();
+        test_clock::set(previous);
+
+        let db = load_tags_db().unwrap();
+        let tag = db.tag("renamed").unwrap();
+        assert_eq!((tag.created_at.clone(), tag.updated_at.clone()), stamps("2024-01-01T10:00:00Z", "2024-01-05T10:00:00Z"));
+        let tag = db.tag("new").unwrap();
+        assert_eq!((tag.created_at.clone(), tag.updated_at.clone()), stamps("2024-01-02T10:00:00Z", "2024-01-02T10:00:00Z"));
+        let association = &db.associations[0];
+        assert_eq!(association.tag_names, ["renamed"]);
+        assert_eq!((association.created_at.clone(), association.updated_at.clone()), stamps("2024-01-03T10:00:00Z", "2024-01-05T10:00:00Z"));
+
+        // Newest first when sorting by creation; tags from older files, without timestamps, come last
+        let mut db = db;
+        db.add_tag(Tag { name: "legacy".to_string(), color: "#0000FF".to_string(), description: None, icon: None, group: None, aliases: Vec::new(), pinned: false, protected: false, sort_index: 0, created_at: None, updated_at: None });
+        save_tags_db(&db).unwrap();
+        let names: Vec<String> = get_all_tags(TagSort::Created).unwrap().into_iter().map(|t| t.name).collect();
+        assert_eq!(names, ["new", "renamed", "legacy"]);
+
+        let legacy: Tag = serde_json::from_str(r##"{"name":"x","color":"#FFFFFF"}"##).unwrap();
+        assert_eq!((legacy.created_at, legacy.updated_at), (None, None));
+    }
+
+    #[test]
+    fn test_rename_tag_cascades_to_associations() {
+        let _env = TestEnv::new();
+        create_tag("keep".to_string(), "#FF0000".to_string(), None, None).unwrap();
+        create_tag("other".to_string(), "#00FF00".to_string(), None, None).unwrap();
+        add_tags_to_backup("Survival/A", "1.tar.gz", vec!["other".to_string(), "keep".to_string()]).unwrap();
+        add_tags_to_backup("Survival/B", "2.tar.gz", vec!["keep".to_string()]).unwrap();
+        add_tags_to_save("Survival/A", vec!["keep".to_string()]).unwrap();
+
+        rename_tag("keep".to_string(), "milestone".to_string()).unwrap();
+
+        let names = |tags: Vec<Tag>| tags.into_iter().map(|t| t.name).collect::<Vec<_>>();
+        assert_eq!(names(get_backup_tags("Survival/A", "1.tar.gz").unwrap()), ["other", "milestone"]);
+        assert_eq!(names(get_backup_tags("Survival/B", "2.tar.gz").unwrap()), ["milestone"]);
+        assert_eq!(names(get_save_tags("Survival/A").unwrap()), ["milestone"]);
+
+        let all = get_all_tags(TagSort::Position).unwrap();
+        assert_eq!(all[0], Tag { name: "milestone".to_string(), color: "#FF0000".to_string(), description: None, icon: None, group: None, aliases: Vec::new(), pinned: false, protected: false, sort_index: 0, created_at: all[0].created_at.clone(), updated_at: all[0].updated_at.clone() });
+        let db = load_tags_db().unwrap();
+        assert!(db.associations.iter().all(|a| !a.tag_names.contains(&"keep".to_string())));
+    }
+
+    #[test]
+    fn test_rename_tag_errors() {
+        let _env = TestEnv::new();
+        create_tag("keep".to_string(), "#FF0000".to_string(), None, None).unwrap();
+        create_tag("other".to_string(), "#00FF00".to_string(), None, None).unwrap();
+        add_tags_to_save("Survival/A", vec!["keep".to_string()]).unwrap();
+
+        assert!(matches!(
+            rename_tag("missing".to_string(), "new".to_string()),
+            Err(TagsError::TagNotFound(name)) if name == "missing"
+        ));
+        assert!(matches!(
+            rename_tag("keep".to_string(), "other".to_string()),
+            Err(TagsError::DuplicateTag(name)) if name == "other"
+        ));
+
+        // Failed renames change nothing
+        assert_eq!(get_save_tags("Survival/A").unwrap()[0].name, "keep");
+        assert_eq!(get_all_tags(TagSort::Position).unwrap().len(), 2);
+
+        // Renaming to the same name is a no-op
+        rename_tag("keep".to_string(), "keep".to_string()).unwrap();
+        assert_eq!(get_save_tags("Survival/A").unwrap()[0].name, "keep");
+    }
+
+    #[test]
+    fn test_rename_tag_merges_stale_entries() {
+        let mut db = TagsDatabase::default();
+        db.create_tag("keep".to_string(), "#FF0000".to_string(), None, None).unwrap();
+        let target = TagTarget::save("Survival/A");
+        // "new" is left over from a tag deleted outside the app
+        db.associations.push(TagAssociation {
+            target: target.clone(),
+            tag_names: vec!["keep".to_string(), "new".to_string()],
+            created_at: None,
+            updated_at: None,
+        });
+        db.rebuild_indices();
+
+        db.rename_tag("keep", "new".to_string()).unwrap();
+        assert_eq!(db.association(&target).unwrap().tag_names, ["new"]);
+    }
+
+    #[test]
+    fn test_update_tag_color() {
+        let _env = TestEnv::new();
+        create_tag("keep".to_string(), "#FF0000".to_string(), None, None).unwrap();
+        add_tags_to_backup("Survival/A", "1.tar.gz", vec!["keep".to_string()]).unwrap();
+        add_tags_to_save("Survival/A", vec!["keep".to_string()]).unwrap();
+
+        update_tag_color("keep".to_string(), "#00ff00".to_string()).unwrap();
+        assert_eq!(get_backup_tags("Survival/A", "1.tar.gz").unwrap()[0].color, "#00ff00");
+        assert_eq!(get_save_tags("Survival/A").unwrap()[0].color, "#00ff00");
+        assert_eq!(get_all_tags(TagSort::Position).unwrap()[0].color, "#00ff00");
+
+        assert!(matches!(
+            update_tag_color("missing".to_string(), "#000".to_string()),
+            Err(TagsError::TagNotFound(_))
+        ));
+        assert!(matches!(
+            update_tag_color("keep".to_string(), "greenish".to_string()),
+            Err(TagsError::InvalidColor(_))
+        ));
+        assert_eq!(get_all_tags(TagSort::Position).unwrap()[0].color, "#00ff00");
+    }
+
+    #[test]
+    fn test_add_and_get_backup_tags() {
+        let _env = TestEnv::new();
+        let _ = create_tag("important".to_string(), "#FF0000".to_string(), None, None);
+        let _ = create_tag("test".to_string(), "#00FF00".to_string(), None, None);
+
+        let result = add_tags_to_backup("Survival", "backup1.tar.gz", vec![
+            "important".to_string(),
+            "test".to_string(),
+        ]);
+        assert!(result.is_ok());
+
+        let tags = get_backup_tags("Survival", "backup1.tar.gz").unwrap();
+        assert_eq!(tags.len(), 2);
+    }
+
+    #[test]
+    fn test_add_and_get_save_tags() {
+        let _env = TestEnv::new();
+        let _ = create_tag("main".to_string(), "#0000FF".to_string(), None, None);
+
+        let result = add_tags_to_save("Survival/MySave", vec!["main".to_string()]);
+        assert!(result.is_ok());
+
+        let tags = get_save_tags("Survival/MySave").unwrap();
+        assert_eq!(tags.len(), 1);
+        assert_eq!(tags[0].name, "main");
+    }
+
+    #[test]
+    fn test_remove_backup_tags() {
+        let _env = TestEnv::new();
+        let _ = create_tag("tag1".to_string(), "#FF0000".to_string(), None, None);
+        let _ = create_tag("tag2".to_string(), "#00FF00".to_string(), None, None);
+
+        let _ = add_tags_to_backup("Survival", "backup1.tar.gz", vec![
+            "tag1".to_string(),
+            "tag2".to_string(),
+        ]);
+
+        let result = remove_tags_from_backup("Survival", "backup1.tar.gz", vec!["tag1".to_string()]);
+        assert!(result.is_ok());
+
+        let tags = get_backup_tags("Survival", "backup1.tar.gz").unwrap();
+        assert_eq!(tags.len(), 1);
+        assert_eq!(tags[0].name, "tag2");
+    }
+
+    #[test]
+    fn test_remove_save_tags() {
+        let _env = TestEnv::new();
+        let _ = create_tag("tag1".to_string(), "#FF0000".to_string(), None, None);
+        let _ = create_tag("tag2".to_string(), "#00FF00".to_string(), None, None);
+
+        let _ = add_tags_to_save("Survival/MySave", vec![
+            "tag1".to_string(),
+            "tag2".to_string(),
+        ]);
+
+        let result = remove_tags_from_save("Survival/MySave", vec!["tag1".to_string()]);
+        assert!(result.is_ok());
+
+        let tags = get_save_tags("Survival/MySave").unwrap();
+        assert_eq!(tags.len(), 1);
+        assert_eq!(tags[0].name, "tag2");
+    }
+
+    /// Replaces tags.json with invalid JSON.
+    fn corrupt_tags_db() {
+        std::fs::write(get_tags_db_path().unwrap(), "{ not json").unwrap();
+    }
+
+    #[test]
+    fn test_removal_fails_on_corrupted_db() {
+        let _env = TestEnv::new();
+        corrupt_tags_db();
+
+        let backup = remove_tags_from_backup("Survival", "backup1.tar.gz", vec!["tag1".to_string()]);
+        let save = remove_tags_from_save("Survival/MySave", vec!["tag1".to_string()]);
+        let clear = clear_backup_tags("Survival", "backup1.tar.gz");
+
+        // The corrupted file is left untouched for the user to inspect
+        let contents = std::fs::read_to_string(get_tags_db_path().unwrap()).unwrap();
+
+        assert!(matches!(backup, Err(TagsError::Json(_))), "{:?}", backup);
+        assert!(matches!(save, Err(TagsError::Json(_))), "{:?}", save);
+        assert!(matches!(clear, Err(TagsError::Json(_))), "{:?}", clear);
+        assert_eq!(contents, "{ not json");
+    }
+
+    /// In-memory store counting how often the database is written.
+    #[derive(Default)]
+    struct CountingStore {
+        db: std::cell::RefCell<TagsDatabase>,
+        writes: std::cell::Cell<usize>,
+    }
+
+    impl TagsStore for CountingStore {
+        fn transact<R>(&self, f: impl FnOnce(&mut TagsDatabase) -> TagsResult<R>) -> TagsResult<R> {
+            let mut db = self.db.borrow().clone();
+            let result = f(&mut db)?;
+            *self.db.borrow_mut() = db;
+            self.writes.set(self.writes.get() + 1);
+            Ok(result)
+        }
+    }
+
+    #[test]
+    fn test_batch_mutations_write_once() {
+        let store = CountingStore::default();
+
+        with_tags_db_in(&store, |db| {
+            db.create_tag("keep".to_string(), "#FF0000".to_string(), None, None)?;
+            db.create_tag("old".to_string(), "#00FF00".to_string(), None, None)?;
+            for i in 0..40 {
+                let target = TagTarget::backup("Survival/MySave", &format!("backup{}.tar.gz", i));
+                db.add_tags_to(&target, vec!["keep".to_string(), "old".to_string()])?;
+            }
+            for i in 0..20 {
+                db.clear_tags(&TagTarget::backup("Survival/MySave", &format!("backup{}.tar.gz", i)));
+            }
+            db.delete_tag("old")
+        })
+        .unwrap();
+
+        assert_eq!(store.writes.get(), 1);
+        let db = store.db.borrow();
+        assert_eq!(db.tags.len(), 1);
+        assert_eq!(db.associations.len(), 20);
+        assert!(db.associations.iter().all(|a| a.tag_names == vec!["keep".to_string()]));
+    }
+
+    #[test]
+    fn test_failed_batch_writes_nothing() {
+        let store = CountingStore::default();
+
+        let result = with_tags_db_in(&store, |db| {
+            db.create_tag("a".to_string(), "#FF0000".to_string(), None, None)?;
+            db.add_tags_to(&TagTarget::save("Survival/MySave"), vec!["missing".to_string()])
+        });
+
+        assert!(matches!(result, Err(TagsError::TagNotFound(_))));
+        assert_eq!(store.writes.get(), 0);
+        assert!(store.db.borrow().tags.is_empty());
+    }
+
+    #[test]
+    fn test_clear_tags_of_backups() {
+        let _env = TestEnv::new();
+        create_tag("tag1".to_string(), "#FF0000".to_string(), None, None).unwrap();
+        for name in ["a.tar.gz", "b.tar.gz", "c.tar.gz"] {
+            add_tags_to_backup("Survival", name, vec!["tag1".to_string()]).unwrap();
+        }
+
+        clear_tags_of_backups("Survival", &["a.tar.gz".to_string(), "b.tar.gz".to_string()]).unwrap();
+
+        assert!(get_backup_tags("Survival", "a.tar.gz").unwrap().is_empty());
+        assert!(get_backup_tags("Survival", "b.tar.gz").unwrap().is_empty());
+        assert_eq!(get_backup_tags("Survival", "c.tar.gz").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_remove_tags_from_all_backups_of_save() {
+        let _env = TestEnv::new();
+        create_tag("main".to_string(), "#FF0000".to_string(), None, None).unwrap();
+        create_tag("keep".to_string(), "#00FF00".to_string(), None, None).unwrap();
+        add_tags_to_backup("Survival/MySave", "a.tar.gz", vec!["main".to_string()]).unwrap();
+        add_tags_to_backup("Survival/MySave", "b.tar.gz", vec!["main".to_string(), "keep".to_string()]).unwrap();
+        add_tags_to_backup("Survival/MySave", "c.tar.gz", vec!["keep".to_string()]).unwrap();
+        add_tags_to_backup("Survival/Other", "a.tar.gz", vec!["main".to_string()]).unwrap();
+        add_tags_to_save("Survival/MySave", vec!["main".to_string()]).unwrap();
+
+        let modified = remove_tags_from_all_backups_of_save(
+            "Survival\\MySave",
+            vec!["MAIN".to_string(), "missing".to_string()],
+        )
+        .unwrap();
+
+        assert_eq!(modified, 2);
+        let db = load_tags_db().unwrap();
+        assert!(db.association(&TagTarget::backup("Survival/MySave", "a.tar.gz")).is_none());
+        assert_eq!(db.association(&TagTarget::backup("Survival/MySave", "b.tar.gz")).unwrap().tag_names, vec!["keep"]);
+        assert_eq!(db.association(&TagTarget::backup("Survival/Other", "a.tar.gz")).unwrap().tag_names, vec!["main"]);
+        assert_eq!(db.association(&TagTarget::save("Survival/MySave")).unwrap().tag_names, vec!["main"]);
+        assert_eq!(remove_tags_from_all_backups_of_save("Survival/MySave", vec!["main".to_string()]).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_get_untagged_backups_and_saves() {
+        let _env = TestEnv::new();
+        create_tag("keep".to_string(), "#FF0000".to_string(), None, None).unwrap();
+        add_tags_to_backup("Survival/MySave", "a.tar.gz", vec!["keep".to_string()]).unwrap();
+        add_tags_to_backup("Survival/Gone", "a.tar.gz", vec!["keep".to_string()]).unwrap();
+        add_tags_to_save("Survival/MySave", vec!["keep".to_string()]).unwrap();
+
+        let backups = vec![
+            ("Survival/MySave".to_string(), "a.tar.gz".to_string()),
+            ("Survival/MySave".to_string(), "b.tar.gz".to_string()),
+            ("Survival/Other".to_string(), "a.tar.gz".to_string()),
+        ];
+        assert_eq!(
+            get_untagged_backups(None, backups.clone()).unwrap(),
+            vec![backups[1].clone(), backups[2].clone()]
+        );
+        assert_eq!(
+            get_untagged_backups(Some("Survival\\MySave"), backups.clone()).unwrap(),
+            vec![backups[1].clone()]
+        );
+
+        let saves = vec!["Survival/MySave".to_string(), "Survival/Other".to_string()];
+        assert_eq!(get_untagged_saves(saves).unwrap(), vec!["Survival/Other"]);
+    }
+
+    #[test]
+    fn test_best_effort_clear_records_suppressed_error() {
+        let _env = TestEnv::new();
+        let save_name = "Survival/BestEffortSave";
+        let filter = history::HistoryFilter {
+            save_name: Some(save_name.to_string()),
+            outcome: Some(history::OperationOutcome::Suppressed),
+            ..Default::default()
+        };
+
+        corrupt_tags_db();
+        let warning = clear_backup_tags_best_effort(save_name, "backup1.tar.gz");
+        assert!(warning.unwrap().contains("tags could not be removed"));
+
+        let page = history::get_operation_history(&filter, 0, 1).unwrap();
+        assert_eq!(page.total, 1);
+        let record = &page.records[0];
+        assert_eq!(record.operation, OperationType::Delete);
+        assert_eq!(record.backup_name.as_deref(), Some("backup1.tar.gz"));
+        assert!(record.message.as_ref().unwrap().contains("Failed to remove tags"));
+    }
+
+    #[test]
+    fn test_clear_backup_tags() {
+        let _env = TestEnv::new();
+        let _ = create_tag("tag1".to_string(), "#FF0000".to_string(), None, None);
+        let _ = add_tags_to_backup("Survival", "backup1.tar.gz", vec!["tag1".to_string()]);
+
+        clear_backup_tags("Survival", "backup1.tar.gz").unwrap();
+        assert!(get_backup_tags("Survival", "backup1.tar.gz").unwrap().is_empty());
+        assert!(load_tags_db().unwrap().associations.is_empty());
+        assert!(find_backups_by_tag("tag1").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_remove_target() {
+        let _env = TestEnv::new();
+        let target = TagTarget::backup("Survival", "backup1.tar.gz");
+        assert!(!remove_target(&target).unwrap());
+        // No association means no write
+        assert!(!get_tags_db_path().unwrap().exists());
+
+        create_tag("tag1".to_string(), "#FF0000".to_string(), None, None).unwrap();
+        add_tags_to_backup("Survival", "backup1.tar.gz", vec!["tag1".to_string()]).unwrap();
+        add_tags_to_save("Survival", vec!["tag1".to_string()]).unwrap();
+        // Names of deleted tags are dropped too
+        with_tags_db(|db| {
+            db.association_or_insert(&target).tag_names.push("gone".to_string());
+            Ok(())
+        })
+        .unwrap();
+
+        assert!(remove_target(&target).unwrap());
+        assert!(load_tags_db().unwrap().association(&target).is_none());
+        assert_eq!(get_save_tags("Survival").unwrap().len(), 1);
+        assert!(!remove_target(&target).unwrap());
+    }
+
+    #[test]
+    fn test_tag_serialization() {
+        let _env = TestEnv::new();
+        let tag = Tag {
+            name: "test".to_string(),
+            color: "#FF5733".to_string(),
+            description: Some("before mod update".to_string()),
+            icon: Some("🚁".to_string()),
+            group: Some("Milestones".to_string()),
+            aliases: vec!["pre-mod".to_string()],
+            pinned: true,
+            protected: true,
+            sort_index: 3,
+            created_at: Some("2024-12-28T14:30:45Z".to_string()),
+            updated_at: Some("2024-12-29T09:00:00Z".to_string()),
+        };
+
+        let json = serde_json::to_string(&tag).unwrap();
+        let parsed: Tag = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(parsed.name, "test");
+        assert_eq!(parsed.color, "#FF5733");
+        assert_eq!(parsed, tag);
+    }
+
+    #[test]
+    fn test_text_color_threshold() {
+        // Black has the higher contrast once relative luminance exceeds about 0.179
+        assert_eq!(text_color("#757575"), "#FFFFFF");
+        assert_eq!(text_color("#767676"), "#000000");
+        assert_eq!(text_color("#FFFF00"), "#000000");
+        assert_eq!(text_color("#0000FF"), "#FFFFFF");
+        // Green counts far more than blue at the same value
+        assert_eq!(text_color("#00A000"), "#000000");
+        assert_eq!(text_color("#A000FF"), "#FFFFFF");
+
+        // Short and alpha forms match their six-digit equivalents
+        assert_eq!(text_color("#FF0"), text_color("#FFFF00"));
+        assert_eq!(text_color("#777"), text_color("#777777"));
+        assert_eq!(text_color("#76767600"), "#000000");
+        assert_eq!(text_color(" #757575FF "), "#FFFFFF");
+        assert_eq!(text_color("not a color"), "#FFFFFF");
+    }
+
+    #[test]
+    fn test_text_color_is_serialized_but_not_stored() {
+        let env = TestEnv::new();
+        create_tag("light".to_string(), "#FFFF00".to_string(), None, None).unwrap();
+        add_tags_to_save("Survival/A", vec!["light".to_string()]).unwrap();
+
+        let listed = serde_json::to_value(get_save_tags("Survival/A").unwrap()).unwrap();
+        assert_eq!(listed[0]["text_color"], "#000000");
+        let parsed: Vec<Tag> = serde_json::from_value(listed).unwrap();
+        assert_eq!(parsed[0].color, "#FFFF00");
+
+        delete_tag("light".to_string()).unwrap();
+        create_tag("dark".to_string(), "#000080".to_string(), None, None).unwrap();
+        let stored = std::fs::read_to_string(env.config_dir().join(TAGS_DB_FILE_NAME)).unwrap();
+        assert!(!stored.contains("text_color"), "{}", stored);
+        assert!(stored.contains("light"), "{}", stored);
+    }
+
+    #[test]
+    fn test_tag_icons() {
+        let env = TestEnv::new();
+        create_tag("heli".to_string(), "#FF0000".to_string(), None, Some(" 🚁 ".to_string())).unwrap();
+        create_tag("plain".to_string(), "#00FF00".to_string(), None, None).unwrap();
+        add_tags_to_save("Survival/A", vec!["heli".to_string()]).unwrap();
+        assert_eq!(get_save_tags("Survival/A").unwrap()[0].icon.as_deref(), Some("🚁"));
+
+        // Flags and skin tones are two chars; short text works too
+        for icon in ["🇩🇪", "👍🏽", "v2", "★"] {
+            update_tag_icon("plain".to_string(), Some(icon.to_string())).unwrap();
+            assert_eq!(get_all_tags(TagSort::Position).unwrap()[1].icon.as_deref(), Some(icon));
+        }
+        for icon in ["toolong", "a b", "\u{7}"] {
+            assert!(matches!(
+                update_tag_icon("plain".to_string(), Some(icon.to_string())),
+                Err(TagsError::InvalidIcon(_))
+            ), "{:?}", icon);
+        }
+        assert!(matches!(
+            create_tag("bad".to_string(), "#000".to_string(), None, Some("toolong".to_string())),
+            Err(TagsError::InvalidIcon(_))
+        ));
+        assert!(matches!(
+            update_tag_icon("missing".to_string(), Some("🚁".to_string())),
+            Err(TagsError::TagNotFound(_))
+        ));
+
+        update_tag_icon("plain".to_string(), None).unwrap();
+        let written = std::fs::read_to_string(env.config_dir().join(TAGS_DB_FILE_NAME)).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&written).unwrap();
+        assert_eq!(value["tags"][0]["icon"], "🚁");
+        assert!(value["tags"][1].get("icon").is_none());
+    }
+
+    #[test]
+    fn test_tag_groups() {
+        let env = TestEnv::new();
+        for name in ["zed", "Alpha", "loose", "beta"] {
+            create_tag(name.to_string(), "#FF0000".to_string(), None, None).unwrap();
+        }
+        set_tag_group("zed".to_string(), Some(" Runs ".to_string())).unwrap();
+        set_tag_group("Alpha".to_string(), Some("Runs".to_string())).unwrap();
+        set_tag_group("beta".to_string(), Some("Events".to_string())).unwrap();
+        assert!(matches!(
+            set_tag_group("missing".to_string(), Some("Runs".to_string())),
+            Err(TagsError::TagNotFound(_))
+        ));
+
+        assert_eq!(get_tag_groups().unwrap(), vec!["Events", "Runs"]);
+        let runs: Vec<String> = get_tags_in_group("Runs").unwrap().into_iter().map(|t| t.name).collect();
+        assert_eq!(runs, vec!["zed", "Alpha"]);
+        assert!(get_tags_in_group("Nope").unwrap().is_empty());
+
+        let names = |tags: Vec<Tag>| tags.into_iter().map(|t| t.name).collect::<Vec<_>>();
+        assert_eq!(names(get_all_tags(TagSort::Position).unwrap()), vec!["zed", "Alpha", "loose", "beta"]);
+        assert_eq!(names(get_all_tags(TagSort::Group).unwrap()), vec!["beta", "Alpha", "zed", "loose"]);
+
+        // A group disappears with its last tag, whether deleted or moved out
+        delete_tag("beta".to_string()).unwrap();
+        set_tag_group("zed".to_string(), Some("  ".to_string())).unwrap();
+        assert_eq!(get_tag_groups().unwrap(), vec!["Runs"]);
+
+        let written = std::fs::read_to_string(env.config_dir().join(TAGS_DB_FILE_NAME)).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&written).unwrap();
+        assert_eq!(value["tags"][1]["name"], "Alpha");
+        assert_eq!(value["tags"][1]["group"], "Runs");
+        assert!(value["tags"][0].get("group").is_none());
+    }
+
+    #[test]
+    fn test_find_backups_by_tag() {
+        let _env = TestEnv::new();
+        create_tag("milestone".to_string(), "#FF0000".to_string(), None, None).unwrap();
+        create_tag("unused".to_string(), "#00FF00".to_string(), None, None).unwrap();
+        add_tags_to_backup("Survival/B", "b1.tar.gz", vec!["milestone".to_string()]).unwrap();
+        add_tags_to_backup("Survival/A", "a2.tar.gz", vec!["milestone".to_string()]).unwrap();
+        add_tags_to_backup("Survival/A", "a1.tar.gz", vec!["milestone".to_string()]).unwrap();
+        add_tags_to_backup("Survival/A", "a3.tar.gz", vec!["unused".to_string()]).unwrap();
+        // Save associations aren't backups
+        add_tags_to_save("Survival/C", vec!["milestone".to_string()]).unwrap();
+
+        let pair = |save: &str, backup: &str| (save.to_string(), backup.to_string());
+        assert_eq!(
+            find_backups_by_tag("milestone").unwrap(),
+            vec![pair("Survival/A", "a1.tar.gz"), pair("Survival/A", "a2.tar.gz"), pair("Survival/B", "b1.tar.gz")]
+        );
+
+        remove_tags_from_backup("Survival/A", "a3.tar.gz", vec!["unused".to_string()]).unwrap();
+        assert!(find_backups_by_tag("unused").unwrap().is_empty());
+        assert!(matches!(find_backups_by_tag("missing"), Err(TagsError::TagNotFound(_))));
+    }
+
+    #[test]
+    fn test_validate_tag_name() {
+        assert_eq!(validate_tag_name("  keep  ").unwrap(), "keep");
+        assert_eq!(validate_tag_name("main playthrough").unwrap(), "main playthrough");
+        assert!(validate_tag_name(&"x".repeat(MAX_TAG_NAME_CHARS)).is_ok());
+        assert!(validate_tag_name(&"é".repeat(MAX_TAG_NAME_CHARS)).is_ok());
+        for invalid in ["", "   ", "two\nlines", "tab\there", "bell\u{7}", &"x".repeat(MAX_TAG_NAME_CHARS + 1)] {
+            assert!(matches!(validate_tag_name(invalid), Err(TagsError::InvalidName(_))), "{:?}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_invalid_tag_names_are_rejected() {
+        let _env = TestEnv::new();
+        assert!(matches!(
+            create_tag("   ".to_string(), "#FF0000".to_string(), None, None),
+            Err(TagsError::InvalidName(_))
+        ));
+        create_tag(" keep ".to_string(), "#FF0000".to_string(), None, None).unwrap();
+        assert_eq!(get_all_tags(TagSort::Position).unwrap()[0].name, "keep");
+        assert!(matches!(
+            create_tag("keep".to_string(), "#FF0000".to_string(), None, None),
+            Err(TagsError::DuplicateTag(_))
+        ));
+
+        assert!(matches!(
+            rename_tag("keep".to_string(), "line\nbreak".to_string()),
+            Err(TagsError::InvalidName(_))
+        ));
+        rename_tag("keep".to_string(), " kept ".to_string()).unwrap();
+        assert_eq!(get_all_tags(TagSort::Position).unwrap()[0].name, "kept");
+    }
+
+    #[test]
+    fn test_tag_names_are_case_insensitive() {
+        let _env = TestEnv::new();
+        create_tag("Milestone".to_string(), "#FF0000".to_string(), None, None).unwrap();
+        for duplicate in ["milestone", "MILESTONE"] {
+            let result = create_tag(duplicate.to_string(), "#00FF00".to_string(), None, None);
+            assert!(matches!(result, Err(TagsError::DuplicateTag(_))), "{}", duplicate);
+        }
+        create_tag("Élan".to_string(), "#0000FF".to_string(), None, None).unwrap();
+        assert!(matches!(
+            create_tag("élan".to_string(), "#0000FF".to_string(), None, None),
+            Err(TagsError::DuplicateTag(_))
+        ));
+
+        // Lookups resolve to the stored name
+        add_tags_to_backup("Survival/A", "a1.tar.gz", vec!["milestone".to_string()]).unwrap();
+        add_tags_to_backup("Survival/A", "a1.tar.gz", vec!["MILESTONE".to_string()]).unwrap();
+        let tags = get_backup_tags("Survival/A", "a1.tar.gz").unwrap();
+        assert_eq!(tags.len(), 1);
+        assert_eq!(tags[0].name, "Milestone");
+        assert_eq!(find_backups_by_tag("milestone").unwrap().len(), 1);
+        let stored = load_tags_db().unwrap();
+        assert_eq!(stored.association(&TagTarget::backup("Survival/A", "a1.tar.gz")).unwrap().tag_names, vec!["Milestone"]);
+
+        remove_tags_from_backup("Survival/A", "a1.tar.gz", vec!["milestone".to_string()]).unwrap();
+        assert!(get_backup_tags("Survival/A", "a1.tar.gz").unwrap().is_empty());
+
+        // Changing only the case of a name is a rename, not a duplicate
+        rename_tag("milestone".to_string(), "milestone".to_string()).unwrap();
+        assert!(get_all_tags(TagSort::Position).unwrap().iter().any(|t| t.name == "milestone"));
+
+        delete_tag("ÉLAN".to_string()).unwrap();
+        assert!(!load_tags_db().unwrap().has_tag("Élan"));
+        assert!(find_conflicting_tags().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_find_conflicting_tags() {
+        let env = TestEnv::new();
+        std::fs::create_dir_all(env.config_dir()).unwrap();
+        std::fs::write(
+            env.config_dir().join(TAGS_DB_FILE_NAME),
+            r##"{
+                "tags": [
+                    {"name": "Milestone", "color": "#FF0000"},
+                    {"name": "keep", "color": "#00FF00"},
+                    {"name": "milestone", "color": "#0000FF"},
+                    {"name": "KEEP", "color": "#00FF00"},
+                    {"name": "MILESTONE", "color": "#000000"}
+                ],
+                "associations": [
+                    {"target": {"type": "Save", "relative_path": "Survival/A"}, "tag_names": ["milestone"]}
+                ]
+            }"##,
+        )
+        .unwrap();
+
+        // Near-duplicates still load; exact names keep resolving to themselves
+        assert_eq!(get_all_tags(TagSort::Position).unwrap().len(), 5);
+        assert_eq!(get_save_tags("Survival/A").unwrap()[0].color, "#0000FF");
+        assert_eq!(
+            find_conflicting_tags().unwrap(),
+            vec![vec!["Milestone", "milestone", "MILESTONE"], vec!["keep", "KEEP"]]
+        );
+    }
+
+    #[test]
+    fn test_find_saves_by_tag() {
+        let _env = TestEnv::new();
+        create_tag("main playthrough".to_string(), "#FF0000".to_string(), None, None).unwrap();
+        create_tag("milestone".to_string(), "#00FF00".to_string(), None, None).unwrap();
+        add_tags_to_save("Survival/B", vec!["main playthrough".to_string()]).unwrap();
+        add_tags_to_save("Survival/A", vec!["main playthrough".to_string(), "milestone".to_string()])
+            .unwrap();
+        add_tags_to_save("Survival/C", vec!["milestone".to_string()]).unwrap();
+        add_tags_to_backup("Survival/D", "d1.tar.gz", vec!["main playthrough".to_string()]).unwrap();
+
+        assert_eq!(find_saves_by_tag("main playthrough").unwrap(), vec!["Survival/A", "Survival/B"]);
+
+        // Only on backups: no saves
+        create_tag("backup-only".to_string(), "#0000FF".to_string(), None, None).unwrap();
+        add_tags_to_backup("Survival/A", "a1.tar.gz", vec!["backup-only".to_string()]).unwrap();
+        assert!(find_saves_by_tag("backup-only").unwrap().is_empty());
+        assert!(matches!(find_saves_by_tag("missing"), Err(TagsError::TagNotFound(_))));
+    }
+
+    #[test]
+    fn test_bulk_backup_tags() {
+        let env = TestEnv::new();
+        create_tag("session".to_string(), "#FF0000".to_string(), None, None).unwrap();
+        create_tag("keep".to_string(), "#00FF00".to_string(), None, None).unwrap();
+        add_tags_to_backup("Survival/A", "a1.tar.gz", vec!["keep".to_string()]).unwrap();
+
+        let targets: Vec<(String, String)> = (1..=40)
+            .map(|i| (format!("Survival/{}", if i % 2 == 0 { "A" } else { "B" }), format!("b{}.tar.gz", i)))
+            .chain(std::iter::once(("Survival/A".to_string(), "a1.tar.gz".to_string())))
+            .collect();
+        add_tags_to_backups(targets.clone(), vec!["session".to_string(), "keep".to_string()]).unwrap();
+        assert_eq!(find_backups_by_tag("session").unwrap().len(), 41);
+        let a1: Vec<String> = get_backup_tags("Survival/A", "a1.tar.gz").unwrap().into_iter().map(|t| t.name).collect();
+        assert_eq!(a1, vec!["keep", "session"]);
+
+        // An unknown tag fails the whole batch without writing
+        let path = env.config_dir().join(TAGS_DB_FILE_NAME);
+        let before = std::fs::read_to_string(&path).unwrap();
+        let result = add_tags_to_backups(
+            vec![("Survival/C".to_string(), "c1.tar.gz".to_string())],
+            vec!["session".to_string(), "missing".to_string()],
+        );
+        assert!(matches!(result, Err(TagsError::TagNotFound(name)) if name == "missing"));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), before);
+
+        remove_tags_from_backups(targets, vec!["session".to_string()]).unwrap();
+        assert!(find_backups_by_tag("session").unwrap().is_empty());
+        assert_eq!(find_backups_by_tag("keep").unwrap().len(), 41);
+    }
+
+    #[test]
+    fn test_compact_tags_db() {
+        let env = TestEnv::new();
+        std::fs::create_dir_all(env.config_dir()).unwrap();
+        let path = env.config_dir().join(TAGS_DB_FILE_NAME);
+        let file = |key_version: u32| {
+            format!(
+                r##"{{
+                    "tags": [{{"name": "a", "color": "#FF0000"}}, {{"name": "b", "color": "#00FF00"}}],
+                    "associations": [
+                        {{"target": {{"type": "Backup", "save_name": "Survival/A", "backup_name": "1.tar.gz"}}, "tag_names": ["b", "b"]}},
+                        {{"target": {{"type": "Save", "relative_path": "Survival/A"}}, "tag_names": []}},
+                        {{"target": {{"type": "Backup", "save_name": "Survival/A", "backup_name": "1.tar.gz"}}, "tag_names": ["a", "b"]}}
+                    ],
+                    "key_version": {}
+                }}"##,
+                key_version
+            )
+        };
+        let stored = || -> TagsDatabase { serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap() };
+
+        std::fs::write(&path, file(KEY_VERSION)).unwrap();
+        assert!(stored().needs_compaction());
+        assert_eq!(
+            compact_tags_db().unwrap(),
+            CompactionStats { merged_associations: 1, removed_duplicate_names: 1, removed_associations: 1 }
+        );
+        let compacted = stored();
+        assert!(!compacted.needs_compaction());
+        assert_eq!(compacted.associations.len(), 1);
+        assert_eq!(compacted.associations[0].tag_names, ["b", "a"]);
+        assert_eq!(compact_tags_db().unwrap(), CompactionStats::default());
+
+        // Loading rewrites a file with duplicates
+        std::fs::write(&path, file(KEY_VERSION)).unwrap();
+        let names: Vec<String> = get_backup_tags("Survival/A", "1.tar.gz").unwrap().into_iter().map(|t| t.name).collect();
+        assert_eq!(names, ["b", "a"]);
+        assert!(!stored().needs_compaction());
+
+        // ...unless `migrate_tag_keys` still has to run on it
+        std::fs::write(&path, file(0)).unwrap();
+        assert_eq!(get_backup_tags("Survival/A", "1.tar.gz").unwrap().len(), 2);
+        assert!(stored().needs_compaction());
+    }
+
+    #[test]
+    fn test_validate_and_repair_database() {
+        let env = TestEnv::new();
+        std::fs::create_dir_all(env.config_dir()).unwrap();
+        let path = env.config_dir().join(TAGS_DB_FILE_NAME);
+        std::fs::write(
+            &path,
+            r##"{
+                "tags": [
+                    {"name": "keep", "color": "#FF0000", "sort_index": 0},
+                    {"name": "twice", "color": "#00FF00", "sort_index": 1},
+                    {"name": "twice", "color": "#0000FF", "sort_index": 2}
+                ],
+                "associations": [
+                    {"target": {"type": "Backup", "save_name": "Survival/A", "backup_name": "a1.tar.gz"}, "tag_names": ["keep", "ghost"]},
+                    {"target": {"type": "Save", "relative_path": "Survival/A"}, "tag_names": ["ghost"]},
+                    {"target": {"type": "Backup", "save_name": "Survival/A", "backup_name": "a1.tar.gz"}, "tag_names": ["twice"]}
+                ]
+            }"##,
+        )
+        .unwrap();
+        let on_disk = || -> TagsDatabase { serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap() };
+        let backup = TagTarget::backup("Survival/A", "a1.tar.gz");
+        let save = TagTarget::save("Survival/A");
+
+        let report = validate_database(&on_disk());
+        assert_eq!(
+            report.dangling_tag_names,
+            vec![(backup.clone(), vec!["ghost".to_string()]), (save.clone(), vec!["ghost".to_string()])]
+        );
+        assert_eq!(report.duplicate_associations, vec![(backup.clone(), 2)]);
+        assert_eq!(report.duplicate_tag_names, ["twice"]);
+        // Loading still works; the findings are only logged
+        assert_eq!(get_backup_tags("Survival/A", "a1.tar.gz").unwrap().len(), 2);
+
+        let summary = repair_database().unwrap();
+        assert_eq!(
+            summary,
+            RepairSummary {
+                removed_tag_names: vec![(backup.clone(), vec!["ghost".to_string()]), (save, vec!["ghost".to_string()])],
+                merged_associations: 1,
+                removed_associations: 1,
+            }
+        );
+        let repaired = on_disk();
+        assert_eq!(repaired.associations.len(), 1);
+        assert_eq!(repaired.associations[0].tag_names, ["keep", "twice"]);
+        let report = validate_database(&repaired);
+        assert!(report.dangling_tag_names.is_empty() && report.duplicate_associations.is_empty());
+        assert_eq!(report.duplicate_tag_names, ["twice"]);
+
+        assert_eq!(repair_database().unwrap(), RepairSummary::default());
+    }
+
+    #[test]
+    fn test_export_tags_csv_quotes_names() {
+        let env = TestEnv::new();
+        create_tag("a,\"b\"".to_string(), "#FF0000".to_string(), Some("commas, too".to_string()), None).unwrap();
+        create_tag("keep".to_string(), "#00FF00".to_string(), None, None).unwrap();
+        add_tags_to_backup("Survival/A", "1.tar.gz", vec!["a,\"b\"".to_string(), "keep".to_string()]).unwrap();
+        add_tags_to_save("Survival/A", vec!["keep".to_string()]).unwrap();
+
+        let path = env.config_dir().join("tags.csv");
+        let export = export_tags_csv(path.clone()).unwrap();
+
+        assert_eq!(export, TagsCsvExport { tag_rows: 2, association_rows: 2 });
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            concat!(
+                "name,color,description,backup_count,save_count\r\n",
+                "\"a,\"\"b\"\"\",#FF0000,\"commas, too\",1,0\r\n",
+                "keep,#00FF00,,1,1\r\n",
+                "\r\n",
+                "target_type,save,backup,tags\r\n",
+                "Backup,Survival/A,1.tar.gz,\"\"\"a,\"\"\"\"b\"\"\"\"\"\",keep\"\r\n",
+                "Save,Survival/A,,keep\r\n",
+            )
+        );
+    }
+
+    #[test]
+    fn test_tag_usage_with_dangling_names() {
+        let env = TestEnv::new();
+        std::fs::create_dir_all(env.config_dir()).unwrap();
+        std::fs::write(
+            env.config_dir().join(TAGS_DB_FILE_NAME),
+            r##"{
+                "tags": [
+                    {"name": "keep", "color": "#FF0000", "sort_index": 1},
+                    {"name": "unused", "color": "#00FF00", "sort_index": 0}
+                ],
+                "associations": [
+                    {"target": {"type": "Backup", "save_name": "Survival/A", "backup_name": "a1.tar.gz"}, "tag_names": ["keep", "ghost"]},
+                    {"target": {"type": "Backup", "save_name": "Survival/B", "backup_name": "b1.tar.gz"}, "tag_names": ["keep"]},
+                    {"target": {"type": "Save", "relative_path": "Survival/A"}, "tag_names": ["keep", "ghost", "another ghost"]}
+                ]
+            }"##,
+        )
+        .unwrap();
+
+        let usage = get_tag_usage().unwrap();
+        let summary: Vec<(&str, usize, usize, bool)> = usage
+            .iter()
+            .map(|u| (u.tag.name.as_str(), u.backup_count, u.save_count, u.dangling))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("unused", 0, 0, false),
+                ("keep", 2, 1, false),
+                ("another ghost", 0, 1, true),
+                ("ghost", 1, 1, true),
+            ]
+        );
+        assert_eq!(usage[1].tag.color, "#FF0000");
+        assert_eq!(usage[3].tag.color, DANGLING_TAG_COLOR);
+    }
+
+    #[test]
+    fn test_copy_tags_from_backup_to_save() {
+        let env = TestEnv::new();
+        std::fs::create_dir_all(env.config_dir()).unwrap();
+        std::fs::write(
+            env.config_dir().join(TAGS_DB_FILE_NAME),
+            r##"{
+                "tags": [
+                    {"name": "pre-helicopter", "color": "#FF0000"},
+                    {"name": "keep", "color": "#00FF00"},
+                    {"name": "modded", "color": "#0000FF"}
+                ],
+                "associations": [
+                    {"target": {"type": "Backup", "save_name": "Survival/A", "backup_name": "a1.tar.gz"}, "tag_names": ["keep", "ghost", "pre-helicopter"]},
+                    {"target": {"type": "Save", "relative_path": "Survival/A"}, "tag_names": ["modded", "keep"]}
+                ]
+            }"##,
+        )
+        .unwrap();
+        let save_tags = || -> Vec<String> { get_save_tags("Survival/A").unwrap().into_iter().map(|t| t.name).collect() };
+
+        // Overlapping tags are kept once and the dangling name is skipped
+        let added = copy_tags_from_backup_to_save("Survival/A", "a1.tar.gz", "Survival/A").unwrap();
+        assert_eq!(added, vec!["pre-helicopter"]);
+        assert_eq!(save_tags(), vec!["modded", "keep", "pre-helicopter"]);
+        let stored = load_tags_db().unwrap();
+        assert_eq!(stored.association(&TagTarget::save("Survival/A")).unwrap().tag_names.len(), 3);
+
+        // Copying again changes nothing
+        assert!(copy_tags_from_backup_to_save("Survival/A", "a1.tar.gz", "Survival/A").unwrap().is_empty());
+        assert_eq!(save_tags(), vec!["modded", "keep", "pre-helicopter"]);
+
+        // A save without tags gets the backup's tags in their order
+        copy_tags_from_backup_to_save("Survival/A", "a1.tar.gz", "Survival/B").unwrap();
+        let names: Vec<String> = get_save_tags("Survival/B").unwrap().into_iter().map(|t| t.name).collect();
+        assert_eq!(names, vec!["keep", "pre-helicopter"]);
+    }
+
+    #[test]
+    fn test_bulk_tag_lookups() {
+        let env = TestEnv::new();
+        std::fs::create_dir_all(env.config_dir()).unwrap();
+        std::fs::write(
+            env.config_dir().join(TAGS_DB_FILE_NAME),
+            r##"{
+                "tags": [
+                    {"name": "keep", "color": "#FF0000"},
+                    {"name": "session", "color": "#00FF00"}
+                ],
+                "associations": [
+                    {"target": {"type": "Backup", "save_name": "Survival/A", "backup_name": "a1.tar.gz"}, "tag_names": ["session", "keep"]},
+                    {"target": {"type": "Backup", "save_name": "Survival/A", "backup_name": "a2.tar.gz"}, "tag_names": ["keep"]},
+                    {"target": {"type": "Backup", "save_name": "Survival/A", "backup_name": "a3.tar.gz"}, "tag_names": ["ghost"]},
+                    {"target": {"type": "Backup", "save_name": "Survival/B", "backup_name": "b1.tar.gz"}, "tag_names": ["keep"]},
+                    {"target": {"type": "Save", "relative_path": "Survival/A"}, "tag_names": ["keep"]},
+                    {"target": {"type": "Save", "relative_path": "Survival/C"}, "tag_names": ["session"]}
+                ]
+            }"##,
+        )
+        .unwrap();
+        let names = |tags: &Vec<Tag>| -> Vec<String> { tags.iter().map(|t| t.name.clone()).collect() };
+
+        // Keys are normalized like single lookups; untagged backups are missing
+        let backups = get_all_backup_tags_for_save("Survival\\A\\").unwrap();
+        assert_eq!(backups.len(), 2);
+        assert_eq!(names(&backups["a1.tar.gz"]), vec!["session", "keep"]);
+        assert_eq!(names(&backups["a2.tar.gz"]), vec!["keep"]);
+        for (backup, tags) in &backups {
+            assert_eq!(tags, &get_backup_tags("Survival/A", backup).unwrap());
+        }
+        assert!(get_all_backup_tags_for_save("Survival/Missing").unwrap().is_empty());
+
+        let saves = get_all_save_tags().unwrap();
+        assert_eq!(saves.len(), 2);
+        assert_eq!(names(&saves["Survival/A"]), vec!["keep"]);
+        assert_eq!(names(&saves["Survival/C"]), vec!["session"]);
+    }
+
+    #[test]
+    fn test_set_backup_and_save_tags() {
+        let _env = TestEnv::new();
+        for name in ["a", "b", "c"] {
+            create_tag(name.to_string(), "#FF0000".to_string(), None, None).unwrap();
+        }
+        let names = |tags: Vec<Tag>| -> Vec<String> { tags.into_iter().map(|t| t.name).collect() };
+        add_tags_to_backup("Survival/A", "a1.tar.gz", vec!["a".to_string()]).unwrap();
+
+        // The given order is kept; case and repeats are normalized
+        set_backup_tags("Survival/A", "a1.tar.gz", vec!["C".to_string(), "a".to_string(), "c".to_string()])
+            .unwrap();
+        assert_eq!(names(get_backup_tags("Survival/A", "a1.tar.gz").unwrap()), vec!["c", "a"]);
+        assert_eq!(names(get_recent_tags().unwrap())[0], "c");
+
+        // An unknown name changes nothing
+        let result = set_backup_tags("Survival/A", "a1.tar.gz", vec!["b".to_string(), "missing".to_string()]);
+        assert!(matches!(result, Err(TagsError::TagNotFound(ref n)) if n == "missing"));
+        assert_eq!(names(get_backup_tags("Survival/A", "a1.tar.gz").unwrap()), vec!["c", "a"]);
+
+        set_save_tags("Survival/A", vec!["b".to_string()]).unwrap();
+        assert_eq!(names(get_save_tags("Survival/A").unwrap()), vec!["b"]);
+
+        // An empty list removes the association
+        set_backup_tags("Survival/A", "a1.tar.gz", Vec::new()).unwrap();
+        set_save_tags("Survival/A", Vec::new()).unwrap();
+        assert!(load_tags_db().unwrap().associations.is_empty());
+    }
+
+    #[test]
+    fn test_suggest_color() {
+        let mut db = TagsDatabase::default();
+        assert_eq!(db.suggest_color(), TAG_COLOR_PALETTE[0]);
+
+        // Palette colors go first, in order, whatever case the tags spell them in
+        for (i, color) in TAG_COLOR_PALETTE.iter().enumerate() {
+            db.create_tag(format!("p{}", i), color.to_ascii_lowercase(), None, None).unwrap();
+            if let Some(next) = TAG_COLOR_PALETTE.get(i + 1) {
+                assert_eq!(db.suggest_color(), *next);
+            }
+        }
+
+        // Then the widest hue gap is split: yellow (45) to green (142)
+        let distance = |db: &TagsDatabase, color: &str| -> f64 {
+            let hue = css_colors::hue(color).unwrap();
+            db.tags
+                .iter()
+                .filter_map(|t| css_colors::hue(&t.color))
+                .map(|h| (h - hue).abs().min(360.0 - (h - hue).abs()))
+                .fold(f64::MAX, f64::min)
+        };
+        let suggested = db.suggest_color();
+        assert!(distance(&db, &suggested) > 45.0, "{}", suggested);
+
+        // Still a valid, distinct color with many tags
+        for i in 0..60 {
+            let color = css_colors::hsl_to_hex(i as f64 * 5.0, 0.7, 0.55);
+            db.create_tag(format!("t{}", i), color, None, None).unwrap();
+        }
+        let suggested = db.suggest_color();
+        assert!(validate_color(&suggested).is_ok());
+        assert!(distance(&db, &suggested) >= 2.0, "{}", suggested);
+    }
+
+    #[test]
+    fn test_tag_statistics() {
+        let json = r##"{
+            "tags": [
+                {"name": "keep", "color": "#FF0000", "aliases": ["old"]},
+                {"name": "session", "color": "#00FF00"},
+                {"name": "unused", "color": "#0000FF"}
+            ],
+            "associations": [
+                {"target": {"type": "Backup", "save_name": "Survival/A", "backup_name": "a1.tar.gz"}, "tag_names": ["keep", "session"]},
+                {"target": {"type": "Backup", "save_name": "Survival/A", "backup_name": "a2.tar.gz"}, "tag_names": ["old"]},
+                {"target": {"type": "Backup", "save_name": "Survival/B", "backup_name": "b1.tar.gz"}, "tag_names": ["session", "ghost"]},
+                {"target": {"type": "Backup", "save_name": "Survival/B", "backup_name": "b2.tar.gz"}, "tag_names": []},
+                {"target": {"type": "Save", "relative_path": "Survival/A"}, "tag_names": ["session"]}
+            ]
+        }"##;
+        let mut db: TagsDatabase = serde_json::from_str(json).unwrap();
+        db.rebuild_indices();
+        let stats = db.tag_statistics();
+
+        assert_eq!(stats.total_tags, 3);
+        assert_eq!((stats.tagged_backups, stats.tagged_saves), (3, 1));
+        assert_eq!(stats.untagged_associations, 1);
+        assert_eq!(stats.dangling_tag_names, 1);
+        assert_eq!(stats.most_used_tag.as_deref(), Some("session"));
+
+        let summary: Vec<(&str, usize, usize, bool)> = stats
+            .tags
+            .iter()
+            .map(|t| (t.name.as_str(), t.backup_count, t.save_count, t.dangling))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("keep", 2, 0, false),
+                ("session", 2, 1, false),
+                ("unused", 0, 0, false),
+                ("ghost", 1, 0, true),
+            ]
+        );
+        let per_save = |i: usize| -> Vec<(&str, usize)> {
+            stats.tags[i].backups_per_save.iter().map(|(s, n)| (s.as_str(), *n)).collect()
+        };
+        assert_eq!(per_save(0), vec![("Survival/A", 2)]);
+        assert_eq!(per_save(1), vec![("Survival/A", 1), ("Survival/B", 1)]);
+        assert!(per_save(2).is_empty());
+
+        // Ties go to the tag listed first; nothing tagged means no most used tag
+        db.associations.retain(|a| a.tag_names != ["session"]);
+        db.rebuild_indices();
+        assert_eq!(db.tag_statistics().most_used_tag.as_deref(), Some("keep"));
+        db.associations.clear();
+        db.rebuild_indices();
+        assert_eq!(db.tag_statistics().most_used_tag, None);
+    }
+
+    #[test]
+    fn test_recent_tags() {
+        let _env = TestEnv::new();
+        for i in 0..12 {
+            create_tag(format!("t{}", i), "#FF0000".to_string(), None, None).unwrap();
+        }
+        let recent = || get_recent_tags().unwrap().into_iter().map(|t| t.name).collect::<Vec<_>>();
+        assert!(recent().is_empty());
+
+        add_tags_to_backup("Survival/A", "1.tar.gz", vec!["t0".to_string(), "t1".to_string()]).unwrap();
+        add_tags_to_save("Survival/A", vec!["t2".to_string()]).unwrap();
+        assert_eq!(recent(), vec!["t2", "t0", "t1"]);
+
+        // Using a tag again moves it to the front, whatever case it is given in
+        add_tags_to_backup("Survival/A", "2.tar.gz", vec!["T1".to_string()]).unwrap();
+        add_tags_to_backup("Survival/A", "3.tar.gz", vec!["t1".to_string()]).unwrap();
+        assert_eq!(recent(), vec!["t1", "t2", "t0"]);
+
+        // Failed adds change nothing
+        assert!(add_tags_to_save("Survival/A", vec!["t3".to_string(), "missing".to_string()]).is_err());
+        assert_eq!(recent(), vec!["t1", "t2", "t0"]);
+
+        let many: Vec<(String, String)> = vec![("Survival/B".to_string(), "1.tar.gz".to_string())];
+        add_tags_to_backups(many, (3..12).map(|i| format!("t{}", i)).collect()).unwrap();
+        let list = recent();
+        assert_eq!(list.len(), MAX_RECENT_TAGS);
+        assert_eq!(&list[..2], ["t3", "t4"]);
+        assert_eq!(list[9], "t1");
+
+        delete_tag("t3".to_string()).unwrap();
+        rename_tag("t4".to_string(), "four".to_string()).unwrap();
+        let list = recent();
+        assert_eq!(&list[..2], ["four", "t5"]);
+        assert!(!load_tags_db().unwrap().recent_tags.contains(&"t3".to_string()));
+    }
+
+    #[test]
+    fn test_pinned_tags_are_listed_first() {
+        let env = TestEnv::new();
+        for name in ["a", "b", "c"] {
+            create_tag(name.to_string(), "#FF0000".to_string(), None, None).unwrap();
+        }
+        set_tag_group("a".to_string(), Some("G".to_string())).unwrap();
+        let order = |by_group: bool| {
+            let sort = if by_group { TagSort::Group } else { TagSort::Position };
+            get_all_tags(sort).unwrap().into_iter().map(|t| t.name).collect::<Vec<_>>()
+        };
+
+        set_tag_pinned("c".to_string(), true).unwrap();
+        set_tag_pinned("b".to_string(), true).unwrap();
+        // Pinned tags keep their relative order
+        assert_eq!(order(false), vec!["b", "c", "a"]);
+        assert_eq!(order(true), vec!["b", "c", "a"]);
+
+        // Survives a reload from disk; unpinned tags don't write the field
+        invalidate_db_cache(&get_tags_db_path().unwrap());
+        assert!(load_tags_db().unwrap().tag("c").unwrap().pinned);
+        let json = std::fs::read_to_string(env.config_dir().join(TAGS_DB_FILE_NAME)).unwrap();
+        assert_eq!(json.matches("\"pinned\"").count(), 2);
+
+        set_tag_pinned("b".to_string(), false).unwrap();
+        set_tag_pinned("c".to_string(), false).unwrap();
+        assert_eq!(order(false), vec!["a", "b", "c"]);
+        assert_eq!(order(true), vec!["a", "b", "c"]);
+        assert!(matches!(
+            set_tag_pinned("missing".to_string(), true),
+            Err(TagsError::TagNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_protected_tag_protects_backups() {
+        let _env = TestEnv::new();
+        create_tag("keep-forever".to_string(), "#FF0000".to_string(), None, None).unwrap();
+        create_tag("milestone".to_string(), "#00FF00".to_string(), None, None).unwrap();
+        add_tags_to_backup("Survival", "a.tar.gz", vec!["milestone".to_string(), "keep-forever".to_string()]).unwrap();
+        add_tags_to_backup("Survival", "b.tar.gz", vec!["milestone".to_string()]).unwrap();
+        assert!(!is_backup_protected("Survival", "a.tar.gz").unwrap());
+
+        set_tag_protected("keep-forever".to_string(), true).unwrap();
+        assert!(is_backup_protected("Survival", "a.tar.gz").unwrap());
+        assert!(!is_backup_protected("Survival", "b.tar.gz").unwrap());
+        assert!(!is_backup_protected("Survival", "untagged.tar.gz").unwrap());
+        // Only the backup's own tags count, not those of another save's backup
+        assert!(!is_backup_protected("Other", "a.tar.gz").unwrap());
+
+        invalidate_db_cache(&get_tags_db_path().unwrap());
+        assert!(load_tags_db().unwrap().tag("keep-forever").unwrap().protected);
+        set_tag_protected("keep-forever".to_string(), false).unwrap();
+        assert!(!is_backup_protected("Survival", "a.tar.gz").unwrap());
+        assert!(matches!(
+            set_tag_protected("missing".to_string(), true),
+            Err(TagsError::TagNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_get_tags_for_targets() {
+        let _env = TestEnv::new();
+        create_tag("a".to_string(), "#FF0000".to_string(), None, None).unwrap();
+        create_tag("b".to_string(), "#00FF00".to_string(), None, None).unwrap();
+        add_tags_to_backup("Survival", "x.tar.gz", vec!["b".to_string(), "a".to_string()]).unwrap();
+        add_tags_to_save("Survival", vec!["a".to_string()]).unwrap();
+
+        let targets = vec![
+            TagTarget::save("Survival"),
+            TagTarget::backup("Survival", "missing.tar.gz"),
+            TagTarget::backup("Survival", "x.tar.gz"),
+        ];
+        let result = get_tags_for_targets(targets.clone()).unwrap();
+        let names: Vec<(TagTarget, Vec<String>)> = result
+            .into_iter()
+            .map(|(target, tags)| (target, tags.into_iter().map(|t| t.name).collect()))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                (targets[0].clone(), vec!["a".to_string()]),
+                (targets[1].clone(), Vec::new()),
+                (targets[2].clone(), vec!["b".to_string(), "a".to_string()]),
+            ]
+        );
+        assert!(get_tags_for_targets(Vec::new()).unwrap().is_empty());
+    }
+
+    /// Records the changes reported on the current thread, like a window listening for
+    /// `TAGS_CHANGED_EVENT` would receive them.
+    fn record_changes() -> Arc<Mutex<Vec<TagsChange>>> {
+        let changes = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&changes);
+        let thread = std::thread::current().id();
+        add_change_listener(move |change| {
+            // Tests run in parallel; other threads' changes belong to other tests
+            if std::thread::current().id() == thread {
+                sink.lock().unwrap().push(change.clone());
+            }
+        });
+        changes
+    }
+
+    #[test]
+    fn test_mutations_report_changes() {
+        let _env = TestEnv::new();
+        let changes = record_changes();
+        let taken = || std::mem::take(&mut *changes.lock().unwrap());
+        let backup = TagTarget::backup("Survival/A", "1.tar.gz");
+
+        create_tag("keep".to_string(), "#FF0000".to_string(), None, None).unwrap();
+        add_tags_to_backup("Survival/A", "1.tar.gz", vec!["keep".to_string()]).unwrap();
+        update_tag_color("keep".to_string(), "#00FF00".to_string()).unwrap();
+        assert_eq!(
+            taken(),
+            [
+                TagsChange::new(TagsChangeKind::TagCreated, None),
+                TagsChange::new(TagsChangeKind::TargetTagsChanged, Some(backup.clone())),
+                TagsChange::new(TagsChangeKind::TagUpdated, None),
+            ]
+        );
+
+        // Failures and calls that change nothing aren't reported
+        assert!(add_tags_to_backup("Survival/A", "1.tar.gz", vec!["missing".to_string()]).is_err());
+        add_tags_to_save("Survival/A", Vec::new()).unwrap();
+        assert_eq!(taken(), []);
+
+        // A batch is reported once
+        apply_tag_batch(vec![
+            TagOp::CreateTag { name: "new".to_string(), color: "#0000FF".to_string(), description: None, icon: None },
+            TagOp::AddToTarget { target: backup.clone(), tags: vec!["new".to_string()] },
+            TagOp::DeleteTag { name: "keep".to_string() },
+        ])
+        .unwrap();
+        add_tags_to_backups(
+            vec![("Survival/A".to_string(), "2.tar.gz".to_string()), ("Survival/A".to_string(), "3.tar.gz".to_string())],
+            vec!["new".to_string()],
+        )
+        .unwrap();
+        assert_eq!(
+            taken(),
+            [
+                TagsChange::new(TagsChangeKind::Batch, None),
+                TagsChange::new(TagsChangeKind::TargetTagsChanged, None),
+            ]
+        );
+
+        let payload = serde_json::to_value(TagsChange::new(TagsChangeKind::TargetTagsChanged, Some(backup))).unwrap();
+        assert_eq!(
+            payload,
+            serde_json::json!({
+                "kind": "TargetTagsChanged",
+                "target": {"type": "Backup", "save_name": "Survival/A", "backup_name": "1.tar.gz"}
+            })
+        );
+        let external = serde_json::to_value(TagsChange::external(vec![SaveKey::new("Survival/A")])).unwrap();
+        assert_eq!(external, serde_json::json!({"kind": "External", "target": null, "saves": ["Survival/A"]}));
     }
 
-    Ok(())
-}
+    #[test]
+    fn test_apply_tag_batch_is_all_or_nothing() {
+        let env = TestEnv::new();
+        create_tag("old".to_string(), "#FF0000".to_string(), None, None).unwrap();
+        create_tag("drop".to_string(), "#00FF00".to_string(), None, None).unwrap();
+        let a = TagTarget::backup("Survival", "a.tar.gz");
+        let b = TagTarget::backup("Survival", "b.tar.gz");
+        add_tags_to_backup("Survival", "a.tar.gz", vec!["old".to_string(), "drop".to_string()]).unwrap();
+        let db_path = env.config_dir().join(TAGS_DB_FILE_NAME);
+        let before = std::fs::read_to_string(&db_path).unwrap();
 
-/// Creates a new tag.
-///
-/// # Arguments
-/// * `name` - Tag name (must be unique)
-/// * `color` - Tag color (hex format like #FF5733)
-///
-/// # Returns
-/// `TagsResult<()>` - Ok(()) on success
-pub fn create_tag(name: String, color: String) -> TagsResult<()> {
-    // Validate color format
-    validate_color(&color)?;
+        // The third change fails, so neither the new tag nor the removal is kept
+        let failing = vec![
+            TagOp::CreateTag { name: "new".to_string(), color: "#0000FF".to_string(), description: None, icon: None },
+            TagOp::RemoveFromTarget { target: a.clone(), tags: vec!["drop".to_string()] },
+            TagOp::AddToTarget { target: b.clone(), tags: vec!["missing".to_string()] },
+        ];
+        match apply_tag_batch(failing) {
+            Err(TagsError::BatchOpFailed { index: 2, source }) => {
+                assert!(matches!(*source, TagsError::TagNotFound(_)))
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+        assert_eq!(std::fs::read_to_string(&db_path).unwrap(), before);
+        assert!(!load_tags_db().unwrap().has_tag("new"));
 
-    let mut db = load_tags_db()?;
+        // Later changes see earlier ones: a new tag can be added, a renamed one used
+        apply_tag_batch(vec![
+            TagOp::CreateTag { name: "new".to_string(), color: "#0000FF".to_string(), description: None, icon: None },
+            TagOp::AddToTarget { target: b.clone(), tags: vec!["new".to_string()] },
+            TagOp::RemoveFromTarget { target: a.clone(), tags: vec!["drop".to_string(), "missing".to_string()] },
+            TagOp::Rename { old_name: "old".to_string(), new_name: "renamed".to_string() },
+            TagOp::AddToTarget { target: b.clone(), tags: vec!["renamed".to_string()] },
+            TagOp::DeleteTag { name: "drop".to_string() },
+        ])
+        .unwrap();
+        let names = |target: &TagTarget| {
+            load_tags_db().unwrap().tags_for(target).into_iter().map(|t| t.name).collect::<Vec<_>>()
+        };
+        assert_eq!(names(&a), vec!["renamed"]);
+        assert_eq!(names(&b), vec!["new", "renamed"]);
+        assert_eq!(get_deleted_tags().unwrap()[0].tag.name, "drop");
 
-    // Check for duplicate tag name
-    if db.tags.iter().any(|t| t.name == name) {
-        return Err(TagsError::DuplicateTag(name));
+        let parsed: Vec<TagOp> = serde_json::from_str(
+            r#"[{"op":"DeleteTag","name":"x"},{"op":"CreateTag","name":"y","color":"red"}]"#,
+        )
+        .unwrap();
+        assert_eq!(parsed[0], TagOp::DeleteTag { name: "x".to_string() });
     }
 
-    // Add new tag
-    db.tags.push(Tag { name, color });
+    #[test]
+    fn test_smart_collections() {
+        let _env = TestEnv::new();
+        for name in ["milestone", "auto", "base"] {
+            create_tag(name.to_string(), "#FF0000".to_string(), None, None).unwrap();
+        }
+        let tags = |names: &[&str]| names.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+        add_tags_to_backup("Survival", "a.tar.gz", tags(&["milestone"])).unwrap();
+        add_tags_to_backup("Survival", "b.tar.gz", tags(&["milestone", "auto"])).unwrap();
+        add_tags_to_backup("Survival", "c.tar.gz", tags(&["auto"])).unwrap();
+        add_tags_to_backup("Builder", "d.tar.gz", tags(&["milestone", "base"])).unwrap();
+        add_tags_to_save("Survival", tags(&["milestone"])).unwrap();
+
+        let collection = SmartCollection {
+            name: "Milestones".to_string(),
+            include_tags: tags(&["MILESTONE"]),
+            exclude_tags: tags(&["auto"]),
+            target_kind: Some(TargetKind::Backup),
+            save_name: Some(SaveKey::new("Survival")),
+        };
+        save_collection(collection.clone()).unwrap();
+        assert_eq!(get_collections().unwrap()[0].include_tags, vec!["milestone"]);
 
-    save_tags_db(&db)
-}
+        // b carries both an included and an excluded tag: the exclusion wins
+        assert_eq!(evaluate_collection("milestones").unwrap(), vec![TagTarget::backup("Survival", "a.tar.gz")]);
 
-/// Deletes a tag and removes all its associations.
-///
-/// # Arguments
-/// * `name` - Tag name to delete
-///
-/// # Returns
-/// `TagsResult<()>` - Ok(()) on success
-pub fn delete_tag(name: String) -> TagsResult<()> {
-    let mut db = load_tags_db()?;
+        // Without filters, every target carrying all included tags and no excluded one
+        save_collection(SmartCollection { target_kind: None, save_name: None, ..collection.clone() }).unwrap();
+        assert_eq!(get_collections().unwrap().len(), 1);
+        assert_eq!(
+            evaluate_collection("Milestones").unwrap(),
+            vec![
+                TagTarget::backup("Survival", "a.tar.gz"),
+                TagTarget::backup("Builder", "d.tar.gz"),
+                TagTarget::save("Survival"),
+            ]
+        );
+
+        // Excluding alone lists every other tagged target; renames carry over
+        save_collection(SmartCollection {
+            name: "Not auto".to_string(),
+            include_tags: Vec::new(),
+            exclude_tags: tags(&["auto", "base"]),
+            target_kind: Some(TargetKind::Backup),
+            save_name: None,
+        })
+        .unwrap();
+        rename_tag("auto".to_string(), "automatic".to_string()).unwrap();
+        assert_eq!(evaluate_collection("not auto").unwrap(), vec![TagTarget::backup("Survival", "a.tar.gz")]);
+        assert_eq!(get_collections().unwrap()[1].exclude_tags, vec!["automatic", "base"]);
 
-    // Check if tag exists
-    if !db.tags.iter().any(|t| t.name == name) {
-        return Err(TagsError::TagNotFound(name));
+        assert!(matches!(
+            save_collection(SmartCollection { include_tags: tags(&["missing"]), ..collection.clone() }),
+            Err(TagsError::TagNotFound(_))
+        ));
+        assert!(matches!(
+            save_collection(SmartCollection { name: " ".to_string(), ..collection }),
+            Err(TagsError::InvalidName(_))
+        ));
+        assert!(delete_collection("MILESTONES").unwrap());
+        assert!(!delete_collection("Milestones").unwrap());
+        assert!(matches!(
+            evaluate_collection("Milestones"),
+            Err(TagsError::CollectionNotFound(_))
+        ));
     }
 
-    // Remove tag
-    db.tags.retain(|t| t.name != name);
+    #[test]
+    fn test_search_tags_ranking() {
+        let _env = TestEnv::new();
+        for name in ["super-important", "Important", "wimpy", "ice map", "other", "imp"] {
+            create_tag(name.to_string(), "#FF0000".to_string(), None, None).unwrap();
+        }
+        add_tag_alias("other".to_string(), "impromptu".to_string()).unwrap();
+        let search = |query: &str, limit: usize| {
+            search_tags(query, limit).unwrap().into_iter().map(|t| t.name).collect::<Vec<_>>()
+        };
+
+        // Exact, then prefixes (aliases included), then substrings, then in-order characters
+        assert_eq!(
+            search("IMP", 10),
+            vec!["imp", "Important", "other", "wimpy", "super-important", "ice map"]
+        );
+        assert_eq!(search("imp", 2), vec!["imp", "Important"]);
+        assert_eq!(search("sprimp", 10), vec!["super-important"]);
+        assert!(search("xyz", 10).is_empty());
 
-    // Remove tag from all associations
-    for association in &mut db.associations {
-        association.tag_names.retain(|t| t != &name);
+        // A blank query lists pinned tags, then the most used
+        set_tag_pinned("wimpy".to_string(), true).unwrap();
+        add_tags_to_backup("Survival", "a.tar.gz", vec!["ice map".to_string(), "other".to_string()]).unwrap();
+        add_tags_to_backup("Survival", "b.tar.gz", vec!["ice map".to_string()]).unwrap();
+        assert_eq!(search(" ", 3), vec!["wimpy", "ice map", "other"]);
     }
 
-    // Clean up empty associations
-    db.associations.retain(|a| !a.tag_names.is_empty());
+    #[test]
+    fn test_deleted_tag_is_restored_to_its_targets() {
+        let env = TestEnv::with_paths();
+        std::fs::create_dir_all(env.save_dir().join("Survival/A")).unwrap();
+        let backup_dir = env.backup_dir().join("Survival/A");
+        std::fs::create_dir_all(&backup_dir).unwrap();
+        std::fs::write(backup_dir.join("kept.tar.gz"), b"archive").unwrap();
 
-    save_tags_db(&db)
-}
+        create_tag("keep".to_string(), "#FF0000".to_string(), None, None).unwrap();
+        create_tag("other".to_string(), "#00FF00".to_string(), None, None).unwrap();
+        add_tags_to_backup("Survival/A", "kept.tar.gz", vec!["keep".to_string(), "other".to_string()]).unwrap();
+        add_tags_to_backup("Survival/A", "gone.tar.gz", vec!["keep".to_string()]).unwrap();
+        add_tags_to_save("Survival/A", vec!["keep".to_string()]).unwrap();
+        set_tag_pinned("keep".to_string(), true).unwrap();
 
-/// Returns all defined tags.
-///
-/// # Returns
-/// `TagsResult<Vec<Tag>>` - List of all tags
-pub fn get_all_tags() -> TagsResult<Vec<Tag>> {
-    let db = load_tags_db()?;
-    Ok(db.tags)
-}
+        delete_tag("keep".to_string()).unwrap();
+        assert!(load_tags_db().unwrap().tag("keep").is_none());
+        assert_eq!(get_backup_tags("Survival/A", "kept.tar.gz").unwrap().len(), 1);
+        let trash = get_deleted_tags().unwrap();
+        assert_eq!(trash.len(), 1);
+        assert_eq!(trash[0].targets.len(), 3);
 
-/// Helper to find or create an association for a target.
-fn find_association_mut<'a>(db: &'a mut TagsDatabase, target: &TagTarget) -> Option<&'a mut TagAssociation> {
-    db.associations.iter_mut().find(|a| &a.target == target)
-}
+        // The trash survives a reload; the backup deleted meanwhile is skipped
+        invalidate_db_cache(&get_tags_db_path().unwrap());
+        assert_eq!(restore_deleted_tag("KEEP".to_string()).unwrap(), 2);
+        assert!(load_tags_db().unwrap().tag("keep").unwrap().pinned);
+        let names = |tags: Vec<Tag>| tags.into_iter().map(|t| t.name).collect::<Vec<_>>();
+        assert_eq!(names(get_backup_tags("Survival/A", "kept.tar.gz").unwrap()), vec!["other", "keep"]);
+        assert!(get_backup_tags("Survival/A", "gone.tar.gz").unwrap().is_empty());
+        assert_eq!(names(get_save_tags("Survival/A").unwrap()), vec!["keep"]);
+        assert!(get_deleted_tags().unwrap().is_empty());
+        assert!(matches!(
+            restore_deleted_tag("keep".to_string()),
+            Err(TagsError::TagNotFound(_))
+        ));
 
-/// Adds tags to a backup.
-///
-/// # Arguments
-/// * `save_name` - Save name
-/// * `backup_name` - Backup name
-/// * `tags` - Tag names to add
-///
-/// # Returns
-/// `TagsResult<()>` - Ok(()) on success
-pub fn add_tags_to_backup(save_name: &str, backup_name: &str, tags: Vec<String>) -> TagsResult<()> {
-    if tags.is_empty() {
-        return Ok(());
+        // A name in the trash can be taken by a new tag, which purges the entry
+        delete_tag("keep".to_string()).unwrap();
+        create_tag("Keep".to_string(), "#0000FF".to_string(), None, None).unwrap();
+        assert!(get_deleted_tags().unwrap().is_empty());
+        assert!(get_save_tags("Survival/A").unwrap().is_empty());
+
+        delete_tag("other".to_string()).unwrap();
+        assert!(purge_deleted_tags(1).unwrap().is_empty());
+        assert_eq!(purge_deleted_tags(0).unwrap(), vec!["other"]);
+        assert!(get_deleted_tags().unwrap().is_empty());
     }
 
-    let mut db = load_tags_db()?;
-    let target = TagTarget::Backup {
-        save_name: save_name.to_string(),
-        backup_name: backup_name.to_string(),
-    };
+    #[test]
+    fn test_tag_aliases() {
+        let _env = TestEnv::new();
+        create_tag("keep".to_string(), "#FF0000".to_string(), None, None).unwrap();
+        create_tag("modded".to_string(), "#00FF00".to_string(), None, None).unwrap();
+        add_tag_alias("keep".to_string(), " Important ".to_string()).unwrap();
+        add_tag_alias("KEEP".to_string(), "save-me".to_string()).unwrap();
 
-    // Validate all tags exist
-    for tag in &tags {
-        if !db.tags.iter().any(|t| &t.name == tag) {
-            return Err(TagsError::TagNotFound(tag.clone()));
+        // Aliases collide with names and other aliases, ignoring case
+        for taken in ["Modded", "important", "KEEP"] {
+            assert!(
+                matches!(add_tag_alias("modded".to_string(), taken.to_string()), Err(TagsError::DuplicateTag(_))),
+                "{}",
+                taken
+            );
         }
-    }
+        assert!(matches!(
+            create_tag("Save-Me".to_string(), "#0000FF".to_string(), None, None),
+            Err(TagsError::DuplicateTag(_))
+        ));
+        assert!(matches!(
+            add_tag_alias("missing".to_string(), "other".to_string()),
+            Err(TagsError::TagNotFound(_))
+        ));
+        assert!(matches!(add_tag_alias("keep".to_string(), " ".to_string()), Err(TagsError::InvalidName(_))));
 
-    // Find or create association
-    let association = match find_association_mut(&mut db, &target) {
-        Some(a) => a,
-        None => {
-            db.associations.push(TagAssociation {
-                target: target.clone(),
-                tag_names: Vec::new(),
-            });
-            db.associations.last_mut().unwrap()
-        }
-    };
+        // Associations store the tag's name
+        add_tags_to_backup("Survival/A", "1.tar.gz", vec!["important".to_string(), "keep".to_string()]).unwrap();
+        add_tags_to_save("Survival/A", vec!["SAVE-ME".to_string()]).unwrap();
+        let db = load_tags_db().unwrap();
+        assert_eq!(db.association(&TagTarget::backup("Survival/A", "1.tar.gz")).unwrap().tag_names, vec!["keep"]);
+        assert_eq!(db.association(&TagTarget::save("Survival/A")).unwrap().tag_names, vec!["keep"]);
+        assert_eq!(find_backups_by_tag("important").unwrap().len(), 1);
 
-    // Add tags (avoid duplicates)
-    for tag in tags {
-        if !association.tag_names.contains(&tag) {
-            association.tag_names.push(tag);
-        }
-    }
+        let all = get_all_tags(TagSort::Position).unwrap();
+        assert_eq!(all[0].aliases, vec!["Important", "save-me"]);
+        assert!(all[1].aliases.is_empty());
 
-    save_tags_db(&db)
-}
+        assert!(remove_tag_alias("keep".to_string(), "IMPORTANT".to_string()).unwrap());
+        assert!(!remove_tag_alias("keep".to_string(), "important".to_string()).unwrap());
+        assert!(matches!(
+            add_tags_to_backup("Survival/A", "2.tar.gz", vec!["important".to_string()]),
+            Err(TagsError::TagNotFound(_))
+        ));
+        // The freed alias can be a tag name again
+        create_tag("important".to_string(), "#0000FF".to_string(), None, None).unwrap();
 
-/// Removes tags from a backup.
-///
-/// # Arguments
-/// * `save_name` - Save name
-/// * `backup_name` - Backup name
-/// * `tags` - Tag names to remove
-///
-/// # Returns
-/// `TagsResult<()>` - Ok(()) on success
-pub fn remove_tags_from_backup(save_name: &str, backup_name: &str, tags: Vec<String>) -> TagsResult<()> {
-    if tags.is_empty() {
-        return Ok(());
+        // Renaming a tag to one of its aliases drops the alias
+        rename_tag("keep".to_string(), "Save-Me".to_string()).unwrap();
+        let renamed = get_all_tags(TagSort::Position).unwrap().remove(0);
+        assert_eq!(renamed.name, "Save-Me");
+        assert!(renamed.aliases.is_empty());
     }
 
-    let db = load_tags_db();
-    if db.is_err() {
-        return Ok(()); // If we can't load db, no tags to remove
-    }
-    let mut db = db.unwrap();
+    #[test]
+    fn test_concurrent_writes_are_not_lost() {
+        let env = TestEnv::new();
+        create_tag("keep".to_string(), "#FF0000".to_string(), None, None).unwrap();
+        const THREADS: usize = 8;
+        const PER_THREAD: usize = 25;
 
-    let target = TagTarget::Backup {
-        save_name: save_name.to_string(),
-        backup_name: backup_name.to_string(),
-    };
+        let handles: Vec<_> = (0..THREADS)
+            .map(|t| {
+                let config_dir = env.config_dir();
+                std::thread::spawn(move || {
+                    crate::config::test_config_dir::set(Some(config_dir));
+                    for i in 0..PER_THREAD {
+                        let backup_name = format!("{}-{}.tar.gz", t, i);
+                        add_tags_to_backup("Survival/A", &backup_name, vec!["keep".to_string()]).unwrap();
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
 
-    if let Some(association) = find_association_mut(&mut db, &target) {
-        association.tag_names.retain(|t| !tags.contains(t));
+        let backups = find_backups_by_tag("keep").unwrap();
+        assert_eq!(backups.len(), THREADS * PER_THREAD);
     }
 
-    // Clean up empty associations
-    db.associations.retain(|a| !a.tag_names.is_empty());
+    #[test]
+    fn test_rename_backup_target() {
+        let _env = TestEnv::new();
+        create_tag("keep".to_string(), "#FF0000".to_string(), None, None).unwrap();
+        create_tag("modded".to_string(), "#00FF00".to_string(), None, None).unwrap();
+        add_tags_to_backup("Survival/A", "a.tar.gz", vec!["keep".to_string(), "modded".to_string()]).unwrap();
+        let names = |backup: &str| {
+            get_backup_tags("Survival/A", backup)
+                .unwrap()
+                .into_iter()
+                .map(|t| t.name)
+                .collect::<Vec<_>>()
+        };
 
-    save_tags_db(&db)
-}
+        rename_backup_target("Survival/A", "a.tar.gz", "b.tar.gz").unwrap();
+        assert!(names("a.tar.gz").is_empty());
+        assert_eq!(names("b.tar.gz"), vec!["keep", "modded"]);
+        rename_backup_target("Survival/A", "b.tar.gz", "a.tar.gz").unwrap();
+        assert_eq!(names("a.tar.gz"), vec!["keep", "modded"]);
+        assert_eq!(load_tags_db().unwrap().associations.len(), 1);
 
-/// Returns all tags for a backup.
-///
-/// # Arguments
-/// * `save_name` - Save name
-/// * `backup_name` - Backup name
-///
-/// # Returns
-/// `TagsResult<Vec<Tag>>` - List of tags
-pub fn get_backup_tags(save_name: &str, backup_name: &str) -> TagsResult<Vec<Tag>> {
-    let db = load_tags_db()?;
-    let target = TagTarget::Backup {
-        save_name: save_name.to_string(),
-        backup_name: backup_name.to_string(),
-    };
+        // Tags already on the new name are kept, without duplicates
+        add_tags_to_backup("Survival/A", "c.tar.gz", vec!["modded".to_string()]).unwrap();
+        rename_backup_target("Survival/A", "a.tar.gz", "c.tar.gz").unwrap();
+        assert_eq!(names("c.tar.gz"), vec!["modded", "keep"]);
+        assert_eq!(load_tags_db().unwrap().associations.len(), 1);
 
-    let association = match db.associations.iter().find(|a| &a.target == &target) {
-        Some(a) => a,
-        None => return Ok(Vec::new()),
-    };
+        // Untagged backups leave the database alone
+        rename_backup_target("Survival/A", "untagged.tar.gz", "other.tar.gz").unwrap();
+        assert!(names("other.tar.gz").is_empty());
+    }
 
-    let mut result = Vec::new();
-    for tag_name in &association.tag_names {
-        if let Some(tag) = db.tags.iter().find(|t| &t.name == tag_name) {
-            result.push(tag.clone());
+    #[test]
+    fn test_rename_save_target_merges_on_collision() {
+        let _env = TestEnv::new();
+        for name in ["keep", "modded", "hardcore"] {
+            create_tag(name.to_string(), "#FF0000".to_string(), None, None).unwrap();
         }
-    }
+        add_tags_to_save("Survival/Old", vec!["keep".to_string(), "modded".to_string()]).unwrap();
+        add_tags_to_save("Sandbox/New", vec!["hardcore".to_string(), "keep".to_string()]).unwrap();
+        add_tags_to_backup("Survival/Old", "a.tar.gz", vec!["keep".to_string()]).unwrap();
+        let names = |path: &str| {
+            get_save_tags(path).unwrap().into_iter().map(|t| t.name).collect::<Vec<_>>()
+        };
 
-    Ok(result)
-}
+        rename_save_target("Survival\\Old\\", "Sandbox/New").unwrap();
+        assert!(names("Survival/Old").is_empty());
+        assert_eq!(names("Sandbox/New"), vec!["hardcore", "keep", "modded"]);
+        // Backup tags stay with the backup directory
+        assert_eq!(get_backup_tags("Survival/Old", "a.tar.gz").unwrap().len(), 1);
+        assert_eq!(load_tags_db().unwrap().associations.len(), 2);
 
-/// Adds tags to a save.
-///
-/// # Arguments
-/// * `relative_path` - Save relative path
-/// * `tags` - Tag names to add
-///
-/// # Returns
-/// `TagsResult<()>` - Ok(()) on success
-pub fn add_tags_to_save(relative_path: &str, tags: Vec<String>) -> TagsResult<()> {
-    if tags.is_empty() {
-        return Ok(());
+        rename_save_target("Sandbox/New", "Survival/Renamed").unwrap();
+        assert_eq!(names("Survival/Renamed"), vec!["hardcore", "keep", "modded"]);
+        assert_eq!(find_saves_by_tag("modded").unwrap(), vec!["Survival/Renamed"]);
     }
 
-    let mut db = load_tags_db()?;
-    let target = TagTarget::Save {
-        relative_path: relative_path.to_string(),
-    };
-
-    // Validate all tags exist
-    for tag in &tags {
-        if !db.tags.iter().any(|t| &t.name == tag) {
-            return Err(TagsError::TagNotFound(tag.clone()));
+    #[test]
+    fn test_orphaned_associations_skip_unchecked_targets() {
+        let mut db = TagsDatabase::default();
+        db.create_tag("keep".to_string(), "#FF0000".to_string(), None, None).unwrap();
+        for target in [
+            TagTarget::backup("Survival/A", "old.tar.gz"),
+            TagTarget::backup("Survival/A", "new.tar.gz"),
+            TagTarget::backup("Survival/B", "old.tar.gz"),
+            TagTarget::save("Survival/A"),
+        ] {
+            db.add_tags_to(&target, vec!["keep".to_string()]).unwrap();
         }
+
+        // Only Survival/A's backups were listed, and saves weren't
+        let mut existing = ExistingTargets::default();
+        existing.backups.insert(
+            SaveKey::new("Survival/A"),
+            [BackupKey::new("new.tar.gz")].into_iter().collect(),
+        );
+        let report = db.remove_orphaned_associations(&existing);
+        assert_eq!(report.backups, vec![("Survival/A".to_string(), "old.tar.gz".to_string())]);
+        assert!(report.saves.is_empty());
+        assert_eq!(db.associations.len(), 3);
+        assert!(db.association(&TagTarget::backup("Survival/A", "old.tar.gz")).is_none());
+
+        existing.saves = Some(HashSet::new());
+        assert_eq!(db.remove_orphaned_associations(&existing).saves, vec!["Survival/A"]);
+        assert_eq!(db.associations.len(), 2);
     }
 
-    // Find or create association
-    let association = match find_association_mut(&mut db, &target) {
-        Some(a) => a,
-        None => {
-            db.associations.push(TagAssociation {
-                target: target.clone(),
-                tag_names: Vec::new(),
-            });
-            db.associations.last_mut().unwrap()
-        }
-    };
+    #[test]
+    fn test_prune_orphaned_tags() {
+        let env = TestEnv::with_paths();
+        std::fs::create_dir_all(env.save_dir().join("Survival/A/map")).unwrap();
+        let backup_dir = env.backup_dir().join("Survival/A");
+        std::fs::create_dir_all(&backup_dir).unwrap();
+        std::fs::write(backup_dir.join("kept.tar.gz"), b"archive").unwrap();
+        create_tag("keep".to_string(), "#FF0000".to_string(), None, None).unwrap();
+        let tag = || vec!["keep".to_string()];
+        add_tags_to_backup("Survival/A", "kept.tar.gz", tag()).unwrap();
+        add_tags_to_backup("Survival/A", "deleted.tar.gz", tag()).unwrap();
+        add_tags_to_backup("Survival/Gone", "deleted.tar.gz", tag()).unwrap();
+        add_tags_to_save("Survival/A", tag()).unwrap();
+        add_tags_to_save("Survival/Gone", tag()).unwrap();
+
+        // Unavailable roots orphan nothing
+        let moved = env.root().join("unmounted");
+        std::fs::rename(env.backup_dir(), &moved).unwrap();
+        std::fs::rename(env.save_dir(), env.root().join("unmounted_saves")).unwrap();
+        assert!(prune_orphaned_tags(false).unwrap().is_empty());
+        std::fs::rename(&moved, env.backup_dir()).unwrap();
+        std::fs::rename(env.root().join("unmounted_saves"), env.save_dir()).unwrap();
 
-    // Add tags (avoid duplicates)
-    for tag in tags {
-        if !association.tag_names.contains(&tag) {
-            association.tag_names.push(tag);
+        let report = prune_orphaned_tags(false).unwrap();
+        assert_eq!(
+            report.backups,
+            vec![
+                ("Survival/A".to_string(), "deleted.tar.gz".to_string()),
+                ("Survival/Gone".to_string(), "deleted.tar.gz".to_string()),
+            ]
+        );
+        assert_eq!(report.saves, vec!["Survival/Gone"]);
+        assert!(!report.needs_confirmation);
+        assert_eq!(get_backup_tags("Survival/A", "kept.tar.gz").unwrap().len(), 1);
+        assert_eq!(get_save_tags("Survival/A").unwrap().len(), 1);
+        assert!(get_save_tags("Survival/Gone").unwrap().is_empty());
+        assert!(prune_orphaned_tags(false).unwrap().is_empty());
+
+        // Large removals wait for confirmation
+        for i in 0..=MAX_UNCONFIRMED_ORPHANS {
+            add_tags_to_backup("Survival/A", &format!("{}.tar.gz", i), tag()).unwrap();
         }
+        let pending = prune_orphaned_tags(false).unwrap();
+        assert!(pending.needs_confirmation);
+        assert_eq!(pending.len(), MAX_UNCONFIRMED_ORPHANS + 1);
+        assert_eq!(find_backups_by_tag("keep").unwrap().len(), MAX_UNCONFIRMED_ORPHANS + 2);
+        prune_orphaned_backup_tags_best_effort("Survival/A", ["kept.tar.gz"]);
+        assert_eq!(find_backups_by_tag("keep").unwrap().len(), MAX_UNCONFIRMED_ORPHANS + 2);
+
+        let confirmed = prune_orphaned_tags(true).unwrap();
+        assert!(!confirmed.needs_confirmation);
+        assert_eq!(confirmed.len(), MAX_UNCONFIRMED_ORPHANS + 1);
+        assert_eq!(find_backups_by_tag("keep").unwrap().len(), 1);
     }
 
-    save_tags_db(&db)
-}
+    #[test]
+    fn test_auto_tag_rules() {
+        let env = TestEnv::new();
+        create_tag("auto".to_string(), "#FF0000".to_string(), None, None).unwrap();
+        create_tag("nightly".to_string(), "#00FF00".to_string(), None, None).unwrap();
+        assert!(matches!(
+            set_auto_tag_rule(OperationTrigger::Scheduled, vec!["missing".to_string()]),
+            Err(TagsError::TagNotFound(_))
+        ));
 
-/// Removes tags from a save.
-///
-/// # Arguments
-/// * `relative_path` - Save relative path
-/// * `tags` - Tag names to remove
-///
-/// # Returns
-/// `TagsResult<()>` - Ok(()) on success
-pub fn remove_tags_from_save(relative_path: &str, tags: Vec<String>) -> TagsResult<()> {
-    if tags.is_empty() {
-        return Ok(());
-    }
+        set_auto_tag_rule(OperationTrigger::Scheduled, vec!["Auto".to_string(), "auto".to_string()]).unwrap();
+        set_auto_tag_rule(OperationTrigger::Manual, vec!["nightly".to_string()]).unwrap();
+        set_auto_tag_rule(OperationTrigger::Scheduled, vec!["auto".to_string(), "nightly".to_string()]).unwrap();
+        assert_eq!(
+            get_auto_tag_rules().unwrap(),
+            vec![
+                AutoTagRule { trigger: OperationTrigger::Manual, tag_names: vec!["nightly".to_string()] },
+                AutoTagRule {
+                    trigger: OperationTrigger::Scheduled,
+                    tag_names: vec!["auto".to_string(), "nightly".to_string()],
+                },
+            ]
+        );
 
-    let db = load_tags_db();
-    if db.is_err() {
-        return Ok(());
-    }
-    let mut db = db.unwrap();
+        let target = TagTarget::backup("Survival/A", "a1.tar.gz");
+        assert_eq!(apply_auto_tags(&target, OperationTrigger::Scheduled).unwrap(), vec!["auto", "nightly"]);
+        assert!(apply_auto_tags(&TagTarget::backup("Survival/A", "a2.tar.gz"), OperationTrigger::System)
+            .unwrap()
+            .is_empty());
+        assert!(get_backup_tags("Survival/A", "a2.tar.gz").unwrap().is_empty());
 
-    let target = TagTarget::Save {
-        relative_path: relative_path.to_string(),
-    };
+        // Renames follow into rules; deleted tags are skipped
+        rename_tag("nightly".to_string(), "night".to_string()).unwrap();
+        delete_tag("auto".to_string()).unwrap();
+        let target = TagTarget::backup("Survival/A", "a3.tar.gz");
+        assert_eq!(apply_auto_tags(&target, OperationTrigger::Scheduled).unwrap(), vec!["night"]);
 
-    if let Some(association) = find_association_mut(&mut db, &target) {
-        association.tag_names.retain(|t| !tags.contains(t));
+        assert!(delete_auto_tag_rule(OperationTrigger::Manual).unwrap());
+        assert!(!delete_auto_tag_rule(OperationTrigger::Manual).unwrap());
+        set_auto_tag_rule(OperationTrigger::Scheduled, Vec::new()).unwrap();
+        assert!(get_auto_tag_rules().unwrap().is_empty());
+        let written = std::fs::read_to_string(env.config_dir().join(TAGS_DB_FILE_NAME)).unwrap();
+        assert!(!written.contains("auto_tag_rules"));
     }
 
-    // Clean up empty associations
-    db.associations.retain(|a| !a.tag_names.is_empty());
-
-    save_tags_db(&db)
-}
+    #[test]
+    fn test_new_backup_gets_auto_tags_of_its_trigger() {
+        let env = TestEnv::with_paths();
+        let save_dir = env.save_dir().join("Survival/A");
+        std::fs::create_dir_all(save_dir.join("map")).unwrap();
+        std::fs::write(save_dir.join("save.bin"), b"game state").unwrap();
+        create_tag("auto".to_string(), "#FF0000".to_string(), None, None).unwrap();
+        set_auto_tag_rule(OperationTrigger::Scheduled, vec!["auto".to_string()]).unwrap();
 
-/// Returns all tags for a save.
-///
-/// # Arguments
-/// * `relative_path` - Save relative path
-///
-/// # Returns
-/// `TagsResult<Vec<Tag>>` - List of tags
-pub fn get_save_tags(relative_path: &str) -> TagsResult<Vec<Tag>> {
-    let db = load_tags_db()?;
-    let target = TagTarget::Save {
-        relative_path: relative_path.to_string(),
-    };
+        // What operations::run_backup does once the archive exists
+        let backup = |trigger| {
+            let name = crate::backup::create_backup("Survival/A").unwrap().backup_name;
+            apply_auto_tags_best_effort("Survival/A", &name, trigger);
+            get_backup_tags("Survival/A", &name).unwrap().into_iter().map(|t| t.name).collect::<Vec<_>>()
+        };
+        assert_eq!(backup(OperationTrigger::Scheduled), vec!["auto"]);
+        // Backup names have one-second resolution
+        std::thread::sleep(std::time::Duration::from_millis(1100));
+        assert!(backup(OperationTrigger::Manual).is_empty());
 
-    let association = match db.associations.iter().find(|a| &a.target == &target) {
-        Some(a) => a,
-        None => return Ok(Vec::new()),
-    };
+        // A deleted rule tag is skipped without recording a failure
+        delete_tag("auto".to_string()).unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(1100));
+        assert!(backup(OperationTrigger::Scheduled).is_empty());
+        let history = std::fs::read_to_string(env.config_dir().join("history.jsonl")).unwrap_or_default();
+        assert!(!history.contains("Suppressed"), "{}", history);
+    }
 
-    let mut result = Vec::new();
-    for tag_name in &association.tag_names {
-        if let Some(tag) = db.tags.iter().find(|t| &t.name == tag_name) {
-            result.push(tag.clone());
+    #[test]
+    fn test_reorder_tags() {
+        let _env = TestEnv::new();
+        for name in ["a", "b", "c"] {
+            create_tag(name.to_string(), "#FF0000".to_string(), None, None).unwrap();
         }
+        let names = || get_all_tags(TagSort::Position).unwrap().into_iter().map(|t| t.name).collect::<Vec<_>>();
+        assert_eq!(names(), vec!["a", "b", "c"]);
+
+        reorder_tags(vec!["c".to_string(), "a".to_string(), "b".to_string()]).unwrap();
+        assert_eq!(names(), vec!["c", "a", "b"]);
+        create_tag("d".to_string(), "#FF0000".to_string(), None, None).unwrap();
+        assert_eq!(names(), vec!["c", "a", "b", "d"]);
+
+        let order = |names: &[&str]| names.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+        assert!(matches!(
+            reorder_tags(order(&["c", "a", "b", "x"])),
+            Err(TagsError::TagNotFound(name)) if name == "x"
+        ));
+        assert!(matches!(reorder_tags(order(&["c", "a", "b"])), Err(TagsError::InvalidOrder(_))));
+        assert!(matches!(
+            reorder_tags(order(&["c", "a", "b", "d", "a"])),
+            Err(TagsError::InvalidOrder(_))
+        ));
+        assert_eq!(names(), vec!["c", "a", "b", "d"]);
     }
 
-    Ok(result)
-}
+    #[test]
+    fn test_legacy_tags_sort_by_name() {
+        let env = TestEnv::new();
+        std::fs::create_dir_all(env.config_dir()).unwrap();
+        std::fs::write(
+            env.config_dir().join(TAGS_DB_FILE_NAME),
+            r##"{"tags": [{"name": "zed", "color": "#FF0000"}, {"name": "alpha", "color": "#00FF00"}]}"##,
+        )
+        .unwrap();
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use serial_test::serial;
-    use tempfile::TempDir;
+        let tags = get_all_tags(TagSort::Position).unwrap();
+        assert_eq!(tags[0].name, "alpha");
+        assert_eq!(tags[1].name, "zed");
+        assert!(tags.iter().all(|t| t.sort_index == 0));
 
-    /// Helper to create a temporary config directory for testing
-    fn setup_temp_config_dir() -> TempDir {
-        TempDir::new().unwrap()
+        // New tags go after every legacy tag
+        create_tag("new".to_string(), "#0000FF".to_string(), None, None).unwrap();
+        assert_eq!(get_all_tags(TagSort::Position).unwrap()[2].name, "new");
     }
 
     #[test]
-    fn test_validate_color_valid() {
-        assert!(validate_color("#FF5733").is_ok());
-        assert!(validate_color("#F53").is_ok());
-        assert!(validate_color("#FF5733AA").is_ok());
-        assert!(validate_color("#abc").is_ok());
+    fn test_reads_use_cache_until_file_changes() {
+        let env = TestEnv::new();
+        create_tag("keep".to_string(), "#FF0000".to_string(), None, None).unwrap();
+        add_tags_to_save("Survival/A", vec!["keep".to_string()]).unwrap();
+        assert_eq!(get_save_tags("Survival/A").unwrap().len(), 1);
+
+        // Same size and modification time: served from the cache without parsing
+        let path = env.config_dir().join(TAGS_DB_FILE_NAME);
+        let metadata = std::fs::metadata(&path).unwrap();
+        let garbage = "x".repeat(metadata.len() as usize);
+        std::fs::write(&path, &garbage).unwrap();
+        std::fs::File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(metadata.modified().unwrap())
+            .unwrap();
+        assert_eq!(get_save_tags("Survival/A").unwrap().len(), 1);
+
+        // Another process rewriting the file is picked up
+        std::fs::write(&path, r##"{"tags": [{"name": "other", "color": "#00FF00"}]}"##).unwrap();
+        assert!(get_save_tags("Survival/A").unwrap().is_empty());
+        assert_eq!(get_all_tags(TagSort::Position).unwrap()[0].name, "other");
+
+        // Writes through this module are visible immediately
+        create_tag("new".to_string(), "#0000FF".to_string(), None, None).unwrap();
+        assert_eq!(get_all_tags(TagSort::Position).unwrap().len(), 2);
     }
 
     #[test]
-    fn test_validate_color_invalid() {
-        assert!(validate_color("FF5733").is_err()); // Missing #
-        assert!(validate_color("#FF5").is_err()); // Invalid length
-        assert!(validate_color("#FF57333").is_err()); // Invalid length
-        assert!(validate_color("#GG5733").is_err()); // Invalid hex
+    fn test_interrupted_write_is_ignored() {
+        let env = TestEnv::new();
+        create_tag("keep".to_string(), "#FF0000".to_string(), None, None).unwrap();
+
+        // A crash mid-write leaves a truncated temp file next to the intact database
+        let tmp_path = env.config_dir().join(format!("{}.tmp", TAGS_DB_FILE_NAME));
+        std::fs::write(&tmp_path, r#"{"tags": [{"name": "ke"#).unwrap();
+        assert_eq!(get_all_tags(TagSort::Position).unwrap().len(), 1);
+
+        create_tag("later".to_string(), "#00FF00".to_string(), None, None).unwrap();
+        assert!(!tmp_path.exists());
+        let written = std::fs::read_to_string(env.config_dir().join(TAGS_DB_FILE_NAME)).unwrap();
+        let db: TagsDatabase = serde_json::from_str(&written).unwrap();
+        assert_eq!(db.tags.len(), 2);
     }
 
     #[test]
-    #[serial]
-    fn test_tags_database_default() {
-        let db = TagsDatabase::default();
-        assert_eq!(db.tags.len(), 0);
-        assert_eq!(db.associations.len(), 0);
+    fn test_tags_db_without_descriptions() {
+        let env = TestEnv::new();
+        // tags.json as written before tags had descriptions
+        let old = r##"{
+            "tags": [{ "name": "keep", "color": "#FF0000" }],
+            "associations": [{ "target": { "type": "Save", "relative_path": "Survival/A" }, "tag_names": ["keep"] }],
+            "key_version": 1
+        }"##;
+        std::fs::write(env.config_dir().join(TAGS_DB_FILE_NAME), old).unwrap();
+
+        let tags = get_all_tags(TagSort::Position).unwrap();
+        assert_eq!(tags[0].description, None);
+        assert_eq!(tags[0].icon, None);
+        assert_eq!(tags[0].group, None);
+        assert_eq!(get_save_tags("Survival/A").unwrap()[0].name, "keep");
+
+        // Round trip: untouched tags are written back without the field
+        update_tag_description("keep".to_string(), Some("  pre-helicopter-event backups ".to_string())).unwrap();
+        create_tag("plain".to_string(), "#00FF00".to_string(), None, None).unwrap();
+        let written = std::fs::read_to_string(env.config_dir().join(TAGS_DB_FILE_NAME)).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&written).unwrap();
+        assert_eq!(value["tags"][0]["description"], "pre-helicopter-event backups");
+        assert!(value["tags"][1].get("description").is_none());
+
+        update_tag_description("keep".to_string(), Some(" ".to_string())).unwrap();
+        assert_eq!(get_all_tags(TagSort::Position).unwrap()[0].description, None);
+        assert!(matches!(
+            update_tag_description("missing".to_string(), None),
+            Err(TagsError::TagNotFound(_))
+        ));
+    }
+
+    /// Builds a database with `tag_count` tags and `association_count` backup associations.
+    fn generate_db(tag_count: usize, association_count: usize) -> TagsDatabase {
+        let mut db = TagsDatabase::default();
+        for i in 0..tag_count {
+            db.add_tag(Tag {
+                name: format!("tag{}", i),
+                color: "#FF0000".to_string(),
+                description: None,
+                icon: None,
+                group: None,
+                aliases: Vec::new(),
+                pinned: false,
+                protected: false,
+                sort_index: i as u32,
+                created_at: None,
+                updated_at: None,
+            });
+        }
+        for i in 0..association_count {
+            let target =
+                TagTarget::backup(&format!("Survival/Save{}", i % 50), &format!("backup{}.tar.gz", i));
+            let association = db.association_or_insert(&target);
+            for j in 0..5 {
+                association.tag_names.push(format!("tag{}", (i + j * 37) % tag_count));
+            }
+        }
+        db
     }
 
     #[test]
-    #[serial]
-    fn test_create_and_get_tag() {
-        // This test uses the actual config directory, so we need to clean up
-        let result = create_tag("important".to_string(), "#FF0000".to_string());
-        assert!(result.is_ok());
+    fn test_indices_follow_mutations() {
+        let mut db = generate_db(20, 100);
+        let target = TagTarget::backup("Survival/Save3", "backup3.tar.gz");
+        let expected: Vec<String> = db.association(&target).unwrap().tag_names.clone();
+        assert_eq!(
+            db.tags_for(&target).iter().map(|t| t.name.clone()).collect::<Vec<_>>(),
+            expected
+        );
 
-        let tags = get_all_tags().unwrap();
-        assert_eq!(tags.len(), 1);
-        assert_eq!(tags[0].name, "important");
-        assert_eq!(tags[0].color, "#FF0000");
+        // Removing a tag shifts positions; lookups must still resolve
+        assert!(db.remove_tag("tag0"));
+        assert!(!db.has_tag("tag0"));
+        assert_eq!(db.tag("tag19").unwrap().name, "tag19");
+        assert!(db.tags_for(&target).iter().all(|t| t.name != "tag0"));
 
-        // Clean up
-        let _ = delete_tag("important".to_string());
+        // Dropping an association keeps the others reachable
+        db.association_mut(&target).unwrap().tag_names.clear();
+        db.remove_empty_associations();
+        assert!(db.association(&target).is_none());
+        let other = TagTarget::backup("Survival/Save4", "backup4.tar.gz");
+        assert_eq!(db.association(&other).unwrap().target, other);
     }
 
     #[test]
-    #[serial]
-    fn test_create_duplicate_tag_fails() {
-        let _ = create_tag("test".to_string(), "#FF0000".to_string());
-        let result = create_tag("test".to_string(), "#00FF00".to_string());
-        assert!(matches!(result, Err(TagsError::DuplicateTag(_))));
+    fn test_serialization_excludes_indices() {
+        let db = generate_db(3, 2);
+        let value = serde_json::to_value(&db).unwrap();
+        let mut keys: Vec<&String> = value.as_object().unwrap().keys().collect();
+        keys.sort();
+        assert_eq!(keys, vec!["associations", "key_version", "schema_version", "tags"]);
 
-        // Clean up
-        let _ = delete_tag("test".to_string());
+        // Round trip rebuilds the indices
+        let mut loaded: TagsDatabase = serde_json::from_value(value.clone()).unwrap();
+        loaded.rebuild_indices();
+        assert_eq!(serde_json::to_value(&loaded).unwrap(), value);
+        assert!(loaded.has_tag("tag2"));
     }
 
     #[test]
-    #[serial]
-    fn test_delete_tag() {
-        let _ = create_tag("to_delete".to_string(), "#FF0000".to_string());
-        assert_eq!(get_all_tags().unwrap().len(), 1);
+    fn test_stored_keys_are_normalized_and_merged() {
+        let json = r##"{
+            "tags": [
+                {"name": "a", "color": "#FF0000"},
+                {"name": "b", "color": "#00FF00"}
+            ],
+            "associations": [
+                {"target": {"type": "Save", "relative_path": "Survival\\Cafe\u0301\\"}, "tag_names": ["a"]},
+                {"target": {"type": "Save", "relative_path": "Survival/Caf\u00e9"}, "tag_names": ["a", "b"]},
+                {"target": {"type": "Backup", "save_name": "Survival/X/", "backup_name": "x.tar.gz"}, "tag_names": ["b"]}
+            ]
+        }"##;
+        let mut db: TagsDatabase = serde_json::from_str(json).unwrap();
+        assert_eq!(db.merge_duplicate_associations(), 1);
+        db.rebuild_indices();
 
-        let result = delete_tag("to_delete".to_string());
-        assert!(result.is_ok());
-        assert_eq!(get_all_tags().unwrap().len(), 0);
+        assert_eq!(db.associations.len(), 2);
+        let names: Vec<String> = db
+            .tags_for(&TagTarget::save("Survival/Caf\u{E9}"))
+            .into_iter()
+            .map(|t| t.name)
+            .collect();
+        assert_eq!(names, vec!["a", "b"]);
+        assert_eq!(db.tags_for(&TagTarget::backup("Survival\\X", "x.tar.gz")).len(), 1);
+
+        // Serialized keys are canonical
+        let value = serde_json::to_value(&db).unwrap();
+        assert_eq!(value["associations"][0]["target"]["relative_path"], "Survival/Caf\u{E9}");
+        assert_eq!(value["associations"][1]["target"]["save_name"], "Survival/X");
     }
 
     #[test]
-    #[serial]
-    fn test_delete_nonexistent_tag_fails() {
-        let result = delete_tag("nonexistent".to_string());
-        assert!(matches!(result, Err(TagsError::TagNotFound(_))));
+    fn test_migrate_tag_keys_runs_once() {
+        let _env = TestEnv::new();
+        let path = get_tags_db_path().unwrap();
+        std::fs::write(
+            &path,
+            r##"{"tags": [{"name": "a", "color": "#FF0000"}], "associations": [
+                {"target": {"type": "Save", "relative_path": "Survival\\MySave"}, "tag_names": ["a"]},
+                {"target": {"type": "Save", "relative_path": "Survival/MySave/"}, "tag_names": ["a"]}
+            ]}"##,
+        )
+        .unwrap();
+
+        let first = migrate_tag_keys();
+        let second = migrate_tag_keys();
+        let stored: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+
+        assert_eq!(first.unwrap(), 1);
+        assert_eq!(second.unwrap(), 0);
+        assert_eq!(stored["key_version"], KEY_VERSION);
+        assert_eq!(stored["associations"].as_array().unwrap().len(), 1);
+        assert_eq!(stored["associations"][0]["target"]["relative_path"], "Survival/MySave");
     }
 
     #[test]
-    #[serial]
-    fn test_add_and_get_backup_tags() {
-        let _ = create_tag("important".to_string(), "#FF0000".to_string());
-        let _ = create_tag("test".to_string(), "#00FF00".to_string());
+    fn test_schema_v0_is_migrated() {
+        let _env = TestEnv::new();
+        let path = get_tags_db_path().unwrap();
+        // tags.json as written before schema versioning, including a null list
+        std::fs::write(
+            &path,
+            r##"{"tags": [{"name": "keep", "color": "#FF0000"}], "associations": null, "key_version": 1}"##,
+        )
+        .unwrap();
 
-        let result = add_tags_to_backup("Survival", "backup1.tar.gz", vec![
-            "important".to_string(),
-            "test".to_string(),
-        ]);
-        assert!(result.is_ok());
+        let db = load_tags_db().unwrap();
+        assert_eq!(db.schema_version, SCHEMA_VERSION);
+        assert_eq!(db.tags[0].name, "keep");
+        assert!(db.associations.is_empty());
 
-        let tags = get_backup_tags("Survival", "backup1.tar.gz").unwrap();
-        assert_eq!(tags.len(), 2);
+        // The next write stamps the current version
+        add_tags_to_save("Survival/A", vec!["keep".to_string()]).unwrap();
+        let stored: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(stored["schema_version"], SCHEMA_VERSION);
+        assert_eq!(stored["associations"].as_array().unwrap().len(), 1);
 
-        // Clean up
-        let _ = delete_tag("important".to_string());
-        let _ = delete_tag("test".to_string());
+        // A missing file is an empty database at the current version
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(load_tags_db().unwrap().schema_version, SCHEMA_VERSION);
     }
 
     #[test]
-    #[serial]
-    fn test_add_and_get_save_tags() {
-        let _ = create_tag("main".to_string(), "#0000FF".to_string());
+    fn test_corrupted_db_is_recovered_from_backup() {
+        let _env = TestEnv::new();
+        let path = get_tags_db_path().unwrap();
+        let store = tags_store().unwrap();
+        create_tag("a".to_string(), "#FF0000".to_string(), None, None).unwrap();
+        create_tag("b".to_string(), "#00FF00".to_string(), None, None).unwrap();
+        assert!(store.backup_path(1).exists());
 
-        let result = add_tags_to_save("Survival/MySave", vec!["main".to_string()]);
-        assert!(result.is_ok());
+        // Left empty by a crash: reads fall back to the copy before the last write
+        std::fs::write(&path, "").unwrap();
+        let names: Vec<String> = get_all_tags(TagSort::Position).unwrap().into_iter().map(|t| t.name).collect();
+        assert_eq!(names, vec!["a"]);
+        let recovery = take_recovery().unwrap();
+        assert_eq!(recovery.backup_file, store.backup_path(1).display().to_string());
+        assert!(serde_json::from_str::<serde_json::Value>(&std::fs::read_to_string(&path).unwrap()).is_ok());
 
-        let tags = get_save_tags("Survival/MySave").unwrap();
-        assert_eq!(tags.len(), 1);
-        assert_eq!(tags[0].name, "main");
+        // Writes recover too, then apply on top of the restored file
+        std::fs::write(&path, "{\"tags\": [").unwrap();
+        create_tag("c".to_string(), "#0000FF".to_string(), None, None).unwrap();
+        assert!(take_recovery().is_some());
+        let db = load_tags_db().unwrap();
+        assert!(db.has_tag("a") && db.has_tag("c"));
 
-        // Clean up
-        let _ = delete_tag("main".to_string());
+        // Only when every generation is unreadable does the error surface
+        for generation in 1..=TAGS_DB_BACKUPS {
+            std::fs::write(store.backup_path(generation), "").unwrap();
+        }
+        std::fs::write(&path, "").unwrap();
+        assert!(matches!(load_tags_db(), Err(TagsError::Json(_))));
+        assert!(take_recovery().is_none());
     }
 
     #[test]
-    #[serial]
-    fn test_remove_backup_tags() {
-        let _ = create_tag("tag1".to_string(), "#FF0000".to_string());
-        let _ = create_tag("tag2".to_string(), "#00FF00".to_string());
+    fn test_watcher_reports_external_edits() {
+        let _env = TestEnv::new();
+        let path = get_tags_db_path().unwrap();
+        create_tag("a".to_string(), "#FF0000".to_string(), None, None).unwrap();
+        add_tags_to_save("Survival/A", vec!["a".to_string()]).unwrap();
+        let mut watcher = TagsDbWatcher::new().unwrap();
+        assert_eq!(watcher.poll().unwrap(), None);
 
-        let _ = add_tags_to_backup("Survival", "backup1.tar.gz", vec![
-            "tag1".to_string(),
-            "tag2".to_string(),
-        ]);
+        // Writes of this process aren't reloaded
+        create_tag("b".to_string(), "#00FF00".to_string(), None, None).unwrap();
+        assert_eq!(watcher.poll().unwrap(), None);
 
-        let result = remove_tags_from_backup("Survival", "backup1.tar.gz", vec!["tag1".to_string()]);
-        assert!(result.is_ok());
+        let edit = |save: &str| {
+            let mut value: serde_json::Value =
+                serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+            value["associations"].as_array_mut().unwrap().push(serde_json::json!({
+                "target": { "type": "Save", "relative_path": save },
+                "tag_names": ["b"]
+            }));
+            std::fs::write(&path, value.to_string()).unwrap();
+        };
 
-        let tags = get_backup_tags("Survival", "backup1.tar.gz").unwrap();
-        assert_eq!(tags.len(), 1);
-        assert_eq!(tags[0].name, "tag2");
+        // The first external edit has nothing to compare against
+        edit("Survival/B");
+        let expected = vec![SaveKey::new("Survival/A"), SaveKey::new("Survival/B")];
+        assert_eq!(watcher.poll().unwrap(), Some(expected));
+        assert_eq!(watcher.poll().unwrap(), None);
+        edit("Survival/C");
+        assert_eq!(watcher.poll().unwrap(), Some(vec![SaveKey::new("Survival/C")]));
+        assert_eq!(get_save_tags("Survival/C").unwrap()[0].name, "b");
 
-        // Clean up
-        let _ = delete_tag("tag1".to_string());
-        let _ = delete_tag("tag2".to_string());
+        // A half-written file is reported once and not recovered
+        std::fs::write(&path, "{").unwrap();
+        assert!(watcher.poll().is_err());
+        assert_eq!(watcher.poll().unwrap(), None);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "{");
     }
 
     #[test]
-    #[serial]
-    fn test_remove_save_tags() {
-        let _ = create_tag("tag1".to_string(), "#FF0000".to_string());
-        let _ = create_tag("tag2".to_string(), "#00FF00".to_string());
+    fn test_newer_schema_is_refused() {
+        let _env = TestEnv::new();
+        let path = get_tags_db_path().unwrap();
+        let future = r##"{"schema_version": 99, "tags": [], "labels": {"keep": "#FF0000"}}"##;
+        std::fs::write(&path, future).unwrap();
 
-        let _ = add_tags_to_save("Survival/MySave", vec![
-            "tag1".to_string(),
-            "tag2".to_string(),
-        ]);
+        assert!(matches!(load_tags_db(), Err(TagsError::UnsupportedSchema(99))));
+        let created = create_tag("new".to_string(), "#00FF00".to_string(), None, None);
+        assert!(matches!(created, Err(TagsError::UnsupportedSchema(99))));
+        assert!(matches!(migrate_tag_keys(), Err(TagsError::UnsupportedSchema(99))));
 
-        let result = remove_tags_from_save("Survival/MySave", vec!["tag1".to_string()]);
-        assert!(result.is_ok());
+        // The file is left for the newer version to read
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), future);
+    }
 
-        let tags = get_save_tags("Survival/MySave").unwrap();
-        assert_eq!(tags.len(), 1);
-        assert_eq!(tags[0].name, "tag2");
+    /// Timing comparison against the previous linear scans; run with `--ignored --nocapture`.
+    #[test]
+    #[ignore]
+    fn bench_indexed_lookups_on_large_database() {
+        use std::time::Instant;
+
+        let db = generate_db(200, 5000);
+        let targets: Vec<TagTarget> = db.associations.iter().map(|a| a.target.clone()).collect();
+
+        let started = Instant::now();
+        let mut linear_count = 0;
+        for target in &targets {
+            let association = db.associations.iter().find(|a| &a.target == target).unwrap();
+            for name in &association.tag_names {
+                if db.tags.iter().any(|t| &t.name == name) {
+                    linear_count += 1;
+                }
+            }
+        }
+        let linear = started.elapsed();
 
-        // Clean up
-        let _ = delete_tag("tag1".to_string());
-        let _ = delete_tag("tag2".to_string());
+        let started = Instant::now();
+        let indexed_count: usize = targets.iter().map(|t| db.tags_for(t).len()).sum();
+        let indexed = started.elapsed();
+
+        println!("linear: {:?}, indexed: {:?}", linear, indexed);
+        assert_eq!(linear_count, indexed_count);
+        assert!(indexed < linear);
     }
 
     #[test]
-    #[serial]
-    fn test_tag_serialization() {
-        let tag = Tag {
-            name: "test".to_string(),
-            color: "#FF5733".to_string(),
-        };
+    fn test_changed_saves_reports_only_affected_saves() {
+        let mut before = TagsDatabase::default();
+        before.create_tag("a".to_string(), "#111".to_string(), None, None).unwrap();
+        before.create_tag("b".to_string(), "#222".to_string(), None, None).unwrap();
+        before.add_tags_to(&TagTarget::backup("S1", "x.tar.gz"), vec!["a".to_string()]).unwrap();
+        before.add_tags_to(&TagTarget::save("S2"), vec!["b".to_string()]).unwrap();
 
-        let json = serde_json::to_string(&tag).unwrap();
-        let parsed: Tag = serde_json::from_str(&json).unwrap();
+        let mut after = before.clone();
+        after.add_tags_to(&TagTarget::save("S3"), vec!["b".to_string()]).unwrap();
+        assert_eq!(changed_saves(&before, &after), vec![SaveKey::new("S3")]);
 
-        assert_eq!(parsed.name, "test");
-        assert_eq!(parsed.color, "#FF5733");
+        let mut after = before.clone();
+        after.delete_tag("a").unwrap();
+        assert_eq!(changed_saves(&before, &after), vec![SaveKey::new("S1")]);
+
+        let mut after = before.clone();
+        after.tags[1].color = "#333".to_string();
+        assert_eq!(changed_saves(&before, &after), vec![SaveKey::new("S2")]);
+
+        let mut after = before.clone();
+        after.create_tag("c".to_string(), "#444".to_string(), None, None).unwrap();
+        assert!(changed_saves(&before, &after).is_empty());
     }
 }
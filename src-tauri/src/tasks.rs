@@ -0,0 +1,455 @@
+//! Registry of long-running background operations.
+//!
+//! This module provides:
+//! - `TaskRegistry`, managed by Tauri, that every long operation registers with
+//! - `TaskHandle`, given to the operation to report progress and check for cancellation
+//! - Snapshots of active and recently finished tasks for the "Activity" panel
+//! - A performance metric point per finished task, passed to the metrics sink
+
+use crate::file_ops::ArchiveObserver;
+use crate::metrics::MetricPoint;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt::Display;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock};
+use std::time::Instant;
+
+/// Maximum number of recently finished tasks kept.
+const MAX_RECENT_TASKS: usize = 20;
+
+/// How long finished tasks stay in the "recently finished" list (minutes).
+const RECENT_TASK_MINUTES: i64 = 10;
+
+/// Kind of a background task.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum TaskKind {
+    Backup,
+    Restore,
+    Scan,
+    Upload,
+    Thumbnail,
+    Update,
+}
+
+/// State of a task.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum TaskState {
+    /// Still running
+    Running,
+    /// Cancellation was requested and the task hasn't stopped yet
+    Cancelling,
+    /// Finished successfully
+    Completed,
+    /// Finished with an error
+    Failed,
+    /// Stopped after a cancellation request
+    Cancelled,
+}
+
+/// Snapshot of a task returned to the frontend.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TaskInfo {
+    /// Task id
+    pub id: u64,
+    /// Task kind
+    pub kind: TaskKind,
+    /// What the task works on (e.g., save or backup name)
+    pub subject: String,
+    /// ISO 8601 timestamp when the task started
+    pub started_at: String,
+    /// Units of work done
+    pub progress_done: u64,
+    /// Total units of work, 0 if unknown
+    pub progress_total: u64,
+    /// Whether `cancel_task` can stop this task
+    pub cancellable: bool,
+    /// Current state
+    pub state: TaskState,
+    /// ISO 8601 timestamp when the task finished
+    pub finished_at: Option<String>,
+    /// Error message for failed tasks
+    pub message: Option<String>,
+}
+
+/// Progress cell, cancellation token and measurements shared between a task and the registry.
+#[derive(Debug, Default)]
+struct TaskShared {
+    cancelled: AtomicBool,
+    progress_done: AtomicU64,
+    progress_total: AtomicU64,
+    /// When a queue worker picked the task up
+    running_since: OnceLock<Instant>,
+    /// (save data size, archive size) processed, if reported
+    bytes: Mutex<(Option<u64>, Option<u64>)>,
+}
+
+/// Handle given to a running operation.
+///
+/// The operation reports progress through it and checks `is_cancelled` in its loops.
+#[derive(Debug, Clone)]
+pub struct TaskHandle {
+    id: u64,
+    shared: Arc<TaskShared>,
+}
+
+impl TaskHandle {
+    /// Creates a handle that isn't registered anywhere (for callers without a registry).
+    pub fn detached() -> Self {
+        TaskHandle {
+            id: 0,
+            shared: Arc::new(TaskShared::default()),
+        }
+    }
+
+    /// Returns the task id.
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// Updates the progress cell.
+    pub fn set_progress(&self, done: u64, total: u64) {
+        self.shared.progress_total.store(total, Ordering::Relaxed);
+        self.shared.progress_done.store(done, Ordering::Relaxed);
+    }
+
+    /// Returns true once cancellation was requested.
+    pub fn is_cancelled(&self) -> bool {
+        self.shared.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Marks the end of the queue wait; call when a worker starts running the task.
+    ///
+    /// Only the first call counts. Tasks that never call it report no queue wait.
+    pub fn mark_running(&self) {
+        let _ = self.shared.running_since.set(Instant::now());
+    }
+
+    /// Reports the amount of data processed, for throughput and compression metrics.
+    ///
+    /// # Arguments
+    /// * `uncompressed` - Size of the save data read or written
+    /// * `compressed` - Size of the archive written or read
+    pub fn set_bytes(&self, uncompressed: Option<u64>, compressed: Option<u64>) {
+        *self.shared.bytes.lock().unwrap_or_else(|e| e.into_inner()) = (uncompressed, compressed);
+    }
+}
+
+impl ArchiveObserver for TaskHandle {
+    fn on_progress(&self, done: u64, total: u64) {
+        self.set_progress(done, total);
+    }
+
+    fn is_cancelled(&self) -> bool {
+        TaskHandle::is_cancelled(self)
+    }
+}
+
+/// A registered, still running task.
+#[derive(Debug)]
+struct ActiveTask {
+    kind: TaskKind,
+    subject: String,
+    started_at: DateTime<Utc>,
+    registered: Instant,
+    cancellable: bool,
+    shared: Arc<TaskShared>,
+}
+
+impl ActiveTask {
+    /// Builds the metric point of the task finishing now.
+    fn metric_point(&self, finished_at: DateTime<Utc>, success: bool) -> MetricPoint {
+        let now = Instant::now();
+        let running_since = self.shared.running_since.get().copied().unwrap_or(self.registered);
+        let (uncompressed_bytes, compressed_bytes) =
+            *self.shared.bytes.lock().unwrap_or_else(|e| e.into_inner());
+
+        MetricPoint {
+            kind: self.kind,
+            subject: self.subject.clone(),
+            finished_at,
+            duration_ms: now.duration_since(running_since).as_millis() as u64,
+            queue_wait_ms: running_since.duration_since(self.registered).as_millis() as u64,
+            uncompressed_bytes,
+            compressed_bytes,
+            success,
+        }
+    }
+
+    fn snapshot(&self, id: u64) -> TaskInfo {
+        let state = if self.shared.cancelled.load(Ordering::Relaxed) {
+            TaskState::Cancelling
+        } else {
+            TaskState::Running
+        };
+
+        TaskInfo {
+            id,
+            kind: self.kind,
+            subject: self.subject.clone(),
+            started_at: self.started_at.to_rfc3339(),
+            progress_done: self.shared.progress_done.load(Ordering::Relaxed),
+            progress_total: self.shared.progress_total.load(Ordering::Relaxed),
+            cancellable: self.cancellable,
+            state,
+            finished_at: None,
+            message: None,
+        }
+    }
+}
+
+/// Mutable registry data guarded by `TaskRegistry`.
+#[derive(Debug, Default)]
+struct RegistryData {
+    next_id: u64,
+    active: BTreeMap<u64, ActiveTask>,
+    recent: VecDeque<(DateTime<Utc>, TaskInfo)>,
+}
+
+impl RegistryData {
+    /// Drops finished tasks that are too old or exceed the list size.
+    fn prune_recent(&mut self, now: DateTime<Utc>) {
+        let cutoff = now - Duration::minutes(RECENT_TASK_MINUTES);
+        self.recent.retain(|(finished_at, _)| *finished_at >= cutoff);
+        while self.recent.len() > MAX_RECENT_TASKS {
+            self.recent.pop_front();
+        }
+    }
+}
+
+/// Receives the metric point of every finished task (e.g., `metrics::record_point_best_effort`).
+pub type MetricsSink = fn(&MetricPoint);
+
+/// Registry of background tasks managed by Tauri.
+#[derive(Debug, Default)]
+pub struct TaskRegistry {
+    data: Mutex<RegistryData>,
+    metrics_sink: Option<MetricsSink>,
+}
+
+impl TaskRegistry {
+    /// Creates an empty registry without a metrics sink.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the sink receiving a metric point for every finished task.
+    pub fn with_metrics_sink(mut self, sink: MetricsSink) -> Self {
+        self.metrics_sink = Some(sink);
+        self
+    }
+
+    /// Locks the data, recovering from a poisoned lock (the data stays usable).
+    fn lock(&self) -> MutexGuard<'_, RegistryData> {
+        self.data.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Registers a new task.
+    ///
+    /// # Arguments
+    /// * `kind` - Task kind
+    /// * `subject` - What the task works on (e.g., save name)
+    /// * `cancellable` - Whether the operation checks its handle for cancellation
+    ///
+    /// # Returns
+    /// `TaskHandle` - Handle to pass to the operation
+    pub fn start(&self, kind: TaskKind, subject: &str, cancellable: bool) -> TaskHandle {
+        let mut data = self.lock();
+        data.next_id += 1;
+        let id = data.next_id;
+
+        let shared = Arc::new(TaskShared::default());
+        data.active.insert(
+            id,
+            ActiveTask {
+                kind,
+                subject: subject.to_string(),
+                started_at: Utc::now(),
+                registered: Instant::now(),
+                cancellable,
+                shared: Arc::clone(&shared),
+            },
+        );
+
+        TaskHandle { id, shared }
+    }
+
+    /// Moves a task into the recently finished list and passes its metrics to the sink.
+    ///
+    /// The final state is `Cancelled` if cancellation was requested and the operation
+    /// failed, `Failed` for other errors and `Completed` otherwise.
+    pub fn finish<T, E: Display>(&self, handle: &TaskHandle, result: &Result<T, E>) {
+        let now = Utc::now();
+        let mut data = self.lock();
+
+        let Some(task) = data.active.remove(&handle.id) else {
+            return;
+        };
+        let point = task.metric_point(now, result.is_ok());
+
+        let mut info = task.snapshot(handle.id);
+        info.finished_at = Some(now.to_rfc3339());
+        info.state = match result {
+            Ok(_) => TaskState::Completed,
+            Err(_) if handle.is_cancelled() => TaskState::Cancelled,
+            Err(_) => TaskState::Failed,
+        };
+        info.message = result.as_ref().err().map(|e| e.to_string());
+
+        data.recent.push_back((now, info));
+        data.prune_recent(now);
+        drop(data);
+
+        if let Some(sink) = self.metrics_sink {
+            sink(&point);
+        }
+    }
+
+    /// Requests cancellation of a task.
+    ///
+    /// # Returns
+    /// `bool` - true if the task is active and cancellable
+    pub fn cancel(&self, id: u64) -> bool {
+        match self.lock().active.get(&id) {
+            Some(task) if task.cancellable => {
+                task.shared.cancelled.store(true, Ordering::Relaxed);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns snapshots of all active tasks, oldest first.
+    pub fn list_active(&self) -> Vec<TaskInfo> {
+        self.lock()
+            .active
+            .iter()
+            .map(|(id, task)| task.snapshot(*id))
+            .collect()
+    }
+
+    /// Returns recently finished tasks, newest first.
+    pub fn list_recent(&self) -> Vec<TaskInfo> {
+        let mut data = self.lock();
+        data.prune_recent(Utc::now());
+        data.recent.iter().rev().map(|(_, info)| info.clone()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_start_and_list_active() {
+        let registry = TaskRegistry::new();
+        let backup = registry.start(TaskKind::Backup, "Survival/A", true);
+        let restore = registry.start(TaskKind::Restore, "Survival/B", false);
+        backup.set_progress(3, 10);
+
+        let active = registry.list_active();
+        assert_eq!(active.len(), 2);
+        assert_eq!(active[0].id, backup.id());
+        assert_eq!(active[0].progress_done, 3);
+        assert_eq!(active[0].progress_total, 10);
+        assert_eq!(active[0].state, TaskState::Running);
+        assert_eq!(active[1].id, restore.id());
+        assert!(!active[1].cancellable);
+    }
+
+    #[test]
+    fn test_cancel_sets_token() {
+        let registry = TaskRegistry::new();
+        let backup = registry.start(TaskKind::Backup, "Survival/A", true);
+        let restore = registry.start(TaskKind::Restore, "Survival/B", false);
+
+        assert!(registry.cancel(backup.id()));
+        assert!(backup.is_cancelled());
+        assert_eq!(registry.list_active()[0].state, TaskState::Cancelling);
+
+        // Non-cancellable and unknown tasks are left alone
+        assert!(!registry.cancel(restore.id()));
+        assert!(!restore.is_cancelled());
+        assert!(!registry.cancel(999));
+    }
+
+    #[test]
+    fn test_finish_moves_to_recent() {
+        let registry = TaskRegistry::new();
+        let ok = registry.start(TaskKind::Backup, "Survival/A", true);
+        let failed = registry.start(TaskKind::Backup, "Survival/B", true);
+        let cancelled = registry.start(TaskKind::Backup, "Survival/C", true);
+        registry.cancel(cancelled.id());
+
+        registry.finish(&ok, &Ok::<(), String>(()));
+        registry.finish(&failed, &Err::<(), _>("disk full".to_string()));
+        registry.finish(&cancelled, &Err::<(), _>("Operation was cancelled".to_string()));
+
+        assert!(registry.list_active().is_empty());
+        let recent = registry.list_recent();
+        assert_eq!(recent.len(), 3);
+        assert_eq!(recent[0].state, TaskState::Cancelled);
+        assert_eq!(recent[1].state, TaskState::Failed);
+        assert_eq!(recent[1].message.as_deref(), Some("disk full"));
+        assert_eq!(recent[2].state, TaskState::Completed);
+        assert!(recent[2].finished_at.is_some());
+    }
+
+    #[test]
+    fn test_recent_list_is_capped() {
+        let registry = TaskRegistry::new();
+        for i in 0..(MAX_RECENT_TASKS + 5) {
+            let handle = registry.start(TaskKind::Scan, &format!("save{}", i), false);
+            registry.finish(&handle, &Ok::<(), String>(()));
+        }
+
+        let recent = registry.list_recent();
+        assert_eq!(recent.len(), MAX_RECENT_TASKS);
+        assert_eq!(recent[0].subject, format!("save{}", MAX_RECENT_TASKS + 4));
+    }
+
+    thread_local! {
+        static SUNK: std::cell::RefCell<Vec<MetricPoint>> = const { std::cell::RefCell::new(Vec::new()) };
+    }
+
+    fn collect_point(point: &MetricPoint) {
+        SUNK.with(|sunk| sunk.borrow_mut().push(point.clone()));
+    }
+
+    #[test]
+    fn test_finish_reports_metrics_to_sink() {
+        let registry = TaskRegistry::new().with_metrics_sink(collect_point);
+        let handle = registry.start(TaskKind::Backup, "Survival/A", true);
+
+        // Queued, then run by a worker
+        std::thread::sleep(std::time::Duration::from_millis(30));
+        handle.mark_running();
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        handle.set_bytes(Some(4096), Some(1024));
+        registry.finish(&handle, &Ok::<(), String>(()));
+
+        let points = SUNK.with(|sunk| sunk.take());
+        assert_eq!(points.len(), 1);
+        let point = &points[0];
+        assert_eq!(point.kind, TaskKind::Backup);
+        assert_eq!(point.subject, "Survival/A");
+        assert!(point.success);
+        assert!(point.queue_wait_ms >= 30, "{:?}", point);
+        assert!(point.duration_ms >= 20, "{:?}", point);
+        assert_eq!(point.uncompressed_bytes, Some(4096));
+        assert_eq!(point.compression_ratio(), Some(0.25));
+    }
+
+    #[test]
+    fn test_unmarked_task_has_no_queue_wait() {
+        let registry = TaskRegistry::new().with_metrics_sink(collect_point);
+        let handle = registry.start(TaskKind::Restore, "Survival/B", false);
+        registry.finish(&handle, &Err::<(), _>("failed".to_string()));
+
+        let points = SUNK.with(|sunk| sunk.take());
+        assert_eq!(points[0].queue_wait_ms, 0);
+        assert!(!points[0].success);
+        assert_eq!(points[0].uncompressed_bytes, None);
+    }
+}
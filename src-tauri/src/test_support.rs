@@ -0,0 +1,118 @@
+//! Shared test harness.
+//!
+//! Every test that touches persisted state (config, tags, history, logs) creates a
+//! `TestEnv`, which points the config directory at a fresh temporary directory for
+//! the current thread and restores the previous setting when dropped. Without one,
+//! `config::get_config_dir` fails instead of resolving to the user's real directory.
+
+use crate::config::{self as config_module, test_config_dir, Config};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+/// Isolated environment for a single test.
+pub struct TestEnv {
+    root: TempDir,
+    previous: Option<PathBuf>,
+}
+
+impl TestEnv {
+    /// Creates an empty environment and makes its config directory current.
+    pub fn new() -> Self {
+        let root = TempDir::new().unwrap();
+        let config_dir = root.path().join("config");
+        fs::create_dir_all(&config_dir).unwrap();
+        let previous = test_config_dir::set(Some(config_dir));
+
+        TestEnv { root, previous }
+    }
+
+    /// Creates an environment whose config points at `saves` and `backups`
+    /// subdirectories of the temporary root (both created).
+    pub fn with_paths() -> Self {
+        let env = TestEnv::new();
+        fs::create_dir_all(env.save_dir()).unwrap();
+        fs::create_dir_all(env.backup_dir()).unwrap();
+
+        let config = Config::with_paths(
+            env.save_dir().to_str().unwrap().to_string(),
+            env.backup_dir().to_str().unwrap().to_string(),
+        );
+        config_module::save_config(&config).unwrap();
+        env
+    }
+
+    /// Temporary root directory, removed on drop.
+    pub fn root(&self) -> &Path {
+        self.root.path()
+    }
+
+    /// Config directory used by `config::get_config_dir`.
+    pub fn config_dir(&self) -> PathBuf {
+        self.root().join("config")
+    }
+
+    /// Save directory used by `with_paths`.
+    pub fn save_dir(&self) -> PathBuf {
+        self.root().join("saves")
+    }
+
+    /// Backup directory used by `with_paths`.
+    pub fn backup_dir(&self) -> PathBuf {
+        self.root().join("backups")
+    }
+}
+
+impl Default for TestEnv {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for TestEnv {
+    fn drop(&mut self) {
+        test_config_dir::set(self.previous.take());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_dir_never_resolves_to_real_location() {
+        // Without an environment there is no config directory at all
+        assert!(config_module::get_config_dir().is_err());
+
+        let env = TestEnv::new();
+        let dir = config_module::get_config_dir().unwrap();
+        assert_eq!(dir, env.config_dir());
+        assert!(dir.starts_with(std::env::temp_dir()), "{:?} is outside the temp dir", dir);
+        if let Some(real) = dirs::config_dir() {
+            assert!(!dir.starts_with(real.join("ZomboidBackupTool")));
+        }
+        drop(env);
+
+        assert!(config_module::get_config_dir().is_err());
+    }
+
+    #[test]
+    fn test_environments_nest_and_restore() {
+        let outer = TestEnv::new();
+        {
+            let inner = TestEnv::new();
+            assert_eq!(config_module::get_config_dir().unwrap(), inner.config_dir());
+        }
+        assert_eq!(config_module::get_config_dir().unwrap(), outer.config_dir());
+    }
+
+    #[test]
+    fn test_environments_are_per_thread() {
+        let env = TestEnv::new();
+        let other = std::thread::spawn(|| config_module::get_config_dir().is_err())
+            .join()
+            .unwrap();
+        assert!(other);
+        assert_eq!(config_module::get_config_dir().unwrap(), env.config_dir());
+    }
+}
@@ -0,0 +1,68 @@
+//! Unicode canonical composition (NFC) for key normalization.
+//!
+//! Backed by the full Unicode composition data in `icu_normalizer`, so every script
+//! composes, including CJK compatibility ideographs, which map to their unified
+//! ideographs.
+
+use icu_normalizer::ComposingNormalizerBorrowed;
+
+/// Converts a string to Unicode Normalization Form C.
+pub fn nfc(input: &str) -> String {
+    ComposingNormalizerBorrowed::new_nfc().normalize(input).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_composes_decomposed_text() {
+        assert_eq!(nfc("Cafe\u{301}"), "Caf\u{E9}");
+        assert_eq!(nfc("\u{439}"), "\u{439}");
+        assert_eq!(nfc("\u{438}\u{306}"), "\u{439}");
+        assert_eq!(nfc("\u{1100}\u{1161}\u{11A8}"), "\u{AC01}");
+        assert_eq!(nfc("plain ascii"), "plain ascii");
+    }
+
+    #[test]
+    fn test_orders_and_composes_multiple_marks() {
+        // e + circumflex + dot below, in either order, is U+1EC7
+        assert_eq!(nfc("e\u{302}\u{323}"), "\u{1EC7}");
+        assert_eq!(nfc("e\u{323}\u{302}"), "\u{1EC7}");
+        assert_eq!(nfc("\u{EA}\u{323}"), "\u{1EC7}");
+    }
+
+    #[test]
+    fn test_replaces_singletons() {
+        // Combining grave tone mark is canonically the grave accent
+        assert_eq!(nfc("a\u{340}"), "\u{E0}");
+        // Greek oxia forms map to tonos forms
+        assert_eq!(nfc("\u{1F71}"), "\u{3AC}");
+    }
+
+    #[test]
+    fn test_composes_kana_sound_marks() {
+        // ka + voiced mark is ga, ha + semi-voiced mark is pa
+        assert_eq!(nfc("\u{304B}\u{3099}"), "\u{304C}");
+        assert_eq!(nfc("\u{30CF}\u{309A}"), "\u{30D1}");
+        assert_eq!(nfc("\u{30D1}"), "\u{30D1}");
+    }
+
+    #[test]
+    fn test_maps_cjk_compatibility_ideographs() {
+        assert_eq!(nfc("\u{F900}"), "\u{8C48}");
+        assert_eq!(nfc("\u{2F800}"), "\u{4E3D}");
+    }
+
+    #[test]
+    fn test_composes_other_scripts() {
+        // Arabic alef + hamza above
+        assert_eq!(nfc("\u{627}\u{654}"), "\u{623}");
+        // Devanagari na + nukta
+        assert_eq!(nfc("\u{928}\u{93C}"), "\u{929}");
+        // Bengali o from its two-part vowel sign
+        assert_eq!(nfc("\u{995}\u{9C7}\u{9BE}"), "\u{995}\u{9CB}");
+        // Hebrew points are reordered by combining class but don't compose
+        assert_eq!(nfc("\u{5D1}\u{5BC}\u{5B8}"), "\u{5D1}\u{5B8}\u{5BC}");
+    }
+}
@@ -0,0 +1,790 @@
+//! Webhook notifications for backup events.
+//!
+//! Webhooks in the config name the events they want. When a matching `AppEvent`
+//! fires, its `WebhookPayload` is POSTed as JSON (or, for the `Discord` format, an
+//! embed message rendered by `discord`) with these headers:
+//! - `X-PZ-Backup-Event` - Event type (e.g., "BackupFailed")
+//! - `X-PZ-Backup-Signature-256` - `sha256=<hex>`, the HMAC-SHA256 of the body keyed
+//!   with the webhook's secret; omitted for webhooks without a secret
+//!
+//! Deliveries run on a background thread and are retried with exponential backoff
+//! (`WebhookRetry`), or after the `Retry-After` delay of a 429 response; the outcome
+//! of every delivery is recorded in the operation history. They never block or fail
+//! the operation that fired the event.
+
+use crate::backup::BackupResult;
+use crate::config as config_module;
+use crate::discord;
+use crate::errors::ErrorPayload;
+use crate::file_ops;
+use crate::history::{self, HistoryRecord, OperationOutcome, OperationTrigger, OperationType};
+use crate::i18n;
+use chrono::{DateTime, Utc};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+/// Header naming the event type.
+pub const EVENT_HEADER: &str = "X-PZ-Backup-Event";
+
+/// Header carrying the body's HMAC-SHA256 signature.
+pub const SIGNATURE_HEADER: &str = "X-PZ-Backup-Signature-256";
+
+/// Timeout of a single delivery attempt.
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Longest `Retry-After` delay honored; longer ones are cut to this.
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(60);
+
+/// Event types a webhook can subscribe to.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum WebhookEvent {
+    /// A backup was created
+    BackupFinished,
+    /// A backup failed (cancelled backups don't fire)
+    BackupFailed,
+    /// Old backups were deleted by a prune or by garbage collection after a backup
+    PruneCompleted,
+    /// Free space on the backup volume dropped below `low_disk_space_mb`
+    DiskSpaceLow,
+}
+
+impl WebhookEvent {
+    /// Returns the event's name as used in payloads and headers.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookEvent::BackupFinished => "BackupFinished",
+            WebhookEvent::BackupFailed => "BackupFailed",
+            WebhookEvent::PruneCompleted => "PruneCompleted",
+            WebhookEvent::DiskSpaceLow => "DiskSpaceLow",
+        }
+    }
+}
+
+/// Body format of a webhook.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum WebhookFormat {
+    /// The `WebhookPayload` JSON
+    #[default]
+    Generic,
+    /// A Discord embed message
+    Discord,
+}
+
+/// A configured webhook.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct WebhookConfig {
+    /// URL the payload is POSTed to
+    pub url: String,
+    /// Body format
+    #[serde(default)]
+    pub format: WebhookFormat,
+    /// Events that are delivered; none if empty
+    #[serde(default)]
+    pub events: Vec<WebhookEvent>,
+    /// Key for the signature header
+    #[serde(default)]
+    pub secret: Option<String>,
+}
+
+/// Event fired by an operation.
+#[derive(Debug, Clone)]
+pub enum AppEvent {
+    /// A backup was created
+    BackupFinished {
+        save_name: String,
+        result: BackupResult,
+        duration_ms: u64,
+    },
+    /// A backup failed
+    BackupFailed {
+        save_name: String,
+        error: ErrorPayload,
+        duration_ms: u64,
+    },
+    /// Backups of a save were deleted
+    PruneCompleted { save_name: String, deleted: Vec<String> },
+    /// The backup volume is running out of space
+    DiskSpaceLow {
+        path: String,
+        free_bytes: u64,
+        threshold_bytes: u64,
+    },
+}
+
+impl AppEvent {
+    /// Returns the event's type.
+    pub fn kind(&self) -> WebhookEvent {
+        match self {
+            AppEvent::BackupFinished { .. } => WebhookEvent::BackupFinished,
+            AppEvent::BackupFailed { .. } => WebhookEvent::BackupFailed,
+            AppEvent::PruneCompleted { .. } => WebhookEvent::PruneCompleted,
+            AppEvent::DiskSpaceLow { .. } => WebhookEvent::DiskSpaceLow,
+        }
+    }
+
+    /// Builds the payload delivered to webhooks.
+    pub fn to_payload(&self) -> WebhookPayload {
+        let mut payload = WebhookPayload {
+            event: self.kind(),
+            timestamp: Utc::now(),
+            save_name: None,
+            backup_name: None,
+            outcome: None,
+            duration_ms: None,
+            details: serde_json::Value::Null,
+        };
+        match self {
+            AppEvent::BackupFinished {
+                save_name,
+                result,
+                duration_ms,
+            } => {
+                payload.save_name = Some(save_name.clone());
+                payload.backup_name = Some(result.backup_name.clone());
+                payload.outcome = Some(OperationOutcome::Success);
+                payload.duration_ms = Some(*duration_ms);
+                payload.details = serde_json::to_value(result).unwrap_or_default();
+            }
+            AppEvent::BackupFailed {
+                save_name,
+                error,
+                duration_ms,
+            } => {
+                payload.save_name = Some(save_name.clone());
+                payload.outcome = Some(OperationOutcome::Failure);
+                payload.duration_ms = Some(*duration_ms);
+                payload.details = serde_json::to_value(error).unwrap_or_default();
+            }
+            AppEvent::PruneCompleted { save_name, deleted } => {
+                payload.save_name = Some(save_name.clone());
+                payload.outcome = Some(OperationOutcome::Success);
+                payload.details = serde_json::json!({ "deleted": deleted });
+            }
+            AppEvent::DiskSpaceLow {
+                path,
+                free_bytes,
+                threshold_bytes,
+            } => {
+                payload.details = serde_json::json!({
+                    "path": path,
+                    "free_bytes": free_bytes,
+                    "threshold_bytes": threshold_bytes,
+                });
+            }
+        }
+        payload
+    }
+}
+
+/// JSON body POSTed to webhooks.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WebhookPayload {
+    /// Event type
+    pub event: WebhookEvent,
+    /// When the event fired
+    pub timestamp: DateTime<Utc>,
+    /// Relative path of the save, for save events
+    pub save_name: Option<String>,
+    /// Backup involved, if any
+    pub backup_name: Option<String>,
+    /// Outcome of the operation, for operation events
+    pub outcome: Option<OperationOutcome>,
+    /// Duration of the operation in milliseconds, for backup events
+    #[serde(default)]
+    pub duration_ms: Option<u64>,
+    /// Event details: the `BackupResult` of a finished backup, the `ErrorPayload` of a
+    /// failed one, `{ deleted }` for prunes, `{ path, free_bytes, threshold_bytes }`
+    /// for low disk space
+    pub details: serde_json::Value,
+}
+
+/// Retry policy of deliveries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WebhookRetry {
+    /// Attempts before a delivery is given up (at least one)
+    pub attempts: u32,
+    /// Wait before the second attempt; doubles after every further attempt
+    pub initial_delay: Duration,
+}
+
+impl Default for WebhookRetry {
+    fn default() -> Self {
+        WebhookRetry {
+            attempts: 3,
+            initial_delay: Duration::from_secs(2),
+        }
+    }
+}
+
+/// Result of delivering one payload to one webhook.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeliveryOutcome {
+    /// Attempts made
+    pub attempts: u32,
+    /// Error of the last attempt; None if the payload was delivered
+    pub error: Option<String>,
+}
+
+/// Computes the HMAC-SHA256 of a message (RFC 2104).
+pub fn hmac_sha256(key: &[u8], message: &[u8]) -> [u8; 32] {
+    const BLOCK_SIZE: usize = 64;
+
+    let mut block = [0u8; BLOCK_SIZE];
+    if key.len() > BLOCK_SIZE {
+        block[..32].copy_from_slice(&Sha256::digest(key));
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+
+    let mut inner = Sha256::new();
+    inner.update(block.map(|b| b ^ 0x36));
+    inner.update(message);
+    let mut outer = Sha256::new();
+    outer.update(block.map(|b| b ^ 0x5c));
+    outer.update(inner.finalize());
+    outer.finalize().into()
+}
+
+/// Returns the signature header value of a body (`sha256=<hex>`).
+pub fn signature(secret: &str, body: &[u8]) -> String {
+    let digest = hmac_sha256(secret.as_bytes(), body);
+    let hex: String = digest.iter().map(|b| format!("{:02x}", b)).collect();
+    format!("sha256={}", hex)
+}
+
+/// Deliveries still running, so short-lived processes (the CLI) can wait for them.
+static PENDING: Mutex<usize> = Mutex::new(0);
+static PENDING_DONE: Condvar = Condvar::new();
+
+/// Counts a running delivery thread until dropped.
+struct PendingDelivery;
+
+impl PendingDelivery {
+    fn start() -> Self {
+        *PENDING.lock().unwrap_or_else(|e| e.into_inner()) += 1;
+        PendingDelivery
+    }
+}
+
+impl Drop for PendingDelivery {
+    fn drop(&mut self) {
+        let mut pending = PENDING.lock().unwrap_or_else(|e| e.into_inner());
+        *pending = pending.saturating_sub(1);
+        PENDING_DONE.notify_all();
+    }
+}
+
+/// Waits until all started deliveries finished.
+///
+/// # Arguments
+/// * `timeout` - Longest time to wait
+///
+/// # Returns
+/// `bool` - True if no deliveries are left
+pub fn wait_for_deliveries(timeout: Duration) -> bool {
+    let pending = PENDING.lock().unwrap_or_else(|e| e.into_inner());
+    let (pending, _) = PENDING_DONE
+        .wait_timeout_while(pending, timeout, |pending| *pending > 0)
+        .unwrap_or_else(|e| e.into_inner());
+    *pending == 0
+}
+
+/// Delivers an event to every webhook subscribed to it, in the background.
+///
+/// # Arguments
+/// * `event` - Event that fired
+///
+/// # Behavior
+/// Returns immediately. Failures to read the config or to start the delivery
+/// thread are logged; nothing is reported to the caller.
+pub fn emit(event: AppEvent) {
+    let webhooks: Vec<WebhookConfig> = config_module::load_config()
+        .map(|config| config.webhooks)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|webhook| webhook.events.contains(&event.kind()))
+        .collect();
+    if webhooks.is_empty() {
+        return;
+    }
+
+    let payload = event.to_payload();
+    let pending = PendingDelivery::start();
+    let spawned = std::thread::Builder::new()
+        .name("webhook-delivery".to_string())
+        .spawn(move || {
+            let _pending = pending;
+            match tokio::runtime::Builder::new_current_thread().enable_all().build() {
+                Ok(runtime) => runtime.block_on(deliver_all(&webhooks, &payload, WebhookRetry::default())),
+                Err(e) => eprintln!("Failed to start webhook delivery: {}", e),
+            }
+        });
+    if let Err(e) = spawned {
+        eprintln!("Failed to start webhook delivery: {}", e);
+    }
+}
+
+/// Fires `DiskSpaceLow` when the backup volume's free space drops below the threshold.
+///
+/// # Arguments
+/// * `backup_dir` - Any directory on the backup volume
+///
+/// # Behavior
+/// Fires once per drop: the event fires again only after free space went back above
+/// the threshold.
+pub fn check_disk_space(backup_dir: &Path) {
+    static LOW: AtomicBool = AtomicBool::new(false);
+
+    let Ok(config) = config_module::load_config() else {
+        return;
+    };
+    let Ok(free_bytes) = file_ops::get_available_space(backup_dir) else {
+        return;
+    };
+    let threshold_bytes = config.low_disk_space_mb.saturating_mul(1024 * 1024);
+    let low = free_bytes < threshold_bytes;
+    if low && !LOW.swap(true, Ordering::SeqCst) {
+        emit(AppEvent::DiskSpaceLow {
+            path: backup_dir.to_string_lossy().to_string(),
+            free_bytes,
+            threshold_bytes,
+        });
+    } else if !low {
+        LOW.store(false, Ordering::SeqCst);
+    }
+}
+
+/// Delivers a payload to each webhook and records the outcomes.
+async fn deliver_all(webhooks: &[WebhookConfig], payload: &WebhookPayload, retry: WebhookRetry) {
+    let client = match reqwest::Client::builder()
+        .user_agent("pz-backup-tool")
+        .timeout(DELIVERY_TIMEOUT)
+        .build()
+    {
+        Ok(client) => client,
+        Err(e) => {
+            eprintln!("Failed to create webhook client: {}", e);
+            return;
+        }
+    };
+    let locale = i18n::current_locale();
+
+    for webhook in webhooks {
+        let body = match webhook.format {
+            WebhookFormat::Generic => serde_json::to_string(payload),
+            WebhookFormat::Discord => serde_json::to_string(&discord::render_message(payload, locale)),
+        };
+        let body = match body {
+            Ok(body) => body,
+            Err(e) => {
+                eprintln!("Failed to serialize webhook payload: {}", e);
+                continue;
+            }
+        };
+
+        let started = Instant::now();
+        let outcome = deliver(&client, webhook, payload.event, &body, retry).await;
+        record_delivery(webhook, payload, &outcome, started);
+    }
+}
+
+/// POSTs a body to a webhook, retrying failed attempts.
+///
+/// # Arguments
+/// * `client` - HTTP client
+/// * `webhook` - Target webhook
+/// * `event` - Event type, sent in the event header
+/// * `body` - Serialized payload
+/// * `retry` - Retry policy
+///
+/// # Returns
+/// `DeliveryOutcome` - Attempts made and the last error; a 2xx response counts as delivered
+///
+/// # Behavior
+/// A 429 response is retried after its `Retry-After` delay (at most `MAX_RETRY_AFTER`)
+/// instead of the backoff delay; it still counts as an attempt.
+pub async fn deliver(
+    client: &reqwest::Client,
+    webhook: &WebhookConfig,
+    event: WebhookEvent,
+    body: &str,
+    retry: WebhookRetry,
+) -> DeliveryOutcome {
+    let attempts = retry.attempts.max(1);
+    let mut delay = retry.initial_delay;
+    let mut rate_limit_delay = None;
+    let mut error = None;
+
+    for attempt in 1..=attempts {
+        if attempt > 1 {
+            tokio::time::sleep(rate_limit_delay.take().unwrap_or(delay)).await;
+            delay = delay.saturating_mul(2);
+        }
+
+        let mut request = client
+            .post(&webhook.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(EVENT_HEADER, event.as_str())
+            .body(body.to_string());
+        if let Some(secret) = webhook.secret.as_deref().filter(|s| !s.is_empty()) {
+            request = request.header(SIGNATURE_HEADER, signature(secret, body.as_bytes()));
+        }
+
+        match request.send().await {
+            Ok(response) if response.status().is_success() => {
+                return DeliveryOutcome {
+                    attempts: attempt,
+                    error: None,
+                };
+            }
+            Ok(response) => {
+                if response.status() == StatusCode::TOO_MANY_REQUESTS {
+                    rate_limit_delay = retry_after(response.headers());
+                }
+                error = Some(format!("HTTP {}", response.status().as_u16()));
+            }
+            Err(e) => error = Some(e.to_string()),
+        }
+    }
+
+    DeliveryOutcome { attempts, error }
+}
+
+/// Reads the delay of a `Retry-After` header given in (possibly fractional) seconds.
+fn retry_after(headers: &reqwest::header::HeaderMap) -> Option<Duration> {
+    let seconds: f64 = headers
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse()
+        .ok()?;
+    if !seconds.is_finite() || seconds < 0.0 {
+        return None;
+    }
+    Some(Duration::from_secs_f64(seconds).min(MAX_RETRY_AFTER))
+}
+
+/// Records the outcome of a delivery in the operation history.
+fn record_delivery(webhook: &WebhookConfig, payload: &WebhookPayload, outcome: &DeliveryOutcome, started: Instant) {
+    let event = payload.event.as_str();
+    let attempts = outcome.attempts.to_string();
+    let message = match &outcome.error {
+        None => i18n::t(
+            i18n::keys::HISTORY_WEBHOOK_DELIVERED,
+            &[("event", event), ("url", &webhook.url), ("attempts", &attempts)],
+        ),
+        Some(error) => i18n::t(
+            i18n::keys::HISTORY_WEBHOOK_FAILED,
+            &[
+                ("event", event),
+                ("url", &webhook.url),
+                ("attempts", &attempts),
+                ("error", error),
+            ],
+        ),
+    };
+
+    let save_name = payload.save_name.as_deref().unwrap_or_default();
+    let mut record = HistoryRecord::new(OperationType::Webhook, save_name, OperationTrigger::System)
+        .with_message(message);
+    if let Some(backup_name) = &payload.backup_name {
+        record = record.with_backup(backup_name);
+    }
+    record.duration_ms = started.elapsed().as_millis() as u64;
+    if outcome.error.is_some() {
+        record.outcome = OperationOutcome::Failure;
+    }
+    history::append_record_best_effort(&record);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::ErrorCode;
+    use crate::history::HistoryFilter;
+    use crate::test_support::TestEnv;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::sync::mpsc;
+
+    /// Request received by the mock server.
+    struct ReceivedRequest {
+        headers: Vec<(String, String)>,
+        body: String,
+    }
+
+    impl ReceivedRequest {
+        fn header(&self, name: &str) -> Option<&str> {
+            self.headers
+                .iter()
+                .find(|(n, _)| n.eq_ignore_ascii_case(name))
+                .map(|(_, v)| v.as_str())
+        }
+    }
+
+    /// Serves one connection per scripted response (status and extra header lines)
+    /// and reports every request.
+    fn mock_server(responses: Vec<(u16, &'static str)>) -> (String, mpsc::Receiver<ReceivedRequest>) {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        let (sender, receiver) = mpsc::channel();
+
+        std::thread::spawn(move || {
+            for (status, extra_headers) in responses {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut headers = Vec::new();
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                loop {
+                    line.clear();
+                    reader.read_line(&mut line).unwrap();
+                    let Some((name, value)) = line.trim_end().split_once(':') else {
+                        break;
+                    };
+                    headers.push((name.to_string(), value.trim().to_string()));
+                }
+                let length: usize = headers
+                    .iter()
+                    .find(|(n, _)| n.eq_ignore_ascii_case("content-length"))
+                    .map(|(_, v)| v.parse().unwrap())
+                    .unwrap_or(0);
+                let mut body = vec![0u8; length];
+                reader.read_exact(&mut body).unwrap();
+
+                let _ = sender.send(ReceivedRequest {
+                    headers,
+                    body: String::from_utf8(body).unwrap(),
+                });
+                let mut stream = stream;
+                write!(
+                    stream,
+                    "HTTP/1.1 {} X\r\n{}Content-Length: 0\r\nConnection: close\r\n\r\n",
+                    status, extra_headers
+                )
+                .unwrap();
+            }
+        });
+
+        (url, receiver)
+    }
+
+    fn quick_retry(attempts: u32) -> WebhookRetry {
+        WebhookRetry {
+            attempts,
+            initial_delay: Duration::from_millis(5),
+        }
+    }
+
+    fn block_on<F: std::future::Future>(future: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+            .block_on(future)
+    }
+
+    fn webhook(url: &str, secret: Option<&str>) -> WebhookConfig {
+        WebhookConfig {
+            url: url.to_string(),
+            format: WebhookFormat::Generic,
+            events: vec![WebhookEvent::BackupFinished],
+            secret: secret.map(str::to_string),
+        }
+    }
+
+    fn finished_event() -> AppEvent {
+        AppEvent::BackupFinished {
+            save_name: "Survival/A".to_string(),
+            result: BackupResult {
+                backup_path: "/backups/Survival/A/2024-12-28_14-30-45.tar.gz".to_string(),
+                backup_name: "2024-12-28_14-30-45.tar.gz".to_string(),
+                retained_count: 3,
+                deleted_count: 0,
+                deleted_backups: Vec::new(),
+                size_bytes: 2048,
+                partial: false,
+                skipped_files: Vec::new(),
+                server_flush: None,
+            },
+            duration_ms: 34_000,
+        }
+    }
+
+    #[test]
+    fn test_hmac_sha256_rfc4231_vectors() {
+        let hex = |bytes: [u8; 32]| bytes.iter().map(|b| format!("{:02x}", b)).collect::<String>();
+        assert_eq!(
+            hex(hmac_sha256(b"Jefe", b"what do ya want for nothing?")),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+        // Keys longer than the block size are hashed first
+        assert_eq!(
+            hex(hmac_sha256(
+                &[0xaa; 131],
+                b"Test Using Larger Than Block-Size Key - Hash Key First"
+            )),
+            "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54"
+        );
+    }
+
+    #[test]
+    fn test_payload_shape() {
+        let payload = serde_json::to_value(finished_event().to_payload()).unwrap();
+        assert_eq!(payload["event"], "BackupFinished");
+        assert_eq!(payload["save_name"], "Survival/A");
+        assert_eq!(payload["backup_name"], "2024-12-28_14-30-45.tar.gz");
+        assert_eq!(payload["outcome"], "Success");
+        assert_eq!(payload["details"]["size_bytes"], 2048);
+        assert_eq!(payload["duration_ms"], 34_000);
+        assert!(payload["timestamp"].as_str().unwrap().ends_with('Z'));
+
+        let failed = AppEvent::BackupFailed {
+            save_name: "Survival/A".to_string(),
+            error: ErrorPayload::new(ErrorCode::InsufficientSpace, "disk full"),
+            duration_ms: 500,
+        };
+        let payload = serde_json::to_value(failed.to_payload()).unwrap();
+        assert_eq!(payload["event"], "BackupFailed");
+        assert_eq!(payload["outcome"], "Failure");
+        assert_eq!(payload["backup_name"], serde_json::Value::Null);
+        assert_eq!(payload["details"]["code"], "InsufficientSpace");
+
+        let low = AppEvent::DiskSpaceLow {
+            path: "/backups".to_string(),
+            free_bytes: 1,
+            threshold_bytes: 2,
+        };
+        let payload = serde_json::to_value(low.to_payload()).unwrap();
+        assert_eq!(payload["save_name"], serde_json::Value::Null);
+        assert_eq!(payload["details"]["free_bytes"], 1);
+    }
+
+    #[test]
+    fn test_delivery_is_signed() {
+        let (url, received) = mock_server(vec![(200, "")]);
+        let body = serde_json::to_string(&finished_event().to_payload()).unwrap();
+
+        let outcome = block_on(deliver(
+            &reqwest::Client::new(),
+            &webhook(&url, Some("s3cret")),
+            WebhookEvent::BackupFinished,
+            &body,
+            quick_retry(3),
+        ));
+        assert_eq!(outcome, DeliveryOutcome { attempts: 1, error: None });
+
+        let request = received.recv().unwrap();
+        assert_eq!(request.body, body);
+        assert_eq!(request.header(EVENT_HEADER), Some("BackupFinished"));
+        assert_eq!(request.header("content-type"), Some("application/json"));
+        assert_eq!(
+            request.header(SIGNATURE_HEADER),
+            Some(signature("s3cret", body.as_bytes()).as_str())
+        );
+    }
+
+    #[test]
+    fn test_unsigned_without_secret() {
+        let (url, received) = mock_server(vec![(204, "")]);
+        block_on(deliver(
+            &reqwest::Client::new(),
+            &webhook(&url, None),
+            WebhookEvent::BackupFinished,
+            "{}",
+            quick_retry(1),
+        ));
+        assert_eq!(received.recv().unwrap().header(SIGNATURE_HEADER), None);
+    }
+
+    #[test]
+    fn test_failed_attempts_are_retried() {
+        let (url, received) = mock_server(vec![(500, ""), (503, ""), (200, "")]);
+        let outcome = block_on(deliver(
+            &reqwest::Client::new(),
+            &webhook(&url, Some("s3cret")),
+            WebhookEvent::BackupFinished,
+            "{}",
+            quick_retry(3),
+        ));
+        assert_eq!(outcome, DeliveryOutcome { attempts: 3, error: None });
+        assert_eq!(received.try_iter().count(), 3);
+
+        let (url, _received) = mock_server(vec![(500, ""), (500, "")]);
+        let outcome = block_on(deliver(
+            &reqwest::Client::new(),
+            &webhook(&url, None),
+            WebhookEvent::BackupFinished,
+            "{}",
+            quick_retry(2),
+        ));
+        assert_eq!(outcome.attempts, 2);
+        assert_eq!(outcome.error.as_deref(), Some("HTTP 500"));
+    }
+
+    #[test]
+    fn test_rate_limit_honors_retry_after() {
+        let (url, received) = mock_server(vec![(429, "Retry-After: 0.3\r\n"), (200, "")]);
+        let started = Instant::now();
+        let outcome = block_on(deliver(
+            &reqwest::Client::new(),
+            &webhook(&url, None),
+            WebhookEvent::BackupFinished,
+            "{}",
+            quick_retry(3),
+        ));
+        assert_eq!(outcome, DeliveryOutcome { attempts: 2, error: None });
+        assert!(started.elapsed() >= Duration::from_millis(300));
+        assert_eq!(received.try_iter().count(), 2);
+    }
+
+    #[test]
+    fn test_discord_webhooks_receive_embeds() {
+        let _env = TestEnv::new();
+        let (url, received) = mock_server(vec![(204, "")]);
+        let discord_webhook = WebhookConfig {
+            format: WebhookFormat::Discord,
+            ..webhook(&url, None)
+        };
+
+        block_on(deliver_all(&[discord_webhook], &finished_event().to_payload(), quick_retry(1)));
+        let body: serde_json::Value = serde_json::from_str(&received.recv().unwrap().body).unwrap();
+        assert_eq!(body["embeds"][0]["title"], "✅ Backup of A completed");
+        assert_eq!(body["embeds"][0]["color"], discord::COLOR_SUCCESS);
+    }
+
+    #[test]
+    fn test_delivery_outcomes_are_recorded() {
+        let _env = TestEnv::new();
+        let (url, _received) = mock_server(vec![(200, ""), (500, "")]);
+        let payload = finished_event().to_payload();
+
+        block_on(deliver_all(&[webhook(&url, None)], &payload, quick_retry(1)));
+        block_on(deliver_all(&[webhook(&url, None)], &payload, quick_retry(1)));
+
+        let filter = HistoryFilter {
+            operation: Some(OperationType::Webhook),
+            ..HistoryFilter::default()
+        };
+        let records = history::get_operation_history(&filter, 0, 10).unwrap().records;
+        assert_eq!(records.len(), 2);
+        // Newest first
+        assert_eq!(records[0].outcome, OperationOutcome::Failure);
+        assert!(records[0].message.as_deref().unwrap().contains("HTTP 500"));
+        assert_eq!(records[1].outcome, OperationOutcome::Success);
+        assert_eq!(records[1].backup_name.as_deref(), Some("2024-12-28_14-30-45.tar.gz"));
+    }
+
+    #[test]
+    fn test_emit_without_subscribers_starts_nothing() {
+        let _env = TestEnv::new();
+        emit(finished_event());
+        assert!(wait_for_deliveries(Duration::ZERO));
+    }
+}
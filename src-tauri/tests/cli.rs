@@ -0,0 +1,230 @@
+//! Runs the headless CLI binary against temporary config, save and backup directories.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use tempfile::TempDir;
+
+const SAVE: &str = "Multiplayer/servertest";
+
+/// Temporary environment: a config directory pointing at its own saves and backups.
+struct CliEnv {
+    root: TempDir,
+}
+
+impl CliEnv {
+    fn new(retention_count: usize) -> Self {
+        let root = TempDir::new().unwrap();
+        let env = CliEnv { root };
+        fs::create_dir_all(env.config_dir()).unwrap();
+        let config = serde_json::json!({
+            "save_path": env.saves_dir(),
+            "backup_path": env.backups_dir(),
+            "retention_count": retention_count,
+        });
+        fs::write(
+            env.config_dir().join("zomboid_backup_config.json"),
+            config.to_string(),
+        )
+        .unwrap();
+
+        let save_dir = env.saves_dir().join(SAVE);
+        fs::create_dir_all(save_dir.join("map")).unwrap();
+        fs::write(save_dir.join("map_meta.bin"), b"meta").unwrap();
+        fs::write(save_dir.join("map/chunk_0_0.bin"), b"original chunk").unwrap();
+        env
+    }
+
+    fn config_dir(&self) -> PathBuf {
+        self.root.path().join("config")
+    }
+
+    fn saves_dir(&self) -> PathBuf {
+        self.root.path().join("saves")
+    }
+
+    fn backups_dir(&self) -> PathBuf {
+        self.root.path().join("backups")
+    }
+
+    fn save_backup_dir(&self) -> PathBuf {
+        self.backups_dir().join(SAVE)
+    }
+
+    /// Runs the binary with `--config-dir` and the given arguments.
+    fn run(&self, args: &[&str]) -> Output {
+        Command::new(env!("CARGO_BIN_EXE_pz-backup-tool"))
+            .arg("--config-dir")
+            .arg(self.config_dir())
+            .args(args)
+            .env("HOME", self.root.path())
+            .env("XDG_CONFIG_HOME", self.root.path())
+            .output()
+            .unwrap()
+    }
+
+    fn backup_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(self.save_backup_dir())
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .map(|e| e.file_name().to_string_lossy().into_owned())
+            .filter(|n| n.ends_with(".tar.gz"))
+            .collect();
+        names.sort();
+        names
+    }
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).into_owned()
+}
+
+/// Creates one backup through the CLI and returns its name.
+fn backup(env: &CliEnv) -> String {
+    let output = env.run(&["backup", "--save", SAVE]);
+    assert_eq!(output.status.code(), Some(0), "{}", stderr(&output));
+    assert!(stdout(&output).starts_with("Created backup "), "{}", stdout(&output));
+    env.backup_names().pop().unwrap()
+}
+
+fn copy_backup(env: &CliEnv, name: &str, copy: &str) {
+    let dir = env.save_backup_dir();
+    fs::copy(dir.join(name), dir.join(copy)).unwrap();
+}
+
+#[test]
+fn test_backup_and_list() {
+    let env = CliEnv::new(10);
+    let name = backup(&env);
+
+    let output = env.run(&["list", "--save", SAVE]);
+    assert_eq!(output.status.code(), Some(0), "{}", stderr(&output));
+    let listed = stdout(&output);
+    assert!(listed.contains(&name), "{}", listed);
+    assert!(listed.contains("1 backup(s)"), "{}", listed);
+
+    // History is written to the selected config directory
+    let history = fs::read_to_string(env.config_dir().join("history.jsonl")).unwrap();
+    assert!(history.contains(&name), "{}", history);
+}
+
+#[test]
+fn test_silent_backup() {
+    let env = CliEnv::new(10);
+    let output = env.run(&["backup", "--save", SAVE, "--silent"]);
+    assert_eq!(output.status.code(), Some(0), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "");
+    assert_eq!(env.backup_names().len(), 1);
+
+    // Runs started by an OS schedule are recorded as scheduled
+    let history = fs::read_to_string(env.config_dir().join("history.jsonl")).unwrap();
+    assert!(history.contains("\"Scheduled\""), "{}", history);
+}
+
+#[test]
+fn test_restore_requires_yes() {
+    let env = CliEnv::new(10);
+    let name = backup(&env);
+    let chunk = env.saves_dir().join(SAVE).join("map/chunk_0_0.bin");
+    fs::write(&chunk, b"changed chunk").unwrap();
+
+    let refused = env.run(&["restore", "--save", SAVE, "--backup", &name]);
+    assert_eq!(refused.status.code(), Some(2));
+    assert!(stderr(&refused).contains("--yes"), "{}", stderr(&refused));
+    assert_eq!(fs::read(&chunk).unwrap(), b"changed chunk");
+
+    let restored = env.run(&["restore", "--save", SAVE, "--backup", &name, "--yes"]);
+    assert_eq!(restored.status.code(), Some(0), "{}", stderr(&restored));
+    assert!(stdout(&restored).contains("Restored"), "{}", stdout(&restored));
+    assert_eq!(fs::read(&chunk).unwrap(), b"original chunk");
+}
+
+#[test]
+fn test_prune_dry_run_then_prune() {
+    let env = CliEnv::new(1);
+    let name = backup(&env);
+    copy_backup(&env, &name, "2000-01-01_00-00-00.tar.gz");
+    copy_backup(&env, &name, "2000-01-02_00-00-00.tar.gz");
+
+    let dry_run = env.run(&["prune", "--dry-run"]);
+    assert_eq!(dry_run.status.code(), Some(0), "{}", stderr(&dry_run));
+    assert!(stdout(&dry_run).contains("Would delete 2 backup(s)"), "{}", stdout(&dry_run));
+    assert_eq!(env.backup_names().len(), 3);
+
+    let pruned = env.run(&["prune", "--save", SAVE]);
+    assert_eq!(pruned.status.code(), Some(0), "{}", stderr(&pruned));
+    assert!(stdout(&pruned).contains("Deleted 2 backup(s)"), "{}", stdout(&pruned));
+    assert_eq!(env.backup_names().len(), 1);
+}
+
+#[test]
+fn test_exit_codes_for_invalid_input() {
+    let env = CliEnv::new(10);
+
+    let unknown_save = env.run(&["backup", "--save", "Multiplayer/missing"]);
+    assert_eq!(unknown_save.status.code(), Some(2), "{}", stderr(&unknown_save));
+
+    let unknown_backup = env.run(&["restore", "--save", SAVE, "--backup", "missing.tar.gz", "--yes"]);
+    assert_eq!(unknown_backup.status.code(), Some(2), "{}", stderr(&unknown_backup));
+
+    let bad_option = env.run(&["list", "--save", SAVE, "--frobnicate"]);
+    assert_eq!(bad_option.status.code(), Some(2));
+    assert!(stderr(&bad_option).contains("Usage:"), "{}", stderr(&bad_option));
+}
+
+#[test]
+fn test_operation_failure_exit_code() {
+    let env = CliEnv::new(10);
+    // A file where the backup directory should be makes the backup itself fail
+    fs::write(env.backups_dir(), b"not a directory").unwrap();
+
+    let output = env.run(&["backup", "--save", SAVE]);
+    assert_eq!(output.status.code(), Some(3), "{}", stderr(&output));
+    assert!(!Path::new(&env.save_backup_dir()).exists());
+}
+
+fn json(output: &Output) -> serde_json::Value {
+    serde_json::from_slice(&output.stdout)
+        .unwrap_or_else(|e| panic!("stdout is not JSON ({}): {}", e, stdout(output)))
+}
+
+#[test]
+fn test_json_output() {
+    let env = CliEnv::new(10);
+
+    let created = env.run(&["--json", "backup", "--save", SAVE]);
+    assert_eq!(created.status.code(), Some(0), "{}", stderr(&created));
+    let name = json(&created)["backup_name"].as_str().unwrap().to_string();
+    assert_eq!(env.backup_names(), vec![name.clone()]);
+
+    let listed = env.run(&["list", "--save", SAVE, "--json"]);
+    assert_eq!(listed.status.code(), Some(0), "{}", stderr(&listed));
+    let listing = json(&listed);
+    assert!(listing["generation"].is_u64());
+    assert_eq!(listing["backups"][0]["name"], name.as_str());
+
+    let pruned = env.run(&["prune", "--dry-run", "--json"]);
+    assert_eq!(pruned.status.code(), Some(0), "{}", stderr(&pruned));
+    assert_eq!(json(&pruned)[0]["dry_run"], true);
+}
+
+#[test]
+fn test_json_errors() {
+    let env = CliEnv::new(10);
+
+    let missing = env.run(&["--json", "backup", "--save", "Multiplayer/missing"]);
+    assert_eq!(missing.status.code(), Some(2));
+    assert_eq!(json(&missing)["code"], "SaveNotFound");
+
+    let refused = env.run(&["--json", "restore", "--save", SAVE, "--backup", "x.tar.gz"]);
+    assert_eq!(refused.status.code(), Some(2));
+    assert_eq!(json(&refused)["code"], "InvalidValue");
+
+    let bad_option = env.run(&["--json", "list", "--frobnicate"]);
+    assert_eq!(bad_option.status.code(), Some(2));
+    assert_eq!(json(&bad_option)["code"], "InvalidValue");
+}
@@ -0,0 +1,234 @@
+//! Drives the HTTP API over real connections against temporary save and backup directories.
+
+use pz_backup_tool_lib::config;
+use pz_backup_tool_lib::dashboard::DashboardState;
+use pz_backup_tool_lib::http_api::{ApiContext, HttpApi, HttpApiError};
+use pz_backup_tool_lib::operations::OperationQueue;
+use pz_backup_tool_lib::tasks::TaskRegistry;
+use std::fs;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::{mpsc, Arc, OnceLock};
+use std::time::Duration;
+use tempfile::TempDir;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+const TOKEN: &str = "test-token";
+
+/// Saves and backups shared by every test; the config directory is per process.
+struct ApiEnv {
+    root: TempDir,
+}
+
+impl ApiEnv {
+    fn get() -> &'static ApiEnv {
+        static ENV: OnceLock<ApiEnv> = OnceLock::new();
+        ENV.get_or_init(|| {
+            let env = ApiEnv {
+                root: TempDir::new().unwrap(),
+            };
+            let config_dir = env.root.path().join("config");
+            fs::create_dir_all(&config_dir).unwrap();
+            let config = serde_json::json!({
+                "save_path": env.saves_dir(),
+                "backup_path": env.backups_dir(),
+                "retention_count": 10,
+            });
+            fs::write(config_dir.join("zomboid_backup_config.json"), config.to_string()).unwrap();
+            config::init_storage_at(Some(config_dir));
+            env
+        })
+    }
+
+    fn saves_dir(&self) -> PathBuf {
+        self.root.path().join("saves")
+    }
+
+    fn backups_dir(&self) -> PathBuf {
+        self.root.path().join("backups")
+    }
+
+    /// Creates a save with a little content.
+    fn create_save(&self, save_name: &str) {
+        let save_dir = self.saves_dir().join(save_name);
+        fs::create_dir_all(save_dir.join("map")).unwrap();
+        fs::write(save_dir.join("map_meta.bin"), b"meta").unwrap();
+    }
+
+    fn backup_count(&self, save_name: &str) -> usize {
+        fs::read_dir(self.backups_dir().join(save_name))
+            .map(|entries| {
+                entries
+                    .filter_map(|e| e.ok())
+                    .filter(|e| e.file_name().to_string_lossy().ends_with(".tar.gz"))
+                    .count()
+            })
+            .unwrap_or(0)
+    }
+}
+
+/// App state the API operates on, with a single worker so operations queue up.
+struct TestContext {
+    dashboard: DashboardState,
+    tasks: TaskRegistry,
+    queue: OperationQueue,
+}
+
+impl ApiContext for TestContext {
+    fn dashboard(&self) -> &DashboardState {
+        &self.dashboard
+    }
+
+    fn tasks(&self) -> &TaskRegistry {
+        &self.tasks
+    }
+
+    fn queue(&self) -> &OperationQueue {
+        &self.queue
+    }
+}
+
+/// Starts an API on a free loopback port.
+async fn start() -> (SocketAddr, Arc<TestContext>) {
+    ApiEnv::get();
+    let context = Arc::new(TestContext {
+        dashboard: DashboardState::new(),
+        tasks: TaskRegistry::new(),
+        queue: OperationQueue::new(1),
+    });
+    let api = HttpApi::bind("127.0.0.1:0", TOKEN).await.unwrap();
+    let addr = api.local_addr().unwrap();
+    tokio::spawn(api.serve(Arc::clone(&context)));
+    (addr, context)
+}
+
+/// Sends a request and returns the status and parsed JSON body.
+async fn request(addr: SocketAddr, method: &str, path: &str, token: Option<&str>) -> (u16, serde_json::Value) {
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    let auth = token
+        .map(|t| format!("Authorization: Bearer {}\r\n", t))
+        .unwrap_or_default();
+    let head = format!(
+        "{} {} HTTP/1.1\r\nHost: localhost\r\n{}Content-Length: 0\r\n\r\n",
+        method, path, auth
+    );
+    stream.write_all(head.as_bytes()).await.unwrap();
+
+    let mut raw = String::new();
+    stream.read_to_string(&mut raw).await.unwrap();
+    let (head, body) = raw.split_once("\r\n\r\n").unwrap();
+    let status = head.split(' ').nth(1).unwrap().parse().unwrap();
+    (status, serde_json::from_str(body).unwrap())
+}
+
+async fn get(addr: SocketAddr, path: &str) -> (u16, serde_json::Value) {
+    request(addr, "GET", path, Some(TOKEN)).await
+}
+
+async fn post(addr: SocketAddr, path: &str) -> (u16, serde_json::Value) {
+    request(addr, "POST", path, Some(TOKEN)).await
+}
+
+#[tokio::test]
+async fn test_requests_need_the_token() {
+    let (addr, _) = start().await;
+
+    let (status, body) = request(addr, "GET", "/status", None).await;
+    assert_eq!(status, 401);
+    assert_eq!(body["code"], "Unauthorized");
+
+    let (status, _) = request(addr, "POST", "/prune", Some("wrong-token")).await;
+    assert_eq!(status, 401);
+
+    let (status, _) = get(addr, "/status").await;
+    assert_eq!(status, 200);
+
+    assert!(matches!(
+        HttpApi::bind("127.0.0.1:0", "").await,
+        Err(HttpApiError::MissingToken)
+    ));
+}
+
+#[tokio::test]
+async fn test_triggered_backup() {
+    let env = ApiEnv::get();
+    env.create_save("Survival/Triggered");
+    let (addr, context) = start().await;
+
+    let (status, created) = post(addr, "/saves/Survival%2FTriggered/backup").await;
+    assert_eq!(status, 200, "{}", created);
+    assert_eq!(env.backup_count("Survival/Triggered"), 1);
+    assert_eq!(context.tasks.list_recent().len(), 1);
+
+    let (status, listing) = get(addr, "/saves/Survival/Triggered/backups").await;
+    assert_eq!(status, 200);
+    assert_eq!(listing["backups"][0]["name"], created["backup_name"]);
+
+    let (status, saves) = get(addr, "/saves").await;
+    assert_eq!(status, 200);
+    assert!(saves
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|s| s["relative_path"] == "Survival/Triggered"));
+
+    let (status, pruned) = post(addr, "/prune?save=Survival%2FTriggered&dry_run=true").await;
+    assert_eq!(status, 200);
+    assert_eq!(pruned[0]["dry_run"], true);
+    assert_eq!(pruned[0]["save_name"], "Survival/Triggered");
+
+    let (status, missing) = post(addr, "/saves/Survival%2FMissing/backup").await;
+    assert_eq!(status, 404);
+    assert_eq!(missing["code"], "SaveNotFound");
+
+    let (status, _) = get(addr, "/saves/Survival%2FTriggered/backup").await;
+    assert_eq!(status, 405);
+
+    // Nothing outside the save and backup directories is archived or listed
+    let (status, escaping) = post(addr, "/saves/..%2F..%2Fsomewhere/backup").await;
+    assert_eq!(status, 400);
+    assert_eq!(escaping["code"], "InvalidSaveName");
+    let (status, _) = get(addr, "/saves/..%2F..%2Fsomewhere/backups").await;
+    assert_eq!(status, 400);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_concurrent_requests_are_queued() {
+    let env = ApiEnv::get();
+    env.create_save("Survival/QueuedA");
+    env.create_save("Survival/QueuedB");
+    let (addr, context) = start().await;
+
+    // Occupy the only worker, as a long backup started from the UI would
+    let (release, blocked) = mpsc::channel::<()>();
+    let blocker = {
+        let context = Arc::clone(&context);
+        tokio::spawn(async move {
+            context
+                .queue
+                .run(move || {
+                    let _ = blocked.recv();
+                    Ok::<(), pz_backup_tool_lib::backup::BackupError>(())
+                })
+                .await
+        })
+    };
+
+    let first = tokio::spawn(post(addr, "/saves/Survival%2FQueuedA/backup"));
+    let second = tokio::spawn(post(addr, "/saves/Survival%2FQueuedB/backup"));
+    tokio::time::sleep(Duration::from_millis(300)).await;
+
+    // Both wait for the queue, while requests that don't need it are still answered
+    assert_eq!(env.backup_count("Survival/QueuedA"), 0);
+    assert_eq!(env.backup_count("Survival/QueuedB"), 0);
+    assert_eq!(context.tasks.list_active().len(), 2);
+    assert_eq!(get(addr, "/status").await.0, 200);
+
+    release.send(()).unwrap();
+    blocker.await.unwrap().unwrap();
+    assert_eq!(first.await.unwrap().0, 200);
+    assert_eq!(second.await.unwrap().0, 200);
+    assert_eq!(env.backup_count("Survival/QueuedA"), 1);
+    assert_eq!(env.backup_count("Survival/QueuedB"), 1);
+}
@@ -0,0 +1,85 @@
+//! Runs update checks against a local mock of the GitHub releases API.
+
+use pz_backup_tool_lib::config;
+use pz_backup_tool_lib::update_checker::{self, ReleaseSource, UpdateChannel, UPDATE_API_URL_ENV};
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::sync::mpsc;
+use std::time::Duration;
+use tempfile::TempDir;
+
+/// Serves one JSON body per connection and reports the request line of each.
+fn mock_api(bodies: Vec<String>) -> (String, mpsc::Receiver<String>) {
+    let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+    let url = format!("http://{}/", listener.local_addr().unwrap());
+    let (sender, receiver) = mpsc::channel();
+
+    std::thread::spawn(move || {
+        for body in bodies {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+            let mut line = String::new();
+            while reader.read_line(&mut line).unwrap() > 0 && line != "\r\n" {
+                line.clear();
+            }
+            let _ = sender.send(request_line.trim_end().to_string());
+
+            let mut stream = stream;
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            )
+            .unwrap();
+        }
+    });
+
+    (url, receiver)
+}
+
+fn release_json(version: &str) -> String {
+    format!(
+        r#"{{"tag_name":"v{0}","name":"{0}","html_url":"https://example.com/{0}","body":"notes {0}","published_at":"2024-01-01T00:00:00Z","prerelease":false}}"#,
+        version
+    )
+}
+
+#[tokio::test]
+async fn test_check_for_updates_reads_configured_source() {
+    let config_dir = TempDir::new().unwrap();
+    config::init_storage_at(Some(config_dir.path().to_path_buf()));
+    for name in ["HTTPS_PROXY", "https_proxy", "HTTP_PROXY", "http_proxy"] {
+        std::env::remove_var(name);
+    }
+
+    let (url, requests) = mock_api(vec![
+        release_json("99.0.0"),
+        format!("[{},{}]", release_json("99.0.0"), release_json("98.0.0")),
+    ]);
+    let env = |name: &str| (name == UPDATE_API_URL_ENV).then(|| url.clone());
+    let source = ReleaseSource::resolve(None, Some("me/fork"), env).unwrap();
+
+    let info = update_checker::check_for_updates(
+        &source,
+        None,
+        None,
+        Duration::from_secs(5),
+        UpdateChannel::Stable,
+        None,
+    )
+    .await
+    .unwrap();
+
+    assert!(info.has_update);
+    assert_eq!(info.latest_version, "99.0.0");
+    assert_eq!(
+        requests.recv().unwrap(),
+        "GET /repos/me/fork/releases/latest HTTP/1.1"
+    );
+    assert!(requests.recv().unwrap().starts_with("GET /repos/me/fork/releases?"));
+    let versions: Vec<_> = info.intermediate_releases.iter().map(|r| r.tag_name.as_str()).collect();
+    assert_eq!(versions, ["v98.0.0"]);
+}
@@ -1,4 +1,4 @@
-import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 import { useCallback, useEffect, useState } from "react";
 import { Dashboard, Layout, Settings, UpdateAvailableModal } from "./components";
 
@@ -9,13 +9,14 @@ interface UpdateInfo {
   release_url: string;
   release_notes: string;
   published_at: string;
+  channel?: "Stable" | "Beta";
+  is_prerelease?: boolean;
 }
 
 function App() {
   const [showSettings, setShowSettings] = useState(false);
   const [showUpdateModal, setShowUpdateModal] = useState(false);
   const [updateInfo, setUpdateInfo] = useState<UpdateInfo | null>(null);
-  const [hasCheckedForUpdates, setHasCheckedForUpdates] = useState(false);
 
   const handleSettingsClick = useCallback(() => {
     setShowSettings((prev) => !prev);
@@ -29,39 +30,16 @@ function App() {
     setShowUpdateModal(false);
   }, []);
 
-  // Check for updates on startup (if enabled)
+  // The backend checks for updates periodically (if enabled) and reports new releases
   useEffect(() => {
-    const checkForUpdatesOnStartup = async () => {
-      // Don't check if already checked
-      if (hasCheckedForUpdates) {
-        return;
-      }
-
-      try {
-        // Check if auto-update is enabled
-        const autoCheck = await invoke<boolean>("get_auto_check_updates");
-
-        if (autoCheck) {
-          // Perform update check
-          const info: UpdateInfo = await invoke("check_for_updates");
-
-          if (info.has_update) {
-            setUpdateInfo(info);
-            // Delay showing the modal to let user settle in first
-            setTimeout(() => {
-              setShowUpdateModal(true);
-            }, 5000);
-          }
-        }
-      } catch (err) {
-        console.error("Failed to check for updates on startup:", err);
-      } finally {
-        setHasCheckedForUpdates(true);
-      }
+    const unlisten = listen<UpdateInfo>("update-available", (event) => {
+      setUpdateInfo(event.payload);
+      setShowUpdateModal(true);
+    });
+    return () => {
+      void unlisten.then((stop) => stop());
     };
-
-    checkForUpdatesOnStartup();
-  }, [hasCheckedForUpdates]);
+  }, []);
 
   return (
     <>
@@ -1,10 +1,11 @@
 import { invoke } from "@tauri-apps/api/core";
-import { useCallback, useEffect, useState } from "react";
+import { useCallback, useEffect, useRef, useState } from "react";
 import { useBackupTags, useTags } from "../hooks/useTags";
 import type { Tag } from "../types/tags";
 import { backupTarget } from "../types/tags";
 import { TagEditor } from "./TagEditor";
 import { TagList } from "./TagList";
+import type { SkippedFile } from "./RestoreModal";
 
 interface BackupListProps {
   saveName: string | null;
@@ -21,6 +22,21 @@ interface BackupInfo {
   path: string;
   tags: Tag[];
   thumb_data?: string;
+  partial: boolean;
+  skipped_files: SkippedFile[];
+  server_flush?: ServerFlush;
+}
+
+/** RCON flush before a server backup */
+interface ServerFlush {
+  flushed: boolean;
+  failure?: string;
+  message?: string;
+}
+
+interface BackupListing {
+  generation: number;
+  backups: BackupInfo[];
 }
 
 interface BackupItem {
@@ -31,6 +47,9 @@ interface BackupItem {
   backupPath: string;
   tags: Tag[];
   thumbData?: string;
+  skippedCount: number;
+  /** Error of the failed server flush, if the backup was taken without flush */
+  flushError?: string;
 }
 
 /**
@@ -84,6 +103,8 @@ export const BackupList: React.FC<BackupListProps> = ({
   const [backups, setBackups] = useState<BackupItem[]>([]);
   const [loading, setLoading] = useState(false);
   const [error, setError] = useState<string | null>(null);
+  // Listing currently shown; an unchanged generation means nothing to re-render
+  const shownListing = useRef<{ saveName: string; generation: number } | null>(null);
 
   // Tag-related state
   const { tags: availableTags, loadAllTags } = useTags();
@@ -105,11 +126,16 @@ export const BackupList: React.FC<BackupListProps> = ({
     try {
       setLoading(true);
       setError(null);
-      const backupInfos: BackupInfo[] = await invoke("list_backups_command", {
+      const listing: BackupListing = await invoke("list_backups_command", {
         saveName,
       });
 
-      const items: BackupItem[] = backupInfos.map((info) => ({
+      const shown = shownListing.current;
+      if (shown?.saveName === saveName && shown.generation === listing.generation) {
+        return;
+      }
+
+      const items: BackupItem[] = listing.backups.map((info) => ({
         name: info.name,
         sizeFormatted: info.size_formatted,
         createdAt: formatDateTime(info.created_at),
@@ -117,13 +143,20 @@ export const BackupList: React.FC<BackupListProps> = ({
         backupPath: info.path,
         tags: info.tags,
         thumbData: info.thumb_data,
+        skippedCount: info.skipped_files.length,
+        flushError:
+          info.server_flush && !info.server_flush.flushed
+            ? info.server_flush.message ?? "Server flush failed"
+            : undefined,
       }));
 
       setBackups(items);
+      shownListing.current = { saveName, generation: listing.generation };
     } catch (err) {
       console.error("Failed to load backups:", err);
       setError("Failed to load backups");
       setBackups([]);
+      shownListing.current = null;
     } finally {
       setLoading(false);
     }
@@ -134,6 +167,7 @@ export const BackupList: React.FC<BackupListProps> = ({
       loadBackups();
     } else {
       setBackups([]);
+      shownListing.current = null;
     }
   }, [saveName, loadBackups]);
 
@@ -350,6 +384,22 @@ export const BackupList: React.FC<BackupListProps> = ({
                       <span className="font-medium text-foreground">{backup.createdAt}</span>
                     </div>
                     <span className="text-sm text-gray-500">({backup.timeAgo})</span>
+                    {backup.skippedCount > 0 && (
+                      <span
+                        className="text-xs px-2 py-0.5 rounded bg-red-900/30 text-red-300"
+                        title={`${backup.skippedCount} locked file(s) missing from this backup`}
+                      >
+                        Partial
+                      </span>
+                    )}
+                    {backup.flushError && (
+                      <span
+                        className="text-xs px-2 py-0.5 rounded bg-yellow-900/30 text-yellow-300"
+                        title={`Taken without server flush: ${backup.flushError}`}
+                      >
+                        No flush
+                      </span>
+                    )}
                   </div>
                   {/* Tags display */}
                   <div className="mt-1">
@@ -1,7 +1,9 @@
 import { invoke } from "@tauri-apps/api/core";
 import { useCallback, useEffect, useState } from "react";
+import { errorMessage } from "../types/errors";
 import { DeleteModal, RestoreModal, Toast, type ToastType, UndoModal } from "./";
 import { BackupList } from "./BackupList";
+import type { ModCompatibilityReport, SkippedFile } from "./RestoreModal";
 import { SaveSelector } from "./SaveSelector";
 
 interface BackupResult {
@@ -9,6 +11,7 @@ interface BackupResult {
   backup_name: string;
   retained_count: number;
   deleted_count: number;
+  deleted_backups: string[];
 }
 
 interface GameProcessCheckResult {
@@ -22,6 +25,7 @@ interface Config {
   retention_count: number;
   auto_check_updates?: boolean;
   last_selected_save?: string | null;
+  locale?: string | null;
 }
 
 /**
@@ -41,6 +45,8 @@ export const Dashboard: React.FC = () => {
     saveName: string;
     backupName: string;
     backupTime: string;
+    skippedFiles?: SkippedFile[];
+    modReport?: ModCompatibilityReport;
   } | null>(null);
   const [isRestoring, setIsRestoring] = useState(false);
 
@@ -66,7 +72,7 @@ export const Dashboard: React.FC = () => {
 
   // Format error message for display (truncate if too long)
   const formatErrorMessage = useCallback((err: unknown): string => {
-    const errStr = String(err);
+    const errStr = errorMessage(err);
     return errStr.length > 100 ? `${errStr.substring(0, 100)}...` : errStr;
   }, []);
 
@@ -138,6 +144,26 @@ export const Dashboard: React.FC = () => {
   const handleRestore = (saveName: string, backupName: string, backupTime: string) => {
     setRestoreData({ saveName, backupName, backupTime });
     setShowRestoreModal(true);
+
+    // Partial backups are flagged in the confirmation once their details arrive
+    invoke<{ skipped_files: SkippedFile[] }>("get_backup_info_command", { saveName, backupName })
+      .then((info) =>
+        setRestoreData((current) =>
+          current?.backupName === backupName
+            ? { ...current, skippedFiles: info.skipped_files }
+            : current,
+        ),
+      )
+      .catch((err) => console.error("Failed to load backup details:", err));
+
+    // Missing or updated mods are flagged once the mod check finishes
+    invoke<ModCompatibilityReport>("check_backup_mods_command", { saveName, backupName })
+      .then((modReport) =>
+        setRestoreData((current) =>
+          current?.backupName === backupName ? { ...current, modReport } : current,
+        ),
+      )
+      .catch((err) => console.error("Failed to check backup mods:", err));
   };
 
   // Confirm restore handler
@@ -198,7 +224,7 @@ export const Dashboard: React.FC = () => {
 
     try {
       setIsDeleting(true);
-      await invoke("delete_backup_command", {
+      const result = await invoke<{ warnings: string[] }>("delete_backup_command", {
         saveName: deleteData.saveName,
         backupName: deleteData.backupName,
       });
@@ -206,7 +232,11 @@ export const Dashboard: React.FC = () => {
       // Trigger refresh of backup list
       refreshBackupList();
 
-      showToast("Backup deleted successfully", "success");
+      if (result.warnings.length > 0) {
+        showToast(result.warnings.join("\n"), "warning");
+      } else {
+        showToast("Backup deleted successfully", "success");
+      }
       setShowDeleteModal(false);
       setDeleteData(null);
     } catch (err) {
@@ -375,6 +405,8 @@ export const Dashboard: React.FC = () => {
           saveName={restoreData.saveName}
           backupName={restoreData.backupName}
           backupTime={restoreData.backupTime}
+          skippedFiles={restoreData.skippedFiles}
+          modReport={restoreData.modReport}
           onConfirm={handleConfirmRestore}
           onCancel={handleCancelRestore}
           isRestoring={isRestoring}
@@ -1,10 +1,43 @@
 import { useEffect } from "react";
 
+/** A save file a partial backup left out */
+export interface SkippedFile {
+  path: string;
+  reason: string;
+}
+
+/** A mod of the backup that isn't installed */
+export interface MissingMod {
+  id: string;
+  workshop_id: string | null;
+  workshop_url: string | null;
+}
+
+/** A mod whose installed content is newer than at backup time */
+export interface UpdatedMod {
+  id: string;
+  workshop_id: string | null;
+  backed_up_modified_at: string;
+  installed_modified_at: string;
+}
+
+/** Comparison of the backup's mod manifest with the installed mods */
+export interface ModCompatibilityReport {
+  has_manifest: boolean;
+  mod_count: number;
+  missing: MissingMod[];
+  updated: UpdatedMod[];
+}
+
 interface RestoreModalProps {
   isOpen: boolean;
   saveName: string | null;
   backupName: string | null;
   backupTime?: string;
+  /** Files missing from the backup; non-empty for partial backups */
+  skippedFiles?: SkippedFile[];
+  /** Mod check of the backup, once loaded */
+  modReport?: ModCompatibilityReport;
   onConfirm: () => void;
   onCancel: () => void;
   isRestoring?: boolean;
@@ -19,6 +52,8 @@ export const RestoreModal: React.FC<RestoreModalProps> = ({
   saveName,
   backupName,
   backupTime,
+  skippedFiles = [],
+  modReport,
   onConfirm,
   onCancel,
   isRestoring = false,
@@ -90,6 +125,69 @@ export const RestoreModal: React.FC<RestoreModalProps> = ({
             {backupTime && <p className="text-xs text-gray-500 mt-2">Created: {backupTime}</p>}
           </div>
 
+          {/* Partial backup warning */}
+          {skippedFiles.length > 0 && (
+            <div className="bg-red-900/20 border border-red-900/50 rounded p-3 mb-4">
+              <p className="text-sm text-red-300 font-medium">Partial backup</p>
+              <p className="text-xs text-gray-400 mt-1">
+                {skippedFiles.length} file{skippedFiles.length === 1 ? " was" : "s were"} locked by
+                the game when this backup was made and {skippedFiles.length === 1 ? "is" : "are"}{" "}
+                missing from it:
+              </p>
+              <ul className="mt-2 max-h-24 overflow-y-auto text-xs font-mono text-gray-300">
+                {skippedFiles.map((file) => (
+                  <li key={file.path} title={file.reason} className="break-all">
+                    {file.path}
+                  </li>
+                ))}
+              </ul>
+            </div>
+          )}
+
+          {/* Mod compatibility warning */}
+          {modReport && (modReport.missing.length > 0 || modReport.updated.length > 0) && (
+            <div className="bg-yellow-900/20 border border-yellow-900/50 rounded p-3 mb-4">
+              <p className="text-sm text-yellow-200 font-medium">Mods differ from this backup</p>
+              {modReport.missing.length > 0 && (
+                <>
+                  <p className="text-xs text-gray-400 mt-1">
+                    Not installed ({modReport.missing.length} of {modReport.mod_count}):
+                  </p>
+                  <ul className="mt-1 max-h-24 overflow-y-auto text-xs font-mono text-gray-300">
+                    {modReport.missing.map((mod) => (
+                      <li key={mod.id} className="break-all">
+                        {mod.workshop_url ? (
+                          <a
+                            href={mod.workshop_url}
+                            target="_blank"
+                            rel="noreferrer"
+                            className="underline hover:text-foreground"
+                          >
+                            {mod.id}
+                          </a>
+                        ) : (
+                          mod.id
+                        )}
+                      </li>
+                    ))}
+                  </ul>
+                </>
+              )}
+              {modReport.updated.length > 0 && (
+                <>
+                  <p className="text-xs text-gray-400 mt-2">Updated since the backup:</p>
+                  <ul className="mt-1 max-h-24 overflow-y-auto text-xs font-mono text-gray-300">
+                    {modReport.updated.map((mod) => (
+                      <li key={mod.id} className="break-all">
+                        {mod.id}
+                      </li>
+                    ))}
+                  </ul>
+                </>
+              )}
+            </div>
+          )}
+
           {/* Safety warning */}
           <div className="flex items-start space-x-3 bg-yellow-900/20 border border-yellow-900/50 rounded p-3">
             <svg
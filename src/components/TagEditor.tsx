@@ -25,7 +25,7 @@ export const TagEditor: React.FC<TagEditorProps> = ({
   onSave,
   onCancel,
 }) => {
-  const { createTag, loadAllTags } = useTags();
+  const { createTag, loadAllTags, suggestTagColor } = useTags();
   const [selectedTags, setSelectedTags] = useState<Tag[]>(currentTags);
   const [saving, setSaving] = useState(false);
   const [error, setError] = useState<string | null>(null);
@@ -123,6 +123,7 @@ export const TagEditor: React.FC<TagEditorProps> = ({
             selectedTags={selectedTags}
             onTagToggle={handleTagToggle}
             onCreateTag={handleCreateTag}
+            suggestColor={suggestTagColor}
             disabled={saving}
           />
 
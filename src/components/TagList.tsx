@@ -21,12 +21,14 @@ export const TagList: React.FC<TagListProps> = ({ tags, onRemoveTag, editable =
       {tags.map((tag) => (
         <span
           key={tag.name}
+          title={tag.description}
           className="inline-flex items-center gap-1 px-2 py-0.5 rounded-full text-xs font-medium"
           style={{
             backgroundColor: tag.color,
-            color: shouldUseLightText(tag.color) ? "#FFFFFF" : "#000000",
+            color: tag.text_color ?? (shouldUseLightText(tag.color) ? "#FFFFFF" : "#000000"),
           }}
         >
+          {tag.icon && <span className="mr-1" aria-hidden="true">{tag.icon}</span>}
           {tag.name}
           {editable && onRemoveTag && (
             <button
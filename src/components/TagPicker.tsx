@@ -7,6 +7,7 @@ interface TagPickerProps {
   selectedTags: Tag[];
   onTagToggle: (tag: Tag) => void;
   onCreateTag?: (name: string, color: string) => void;
+  suggestColor?: () => Promise<string | null>;
   disabled?: boolean;
 }
 
@@ -19,6 +20,7 @@ export const TagPicker: React.FC<TagPickerProps> = ({
   selectedTags,
   onTagToggle,
   onCreateTag,
+  suggestColor,
   disabled = false,
 }) => {
   const [showCreateForm, setShowCreateForm] = useState(false);
@@ -51,6 +53,22 @@ export const TagPicker: React.FC<TagPickerProps> = ({
     setShowCreateForm(false);
   };
 
+  const openCreateForm = async () => {
+    setShowCreateForm(true);
+    const suggested = await suggestColor?.();
+    if (!suggested) {
+      return;
+    }
+    // Pre-select the swatch if the suggestion is one, else fill the custom color
+    const swatch = DEFAULT_TAG_COLORS.find((c) => c.value.toUpperCase() === suggested.toUpperCase());
+    if (swatch) {
+      setNewTagColor(swatch.value);
+      setCustomColor("");
+    } else {
+      setCustomColor(suggested);
+    }
+  };
+
   const isTagSelected = (tag: Tag) => selectedTagNames.has(tag.name);
 
   return (
@@ -79,9 +97,10 @@ export const TagPicker: React.FC<TagPickerProps> = ({
                   `}
                   style={{
                     backgroundColor: tag.color,
-                    color: shouldUseLightText(tag.color) ? "#FFFFFF" : "#000000",
+                    color: tag.text_color ?? (shouldUseLightText(tag.color) ? "#FFFFFF" : "#000000"),
                   }}
                 >
+                  {tag.icon && <span className="mr-1" aria-hidden="true">{tag.icon}</span>}
                   {tag.name}
                   {selected && (
                     <span className="ml-1.5">
@@ -110,7 +129,7 @@ export const TagPicker: React.FC<TagPickerProps> = ({
           {!showCreateForm ? (
             <button
               type="button"
-              onClick={() => setShowCreateForm(true)}
+              onClick={openCreateForm}
               disabled={disabled}
               className={`
                 text-sm text-blue-400 hover:text-blue-300 transition-colors
@@ -1,4 +1,7 @@
-import { useEffect } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import { useEffect, useState } from "react";
+import { errorMessage, isErrorPayload } from "../types/errors";
 
 interface UpdateInfo {
   has_update: boolean;
@@ -6,7 +9,70 @@ interface UpdateInfo {
   latest_version: string;
   release_url: string;
   release_notes: string;
+  release_notes_html?: string;
   published_at: string;
+  channel?: "Stable" | "Beta";
+  is_prerelease?: boolean;
+  asset?: ReleaseAsset | null;
+  intermediate_releases?: ReleaseNotes[];
+}
+
+interface ReleaseAsset {
+  name: string;
+  size: number;
+  browser_download_url: string;
+}
+
+interface ReleaseNotes {
+  tag_name: string;
+  body: string;
+  body_html?: string;
+  published_at: string;
+}
+
+interface DownloadProgress {
+  downloaded: number;
+  total: number;
+  bytes_per_sec: number;
+}
+
+interface TaskInfo {
+  id: number;
+  kind: string;
+}
+
+interface InstalledUpdate {
+  path: string;
+  action: "LaunchedInstaller" | "PlacedAppImage" | "OpenedPackage" | "Downloaded";
+  checksum: { type: "Verified"; sha256: string } | { type: "Unverified"; reason: string };
+}
+
+/**
+ * Release notes as the sanitized HTML the backend rendered, or the raw markdown for
+ * older backends. Links open in the browser instead of the app window.
+ */
+function ReleaseNotesBody({ markdown, html }: { markdown: string; html?: string }) {
+  if (!markdown) {
+    return <div className="text-sm text-gray-300">No release notes available.</div>;
+  }
+  if (!html) {
+    return <div className="text-sm text-gray-300 whitespace-pre-wrap">{markdown}</div>;
+  }
+  const handleClick = (event: React.MouseEvent<HTMLDivElement>) => {
+    const link = (event.target as HTMLElement).closest("a");
+    if (link) {
+      event.preventDefault();
+      window.open(link.href, "_blank");
+    }
+  };
+  return (
+    <div
+      className="text-sm text-gray-300 space-y-2 [&_a]:text-primary [&_a]:underline [&_code]:bg-gray-800 [&_code]:px-1 [&_code]:rounded [&_h1]:font-semibold [&_h2]:font-semibold [&_h3]:font-semibold [&_ol]:list-decimal [&_ol]:pl-5 [&_pre]:bg-gray-800 [&_pre]:p-2 [&_pre]:rounded [&_pre]:overflow-x-auto [&_ul]:list-disc [&_ul]:pl-5 [&_blockquote]:border-l-2 [&_blockquote]:border-gray-700 [&_blockquote]:pl-3"
+      onClick={handleClick}
+      // Sanitized by the backend (markdown::render_html): allowlisted tags, safe links only
+      dangerouslySetInnerHTML={{ __html: html }}
+    />
+  );
 }
 
 interface UpdateAvailableModalProps {
@@ -20,6 +86,11 @@ export const UpdateAvailableModal: React.FC<UpdateAvailableModalProps> = ({
   onClose,
   updateInfo,
 }) => {
+  const [isInstalling, setIsInstalling] = useState(false);
+  const [progress, setProgress] = useState<DownloadProgress | null>(null);
+  const [installed, setInstalled] = useState<InstalledUpdate | null>(null);
+  const [error, setError] = useState<string | null>(null);
+
   // Handle ESC key press
   useEffect(() => {
     if (!isOpen) return;
@@ -40,6 +111,50 @@ export const UpdateAvailableModal: React.FC<UpdateAvailableModalProps> = ({
     window.open(updateInfo.release_url, "_blank");
   };
 
+  const handleInstall = async () => {
+    setIsInstalling(true);
+    setError(null);
+    setProgress(null);
+    const unlisten = await listen<DownloadProgress>("update-download-progress", (event) => {
+      setProgress(event.payload);
+    });
+    try {
+      setInstalled(await invoke<InstalledUpdate>("download_update"));
+    } catch (err) {
+      if (!(isErrorPayload(err) && err.code === "Cancelled")) {
+        setError(`Update failed: ${errorMessage(err)}`);
+      }
+    } finally {
+      unlisten();
+      setIsInstalling(false);
+    }
+  };
+
+  // The download runs as an "Update" task, cancelled like any other
+  const handleCancelInstall = async () => {
+    try {
+      const tasks = await invoke<TaskInfo[]>("list_active_tasks");
+      const download = tasks.find((task) => task.kind === "Update");
+      if (download) {
+        await invoke("cancel_task", { id: download.id });
+      }
+    } catch (err) {
+      setError(`Failed to cancel the update: ${errorMessage(err)}`);
+    }
+  };
+
+  const handleSkip = async () => {
+    try {
+      await invoke("skip_version", { version: updateInfo.latest_version });
+      onClose();
+    } catch (err) {
+      setError(`Failed to skip version: ${errorMessage(err)}`);
+    }
+  };
+
+  const progressPercent =
+    progress && progress.total > 0 ? Math.floor((progress.downloaded / progress.total) * 100) : 0;
+
   return (
     <div className="fixed inset-0 z-50 flex items-center justify-center bg-black/50">
       <div className="bg-[#1a1a1a] border border-gray-800 rounded-lg shadow-xl w-full max-w-lg mx-4">
@@ -78,19 +193,65 @@ export const UpdateAvailableModal: React.FC<UpdateAvailableModalProps> = ({
             <p className="text-sm text-gray-400">Current version: {updateInfo.current_version}</p>
             <p className="text-sm text-primary font-medium">
               Latest version: {updateInfo.latest_version}
+              {updateInfo.is_prerelease && (
+                <span className="ml-2 text-xs text-yellow-400">(Pre-release)</span>
+              )}
             </p>
+            {updateInfo.asset && (
+              <p className="text-xs text-gray-500">
+                {updateInfo.asset.name} · {(updateInfo.asset.size / (1024 * 1024)).toFixed(1)} MB
+              </p>
+            )}
           </div>
 
           {/* Release notes */}
           <div>
             <h3 className="text-sm font-medium text-foreground mb-2">What's New</h3>
             <div className="bg-gray-900 border border-gray-800 rounded-lg p-4 max-h-48 overflow-y-auto">
-              <div className="text-sm text-gray-300 whitespace-pre-wrap">
-                {updateInfo.release_notes || "No release notes available."}
-              </div>
+              <ReleaseNotesBody markdown={updateInfo.release_notes} html={updateInfo.release_notes_html} />
+              {/* Skipped versions, newest first below the latest notes */}
+              {[...(updateInfo.intermediate_releases ?? [])].reverse().map((release) => (
+                <div key={release.tag_name} className="mt-4 pt-4 border-t border-gray-800">
+                  <p className="text-xs font-medium text-gray-400 mb-1">
+                    {release.tag_name} · {new Date(release.published_at).toLocaleDateString()}
+                  </p>
+                  <ReleaseNotesBody markdown={release.body} html={release.body_html} />
+                </div>
+              ))}
             </div>
           </div>
 
+          {/* Install status */}
+          {isInstalling && (
+            <div className="space-y-1">
+              <div className="h-2 bg-gray-800 rounded-full overflow-hidden">
+                <div className="h-full bg-primary transition-all" style={{ width: `${progressPercent}%` }} />
+              </div>
+              <p className="text-xs text-gray-400">
+                Downloading... {progressPercent}%
+                {progress && progress.bytes_per_sec > 0 &&
+                  ` · ${(progress.bytes_per_sec / (1024 * 1024)).toFixed(1)} MB/s`}
+              </p>
+            </div>
+          )}
+          {installed && (
+            <p className="text-sm text-green-400">
+              {installed.action === "LaunchedInstaller"
+                ? "The installer has started. Close the app to finish updating."
+                : installed.action === "OpenedPackage"
+                  ? "The update was opened. Follow the installer to finish updating."
+                  : installed.action === "Downloaded"
+                    ? `The update was saved to ${installed.path}. Extract it over the app to finish updating.`
+                    : `The new version was saved to ${installed.path}. Start it to finish updating.`}
+            </p>
+          )}
+          {installed?.checksum.type === "Unverified" && (
+            <p className="text-sm text-yellow-400">
+              Warning: the download could not be verified. {installed.checksum.reason}.
+            </p>
+          )}
+          {error && <p className="text-sm text-red-400">{error}</p>}
+
           {/* Published date */}
           <p className="text-xs text-gray-500">
             Published: {new Date(updateInfo.published_at).toLocaleDateString()}
@@ -106,13 +267,41 @@ export const UpdateAvailableModal: React.FC<UpdateAvailableModalProps> = ({
           >
             Remind Me Later
           </button>
+          <button
+            type="button"
+            onClick={handleSkip}
+            disabled={isInstalling}
+            className="px-4 py-2 bg-gray-800 hover:bg-gray-700 text-foreground rounded-lg transition-colors disabled:opacity-50 disabled:cursor-not-allowed"
+          >
+            Skip This Version
+          </button>
           <button
             type="button"
             onClick={handleDownload}
-            className="px-6 py-2 bg-primary hover:bg-red-700 text-white rounded-lg transition-colors"
+            className="px-4 py-2 bg-gray-800 hover:bg-gray-700 text-foreground rounded-lg transition-colors"
           >
-            Download Update
+            Open Release Page
           </button>
+          {isInstalling && (
+            <button
+              type="button"
+              onClick={handleCancelInstall}
+              className="px-4 py-2 bg-gray-800 hover:bg-gray-700 text-foreground rounded-lg transition-colors"
+            >
+              Cancel Download
+            </button>
+          )}
+          {/* Without an asset for this platform, the release page is the way to update */}
+          {updateInfo.asset && (
+            <button
+              type="button"
+              onClick={handleInstall}
+              disabled={isInstalling || installed !== null}
+              className="px-6 py-2 bg-primary hover:bg-red-700 text-white rounded-lg transition-colors disabled:opacity-50 disabled:cursor-not-allowed"
+            >
+              {isInstalling ? "Installing..." : "Install Update"}
+            </button>
+          )}
         </div>
       </div>
     </div>
@@ -3,8 +3,23 @@
  */
 
 import { invoke } from "@tauri-apps/api/core";
-import { useCallback, useState } from "react";
-import type { Tag } from "../types/tags";
+import { listen } from "@tauri-apps/api/event";
+import { useCallback, useEffect, useState } from "react";
+import type {
+  AutoTagRule,
+  BackupTrigger,
+  DeletedTag,
+  OrphanReport,
+  RepairSummary,
+  SmartCollection,
+  StoredTagTarget,
+  Tag,
+  TagOp,
+  TagStatistics,
+  TagsChange,
+  TagsCsvExport,
+  TagUsage,
+} from "../types/tags";
 
 /**
  * Tag management hook.
@@ -31,15 +46,270 @@ export function useTags() {
     }
   }, []);
 
+  // Reload after changes made in other views or outside the app
+  useEffect(() => {
+    const unlisten = listen<TagsChange>("tags-changed", () => {
+      void loadAllTags();
+    });
+    return () => {
+      void unlisten.then((stop) => stop());
+    };
+  }, [loadAllTags]);
+
+  /**
+   * Removes tags of backups and saves deleted outside the app.
+   * Large removals are only reported until called with `confirm`.
+   */
+  const pruneOrphanedTags = useCallback(
+    async (confirm = false): Promise<OrphanReport | null> => {
+      try {
+        setError(null);
+        const report: OrphanReport = await invoke("prune_orphaned_tags_command", { confirm });
+        return report;
+      } catch (err) {
+        console.error("Failed to prune orphaned tags:", err);
+        setError("Failed to prune orphaned tags");
+        return null;
+      }
+    },
+    []
+  );
+
+  /**
+   * Removes undefined tag names and merges duplicate associations in tags.json.
+   */
+  const repairDatabase = useCallback(async (): Promise<RepairSummary | null> => {
+    try {
+      setError(null);
+      const summary: RepairSummary = await invoke("repair_database_command");
+      return summary;
+    } catch (err) {
+      console.error("Failed to repair tags database:", err);
+      setError("Failed to repair tags database");
+      return null;
+    }
+  }, []);
+
+  /**
+   * Writes every tag and association to a CSV file.
+   */
+  const exportTagsCsv = useCallback(async (path: string): Promise<TagsCsvExport | null> => {
+    try {
+      setError(null);
+      const result: TagsCsvExport = await invoke("export_tags_csv_command", { path });
+      return result;
+    } catch (err) {
+      console.error("Failed to export tags:", err);
+      setError("Failed to export tags");
+      return null;
+    }
+  }, []);
+
+  /**
+   * Gets the tags added to backups or saves most recently, most recent first.
+   */
+  const getRecentTags = useCallback(async (): Promise<Tag[]> => {
+    try {
+      setError(null);
+      const recent: Tag[] = await invoke("get_recent_tags_command");
+      return recent;
+    } catch (err) {
+      console.error("Failed to get recent tags:", err);
+      setError("Failed to get recent tags");
+      return [];
+    }
+  }, []);
+
+  /**
+   * Searches tags by name or alias, best matches first.
+   */
+  const searchTags = useCallback(async (query: string, limit: number): Promise<Tag[]> => {
+    try {
+      return await invoke<Tag[]>("search_tags_command", { query, limit });
+    } catch (err) {
+      console.error("Failed to search tags:", err);
+      return [];
+    }
+  }, []);
+
+  /**
+   * Gets how many backups and saves each tag is applied to.
+   */
+  const getTagUsage = useCallback(async (): Promise<TagUsage[]> => {
+    try {
+      setError(null);
+      const usage: TagUsage[] = await invoke("get_tag_usage_command");
+      return usage;
+    } catch (err) {
+      console.error("Failed to get tag usage:", err);
+      setError("Failed to get tag usage");
+      return [];
+    }
+  }, []);
+
+  /**
+   * Suggests a color for a new tag that stands apart from existing tag colors.
+   */
+  const suggestTagColor = useCallback(async (): Promise<string | null> => {
+    try {
+      const color: string = await invoke("suggest_tag_color_command");
+      return color;
+    } catch (err) {
+      console.error("Failed to suggest tag color:", err);
+      return null;
+    }
+  }, []);
+
+  /**
+   * Gets tag usage statistics for the dashboard.
+   */
+  const getTagStatistics = useCallback(async (): Promise<TagStatistics | null> => {
+    try {
+      setError(null);
+      const stats: TagStatistics = await invoke("get_tag_statistics_command");
+      return stats;
+    } catch (err) {
+      console.error("Failed to get tag statistics:", err);
+      setError("Failed to get tag statistics");
+      return null;
+    }
+  }, []);
+
+  /**
+   * Gets the auto-tag rules.
+   */
+  const getAutoTagRules = useCallback(async (): Promise<AutoTagRule[]> => {
+    try {
+      setError(null);
+      const rules: AutoTagRule[] = await invoke("get_auto_tag_rules_command");
+      return rules;
+    } catch (err) {
+      console.error("Failed to get auto-tag rules:", err);
+      setError("Failed to get auto-tag rules");
+      return [];
+    }
+  }, []);
+
+  /**
+   * Sets the tags added to new backups started by a trigger; an empty list removes the rule.
+   */
+  const setAutoTagRule = useCallback(async (trigger: BackupTrigger, tagNames: string[]) => {
+    try {
+      setError(null);
+      await invoke("set_auto_tag_rule_command", { trigger, tagNames });
+    } catch (err) {
+      console.error("Failed to set auto-tag rule:", err);
+      setError("Failed to set auto-tag rule");
+      throw err;
+    }
+  }, []);
+
+  /**
+   * Removes the auto-tag rule of a trigger.
+   */
+  const deleteAutoTagRule = useCallback(async (trigger: BackupTrigger): Promise<boolean> => {
+    try {
+      setError(null);
+      const existed: boolean = await invoke("delete_auto_tag_rule_command", { trigger });
+      return existed;
+    } catch (err) {
+      console.error("Failed to delete auto-tag rule:", err);
+      setError("Failed to delete auto-tag rule");
+      throw err;
+    }
+  }, []);
+
+  /**
+   * Applies several tag changes at once; if one fails, none is applied.
+   */
+  const applyTagBatch = useCallback(
+    async (ops: TagOp[]) => {
+      try {
+        setLoading(true);
+        setError(null);
+        await invoke("apply_tag_batch_command", { ops });
+        await loadAllTags();
+      } catch (err) {
+        console.error("Failed to apply tag changes:", err);
+        setError("Failed to apply tag changes");
+        throw err;
+      } finally {
+        setLoading(false);
+      }
+    },
+    [loadAllTags],
+  );
+
+  /**
+   * Gets the smart collections (saved tag queries).
+   */
+  const getCollections = useCallback(async (): Promise<SmartCollection[]> => {
+    try {
+      setError(null);
+      return await invoke<SmartCollection[]>("get_collections_command");
+    } catch (err) {
+      console.error("Failed to get smart collections:", err);
+      setError("Failed to get smart collections");
+      return [];
+    }
+  }, []);
+
+  /**
+   * Creates a smart collection, or replaces the one with the same name.
+   */
+  const createCollection = useCallback(async (collection: SmartCollection) => {
+    try {
+      setError(null);
+      await invoke("create_collection_command", { collection });
+    } catch (err) {
+      console.error("Failed to save smart collection:", err);
+      setError("Failed to save smart collection");
+      throw err;
+    }
+  }, []);
+
+  /**
+   * Lists the backups and saves a smart collection matches.
+   */
+  const evaluateCollection = useCallback(async (name: string): Promise<StoredTagTarget[]> => {
+    try {
+      setError(null);
+      return await invoke<StoredTagTarget[]>("evaluate_collection_command", { name });
+    } catch (err) {
+      console.error("Failed to evaluate smart collection:", err);
+      setError("Failed to evaluate smart collection");
+      throw err;
+    }
+  }, []);
+
+  /**
+   * Deletes a smart collection.
+   */
+  const deleteCollection = useCallback(async (name: string): Promise<boolean> => {
+    try {
+      setError(null);
+      return await invoke<boolean>("delete_collection_command", { name });
+    } catch (err) {
+      console.error("Failed to delete smart collection:", err);
+      setError("Failed to delete smart collection");
+      throw err;
+    }
+  }, []);
+
   /**
    * Creates a new tag.
    */
   const createTag = useCallback(
-    async (name: string, color: string) => {
+    async (name: string, color: string, description?: string, icon?: string) => {
       try {
         setLoading(true);
         setError(null);
-        await invoke("create_tag_command", { name, color });
+        await invoke("create_tag_command", {
+          name,
+          color,
+          description: description ?? null,
+          icon: icon ?? null,
+        });
         // Reload tags after creating
         await loadAllTags();
       } catch (err) {
@@ -53,6 +323,221 @@ export function useTags() {
     [loadAllTags],
   );
 
+  /**
+   * Renames a tag; backups and saves keep it under the new name.
+   */
+  const renameTag = useCallback(
+    async (oldName: string, newName: string) => {
+      try {
+        setLoading(true);
+        setError(null);
+        await invoke("rename_tag_command", { oldName, newName });
+        // Reload tags after renaming
+        await loadAllTags();
+      } catch (err) {
+        console.error("Failed to rename tag:", err);
+        setError("Failed to rename tag");
+        throw err;
+      } finally {
+        setLoading(false);
+      }
+    },
+    [loadAllTags],
+  );
+
+  /**
+   * Changes a tag's color.
+   */
+  const updateTagColor = useCallback(
+    async (name: string, color: string) => {
+      try {
+        setLoading(true);
+        setError(null);
+        await invoke("update_tag_color_command", { name, color });
+        // Reload tags after updating
+        await loadAllTags();
+      } catch (err) {
+        console.error("Failed to update tag color:", err);
+        setError("Failed to update tag color");
+        throw err;
+      } finally {
+        setLoading(false);
+      }
+    },
+    [loadAllTags],
+  );
+
+  /**
+   * Sets or clears a tag's description.
+   */
+  const updateTagDescription = useCallback(
+    async (name: string, description: string | null) => {
+      try {
+        setLoading(true);
+        setError(null);
+        await invoke("update_tag_description_command", { name, description });
+        // Reload tags after updating
+        await loadAllTags();
+      } catch (err) {
+        console.error("Failed to update tag description:", err);
+        setError("Failed to update tag description");
+        throw err;
+      } finally {
+        setLoading(false);
+      }
+    },
+    [loadAllTags],
+  );
+
+  /**
+   * Sets or clears a tag's icon.
+   */
+  const updateTagIcon = useCallback(
+    async (name: string, icon: string | null) => {
+      try {
+        setLoading(true);
+        setError(null);
+        await invoke("update_tag_icon_command", { name, icon });
+        // Reload tags after updating
+        await loadAllTags();
+      } catch (err) {
+        console.error("Failed to update tag icon:", err);
+        setError("Failed to update tag icon");
+        throw err;
+      } finally {
+        setLoading(false);
+      }
+    },
+    [loadAllTags],
+  );
+
+  /**
+   * Pins a tag so it is listed first, or unpins it.
+   */
+  const setTagPinned = useCallback(
+    async (name: string, pinned: boolean) => {
+      try {
+        setLoading(true);
+        setError(null);
+        await invoke("set_tag_pinned_command", { name, pinned });
+        await loadAllTags();
+      } catch (err) {
+        console.error("Failed to pin tag:", err);
+        setError("Failed to pin tag");
+        throw err;
+      } finally {
+        setLoading(false);
+      }
+    },
+    [loadAllTags],
+  );
+
+  /**
+   * Protects a tag's backups from retention pruning, or lifts the protection.
+   */
+  const setTagProtected = useCallback(
+    async (name: string, isProtected: boolean) => {
+      try {
+        setLoading(true);
+        setError(null);
+        await invoke("set_tag_protected_command", { name, protected: isProtected });
+        await loadAllTags();
+      } catch (err) {
+        console.error("Failed to protect tag:", err);
+        setError("Failed to protect tag");
+        throw err;
+      } finally {
+        setLoading(false);
+      }
+    },
+    [loadAllTags],
+  );
+
+  /**
+   * Adds an alias that also finds a tag.
+   */
+  const addTagAlias = useCallback(
+    async (name: string, alias: string) => {
+      try {
+        setLoading(true);
+        setError(null);
+        await invoke("add_tag_alias_command", { name, alias });
+        await loadAllTags();
+      } catch (err) {
+        console.error("Failed to add tag alias:", err);
+        setError("Failed to add tag alias");
+        throw err;
+      } finally {
+        setLoading(false);
+      }
+    },
+    [loadAllTags],
+  );
+
+  /**
+   * Removes an alias from a tag.
+   */
+  const removeTagAlias = useCallback(
+    async (name: string, alias: string) => {
+      try {
+        setLoading(true);
+        setError(null);
+        await invoke("remove_tag_alias_command", { name, alias });
+        await loadAllTags();
+      } catch (err) {
+        console.error("Failed to remove tag alias:", err);
+        setError("Failed to remove tag alias");
+        throw err;
+      } finally {
+        setLoading(false);
+      }
+    },
+    [loadAllTags],
+  );
+
+  /**
+   * Sets the order tags are listed in; `namesInOrder` must contain every tag.
+   */
+  const reorderTags = useCallback(
+    async (namesInOrder: string[]) => {
+      try {
+        setLoading(true);
+        setError(null);
+        await invoke("reorder_tags_command", { namesInOrder });
+        // Reload tags after reordering
+        await loadAllTags();
+      } catch (err) {
+        console.error("Failed to reorder tags:", err);
+        setError("Failed to reorder tags");
+        throw err;
+      } finally {
+        setLoading(false);
+      }
+    },
+    [loadAllTags],
+  );
+
+  /**
+   * Moves a tag into a group, or out of its group when `group` is null.
+  const setTagGroup = useCallback(
+    async (name: string, group: string | null) => {
+      try {
+        setLoading(true);
+        setError(null);
+        await invoke("set_tag_group_command", { name, group });
+        // Reload tags after updating
+        await loadAllTags();
+      } catch (err) {
+        console.error("Failed to set tag group:", err);
+        setError("Failed to set tag group");
+        throw err;
+      } finally {
+        setLoading(false);
+      }
+    },
+    [loadAllTags],
+  );
+
   /**
    * Deletes a tag.
    */
@@ -75,13 +560,89 @@ export function useTags() {
     [loadAllTags],
   );
 
+  /**
+   * Lists deleted tags that can still be restored.
+   */
+  const getDeletedTags = useCallback(async (): Promise<DeletedTag[]> => {
+    try {
+      return await invoke<DeletedTag[]>("get_deleted_tags_command");
+    } catch (err) {
+      console.error("Failed to load deleted tags:", err);
+      return [];
+    }
+  }, []);
+
+  /**
+   * Undoes a tag deletion, re-adding the tag to the backups and saves it had.
+   * Returns how many of them were tagged again.
+   */
+  const restoreDeletedTag = useCallback(
+    async (name: string): Promise<number> => {
+      try {
+        setLoading(true);
+        setError(null);
+        const restored = await invoke<number>("restore_deleted_tag_command", { name });
+        await loadAllTags();
+        return restored;
+      } catch (err) {
+        console.error("Failed to restore tag:", err);
+        setError("Failed to restore tag");
+        throw err;
+      } finally {
+        setLoading(false);
+      }
+    },
+    [loadAllTags],
+  );
+
+  /**
+   * Permanently removes tags deleted at least `olderThanDays` days ago.
+   */
+  const purgeDeletedTags = useCallback(async (olderThanDays: number): Promise<string[]> => {
+    try {
+      return await invoke<string[]>("purge_deleted_tags_command", { olderThanDays });
+    } catch (err) {
+      console.error("Failed to purge deleted tags:", err);
+      throw err;
+    }
+  }, []);
+
   return {
     tags,
     loading,
     error,
     loadAllTags,
+    getTagUsage,
+    getTagStatistics,
+    suggestTagColor,
+    getRecentTags,
+    searchTags,
+    pruneOrphanedTags,
+    repairDatabase,
+    exportTagsCsv,
+    getAutoTagRules,
+    setAutoTagRule,
+    deleteAutoTagRule,
+    applyTagBatch,
+    getCollections,
+    createCollection,
+    evaluateCollection,
+    deleteCollection,
     createTag,
+    renameTag,
+    updateTagColor,
+    updateTagDescription,
+    updateTagIcon,
+    setTagPinned,
+    setTagProtected,
+    addTagAlias,
+    removeTagAlias,
+    reorderTags,
+    setTagGroup,
     deleteTag,
+    getDeletedTags,
+    restoreDeletedTag,
+    purgeDeletedTags,
   };
 }
 
@@ -116,6 +677,88 @@ export function useBackupTags() {
     [],
   );
 
+  /**
+   * Gets the tags of all backups of a save in one call, keyed by backup name.
+   * Untagged backups are missing from the result.
+   */
+  const getAllBackupTagsForSave = useCallback(
+    async (saveName: string): Promise<Record<string, Tag[]>> => {
+      try {
+        setLoading(true);
+        setError(null);
+        const tags: Record<string, Tag[]> = await invoke("get_all_backup_tags_for_save_command", {
+          saveName,
+        });
+        return tags;
+      } catch (err) {
+        console.error("Failed to get backup tags:", err);
+        setError("Failed to get backup tags");
+        return {};
+      } finally {
+        setLoading(false);
+      }
+    },
+    [],
+  );
+
+  /**
+   * Gets the tags of several backups and saves in one call, in the given order.
+   * Untagged targets get an empty list.
+   */
+  const getTagsForTargets = useCallback(
+    async (targets: StoredTagTarget[]): Promise<[StoredTagTarget, Tag[]][]> => {
+      try {
+        setError(null);
+        return await invoke<[StoredTagTarget, Tag[]][]>("get_tags_for_targets_command", { targets });
+      } catch (err) {
+        console.error("Failed to get tags:", err);
+        setError("Failed to get tags");
+        return targets.map((target) => [target, []]);
+      }
+    },
+    [],
+  );
+
+  /**
+   * Finds all backups carrying a tag, as `[saveName, backupName]` pairs.
+   */
+  const findBackupsByTag = useCallback(async (tagName: string): Promise<[string, string][]> => {
+    try {
+      setLoading(true);
+      setError(null);
+      const backups: [string, string][] = await invoke("find_backups_by_tag_command", {
+        tagName,
+      });
+      return backups;
+    } catch (err) {
+      console.error("Failed to find backups by tag:", err);
+      setError("Failed to find backups by tag");
+      return [];
+    } finally {
+      setLoading(false);
+    }
+  }, []);
+
+  /**
+   * Lists the backups on disk without tags, optionally of one save only.
+   */
+  const getUntaggedBackups = useCallback(async (saveName?: string): Promise<[string, string][]> => {
+    try {
+      setLoading(true);
+      setError(null);
+      const backups: [string, string][] = await invoke("get_untagged_backups_command", {
+        saveName: saveName ?? null,
+      });
+      return backups;
+    } catch (err) {
+      console.error("Failed to list untagged backups:", err);
+      setError("Failed to list untagged backups");
+      return [];
+    } finally {
+      setLoading(false);
+    }
+  }, []);
+
   /**
    * Adds tags to a backup.
    */
@@ -164,12 +807,107 @@ export function useBackupTags() {
     [],
   );
 
+  /**
+   * Replaces all tags of a backup with one write; an empty list removes them all.
+   */
+  const setBackupTags = useCallback(
+    async (saveName: string, backupName: string, tagNames: string[]) => {
+      try {
+        setLoading(true);
+        setError(null);
+        await invoke("set_backup_tags_command", {
+          saveName,
+          backupName,
+          tags: tagNames,
+        });
+      } catch (err) {
+        console.error("Failed to set backup tags:", err);
+        setError("Failed to set backup tags");
+        throw err;
+      } finally {
+        setLoading(false);
+      }
+    },
+    [],
+  );
+
+  /**
+   * Adds tags to many backups in one call; fails without changes if a tag is unknown.
+   */
+  const addTagsToBackups = useCallback(
+    async (targets: [string, string][], tagNames: string[]) => {
+      try {
+        setLoading(true);
+        setError(null);
+        await invoke("add_tags_to_backups_command", { targets, tags: tagNames });
+      } catch (err) {
+        console.error("Failed to add tags to backups:", err);
+        setError("Failed to add tags to backups");
+        throw err;
+      } finally {
+        setLoading(false);
+      }
+    },
+    [],
+  );
+
+  /**
+   * Removes tags from many backups in one call.
+   */
+  const removeTagsFromBackups = useCallback(
+    async (targets: [string, string][], tagNames: string[]) => {
+      try {
+        setLoading(true);
+        setError(null);
+        await invoke("remove_tags_from_backups_command", { targets, tags: tagNames });
+      } catch (err) {
+        console.error("Failed to remove tags from backups:", err);
+        setError("Failed to remove tags from backups");
+        throw err;
+      } finally {
+        setLoading(false);
+      }
+    },
+    [],
+  );
+
+  /**
+   * Removes tags from every backup of a save; resolves to the number of backups changed.
+   */
+  const removeTagsFromAllBackupsOfSave = useCallback(
+    async (saveName: string, tagNames: string[]): Promise<number> => {
+      try {
+        setLoading(true);
+        setError(null);
+        return await invoke<number>("remove_tags_from_all_backups_of_save_command", {
+          saveName,
+          tags: tagNames,
+        });
+      } catch (err) {
+        console.error("Failed to remove tags from all backups:", err);
+        setError("Failed to remove tags from all backups");
+        throw err;
+      } finally {
+        setLoading(false);
+      }
+    },
+    [],
+  );
+
   return {
     loading,
     error,
     getBackupTags,
+    getAllBackupTagsForSave,
+    getTagsForTargets,
+    findBackupsByTag,
+    getUntaggedBackups,
     addBackupTags,
     removeBackupTags,
+    setBackupTags,
+    addTagsToBackups,
+    removeTagsFromBackups,
+    removeTagsFromAllBackupsOfSave,
   };
 }
 
@@ -200,6 +938,61 @@ export function useSaveTags() {
     }
   }, []);
 
+  /**
+   * Gets the tags of all saves in one call, keyed by relative path.
+   * Untagged saves are missing from the result.
+   */
+  const getAllSaveTags = useCallback(async (): Promise<Record<string, Tag[]>> => {
+    try {
+      setLoading(true);
+      setError(null);
+      const tags: Record<string, Tag[]> = await invoke("get_all_save_tags_command");
+      return tags;
+    } catch (err) {
+      console.error("Failed to get save tags:", err);
+      setError("Failed to get save tags");
+      return {};
+    } finally {
+      setLoading(false);
+    }
+  }, []);
+
+  /**
+   * Finds the relative paths of all saves carrying a tag.
+   */
+  const findSavesByTag = useCallback(async (tagName: string): Promise<string[]> => {
+    try {
+      setLoading(true);
+      setError(null);
+      const saves: string[] = await invoke("find_saves_by_tag_command", { tagName });
+      return saves;
+    } catch (err) {
+      console.error("Failed to find saves by tag:", err);
+      setError("Failed to find saves by tag");
+      return [];
+    } finally {
+      setLoading(false);
+    }
+  }, []);
+
+  /**
+   * Lists the relative paths of the saves on disk without tags.
+   */
+  const getUntaggedSaves = useCallback(async (): Promise<string[]> => {
+    try {
+      setLoading(true);
+      setError(null);
+      const saves: string[] = await invoke("get_untagged_saves_command");
+      return saves;
+    } catch (err) {
+      console.error("Failed to list untagged saves:", err);
+      setError("Failed to list untagged saves");
+      return [];
+    } finally {
+      setLoading(false);
+    }
+  }, []);
+
   /**
    * Adds tags to a save.
    */
@@ -240,11 +1033,53 @@ export function useSaveTags() {
     }
   }, []);
 
+  /**
+   * Replaces all tags of a save with one write; an empty list removes them all.
+   */
+  const setSaveTags = useCallback(async (relativePath: string, tagNames: string[]) => {
+    try {
+      setLoading(true);
+      setError(null);
+      await invoke("set_save_tags_command", {
+        relativePath,
+        tags: tagNames,
+      });
+    } catch (err) {
+      console.error("Failed to set save tags:", err);
+      setError("Failed to set save tags");
+      throw err;
+    } finally {
+      setLoading(false);
+    }
+  }, []);
+
+  /**
+   * Moves a save's tags to its new path after the save folder was renamed or moved.
+   */
+  const renameSaveTarget = useCallback(async (oldRelativePath: string, newRelativePath: string) => {
+    try {
+      setLoading(true);
+      setError(null);
+      await invoke("rename_save_target_command", { oldRelativePath, newRelativePath });
+    } catch (err) {
+      console.error("Failed to move save tags:", err);
+      setError("Failed to move save tags");
+      throw err;
+    } finally {
+      setLoading(false);
+    }
+  }, []);
+
   return {
     loading,
     error,
     getSaveTags,
+    getAllSaveTags,
+    findSavesByTag,
+    getUntaggedSaves,
     addSaveTags,
     removeSaveTags,
+    setSaveTags,
+    renameSaveTarget,
   };
 }
@@ -0,0 +1,71 @@
+/**
+ * Error type definitions for Tauri command failures.
+ */
+
+/**
+ * Machine-readable error code returned by every command.
+ */
+export type ErrorCode =
+  | "Internal"
+  | "Io"
+  | "PermissionDenied"
+  | "InsufficientSpace"
+  | "SaveBusy"
+  | "CorruptArchive"
+  | "CorruptData"
+  | "PathNotFound"
+  | "AlreadyExists"
+  | "NotADirectory"
+  | "Cancelled"
+  | "StoreBusy"
+  | "InvalidConfig"
+  | "ConfigDirNotFound"
+  | "EphemeralStorage"
+  | "InvalidValue"
+  | "Unauthorized"
+  | "SaveNotFound"
+  | "BackupNotFound"
+  | "InvalidBackupName"
+  | "InvalidSaveName"
+  | "UndoSnapshotFailed"
+  | "GameRunning"
+  | "TagNotFound"
+  | "DuplicateTag"
+  | "CollectionNotFound"
+  | "InvalidColor"
+  | "InvalidTagName"
+  | "InvalidIcon"
+  | "Offline"
+  | "UpdateRequestFailed"
+  | "UpdateServerError"
+  | "RateLimited"
+  | "InvalidUpdateResponse"
+  | "InvalidVersion"
+  | "InvalidProxy"
+  | "InvalidUpdateSource"
+  | "NoUpdateAsset"
+  | "ChecksumMismatch"
+  | "SchedulerFailed";
+
+/**
+ * Error payload rejected by `invoke` when a command fails.
+ */
+export interface ErrorPayload {
+  code: ErrorCode;
+  message: string; // advisory, don't match on it
+  details: Record<string, unknown> | null;
+}
+
+/**
+ * Checks whether a caught value is an error payload.
+ */
+export function isErrorPayload(err: unknown): err is ErrorPayload {
+  return typeof err === "object" && err !== null && "code" in err && "message" in err;
+}
+
+/**
+ * Gets a displayable message from a caught value.
+ */
+export function errorMessage(err: unknown): string {
+  return isErrorPayload(err) ? err.message : String(err);
+}
@@ -3,11 +3,146 @@
  */
 
 /**
- * Tag with name and color.
+ * Tag with name, color and an optional description and icon.
  */
 export interface Tag {
   name: string;
   color: string; // hex color like "#FF5733"
+  description?: string;
+  icon?: string; // emoji or up to 4 characters
+  group?: string;
+  aliases?: string[]; // other names that find the tag
+  pinned?: boolean; // listed before unpinned tags
+  protected?: boolean; // backups carrying it are never pruned
+  sort_index: number;
+  created_at?: string; // RFC 3339; missing on tags from older versions
+  updated_at?: string;
+  text_color?: string; // "#000000" or "#FFFFFF", computed by the backend for `color`
+}
+
+/**
+ * What started a backup: the user, an OS schedule, or launch arguments.
+ */
+export type BackupTrigger = "Manual" | "Scheduled" | "System";
+
+/**
+ * Tags added automatically to every new backup started by a trigger.
+ */
+export interface AutoTagRule {
+  trigger: BackupTrigger;
+  tag_names: string[];
+}
+
+/**
+ * How many backups and saves carry a tag.
+ */
+export interface TagUsage {
+  tag: Tag;
+  backup_count: number;
+  save_count: number;
+  dangling: boolean; // used in associations but missing from the tag list
+}
+
+/**
+ * Usage counts of one tag name within TagStatistics.
+ */
+export interface TagCounts {
+  name: string;
+  backup_count: number;
+  save_count: number;
+  backups_per_save: Record<string, number>; // save relative path -> backups carrying the tag
+  dangling: boolean;
+}
+
+/**
+ * Overview of tag usage for the dashboard.
+ */
+export interface TagStatistics {
+  total_tags: number;
+  tagged_backups: number;
+  tagged_saves: number;
+  most_used_tag: string | null;
+  untagged_associations: number;
+  dangling_tag_names: number;
+  tags: TagCounts[];
+}
+
+/**
+ * A deleted tag that can still be restored to the targets it had.
+ */
+export interface DeletedTag {
+  tag: Tag;
+  targets: StoredTagTarget[];
+  deleted_at: string; // ISO 8601
+}
+
+/**
+ * One change of a tag batch; a batch is applied all or nothing.
+ */
+export type TagOp =
+  | { op: "CreateTag"; name: string; color: string; description?: string; icon?: string }
+  | { op: "DeleteTag"; name: string }
+  | { op: "AddToTarget"; target: StoredTagTarget; tags: string[] }
+  | { op: "RemoveFromTarget"; target: StoredTagTarget; tags: string[] }
+  | { op: "Rename"; old_name: string; new_name: string };
+
+/**
+ * Smart collection: a saved tag query. Excluded tags win over included ones.
+ */
+export interface SmartCollection {
+  name: string;
+  include_tags: string[]; // a target must carry all of them
+  exclude_tags: string[]; // a target must carry none of them
+  target_kind: "Backup" | "Save" | null; // null lists both
+  save_name?: string; // only this save and its backups
+}
+
+/**
+ * Tag associations of deleted backups and saves, removed or awaiting confirmation.
+ */
+export interface OrphanReport {
+  backups: [string, string][]; // [saveName, backupName]
+  saves: string[];
+  needs_confirmation: boolean; // nothing was removed; call again with confirm
+}
+
+/**
+ * What repairing tags.json fixed.
+ */
+export interface RepairSummary {
+  removed_tag_names: [StoredTagTarget, string[]][]; // undefined tags removed per target
+  merged_associations: number;
+  removed_associations: number; // left without tags
+}
+
+/**
+ * Payload of the "tags-changed" event, sent after every change to tags.
+ */
+export interface TagsChange {
+  kind:
+    | "TagCreated"
+    | "TagUpdated"
+    | "TagsReordered"
+    | "TagDeleted"
+    | "TagRestored"
+    | "TrashPurged"
+    | "TargetTagsChanged"
+    | "AutoTagRulesChanged"
+    | "CollectionsChanged"
+    | "Batch"
+    | "Imported"
+    | "Cleanup"
+    | "External"; // tags.json edited by another program
+  target: StoredTagTarget | null; // set when a single backup or save changed
+  saves?: string[]; // for external edits, the affected saves
+}
+
+/**
+ * Rows written by a CSV export of tags and associations.
+ */
+export interface TagsCsvExport {
+  tag_rows: number;
+  association_rows: number;
 }
 
 /**
@@ -17,6 +152,13 @@ export type TagTarget =
   | { type: "backup"; saveName: string; backupName: string }
   | { type: "save"; relativePath: string };
 
+/**
+ * Tag target as commands return it.
+ */
+export type StoredTagTarget =
+  | { type: "Backup"; save_name: string; backup_name: string }
+  | { type: "Save"; relative_path: string };
+
 /**
  * Tag association linking a target to tag names.
  */
@@ -65,6 +207,7 @@ export function isSaveTarget(target: TagTarget): target is Extract<TagTarget, {
 
 /**
  * Default tag colors for quick selection.
+ * Keep in sync with TAG_COLOR_PALETTE in tags.rs, which suggests them in this order.
  */
 export const DEFAULT_TAG_COLORS = [
   { name: "Red", value: "#EF4444" },